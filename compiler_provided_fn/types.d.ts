@@ -1,3 +1,12 @@
 declare module "compilerInternal" {
     export function syscallPrint(rdi : number, s : string, size : number) : void
-}
\ No newline at end of file
+}
+
+/*
+ * Provided by the compiler without any import, console.log writes to stdout and
+ * console.error writes to stderr
+ */
+declare var console : {
+    log(...data : any[]) : void
+    error(...data : any[]) : void
+}
@@ -0,0 +1,105 @@
+use indexmap::IndexMap;
+
+use crate::data_type::DataType;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum AccessModifier {
+    Public,
+    // Accessible inside of the class and its subclasses
+    Protected,
+    // Accessible only inside of the class declaring the member
+    Private,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassField {
+    pub data_type: DataType,
+    pub access: AccessModifier,
+    // Llvm name of the class declaring the field
+    pub owner: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassMethod {
+    // Data type of the method without `this`, the function takes it as the first argument
+    pub data_type: DataType,
+    pub access: AccessModifier,
+    // Llvm name of the class declaring the method
+    pub owner: String,
+    // Llvm name of the function implementing the method
+    pub function: String,
+}
+
+/*
+ * Everything known about a class once its declaration is parsed, shared by the
+ * parser to check the uses of the class and by llvm to lay out its instances
+ *
+ * An instance is a struct of the vtable followed by the fields
+ *
+ *      { i8** vtable, <field_0>, <field_1>, ... }
+ *
+ * Inherited fields come first so an instance of a subclass can be used as an
+ * instance of its parent class. For the same reason inherited methods keep their
+ * slot in the vtable, overriding one only replaces the function in the slot
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Class {
+    // Name in the source, used in messages and by console.log
+    pub name: String,
+    pub llvm_name: String,
+    // Llvm names of the ancestors, the parent first
+    pub parents: Vec<String>,
+    pub fields: IndexMap<String, ClassField>,
+    // In the order of the slots in the vtable
+    pub methods: IndexMap<String, ClassMethod>,
+    pub static_methods: IndexMap<String, ClassMethod>,
+    // Function initializing a new instance, it takes `this` like a method
+    pub constructor: ClassMethod,
+}
+
+impl Class {
+    pub fn get_data_type(&self) -> DataType {
+        return DataType::ClassType {
+            name: self.llvm_name.clone(),
+            parents: self.parents.clone(),
+        };
+    }
+
+    /*
+     * Object type an instance is structurally assignable to, the public fields and
+     * methods of the class, see data_type::is_assignable_with_classes
+     *
+     * */
+    pub fn get_instance_type(&self) -> DataType {
+        let fields = self
+            .fields
+            .iter()
+            .filter(|(_, field)| field.access == AccessModifier::Public)
+            .map(|(name, field)| (name.clone(), field.data_type.clone()));
+        let methods = self
+            .methods
+            .iter()
+            .filter(|(_, method)| method.access == AccessModifier::Public)
+            .map(|(name, method)| (name.clone(), method.data_type.clone()));
+
+        return DataType::ObjectType {
+            entries: fields.chain(methods).collect(),
+        };
+    }
+
+    // Function allocating an instance and running the constructor on it
+    pub fn get_allocator_name(&self) -> String {
+        return format!("|new|{}", self.llvm_name);
+    }
+
+    pub fn get_vtable_name(&self) -> String {
+        return format!("|vtable|{}", self.llvm_name);
+    }
+
+    // Whether the class is the other class or one of its subclasses
+    pub fn is_subclass_of(&self, llvm_name: &str) -> bool {
+        return self.llvm_name == llvm_name
+            || self.parents.iter().any(|parent| parent == llvm_name);
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashMap, fmt};
+
 use indexmap::IndexMap;
 
 #[derive(PartialEq, Debug, Clone)]
@@ -8,6 +10,9 @@ pub enum DataType {
 
     Void, // only permitted as a function return type
 
+    Null,      // null
+    Undefined, // undefined, also the value of a missing optional property or parameter
+
     Unknown,
     ArrayType {
         base_type: Box<DataType>,
@@ -19,6 +24,51 @@ pub enum DataType {
         return_type: Box<DataType>,
         arguments: Vec<DataType>,
     },
+    /*
+     * Instance of a class, unlike an object it is matched by the name of the class
+     * and not by its fields, see class::Class for the fields and methods
+     *
+     * */
+    ClassType {
+        name: String, // llvm name of the class
+        parents: Vec<String>,
+    },
+    /*
+     * `object`, any array, object or instance of a class e.g. a thrown one. Its
+     * data type is only known at runtime, so the value carries how to print it and
+     * the vtable of the class it is an instance of, see build_any_object of llvm
+     *
+     * */
+    Object,
+    /*
+     * Member of an enum, a numeric enum holds numbers and a string enum strings.
+     * It is only assignable to the data type of the values, so llvm sees that
+     * data type instead, see enumeration::erase_enums
+     *
+     * */
+    EnumType {
+        name: String, // llvm name of the enum
+        is_numeric: bool,
+    },
+    /*
+     * `number | string`, members are never unions themselves. The value is tagged
+     * by the member it holds, so members can share the result of `typeof` e.g.
+     * `Cat | Dog` or `number[] | string[]`, see create_union
+     *
+     * */
+    UnionType {
+        types: Vec<DataType>,
+    },
+    /*
+     * Type parameter of a generic function or a generic type alias, nothing is
+     * known about it so it is only assignable to itself. llvm generates the
+     * generic function for every list of type arguments it is called with, so it
+     * never sees a type parameter, see generic::GenericFunction
+     *
+     * */
+    TypeParameter {
+        name: String,
+    },
 
     /*
      * If parser cannot figure out datatype for an variable it will be marked as
@@ -52,3 +102,457 @@ pub enum DataType {
      *  */
     NA,
 }
+
+impl DataType {
+    /*
+     * Result of `typeof` for a value of the data type, for a union it depends on
+     * the member the value holds so it is only known at runtime
+     *
+     * */
+    pub fn get_typeof_name(&self) -> Option<&'static str> {
+        match self {
+            DataType::Float => return Some("number"),
+            DataType::String => return Some("string"),
+            DataType::Boolean => return Some("boolean"),
+            DataType::Void | DataType::Undefined => return Some("undefined"),
+            DataType::Null
+            | DataType::ArrayType { .. }
+            | DataType::ObjectType { .. }
+            | DataType::ClassType { .. }
+            | DataType::Object => return Some("object"),
+            DataType::FunctionType { .. } => return Some("function"),
+            DataType::EnumType { is_numeric, .. } => {
+                return Some(if *is_numeric { "number" } else { "string" })
+            }
+            DataType::UnionType { .. }
+            | DataType::TypeParameter { .. }
+            | DataType::Unknown
+            | DataType::NA => return None,
+        }
+    }
+
+    pub fn is_nullish(&self) -> bool {
+        return matches!(self, DataType::Null | DataType::Undefined);
+    }
+
+    // `null` or `undefined` can be assigned to the data type
+    pub fn accepts_nullish(&self, nullish: &DataType) -> bool {
+        match self {
+            DataType::UnionType { types } => return types.contains(nullish),
+            data_type => return data_type == nullish,
+        }
+    }
+
+    /*
+     * Values of both data types look the same at runtime, unlike `==` the order of
+     * the properties matters as it is the order of the fields of the object
+     *
+     *      { a: number, b: string } == { b: string, a: number }
+     *
+     * */
+    pub fn has_same_layout(&self, other: &DataType) -> bool {
+        match (self, other) {
+            (DataType::ObjectType { entries }, DataType::ObjectType { entries: other }) => {
+                return entries.len() == other.len()
+                    && entries.iter().zip(other.iter()).all(
+                        |((key, entry), (other_key, other_entry))| {
+                            key == other_key && entry.has_same_layout(other_entry)
+                        },
+                    );
+            }
+
+            (
+                DataType::ArrayType { base_type },
+                DataType::ArrayType {
+                    base_type: other_base_type,
+                },
+            ) => return base_type.has_same_layout(other_base_type),
+
+            (
+                DataType::FunctionType {
+                    return_type,
+                    arguments,
+                },
+                DataType::FunctionType {
+                    return_type: other_return_type,
+                    arguments: other_arguments,
+                },
+            ) => {
+                return return_type.has_same_layout(other_return_type)
+                    && arguments.len() == other_arguments.len()
+                    && arguments
+                        .iter()
+                        .zip(other_arguments.iter())
+                        .all(|(argument, other)| argument.has_same_layout(other));
+            }
+
+            // Value of a union is tagged by the index of its member, see create_union
+            (DataType::UnionType { types }, DataType::UnionType { types: other }) => {
+                return types.len() == other.len()
+                    && types
+                        .iter()
+                        .zip(other.iter())
+                        .all(|(member, other)| member.has_same_layout(other));
+            }
+
+            (data_type, other) => return data_type == other,
+        }
+    }
+
+    // Data type with its type parameters replaced by the type arguments
+    pub fn substitute(&self, type_arguments: &HashMap<String, DataType>) -> DataType {
+        match self {
+            DataType::TypeParameter { name } => match type_arguments.get(name) {
+                Some(type_argument) => return type_argument.clone(),
+                None => return self.clone(),
+            },
+
+            data_type => return data_type.map_nested(|nested| nested.substitute(type_arguments)),
+        }
+    }
+
+    // Data type with its enums replaced by the data type of their values
+    pub fn erase_enums(&self) -> DataType {
+        match self {
+            DataType::EnumType {
+                is_numeric: true, ..
+            } => return DataType::Float,
+            DataType::EnumType {
+                is_numeric: false, ..
+            } => return DataType::String,
+
+            data_type => return data_type.map_nested(|nested| nested.erase_enums()),
+        }
+    }
+
+    // Data type with map applied to the data types it is made of
+    fn map_nested<F: Fn(&DataType) -> DataType>(&self, map: F) -> DataType {
+        match self {
+            DataType::ArrayType { base_type } => {
+                return DataType::ArrayType {
+                    base_type: Box::new(map(base_type)),
+                }
+            }
+
+            DataType::ObjectType { entries } => {
+                return DataType::ObjectType {
+                    entries: entries
+                        .iter()
+                        .map(|(key, entry)| (key.clone(), map(entry)))
+                        .collect(),
+                }
+            }
+
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            } => {
+                return DataType::FunctionType {
+                    return_type: Box::new(map(return_type)),
+                    arguments: arguments.iter().map(&map).collect(),
+                }
+            }
+
+            /*
+             * Mapped members can repeat e.g. `T | null` with T = null. The value is
+             * still boxed the way the generic function was parsed, so it stays a
+             * union even when a single member is left
+             *
+             * */
+            DataType::UnionType { types } => {
+                return match create_union(types.iter().map(&map).collect()) {
+                    DataType::UnionType { types } => DataType::UnionType { types },
+                    member => DataType::UnionType {
+                        types: vec![member],
+                    },
+                };
+            }
+
+            data_type => return data_type.clone(),
+        }
+    }
+
+    /*
+     * Member of the union which a value of the data type is converted to, a member
+     * with the same layout is preferred over any other one it is assignable to
+     *
+     * */
+    pub fn find_assignable_member<'a>(&self, types: &'a [DataType]) -> Option<&'a DataType> {
+        return types
+            .iter()
+            .find(|member| self.has_same_layout(member))
+            .or_else(|| types.iter().find(|member| is_assignable(self, member)));
+    }
+}
+
+/*
+ * Union of the data types, nested unions are flattened and repeated members are
+ * removed. Members are sorted so that `number | string` and `string | number` are
+ * the same data type
+ *
+ * Value of a union is a box tagged by the index of its member in this order, so
+ * every module tags the members of the same union the same way
+ *
+ * A union with a single member is the member itself
+ *
+ * */
+pub fn create_union(types: Vec<DataType>) -> DataType {
+    let mut members: Vec<DataType> = vec![];
+
+    for data_type in types {
+        let flattened = match data_type {
+            DataType::UnionType { types } => types,
+            data_type => vec![data_type],
+        };
+
+        for member in flattened {
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+
+    members.sort_by_key(|member| format!("{:?}", member));
+
+    if members.len() == 1 {
+        return members.pop().unwrap();
+    }
+
+    return DataType::UnionType { types: members };
+}
+
+/*
+ * Data type of every thrown value and of the catch binding, the value is boxed
+ * like any other union so the catch block narrows it with typeof or instanceof.
+ * Arrays, objects and instances of classes are thrown as `object`
+ *
+ * */
+pub fn get_exception_type() -> DataType {
+    return create_union(vec![
+        DataType::Float,
+        DataType::String,
+        DataType::Boolean,
+        DataType::Null,
+        DataType::Undefined,
+        DataType::Object,
+    ]);
+}
+
+/*
+ * Data type the way it is written in TypeScript, used in the messages shown to the
+ * user so classes and enums are printed by their name in the source
+ *
+ *      { a: number; b: (number | string)[] }
+ *
+ * Parameters of a function type have no names, so they are printed by position
+ *
+ *      (arg0: number, arg1: string) => void
+ *
+ * */
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Float => return write!(f, "number"),
+            DataType::String => return write!(f, "string"),
+            DataType::Boolean => return write!(f, "boolean"),
+            DataType::Void => return write!(f, "void"),
+            DataType::Null => return write!(f, "null"),
+            DataType::Undefined => return write!(f, "undefined"),
+            DataType::Object => return write!(f, "object"),
+            DataType::Unknown | DataType::NA => return write!(f, "unknown"),
+
+            DataType::ArrayType { base_type } => match base_type.as_ref() {
+                DataType::UnionType { .. } | DataType::FunctionType { .. } => {
+                    return write!(f, "({})[]", base_type)
+                }
+                base_type => return write!(f, "{}[]", base_type),
+            },
+
+            DataType::ObjectType { entries } => {
+                if entries.is_empty() {
+                    return write!(f, "{{}}");
+                }
+
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, entry)| format!("{}: {}", key, entry))
+                    .collect();
+                return write!(f, "{{ {} }}", entries.join("; "));
+            }
+
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            } => {
+                let arguments: Vec<String> = arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, argument)| format!("arg{}: {}", i, argument))
+                    .collect();
+                return write!(f, "({}) => {}", arguments.join(", "), return_type);
+            }
+
+            DataType::ClassType { name, .. } | DataType::EnumType { name, .. } => {
+                return write!(f, "{}", get_source_name(name))
+            }
+
+            DataType::UnionType { types } => {
+                let types: Vec<String> = types
+                    .iter()
+                    .map(|member| match member {
+                        DataType::FunctionType { .. } => format!("({})", member),
+                        member => member.to_string(),
+                    })
+                    .collect();
+                return write!(f, "{}", types.join(" | "));
+            }
+
+            DataType::TypeParameter { name } => return write!(f, "{}", name),
+        }
+    }
+}
+
+/*
+ * Removes the file id and suffix the parser adds to the name of variables, classes
+ * and enums
+ *
+ *   "foo|_|" => "foo"
+ *   "|fn:1|foo|_|" => "foo"
+ *
+ * */
+pub fn get_source_name(llvm_name: &str) -> &str {
+    let name = match llvm_name.strip_prefix("|fn:") {
+        Some(rest) => rest.split_once('|').map(|(_, name)| name).unwrap_or(rest),
+        None => llvm_name,
+    };
+
+    return name.split('|').next().unwrap_or(name);
+}
+
+/*
+ * Whether a value of `source` can be used where `target` is expected, structurally
+ * like TypeScript
+ *
+ * - an object can have more properties than the target, a property missing from
+ *   it has to be optional in the target
+ * - an array is assignable when its elements are, even though writing to it
+ *   through the target is unsound
+ * - a function can take fewer parameters than the target, the parameters are
+ *   contravariant like with `strictFunctionTypes` and the return type covariant,
+ *   any function is assignable to one returning void
+ * - a union is assignable when every member is, anything is assignable to a
+ *   union when it is assignable to one of the members
+ * - an instance of a class is assignable to the class and its ancestors, and to an
+ *   object type when its public fields and methods are, see
+ *   is_assignable_with_classes
+ * - a member of an enum is assignable to the enum and to the data type of its
+ *   value, but a number or a string is not assignable to an enum
+ * - an array, an object or an instance of a class is assignable to `object`
+ *
+ * */
+pub fn is_assignable(source: &DataType, target: &DataType) -> bool {
+    return is_assignable_with_classes(source, target, &|_| None);
+}
+
+/*
+ * is_assignable which also sees an instance of a class as an object of its public
+ * fields and methods, `get_instance_type` gives the object type of the class with
+ * the llvm name, see Class::get_instance_type
+ *
+ *      interface Shape { area(): number }
+ *      const shape: Shape = new Square(3);
+ *
+ * */
+pub fn is_assignable_with_classes(
+    source: &DataType,
+    target: &DataType,
+    get_instance_type: &dyn Fn(&str) -> Option<DataType>,
+) -> bool {
+    match (source, target) {
+        (source, target) if source == target => return true,
+
+        (DataType::UnionType { types }, target) => {
+            return types
+                .iter()
+                .all(|member| is_assignable_with_classes(member, target, get_instance_type));
+        }
+
+        (source, DataType::UnionType { types }) => {
+            return types
+                .iter()
+                .any(|member| is_assignable_with_classes(source, member, get_instance_type));
+        }
+
+        (
+            DataType::ObjectType { entries },
+            DataType::ObjectType {
+                entries: target_entries,
+            },
+        ) => {
+            return target_entries
+                .iter()
+                .all(|(key, target_entry)| match entries.get(key) {
+                    Some(entry) => {
+                        is_assignable_with_classes(entry, target_entry, get_instance_type)
+                    }
+                    None => target_entry.accepts_nullish(&DataType::Undefined),
+                });
+        }
+
+        (
+            DataType::ArrayType { base_type },
+            DataType::ArrayType {
+                base_type: target_base_type,
+            },
+        ) => return is_assignable_with_classes(base_type, target_base_type, get_instance_type),
+
+        (
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            },
+            DataType::FunctionType {
+                return_type: target_return_type,
+                arguments: target_arguments,
+            },
+        ) => {
+            let are_arguments_assignable = arguments.len() <= target_arguments.len()
+                && arguments.iter().zip(target_arguments.iter()).all(
+                    |(argument, target_argument)| {
+                        is_assignable_with_classes(target_argument, argument, get_instance_type)
+                    },
+                );
+
+            return are_arguments_assignable
+                && (target_return_type.as_ref() == &DataType::Void
+                    || is_assignable_with_classes(
+                        return_type,
+                        target_return_type,
+                        get_instance_type,
+                    ));
+        }
+
+        (DataType::ClassType { parents, .. }, DataType::ClassType { name, .. }) => {
+            return parents.contains(name);
+        }
+
+        (DataType::ClassType { name, .. }, DataType::ObjectType { .. }) => {
+            return match get_instance_type(name) {
+                Some(instance_type) => {
+                    is_assignable_with_classes(&instance_type, target, get_instance_type)
+                }
+                None => false,
+            };
+        }
+
+        (DataType::EnumType { is_numeric, .. }, DataType::Float) => return *is_numeric,
+        (DataType::EnumType { is_numeric, .. }, DataType::String) => return !is_numeric,
+
+        (
+            DataType::ArrayType { .. } | DataType::ObjectType { .. } | DataType::ClassType { .. },
+            DataType::Object,
+        ) => return true,
+
+        _ => return false,
+    }
+}
@@ -1,7 +1,14 @@
 use indexmap::IndexMap;
-use lexer::token::{KeywordKind, Token};
+use lexer::token::{KeywordKind, Span, Token};
 
-use crate::{data_type::DataType, expression::Expression, AstPtr};
+use crate::{
+    class::Class,
+    data_type::DataType,
+    enumeration::Enum,
+    expression::{BinaryOperator, Expression},
+    generic::GenericFunction,
+    AstPtr,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum VariableDeclarationKind {
@@ -13,13 +20,21 @@ pub enum VariableDeclarationKind {
 pub enum Declaration {
     ImportDeclaration {
         ident: IndexMap<String, DataType>,
+        // Every class known to the imported file, its instances can reach this one
+        classes: Vec<Class>,
+        // Imported generic functions along with the ones they call
+        generic_functions: Vec<GenericFunction>,
         from: String,
+        span: Span,
     },
 
     VariableDeclaration {
         ident_name: String,
         exp: Expression,
         kind: VariableDeclarationKind,
+        // Used by a closure, so the variable lives in a cell shared with the closure
+        is_captured: bool,
+        span: Span,
     },
 
     FunctionDeclaration {
@@ -27,12 +42,27 @@ pub enum Declaration {
         return_type: DataType,
         arguments: IndexMap<String, DataType>,
         blocks: Vec<AstPtr>,
+        /*
+         * Variables of the enclosing functions used by the body, keyed by their
+         * llvm name. A closure gets them from its environment, a function declared
+         * at the top of the file reads them from globals
+         *
+         * */
+        captures: IndexMap<String, DataType>,
+        span: Span,
+    },
+
+    // Nothing is generated for it till it is called, see Expression::GenericInstance
+    GenericFunctionDeclaration {
+        function: GenericFunction,
+        span: Span,
     },
 
     VariableAssignment {
         ident_name: String,
         operator: VariableAssignmentOperator,
         exp: Expression,
+        span: Span,
     },
 
     ArrayMemberAssignment {
@@ -40,30 +70,156 @@ pub enum Declaration {
         member_access_exp: Expression,
         operator: VariableAssignmentOperator,
         exp: Expression,
+        span: Span,
+    },
+
+    // `<object>.<field> = <exp>` where object is an instance of a class
+    FieldAssignment {
+        object: Expression,
+        field: String,
+        operator: VariableAssignmentOperator,
+        exp: Expression,
+        span: Span,
     },
 
     NewIfBlockDeclaration {
         if_block: BlockWithCondition,
         else_if_block: Vec<BlockWithCondition>,
         else_block: Option<Vec<AstPtr>>,
+        span: Span,
     },
 
     WhileLoopDeclaration {
         condition: Expression,
         block: Vec<AstPtr>,
+        span: Span,
     },
 
     DoWhileLoopDeclaration {
         condition: Expression,
         block: Vec<AstPtr>,
+        span: Span,
     },
 
+    /*
+     * for (<initializer>; <condition>; <update>) {
+     *     <block>
+     * }
+     *
+     * Every part inside the brackets is optional, loop without a condition runs
+     * till it is broken out of
+     *
+     * */
+    ForLoopDeclaration {
+        initializer: Option<AstPtr>,
+        condition: Option<Expression>,
+        update: Option<AstPtr>,
+        block: Vec<AstPtr>,
+        span: Span,
+    },
+
+    // for (const <ident_name> of <iterable>) where iterable is an array or a string
+    ForOfLoopDeclaration {
+        ident_name: String,
+        kind: VariableDeclarationKind,
+        iterable: Expression,
+        block: Vec<AstPtr>,
+        span: Span,
+    },
+
+    // for (const <ident_name> in <object>) where ident_name takes every key of object
+    ForInLoopDeclaration {
+        ident_name: String,
+        kind: VariableDeclarationKind,
+        object: Expression,
+        block: Vec<AstPtr>,
+        span: Span,
+    },
+
+    // switch (<discriminant>) { <cases> }, cases are kept in the order of the source
+    SwitchDeclaration {
+        discriminant: Expression,
+        cases: Vec<SwitchCase>,
+        span: Span,
+    },
+
+    // `break <label>` or `continue <label>`, label is None when it targets the innermost loop
     LoopControlFlow {
         keyword: KeywordKind,
+        label: Option<String>,
+        span: Span,
+    },
+
+    /*
+     * try {
+     *     <try_block>
+     * } catch (<binding>) {
+     *     <block of catch_clause>
+     * } finally {
+     *     <finally_block>
+     * }
+     *
+     * At least one of catch_clause and finally_block is present
+     *
+     * */
+    TryStatement {
+        try_block: Vec<AstPtr>,
+        catch_clause: Option<CatchClause>,
+        finally_block: Option<Vec<AstPtr>>,
+        span: Span,
+    },
+
+    // `throw <exp>` where exp is converted to the data type of the catch binding
+    ThrowStatement {
+        exp: Expression,
+        span: Span,
+    },
+
+    // <label>: <statement> where statement is always a loop
+    LabeledStatement {
+        label: String,
+        statement: AstPtr,
+        span: Span,
     },
 
     ReturnStatement {
         return_exp: Option<Expression>, // if Option is Option::None then the fn is returning void
+        span: Span,
+    },
+
+    /*
+     * `type <ident_name> = <data_type>` or `interface <ident_name> { .. }`, names of
+     * types are resolved by the parser so there is nothing left to generate
+     *
+     * */
+    TypeDeclaration {
+        ident_name: String,
+        data_type: DataType,
+        span: Span,
+    },
+
+    /*
+     * Constructor, methods and static methods of the class are FunctionDeclarations
+     * named after class::ClassMethod::function, the constructor and the methods
+     * take the instance as their first argument `this`
+     *
+     * */
+    ClassDeclaration {
+        class: Class,
+        functions: Vec<AstPtr>,
+        span: Span,
+    },
+
+    /*
+     * Members are replaced by their values where they are used, so only the
+     * reverse mapping of a numeric enum is generated, a FunctionDeclaration
+     * named after enumeration::Enum::get_reverse_mapping_name
+     *
+     * */
+    EnumDeclaration {
+        enumeration: Enum,
+        reverse_mapping: Option<AstPtr>,
+        span: Span,
     },
 }
 
@@ -74,6 +230,14 @@ pub enum VariableAssignmentOperator {
     MinusAssign,
     StarAssign,
     SlashAssign,
+    PercentAssign,
+    StarStarAssign,
+    AmpersandAssign,
+    VerticalBarAssign,
+    CaretAssign,
+    LeftShiftAssign,
+    RightShiftAssign,
+    UnsignedRightShiftAssign,
 }
 
 #[derive(Debug, PartialEq)]
@@ -91,6 +255,61 @@ impl BlockWithCondition {
     }
 }
 
+// `case <test>:` followed by its block, test is None for `default:`
+#[derive(Debug, PartialEq)]
+pub struct SwitchCase {
+    pub test: Option<Expression>,
+    pub block: Vec<AstPtr>,
+}
+
+impl SwitchCase {
+    pub fn new(test: Option<Expression>, block: Vec<AstPtr>) -> Self {
+        return SwitchCase { test, block };
+    }
+}
+
+// `catch (<binding>)` followed by its block, binding is None for `catch`
+#[derive(Debug, PartialEq)]
+pub struct CatchClause {
+    pub binding: Option<String>,
+    pub block: Vec<AstPtr>,
+}
+
+impl CatchClause {
+    pub fn new(binding: Option<String>, block: Vec<AstPtr>) -> Self {
+        return CatchClause { binding, block };
+    }
+}
+
+impl Declaration {
+    pub fn get_span(&self) -> Span {
+        match self {
+            Declaration::ImportDeclaration { span, .. }
+            | Declaration::VariableDeclaration { span, .. }
+            | Declaration::FunctionDeclaration { span, .. }
+            | Declaration::GenericFunctionDeclaration { span, .. }
+            | Declaration::VariableAssignment { span, .. }
+            | Declaration::ArrayMemberAssignment { span, .. }
+            | Declaration::FieldAssignment { span, .. }
+            | Declaration::NewIfBlockDeclaration { span, .. }
+            | Declaration::WhileLoopDeclaration { span, .. }
+            | Declaration::DoWhileLoopDeclaration { span, .. }
+            | Declaration::ForLoopDeclaration { span, .. }
+            | Declaration::ForOfLoopDeclaration { span, .. }
+            | Declaration::ForInLoopDeclaration { span, .. }
+            | Declaration::SwitchDeclaration { span, .. }
+            | Declaration::LoopControlFlow { span, .. }
+            | Declaration::TryStatement { span, .. }
+            | Declaration::ThrowStatement { span, .. }
+            | Declaration::LabeledStatement { span, .. }
+            | Declaration::ReturnStatement { span, .. }
+            | Declaration::TypeDeclaration { span, .. }
+            | Declaration::ClassDeclaration { span, .. }
+            | Declaration::EnumDeclaration { span, .. } => return *span,
+        }
+    }
+}
+
 impl VariableAssignmentOperator {
     pub fn is_lexer_assignment_operator(token: &Token) -> bool {
         match token {
@@ -98,9 +317,40 @@ impl VariableAssignmentOperator {
             | Token::MinusAssign
             | Token::PlusAssign
             | Token::SlashAssign
-            | Token::StarAssign => true,
+            | Token::StarAssign
+            | Token::PercentAssign
+            | Token::StarStarAssign
+            | Token::AmpersandAssign
+            | Token::VerticalBarAssign
+            | Token::CaretAssign
+            | Token::LeftShiftAssign
+            | Token::RightShiftAssign
+            | Token::UnsignedRightShiftAssign => true,
 
             _ => false,
         }
     }
+
+    // `a <operator>= b` is evaluated as `a = a <operator> b`
+    pub fn get_binary_operator(&self) -> Option<BinaryOperator> {
+        let operator = match self {
+            VariableAssignmentOperator::Assign => return None,
+            VariableAssignmentOperator::PlusAssign => BinaryOperator::Plus,
+            VariableAssignmentOperator::MinusAssign => BinaryOperator::Minus,
+            VariableAssignmentOperator::StarAssign => BinaryOperator::Star,
+            VariableAssignmentOperator::SlashAssign => BinaryOperator::Slash,
+            VariableAssignmentOperator::PercentAssign => BinaryOperator::Percent,
+            VariableAssignmentOperator::StarStarAssign => BinaryOperator::StarStar,
+            VariableAssignmentOperator::AmpersandAssign => BinaryOperator::Ampersand,
+            VariableAssignmentOperator::VerticalBarAssign => BinaryOperator::VerticalBar,
+            VariableAssignmentOperator::CaretAssign => BinaryOperator::Caret,
+            VariableAssignmentOperator::LeftShiftAssign => BinaryOperator::LeftShift,
+            VariableAssignmentOperator::RightShiftAssign => BinaryOperator::RightShift,
+            VariableAssignmentOperator::UnsignedRightShiftAssign => {
+                BinaryOperator::UnsignedRightShift
+            }
+        };
+
+        return Some(operator);
+    }
 }
@@ -0,0 +1,126 @@
+use indexmap::IndexMap;
+
+use crate::{
+    data_type::DataType,
+    expression::Expression,
+    transform::{transform_ast, Transform},
+    AstPtr,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EnumValue {
+    Number(f64),
+    String(String),
+}
+
+/*
+ * Everything known about an enum once its declaration is parsed
+ *
+ *      enum Color { Red, Green = 5, Blue }
+ *
+ * A member without an initializer takes the value of the member before it plus
+ * one, the first one takes 0. Members are either all numbers or all strings
+ *
+ * Members of every enum are replaced by their values where they are used. A
+ * numeric enum which is not a const enum can also be indexed by a value to get
+ * the name of its member, e.g. `Color[5]` is "Green"
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Enum {
+    // Name in the source, used in messages
+    pub name: String,
+    pub llvm_name: String,
+    pub members: IndexMap<String, EnumValue>,
+    pub is_const: bool,
+}
+
+impl Enum {
+    pub fn is_numeric(&self) -> bool {
+        return self
+            .members
+            .values()
+            .all(|value| matches!(value, EnumValue::Number(_)));
+    }
+
+    pub fn get_data_type(&self) -> DataType {
+        return DataType::EnumType {
+            name: self.llvm_name.clone(),
+            is_numeric: self.is_numeric(),
+        };
+    }
+
+    pub fn has_reverse_mapping(&self) -> bool {
+        return !self.is_const && self.is_numeric();
+    }
+
+    // Function returning the name of the member with the value, if there is one
+    pub fn get_reverse_mapping_name(&self) -> String {
+        return format!("|enum|{}", self.llvm_name);
+    }
+
+    pub fn get_reverse_mapping_type() -> DataType {
+        return DataType::FunctionType {
+            arguments: vec![DataType::Float],
+            return_type: Box::new(DataType::UnionType {
+                types: vec![DataType::String, DataType::Undefined],
+            }),
+        };
+    }
+}
+
+/*
+ * Asts of a file as llvm gets them, with members of enums replaced by their values
+ * and enums by the data type of the values
+ *
+ * */
+pub fn erase_enums(asts: &[AstPtr]) -> Vec<AstPtr> {
+    return asts
+        .iter()
+        .map(|ast| transform_ast(ast, &EnumEraser))
+        .collect();
+}
+
+struct EnumEraser;
+
+impl Transform for EnumEraser {
+    fn transform_data_type(&self, data_type: &DataType) -> DataType {
+        return data_type.erase_enums();
+    }
+
+    fn replace_expression(&self, exp: &Expression) -> Option<Expression> {
+        match exp {
+            Expression::EnumMember {
+                value: EnumValue::Number(value),
+                span,
+                ..
+            } => {
+                return Some(Expression::FloatLiteralExp {
+                    name: value.to_string(),
+                    value: *value,
+                    span: *span,
+                })
+            }
+
+            Expression::EnumMember {
+                value: EnumValue::String(value),
+                span,
+                ..
+            } => {
+                return Some(Expression::StringLiteralExp {
+                    value: value.clone(),
+                    span: *span,
+                })
+            }
+
+            // Member converted to the data type of its value is the value itself
+            Expression::StructuralConversion { exp, data_type, .. }
+                if exp.get_data_type().has_same_layout(data_type) =>
+            {
+                return Some(*exp.clone())
+            }
+
+            _ => return None,
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use std::collections::HashMap;
 
-use crate::data_type::DataType;
+use lexer::token::Span;
+
+use crate::{
+    data_type::{create_union, DataType},
+    enumeration::EnumValue,
+    Ast,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 
@@ -8,6 +14,9 @@ pub enum UnaryOperator {
     Plus,  // +
     Minus, // -
     Bang,  // !
+    Tilde, // ~
+
+    Typeof, // typeof
 }
 #[derive(Debug, PartialEq, Clone)]
 
@@ -17,13 +26,23 @@ pub enum BinaryOperator {
     Star,  // *
     Slash, // /
 
+    Percent,  // %
+    StarStar, // **
+
     VerticalBar, // |
     Caret,       // ^
     Ampersand,   // &
 
+    LeftShift,          // <<
+    RightShift,         // >>
+    UnsignedRightShift, // >>>
+
     StrictEquality, // ===
     StrictNotEqual, // !==
 
+    Equality, // ==
+    NotEqual, // !=
+
     LessThan,        // <
     LessThanOrEqual, // <=
 
@@ -31,59 +50,271 @@ pub enum BinaryOperator {
     GreaterThanOrEqual, // >=
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum UpdateOperator {
+    Increment, // ++
+    Decrement, // --
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LogicalOperator {
+    And,               // &&
+    Or,                // ||
+    NullishCoalescing, // ??
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConsoleMethod {
+    Log,   // console.log writes to stdout
+    Error, // console.error writes to stderr
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrayMethod {
+    Push,    // arr.push(a, b) returns the new length
+    Pop,     // arr.pop() returns the removed element
+    Slice,   // arr.slice(start?, end?) returns a new array
+    IndexOf, // arr.indexOf(a) returns -1 when a is not found
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     // Literal Expressions
     FloatLiteralExp {
         name: String,
         value: f64,
+        span: Span,
     },
     StringLiteralExp {
         value: String,
+        span: Span,
     },
     BooleanLiteralExp {
         name: String,
         value: bool,
+        span: Span,
+    },
+    NullLiteralExp {
+        span: Span,
+    },
+    UndefinedLiteralExp {
+        span: Span,
+    },
+    /*
+     * `a${x}b${y}c` is stored as quasis ["a", "b", "c"] and expressions [x, y]
+     * there is always one more quasi than expressions
+     *
+     * */
+    TemplateLiteral {
+        quasis: Vec<String>,
+        expressions: Vec<Expression>,
+        span: Span,
     },
     ArrayLiteral {
         expression: Box<Vec<Expression>>,
         expression_data_type: DataType, // Should correspond to DataType::ArrayType
+        span: Span,
     },
     ObjectLiteral {
         expression: HashMap<String, Expression>,
         data_type: DataType, // should correspond to DataType::ObjectType
+        span: Span,
     },
 
     IdentExp {
         name: String,
         data_type: DataType,
+        span: Span,
     },
 
     UnaryExp {
         operator: UnaryOperator,
         argument: Box<Expression>,
+        span: Span,
     },
 
     BinaryExp {
         operator: BinaryOperator,
         left: Box<Expression>,
         right: Box<Expression>,
+        span: Span,
+    },
+
+    /*
+     * `++x` evaluates to the updated value and `x++` to the value before the update,
+     * argument is either a variable or an array element holding a number
+     *
+     * */
+    UpdateExp {
+        operator: UpdateOperator,
+        prefix: bool,
+        argument: Box<Expression>,
+        span: Span,
+    },
+
+    /*
+     * Right side of a logical expression is only evaluated when the left side does
+     * not decide the result, so it is kept apart from BinaryExp
+     *
+     * */
+    LogicalExp {
+        operator: LogicalOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+        span: Span,
+    },
+
+    // <test> ? <consequent> : <alternate>
+    ConditionalExp {
+        test: Box<Expression>,
+        consequent: Box<Expression>,
+        alternate: Box<Expression>,
+        span: Span,
     },
 
     ArrayMemberAccess {
         array: Box<Expression>,
         argument: Box<Expression>,
+        span: Span,
     },
 
     DotMemberAccess {
         container: Box<Expression>,
         argument: String,
+        span: Span,
+    },
+
+    /*
+     * `container?.argument` is undefined when the container is null or undefined,
+     * data_type is the data type of the field with undefined added to it
+     *
+     * */
+    OptionalDotMemberAccess {
+        container: Box<Expression>,
+        argument: String,
+        data_type: DataType,
+        span: Span,
+    },
+
+    // Field of an instance of a class, data_type is the data type of the field
+    FieldAccess {
+        object: Box<Expression>,
+        field: String,
+        data_type: DataType,
+        span: Span,
+    },
+
+    ArrayLength {
+        array: Box<Expression>,
+        span: Span,
+    },
+
+    ArrayMethodCall {
+        array: Box<Expression>,
+        method: ArrayMethod,
+        arguments: Vec<Expression>,
+        span: Span,
     },
 
     FunctionCall {
         fn_exp: Box<Expression>,
         parameters: Vec<Expression>,
         return_type: DataType,
+        span: Span,
+    },
+    /*
+     * Calls the method found in the vtable of the instance, so an overriding method
+     * is called even when the instance is used as one of its parent class. The
+     * instance is passed as `this` before the arguments
+     *
+     * */
+    MethodCall {
+        object: Box<Expression>,
+        method: String,
+        arguments: Vec<Expression>,
+        return_type: DataType,
+        span: Span,
+    },
+    /*
+     * `exp instanceof <class>`, the instance is checked against the vtable of the
+     * class and the vtables of its ancestors
+     *
+     * */
+    InstanceofExp {
+        exp: Box<Expression>,
+        class: DataType, // Should correspond to DataType::ClassType
+        span: Span,
+    },
+    /*
+     * Converts between a union type and its members, a member is boxed into the
+     * union and a union narrowed by `typeof` or `instanceof` is unboxed into the
+     * member, converting a union into another union boxes the member again with
+     * its tag in that union
+     *
+     * */
+    UnionConversion {
+        exp: Box<Expression>,
+        data_type: DataType,
+        span: Span,
+    },
+    /*
+     * Converts a value assignable to data_type, see data_type::is_assignable, into
+     * the layout of data_type e.g. `number` passed as `number | string` inside of
+     * a function type
+     *
+     * Arrays and objects are shared by reference so they are never copied, except
+     * a new object literal having more properties than data_type
+     *
+     * */
+    StructuralConversion {
+        exp: Box<Expression>,
+        data_type: DataType,
+        span: Span,
+    },
+    /*
+     * Arrow function, function expression or function declared inside of another
+     * function. function is the FunctionDeclaration of its body, the variables it
+     * captures are put in the environment of the closure when it is created
+     *
+     * */
+    Closure {
+        function: &'static Ast,
+        data_type: DataType,
+        span: Span,
+    },
+    /*
+     * Generic function with the type arguments it is called with, llvm generates a
+     * function for every list of type arguments. data_type is the data type of the
+     * function with the type arguments in place of the type parameters
+     *
+     * */
+    GenericInstance {
+        name: String, // llvm name of the generic function
+        type_arguments: Vec<DataType>,
+        data_type: DataType,
+        span: Span,
+    },
+    /*
+     * `Color.Red`, the value of every member is known when the enum is declared
+     * so it is replaced by a literal of the value before llvm sees it, see
+     * enumeration::erase_enums
+     *
+     * */
+    EnumMember {
+        member: String,
+        value: EnumValue,
+        data_type: DataType,
+        span: Span,
+    },
+    /*
+     * console.log(a, b, c) takes any number of arguments of any data type, so
+     * it is not typed as a regular FunctionCall
+     *
+     * */
+    ConsoleCall {
+        method: ConsoleMethod,
+        arguments: Vec<Expression>,
+        span: Span,
     },
     NAExp,
 }
@@ -91,12 +322,28 @@ pub enum Expression {
 impl Expression {
     pub fn get_data_type(&self) -> DataType {
         match self {
-            Expression::FloatLiteralExp { name: _, value: _ } => return DataType::Float,
-            Expression::StringLiteralExp { value: _ } => return DataType::String,
-            Expression::BooleanLiteralExp { name: _, value: _ } => return DataType::Boolean,
+            Expression::FloatLiteralExp {
+                name: _,
+                value: _,
+                span: _,
+            } => return DataType::Float,
+            Expression::StringLiteralExp { value: _, span: _ } => return DataType::String,
+            Expression::BooleanLiteralExp {
+                name: _,
+                value: _,
+                span: _,
+            } => return DataType::Boolean,
+            Expression::NullLiteralExp { span: _ } => return DataType::Null,
+            Expression::UndefinedLiteralExp { span: _ } => return DataType::Undefined,
+            Expression::TemplateLiteral {
+                quasis: _,
+                expressions: _,
+                span: _,
+            } => return DataType::String,
             Expression::ArrayLiteral {
                 expression: _,
                 expression_data_type: data_type,
+                span: _,
             } => {
                 return DataType::ArrayType {
                     base_type: Box::new(data_type.clone()),
@@ -105,37 +352,64 @@ impl Expression {
             Expression::ObjectLiteral {
                 expression: _,
                 data_type,
+                span: _,
             } => {
                 return data_type.clone();
             }
 
-            Expression::IdentExp { name: _, data_type } => return data_type.clone(),
+            Expression::IdentExp {
+                name: _,
+                data_type,
+                span: _,
+            } => return data_type.clone(),
 
             Expression::UnaryExp {
                 operator,
                 argument: _,
+                span: _,
             } => match operator {
                 UnaryOperator::Bang => return DataType::Boolean,
-                UnaryOperator::Minus | UnaryOperator::Plus => return DataType::Float,
+                UnaryOperator::Typeof => return DataType::String,
+                UnaryOperator::Minus | UnaryOperator::Plus | UnaryOperator::Tilde => {
+                    return DataType::Float
+                }
             },
 
             Expression::BinaryExp {
                 operator,
                 left,
                 right,
+                span: _,
             } => match operator {
                 BinaryOperator::Minus
                 | BinaryOperator::Star
                 | BinaryOperator::Slash
+                | BinaryOperator::Percent
+                | BinaryOperator::StarStar
                 | BinaryOperator::VerticalBar
                 | BinaryOperator::Caret
-                | BinaryOperator::Ampersand => return DataType::Float,
+                | BinaryOperator::Ampersand
+                | BinaryOperator::LeftShift
+                | BinaryOperator::RightShift
+                | BinaryOperator::UnsignedRightShift => return DataType::Float,
 
                 BinaryOperator::Plus => {
                     let left_data_type = left.get_data_type();
                     let right_data_type = right.get_data_type();
 
-                    if left_data_type == DataType::String || right_data_type == DataType::String {
+                    // Member of a string enum is concatenated like a string
+                    let is_string = |data_type: &DataType| {
+                        matches!(
+                            data_type,
+                            DataType::String
+                                | DataType::EnumType {
+                                    is_numeric: false,
+                                    ..
+                                }
+                        )
+                    };
+
+                    if is_string(&left_data_type) || is_string(&right_data_type) {
                         return DataType::String;
                     } else {
                         return DataType::Float;
@@ -144,22 +418,61 @@ impl Expression {
 
                 BinaryOperator::StrictEquality
                 | BinaryOperator::StrictNotEqual
+                | BinaryOperator::Equality
+                | BinaryOperator::NotEqual
                 | BinaryOperator::LessThan
                 | BinaryOperator::LessThanOrEqual
                 | BinaryOperator::GreaterThan
                 | BinaryOperator::GreaterThanOrEqual => return DataType::Boolean,
             },
 
+            Expression::UpdateExp {
+                operator: _,
+                prefix: _,
+                argument: _,
+                span: _,
+            } => return DataType::Float,
+
+            /*
+             * Both sides are checked to have the same datatype by the parser, except
+             * for `??` whose right side has the data type of the result as null and
+             * undefined are removed from the left side
+             *
+             * */
+            Expression::LogicalExp {
+                operator,
+                left,
+                right,
+                span: _,
+            } => match operator {
+                LogicalOperator::NullishCoalescing => return right.get_data_type(),
+                LogicalOperator::And | LogicalOperator::Or => return left.get_data_type(),
+            },
+
+            Expression::ConditionalExp {
+                test: _,
+                consequent,
+                alternate: _,
+                span: _,
+            } => return consequent.get_data_type(),
+
             /*
              * It only validates that datatype of field "array" is of DataType::ArrayType
              * it does not validate weather argument is valid or not
              *
+             * Element which does not exist reads as undefined, so undefined is added
+             * to the element type. Assignment to the element is of the element type
+             *
              * */
-            Expression::ArrayMemberAccess { argument: _, array } => {
+            Expression::ArrayMemberAccess {
+                argument: _,
+                array,
+                span: _,
+            } => {
                 let data_type = array.get_data_type();
 
                 if let DataType::ArrayType { base_type } = data_type {
-                    return base_type.as_ref().clone();
+                    return create_union(vec![base_type.as_ref().clone(), DataType::Undefined]);
                 } else {
                     unreachable!();
                 }
@@ -168,6 +481,7 @@ impl Expression {
             Expression::DotMemberAccess {
                 argument,
                 container,
+                span: _,
             } => {
                 let exp_data_type = container.get_data_type();
 
@@ -181,13 +495,167 @@ impl Expression {
                 }
             }
 
+            Expression::OptionalDotMemberAccess {
+                container: _,
+                argument: _,
+                data_type,
+                span: _,
+            } => return data_type.clone(),
+
+            Expression::FieldAccess {
+                object: _,
+                field: _,
+                data_type,
+                span: _,
+            } => return data_type.clone(),
+
+            Expression::ArrayLength { array: _, span: _ } => return DataType::Float,
+
+            Expression::ArrayMethodCall {
+                array,
+                method,
+                arguments: _,
+                span: _,
+            } => match method {
+                ArrayMethod::Push | ArrayMethod::IndexOf => return DataType::Float,
+                ArrayMethod::Slice => return array.get_data_type(),
+                // Popping an empty array gives undefined
+                ArrayMethod::Pop => {
+                    if let DataType::ArrayType { base_type } = array.get_data_type() {
+                        return create_union(vec![base_type.as_ref().clone(), DataType::Undefined]);
+                    } else {
+                        unreachable!();
+                    }
+                }
+            },
+
             Expression::FunctionCall {
                 parameters: _,
                 fn_exp: _,
                 return_type,
+                span: _,
+            }
+            | Expression::MethodCall {
+                object: _,
+                method: _,
+                arguments: _,
+                return_type,
+                span: _,
             } => return return_type.clone(),
 
+            Expression::InstanceofExp {
+                exp: _,
+                class: _,
+                span: _,
+            } => return DataType::Boolean,
+
+            Expression::UnionConversion {
+                exp: _,
+                data_type,
+                span: _,
+            }
+            | Expression::StructuralConversion {
+                exp: _,
+                data_type,
+                span: _,
+            }
+            | Expression::Closure {
+                function: _,
+                data_type,
+                span: _,
+            }
+            | Expression::GenericInstance {
+                name: _,
+                type_arguments: _,
+                data_type,
+                span: _,
+            }
+            | Expression::EnumMember {
+                member: _,
+                value: _,
+                data_type,
+                span: _,
+            } => return data_type.clone(),
+
+            Expression::ConsoleCall {
+                method: _,
+                arguments: _,
+                span: _,
+            } => return DataType::Void,
+
             Expression::NAExp => return DataType::NA,
         }
     }
+
+    pub fn get_span(&self) -> Span {
+        match self {
+            Expression::FloatLiteralExp { span, .. }
+            | Expression::StringLiteralExp { span, .. }
+            | Expression::BooleanLiteralExp { span, .. }
+            | Expression::NullLiteralExp { span }
+            | Expression::UndefinedLiteralExp { span }
+            | Expression::TemplateLiteral { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::ObjectLiteral { span, .. }
+            | Expression::IdentExp { span, .. }
+            | Expression::UnaryExp { span, .. }
+            | Expression::BinaryExp { span, .. }
+            | Expression::UpdateExp { span, .. }
+            | Expression::LogicalExp { span, .. }
+            | Expression::ConditionalExp { span, .. }
+            | Expression::ArrayMemberAccess { span, .. }
+            | Expression::DotMemberAccess { span, .. }
+            | Expression::OptionalDotMemberAccess { span, .. }
+            | Expression::FieldAccess { span, .. }
+            | Expression::ArrayLength { span, .. }
+            | Expression::ArrayMethodCall { span, .. }
+            | Expression::FunctionCall { span, .. }
+            | Expression::MethodCall { span, .. }
+            | Expression::InstanceofExp { span, .. }
+            | Expression::UnionConversion { span, .. }
+            | Expression::StructuralConversion { span, .. }
+            | Expression::Closure { span, .. }
+            | Expression::GenericInstance { span, .. }
+            | Expression::EnumMember { span, .. }
+            | Expression::ConsoleCall { span, .. } => return *span,
+
+            Expression::NAExp => return Span::default(),
+        }
+    }
+
+    pub(crate) fn get_span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            Expression::FloatLiteralExp { span, .. }
+            | Expression::StringLiteralExp { span, .. }
+            | Expression::BooleanLiteralExp { span, .. }
+            | Expression::NullLiteralExp { span }
+            | Expression::UndefinedLiteralExp { span }
+            | Expression::TemplateLiteral { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::ObjectLiteral { span, .. }
+            | Expression::IdentExp { span, .. }
+            | Expression::UnaryExp { span, .. }
+            | Expression::BinaryExp { span, .. }
+            | Expression::UpdateExp { span, .. }
+            | Expression::LogicalExp { span, .. }
+            | Expression::ConditionalExp { span, .. }
+            | Expression::ArrayMemberAccess { span, .. }
+            | Expression::DotMemberAccess { span, .. }
+            | Expression::OptionalDotMemberAccess { span, .. }
+            | Expression::FieldAccess { span, .. }
+            | Expression::ArrayLength { span, .. }
+            | Expression::ArrayMethodCall { span, .. }
+            | Expression::FunctionCall { span, .. }
+            | Expression::MethodCall { span, .. }
+            | Expression::InstanceofExp { span, .. }
+            | Expression::UnionConversion { span, .. }
+            | Expression::StructuralConversion { span, .. }
+            | Expression::Closure { span, .. }
+            | Expression::GenericInstance { span, .. }
+            | Expression::EnumMember { span, .. }
+            | Expression::ConsoleCall { span, .. } => return Some(span),
+
+            Expression::NAExp => return None,
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::{
+    data_type::DataType,
+    declaration::Declaration,
+    transform::{transform_block, transform_entries, Transform},
+    Ast, AstPtr,
+};
+
+/*
+ * Function declared at the top of a file with type parameters
+ *
+ *      function <name><T, U>(a: T, b: U): <return_type> {
+ *          <block>
+ *      }
+ *
+ * The parser checks its body once with the type parameters as data types of their
+ * own. llvm generates a private function for every list of type arguments it is
+ * called with, from the FunctionDeclaration with the type arguments substituted,
+ * so a module calling a generic function of another file has its own copies
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub struct GenericFunction {
+    pub llvm_name: String,
+    pub type_parameters: Vec<String>,
+    // FunctionDeclaration of the body, None while the body is being parsed
+    pub function: Option<&'static Ast>,
+    /*
+     * Functions declared at the top of a file which the body uses, keyed by their
+     * llvm name. A module using the generic function of another file has to
+     * declare them
+     *
+     * */
+    pub functions: IndexMap<String, DataType>,
+    // Llvm names of the generic functions the body calls
+    pub generic_functions: Vec<String>,
+}
+
+impl GenericFunction {
+    pub fn new(llvm_name: String, type_parameters: Vec<String>) -> Self {
+        return GenericFunction {
+            llvm_name,
+            type_parameters,
+            function: None,
+            functions: IndexMap::new(),
+            generic_functions: vec![],
+        };
+    }
+
+    pub fn get_type_argument_map(&self, type_arguments: &[DataType]) -> HashMap<String, DataType> {
+        return self
+            .type_parameters
+            .iter()
+            .cloned()
+            .zip(type_arguments.iter().cloned())
+            .collect();
+    }
+
+    /*
+     * Name of the function generated for the type arguments, e.g. `identity|_|<Float>`
+     *
+     * Keys of object types are printed with quotes which the assembler does not
+     * accept in a name, they are left out
+     *
+     * */
+    pub fn get_instance_name(&self, type_arguments: &[DataType]) -> String {
+        let type_arguments: Vec<String> = type_arguments
+            .iter()
+            .map(|type_argument| format!("{:?}", type_argument).replace('"', ""))
+            .collect();
+
+        return format!("{}<{}>", self.llvm_name, type_arguments.join(", "));
+    }
+
+    // FunctionDeclaration of the function generated for the type arguments
+    pub fn instantiate(&self, type_arguments: &[DataType]) -> AstPtr {
+        let instance = Instance {
+            name: self.get_instance_name(type_arguments),
+            type_arguments: self.get_type_argument_map(type_arguments),
+        };
+
+        match self.function {
+            Some(Ast::Declaration(Declaration::FunctionDeclaration {
+                ident_name: _,
+                return_type,
+                arguments,
+                blocks,
+                captures,
+                span,
+            })) => {
+                return Ast::new_function_declaration(
+                    transform_entries(arguments, &instance),
+                    transform_block(blocks, &instance),
+                    instance.name.clone(),
+                    instance.transform_data_type(return_type),
+                    transform_entries(captures, &instance),
+                    *span,
+                );
+            }
+
+            function => panic!(
+                "Expected generic function {} to have a FunctionDeclaration but got {:?}",
+                self.llvm_name, function
+            ),
+        }
+    }
+}
+
+// Function being generated for a list of type arguments
+struct Instance {
+    name: String,
+    type_arguments: HashMap<String, DataType>,
+}
+
+impl Transform for Instance {
+    fn transform_data_type(&self, data_type: &DataType) -> DataType {
+        return data_type.substitute(&self.type_arguments);
+    }
+
+    // Closures of every instance are functions of their own
+    fn transform_function_name(&self, name: &str) -> String {
+        return format!("{}{}", self.name, name);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod class;
 pub mod data_type;
 pub mod declaration;
+pub mod enumeration;
 pub mod expression;
+pub mod generic;
+mod transform;
 
+use class::Class;
 use data_type::DataType;
 use declaration::{
-    BlockWithCondition, Declaration, VariableAssignmentOperator, VariableDeclarationKind,
+    BlockWithCondition, CatchClause, Declaration, SwitchCase, VariableAssignmentOperator,
+    VariableDeclarationKind,
 };
+use enumeration::Enum;
 use expression::{BinaryOperator, Expression, UnaryOperator};
+use generic::GenericFunction;
 use indexmap::IndexMap;
+use lexer::token::{KeywordKind, Span};
+use transform::{transform_block, transform_expression, Transform};
 
 #[derive(Debug, PartialEq)]
 pub enum Ast {
@@ -18,57 +28,78 @@ pub enum Ast {
 // constructors
 
 impl Ast {
-    pub fn new_float_literal(name: &String, value: f64) -> AstPtr {
+    pub fn new_float_literal(name: &String, value: f64, span: Span) -> AstPtr {
         return leak_ast(Ast::Expression(Expression::FloatLiteralExp {
             name: name.to_string(),
             value,
+            span,
         }));
     }
 
-    pub fn new_boolean_literal(name: &String, value: bool) -> AstPtr {
+    pub fn new_boolean_literal(name: &String, value: bool, span: Span) -> AstPtr {
         return leak_ast(Ast::Expression(Expression::BooleanLiteralExp {
             name: name.to_string(),
             value,
+            span,
         }));
     }
 
-    pub fn new_string_literal(name: &String) -> AstPtr {
+    pub fn new_string_literal(name: &String, span: Span) -> AstPtr {
         return leak_ast(Ast::Expression(Expression::StringLiteralExp {
             value: name.to_string(),
+            span,
         }));
     }
 
-    pub fn new_unary_exp(argument: Box<Expression>, operator: UnaryOperator) -> AstPtr {
-        return leak_ast(Ast::Expression(Expression::UnaryExp { operator, argument }));
+    pub fn new_unary_exp(argument: Box<Expression>, operator: UnaryOperator, span: Span) -> AstPtr {
+        return leak_ast(Ast::Expression(Expression::UnaryExp {
+            operator,
+            argument,
+            span,
+        }));
     }
 
     pub fn new_binary_exp(
         left: Box<Expression>,
         right: Box<Expression>,
         operator: BinaryOperator,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Expression(Expression::BinaryExp {
             operator,
             left,
             right,
+            span,
         }));
     }
 
-    pub fn new_import_declaration(ident: IndexMap<String, DataType>, from: &str) -> AstPtr {
+    pub fn new_import_declaration(
+        ident: IndexMap<String, DataType>,
+        classes: Vec<Class>,
+        generic_functions: Vec<GenericFunction>,
+        from: &str,
+        span: Span,
+    ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::ImportDeclaration {
             ident,
+            classes,
+            generic_functions,
             from: from.to_string(),
+            span,
         }));
     }
     pub fn new_variable_declaration(
         ident_name: &str,
         exp: Expression,
         kind: VariableDeclarationKind,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::VariableDeclaration {
             ident_name: ident_name.to_string(),
             exp,
             kind,
+            is_captured: false,
+            span,
         }));
     }
 
@@ -76,11 +107,13 @@ impl Ast {
         ident_name: &str,
         operator: VariableAssignmentOperator,
         exp: Expression,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::VariableAssignment {
             operator,
             ident_name: ident_name.to_string(),
             exp,
+            span,
         }));
     }
 
@@ -89,12 +122,30 @@ impl Ast {
         member_access_exp: Expression,
         operator: VariableAssignmentOperator,
         exp: Expression,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::ArrayMemberAssignment {
             operator,
             exp,
             ident_name: ident_name.to_string(),
             member_access_exp,
+            span,
+        }));
+    }
+
+    pub fn new_field_assignment(
+        object: Expression,
+        field: &str,
+        operator: VariableAssignmentOperator,
+        exp: Expression,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::FieldAssignment {
+            object,
+            field: field.to_string(),
+            operator,
+            exp,
+            span,
         }));
     }
 
@@ -102,25 +153,123 @@ impl Ast {
         if_block: BlockWithCondition,
         else_if_block: Vec<BlockWithCondition>,
         else_block: Option<Vec<AstPtr>>,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::NewIfBlockDeclaration {
             if_block,
             else_block: else_block,
             else_if_block,
+            span,
         }));
     }
 
-    pub fn new_while_loop(while_loop: BlockWithCondition) -> AstPtr {
+    pub fn new_while_loop(while_loop: BlockWithCondition, span: Span) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::WhileLoopDeclaration {
             condition: while_loop.condition,
             block: while_loop.block,
+            span,
         }));
     }
 
-    pub fn new_do_while_loop(do_while_loop: BlockWithCondition) -> AstPtr {
+    pub fn new_do_while_loop(do_while_loop: BlockWithCondition, span: Span) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::DoWhileLoopDeclaration {
             condition: do_while_loop.condition,
             block: do_while_loop.block,
+            span,
+        }));
+    }
+
+    pub fn new_for_loop(
+        initializer: Option<AstPtr>,
+        condition: Option<Expression>,
+        update: Option<AstPtr>,
+        block: Vec<AstPtr>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::ForLoopDeclaration {
+            initializer,
+            condition,
+            update,
+            block,
+            span,
+        }));
+    }
+
+    pub fn new_for_of_loop(
+        ident_name: &str,
+        kind: VariableDeclarationKind,
+        iterable: Expression,
+        block: Vec<AstPtr>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::ForOfLoopDeclaration {
+            ident_name: ident_name.to_string(),
+            kind,
+            iterable,
+            block,
+            span,
+        }));
+    }
+
+    pub fn new_for_in_loop(
+        ident_name: &str,
+        kind: VariableDeclarationKind,
+        object: Expression,
+        block: Vec<AstPtr>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::ForInLoopDeclaration {
+            ident_name: ident_name.to_string(),
+            kind,
+            object,
+            block,
+            span,
+        }));
+    }
+
+    pub fn new_switch(discriminant: Expression, cases: Vec<SwitchCase>, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::SwitchDeclaration {
+            discriminant,
+            cases,
+            span,
+        }));
+    }
+
+    pub fn new_loop_control_flow(
+        keyword: KeywordKind,
+        label: Option<String>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::LoopControlFlow {
+            keyword,
+            label,
+            span,
+        }));
+    }
+
+    pub fn new_try_statement(
+        try_block: Vec<AstPtr>,
+        catch_clause: Option<CatchClause>,
+        finally_block: Option<Vec<AstPtr>>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::TryStatement {
+            try_block,
+            catch_clause,
+            finally_block,
+            span,
+        }));
+    }
+
+    pub fn new_throw_statement(exp: Expression, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::ThrowStatement { exp, span }));
+    }
+
+    pub fn new_labeled_statement(label: &str, statement: AstPtr, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::LabeledStatement {
+            label: label.to_string(),
+            statement,
+            span,
         }));
     }
 
@@ -129,23 +278,70 @@ impl Ast {
         blocks: Vec<AstPtr>,
         ident_name: String,
         return_type: DataType,
+        captures: IndexMap<String, DataType>,
+        span: Span,
     ) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::FunctionDeclaration {
             arguments,
             blocks,
             ident_name,
             return_type,
+            captures,
+            span,
         }));
     }
 
-    pub fn new_return_statement(exp: Option<Expression>) -> AstPtr {
+    pub fn new_generic_function_declaration(function: GenericFunction, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::GenericFunctionDeclaration {
+            function,
+            span,
+        }));
+    }
+
+    pub fn new_return_statement(exp: Option<Expression>, span: Span) -> AstPtr {
         return leak_ast(Ast::Declaration(Declaration::ReturnStatement {
             return_exp: exp,
+            span,
+        }));
+    }
+
+    pub fn new_type_declaration(ident_name: &str, data_type: DataType, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::TypeDeclaration {
+            ident_name: ident_name.to_string(),
+            data_type,
+            span,
+        }));
+    }
+
+    pub fn new_class_declaration(class: Class, functions: Vec<AstPtr>, span: Span) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::ClassDeclaration {
+            class,
+            functions,
+            span,
+        }));
+    }
+
+    pub fn new_enum_declaration(
+        enumeration: Enum,
+        reverse_mapping: Option<AstPtr>,
+        span: Span,
+    ) -> AstPtr {
+        return leak_ast(Ast::Declaration(Declaration::EnumDeclaration {
+            enumeration,
+            reverse_mapping,
+            span,
         }));
     }
 }
 
 impl Ast {
+    pub fn get_span(&self) -> Span {
+        match self {
+            Ast::Expression(exp) => return exp.get_span(),
+            Ast::Declaration(dec) => return dec.get_span(),
+        }
+    }
+
     pub fn get_data_type(&self) -> Result<DataType, String> {
         match self {
             Ast::Expression(exp) => {
@@ -160,6 +356,31 @@ impl Ast {
         }
     }
 }
+/*
+ * Copy of the asts with every span set to the default, so tests can compare asts
+ * without spelling out the location of each node
+ *
+ * */
+pub fn erase_spans(asts: &[AstPtr]) -> Vec<AstPtr> {
+    return transform_block(asts, &SpanEraser);
+}
+
+pub fn erase_expression_spans(exp: &Expression) -> Expression {
+    return transform_expression(exp, &SpanEraser);
+}
+
+struct SpanEraser;
+
+impl Transform for SpanEraser {
+    fn transform_data_type(&self, data_type: &DataType) -> DataType {
+        return data_type.clone();
+    }
+
+    fn transform_span(&self, _span: &Span) -> Span {
+        return Span::default();
+    }
+}
+
 pub type AstPtr = &'static mut Ast;
 pub type VecAstPtr = Vec<AstPtr>;
 pub fn leak_ast(ast: Ast) -> AstPtr {
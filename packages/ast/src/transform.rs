@@ -0,0 +1,576 @@
+use indexmap::IndexMap;
+use lexer::token::Span;
+
+use crate::{
+    class::{Class, ClassField, ClassMethod},
+    data_type::DataType,
+    declaration::{BlockWithCondition, CatchClause, Declaration, SwitchCase},
+    expression::Expression,
+    generic::GenericFunction,
+    leak_ast, Ast, AstPtr,
+};
+
+/*
+ * Copy of an ast with its data types changed, it is how llvm gets the body of a
+ * generic function for the type arguments and the asts without enums
+ *
+ * */
+pub(crate) trait Transform {
+    fn transform_data_type(&self, data_type: &DataType) -> DataType;
+
+    // Llvm name of a function declared inside of the ast
+    fn transform_function_name(&self, name: &str) -> String {
+        return name.to_string();
+    }
+
+    fn transform_span(&self, span: &Span) -> Span {
+        return *span;
+    }
+
+    // Expression taking the place of the expression once its parts are transformed
+    fn replace_expression(&self, _exp: &Expression) -> Option<Expression> {
+        return None;
+    }
+}
+
+pub(crate) fn transform_entries<T: Transform>(
+    entries: &IndexMap<String, DataType>,
+    transformer: &T,
+) -> IndexMap<String, DataType> {
+    return entries
+        .iter()
+        .map(|(name, data_type)| (name.clone(), transformer.transform_data_type(data_type)))
+        .collect();
+}
+
+pub(crate) fn transform_block<T: Transform>(block: &[AstPtr], transformer: &T) -> Vec<AstPtr> {
+    return block
+        .iter()
+        .map(|ast| transform_ast(ast, transformer))
+        .collect();
+}
+
+pub(crate) fn transform_ast<T: Transform>(ast: &Ast, transformer: &T) -> AstPtr {
+    match ast {
+        Ast::Expression(exp) => {
+            return leak_ast(Ast::Expression(transform_expression(exp, transformer)))
+        }
+        Ast::Declaration(dec) => {
+            return leak_ast(Ast::Declaration(transform_declaration(dec, transformer)))
+        }
+    }
+}
+
+fn transform_declaration<T: Transform>(dec: &Declaration, transformer: &T) -> Declaration {
+    let exp = |exp: &Expression| transform_expression(exp, transformer);
+    let block = |block: &Vec<AstPtr>| transform_block(block, transformer);
+
+    match dec {
+        Declaration::VariableDeclaration {
+            ident_name,
+            exp: value,
+            kind,
+            is_captured,
+            span,
+        } => {
+            return Declaration::VariableDeclaration {
+                ident_name: ident_name.clone(),
+                exp: exp(value),
+                kind: kind.clone(),
+                is_captured: *is_captured,
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::FunctionDeclaration {
+            ident_name,
+            return_type,
+            arguments,
+            blocks,
+            captures,
+            span,
+        } => {
+            return Declaration::FunctionDeclaration {
+                ident_name: transformer.transform_function_name(ident_name),
+                return_type: transformer.transform_data_type(return_type),
+                arguments: transform_entries(arguments, transformer),
+                blocks: block(blocks),
+                captures: transform_entries(captures, transformer),
+                span: transformer.transform_span(span),
+            };
+        }
+
+        Declaration::VariableAssignment {
+            ident_name,
+            operator,
+            exp: value,
+            span,
+        } => {
+            return Declaration::VariableAssignment {
+                ident_name: ident_name.clone(),
+                operator: operator.clone(),
+                exp: exp(value),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ArrayMemberAssignment {
+            ident_name,
+            member_access_exp,
+            operator,
+            exp: value,
+            span,
+        } => {
+            return Declaration::ArrayMemberAssignment {
+                ident_name: ident_name.clone(),
+                member_access_exp: exp(member_access_exp),
+                operator: operator.clone(),
+                exp: exp(value),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::FieldAssignment {
+            object,
+            field,
+            operator,
+            exp: value,
+            span,
+        } => {
+            return Declaration::FieldAssignment {
+                object: exp(object),
+                field: field.clone(),
+                operator: operator.clone(),
+                exp: exp(value),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::NewIfBlockDeclaration {
+            if_block,
+            else_if_block,
+            else_block,
+            span,
+        } => {
+            let block_with_condition = |if_block: &BlockWithCondition| {
+                BlockWithCondition::new(exp(&if_block.condition), block(&if_block.block))
+            };
+
+            return Declaration::NewIfBlockDeclaration {
+                if_block: block_with_condition(if_block),
+                else_if_block: else_if_block.iter().map(block_with_condition).collect(),
+                else_block: else_block.as_ref().map(block),
+                span: transformer.transform_span(span),
+            };
+        }
+
+        Declaration::WhileLoopDeclaration {
+            condition,
+            block: loop_block,
+            span,
+        } => {
+            return Declaration::WhileLoopDeclaration {
+                condition: exp(condition),
+                block: block(loop_block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::DoWhileLoopDeclaration {
+            condition,
+            block: loop_block,
+            span,
+        } => {
+            return Declaration::DoWhileLoopDeclaration {
+                condition: exp(condition),
+                block: block(loop_block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ForLoopDeclaration {
+            initializer,
+            condition,
+            update,
+            block: loop_block,
+            span,
+        } => {
+            return Declaration::ForLoopDeclaration {
+                initializer: initializer
+                    .as_ref()
+                    .map(|ast| transform_ast(ast, transformer)),
+                condition: condition.as_ref().map(exp),
+                update: update.as_ref().map(|ast| transform_ast(ast, transformer)),
+                block: block(loop_block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ForOfLoopDeclaration {
+            ident_name,
+            kind,
+            iterable,
+            block: loop_block,
+            span,
+        } => {
+            return Declaration::ForOfLoopDeclaration {
+                ident_name: ident_name.clone(),
+                kind: kind.clone(),
+                iterable: exp(iterable),
+                block: block(loop_block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ForInLoopDeclaration {
+            ident_name,
+            kind,
+            object,
+            block: loop_block,
+            span,
+        } => {
+            return Declaration::ForInLoopDeclaration {
+                ident_name: ident_name.clone(),
+                kind: kind.clone(),
+                object: exp(object),
+                block: block(loop_block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::SwitchDeclaration {
+            discriminant,
+            cases,
+            span,
+        } => {
+            return Declaration::SwitchDeclaration {
+                discriminant: exp(discriminant),
+                cases: cases
+                    .iter()
+                    .map(|case| SwitchCase::new(case.test.as_ref().map(exp), block(&case.block)))
+                    .collect(),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::LoopControlFlow {
+            keyword,
+            label,
+            span,
+        } => {
+            return Declaration::LoopControlFlow {
+                keyword: keyword.clone(),
+                label: label.clone(),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::TryStatement {
+            try_block,
+            catch_clause,
+            finally_block,
+            span,
+        } => {
+            return Declaration::TryStatement {
+                try_block: block(try_block),
+                catch_clause: catch_clause.as_ref().map(|catch_clause| {
+                    CatchClause::new(catch_clause.binding.clone(), block(&catch_clause.block))
+                }),
+                finally_block: finally_block.as_ref().map(block),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ThrowStatement { exp: value, span } => {
+            return Declaration::ThrowStatement {
+                exp: exp(value),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::LabeledStatement {
+            label,
+            statement,
+            span,
+        } => {
+            return Declaration::LabeledStatement {
+                label: label.clone(),
+                statement: transform_ast(statement, transformer),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ReturnStatement { return_exp, span } => {
+            return Declaration::ReturnStatement {
+                return_exp: return_exp.as_ref().map(exp),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::TypeDeclaration {
+            ident_name,
+            data_type,
+            span,
+        } => {
+            return Declaration::TypeDeclaration {
+                ident_name: ident_name.clone(),
+                data_type: transformer.transform_data_type(data_type),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ImportDeclaration {
+            ident,
+            classes,
+            generic_functions,
+            from,
+            span,
+        } => {
+            return Declaration::ImportDeclaration {
+                ident: transform_entries(ident, transformer),
+                classes: classes
+                    .iter()
+                    .map(|class| transform_class(class, transformer))
+                    .collect(),
+                generic_functions: generic_functions
+                    .iter()
+                    .map(|generic| transform_generic_function(generic, transformer))
+                    .collect(),
+                from: from.clone(),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::GenericFunctionDeclaration { function, span } => {
+            return Declaration::GenericFunctionDeclaration {
+                function: transform_generic_function(function, transformer),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::ClassDeclaration {
+            class,
+            functions,
+            span,
+        } => {
+            return Declaration::ClassDeclaration {
+                class: transform_class(class, transformer),
+                functions: block(functions),
+                span: transformer.transform_span(span),
+            }
+        }
+
+        Declaration::EnumDeclaration {
+            enumeration,
+            reverse_mapping,
+            span,
+        } => {
+            return Declaration::EnumDeclaration {
+                enumeration: enumeration.clone(),
+                reverse_mapping: reverse_mapping
+                    .as_ref()
+                    .map(|ast| transform_ast(ast, transformer)),
+                span: transformer.transform_span(span),
+            }
+        }
+    }
+}
+
+fn transform_class<T: Transform>(class: &Class, transformer: &T) -> Class {
+    let transform_method = |method: &ClassMethod| ClassMethod {
+        data_type: transformer.transform_data_type(&method.data_type),
+        ..method.clone()
+    };
+    let transform_methods = |methods: &IndexMap<String, ClassMethod>| {
+        methods
+            .iter()
+            .map(|(name, method)| (name.clone(), transform_method(method)))
+            .collect()
+    };
+
+    return Class {
+        fields: class
+            .fields
+            .iter()
+            .map(|(name, field)| {
+                let field = ClassField {
+                    data_type: transformer.transform_data_type(&field.data_type),
+                    ..field.clone()
+                };
+                (name.clone(), field)
+            })
+            .collect(),
+        methods: transform_methods(&class.methods),
+        static_methods: transform_methods(&class.static_methods),
+        constructor: transform_method(&class.constructor),
+        ..class.clone()
+    };
+}
+
+fn transform_generic_function<T: Transform>(
+    generic: &GenericFunction,
+    transformer: &T,
+) -> GenericFunction {
+    let function: Option<&'static Ast> = generic
+        .function
+        .map(|function| &*transform_ast(function, transformer));
+
+    return GenericFunction {
+        function,
+        functions: transform_entries(&generic.functions, transformer),
+        ..generic.clone()
+    };
+}
+
+pub(crate) fn transform_expression<T: Transform>(exp: &Expression, transformer: &T) -> Expression {
+    let mut exp = exp.clone();
+    transform_expression_in_place(&mut exp, transformer);
+
+    return exp;
+}
+
+fn transform_expression_in_place<T: Transform>(exp: &mut Expression, transformer: &T) {
+    let transform = |exp: &mut Expression| transform_expression_in_place(exp, transformer);
+
+    match exp {
+        Expression::FloatLiteralExp { .. }
+        | Expression::StringLiteralExp { .. }
+        | Expression::BooleanLiteralExp { .. }
+        | Expression::NullLiteralExp { .. }
+        | Expression::UndefinedLiteralExp { .. }
+        | Expression::NAExp => {}
+
+        Expression::TemplateLiteral { expressions, .. }
+        | Expression::ConsoleCall {
+            arguments: expressions,
+            ..
+        } => expressions.iter_mut().for_each(transform),
+
+        Expression::ArrayLiteral {
+            expression,
+            expression_data_type: data_type,
+            ..
+        } => {
+            expression.iter_mut().for_each(transform);
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::ObjectLiteral {
+            expression,
+            data_type,
+            ..
+        } => {
+            expression.values_mut().for_each(transform);
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::IdentExp { data_type, .. } => {
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::UnaryExp { argument, .. }
+        | Expression::UpdateExp { argument, .. }
+        | Expression::DotMemberAccess {
+            container: argument,
+            ..
+        }
+        | Expression::ArrayLength {
+            array: argument, ..
+        }
+        | Expression::InstanceofExp { exp: argument, .. } => transform(argument),
+
+        Expression::BinaryExp { left, right, .. }
+        | Expression::LogicalExp { left, right, .. }
+        | Expression::ArrayMemberAccess {
+            array: left,
+            argument: right,
+            ..
+        } => {
+            transform(left);
+            transform(right);
+        }
+
+        Expression::ConditionalExp {
+            test,
+            consequent,
+            alternate,
+            ..
+        } => {
+            transform(test);
+            transform(consequent);
+            transform(alternate);
+        }
+
+        Expression::OptionalDotMemberAccess {
+            container: exp,
+            data_type,
+            ..
+        }
+        | Expression::FieldAccess {
+            object: exp,
+            data_type,
+            ..
+        }
+        | Expression::UnionConversion { exp, data_type, .. }
+        | Expression::StructuralConversion { exp, data_type, .. } => {
+            transform(exp);
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::ArrayMethodCall {
+            array, arguments, ..
+        } => {
+            transform(array);
+            arguments.iter_mut().for_each(transform);
+        }
+
+        Expression::FunctionCall {
+            fn_exp: callee,
+            parameters: arguments,
+            return_type,
+            ..
+        }
+        | Expression::MethodCall {
+            object: callee,
+            arguments,
+            return_type,
+            ..
+        } => {
+            transform(callee);
+            arguments.iter_mut().for_each(transform);
+            *return_type = transformer.transform_data_type(return_type);
+        }
+
+        Expression::Closure {
+            function,
+            data_type,
+            ..
+        } => {
+            *function = transform_ast(function, transformer);
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::GenericInstance {
+            type_arguments: instance_type_arguments,
+            data_type,
+            ..
+        } => {
+            for type_argument in instance_type_arguments.iter_mut() {
+                *type_argument = transformer.transform_data_type(type_argument);
+            }
+            *data_type = transformer.transform_data_type(data_type);
+        }
+
+        Expression::EnumMember { data_type, .. } => {
+            *data_type = transformer.transform_data_type(data_type);
+        }
+    }
+
+    if let Some(span) = exp.get_span_mut() {
+        *span = transformer.transform_span(span);
+    }
+
+    if let Some(replaced) = transformer.replace_expression(exp) {
+        *exp = replaced;
+    }
+}
@@ -7,31 +7,50 @@ use std::{
 
 use ast::AstPtr;
 use inkwell::{context::Context, module::Module};
-use lexer::convert_to_token;
+use lexer::{diagnostic::Diagnostic, tokenize};
 use llvm::{compile_to_llvm_module, compiler_provided_fn::get_compiler_provided_module};
-use parser::{consume_token, symbol_table::SymbolMetaInsert, traits::ImportResolver};
+use parser::{
+    consume_token,
+    symbol_table::{SymbolMetaInsert, TypeSymbol},
+    traits::ImportResolver,
+    ParsedFile,
+};
 use path_absolutize::Absolutize;
 
 use crate::{file_unique_id::FileUniqueId, utils::convert_to_absolute_path};
 
 pub struct CommandLineResolver {
     symbol_db: HashMap<String, HashMap<String, SymbolMetaInsert>>,
+    type_db: HashMap<String, HashMap<String, TypeSymbol>>,
     ast_db: HashMap<String, Vec<AstPtr>>,
+    source_db: HashMap<String, String>,
     id_db: FileUniqueId,
+
+    // Rendered diagnostics of every file which failed to compile
+    reports: Vec<String>,
 }
 
 impl<'a> CommandLineResolver {
     pub fn new() -> Self {
         return CommandLineResolver {
             symbol_db: HashMap::new(),
+            type_db: HashMap::new(),
             ast_db: HashMap::new(),
+            source_db: HashMap::new(),
             id_db: FileUniqueId::new(),
+            reports: vec![],
         };
     }
 
-    pub fn get_main_file_path(&self) -> PathBuf {
-        let name = std::env::args().nth(1).unwrap();
-        return self.get_absolute_file_path(&name);
+    pub fn get_main_file_path(&self) -> Result<PathBuf, String> {
+        let file_name = std::env::args()
+            .skip(1)
+            .find(|arg| return !arg.starts_with("--"));
+
+        match file_name {
+            Some(name) => return Ok(self.get_absolute_file_path(&name)),
+            None => return Err(Diagnostic::error("no input file").render("", "")),
+        }
     }
 
     pub fn get_absolute_file_path(&self, name: &str) -> PathBuf {
@@ -45,34 +64,123 @@ impl<'a> CommandLineResolver {
         return self.get_absolute_file_path(file_name);
     }
 
-    pub fn get_file_content(&self, file_path: &PathBuf) -> String {
-        let content = fs::read_to_string(file_path).unwrap();
-        return content;
+    pub fn get_file_content(&self, file_path: &PathBuf) -> Result<String, String> {
+        match fs::read_to_string(file_path) {
+            Ok(content) => return Ok(content),
+            Err(err) => {
+                let file_name = self.get_display_name(file_path.to_str().unwrap());
+                let message = format!("cannot read file {}: {}", file_name, err);
+                return Err(Diagnostic::error(&message).render(&file_name, ""));
+            }
+        }
     }
 
-    pub fn compile(mut self, context: &'a Context) -> Module<'a> {
-        let main_file_path = self.get_main_file_path();
+    /*
+     * Returns the rendered report of all the errors when compilation fails
+     *
+     * */
+    pub fn compile(mut self, context: &'a Context) -> Result<Module<'a>, Vec<String>> {
+        let main_file_path = self.get_main_file_path().map_err(|err| vec![err])?;
         let main_file_name = main_file_path.to_str().unwrap().to_string();
-        let main_file_content = self.get_file_content(&main_file_path);
-        let main_tokens = convert_to_token(&main_file_content);
 
         self.id_db.insert_main(&main_file_name);
 
-        let (main_ast, _) = consume_token(main_tokens, &mut self, Some(&main_file_name));
+        let main_ast = match self.parse_file(&main_file_name) {
+            Ok((ast, _, _)) => ast,
+            Err(report) => {
+                self.reports.push(report);
+                return Err(self.reports);
+            }
+        };
+
+        let main_llvm_module = compile_to_llvm_module(main_ast, &context, "main", true)
+            .map_err(|diagnostic| self.render(&main_file_name, &diagnostic));
+
+        let modules: Vec<Result<Module<'a>, String>> = std::mem::take(&mut self.ast_db)
+            .into_iter()
+            .map(|(file_name, ast)| {
+                return compile_to_llvm_module(ast, &context, &file_name, false)
+                    .map_err(|diagnostic| self.render(&file_name, &diagnostic));
+            })
+            .collect();
+
+        let mut reports: Vec<String> = vec![];
+        let main_llvm_module = match main_llvm_module {
+            Ok(module) => Some(module),
+            Err(report) => {
+                reports.push(report);
+                None
+            }
+        };
+
+        for module in modules {
+            match module {
+                Ok(module) => {
+                    if let Some(main_llvm_module) = &main_llvm_module {
+                        main_llvm_module.link_module(module).unwrap();
+                    }
+                }
+                Err(report) => reports.push(report),
+            }
+        }
+
+        match main_llvm_module {
+            Some(main_llvm_module) if reports.is_empty() => {
+                let compiler_internal_module =
+                    get_compiler_provided_module(context, self.is_strict_mode());
+                main_llvm_module
+                    .link_module(compiler_internal_module)
+                    .unwrap();
+
+                return Ok(main_llvm_module);
+            }
+            _ => return Err(reports),
+        }
+    }
+
+    /*
+     * Lexes and parses the file, when either of them fails the diagnostics are
+     * rendered against the content of the file
+     *
+     * */
+    fn parse_file(&mut self, absolute_file_name: &str) -> Result<ParsedFile, String> {
+        let content = self.get_file_content(&PathBuf::from(absolute_file_name))?;
+        self.source_db
+            .insert(absolute_file_name.to_string(), content.clone());
 
-        let main_llvm_module = compile_to_llvm_module(main_ast, &context, "main", true);
+        let (tokens, diagnostics) = tokenize(&content);
 
-        for (file_name, ast) in self.ast_db {
-            let module = compile_to_llvm_module(ast, &context, &file_name, false);
-            main_llvm_module.link_module(module).unwrap();
+        if !diagnostics.is_empty() {
+            return Err(self.render_all(absolute_file_name, &diagnostics));
         }
 
-        let compiler_internal_module = get_compiler_provided_module(context);
-        main_llvm_module
-            .link_module(compiler_internal_module)
-            .unwrap();
+        match consume_token(tokens, self, Some(absolute_file_name)) {
+            Ok(result) => return Ok(result),
+            Err(diagnostics) => return Err(self.render_all(absolute_file_name, &diagnostics)),
+        }
+    }
 
-        return main_llvm_module;
+    fn render(&self, absolute_file_name: &str, diagnostic: &Diagnostic) -> String {
+        let content = self.source_db.get(absolute_file_name).unwrap();
+        return diagnostic.render(&self.get_display_name(absolute_file_name), content);
+    }
+
+    fn render_all(&self, absolute_file_name: &str, diagnostics: &Vec<Diagnostic>) -> String {
+        return diagnostics
+            .iter()
+            .map(|diagnostic| self.render(absolute_file_name, diagnostic))
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+
+    // Path of file relative to the directory from where the driver is invoked
+    fn get_display_name(&self, absolute_file_name: &str) -> String {
+        let cwd = std::env::current_dir().unwrap();
+
+        match Path::new(absolute_file_name).strip_prefix(&cwd) {
+            Ok(relative_path) => return relative_path.to_str().unwrap().to_string(),
+            Err(_) => return absolute_file_name.to_string(),
+        }
     }
 
     fn resolve_imported_file_name(&self, relative_file_name: &str, cur_file_name: &str) -> String {
@@ -107,20 +215,36 @@ impl ImportResolver for CommandLineResolver {
         return self.symbol_db.get(&absolute_path);
     }
 
+    fn get_types(
+        &self,
+        relative_file_name: &str,
+        cur_file_name: &str,
+    ) -> Option<&HashMap<String, TypeSymbol>> {
+        let absolute_path = self.resolve_imported_file_name(relative_file_name, cur_file_name);
+        return self.type_db.get(&absolute_path);
+    }
+
     fn resolve(&mut self, relative_file_name: &str, cur_file_name: &str) -> Result<(), String> {
         let absolute_file_name = self.resolve_imported_file_name(relative_file_name, cur_file_name);
 
         if !self.contains(relative_file_name, cur_file_name) {
-            let file_content = self.get_file_content(&PathBuf::from(absolute_file_name.clone()));
             self.id_db.insert(&absolute_file_name);
-            let (ast, table) = consume_token(
-                convert_to_token(&file_content),
-                self,
-                Some(absolute_file_name.as_str()),
-            );
-            self.symbol_db.insert(absolute_file_name.clone(), table);
-            self.ast_db.insert(absolute_file_name, ast);
-            return Ok(());
+
+            match self.parse_file(&absolute_file_name) {
+                Ok((ast, table, types)) => {
+                    self.symbol_db.insert(absolute_file_name.clone(), table);
+                    self.type_db.insert(absolute_file_name.clone(), types);
+                    self.ast_db.insert(absolute_file_name, ast);
+                    return Ok(());
+                }
+                Err(report) => {
+                    self.reports.push(report);
+                    return Err(format!(
+                        "Cannot import from file {} because it failed to compile",
+                        self.get_display_name(&absolute_file_name)
+                    ));
+                }
+            }
         } else {
             return Err(format!(
                 "There is already a file with path {}",
@@ -137,11 +261,32 @@ impl ImportResolver for CommandLineResolver {
         let absolute_file_name = self.resolve_imported_file_name(relative_file_name, cur_file_name);
         return self.get_id_for_file_name(&absolute_file_name);
     }
+
+    /*
+     * With `--strict` reading an element which does not exist is a RangeError instead
+     * of undefined and the parser warns about error prone code
+     *
+     * */
+    fn is_strict_mode(&self) -> bool {
+        return std::env::args().skip(1).any(|arg| return arg == "--strict");
+    }
+
+    // Warnings do not stop the compilation, so they are printed right away
+    fn report_warnings(&mut self, cur_file_name: Option<&str>, warnings: Vec<Diagnostic>) {
+        if let Some(cur_file_name) = cur_file_name {
+            eprintln!("{}", self.render_all(cur_file_name, &warnings));
+        }
+    }
 }
 
-pub fn compile_assembly_to_exec(assembly_file_path: &PathBuf) {
-    Command::new("gcc")
-        .args([assembly_file_path.to_str().unwrap(), "-o", "output"])
-        .status()
-        .unwrap();
+pub fn compile_assembly_to_exec(assembly_file_path: &PathBuf) -> Result<(), String> {
+    let status = Command::new("gcc")
+        .args([assembly_file_path.to_str().unwrap(), "-o", "output", "-lm"])
+        .status();
+
+    match status {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => return Err(format!("error: gcc exited with {}\n", status)),
+        Err(err) => return Err(format!("error: could not run gcc: {}\n", err)),
+    }
 }
@@ -1,6 +1,8 @@
 mod cmd_import_resolver;
 mod file_unique_id;
 mod utils;
+use std::process;
+
 use cmd_import_resolver::{compile_assembly_to_exec, CommandLineResolver};
 use inkwell::context::Context;
 use llvm::write_assembly_file_to_path;
@@ -9,8 +11,22 @@ fn main() {
     let context = Context::create();
     let command_line_resolver = CommandLineResolver::new();
     let assembly_path = command_line_resolver.get_assembly_file_path();
-    let final_module = command_line_resolver.compile(&context);
+
+    let final_module = match command_line_resolver.compile(&context) {
+        Ok(final_module) => final_module,
+        Err(reports) => {
+            for report in reports.iter() {
+                eprintln!("{}", report);
+            }
+            eprintln!("error: aborting due to previous errors");
+            process::exit(1);
+        }
+    };
 
     write_assembly_file_to_path(&final_module, &assembly_path);
-    compile_assembly_to_exec(&assembly_path);
+
+    if let Err(report) = compile_assembly_to_exec(&assembly_path) {
+        eprintln!("{}", report);
+        process::exit(1);
+    }
 }
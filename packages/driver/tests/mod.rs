@@ -12,6 +12,8 @@ use path_absolutize::Absolutize;
 struct TestSetup {
     dir_name: String,
     stdout: Option<String>,
+    stderr: Option<String>,
+    exit_code: Option<i32>,
 }
 
 impl TestSetup {
@@ -21,6 +23,8 @@ impl TestSetup {
         let t_setup = TestSetup {
             dir_name: name,
             stdout: None,
+            stderr: None,
+            exit_code: None,
         };
         t_setup.setup();
         return t_setup;
@@ -79,13 +83,58 @@ impl TestSetup {
     }
 
     pub fn compile(&mut self, main_file_path: &str) {
+        self.compile_with_args(main_file_path, &[]);
+    }
+
+    // Same as compile but the driver is also given `args`, e.g. `--strict`
+    pub fn compile_with_args(&mut self, main_file_path: &str, args: &[&str]) {
         let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
 
         cmd.current_dir(self.get_setup_test_dir_path());
         cmd.arg(main_file_path);
+        cmd.args(args);
         cmd.output().unwrap();
         let output = Command::new(self.get_built_exec_path()).output().unwrap();
         self.stdout = Some(String::from_utf8(output.stdout).unwrap());
+        self.stderr = Some(String::from_utf8(output.stderr).unwrap());
+        self.exit_code = output.status.code();
+    }
+
+    /*
+     * Same as compile but the program runs with its virtual memory limited to
+     * `limit_in_kb`, so a program which never frees memory gets killed
+     *
+     * */
+    pub fn compile_with_memory_limit(&mut self, main_file_path: &str, limit_in_kb: u64) {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        cmd.current_dir(self.get_setup_test_dir_path());
+        cmd.arg(main_file_path);
+        cmd.output().unwrap();
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "ulimit -v {}; exec {}",
+                limit_in_kb,
+                self.get_built_exec_path().display()
+            ))
+            .output()
+            .unwrap();
+        self.stdout = Some(String::from_utf8(output.stdout).unwrap());
+        self.stderr = Some(String::from_utf8(output.stderr).unwrap());
+        self.exit_code = output.status.code();
+    }
+
+    // Compiles the file expecting it to fail and returns the error report
+    pub fn compile_with_error(&self, main_file_path: &str) -> String {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        cmd.current_dir(self.get_setup_test_dir_path());
+        cmd.arg(main_file_path);
+        let output = cmd.output().unwrap();
+
+        assert_eq!(output.status.code(), Some(1));
+        return String::from_utf8(output.stderr).unwrap();
     }
 
     pub fn assert(&self, expected_output: &str) {
@@ -93,6 +142,17 @@ impl TestSetup {
             assert_eq!(actual_output, expected_output);
         }
     }
+
+    pub fn assert_stderr(&self, expected_output: &str) {
+        if let Some(actual_output) = &self.stderr {
+            assert_eq!(actual_output, expected_output);
+        }
+    }
+
+    // Exit code of the program, None when it was killed by a signal
+    pub fn assert_exit_code(&self, expected_code: Option<i32>) {
+        assert_eq!(self.exit_code, expected_code);
+    }
 }
 
 #[test]
@@ -398,3 +458,1621 @@ fn test_reassignment() {
     setup.assert("10");
     setup.clean();
 }
+
+#[test]
+fn test_template_literals() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    import {syscallPrint} from \"compilerInternal\";
+
+    const name = \"World\";
+    const greeting = `Hello ${name}!\\n`;
+
+    syscallPrint(1, greeting, 13);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("Hello World!\n");
+    setup.clean();
+}
+
+#[test]
+fn test_string_concatenation_and_comparison() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    import {syscallPrint} from \"compilerInternal\";
+
+    function greet(name : string) : string {
+        return \"Hello \" + name;
+    }
+
+    let message = greet(\"World\") + \"! \";
+    message += 1.5 + \"\";
+    message = message + \" \" + true;
+
+    syscallPrint(1, message, 21);
+
+    if (\"apple\" < \"banana\") {
+        syscallPrint(1, \" lt\", 3);
+    }
+
+    if (\"abc\" === \"ab\" + \"c\") {
+        syscallPrint(1, \" eq\", 3);
+    }
+
+    if (\"abc\" !== \"abd\") {
+        syscallPrint(1, \" ne\", 3);
+    }
+
+    if (\"b\" > \"abc\") {
+        syscallPrint(1, \" gt\", 3);
+    }
+
+    if (\"ab\" >= \"abc\") {
+        syscallPrint(1, \" ge\", 3);
+    }
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("Hello World! 1.5 true lt eq ne gt");
+    setup.clean();
+}
+
+#[test]
+fn test_number_to_string() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    import {syscallPrint} from \"compilerInternal\";
+
+    const s = `${100} ${0.1 + 0.2} ${1 / 0} ${-0} ${0 / 0} ${-2.5}`;
+    syscallPrint(1, s, 43);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("100 0.30000000000000004 Infinity 0 NaN -2.5");
+    setup.clean();
+}
+
+#[test]
+fn test_number_to_string_exponent_form() {
+    let mut setup = TestSetup::new();
+
+    // Exponent form is used only below 1e-6 and from 1e21 on
+    let main_file = "
+    import {syscallPrint} from \"compilerInternal\";
+
+    const small = `${1e-7} ${0.00001} ${1e-6}`;
+    const large = `${1e18} ${1e19} ${2e20} ${123456789012345680000} ${1e21}`;
+    const other = `${-1.5e-7} ${2 ** 60}`;
+    syscallPrint(1, small + \" \" + large + \" \" + other, 140);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "1e-7 0.00001 0.000001 1000000000000000000 10000000000000000000 200000000000000000000 123456789012345680000 1e+21 -1.5e-7 1152921504606847000",
+    );
+    setup.clean();
+}
+
+#[test]
+fn test_error_report() {
+    let setup = TestSetup::new();
+
+    let main_file = "import {isTrue} from \"./foo.ts\";
+
+let x = isTrue() + 1;";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    let foo_file = "export function isTrue() : boolean {
+    return #;
+}";
+
+    let foo_file_path = "./foo.ts";
+    setup.create_file(foo_file_path, foo_file);
+
+    let report = setup.compile_with_error(main_file_path);
+
+    let expected_report = "error[E0001]: unknown character `#`
+ --> foo.ts:2:12
+  |
+2 |     return #;
+  |            ^ not valid in this position
+
+error: Cannot import from file foo.ts because it failed to compile
+ --> main.ts:1:22
+  |
+1 | import {isTrue} from \"./foo.ts\";
+  |                      ^^^^^^^^^^
+
+error: aborting due to previous errors
+";
+
+    assert_eq!(report, expected_report);
+
+    setup.clean();
+}
+
+#[test]
+fn test_unsupported_construct_report() {
+    let setup = TestSetup::new();
+
+    let main_file = "function sign(x: number): number {
+    if (x < 0) {
+        return -1;
+    }
+    return 1;
+}";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    let report = setup.compile_with_error(main_file_path);
+
+    let expected_report = "error: Return is only supported as a statement of the function body
+ --> main.ts:3:9
+  |
+3 |         return -1;
+  |         ^^^^^^^^^^ nested in a block
+
+error: aborting due to previous errors
+";
+
+    assert_eq!(report, expected_report);
+
+    setup.clean();
+}
+
+#[test]
+fn test_unsupported_operands_report() {
+    let setup = TestSetup::new();
+    let main_file_path = "./main.ts";
+
+    let main_files = [
+        "const t = true + 1;",
+        "const w = -\"a\";",
+        "const x = +\"5\";",
+        "const x = 1 < true;",
+        "const x = true < false;",
+        "const r = { a: 1 } + 1;",
+        "const s = \"a\" - 1;",
+        "const x = (1, 2);",
+        "const x = 1 === \"a\";",
+    ];
+
+    for main_file in main_files {
+        setup.create_file(main_file_path, main_file);
+
+        let report = setup.compile_with_error(main_file_path);
+
+        assert!(report.starts_with("error: "), "{}", report);
+        assert!(!report.contains("panicked"), "{}", report);
+    }
+
+    setup.clean();
+}
+
+#[test]
+fn test_console_log() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function add(x : number, y : number) : number {
+        return x + y;
+    }
+
+    const point = { x: 1.5, y: -2, label: \"origin\", nested: { ok: true } };
+
+    console.log(\"sum:\", add(1, 2), true, 0.1 + 0.2);
+    console.log([1, 2, 3], point);
+    console.log(add);
+    console.log();
+    console.log({ a: { b: { c: { d: 1 } } } });
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "sum: 3 true 0.30000000000000004
+[ 1, 2, 3 ] { x: 1.5, y: -2, label: 'origin', nested: { ok: true } }
+[Function: add]
+
+{ a: { b: { c: [Object] } } }
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_console_log_number_magnitudes() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    console.log(0.00001, 1e19);
+    console.log(1e-7, 1e21, -1e-6, 123456789012345680000);
+    console.log([1e-7, 2e20], { small: 0.000001, large: 1e21 });
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "0.00001 10000000000000000000
+1e-7 1e+21 -0.000001 123456789012345680000
+[ 1e-7, 200000000000000000000 ] { small: 0.000001, large: 1e+21 }
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_console_error() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    console.log(\"to stdout\");
+    console.error(\"to stderr\", 42);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("to stdout\n");
+    setup.assert_stderr("to stderr 42\n");
+    setup.clean();
+}
+
+#[test]
+fn test_garbage_collection() {
+    let mut setup = TestSetup::new();
+
+    /*
+     * Every iteration allocates more than a kilobyte which becomes garbage right
+     * away, without a collector the program would need more than 100MB
+     *
+     * */
+    let main_file = "
+    function makeLabel(n: number): { label: string, n: number } {
+        return { label: \"item \" + n, n: n };
+    }
+
+    const origin = makeLabel(-1);
+
+    let chunk = \"0123456789\";
+    let doubling = 0;
+    while (doubling < 7) {
+        chunk = chunk + chunk;
+        doubling += 1;
+    }
+
+    let kept = \"\";
+    let sum = 0;
+    let count = 0;
+    while (count < 100000) {
+        const garbage = chunk + count;
+        const item = makeLabel(count);
+        sum += item.n;
+        if (count === 99999) {
+            kept = item.label;
+        }
+        count += 1;
+    }
+
+    console.log(kept, sum, origin);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile_with_memory_limit(main_file_path, 64000);
+    setup.assert("item 99999 4999950000 { label: 'item -1', n: -1 }\n");
+    setup.clean();
+}
+
+#[test]
+fn test_arrays() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function sum(values: number[]): number {
+        let total = 0;
+        let i = 0;
+        while (i < values.length) {
+            total += values[i] ?? 0;
+            i += 1;
+        }
+        return total;
+    }
+
+    const numbers = [1, 2, 3];
+    const length = numbers.push(4, 5);
+    numbers[numbers.length] = 6;
+    numbers[0] += 10;
+    const last = numbers.pop();
+
+    console.log(numbers, length, last, sum(numbers));
+    console.log(numbers.slice(1, -1), numbers.slice(-2), numbers.slice());
+    console.log(numbers.indexOf(3), numbers.indexOf(42));
+
+    const words: string[] = [];
+    words.push(\"a\", \"b\");
+    console.log(words, words.indexOf(\"b\"), words.pop(), words.length);
+
+    const flags = [true, false];
+    console.log(flags.indexOf(false), [[1], [2, 3]]);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "[ 11, 2, 3, 4, 5 ] 5 6 25
+[ 2, 3, 4 ] [ 4, 5 ] [ 11, 2, 3, 4, 5 ]
+2 -1
+[ 'a' ] 1 b 1
+1 [ [ 1 ], [ 2, 3 ] ]
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_growing_arrays() {
+    let mut setup = TestSetup::new();
+
+    // Every push past the capacity moves the elements to a new buffer
+    let main_file = "
+    const labels: string[] = [];
+    let count = 0;
+    while (count < 10000) {
+        labels.push(\"item \" + count);
+        count += 1;
+    }
+
+    console.log(labels.length, labels[0], labels[9999], labels.indexOf(\"item 4321\"));
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("10000 item 0 item 9999 4321\n");
+    setup.clean();
+}
+
+#[test]
+fn test_for_loops() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function sum(values: number[]): number {
+        let total = 0;
+        for (const value of values) {
+            total += value;
+        }
+        return total;
+    }
+
+    const squares: number[] = [];
+    for (let i = 0; i < 5; i += 1) {
+        squares.push(i * i);
+    }
+    console.log(squares, sum(squares));
+
+    let count = 0;
+    for (; count < 3;) {
+        count += 1;
+    }
+    console.log(count);
+
+    // Elements pushed while iterating are visited too
+    const queue = [1];
+    for (const item of queue) {
+        if (item < 4) {
+            queue.push(item + 1);
+        }
+    }
+    console.log(queue);
+
+    let characters = \"\";
+    for (const character of \"añ€😀\") {
+        characters = characters + \"[\" + character + \"]\";
+    }
+    console.log(characters);
+
+    const point = { x: 1, y: 2, label: \"origin\" };
+    for (const key in point) {
+        console.log(key);
+    }
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "[ 0, 1, 4, 9, 16 ] 30
+3
+[ 1, 2, 3, 4 ]
+[a][ñ][€][😀]
+x
+y
+label
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_labeled_loops() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function findPair(values: number[], target: number): string {
+        let pair = \"none\";
+        outer: for (let i = 0; i < values.length; i += 1) {
+            for (let j = i + 1; j < values.length; j += 1) {
+                const first = values[i] ?? 0;
+                const second = values[j] ?? 0;
+                if (first + second === target) {
+                    pair = first + \" + \" + second;
+                    break outer;
+                }
+            }
+        }
+        return pair;
+    }
+
+    console.log(findPair([1, 4, 6, 9], 10), findPair([1, 2], 10));
+
+    let rows = \"\";
+    let row = 0;
+    nextRow: while (row < 4) {
+        row += 1;
+        let column = 0;
+        do {
+            column += 1;
+            if (column > row) {
+                continue nextRow;
+            }
+            rows = rows + row + \"\" + column + \" \";
+        } while (column < 3)
+        rows = rows + \"| \";
+    }
+    console.log(rows);
+
+    let skipped = 0;
+    for (const word of [\"a\", \"\", \"b\"]) {
+        if (word === \"\") {
+            skipped += 1;
+            continue;
+        }
+        console.log(word);
+    }
+    console.log(skipped);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "1 + 9 none
+11 21 22 31 32 33 | 41 42 43 | 
+a
+b
+1
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_switch() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function describe(n: number): string {
+        let description = \"\";
+        switch (n) {
+            case 0:
+                description = \"zero\";
+                break;
+            case 1:
+            case 2:
+                description = \"small \";
+            case 3:
+                description = description + \"few\";
+                break;
+            case -1:
+                description = \"negative\";
+                break;
+            default:
+                description = \"many\";
+        }
+        return description;
+    }
+
+    console.log(describe(0), describe(1), describe(3), describe(-1), describe(2.5), describe(0 / 0));
+
+    function greet(language: string): string {
+        let greeting = \"\";
+        switch (language) {
+            default:
+                greeting = \"?\";
+                break;
+            case \"en\":
+                greeting = \"hello\";
+                break;
+            case \"es\":
+                greeting = \"hola\";
+        }
+        return greeting;
+    }
+    console.log(greet(\"es\"), greet(\"fr\"));
+
+    let count = 0;
+    for (let i = 0; i < 6; i += 1) {
+        switch (i > 2) {
+            case true:
+                continue;
+            case false:
+                count += 1;
+        }
+        count += 10;
+    }
+    console.log(count);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "zero small few few negative many many
+hola ?
+33
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_logical_and_conditional_expressions() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function check(value: boolean): boolean {
+        console.log(\"checked\");
+        return value;
+    }
+
+    console.log(false && check(true));
+    console.log(true || check(false));
+    console.log(true && check(false));
+
+    function greet(name: string): string {
+        return \"hello \" + (name || \"stranger\");
+    }
+    console.log(greet(\"\"), greet(\"bob\"));
+    console.log(0 || 0 / 0 || 7, 3 && 4, 1 ?? 2);
+
+    function describe(n: number): string {
+        return n > 9 ? \"many\" : n > 1 ? \"few\" : n === 1 ? \"one\" : \"none\";
+    }
+    console.log(describe(0), describe(1), describe(5), describe(10));
+
+    const values = [1, 2];
+    console.log((values[0] ?? 0) + (values[1] ?? 0) === 3, 10 - 2 * 3 - 1);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "false
+true
+checked
+false
+hello stranger hello bob
+7 4 1
+none one few many
+true 3
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_arithmetic_operators() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    console.log(7 % 3, -7 % 3, 5.5 % 2, 1 % 0);
+    console.log(2 ** 10, 2 ** 3 ** 2, 1 ** (0 / 0), (0 / 0) ** 0);
+    console.log(1 << 31, 1 << 32, -16 >> 2, -1 >>> 0, 5 & 3, 5 | 3, 5 ^ 3, ~5);
+    console.log(4294967297 | 0, (0 / 0) | 0, (1 / 0) | 0, -1.9 | 0);
+
+    let x = 5;
+    console.log(x++, x, ++x, x--, --x);
+
+    let y = 10;
+    y %= 4;
+    y **= 3;
+    y <<= 2;
+    y >>>= 1;
+    y |= 1;
+    console.log(y);
+
+    const counts = [1, 2];
+    counts[1]++;
+    counts[0] *= 10;
+    console.log(counts[0], counts[1]);
+
+    let total = 0;
+    for (let i = 0; i < 4; i++) {
+        total += i;
+    }
+    console.log(total);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "1 -1 1.5 NaN
+1024 512 NaN 1
+-2147483648 1 -4 4294967295 1 7 6 -6
+1 0 0 -1
+5 6 7 7 5
+17
+10 3
+6
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_loose_equality() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    console.log(1 == 1, 0 / 0 == 0 / 0, 0 / 0 != 0 / 0);
+    console.log(\"1\" == 1, \" 12 \" == 12, \"\" == 0, \"abc\" == 0, \"1e3\" == 1000);
+    console.log(\"0x1F\" == 31, \"0b101\" == 5, \"-0x10\" == -16, \"Infinity\" == 1 / 0);
+    console.log(true == 1, false == \"\", true == \"true\", \"a\" != \"b\");
+
+    const point = { x: 1 };
+    const same = point;
+    console.log(point == same, point == { x: 1 }, point == \"[object Object]\");
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "true false true
+true true true false true
+true true false true
+true true false true
+true false true
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_union_types() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function describe(v: number | string): string {
+        return typeof v === \"number\" ? \"number \" + v * 2 : \"string \" + v;
+    }
+
+    let a: number | string = 5;
+    const b: string | number = \"hello\";
+    console.log(describe(a), describe(b), typeof a, typeof b, typeof [1]);
+    console.log(a, `${b}!`, a === 5, a === \"5\", a == \"5\", a !== b);
+
+    a = \"now a string\";
+    console.log(a, typeof a);
+
+    const values = [1, \"two\", true];
+    console.log(values, values.indexOf(\"two\"), values.indexOf(3));
+
+    for (const value of values) {
+        if (typeof value === \"boolean\") {
+            console.log(!value);
+        } else if (typeof value === \"number\") {
+            console.log(value + 1);
+        } else {
+            console.log(value + \"?\");
+        }
+    }
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "number 10 string hello number string object
+5 hello! true false true true
+now a string string
+[ 1, 'two', true ] 1 -1
+2
+two?
+false
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_null_and_undefined() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function greet(name: string, greeting?: string): string {
+        return (greeting ?? \"Hello\") + \", \" + name;
+    }
+
+    let count: number | undefined;
+    console.log(count, count ?? 0, greet(\"a\"), greet(\"b\", \"Hi\"));
+
+    count = 2;
+    if (count !== undefined) {
+        console.log(count * 2);
+    }
+
+    const point: { x: number, label?: string } | null = { x: 1 };
+    console.log(point, point?.x, point?.label, point!.x);
+
+    const missing: { x: number } | null = null;
+    console.log(missing?.x, missing == undefined, missing === undefined, `${missing?.x}`);
+    console.log([1, null, undefined], typeof null, typeof undefined, !null);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert(
+        "undefined 0 Hello, a Hi, b
+4
+{ x: 1, label: undefined } 1 undefined 1
+undefined true false undefined
+[ 1, null, undefined ] object undefined true
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_type_aliases_and_interfaces() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    import { Shape, Point, describe } from \"./shapes.ts\";
+
+    interface Named { label: string }
+    interface Labeled extends Named, Point { color: string }
+    type Id = number | string;
+
+    function squareArea(): number {
+        return 4;
+    }
+
+    const square: Shape = { name: \"square\", area: squareArea };
+    const labeled: Labeled = { label: \"a\", x: 3, y: 4, color: \"red\" };
+    const ids: Id[] = [1, \"two\"];
+    console.log(describe(square), square.sides, labeled.x + labeled.y, ids);
+    ";
+
+    let shapes_file = "
+    export interface Shape {
+        name: string;
+        area(): number;
+    }
+
+    export interface Shape {
+        sides?: number;
+    }
+
+    export type Point = { x: number; y: number };
+
+    export function describe(shape: Shape): string {
+        return shape.name + \" \" + shape.area();
+    }
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.create_file("./shapes.ts", shapes_file);
+
+    setup.compile(main_file_path);
+    setup.assert("square 4 undefined 7 [ 1, 'two' ]\n");
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_structural_assignability() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    interface Point { x: number; y?: number }
+
+    function getX(point: { y: string, x: number }): number {
+        return point.x;
+    }
+
+    function describe(point: Point): string {
+        return \"x \" + point.x + \" y \" + point.y;
+    }
+
+    function apply(callback: (x: number, y: number) => void): void {
+        callback(1, 2);
+    }
+
+    function show(x: number | string): number | string {
+        console.log(\"show\", typeof x, x);
+        return x;
+    }
+
+    function double(x: number): number {
+        console.log(\"double\", x * 2);
+        return x * 2;
+    }
+
+    const point: { y: string, x: number } = { x: 3, y: \"b\" };
+    const narrow: Point[] = [{ x: 1, z: true }, { x: 2, z: false }];
+    let maybe: Point | null = null;
+    maybe = { x: 5, y: 6, z: 7 };
+
+    console.log(getX(point), describe({ x: 4, label: \"a\" }), narrow[1]?.x, narrow.length);
+    if (maybe !== null) {
+        console.log(describe(maybe));
+    }
+    apply(double);
+    apply(show);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("3 x 4 y undefined 2 2\nx 5 y 6\ndouble 2\nshow number 1\n");
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_closures() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    type Callback = (x: number) => number;
+
+    function makeCounter(): () => number {
+        let count = 0;
+        return () => {
+            count += 1;
+            return count;
+        };
+    }
+
+    function apply(callback: Callback, value: number): number {
+        return callback(value);
+    }
+
+    function double(x: number): number {
+        return x * 2;
+    }
+
+    function factorial(n: number): number {
+        function go(k: number): number {
+            let result = 1;
+            if (k > 1) {
+                result = k * go(k - 1);
+            }
+            return result;
+        }
+        return go(n);
+    }
+
+    const counter = makeCounter();
+    counter();
+    const other = makeCounter();
+    console.log(counter(), other());
+
+    const offset = 10;
+    const add = function (a: number, b: number): number {
+        return a + b + offset;
+    };
+    console.log(apply((x: number) => x + offset, 5), apply(double, 21), add(1, 2));
+
+    let callbacks: Callback[] = [];
+    for (let i = 0; i < 3; i++) {
+        const factor = i;
+        callbacks.push((x: number) => x * factor);
+    }
+    const third = callbacks[2];
+    if (third !== undefined) {
+        console.log(third(7), factorial(5));
+    }
+
+    let shared = 0;
+    const increment = () => {
+        shared += 1;
+    };
+    increment();
+    increment();
+    console.log(shared);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("2 1\n15 42 13\n14 120\n2\n");
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_closures_capture_loop_variable() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    type Getter = () => number;
+    type Label = () => string;
+
+    // Element which does not exist reads as undefined
+    const missing = (): number => -1;
+    const noLabel = (): string => \"\";
+
+    let fns: Getter[] = [];
+    for (let i = 0; i < 3; i++) {
+        fns.push(() => i);
+    }
+    console.log((fns[0] ?? missing)(), (fns[1] ?? missing)(), (fns[2] ?? missing)());
+
+    function labels(): Label[] {
+        let result: Label[] = [];
+        for (let s = \"a\"; s !== \"aaaa\"; s = s + \"a\") {
+            result.push(() => s);
+            if (s === \"aa\") {
+                continue;
+            }
+        }
+        return result;
+    }
+    const all = labels();
+    console.log((all[0] ?? noLabel)(), (all[1] ?? noLabel)(), (all[2] ?? noLabel)());
+
+    let skipped: Getter[] = [];
+    for (let j = 0; j < 4; j++) {
+        j += 1;
+        skipped.push(() => j);
+    }
+    console.log((skipped[0] ?? missing)(), (skipped[1] ?? missing)());
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("0 1 2\na aa aaa\n1 3\n");
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_array_out_of_bounds() {
+    let mut setup = TestSetup::new();
+    let main_file_path = "./main.ts";
+
+    // Element which does not exist reads as undefined whatever the element type
+    let arrays = [
+        ("[1, 2]", "number"),
+        ("[\"a\", \"b\"]", "string"),
+        ("[true, false]", "boolean"),
+        ("[{ a: 1 }, { a: 2 }]", "object"),
+        ("[[1], [2]]", "object"),
+    ];
+
+    for (array, type_name) in arrays {
+        let main_file = format!(
+            "
+    const elements = {};
+    console.log(elements[2], elements[-1], elements[0.5], typeof elements[1]);
+    elements.pop();
+    elements.pop();
+    console.log(elements.pop(), elements.length);
+    ",
+            array
+        );
+        setup.create_file(main_file_path, &main_file);
+
+        setup.compile(main_file_path);
+        setup.assert(&format!(
+            "undefined undefined undefined {}\nundefined 0\n",
+            type_name
+        ));
+        setup.assert_stderr("");
+
+        setup.compile_with_args(main_file_path, &["--strict"]);
+        setup.assert("");
+        setup.assert_stderr("Uncaught RangeError: Array index out of bounds\n");
+    }
+
+    let main_file = "
+    const numbers = [1, 2];
+    let total = 0;
+    for (let i = 0; i < 3; i++) {
+        total += numbers[i] ?? 10;
+    }
+    const words: (string | undefined)[] = [];
+    console.log(total, words.pop(), typeof words[0]);
+    console.log(\"unreachable in strict mode\");
+    ";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("13 undefined undefined\nunreachable in strict mode\n");
+    setup.assert_stderr("");
+
+    setup.compile_with_args(main_file_path, &["--strict"]);
+    setup.assert("");
+    setup.assert_stderr("Uncaught RangeError: Array index out of bounds\n");
+
+    // RangeError is thrown like any other exception
+    let main_file = "
+    function read(values: number[], i: number): number {
+        return values[i] ?? -1;
+    }
+
+    const words = [\"a\"];
+    try {
+        console.log(read([1, 2], 1), read([1, 2], 2));
+        words[2] = \"c\";
+        console.log(\"unreachable\");
+    } catch (e) {
+        console.log(\"caught\", e);
+    }
+    words[3] = \"d\";
+    console.log(\"unreachable\");
+    ";
+    setup.create_file(main_file_path, main_file);
+
+    setup.compile(main_file_path);
+    setup.assert("2 -1\ncaught RangeError: Array index out of bounds\n");
+    setup.assert_stderr("Uncaught RangeError: Array index out of bounds\n");
+
+    setup.compile_with_args(main_file_path, &["--strict"]);
+    setup.assert("caught RangeError: Array index out of bounds\n");
+    setup.assert_stderr("Uncaught RangeError: Array index out of bounds\n");
+    setup.clean();
+}
+
+#[test]
+fn test_exceptions() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    function fail(x: number): number {
+        if (x > 2) {
+            throw \"too big \" + x;
+        }
+        return x * 2;
+    }
+
+    try {
+        console.log(fail(1));
+        console.log(fail(5));
+        console.log(\"unreachable\");
+    } catch (e) {
+        if (typeof e === \"string\") {
+            console.log(\"caught\", e);
+        }
+    }
+
+    let log = \"\";
+    for (let i = 0; i < 5; i++) {
+        try {
+            if (i == 1) {
+                continue;
+            }
+            if (i == 3) {
+                break;
+            }
+            log = log + i;
+        } finally {
+            log = log + \"f\";
+        }
+    }
+    console.log(log);
+
+    try {
+        try {
+            throw 42;
+        } finally {
+            console.log(\"inner finally\");
+        }
+    } catch (e) {
+        console.log(\"outer\", e);
+    }
+
+    const thrower = (value: boolean) => {
+        throw value;
+    };
+    try {
+        thrower(true);
+    } catch (e) {
+        console.log(\"closure\", e);
+    }
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert("2\ncaught too big 5\n0ff2ff\ninner finally\nouter 42\nclosure true\n");
+    setup.assert_stderr("");
+    setup.assert_exit_code(Some(0));
+
+    let main_file = "
+    try {
+        console.log(\"before\");
+    } finally {
+        console.log(\"finally\");
+    }
+    throw \"boom\";
+    console.log(\"unreachable\");
+    ";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert("before\nfinally\n");
+    setup.assert_stderr("Uncaught boom\n");
+    setup.assert_exit_code(Some(1));
+    setup.clean();
+}
+
+#[test]
+fn test_throwing_objects() {
+    let mut setup = TestSetup::new();
+    let main_file = "
+    class MyError {
+        message: string;
+
+        constructor(message: string) {
+            this.message = message;
+        }
+    }
+
+    class NotFound extends MyError {
+        constructor(path: string) {
+            super(\"not found: \" + path);
+        }
+    }
+
+    function fail(kind: number): void {
+        if (kind === 0) {
+            throw { code: 1 };
+        }
+        if (kind === 1) {
+            throw [1, 2];
+        }
+        if (kind === 2) {
+            throw new MyError(\"boom\");
+        }
+        throw new NotFound(\"a.ts\");
+    }
+
+    for (let i = 0; i < 4; i++) {
+        try {
+            fail(i);
+        } catch (e) {
+            let text = \"other\";
+            if (e instanceof NotFound) {
+                text = \"missing\";
+            } else if (e instanceof MyError) {
+                text = e.message;
+            }
+            console.log(typeof e, text, e);
+        }
+    }
+
+    throw new NotFound(\"b.ts\");
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert(
+        "object other { code: 1 }\nobject other [ 1, 2 ]\nobject boom MyError { message: 'boom' }\nobject missing NotFound { message: 'not found: a.ts' }\n",
+    );
+    setup.assert_stderr("Uncaught NotFound { message: 'not found: b.ts' }\n");
+    setup.assert_exit_code(Some(1));
+    setup.clean();
+}
+
+#[test]
+fn test_classes() {
+    let mut setup = TestSetup::new();
+
+    let shapes_file = "
+    export class Shape {
+        protected label: string;
+
+        constructor(label: string) {
+            this.label = label;
+        }
+
+        area(): number {
+            return 0;
+        }
+
+        describe(): string {
+            return this.label + \" \" + this.area();
+        }
+
+        static unit(): Shape {
+            return new Shape(\"unit\");
+        }
+    }
+
+    export class Square extends Shape {
+        private side: number;
+
+        constructor(side: number) {
+            super(\"square\");
+            this.side = side;
+        }
+
+        area(): number {
+            return this.side * this.side;
+        }
+    }
+    ";
+    setup.create_file("./shapes.ts", shapes_file);
+
+    // Methods are called through the vtable, so the override of the instance runs
+    let main_file = "
+    import { Shape, Square } from \"./shapes.ts\";
+
+    class Circle extends Shape {
+        r: number;
+        hits: number = 0;
+
+        constructor(r: number) {
+            super(\"circle\");
+            this.r = r;
+        }
+
+        area(): number {
+            this.hits++;
+            return 3 * this.r * this.r;
+        }
+
+        describe(): string {
+            return super.describe() + \"!\";
+        }
+    }
+
+    const shapes: Shape[] = [new Circle(2), new Square(3), Shape.unit()];
+    for (const shape of shapes) {
+        console.log(shape.describe());
+    }
+
+    const circle = new Circle(1);
+    circle.area();
+    circle.r += 1;
+    console.log(circle.area(), circle.hits, circle, new Square(5));
+
+    let count = 0;
+    while (count < 10000) {
+        const square = new Square(count);
+        count += 1;
+    }
+    console.log(count, circle === circle);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert(
+        "circle 12!
+square 9
+unit 0
+12 2 Circle { label: 'circle', r: 2, hits: 2 } Square { label: 'square', side: 5 }
+10000 true
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_classes_as_object_types() {
+    let mut setup = TestSetup::new();
+
+    // Methods of the object are bound to the instance, so they see its later changes
+    let main_file = "
+    interface Shape { area(): number; label?: string }
+
+    class Square {
+        side: number;
+
+        constructor(side: number) {
+            this.side = side;
+        }
+
+        area(): number {
+            return this.side * this.side;
+        }
+    }
+
+    class Cube extends Square {
+        area(): number {
+            return 6 * this.side * this.side;
+        }
+    }
+
+    function total(shapes: Shape[]): number {
+        let sum = 0;
+        for (const shape of shapes) {
+            sum += shape.area();
+        }
+        return sum;
+    }
+
+    const square = new Square(2);
+    const shape: Shape = square;
+    square.side = 3;
+    const loose: { area(): number | string } = new Cube(1);
+
+    console.log(shape.area(), shape.label, total([square, new Cube(1)]), loose.area());
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert("9 undefined 15 6\n");
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_unions_of_object_types() {
+    let mut setup = TestSetup::new();
+
+    let animals_file = "
+    export class Animal {
+        name: string;
+
+        constructor(name: string) {
+            this.name = name;
+        }
+
+        sound(): string {
+            return \"...\";
+        }
+    }
+
+    export class Cat extends Animal {
+        purr(): string {
+            return this.name + \" purrs\";
+        }
+    }
+
+    export class Dog extends Animal {
+        fetch(): string {
+            return this.name + \" fetches\";
+        }
+    }
+    ";
+    setup.create_file("./animals.ts", animals_file);
+
+    // Kitten extends a class of another module, so its vtable points to the imported one
+    let main_file = "
+    import { Animal, Cat, Dog } from \"./animals.ts\";
+
+    class Kitten extends Cat {}
+
+    function describe(pet: Cat | Dog | null): string {
+        let text = \"no pet\";
+        if (pet instanceof Cat) {
+            text = pet.purr();
+        } else if (pet instanceof Dog) {
+            text = pet.fetch();
+        }
+        return text;
+    }
+
+    const animals: Animal[] = [new Animal(\"a\"), new Cat(\"c\"), new Dog(\"d\"), new Kitten(\"k\")];
+    for (const animal of animals) {
+        console.log(animal instanceof Cat, animal instanceof Dog, animal instanceof Kitten);
+    }
+    console.log(describe(new Kitten(\"Kit\")), describe(new Dog(\"Rex\")), describe(null));
+
+    const shape: { kind: string; r: number } | { kind: string; side: number } = { kind: \"square\", side: 2 };
+    let list: number[] | string[] = [1, 2];
+    console.log(shape, list);
+    list = [\"x\"];
+    const wider: number[] | string[] | boolean = list;
+    console.log(wider, typeof wider);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert(
+        "false false false
+true false false
+false true false
+true false true
+Kit purrs Rex fetches no pet
+{ kind: 'square', side: 2 } [ 1, 2 ]
+[ 'x' ] object
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_generics() {
+    let mut setup = TestSetup::new();
+
+    let utils_file = "
+    function twice(x: number): number {
+        return x * 2;
+    }
+
+    export function map<T, U>(arr: T[], f: (x: T) => U): U[] {
+        let out: U[] = [];
+        for (const item of arr) {
+            out.push(f(item));
+        }
+        return out;
+    }
+
+    export function scale<T>(arr: T[], f: (x: T) => number): number[] {
+        return map(arr, (x: T): number => twice(f(x)));
+    }
+    ";
+    setup.create_file("./utils.ts", utils_file);
+
+    // Every list of type arguments gets a function of its own
+    let main_file = "
+    import { map, scale } from \"./utils.ts\";
+
+    type Box<T> = { value: T };
+
+    function identity<T>(x: T): T {
+        return x;
+    }
+
+    function wrap<T>(x: T): Box<T> {
+        const b: Box<T> = { value: x };
+        return b;
+    }
+
+    type Maybe<T> = T | null;
+
+    function orElse<T>(x: Maybe<T>, d: T): T {
+        let result = d;
+        if (x !== null) {
+            result = x;
+        }
+        return result;
+    }
+
+    const boxed: Box<Box<number>> = wrap(wrap(3));
+    console.log(identity(5), identity<string>(\"hi\"), boxed.value.value);
+
+    const missing: Maybe<number> = null;
+    const either: number | string = \"s\";
+    console.log(orElse(missing, 1), orElse<number | string>(either, 2), orElse<null>(null, null));
+
+    const strings = map([1, 2], (x: number): string => \"n\" + x);
+    const exclaimed = map([\"a\", \"b\"], (x: string): string => x + \"!\");
+    console.log(strings[1], exclaimed[0]);
+    console.log(scale([1, 2, 3], (x: number): number => x + 1)[2]);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert(
+        "5 hi 3
+1 s null
+n2 a!
+8
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
+
+#[test]
+fn test_enums() {
+    let mut setup = TestSetup::new();
+
+    let colors_file = "
+    export enum Color { Red, Green = 5, Blue }
+
+    export const enum Size { Small = -1, Large = 10 }
+
+    export function favourite(): Color {
+        return Color.Blue;
+    }
+    ";
+    setup.create_file("./colors.ts", colors_file);
+
+    // Members are replaced by their values, a numeric enum maps values to names
+    let main_file = "
+    import { Color, Size, favourite } from \"./colors.ts\";
+
+    enum Dir { Up = \"UP\", Down = \"DOWN\" }
+
+    function describe(c: Color): string {
+        let name = \"\";
+        switch (c) {
+            case Color.Red:
+                name = \"warm\";
+                break;
+            case Color.Green:
+            case Color.Blue:
+                name = \"cold\";
+                break;
+        }
+        return name;
+    }
+
+    console.log(Color.Red, Color.Green, Color.Blue, Color[5], Color[7]);
+    console.log(\"go \" + Dir.Down, Size.Small + Size.Large);
+
+    const c: Color = favourite();
+    const n: number = c;
+    console.log(describe(c), describe(Color.Red), n);
+    ";
+
+    let main_file_path = "./main.ts";
+    setup.create_file(main_file_path, main_file);
+    setup.compile(main_file_path);
+
+    setup.assert(
+        "0 5 6 Green undefined
+go DOWN 9
+cold warm 6
+",
+    );
+    setup.assert_stderr("");
+    setup.clean();
+}
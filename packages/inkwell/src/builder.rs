@@ -3,11 +3,16 @@ use std::marker::PhantomData;
 use either::Either;
 use llvm_sys::{
     core::{
-        LLVMAddClause, LLVMBuildAlloca, LLVMBuildBr, LLVMBuildCall2, LLVMBuildCondBr,
-        LLVMBuildFAdd, LLVMBuildFCmp, LLVMBuildFDiv, LLVMBuildFMul, LLVMBuildFNeg, LLVMBuildFPToSI,
-        LLVMBuildFSub, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildInvoke2, LLVMBuildLandingPad,
-        LLVMBuildLoad2, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildStore, LLVMBuildXor,
-        LLVMDisposeBuilder, LLVMPositionBuilderAtEnd, LLVMSetCleanup,
+        LLVMAddCase, LLVMAddClause, LLVMAddIncoming, LLVMBuildAShr, LLVMBuildAdd, LLVMBuildAlloca,
+        LLVMBuildAnd, LLVMBuildBitCast, LLVMBuildBr, LLVMBuildCall2, LLVMBuildCondBr,
+        LLVMBuildExtractValue, LLVMBuildFAdd, LLVMBuildFCmp, LLVMBuildFDiv, LLVMBuildFMul,
+        LLVMBuildFNeg, LLVMBuildFPToSI, LLVMBuildFRem, LLVMBuildFSub, LLVMBuildGEP2, LLVMBuildICmp,
+        LLVMBuildInvoke2, LLVMBuildLShr, LLVMBuildLandingPad, LLVMBuildLoad2, LLVMBuildMul,
+        LLVMBuildOr, LLVMBuildPhi, LLVMBuildPtrToInt, LLVMBuildRet, LLVMBuildRetVoid,
+        LLVMBuildSIToFP, LLVMBuildSelect, LLVMBuildShl, LLVMBuildStore, LLVMBuildSub,
+        LLVMBuildSwitch, LLVMBuildTrunc, LLVMBuildUDiv, LLVMBuildUIToFP, LLVMBuildUnreachable,
+        LLVMBuildXor, LLVMBuildZExt, LLVMDisposeBuilder, LLVMGetInsertBlock,
+        LLVMPositionBuilderAtEnd, LLVMSetCleanup,
     },
     prelude::{LLVMBuilderRef, LLVMValueRef},
 };
@@ -17,8 +22,10 @@ use crate::{
     enums::{IntCompareOperator, RealCompareOperator},
     types::{
         enums::BasicTypeEnum,
+        float_type::FloatType,
         fn_type::FunctionType,
         int_type::IntType,
+        ptr_type::PointerType,
         traits::{AsTypeRef, BasicTypeTrait, FloatMathTypeTrait, IntMathTypeTrait},
     },
     utils::to_c_str,
@@ -30,6 +37,7 @@ use crate::{
         int_value::IntValue,
         ptr_value::PointerValue,
         returned_value::ReturnedValue,
+        struct_value::StructValue,
         traits::{AsValueRef, BasicValueTrait, FloatMathValueTrait, IntMathValueTrait},
     },
 };
@@ -55,6 +63,13 @@ impl<'a> Builder<'a> {
         }
     }
 
+    pub fn get_insert_block(&self) -> BasicBlock<'a> {
+        unsafe {
+            let basic_block = LLVMGetInsertBlock(self.builder);
+            return BasicBlock::new(basic_block);
+        }
+    }
+
     pub fn build_gep_2<T: AsTypeRef>(
         &self,
         ty: T,
@@ -174,6 +189,20 @@ impl<'a> Builder<'a> {
         }
     }
 
+    pub fn build_float_rem<T: FloatMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildFRem(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
     pub fn build_float_neg<T: FloatMathValueTrait<'a>>(&self, value: T, name: &str) -> T {
         let c_name = to_c_str(name);
 
@@ -197,13 +226,150 @@ impl<'a> Builder<'a> {
         }
     }
 
+    pub fn build_and<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildAnd(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    pub fn build_or<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildOr(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    pub fn build_left_shift<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildShl(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    /*
+     * Fills the vacated bits with the sign bit if `sign_extend` is true, otherwise
+     * with zeros
+     *
+     * */
+    pub fn build_right_shift<T: IntMathValueTrait<'a>>(
+        &self,
+        lhs: T,
+        rhs: T,
+        sign_extend: bool,
+        name: &str,
+    ) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = if sign_extend {
+                LLVMBuildAShr(
+                    self.builder,
+                    lhs.as_value_ref(),
+                    rhs.as_value_ref(),
+                    c_name.as_ptr(),
+                )
+            } else {
+                LLVMBuildLShr(
+                    self.builder,
+                    lhs.as_value_ref(),
+                    rhs.as_value_ref(),
+                    c_name.as_ptr(),
+                )
+            };
+            return T::new(value);
+        }
+    }
+
+    pub fn build_int_add<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildAdd(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    pub fn build_int_sub<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildSub(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    pub fn build_int_mul<T: IntMathValueTrait<'a>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildMul(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
+    pub fn build_int_unsigned_div<T: IntMathValueTrait<'a>>(
+        &self,
+        lhs: T,
+        rhs: T,
+        name: &str,
+    ) -> T {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildUDiv(
+                self.builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                c_name.as_ptr(),
+            );
+            return T::new(value);
+        }
+    }
+
     pub fn build_int_compare<T: IntMathValueTrait<'a>>(
         &self,
         operator: IntCompareOperator,
         lhs: T,
         rhs: T,
         name: &str,
-    ) -> IntValue {
+    ) -> IntValue<'a> {
         let c_name = to_c_str(name);
 
         unsafe {
@@ -223,7 +389,7 @@ impl<'a> Builder<'a> {
         lhs: T,
         rhs: T,
         name: &str,
-    ) -> IntValue {
+    ) -> IntValue<'a> {
         let c_name = to_c_str(name);
 
         unsafe {
@@ -269,6 +435,14 @@ impl<'a> Builder<'a> {
         }
     }
 
+    // Terminates a block which is never reached, like the one after a call which never returns
+    pub fn build_unreachable(&self) -> InstructionValue<'a> {
+        unsafe {
+            let value = LLVMBuildUnreachable(self.builder);
+            return InstructionValue::new(value);
+        }
+    }
+
     pub fn build_unconditional_branch(
         &self,
         destination_block: &BasicBlock<'a>,
@@ -296,7 +470,43 @@ impl<'a> Builder<'a> {
         }
     }
 
-    pub fn build_fp_to_si(&self, float: FloatValue, int_type: IntType, name: &str) -> IntValue {
+    /*
+     * Jumps to the block of the case equal to `value` or to `else_block` if there
+     * is none, case values have to be constants
+     *
+     * */
+    pub fn build_switch(
+        &self,
+        value: IntValue<'a>,
+        else_block: &BasicBlock<'a>,
+        cases: &[(IntValue<'a>, &BasicBlock<'a>)],
+    ) -> InstructionValue<'a> {
+        unsafe {
+            let switch_value = LLVMBuildSwitch(
+                self.builder,
+                value.as_value_ref(),
+                else_block.basic_block,
+                cases.len() as u32,
+            );
+
+            for (case_value, case_block) in cases {
+                LLVMAddCase(
+                    switch_value,
+                    case_value.as_value_ref(),
+                    case_block.basic_block,
+                );
+            }
+
+            return InstructionValue::new(switch_value);
+        }
+    }
+
+    pub fn build_fp_to_si(
+        &self,
+        float: FloatValue<'a>,
+        int_type: IntType<'a>,
+        name: &str,
+    ) -> IntValue<'a> {
         let c_string = to_c_str(name);
 
         unsafe {
@@ -310,6 +520,174 @@ impl<'a> Builder<'a> {
         }
     }
 
+    pub fn build_si_to_fp(
+        &self,
+        int: IntValue<'a>,
+        float_type: FloatType<'a>,
+        name: &str,
+    ) -> FloatValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildSIToFP(
+                self.builder,
+                int.as_value_ref(),
+                float_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return FloatValue::new(value);
+        }
+    }
+
+    pub fn build_ui_to_fp(
+        &self,
+        int: IntValue<'a>,
+        float_type: FloatType<'a>,
+        name: &str,
+    ) -> FloatValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildUIToFP(
+                self.builder,
+                int.as_value_ref(),
+                float_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return FloatValue::new(value);
+        }
+    }
+
+    // Drops the high bits of `int` which do not fit in `int_type`
+    pub fn build_int_truncate(
+        &self,
+        int: IntValue<'a>,
+        int_type: IntType<'a>,
+        name: &str,
+    ) -> IntValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildTrunc(
+                self.builder,
+                int.as_value_ref(),
+                int_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return IntValue::new(value);
+        }
+    }
+
+    // Fills the high bits of `int` with zeros to make it `int_type`
+    pub fn build_int_z_extend(
+        &self,
+        int: IntValue<'a>,
+        int_type: IntType<'a>,
+        name: &str,
+    ) -> IntValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildZExt(
+                self.builder,
+                int.as_value_ref(),
+                int_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return IntValue::new(value);
+        }
+    }
+
+    pub fn build_ptr_to_int(
+        &self,
+        pointer: PointerValue<'a>,
+        int_type: IntType<'a>,
+        name: &str,
+    ) -> IntValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildPtrToInt(
+                self.builder,
+                pointer.as_value_ref(),
+                int_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return IntValue::new(value);
+        }
+    }
+
+    pub fn build_bitcast(
+        &self,
+        pointer: PointerValue<'a>,
+        pointer_type: PointerType<'a>,
+        name: &str,
+    ) -> PointerValue<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildBitCast(
+                self.builder,
+                pointer.as_value_ref(),
+                pointer_type.as_type_ref(),
+                c_string.as_ptr(),
+            );
+            return PointerValue::new(value);
+        }
+    }
+
+    /*
+     * Both `then_value` and `else_value` must be of same type
+     *
+     * */
+    pub fn build_select(
+        &self,
+        condition: IntValue<'a>,
+        then_value: BasicValueEnum<'a>,
+        else_value: BasicValueEnum<'a>,
+        name: &str,
+    ) -> BasicValueEnum<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let value = LLVMBuildSelect(
+                self.builder,
+                condition.as_value_ref(),
+                then_value.as_value_ref(),
+                else_value.as_value_ref(),
+                c_string.as_ptr(),
+            );
+            return BasicValueEnum::new(value);
+        }
+    }
+
+    /*
+     * Value of the phi is the incoming value of the block from which control came,
+     * every incoming value must be of type `ty`
+     *
+     * */
+    pub fn build_phi(
+        &self,
+        ty: BasicTypeEnum,
+        incoming: &[(BasicValueEnum<'a>, &BasicBlock<'a>)],
+        name: &str,
+    ) -> BasicValueEnum<'a> {
+        let c_string = to_c_str(name);
+
+        unsafe {
+            let phi_value = LLVMBuildPhi(self.builder, ty.as_type_ref(), c_string.as_ptr());
+
+            for (value, basic_block) in incoming {
+                let mut values = [value.as_value_ref()];
+                let mut basic_blocks = [basic_block.basic_block];
+
+                LLVMAddIncoming(phi_value, values.as_mut_ptr(), basic_blocks.as_mut_ptr(), 1);
+            }
+
+            return BasicValueEnum::new(phi_value);
+        }
+    }
+
     pub fn build_invoke_2(
         &self,
         fn_value: Either<&FunctionValue<'a>, &PointerValue<'a>>,
@@ -444,6 +822,26 @@ impl<'a> Builder<'a> {
         }
     }
 
+    // Field `index` of a struct value, like the values returned by build_landing_pad
+    pub fn build_extract_value(
+        &self,
+        aggregate: StructValue<'a>,
+        index: u32,
+        name: &str,
+    ) -> BasicValueEnum<'a> {
+        unsafe {
+            let c_string = to_c_str(name);
+            let value = LLVMBuildExtractValue(
+                self.builder,
+                aggregate.as_value_ref(),
+                index,
+                c_string.as_ptr(),
+            );
+
+            return BasicValueEnum::new(value);
+        }
+    }
+
     /*
      * TODO:
      * Learn more about personality functions
@@ -457,7 +855,7 @@ impl<'a> Builder<'a> {
         clauses: &[BasicValueEnum<'a>],
         is_cleanup: bool,
         name: &str,
-    ) -> BasicValueEnum {
+    ) -> StructValue<'a> {
         unsafe {
             let c_string = to_c_str(name);
             let num_clauses = clauses.len() as u32;
@@ -476,7 +874,7 @@ impl<'a> Builder<'a> {
 
             LLVMSetCleanup(value, is_cleanup as i32);
 
-            return BasicValueEnum::new(value);
+            return StructValue::new(value);
         }
     }
 }
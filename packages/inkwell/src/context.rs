@@ -1,9 +1,10 @@
 use llvm_sys::{
     core::{
-        LLVMAppendBasicBlockInContext, LLVMContextCreate, LLVMContextDispose,
-        LLVMCreateBuilderInContext, LLVMDoubleTypeInContext, LLVMInt1TypeInContext,
-        LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext,
-        LLVMModuleCreateWithNameInContext, LLVMStructTypeInContext, LLVMVoidTypeInContext,
+        LLVMAppendBasicBlockInContext, LLVMConstStringInContext, LLVMContextCreate,
+        LLVMContextDispose, LLVMCreateBuilderInContext, LLVMDoubleTypeInContext,
+        LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMInt64TypeInContext,
+        LLVMInt8TypeInContext, LLVMModuleCreateWithNameInContext, LLVMStructTypeInContext,
+        LLVMVoidTypeInContext,
     },
     prelude::{LLVMContextRef, LLVMTypeRef},
 };
@@ -17,7 +18,7 @@ use crate::{
         traits::AsTypeRef, void_type::VoidType,
     },
     utils::to_c_str,
-    values::{fn_value::FunctionValue, traits::AsValueRef},
+    values::{array_value::ArrayValue, fn_value::FunctionValue, traits::AsValueRef},
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -92,6 +93,18 @@ impl Context {
         unsafe { return VoidType::new(LLVMVoidTypeInContext(self.context)) }
     }
 
+    // Constant `[N x i8]` holding exactly the given bytes, no null byte is added
+    pub fn const_string(&self, bytes: &[u8]) -> ArrayValue {
+        unsafe {
+            return ArrayValue::new(LLVMConstStringInContext(
+                self.context,
+                bytes.as_ptr() as *const std::os::raw::c_char,
+                bytes.len() as u32,
+                true as i32,
+            ));
+        }
+    }
+
     // TODO: I have no idea on what does packed means, should know about it
     pub fn struct_type(&self, field_types: &Vec<BasicTypeEnum>, packed: bool) -> StructType {
         let mut field_types: Vec<LLVMTypeRef> =
@@ -3,6 +3,12 @@ use llvm_sys::{LLVMInlineAsmDialect, LLVMIntPredicate, LLVMRealPredicate};
 pub enum IntCompareOperator {
     Equal,
     NotEqual,
+    SignedGreaterThan,
+    SignedGreaterThanOrEqual,
+    SignedLessThan,
+    SignedLessThanOrEqual,
+    UnsignedGreaterThanOrEqual,
+    UnsignedLessThan,
 }
 
 impl IntCompareOperator {
@@ -10,6 +16,12 @@ impl IntCompareOperator {
         match self {
             IntCompareOperator::Equal => LLVMIntPredicate::LLVMIntEQ,
             IntCompareOperator::NotEqual => LLVMIntPredicate::LLVMIntNE,
+            IntCompareOperator::SignedGreaterThan => LLVMIntPredicate::LLVMIntSGT,
+            IntCompareOperator::SignedGreaterThanOrEqual => LLVMIntPredicate::LLVMIntSGE,
+            IntCompareOperator::SignedLessThan => LLVMIntPredicate::LLVMIntSLT,
+            IntCompareOperator::SignedLessThanOrEqual => LLVMIntPredicate::LLVMIntSLE,
+            IntCompareOperator::UnsignedGreaterThanOrEqual => LLVMIntPredicate::LLVMIntUGE,
+            IntCompareOperator::UnsignedLessThan => LLVMIntPredicate::LLVMIntULT,
         }
     }
 }
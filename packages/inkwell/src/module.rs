@@ -3,8 +3,8 @@ use std::{marker::PhantomData, mem::forget, mem::MaybeUninit};
 use llvm_sys::{
     analysis::{LLVMVerifierFailureAction, LLVMVerifyModule},
     core::{
-        LLVMAddFunction, LLVMDisposeModule, LLVMGetModuleContext, LLVMGetNamedFunction,
-        LLVMPrintModuleToString,
+        LLVMAddFunction, LLVMAddGlobal, LLVMDisposeModule, LLVMGetModuleContext,
+        LLVMGetNamedFunction, LLVMGetNamedGlobal, LLVMPrintModuleToString,
     },
     linker::LLVMLinkModules2,
     prelude::LLVMModuleRef,
@@ -13,9 +13,12 @@ use llvm_sys::{
 use crate::{
     context::Context,
     enums::Linkage,
-    types::{fn_type::FunctionType, traits::AsTypeRef},
+    types::{
+        fn_type::FunctionType,
+        traits::{AsTypeRef, BasicTypeTrait},
+    },
     utils::{llvm_string::LLVMString, to_c_str},
-    values::fn_value::FunctionValue,
+    values::{fn_value::FunctionValue, global_value::GlobalValue},
 };
 
 pub struct Module<'a> {
@@ -59,6 +62,37 @@ impl<'a> Module<'a> {
         }
     }
 
+    /*
+     * Global without initializer is only a declaration, the definition is expected
+     * to come from another module linked later
+     *
+     * */
+    pub fn add_global<T: BasicTypeTrait<'a>>(&self, ty: T, name: &str) -> GlobalValue<'a> {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            return GlobalValue::new(LLVMAddGlobal(
+                self.module,
+                ty.as_type_ref(),
+                c_name.as_ptr(),
+            ));
+        }
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<GlobalValue<'a>> {
+        let c_name = to_c_str(name);
+
+        unsafe {
+            let value = LLVMGetNamedGlobal(self.module, c_name.as_ptr());
+
+            if value.is_null() {
+                return None;
+            } else {
+                return Some(GlobalValue::new(value));
+            }
+        }
+    }
+
     pub fn get_string_representation(&self) -> LLVMString {
         unsafe { LLVMString::new(LLVMPrintModuleToString(self.module)) }
     }
@@ -71,6 +105,24 @@ impl<'a> Module<'a> {
         }
     }
 
+    /*
+     * Unlike `get_fn_value` it does not expect the function to be present in
+     * the module
+     *
+     * */
+    pub fn get_function(&self, name: &str) -> Option<FunctionValue<'a>> {
+        unsafe {
+            let c_name = to_c_str(name);
+            let value = LLVMGetNamedFunction(self.module, c_name.as_ptr());
+
+            if value.is_null() {
+                return None;
+            }
+
+            return Some(FunctionValue::new(value));
+        }
+    }
+
     pub fn get_context(&'a self) -> Context {
         unsafe {
             let context_ref = LLVMGetModuleContext(self.module);
@@ -1,10 +1,13 @@
 use llvm_sys::{
-    core::{LLVMGetArrayLength, LLVMGetElementType},
+    core::{LLVMConstNull, LLVMGetArrayLength, LLVMGetElementType},
     prelude::LLVMTypeRef,
 };
 
+use crate::values::array_value::ArrayValue;
+
 use super::{
-    enums::BasicTypeEnum,
+    enums::{AddressSpace, BasicTypeEnum},
+    ptr_type::PointerType,
     traits::{AsTypeRef, BasicTypeTrait},
     Type,
 };
@@ -30,6 +33,17 @@ impl<'a> ArrayType<'a> {
         }
     }
 
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'a> {
+        return self.array_type.ptr_type(address_space);
+    }
+
+    // Array with every element set to zero
+    pub fn const_zero(&self) -> ArrayValue<'a> {
+        unsafe {
+            return ArrayValue::new(LLVMConstNull(self.as_type_ref()));
+        }
+    }
+
     pub fn get_element_type(&self) -> BasicTypeEnum {
         unsafe {
             let element_type = LLVMGetElementType(self.as_type_ref());
@@ -11,6 +11,7 @@ use super::{
     struct_type::StructType,
     traits::{AsTypeRef, BasicTypeTrait},
     void_type::VoidType,
+    Type,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -49,6 +50,12 @@ impl<'a> BasicTypeEnum<'a> {
             _ => panic!("Cannot convert {:?} to fn_type", self),
         }
     }
+
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'a> {
+        unsafe {
+            return Type::new(self.as_type_ref()).ptr_type(address_space);
+        }
+    }
 }
 
 impl<'a> AsTypeRef for BasicTypeEnum<'a> {
@@ -2,9 +2,10 @@ use crate::values::float_value::FloatValue;
 
 use super::{
     array_type::ArrayType,
-    enums::BasicTypeEnum,
+    enums::{AddressSpace, BasicTypeEnum},
     fn_type::FunctionType,
     int_type::IntType,
+    ptr_type::PointerType,
     traits::{AsTypeRef, BasicTypeTrait, FloatMathTypeTrait},
     Type,
 };
@@ -33,6 +34,10 @@ impl<'a> FloatType<'a> {
         return self.float_type.array_type(size);
     }
 
+    pub fn ptr_type(self, address_space: AddressSpace) -> PointerType<'a> {
+        return self.float_type.ptr_type(address_space);
+    }
+
     pub fn const_float(self, value: f64) -> FloatValue<'a> {
         unsafe {
             return FloatValue::new(LLVMConstReal(self.float_type.ty, value));
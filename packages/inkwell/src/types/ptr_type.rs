@@ -1,11 +1,13 @@
 use llvm_sys::{
-    core::{LLVMGetElementType, LLVMGetTypeKind},
-    prelude::LLVMTypeRef,
+    core::{LLVMConstArray, LLVMConstPointerNull, LLVMGetElementType, LLVMGetTypeKind},
+    prelude::{LLVMTypeRef, LLVMValueRef},
 };
 
+use crate::values::{array_value::ArrayValue, ptr_value::PointerValue, traits::AsValueRef};
+
 use super::{
     array_type::ArrayType,
-    enums::BasicTypeEnum,
+    enums::{AddressSpace, BasicTypeEnum},
     fn_type::FunctionType,
     struct_type::StructType,
     traits::{AsTypeRef, BasicTypeTrait},
@@ -30,6 +32,34 @@ impl<'a> PointerType<'a> {
         return self.ptr_type.fn_type(param_types, variadic_arg);
     }
 
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'a> {
+        return self.ptr_type.ptr_type(address_space);
+    }
+
+    pub fn array_type(&self, size: u32) -> ArrayType<'a> {
+        return self.ptr_type.array_type(size);
+    }
+
+    pub fn const_null(&self) -> PointerValue<'a> {
+        unsafe {
+            return PointerValue::new(LLVMConstPointerNull(self.as_type_ref()));
+        }
+    }
+
+    // Constant array of the pointers, which have to be constants of this type
+    pub fn const_array(&self, values: &[PointerValue<'a>]) -> ArrayValue<'a> {
+        let mut values: Vec<LLVMValueRef> =
+            values.iter().map(|value| value.as_value_ref()).collect();
+
+        unsafe {
+            return ArrayValue::new(LLVMConstArray(
+                self.as_type_ref(),
+                values.as_mut_ptr(),
+                values.len() as u32,
+            ));
+        }
+    }
+
     pub(crate) unsafe fn into_element_type(&self) -> Type<'a> {
         let element_type = LLVMGetElementType(self.as_type_ref());
         return Type::new(element_type);
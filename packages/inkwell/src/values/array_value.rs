@@ -0,0 +1,28 @@
+use llvm_sys::prelude::LLVMValueRef;
+
+use super::{traits::AsValueRef, Value};
+
+/*
+ * Constant array, used only to initialize globals so it is not a BasicValueEnum
+ *
+ * */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ArrayValue<'a> {
+    array_value: Value<'a>,
+}
+
+impl<'a> ArrayValue<'a> {
+    pub(crate) unsafe fn new(value: LLVMValueRef) -> Self {
+        assert!(!value.is_null());
+
+        return ArrayValue {
+            array_value: Value::new(value),
+        };
+    }
+}
+
+impl<'a> AsValueRef for ArrayValue<'a> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        return self.array_value.as_value_ref();
+    }
+}
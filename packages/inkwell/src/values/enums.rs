@@ -35,7 +35,7 @@ impl<'a> BasicValueEnum<'a> {
         }
     }
 
-    pub fn get_type(&self) -> BasicTypeEnum {
+    pub fn get_type(&self) -> BasicTypeEnum<'a> {
         match &self {
             BasicValueEnum::IntValue(value) => return value.get_type().as_basic_type_enum(),
             BasicValueEnum::FloatValue(value) => return value.get_type().as_basic_type_enum(),
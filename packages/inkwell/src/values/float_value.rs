@@ -7,7 +7,7 @@ use super::{
     Value,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct FloatValue<'a> {
     pub(crate) float_value: Value<'a>,
 }
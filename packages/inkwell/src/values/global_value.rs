@@ -0,0 +1,67 @@
+use llvm_sys::{
+    core::{LLVMSetAlignment, LLVMSetGlobalConstant, LLVMSetInitializer, LLVMSetLinkage},
+    prelude::LLVMValueRef,
+    LLVMLinkage,
+};
+
+use crate::enums::Linkage;
+
+use super::{ptr_value::PointerValue, traits::AsValueRef, Value};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct GlobalValue<'a> {
+    global_value: Value<'a>,
+}
+
+impl<'a> GlobalValue<'a> {
+    pub(crate) unsafe fn new(value: LLVMValueRef) -> Self {
+        assert!(!value.is_null());
+
+        return GlobalValue {
+            global_value: Value::new(value),
+        };
+    }
+
+    pub fn set_initializer(&self, value: &dyn AsValueRef) {
+        unsafe {
+            LLVMSetInitializer(self.as_value_ref(), value.as_value_ref());
+        }
+    }
+
+    pub fn set_constant(&self, is_constant: bool) {
+        unsafe {
+            LLVMSetGlobalConstant(self.as_value_ref(), is_constant as i32);
+        }
+    }
+
+    pub fn set_linkage(&self, linkage: &Linkage) {
+        unsafe {
+            let llvm_linkage = match linkage {
+                Linkage::External => LLVMLinkage::LLVMExternalLinkage,
+                Linkage::Private => LLVMLinkage::LLVMPrivateLinkage,
+            };
+
+            LLVMSetLinkage(self.as_value_ref(), llvm_linkage);
+        }
+    }
+
+    // Alignment in bytes
+    pub fn set_alignment(&self, alignment: u32) {
+        unsafe {
+            LLVMSetAlignment(self.as_value_ref(), alignment);
+        }
+    }
+
+    // Global is always accessed through the pointer to it
+    pub fn as_pointer_value(&self) -> PointerValue<'a> {
+        unsafe {
+            return PointerValue::new(self.as_value_ref());
+        }
+    }
+}
+
+impl<'a> AsValueRef for GlobalValue<'a> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        return self.global_value.as_value_ref();
+    }
+}
@@ -9,13 +9,16 @@ use crate::utils::llvm_string::LLVMString;
 
 use self::traits::AsValueRef;
 
+pub mod array_value;
 pub mod enums;
 pub mod float_value;
 pub mod fn_value;
+pub mod global_value;
 pub mod instruction_value;
 pub mod int_value;
 pub mod ptr_value;
 pub mod returned_value;
+pub mod struct_value;
 pub mod traits;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -1,6 +1,6 @@
-use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::{core::LLVMConstBitCast, prelude::LLVMValueRef};
 
-use crate::types::ptr_type::PointerType;
+use crate::types::{ptr_type::PointerType, traits::AsTypeRef};
 
 use super::{
     traits::{AsValueRef, BasicValueTrait},
@@ -26,6 +26,16 @@ impl<'a> PointerValue<'a> {
             return PointerType::new(self.ptr_value.get_type());
         }
     }
+
+    // Same as Builder::build_bitcast for a constant, e.g. a function in a global
+    pub fn const_bitcast(&self, pointer_type: PointerType<'a>) -> PointerValue<'a> {
+        unsafe {
+            return PointerValue::new(LLVMConstBitCast(
+                self.as_value_ref(),
+                pointer_type.as_type_ref(),
+            ));
+        }
+    }
 }
 
 impl<'a> AsValueRef for PointerValue<'a> {
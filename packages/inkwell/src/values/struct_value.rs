@@ -0,0 +1,29 @@
+use llvm_sys::prelude::LLVMValueRef;
+
+use super::{traits::AsValueRef, Value};
+
+/*
+ * Struct held in a register, like the value of a landing pad. Its fields are read
+ * with build_extract_value so it is not a BasicValueEnum
+ *
+ * */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct StructValue<'a> {
+    struct_value: Value<'a>,
+}
+
+impl<'a> StructValue<'a> {
+    pub(crate) unsafe fn new(value: LLVMValueRef) -> Self {
+        assert!(!value.is_null());
+
+        return StructValue {
+            struct_value: Value::new(value),
+        };
+    }
+}
+
+impl<'a> AsValueRef for StructValue<'a> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        return self.struct_value.as_value_ref();
+    }
+}
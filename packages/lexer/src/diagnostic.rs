@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::token::Span;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+
+        return write!(f, "{}", name);
+    }
+}
+
+/*
+ * A message attached to a region of the source code
+ *
+ * Primary label is underlined with `^` and secondary labels with `-`
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: &str) -> Self {
+        return Label {
+            span,
+            message: message.to_string(),
+        };
+    }
+}
+
+/*
+ * Problem found in the source code by lexer, parser or codegen
+ *
+ * Diagnostic does not know about the file it belongs to, the caller which
+ * owns the source passes file name and content to `render`
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub primary: Option<Label>,
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: &str) -> Self {
+        return Diagnostic {
+            severity,
+            code: None,
+            message: message.to_string(),
+            primary: None,
+            secondary: vec![],
+            notes: vec![],
+        };
+    }
+
+    pub fn error(message: &str) -> Self {
+        return Diagnostic::new(Severity::Error, message);
+    }
+
+    pub fn warning(message: &str) -> Self {
+        return Diagnostic::new(Severity::Warning, message);
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        return self;
+    }
+
+    pub fn with_primary(mut self, span: Span, message: &str) -> Self {
+        self.primary = Some(Label::new(span, message));
+        return self;
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: &str) -> Self {
+        self.secondary.push(Label::new(span, message));
+        return self;
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_string());
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.severity == Severity::Error;
+    }
+
+    /*
+     * Renders the diagnostic in the same shape as rustc
+     *
+     * error[E0001]: unknown character `#`
+     *  --> main.ts:1:11
+     *   |
+     * 1 | const x = #;
+     *   |           ^ not valid here
+     *   |
+     *   = note: ...
+     *
+     * */
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut output = String::new();
+
+        match &self.code {
+            Some(code) => {
+                output.push_str(&format!("{}[{}]: {}\n", self.severity, code, self.message))
+            }
+            None => output.push_str(&format!("{}: {}\n", self.severity, self.message)),
+        }
+
+        let mut labels: Vec<(&Label, char)> = vec![];
+        if let Some(primary) = &self.primary {
+            labels.push((primary, '^'));
+        }
+        for label in self.secondary.iter() {
+            labels.push((label, '-'));
+        }
+
+        let max_line = labels
+            .iter()
+            .map(|(label, _)| label.span.start.line)
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat(max_line.to_string().len());
+
+        if let Some((label, _)) = labels.first() {
+            output.push_str(&format!(
+                "{}--> {}:{}:{}\n",
+                gutter, file_name, label.span.start.line, label.span.start.column
+            ));
+            output.push_str(&format!("{} |\n", gutter));
+        }
+
+        labels.sort_by_key(|(label, _)| (label.span.start.line, label.span.start.column));
+
+        let lines: Vec<&str> = source.lines().collect();
+        let mut last_printed_line = 0;
+
+        for (label, marker) in labels.iter() {
+            let start = label.span.start;
+            let end = label.span.end;
+            let line_content = lines.get(start.line - 1).unwrap_or(&"");
+
+            if start.line != last_printed_line {
+                output.push_str(&format!(
+                    "{:>width$} | {}\n",
+                    start.line,
+                    line_content,
+                    width = gutter.len()
+                ));
+                last_printed_line = start.line;
+            }
+
+            /*
+             * Span covering multiple lines is underlined till the end of its
+             * first line. Empty span (Eof) still gets a single marker
+             *
+             * */
+            let line_len = line_content.chars().count();
+            let end_column = {
+                if end.line == start.line {
+                    end.column
+                } else {
+                    line_len + 1
+                }
+            };
+            let marker_len = end_column.saturating_sub(start.column).max(1);
+
+            let mut marker_line = format!(
+                "{} | {}{}",
+                gutter,
+                " ".repeat(start.column - 1),
+                marker.to_string().repeat(marker_len)
+            );
+            if !label.message.is_empty() {
+                marker_line.push(' ');
+                marker_line.push_str(&label.message);
+            }
+            output.push_str(&marker_line);
+            output.push('\n');
+        }
+
+        if !self.notes.is_empty() {
+            output.push_str(&format!("{} |\n", gutter));
+        }
+        for note in self.notes.iter() {
+            output.push_str(&format!("{} = note: {}\n", gutter, note));
+        }
+
+        return output;
+    }
+}
+
+#[cfg(test)]
+mod diagnostic_test {
+    use super::Diagnostic;
+    use crate::token::{Position, Span};
+
+    #[test]
+    fn test_render() {
+        let source = "const x = 1;\nconst y = x + ;";
+
+        let diagnostic = Diagnostic::error("expected expression")
+            .with_code("E0100")
+            .with_primary(
+                Span::new(Position::new(27, 2, 15), Position::new(28, 2, 16)),
+                "expected expression after `+`",
+            )
+            .with_secondary(
+                Span::new(Position::new(6, 1, 7), Position::new(7, 1, 8)),
+                "`x` is declared here",
+            )
+            .with_note("binary operators need an expression on both sides");
+
+        let expected_output = "error[E0100]: expected expression
+ --> main.ts:2:15
+  |
+1 | const x = 1;
+  |       - `x` is declared here
+2 | const y = x + ;
+  |               ^ expected expression after `+`
+  |
+  = note: binary operators need an expression on both sides
+";
+
+        assert_eq!(expected_output, diagnostic.render("main.ts", source));
+    }
+
+    #[test]
+    fn test_render_multiline_span() {
+        let source = "let x = \"abc\nlet y = 1;";
+
+        let diagnostic = Diagnostic::error("unterminated string literal").with_primary(
+            Span::new(Position::new(8, 1, 9), Position::new(23, 2, 11)),
+            "",
+        );
+
+        let expected_output = "error: unterminated string literal
+ --> main.ts:1:9
+  |
+1 | let x = \"abc
+  |         ^^^^
+";
+
+        assert_eq!(expected_output, diagnostic.render("main.ts", source));
+    }
+}
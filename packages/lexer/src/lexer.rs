@@ -1,7 +1,7 @@
-use crate::token::LiteralKind;
+use crate::diagnostic::Diagnostic;
+use crate::token::{CommentKind, LiteralKind, Position, Span, SpannedToken};
 
 use super::token::KeywordKind;
-use core::panic;
 use std::str::Chars;
 
 use super::token::Token;
@@ -9,15 +9,35 @@ use super::token::Token;
 pub(crate) struct Lexer<'a> {
     content: Chars<'a>,
     cur_char: Option<char>,
+
+    cur_position: Position, // Position of cur_char in the source
+    keep_trivia: bool,      // Whether to return comments as Token::Comment or skip them
+
+    /*
+     * One entry for every `${` of template literal which is not closed yet, the
+     * entry counts `{` opened inside the substitution so that the `}` which closes
+     * the substitution can be told apart from the `}` of an object or block
+     *
+     * */
+    template_brace_depth: Vec<usize>,
+
+    // Error found while reading the current token along with the label for its span
+    pending_diagnostic: Option<(Diagnostic, String)>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
-    pub(crate) fn new(input: &'a str) -> Lexer<'a> {
+    pub(crate) fn new(input: &'a str, keep_trivia: bool) -> Lexer<'a> {
         let content = input.chars();
 
         let mut lexer = Lexer {
             content,
             cur_char: None,
+            cur_position: Position::new(0, 1, 1),
+            keep_trivia,
+            template_brace_depth: vec![],
+            pending_diagnostic: None,
+            diagnostics: vec![],
         };
 
         lexer.next();
@@ -25,9 +45,31 @@ impl<'a> Lexer<'a> {
         return lexer;
     }
 
-    pub(crate) fn next_token(&mut self) -> Token {
-        self.eat_whitespace();
+    pub(crate) fn next_token(&mut self) -> SpannedToken {
+        loop {
+            self.eat_whitespace();
+
+            let start = self.cur_position;
+            let token = self.read_token();
+            let end = self.cur_position;
+            let span = Span::new(start, end);
 
+            if let Some((diagnostic, label)) = self.pending_diagnostic.take() {
+                self.diagnostics.push(diagnostic.with_primary(span, &label));
+            }
+
+            if let Token::Comment(_) = token {
+                if !self.keep_trivia {
+                    continue;
+                }
+            }
+
+            return SpannedToken::new(token, span);
+        }
+    }
+
+    // Assumes there is no whitespace before cur_char
+    fn read_token(&mut self) -> Token {
         let cur_char = self.cur_char;
 
         match cur_char {
@@ -42,6 +84,12 @@ impl<'a> Lexer<'a> {
                     self.next(); // consumes :
                     return Colon;
                 } else if char == '.' {
+                    if let Some(next_char) = self.peek() {
+                        if is_digit(&next_char) {
+                            return Literal(self.read_number());
+                        }
+                    }
+
                     self.next(); // consumes .
                     return Dot;
                 } else if char == '=' {
@@ -67,9 +115,29 @@ impl<'a> Lexer<'a> {
 
                     return Assign;
                 } else if char == '{' {
+                    if let Some(depth) = self.template_brace_depth.last_mut() {
+                        *depth += 1;
+                    }
+
                     self.next(); // consumes {
                     return AngleOpenBracket;
                 } else if char == '}' {
+                    match self.template_brace_depth.last_mut() {
+                        Some(0) => {
+                            self.template_brace_depth.pop();
+                            let (name, has_substitution) = self.read_template_text();
+
+                            if has_substitution {
+                                self.template_brace_depth.push(0);
+                                return TemplateMiddle { name };
+                            } else {
+                                return TemplateTail { name };
+                            }
+                        }
+                        Some(depth) => *depth -= 1,
+                        None => {}
+                    }
+
                     self.next(); // consumes }
                     return AngleCloseBracket;
                 } else if char == '(' {
@@ -113,6 +181,9 @@ impl<'a> Lexer<'a> {
                         if char == '=' {
                             self.next(); // consumes =
                             return PlusAssign;
+                        } else if char == '+' {
+                            self.next(); // consumes +
+                            return Increment;
                         }
                     }
 
@@ -124,17 +195,31 @@ impl<'a> Lexer<'a> {
                         if char == '=' {
                             self.next(); // consumes =
                             return MinusAssign;
+                        } else if char == '-' {
+                            self.next(); // consumes -
+                            return Decrement;
                         }
                     }
 
                     return Minus;
                 } else if char == '*' {
-                    self.next(); // consumes +
+                    self.next(); // consumes *
 
                     if let Some(char) = self.cur_char {
                         if char == '=' {
                             self.next(); // consumes =
                             return StarAssign;
+                        } else if char == '*' {
+                            self.next(); // consumes *
+
+                            if let Some(char) = self.cur_char {
+                                if char == '=' {
+                                    self.next(); // consumes =
+                                    return StarStarAssign;
+                                }
+                            }
+
+                            return StarStar;
                         }
                     }
 
@@ -146,6 +231,10 @@ impl<'a> Lexer<'a> {
                         if char == '=' {
                             self.next(); // consumes =
                             return SlashAssign;
+                        } else if char == '/' {
+                            return Comment(self.read_line_comment());
+                        } else if char == '*' {
+                            return Comment(self.read_block_comment());
                         }
                     }
                     return Slash;
@@ -156,30 +245,106 @@ impl<'a> Lexer<'a> {
                         if char == '=' {
                             self.next(); // consumes =
                             return LessThanOrEqual;
+                        } else if char == '<' {
+                            self.next(); // consumes <
+
+                            if let Some(char) = self.cur_char {
+                                if char == '=' {
+                                    self.next(); // consumes =
+                                    return LeftShiftAssign;
+                                }
+                            }
+
+                            return LeftShift;
                         }
                     }
 
                     return LessThan;
                 } else if char == '>' {
-                    self.next(); // consumes <
+                    self.next(); // consumes >
 
                     if let Some(char) = self.cur_char {
                         if char == '=' {
                             self.next(); // consumes =
                             return GreaterThanOrEqual;
+                        } else if char == '>' {
+                            self.next(); // consumes >
+                            return self.read_right_shift();
                         }
                     }
 
                     return GreaterThan;
                 } else if char == '|' {
                     self.next(); // consumes |
+
+                    if let Some(char) = self.cur_char {
+                        if char == '|' {
+                            self.next(); // consumes |
+                            return LogicalOr;
+                        } else if char == '=' {
+                            self.next(); // consumes =
+                            return VerticalBarAssign;
+                        }
+                    }
+
                     return VerticalBar;
                 } else if char == '^' {
                     self.next(); // consumes ^
+
+                    if let Some(char) = self.cur_char {
+                        if char == '=' {
+                            self.next(); // consumes =
+                            return CaretAssign;
+                        }
+                    }
+
                     return Caret;
                 } else if char == '&' {
                     self.next(); // consumes &
+
+                    if let Some(char) = self.cur_char {
+                        if char == '&' {
+                            self.next(); // consumes &
+                            return LogicalAnd;
+                        } else if char == '=' {
+                            self.next(); // consumes =
+                            return AmpersandAssign;
+                        }
+                    }
+
                     return Ampersand;
+                } else if char == '%' {
+                    self.next(); // consumes %
+
+                    if let Some(char) = self.cur_char {
+                        if char == '=' {
+                            self.next(); // consumes =
+                            return PercentAssign;
+                        }
+                    }
+
+                    return Percent;
+                } else if char == '~' {
+                    self.next(); // consumes ~
+                    return Tilde;
+                } else if char == '?' {
+                    self.next(); // consumes ?
+
+                    if let Some(char) = self.cur_char {
+                        if char == '?' {
+                            self.next(); // consumes ?
+                            return NullishCoalescing;
+                        }
+
+                        // `a?.5:1` is a conditional with the number .5
+                        let is_next_digit = matches!(self.peek(), Some(c) if c.is_ascii_digit());
+                        if char == '.' && !is_next_digit {
+                            self.next(); // consumes .
+                            return QuestionDot;
+                        }
+                    }
+
+                    return QuestionMark;
                 } else if char == '\'' {
                     let string_name = self.read_string('\'');
                     return Literal(LiteralKind::String { name: string_name });
@@ -187,8 +352,14 @@ impl<'a> Lexer<'a> {
                     let string_name = self.read_string('\"');
                     return Literal(LiteralKind::String { name: string_name });
                 } else if char == '`' {
-                    let string_name = self.read_string('`');
-                    return Literal(LiteralKind::String { name: string_name });
+                    let (name, has_substitution) = self.read_template_text();
+
+                    if has_substitution {
+                        self.template_brace_depth.push(0);
+                        return TemplateHead { name };
+                    } else {
+                        return Literal(LiteralKind::String { name });
+                    }
                 } else {
                     if is_letter(&char) {
                         let ident_name = self.read_identifier();
@@ -199,22 +370,61 @@ impl<'a> Lexer<'a> {
                             IsKeyword::No => return Ident { name: ident_name },
                         }
                     } else if is_digit(&char) {
-                        let digit_name = self.read_digit();
-                        let digit_value: f64 = digit_name.parse().unwrap();
-
-                        return Literal(LiteralKind::Float {
-                            name: digit_name,
-                            value: digit_value,
-                        });
+                        return Literal(self.read_number());
                     }
                     self.next(); // consumes Illegal
+                    self.pending_diagnostic = Some((
+                        Diagnostic::error(&format!("unknown character `{}`", char))
+                            .with_code("E0001"),
+                        "not valid in this position".to_string(),
+                    ));
                     return Illegal;
                 }
             }
         }
     }
 
+    /*
+     * Assumes `>>` to be consumed, reads the rest of `>>`, `>>=`, `>>>` or `>>>=`
+     *
+     * */
+    fn read_right_shift(&mut self) -> Token {
+        let mut is_unsigned = false;
+
+        if self.cur_char == Some('>') {
+            self.next(); // consumes >
+            is_unsigned = true;
+        }
+
+        if self.cur_char == Some('=') {
+            self.next(); // consumes =
+
+            if is_unsigned {
+                return Token::UnsignedRightShiftAssign;
+            } else {
+                return Token::RightShiftAssign;
+            }
+        }
+
+        if is_unsigned {
+            return Token::UnsignedRightShift;
+        } else {
+            return Token::RightShift;
+        }
+    }
+
     fn next(&mut self) -> Option<char> {
+        if let Some(prev_char) = self.cur_char {
+            self.cur_position.offset += prev_char.len_utf8();
+
+            if prev_char == '\n' {
+                self.cur_position.line += 1;
+                self.cur_position.column = 1;
+            } else {
+                self.cur_position.column += 1;
+            }
+        }
+
         let next_cur = self.content.next();
         self.cur_char = next_cur;
 
@@ -261,52 +471,466 @@ impl<'a> Lexer<'a> {
         }
     }
     // After executing this function the cur_char wont be a digit
-    fn read_digit(&mut self) -> String {
-        let mut digit_name = self.cur_char.expect("unreachable").to_string();
+    /*
+     * Assumes the cur_char is a digit or `.` followed by a digit
+     *
+     * Reads every form of numeric literal
+     *
+     * 3, 3.14, .5, 5., 1e9, 2.5E-3, 0xFF, 0b1010, 0o777, 1_000_000
+     *
+     * and BigInt literals which are integers followed by `n` like 10n, 0xFFn
+     *
+     * `name` of the literal is the text as written in the source. After executing
+     * this function the cur_char wont be part of the literal
+     *
+     * */
+    fn read_number(&mut self) -> LiteralKind {
+        let mut name = String::new();
 
-        let next_cur = self.next();
+        let radix = {
+            if self.cur_char == Some('0') {
+                match self.peek() {
+                    Some('x') | Some('X') => 16,
+                    Some('b') | Some('B') => 2,
+                    Some('o') | Some('O') => 8,
+                    _ => 10,
+                }
+            } else {
+                10
+            }
+        };
 
-        match next_cur {
-            None => return digit_name,
-            Some(mut c) => {
-                while is_digit(&c) {
-                    digit_name.push(c);
+        if radix != 10 {
+            name.push('0');
+            self.next(); // consumes 0
+            name.push(self.cur_char.expect("unreachable"));
+            self.next(); // consumes x, b or o
 
-                    let next_cur = self.next();
+            let digits = self.read_digits(radix, &mut name);
 
-                    match next_cur {
-                        None => return digit_name,
-                        Some(ch) => c = ch,
-                    }
+            if digits.is_empty() {
+                self.report_invalid_number("expected digits after the radix prefix");
+            }
+
+            if self.cur_char == Some('n') {
+                self.next(); // consumes n
+                name.push('n');
+                self.check_end_of_number();
+
+                return LiteralKind::BigInt {
+                    name,
+                    value: convert_to_decimal_string(&digits, radix),
+                };
+            }
+
+            self.check_end_of_number();
+
+            return LiteralKind::Float {
+                name,
+                value: convert_to_f64(&digits, radix),
+            };
+        }
+
+        // Literal without separators which is handed over to f64 parser
+        let integer = self.read_digits(10, &mut name);
+        let mut literal = integer.clone();
+
+        // Legacy octal literals like 010 are not allowed in strict mode
+        if integer.len() > 1 && integer.starts_with('0') {
+            self.report_invalid_number("decimal literals cannot have leading zeros");
+        }
+        let mut is_integer = true;
+
+        if self.cur_char == Some('.') {
+            is_integer = false;
+            self.next(); // consumes .
+            name.push('.');
+            literal.push('.');
+
+            let fraction = self.read_digits(10, &mut name);
+            literal.push_str(&fraction);
+        }
+
+        if self.cur_char == Some('e') || self.cur_char == Some('E') {
+            is_integer = false;
+            name.push(self.cur_char.expect("unreachable"));
+            self.next(); // consumes e
+
+            let mut exponent = String::new();
+            if self.cur_char == Some('+') || self.cur_char == Some('-') {
+                exponent.push(self.cur_char.expect("unreachable"));
+                name.push(self.cur_char.expect("unreachable"));
+                self.next(); // consumes sign
+            }
+
+            let exponent_digits = self.read_digits(10, &mut name);
+
+            if exponent_digits.is_empty() {
+                self.report_invalid_number("expected digits in the exponent");
+            } else {
+                literal.push('e');
+                literal.push_str(&exponent);
+                literal.push_str(&exponent_digits);
+            }
+        }
+
+        if self.cur_char == Some('n') {
+            self.next(); // consumes n
+            name.push('n');
+
+            if !is_integer {
+                self.report_invalid_number("a BigInt literal must be an integer");
+            }
+            self.check_end_of_number();
+
+            return LiteralKind::BigInt {
+                name,
+                value: convert_to_decimal_string(&integer, 10),
+            };
+        }
+
+        self.check_end_of_number();
+
+        // Rust parses decimal literal to the nearest f64 same as JS
+        let value: f64 = literal.parse().unwrap_or(f64::NAN);
+
+        return LiteralKind::Float { name, value };
+    }
+
+    /*
+     * Reads digits of given radix along with `_` separators into `name` and
+     * returns the digits without separators
+     *
+     * Separator is only allowed between two digits
+     *
+     * */
+    fn read_digits(&mut self, radix: u32, name: &mut String) -> String {
+        let mut digits = String::new();
+        let mut prev_char: Option<char> = None;
+
+        while let Some(c) = self.cur_char {
+            if c == '_' {
+                let is_between_digits = prev_char.is_some_and(|p| p.is_digit(radix))
+                    && self.peek().is_some_and(|n| n.is_digit(radix));
+
+                if !is_between_digits {
+                    self.report_invalid_number(
+                        "numeric separators are only allowed between digits",
+                    );
                 }
+            } else if c.is_digit(radix) {
+                digits.push(c);
+            } else {
+                break;
+            }
 
-                return digit_name;
+            name.push(c);
+            prev_char = Some(c);
+            self.next();
+        }
+
+        return digits;
+    }
+
+    // Identifier or digit cannot start right after a numeric literal like 3in or 0b12
+    fn check_end_of_number(&mut self) {
+        if let Some(c) = self.cur_char {
+            if is_letter(&c) || is_digit(&c) {
+                self.report_invalid_number(
+                    "an identifier or digit cannot immediately follow a numeric literal",
+                );
+            }
+        }
+    }
+
+    fn report_invalid_number(&mut self, label: &str) {
+        self.report_once(
+            Diagnostic::error("invalid numeric literal").with_code("E0004"),
+            label,
+        );
+    }
+
+    // Assumes the cur_char is the second `/` of `//`
+    // It will end at the `\n` which ends the comment, `\n` is not consumed
+    fn read_line_comment(&mut self) -> CommentKind {
+        let mut text = String::new();
+
+        loop {
+            let next_char = self.next();
+
+            match next_char {
+                Some(ch) if ch != '\n' => text.push(ch),
+                _ => break,
+            }
+        }
+
+        return CommentKind::Line { text };
+    }
+
+    // Assumes the cur_char is `*` of `/*`
+    // It will end after consuming the `*/`
+    fn read_block_comment(&mut self) -> CommentKind {
+        let mut text = String::new();
+        let mut is_js_doc = false;
+
+        self.next(); // consumes *
+
+        // `/**/` is an empty block comment and not the start of a JSDoc comment
+        if self.cur_char == Some('*') && self.peek() != Some('/') {
+            is_js_doc = true;
+            self.next(); // consumes *
+        }
+
+        loop {
+            match self.cur_char {
+                None => {
+                    self.pending_diagnostic = Some((
+                        Diagnostic::error("unterminated block comment").with_code("E0003"),
+                        "expected */ before end of file".to_string(),
+                    ));
+                    break;
+                }
+                Some('*') if self.peek() == Some('/') => {
+                    self.next(); // consumes *
+                    self.next(); // consumes /
+                    break;
+                }
+                Some(ch) => {
+                    text.push(ch);
+                    self.next();
+                }
             }
         }
+
+        if is_js_doc {
+            return CommentKind::JsDoc { text };
+        } else {
+            return CommentKind::Block { text };
+        }
     }
+
+    // Returns the char after cur_char without consuming anything
+    fn peek(&self) -> Option<char> {
+        return self.content.clone().next();
+    }
+
     // Assumes the cur_char is starting char of string_literal
-    // Ex: ' " `
+    // Ex: ' "
     // It will end after consuming the end char that
     // cur_char wont be end_char
     fn read_string(&mut self, end_char: char) -> String {
         let mut string_name = String::new();
+        self.next(); // consumes starting char
+
         loop {
-            let next_char = self.next();
+            match self.cur_char {
+                // String literal cannot span multiple lines without `\`
+                None | Some('\n') => {
+                    self.report_once(
+                        Diagnostic::error("unterminated string literal").with_code("E0002"),
+                        &format!("expected {} before end of line", end_char),
+                    );
+                    break;
+                }
+                Some('\\') => self.read_escape_sequence(&mut string_name),
+                Some(ch) if ch == end_char => {
+                    self.next(); // consumes end_char
+                    break;
+                }
+                Some(ch) => {
+                    string_name.push(ch);
+                    self.next();
+                }
+            }
+        }
 
-            match next_char {
-                None => panic!("Lexer error expected {} before end of file", end_char),
+        return string_name;
+    }
+
+    /*
+     * Assumes the cur_char is either the ` which starts template literal or
+     * the } which ends a substitution `${...}`
+     *
+     * Reads the text till the closing ` or till the start of next substitution `${`
+     * and consumes them. Returns the text and whether a substitution started
+     *
+     * */
+    fn read_template_text(&mut self) -> (String, bool) {
+        let mut text = String::new();
+        self.next(); // consumes ` or }
+
+        loop {
+            match self.cur_char {
+                None => {
+                    self.report_once(
+                        Diagnostic::error("unterminated template literal").with_code("E0002"),
+                        "expected ` before end of file",
+                    );
+                    return (text, false);
+                }
+                Some('`') => {
+                    self.next(); // consumes `
+                    return (text, false);
+                }
+                Some('$') if self.peek() == Some('{') => {
+                    self.next(); // consumes $
+                    self.next(); // consumes {
+                    return (text, true);
+                }
+                Some('\\') => self.read_escape_sequence(&mut text),
+                // Line terminator \r\n is normalized to \n inside template literal
+                Some('\r') => {
+                    self.next(); // consumes \r
+                    if self.cur_char == Some('\n') {
+                        self.next(); // consumes \n
+                    }
+                    text.push('\n');
+                }
                 Some(ch) => {
-                    if ch == end_char {
-                        self.next(); // consumes end_char
-                        break;
+                    text.push(ch);
+                    self.next();
+                }
+            }
+        }
+    }
+
+    /*
+     * Assumes the cur_char is `\` and pushes the char it stands for to `text`
+     *
+     * \n \t \r \b \f \v \0, \xHH, \uHHHH, \u{H..} and line continuation, any other
+     * char after `\` stands for itself
+     *
+     * */
+    fn read_escape_sequence(&mut self, text: &mut String) {
+        self.next(); // consumes \
+
+        let ch = match self.cur_char {
+            None => return, // Caller reports the unterminated literal
+            Some(ch) => ch,
+        };
+        self.next(); // consumes the escaped char
+
+        match ch {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            'r' => text.push('\r'),
+            'b' => text.push('\u{8}'),
+            'f' => text.push('\u{c}'),
+            'v' => text.push('\u{b}'),
+            '0' if !self.cur_char.is_some_and(|c| is_digit(&c)) => text.push('\0'),
+            '0'..='9' => {
+                self.report_invalid_escape("octal escape sequences are not allowed");
+                text.push(ch);
+            }
+
+            // Line continuation
+            '\n' => {}
+            '\r' => {
+                if self.cur_char == Some('\n') {
+                    self.next(); // consumes \n
+                }
+            }
+
+            'x' => match self.read_hex_digits(2) {
+                Some(value) => text.push(std::char::from_u32(value).expect("unreachable")),
+                None => self.report_invalid_escape("expected 2 hex digits after \\x"),
+            },
+
+            'u' => {
+                let code_point = self.read_unicode_escape();
+
+                if let Some(high) = code_point {
+                    if (0xD800..0xDC00).contains(&high)
+                        && self.cur_char == Some('\\')
+                        && self.peek() == Some('u')
+                    {
+                        self.next(); // consumes \
+                        self.next(); // consumes u
+
+                        match self.read_unicode_escape() {
+                            Some(low) if (0xDC00..0xE000).contains(&low) => {
+                                let value = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                                text.push(std::char::from_u32(value).expect("unreachable"));
+                            }
+                            low => {
+                                text.push(std::char::REPLACEMENT_CHARACTER);
+                                if let Some(low) = low {
+                                    text.push(
+                                        std::char::from_u32(low)
+                                            .unwrap_or(std::char::REPLACEMENT_CHARACTER),
+                                    );
+                                }
+                            }
+                        }
                     } else {
-                        string_name.push(ch)
+                        // Lone surrogate cannot be stored in utf-8
+                        text.push(
+                            std::char::from_u32(high).unwrap_or(std::char::REPLACEMENT_CHARACTER),
+                        );
                     }
                 }
             }
+
+            _ => text.push(ch),
         }
+    }
 
-        return string_name;
+    // Assumes the cur_char is right after `\u` and reads either HHHH or {H..}
+    fn read_unicode_escape(&mut self) -> Option<u32> {
+        if self.cur_char == Some('{') {
+            self.next(); // consumes {
+
+            let mut value: u32 = 0;
+            let mut digit_count = 0;
+
+            while let Some(digit) = self.cur_char.and_then(|c| c.to_digit(16)) {
+                value = value.saturating_mul(16).saturating_add(digit);
+                digit_count += 1;
+                self.next();
+            }
+
+            if self.cur_char != Some('}') || digit_count == 0 || value > 0x10FFFF {
+                self.report_invalid_escape("expected hex code point not greater than 10FFFF");
+                return None;
+            }
+
+            self.next(); // consumes }
+            return Some(value);
+        }
+
+        let value = self.read_hex_digits(4);
+        if value.is_none() {
+            self.report_invalid_escape("expected 4 hex digits after \\u");
+        }
+
+        return value;
+    }
+
+    // Reads exactly `count` hex digits, stops at the first char which is not a hex digit
+    fn read_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut value: u32 = 0;
+
+        for _ in 0..count {
+            let digit = self.cur_char.and_then(|c| c.to_digit(16))?;
+            value = value * 16 + digit;
+            self.next();
+        }
+
+        return Some(value);
+    }
+
+    fn report_invalid_escape(&mut self, label: &str) {
+        self.report_once(
+            Diagnostic::error("invalid escape sequence").with_code("E0005"),
+            label,
+        );
+    }
+
+    // Only the first error found in a token is reported
+    fn report_once(&mut self, diagnostic: Diagnostic, label: &str) {
+        if self.pending_diagnostic.is_none() {
+            self.pending_diagnostic = Some((diagnostic, label.to_string()));
+        }
     }
 }
 
@@ -344,7 +968,85 @@ fn is_letter(c: &char) -> bool {
 }
 
 fn is_digit(c: &char) -> bool {
-    return matches!(c, '0'..='9');
+    return c.is_ascii_digit();
+}
+
+/*
+ * Radix is 2, 8 or 16 so every digit is a fixed number of bits. The integer is
+ * kept as its bits and rounded once to the 53 significant bits of f64, ties to
+ * even, so a literal of any length gives the nearest f64. Accumulating it in f64
+ * digit by digit would round more than once
+ *
+ * */
+fn convert_to_f64(digits: &str, radix: u32) -> f64 {
+    let bits_per_digit = radix.trailing_zeros();
+
+    // Bits from the most significant one, leading zeros are dropped
+    let mut bits: Vec<bool> = vec![];
+    for c in digits.chars() {
+        let digit = c.to_digit(radix).expect("unreachable");
+
+        for i in (0..bits_per_digit).rev() {
+            let bit = (digit >> i) & 1 == 1;
+            if bit || !bits.is_empty() {
+                bits.push(bit);
+            }
+        }
+    }
+
+    let significant_bits = bits.len().min(53);
+    let mut mantissa = bits[..significant_bits]
+        .iter()
+        .fold(0u64, |mantissa, bit| (mantissa << 1) | *bit as u64);
+
+    if bits.len() > significant_bits {
+        // Rest of the bits compared with half of the last significant bit
+        let is_half_or_more = bits[significant_bits];
+        let is_exactly_half = !bits[significant_bits + 1..].contains(&true);
+
+        if is_half_or_more && (!is_exactly_half || mantissa & 1 == 1) {
+            mantissa += 1;
+        }
+    }
+
+    // Scaling by a power of two is exact, it only overflows to infinity
+    let mut value = mantissa as f64;
+    for _ in significant_bits..bits.len() {
+        value *= 2.0;
+    }
+
+    return value;
+}
+
+// Converts digits of any radix to decimal digits without any size limit, used for BigInt
+fn convert_to_decimal_string(digits: &str, radix: u32) -> String {
+    // Decimal digits in reverse order
+    let mut decimal_digits: Vec<u32> = vec![0];
+
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).expect("unreachable");
+
+        for decimal_digit in decimal_digits.iter_mut() {
+            let value = *decimal_digit * radix + carry;
+            *decimal_digit = value % 10;
+            carry = value / 10;
+        }
+
+        while carry > 0 {
+            decimal_digits.push(carry % 10);
+            carry /= 10;
+        }
+    }
+
+    while decimal_digits.len() > 1 && decimal_digits.last() == Some(&0) {
+        decimal_digits.pop();
+    }
+
+    return decimal_digits
+        .iter()
+        .rev()
+        .map(|digit| std::char::from_digit(*digit, 10).expect("unreachable"))
+        .collect();
 }
 
 pub enum IsKeyword {
@@ -359,6 +1061,10 @@ fn is_keyword(word: &String) -> IsKeyword {
         return IsKeyword::Yes(KeywordKind::True);
     } else if word == "false" {
         return IsKeyword::Yes(KeywordKind::False);
+    } else if word == "null" {
+        return IsKeyword::Yes(KeywordKind::Null);
+    } else if word == "undefined" {
+        return IsKeyword::Yes(KeywordKind::Undefined);
     } else if word == "let" {
         return IsKeyword::Yes(KeywordKind::Let);
     } else if word == "if" {
@@ -369,14 +1075,50 @@ fn is_keyword(word: &String) -> IsKeyword {
         return IsKeyword::Yes(KeywordKind::While);
     } else if word == "do" {
         return IsKeyword::Yes(KeywordKind::Do);
+    } else if word == "for" {
+        return IsKeyword::Yes(KeywordKind::For);
+    } else if word == "of" {
+        return IsKeyword::Yes(KeywordKind::Of);
+    } else if word == "in" {
+        return IsKeyword::Yes(KeywordKind::In);
     } else if word == "break" {
         return IsKeyword::Yes(KeywordKind::Break);
     } else if word == "continue" {
         return IsKeyword::Yes(KeywordKind::Continue);
+    } else if word == "switch" {
+        return IsKeyword::Yes(KeywordKind::Switch);
+    } else if word == "case" {
+        return IsKeyword::Yes(KeywordKind::Case);
+    } else if word == "default" {
+        return IsKeyword::Yes(KeywordKind::Default);
+    } else if word == "try" {
+        return IsKeyword::Yes(KeywordKind::Try);
+    } else if word == "catch" {
+        return IsKeyword::Yes(KeywordKind::Catch);
+    } else if word == "finally" {
+        return IsKeyword::Yes(KeywordKind::Finally);
+    } else if word == "throw" {
+        return IsKeyword::Yes(KeywordKind::Throw);
     } else if word == "function" {
         return IsKeyword::Yes(KeywordKind::Function);
     } else if word == "return" {
         return IsKeyword::Yes(KeywordKind::Return);
+    } else if word == "class" {
+        return IsKeyword::Yes(KeywordKind::Class);
+    } else if word == "extends" {
+        return IsKeyword::Yes(KeywordKind::Extends);
+    } else if word == "super" {
+        return IsKeyword::Yes(KeywordKind::Super);
+    } else if word == "this" {
+        return IsKeyword::Yes(KeywordKind::This);
+    } else if word == "new" {
+        return IsKeyword::Yes(KeywordKind::New);
+    } else if word == "enum" {
+        return IsKeyword::Yes(KeywordKind::Enum);
+    } else if word == "typeof" {
+        return IsKeyword::Yes(KeywordKind::Typeof);
+    } else if word == "instanceof" {
+        return IsKeyword::Yes(KeywordKind::Instanceof);
     } else if word == "import" {
         return IsKeyword::Yes(KeywordKind::Import);
     } else if word == "from" {
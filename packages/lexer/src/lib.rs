@@ -1,21 +1,44 @@
+pub mod diagnostic;
 mod lexer;
 pub mod token;
 
+use crate::diagnostic::Diagnostic;
 use crate::lexer::Lexer;
-use crate::token::Token;
+use crate::token::{SpannedToken, Token};
 
 #[cfg(test)]
 mod test;
 
-pub fn convert_to_token(input: &str) -> Vec<Token> {
-    let mut lexer = Lexer::new(input);
-    let mut tokens: Vec<Token> = Vec::new();
+/*
+ * Unknown characters are returned as Token::Illegal and unterminated strings
+ * are closed at end of file, the diagnostics for both are returned alongside
+ * the tokens so that the caller can decide whether to go ahead with parsing
+ *
+ * Comments are skipped
+ *
+ * */
+pub fn tokenize(input: &str) -> (Vec<SpannedToken>, Vec<Diagnostic>) {
+    return read_all_tokens(input, false);
+}
+
+/*
+ * Same as `tokenize` but comments are kept as Token::Comment, for tools like
+ * formatter which need to reproduce the source
+ *
+ * */
+pub fn tokenize_with_trivia(input: &str) -> (Vec<SpannedToken>, Vec<Diagnostic>) {
+    return read_all_tokens(input, true);
+}
+
+fn read_all_tokens(input: &str, keep_trivia: bool) -> (Vec<SpannedToken>, Vec<Diagnostic>) {
+    let mut lexer = Lexer::new(input, keep_trivia);
+    let mut tokens: Vec<SpannedToken> = Vec::new();
 
     loop {
         let token = lexer.next_token();
         let mut should_break = false;
 
-        if let Token::Eof = token {
+        if let Token::Eof = token.token {
             should_break = true
         }
 
@@ -26,5 +49,9 @@ pub fn convert_to_token(input: &str) -> Vec<Token> {
         }
     }
 
-    return tokens;
+    return (tokens, lexer.diagnostics);
+}
+
+pub fn convert_to_token(input: &str) -> Vec<SpannedToken> {
+    return tokenize(input).0;
 }
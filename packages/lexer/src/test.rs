@@ -1,9 +1,17 @@
-use crate::convert_to_token;
 use crate::token;
+use crate::{convert_to_token, tokenize, tokenize_with_trivia};
 use token::KeywordKind;
 use token::LiteralKind;
+use token::Position;
 use token::Token;
 
+fn get_tokens(input: &str) -> Vec<Token> {
+    return convert_to_token(input)
+        .into_iter()
+        .map(|spanned_token| spanned_token.token)
+        .collect();
+}
+
 #[test]
 fn test_operators() {
     use Token::*;
@@ -53,6 +61,10 @@ fn test_operators() {
     |
     ^
     &
+    &&
+    ||
+    ??
+    ?
     ";
 
     let expected_output: Vec<Token> = vec![
@@ -88,15 +100,98 @@ fn test_operators() {
         VerticalBar,
         Caret,
         Ampersand,
+        LogicalAnd,
+        LogicalOr,
+        NullishCoalescing,
+        QuestionMark,
         Eof,
     ];
 
-    let actual_output = convert_to_token(input);
+    let actual_output = get_tokens(input);
 
     assert_eq!(expected_output.len(), actual_output.len());
     assert_eq!(expected_output, actual_output)
 }
 
+#[test]
+fn test_arithmetic_operators() {
+    use Token::*;
+
+    let input = "
+    % %= ** **= ++ -- ~
+    << <<= >> >>= >>> >>>=
+    &= |= ^=
+    a---b
+    ";
+
+    let expected_output: Vec<Token> = vec![
+        Percent,
+        PercentAssign,
+        StarStar,
+        StarStarAssign,
+        Increment,
+        Decrement,
+        Tilde,
+        LeftShift,
+        LeftShiftAssign,
+        RightShift,
+        RightShiftAssign,
+        UnsignedRightShift,
+        UnsignedRightShiftAssign,
+        AmpersandAssign,
+        VerticalBarAssign,
+        CaretAssign,
+        Ident {
+            name: String::from("a"),
+        },
+        Decrement,
+        Minus,
+        Ident {
+            name: String::from("b"),
+        },
+        Eof,
+    ];
+
+    let actual_output = get_tokens(input);
+
+    assert_eq!(expected_output, actual_output)
+}
+
+#[test]
+fn test_optional_chaining() {
+    use Token::*;
+
+    let input = "a?.b c?.5:1";
+
+    let expected_output: Vec<Token> = vec![
+        Ident {
+            name: String::from("a"),
+        },
+        QuestionDot,
+        Ident {
+            name: String::from("b"),
+        },
+        Ident {
+            name: String::from("c"),
+        },
+        QuestionMark,
+        Literal(LiteralKind::Float {
+            name: String::from(".5"),
+            value: 0.5,
+        }),
+        Colon,
+        Literal(LiteralKind::Float {
+            name: String::from("1"),
+            value: 1.0,
+        }),
+        Eof,
+    ];
+
+    let actual_output = get_tokens(input);
+
+    assert_eq!(expected_output, actual_output)
+}
+
 #[test]
 fn test_ident() {
     use Token::*;
@@ -119,7 +214,7 @@ fn test_ident() {
         Eof,
     ];
 
-    let actual_output = convert_to_token(input);
+    let actual_output = get_tokens(input);
 
     assert_eq!(expected_output, actual_output);
 }
@@ -134,17 +229,41 @@ fn test_keyword() {
 
     true
     false
+    null
+    undefined
     
     if
     else
     
     while
     do
+    for
+    of
+    in
     break
     continue
+    switch
+    case
+    default
+
+    try
+    catch
+    finally
+    throw
     
     function
     return
+
+    class
+    extends
+    super
+    this
+    new
+
+    enum
+    
+    typeof
+    instanceof
     
     import
     from
@@ -155,21 +274,41 @@ fn test_keyword() {
         Keyword(KeywordKind::Let),
         Keyword(KeywordKind::True),
         Keyword(KeywordKind::False),
+        Keyword(KeywordKind::Null),
+        Keyword(KeywordKind::Undefined),
         Keyword(KeywordKind::If),
         Keyword(KeywordKind::Else),
         Keyword(KeywordKind::While),
         Keyword(KeywordKind::Do),
+        Keyword(KeywordKind::For),
+        Keyword(KeywordKind::Of),
+        Keyword(KeywordKind::In),
         Keyword(KeywordKind::Break),
         Keyword(KeywordKind::Continue),
+        Keyword(KeywordKind::Switch),
+        Keyword(KeywordKind::Case),
+        Keyword(KeywordKind::Default),
+        Keyword(KeywordKind::Try),
+        Keyword(KeywordKind::Catch),
+        Keyword(KeywordKind::Finally),
+        Keyword(KeywordKind::Throw),
         Keyword(KeywordKind::Function),
         Keyword(KeywordKind::Return),
+        Keyword(KeywordKind::Class),
+        Keyword(KeywordKind::Extends),
+        Keyword(KeywordKind::Super),
+        Keyword(KeywordKind::This),
+        Keyword(KeywordKind::New),
+        Keyword(KeywordKind::Enum),
+        Keyword(KeywordKind::Typeof),
+        Keyword(KeywordKind::Instanceof),
         Keyword(KeywordKind::Import),
         Keyword(KeywordKind::From),
         Keyword(KeywordKind::Export),
         Eof,
     ];
 
-    let actual_output = convert_to_token(input);
+    let actual_output = get_tokens(input);
 
     assert_eq!(expected_output, actual_output);
 }
@@ -202,7 +341,388 @@ fn test_literals() {
         Eof,
     ];
 
-    let actual_output = convert_to_token(input);
+    let actual_output = get_tokens(input);
 
     assert_eq!(expected_output, actual_output)
 }
+
+#[test]
+fn test_spans() {
+    let input = "const x = 10;
+  x += 'ab';";
+
+    let actual_output: Vec<(Position, Position)> = convert_to_token(input)
+        .iter()
+        .map(|spanned_token| (spanned_token.span.start, spanned_token.span.end))
+        .collect();
+
+    let expected_output = vec![
+        (Position::new(0, 1, 1), Position::new(5, 1, 6)), // const
+        (Position::new(6, 1, 7), Position::new(7, 1, 8)), // x
+        (Position::new(8, 1, 9), Position::new(9, 1, 10)), // =
+        (Position::new(10, 1, 11), Position::new(12, 1, 13)), // 10
+        (Position::new(12, 1, 13), Position::new(13, 1, 14)), // ;
+        (Position::new(16, 2, 3), Position::new(17, 2, 4)), // x
+        (Position::new(18, 2, 5), Position::new(20, 2, 7)), // +=
+        (Position::new(21, 2, 8), Position::new(25, 2, 12)), // 'ab'
+        (Position::new(25, 2, 12), Position::new(26, 2, 13)), // ;
+        (Position::new(26, 2, 13), Position::new(26, 2, 13)), // Eof
+    ];
+
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn test_diagnostics() {
+    let input = "const x = #;\nconst y = 'abc";
+
+    let (tokens, diagnostics) = tokenize(input);
+
+    assert_eq!(Token::Illegal, tokens[3].token);
+    assert_eq!(Token::Eof, tokens.last().unwrap().token);
+    assert_eq!(2, diagnostics.len());
+
+    assert_eq!(Some("E0001".to_string()), diagnostics[0].code);
+    let span = diagnostics[0].primary.as_ref().unwrap().span;
+    assert_eq!(Position::new(10, 1, 11), span.start);
+    assert_eq!(Position::new(11, 1, 12), span.end);
+
+    assert_eq!(Some("E0002".to_string()), diagnostics[1].code);
+    let span = diagnostics[1].primary.as_ref().unwrap().span;
+    assert_eq!(Position::new(23, 2, 11), span.start);
+    assert_eq!(Position::new(27, 2, 15), span.end);
+}
+
+#[test]
+fn test_comments() {
+    use Token::*;
+
+    let input = "
+    // line comment
+    let x = 1; // trailing comment
+    /* block
+       comment */
+    x /= 2 / 1;
+    /**
+     * JSDoc comment
+     */
+    /**/
+    ";
+
+    let expected_output = vec![
+        Keyword(KeywordKind::Let),
+        Ident {
+            name: "x".to_string(),
+        },
+        Assign,
+        Literal(LiteralKind::Float {
+            name: "1".to_string(),
+            value: 1.0,
+        }),
+        SemiColon,
+        Ident {
+            name: "x".to_string(),
+        },
+        SlashAssign,
+        Literal(LiteralKind::Float {
+            name: "2".to_string(),
+            value: 2.0,
+        }),
+        Slash,
+        Literal(LiteralKind::Float {
+            name: "1".to_string(),
+            value: 1.0,
+        }),
+        SemiColon,
+        Eof,
+    ];
+
+    assert_eq!(get_tokens(input), expected_output);
+}
+
+#[test]
+fn test_comments_as_trivia() {
+    use token::CommentKind;
+    use Token::*;
+
+    let input = "// line\nx /* block */ /** doc */ /**/";
+
+    let tokens: Vec<Token> = tokenize_with_trivia(input)
+        .0
+        .into_iter()
+        .map(|spanned_token| spanned_token.token)
+        .collect();
+
+    let expected_output = vec![
+        Comment(CommentKind::Line {
+            text: " line".to_string(),
+        }),
+        Ident {
+            name: "x".to_string(),
+        },
+        Comment(CommentKind::Block {
+            text: " block ".to_string(),
+        }),
+        Comment(CommentKind::JsDoc {
+            text: " doc ".to_string(),
+        }),
+        Comment(CommentKind::Block {
+            text: "".to_string(),
+        }),
+        Eof,
+    ];
+
+    assert_eq!(tokens, expected_output);
+
+    let (tokens, diagnostics) = tokenize("x /* never closed");
+
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0003".to_string()));
+}
+
+#[test]
+fn test_numeric_literals() {
+    let input = "
+    3.25 .5 5. 1e9 2.5E-3 1e+2
+    0xFF 0Xff 0b1010 0B11 0o777 0O17
+    1_000_000 0xFF_FF 0.000_001
+    9007199254740993 0x20000000000001
+    0x200000000000050000000000000000000000001
+    0x200000000000050000000000000000000000000 0x200000000000070000000000000000000000000
+    0o1000000000000000012000000000000000000000000000000001
+    0.1 1.7976931348623157e308 1e400 5e-324
+    ";
+
+    let expected_output: Vec<(&str, f64)> = vec![
+        ("3.25", 3.25),
+        (".5", 0.5),
+        ("5.", 5.0),
+        ("1e9", 1e9),
+        ("2.5E-3", 2.5e-3),
+        ("1e+2", 100.0),
+        ("0xFF", 255.0),
+        ("0Xff", 255.0),
+        ("0b1010", 10.0),
+        ("0B11", 3.0),
+        ("0o777", 511.0),
+        ("0O17", 15.0),
+        ("1_000_000", 1_000_000.0),
+        ("0xFF_FF", 65535.0),
+        ("0.000_001", 0.000_001),
+        // Both round to nearest even 2^53
+        ("9007199254740993", 9007199254740992.0),
+        ("0x20000000000001", 9007199254740992.0),
+        // Wider than u128, rounds up only because of the last bit which is lost when
+        // rounding digit by digit
+        (
+            "0x200000000000050000000000000000000000001",
+            1.1417981541647687e46,
+        ),
+        // Exactly half rounds to the even mantissa, down and up
+        (
+            "0x200000000000050000000000000000000000000",
+            1.1417981541647684e46,
+        ),
+        (
+            "0x200000000000070000000000000000000000000",
+            1.141798154164769e46,
+        ),
+        (
+            "0o1000000000000000012000000000000000000000000000000001",
+            1.1417981541647687e46,
+        ),
+        ("0.1", 0.1),
+        ("1.7976931348623157e308", f64::MAX),
+        ("1e400", f64::INFINITY),
+        ("5e-324", 5e-324),
+    ];
+
+    let (tokens, diagnostics) = tokenize(input);
+    assert!(diagnostics.is_empty());
+
+    let actual_output: Vec<(String, f64)> = tokens
+        .into_iter()
+        .filter_map(|spanned_token| match spanned_token.token {
+            Token::Literal(LiteralKind::Float { name, value }) => Some((name, value)),
+            Token::Eof => None,
+            tok => panic!("Expected only float literals but got {:?}", tok),
+        })
+        .collect();
+
+    assert_eq!(actual_output.len(), expected_output.len());
+
+    for ((actual_name, actual_value), (name, value)) in actual_output.iter().zip(expected_output) {
+        assert_eq!(actual_name, name);
+        assert_eq!(actual_value.to_bits(), value.to_bits(), "value of {}", name);
+    }
+}
+
+#[test]
+fn test_bigint_literals() {
+    use Token::*;
+
+    let input = "10n 0n 0xFFn 0b1010n 1_000n 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFn";
+
+    let expected_output = vec![
+        ("10n", "10"),
+        ("0n", "0"),
+        ("0xFFn", "255"),
+        ("0b1010n", "10"),
+        ("1_000n", "1000"),
+        (
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFn",
+            "340282366920938463463374607431768211455",
+        ),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        Literal(LiteralKind::BigInt {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    })
+    .chain(vec![Eof])
+    .collect::<Vec<Token>>();
+
+    assert_eq!(get_tokens(input), expected_output);
+}
+
+#[test]
+fn test_invalid_numeric_literals() {
+    let inputs = [
+        "1__0", "1_", "0x", "0x_1", "1e", "1.5n", "1e3n", "3in", "0b12", "0o8", "010", "0_1", "00",
+    ];
+
+    for input in inputs {
+        let (tokens, diagnostics) = tokenize(input);
+
+        assert_eq!(diagnostics.len(), 1, "input {}", input);
+        assert_eq!(diagnostics[0].code, Some("E0004".to_string()));
+
+        if let Token::Literal(_) = tokens[0].token {
+        } else {
+            panic!("Expected input {} to still give a literal", input);
+        }
+    }
+
+    // Dot followed by non digit is still a dot
+    let tokens = get_tokens("x.y");
+    assert_eq!(tokens[1], Token::Dot);
+}
+
+#[test]
+fn test_escape_sequences() {
+    let input = r#"
+    "a\nb\tc\\d\"e\'f"
+    'it\'s'
+    "\x41B\u{43}\u{1F600}😀\0"
+    "\b\f\v\r\q"
+    "line \
+continuation"
+    "#;
+
+    let expected_output = vec![
+        "a\nb\tc\\d\"e'f",
+        "it's",
+        "ABC\u{1F600}\u{1F600}\0",
+        "\u{8}\u{c}\u{b}\rq",
+        "line continuation",
+    ];
+
+    let (tokens, diagnostics) = tokenize(input);
+    assert!(diagnostics.is_empty());
+
+    let actual_output: Vec<String> = tokens
+        .into_iter()
+        .filter_map(|spanned_token| match spanned_token.token {
+            Token::Literal(LiteralKind::String { name }) => Some(name),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(actual_output, expected_output);
+}
+
+#[test]
+fn test_invalid_escape_sequences() {
+    let inputs = [
+        r#""\x4""#,
+        r#""\u12""#,
+        r#""\u{110000}""#,
+        r#""\u{41""#,
+        r#""\01""#,
+    ];
+
+    for input in inputs {
+        let (_, diagnostics) = tokenize(input);
+
+        assert_eq!(diagnostics.len(), 1, "input {}", input);
+        assert_eq!(diagnostics[0].code, Some("E0005".to_string()));
+    }
+
+    let (_, diagnostics) = tokenize("'abc\nlet x = 1;");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0002".to_string()));
+}
+
+#[test]
+fn test_template_literals() {
+    use Token::*;
+
+    let input = "`plain\\n` `a${x}b${ {c: 1}.c }c` `${`nested ${y}`}`";
+
+    let expected_output = vec![
+        Literal(LiteralKind::String {
+            name: "plain\n".to_string(),
+        }),
+        TemplateHead {
+            name: "a".to_string(),
+        },
+        Ident {
+            name: "x".to_string(),
+        },
+        TemplateMiddle {
+            name: "b".to_string(),
+        },
+        AngleOpenBracket,
+        Ident {
+            name: "c".to_string(),
+        },
+        Colon,
+        Literal(LiteralKind::Float {
+            name: "1".to_string(),
+            value: 1.0,
+        }),
+        AngleCloseBracket,
+        Dot,
+        Ident {
+            name: "c".to_string(),
+        },
+        TemplateTail {
+            name: "c".to_string(),
+        },
+        TemplateHead {
+            name: "".to_string(),
+        },
+        TemplateHead {
+            name: "nested ".to_string(),
+        },
+        Ident {
+            name: "y".to_string(),
+        },
+        TemplateTail {
+            name: "".to_string(),
+        },
+        TemplateTail {
+            name: "".to_string(),
+        },
+        Eof,
+    ];
+
+    assert_eq!(get_tokens(input), expected_output);
+
+    let (_, diagnostics) = tokenize("`abc ${x}");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0002".to_string()));
+}
@@ -1,3 +1,64 @@
+/*
+ * Location of a single character in the source file
+ *
+ * `offset` is the byte offset from the start of the file, `line` and `column`
+ * start from 1 and `column` is counted in chars
+ *
+ * */
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(offset: usize, line: usize, column: usize) -> Self {
+        return Position {
+            offset,
+            line,
+            column,
+        };
+    }
+}
+
+/*
+ * Region of source code covered by a token or an ast node, `end` is
+ * exclusive that is it points to the char right after the region
+ *
+ * */
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        return Span { start, end };
+    }
+
+    // Returns span starting from start of self till end of other
+    pub fn to(&self, other: &Span) -> Span {
+        return Span {
+            start: self.start,
+            end: other.end,
+        };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        return SpannedToken { token, span };
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // Assignment operators
@@ -7,6 +68,17 @@ pub enum Token {
     StarAssign,  // *=
     SlashAssign, // /=
 
+    PercentAssign,  // %=
+    StarStarAssign, // **=
+
+    AmpersandAssign,   // &=
+    VerticalBarAssign, // |=
+    CaretAssign,       // ^=
+
+    LeftShiftAssign,          // <<=
+    RightShiftAssign,         // >>=
+    UnsignedRightShiftAssign, // >>>=
+
     // Comparison operators
     Equality,       // ==
     StrictEquality, // ===
@@ -42,15 +114,48 @@ pub enum Token {
     Star,  // *
     Slash, // /
 
+    Percent,  // %
+    StarStar, // **
+
+    Increment, // ++
+    Decrement, // --
+
     VerticalBar, // |
     Caret,       // ^
     Ampersand,   // &
+    Tilde,       // ~
+
+    LeftShift,          // <<
+    RightShift,         // >>
+    UnsignedRightShift, // >>>
+
+    // Logical operators
+    LogicalAnd,        // &&
+    LogicalOr,         // ||
+    NullishCoalescing, // ??
+    QuestionMark,      // ?
+    QuestionDot,       // ?.
 
     Illegal, // Unknown token
     Eof,     // End of File
 
+    // Only produced when lexer is asked to keep trivia, parser never sees it
+    Comment(CommentKind),
+
     Ident { name: String },
 
+    /*
+     * Template literal `a${x}b${y}c` is split into
+     *
+     * TemplateHead { a }, <tokens of x>, TemplateMiddle { b }, <tokens of y>, TemplateTail { c }
+     *
+     * Template literal without substitution is a plain LiteralKind::String
+     *
+     * */
+    TemplateHead { name: String },
+    TemplateMiddle { name: String },
+    TemplateTail { name: String },
+
     Keyword(KeywordKind),
 
     Literal(LiteralKind),
@@ -63,28 +168,66 @@ pub enum KeywordKind {
 
     True,
     False,
+    Null,
+    Undefined,
 
     If,
     Else,
 
     While,
     Do,
+    For,
+    Of,
+    In,
     Break,
     Continue,
+    Switch,
+    Case,
+    Default,
+
+    Try,
+    Catch,
+    Finally,
+    Throw,
 
     Function,
     Return,
 
+    Class,
+    Extends,
+    Super,
+    This,
+    New,
+
+    Enum,
+
+    Typeof,
+    Instanceof,
+
     Import,
     From,
     Export,
 }
 
+/*
+ * `text` is the content of the comment without its delimiters
+ *
+ * */
+#[derive(Debug, PartialEq, Clone)]
+pub enum CommentKind {
+    Line { text: String },  // // ...
+    Block { text: String }, // /* ... */
+    JsDoc { text: String }, // /** ... */
+}
+
 #[derive(Debug, PartialEq, Clone)]
 
 pub enum LiteralKind {
     Float { name: String, value: f64 },
 
+    // `value` is the decimal digits of the integer
+    BigInt { name: String, value: String },
+
     String { name: String },
 }
 
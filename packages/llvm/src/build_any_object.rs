@@ -0,0 +1,226 @@
+use ast::data_type::DataType;
+use either::Either;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::Module,
+    types::{
+        enums::{AddressSpace, BasicTypeEnum},
+        fn_type::FunctionType,
+        ptr_type::PointerType,
+        struct_type::StructType,
+        traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, ptr_value::PointerValue,
+        traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_class::build_load_vtable,
+    build_console::get_inspect_function,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        expect_pointer, gc::build_gc_alloc, get_byte_pointer_type, string::get_string_pointer_type,
+    },
+    symbol_table::SymbolTable,
+};
+
+const VALUE_FIELD: u64 = 0;
+const INSPECT_FIELD: u64 = 1;
+const VTABLE_FIELD: u64 = 2;
+
+// Size of the record in bytes, only the value at offset 0 is traced
+const RECORD_SIZE: u64 = 24;
+
+/*
+ * Value of `object` is a pointer to a record holding the array, object or instance
+ * of a class along with what is known about it only at compile time
+ *
+ *      { i8* value, i8* inspect, i8* vtable }
+ *
+ * `inspect` is the function which formats the value like console.log does, see
+ * get_inspect_function. `vtable` is the vtable of the class the value is an
+ * instance of or null, so `instanceof` can walk it like the vtable of an instance
+ *
+ * */
+fn get_any_object_type<'a>(context: &'a Context) -> StructType<'a> {
+    let byte_pointer_type = get_byte_pointer_type(context).as_basic_type_enum();
+    let field_types = vec![
+        byte_pointer_type.clone(),
+        byte_pointer_type.clone(),
+        byte_pointer_type,
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+// Function of the `inspect` field, it takes the value and returns a string
+pub(crate) fn get_inspect_function_type<'a>(context: &'a Context) -> FunctionType<'a> {
+    return get_string_pointer_type(context).fn_type(
+        &[get_byte_pointer_type(context).as_basic_type_enum()],
+        false,
+    );
+}
+
+/*
+ * Wraps the array, object or instance of a class into a new record, see
+ * Expression::StructuralConversion
+ *
+ * */
+pub(crate) fn build_any_object<'a>(
+    value: BasicValueEnum<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let byte_pointer_type = get_byte_pointer_type(context);
+
+    let record = build_gc_alloc(
+        context.i64_type().const_int(RECORD_SIZE, false),
+        &[0],
+        context,
+        builder,
+        module,
+        name,
+    );
+
+    let value = builder.build_bitcast(
+        expect_pointer(value),
+        byte_pointer_type.clone(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let inspect = get_inspect_function(data_type, context, symbol_table, module)
+        .to_pointer_value()
+        .const_bitcast(byte_pointer_type.clone())
+        .as_basic_value_enum();
+
+    let vtable = match data_type {
+        DataType::ClassType { .. } => {
+            let vtable = build_load_vtable(value, context, builder, function_value);
+            builder
+                .build_bitcast(
+                    vtable,
+                    byte_pointer_type.clone(),
+                    &function_value.get_unique_reg_name(),
+                )
+                .as_basic_value_enum()
+        }
+        _ => byte_pointer_type.const_null().as_basic_value_enum(),
+    };
+
+    for (field, field_value) in [
+        (VALUE_FIELD, value.as_basic_value_enum()),
+        (INSPECT_FIELD, inspect),
+        (VTABLE_FIELD, vtable),
+    ] {
+        let field_pointer = build_field_pointer(record, field, context, builder, name);
+        builder.build_store(field_pointer, field_value);
+    }
+
+    return record;
+}
+
+// Array, object or instance the record holds, the parser knows its data type
+pub(crate) fn build_any_object_value<'a>(
+    record: PointerValue<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let value = build_load_field(record, VALUE_FIELD, context, builder, name);
+
+    return builder
+        .build_bitcast(value, get_value_pointer_type(data_type, context), name)
+        .as_basic_value_enum();
+}
+
+// Array, object and instance of a class are all pointers
+pub(crate) fn get_value_pointer_type<'a>(
+    data_type: &DataType,
+    context: &'a Context,
+) -> PointerType<'a> {
+    match data_type.force_to_basic_type(context) {
+        BasicTypeEnum::PointerType(pointer_type) => return pointer_type,
+        basic_type => panic!("Expected {:?} to be a pointer type", basic_type),
+    }
+}
+
+// Vtable of the class the value is an instance of, null for arrays and objects
+pub(crate) fn build_any_object_vtable<'a>(
+    record: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> PointerValue<'a> {
+    return build_load_field(record, VTABLE_FIELD, context, builder, name);
+}
+
+/*
+ * Formats the value the way console.log does, the function never collects so it
+ * is called even by the runtime
+ *
+ * */
+pub(crate) fn build_any_object_inspect<'a>(
+    record: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> PointerValue<'a> {
+    let inspect = builder.build_bitcast(
+        build_load_field(record, INSPECT_FIELD, context, builder, name),
+        get_inspect_function_type(context).ptr_type(AddressSpace::Generic),
+        name,
+    );
+    let value = build_load_field(record, VALUE_FIELD, context, builder, name);
+
+    let text = builder.build_call2(
+        Either::Right(&inspect),
+        &[value.as_basic_value_enum()],
+        name,
+    );
+    return expect_pointer(text.to_basic_value_enum().unwrap());
+}
+
+fn build_load_field<'a>(
+    record: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> PointerValue<'a> {
+    let field_pointer = build_field_pointer(record, field, context, builder, name);
+
+    return expect_pointer(builder.build_load(
+        field_pointer,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        name,
+    ));
+}
+
+fn build_field_pointer<'a>(
+    record: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> PointerValue<'a> {
+    let record = builder.build_bitcast(
+        record,
+        get_any_object_type(context).ptr_type(AddressSpace::Generic),
+        name,
+    );
+    let indices = vec![
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(get_any_object_type(context), &record, &indices, name);
+}
@@ -0,0 +1,728 @@
+use ast::{
+    data_type::{create_union, DataType},
+    expression::{ArrayMethod, BinaryOperator},
+};
+use either::Either;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage},
+    module::Module,
+    types::{enums::AddressSpace, traits::BasicTypeTrait},
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_equality::build_strict_equality,
+    build_exception::build_call_or_invoke,
+    build_union::{build_undefined_union, build_union_conversion, get_union_pointer_type},
+    llvm_utils::LLVMUtils,
+    runtime::{
+        array::{build_array_slot, build_load_array_length},
+        build_runtime_call, build_runtime_call_returning_pointer, expect_int, expect_pointer,
+        get_byte_pointer_type, get_runtime_fn, ARRAY_GET_SLOT, ARRAY_INDEX_OF_BITS,
+        ARRAY_INDEX_OF_NUMBER, ARRAY_INDEX_OF_STRING, ARRAY_NEW, ARRAY_POP_SLOT, ARRAY_PUSH_SLOT,
+        ARRAY_SET_SLOT, ARRAY_SLICE,
+    },
+    symbol_table::SymbolTable,
+};
+
+const UNDEFINED_SLOT: &str = "|array|undefined_slot|";
+
+/*
+ * Elements of these data types point to memory owned by the garbage collector, so
+ * the buffer of the array has to be traced
+ *
+ * */
+pub(crate) fn holds_pointers(data_type: &DataType) -> bool {
+    return matches!(
+        data_type,
+        DataType::String
+            | DataType::ArrayType { .. }
+            | DataType::ObjectType { .. }
+            | DataType::UnionType { .. }
+            | DataType::FunctionType { .. }
+            | DataType::ClassType { .. }
+            | DataType::Object
+    );
+}
+
+/*
+ * Allocates the array and stores the already evaluated elements in order
+ *
+ * */
+pub(crate) fn build_array_literal<'a>(
+    values: Vec<BasicValueEnum<'a>>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let array = build_runtime_call_returning_pointer(
+        ARRAY_NEW,
+        &[
+            context
+                .i64_type()
+                .const_int(values.len() as u64, false)
+                .as_basic_value_enum(),
+            build_holds_pointers(base_type, context).as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        name,
+    );
+
+    for (i, value) in values.into_iter().enumerate() {
+        let slot = build_array_slot(
+            array,
+            context.i64_type().const_int(i as u64, false),
+            context,
+            builder,
+            &function_value.get_unique_reg_name(),
+        );
+        let element_pointer =
+            build_element_pointer(slot, base_type, context, builder, function_value);
+        builder.build_store(element_pointer, value);
+    }
+
+    return array;
+}
+
+/*
+ * Element which does not exist reads as undefined, so the element is boxed into a
+ * union with undefined unless the element type already holds it. In strict mode it
+ * is a RangeError instead, see get_undefined_slot
+ *
+ * */
+pub(crate) fn build_array_member_access<'a>(
+    array: PointerValue<'a>,
+    index: FloatValue<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let undefined_slot = get_undefined_slot(base_type, context, builder, function_value, module);
+    let slot = build_runtime_invoke_returning_pointer(
+        ARRAY_GET_SLOT,
+        &[
+            array.as_basic_value_enum(),
+            index.as_basic_value_enum(),
+            undefined_slot.as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+    );
+
+    return build_load_element_or_undefined(
+        slot,
+        undefined_slot,
+        base_type,
+        context,
+        builder,
+        function_value,
+        module,
+        name,
+    );
+}
+
+/*
+ * Loads the element at `index` without checking the bounds, the caller makes sure
+ * the index is below the length, e.g. while iterating with for...of
+ *
+ * */
+pub(crate) fn build_array_element<'a>(
+    array: PointerValue<'a>,
+    index: IntValue<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let slot = build_array_slot(
+        array,
+        index,
+        context,
+        builder,
+        &function_value.get_unique_reg_name(),
+    );
+
+    return build_load_element(slot, base_type, context, builder, function_value, name);
+}
+
+/*
+ * Returns pointer to the element at `index` for assignment, assigning at the length
+ * appends the element
+ *
+ * Compound assignment reads the element first, so it is a RangeError for it to be
+ * past the end like for strings, arrays and objects
+ *
+ * */
+pub(crate) fn build_assignable_element_pointer<'a>(
+    array: PointerValue<'a>,
+    index: FloatValue<'a>,
+    base_type: &DataType,
+    is_compound: bool,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let slot = if is_compound {
+        build_runtime_invoke_returning_pointer(
+            ARRAY_GET_SLOT,
+            &[
+                array.as_basic_value_enum(),
+                index.as_basic_value_enum(),
+                get_byte_pointer_type(context)
+                    .const_null()
+                    .as_basic_value_enum(),
+            ],
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )
+    } else {
+        build_runtime_invoke_returning_pointer(
+            ARRAY_SET_SLOT,
+            &[
+                array.as_basic_value_enum(),
+                index.as_basic_value_enum(),
+                build_holds_pointers(base_type, context).as_basic_value_enum(),
+            ],
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )
+    };
+
+    return build_element_pointer(slot, base_type, context, builder, function_value);
+}
+
+pub(crate) fn build_array_length<'a>(
+    array: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> FloatValue<'a> {
+    let length = build_load_array_length(
+        array,
+        context,
+        builder,
+        &function_value.get_unique_reg_name(),
+    );
+
+    return builder.build_si_to_fp(length, context.f64_type(), name);
+}
+
+/*
+ * Slots of a union array hold pointers to boxes, so indexOf compares the members
+ * the boxes hold with `===` instead of the bits of the slots
+ *
+ *      i = 0
+ *      br condition
+ *
+ *  condition:
+ *      br i < length, body, exit
+ *
+ *  body:
+ *      br <element i> === <value>, found, next
+ *
+ *  next:
+ *      i = i + 1
+ *      br condition
+ *
+ *  found:
+ *      position = i
+ *      br exit
+ *
+ * */
+pub(crate) fn build_union_index_of<'a>(
+    array: PointerValue<'a>,
+    value: BasicValueEnum<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> FloatValue<'a> {
+    let i64_type = context.i64_type();
+    let f64_type = context.f64_type();
+
+    let reg_name = function_value.get_unique_reg_name();
+    let length = build_load_array_length(array, context, builder, &reg_name);
+
+    let gc_frame = symbol_table.get_gc_frame();
+    let index_pointer =
+        gc_frame.build_alloca(i64_type, context, &function_value.get_unique_reg_name());
+    let position_pointer =
+        gc_frame.build_alloca(f64_type, context, &function_value.get_unique_reg_name());
+    builder.build_store(index_pointer, i64_type.const_int(0, false));
+    builder.build_store(position_pointer, f64_type.const_float(-1.0));
+
+    let condition_block_name = function_value.get_unique_block_name();
+    let condition_block = context.append_basic_block(function_value, &condition_block_name);
+    let body_block_name = function_value.get_unique_block_name();
+    let body_block = context.append_basic_block(function_value, &body_block_name);
+    let next_block_name = function_value.get_unique_block_name();
+    let next_block = context.append_basic_block(function_value, &next_block_name);
+    let found_block_name = function_value.get_unique_block_name();
+    let found_block = context.append_basic_block(function_value, &found_block_name);
+    let exit_block_name = function_value.get_unique_block_name();
+    let exit_block = context.append_basic_block(function_value, &exit_block_name);
+
+    builder.build_unconditional_branch(&condition_block);
+    builder.position_at_end(&condition_block);
+    let index = expect_int(builder.build_load(
+        index_pointer,
+        i64_type.as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+    let is_in_range = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        index,
+        length,
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_conditional_branch(is_in_range, &body_block, &exit_block);
+
+    builder.position_at_end(&body_block);
+    let reg_name = function_value.get_unique_reg_name();
+    let element = build_array_element(
+        array,
+        index,
+        base_type,
+        context,
+        builder,
+        function_value,
+        &reg_name,
+    );
+    let reg_name = function_value.get_unique_reg_name();
+    let is_equal = build_strict_equality(
+        &BinaryOperator::StrictEquality,
+        element,
+        base_type,
+        value,
+        base_type,
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+    builder.build_conditional_branch(is_equal, &found_block, &next_block);
+
+    builder.position_at_end(&next_block);
+    let next_index = builder.build_int_add(
+        index,
+        i64_type.const_int(1, false),
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_store(index_pointer, next_index);
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&found_block);
+    let position = builder.build_si_to_fp(index, f64_type, &function_value.get_unique_reg_name());
+    builder.build_store(position_pointer, position);
+    builder.build_unconditional_branch(&exit_block);
+
+    builder.position_at_end(&exit_block);
+    let position = builder.build_load(position_pointer, f64_type.as_basic_type_enum(), name);
+
+    if let BasicValueEnum::FloatValue(position) = position {
+        return position;
+    } else {
+        unreachable!();
+    }
+}
+
+/*
+ * Arguments are evaluated by the caller before any method is called, so pushing
+ * `arr.push(1, f())` never sees the array half updated
+ *
+ * */
+pub(crate) fn build_array_method_call<'a>(
+    array: PointerValue<'a>,
+    method: &ArrayMethod,
+    arguments: Vec<BasicValueEnum<'a>>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    match method {
+        ArrayMethod::Push => {
+            for value in arguments {
+                let slot = build_runtime_call_returning_pointer(
+                    ARRAY_PUSH_SLOT,
+                    &[
+                        array.as_basic_value_enum(),
+                        build_holds_pointers(base_type, context).as_basic_value_enum(),
+                    ],
+                    context,
+                    builder,
+                    module,
+                    &function_value.get_unique_reg_name(),
+                );
+                let element_pointer =
+                    build_element_pointer(slot, base_type, context, builder, function_value);
+                builder.build_store(element_pointer, value);
+            }
+
+            let length = build_array_length(array, context, builder, function_value, name);
+            return length.as_basic_value_enum();
+        }
+
+        ArrayMethod::Pop => {
+            let undefined_slot =
+                get_undefined_slot(base_type, context, builder, function_value, module);
+            let slot = build_runtime_invoke_returning_pointer(
+                ARRAY_POP_SLOT,
+                &[
+                    array.as_basic_value_enum(),
+                    undefined_slot.as_basic_value_enum(),
+                ],
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            );
+
+            return build_load_element_or_undefined(
+                slot,
+                undefined_slot,
+                base_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        ArrayMethod::Slice => {
+            let mut arguments = arguments.into_iter();
+            let start = match arguments.next() {
+                Some(start) => start,
+                None => context.f64_type().const_float(0.0).as_basic_value_enum(),
+            };
+            let end = match arguments.next() {
+                Some(end) => end,
+                None => {
+                    let reg_name = function_value.get_unique_reg_name();
+                    build_array_length(array, context, builder, function_value, &reg_name)
+                        .as_basic_value_enum()
+                }
+            };
+
+            let sliced = build_runtime_call_returning_pointer(
+                ARRAY_SLICE,
+                &[
+                    array.as_basic_value_enum(),
+                    start,
+                    end,
+                    build_holds_pointers(base_type, context).as_basic_value_enum(),
+                ],
+                context,
+                builder,
+                module,
+                name,
+            );
+
+            return sliced.as_basic_value_enum();
+        }
+
+        /*
+         * Elements are compared with `===`, numbers by value, strings by content and
+         * booleans, arrays and objects by the bits of their slot
+         *
+         * */
+        ArrayMethod::IndexOf => {
+            let value = arguments.into_iter().next().unwrap();
+
+            let (runtime_fn, value) = match (base_type, value) {
+                (DataType::Float, value) => (ARRAY_INDEX_OF_NUMBER, value),
+                (DataType::String, value) => (ARRAY_INDEX_OF_STRING, value),
+                (DataType::Boolean, BasicValueEnum::IntValue(boolean)) => {
+                    let bits = builder.build_select(
+                        boolean,
+                        context.i64_type().const_int(1, false).as_basic_value_enum(),
+                        context.i64_type().const_int(0, false).as_basic_value_enum(),
+                        &function_value.get_unique_reg_name(),
+                    );
+                    (ARRAY_INDEX_OF_BITS, bits)
+                }
+                (_, BasicValueEnum::PointerValue(pointer)) => {
+                    let bits = builder.build_ptr_to_int(
+                        pointer,
+                        context.i64_type(),
+                        &function_value.get_unique_reg_name(),
+                    );
+                    (ARRAY_INDEX_OF_BITS, bits.as_basic_value_enum())
+                }
+                (data_type, value) => panic!(
+                    "indexOf is not supported for value {:?} of data type {:?}",
+                    value, data_type
+                ),
+            };
+
+            let position = build_runtime_call(
+                runtime_fn,
+                &[array.as_basic_value_enum(), value],
+                context,
+                builder,
+                module,
+                name,
+            );
+
+            return position.unwrap();
+        }
+    }
+}
+
+fn build_holds_pointers<'a>(base_type: &DataType, context: &'a Context) -> IntValue<'a> {
+    return context
+        .i1_type()
+        .const_int(holds_pointers(base_type) as u64, false);
+}
+
+/*
+ * Casts the 8 byte slot returned by the runtime to a pointer of the element type
+ *
+ * */
+fn build_element_pointer<'a>(
+    slot: PointerValue<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> PointerValue<'a> {
+    let element_type = base_type.force_to_basic_type(context);
+
+    return builder.build_bitcast(
+        slot,
+        element_type.ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+}
+
+fn build_load_element<'a>(
+    slot: PointerValue<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let element_pointer = build_element_pointer(slot, base_type, context, builder, function_value);
+
+    return builder.build_load(
+        element_pointer,
+        base_type.force_to_basic_type(context),
+        name,
+    );
+}
+
+/*
+ * Runtime functions which look up a slot throw a RangeError when there is no such
+ * element, so inside of a try they are invoked like any other function which throws
+ *
+ * */
+fn build_runtime_invoke_returning_pointer<'a>(
+    runtime_fn: &str,
+    args: &[BasicValueEnum<'a>],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let runtime_fn = get_runtime_fn(runtime_fn, context, module);
+    let reg_name = function_value.get_unique_reg_name();
+    let slot = build_call_or_invoke(
+        Either::Left(&runtime_fn),
+        args,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        &reg_name,
+    );
+
+    return expect_pointer(slot.unwrap());
+}
+
+/*
+ * Runtime returns the `undefined_slot` when the element does not exist, for element
+ * types which hold undefined the slot already holds it
+ *
+ *      br <slot> == <undefined_slot>, undefined, element
+ *
+ *  undefined:
+ *      <undefined> = undefined box of <base_type | undefined>
+ *      br end
+ *
+ *  element:
+ *      <boxed> = <element> boxed into <base_type | undefined>
+ *      br end
+ *
+ *  end:
+ *      phi [<undefined>, undefined], [<boxed>, element]
+ *
+ * */
+fn build_load_element_or_undefined<'a>(
+    slot: PointerValue<'a>,
+    undefined_slot: PointerValue<'a>,
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    if base_type.accepts_nullish(&DataType::Undefined) {
+        return build_load_element(slot, base_type, context, builder, function_value, name);
+    }
+
+    let data_type = create_union(vec![base_type.clone(), DataType::Undefined]);
+    let types = match &data_type {
+        DataType::UnionType { types } => types,
+        _ => unreachable!(),
+    };
+
+    let address = builder.build_ptr_to_int(
+        slot,
+        context.i64_type(),
+        &function_value.get_unique_reg_name(),
+    );
+    let undefined_address = builder.build_ptr_to_int(
+        undefined_slot,
+        context.i64_type(),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_undefined = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        address,
+        undefined_address,
+        &function_value.get_unique_reg_name(),
+    );
+
+    let undefined_block_name = function_value.get_unique_block_name();
+    let undefined_block = context.append_basic_block(function_value, &undefined_block_name);
+    let element_block_name = function_value.get_unique_block_name();
+    let element_block = context.append_basic_block(function_value, &element_block_name);
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block = context.append_basic_block(function_value, &end_block_name);
+    builder.build_conditional_branch(is_undefined, &undefined_block, &element_block);
+
+    builder.position_at_end(&undefined_block);
+    let reg_name = function_value.get_unique_reg_name();
+    let undefined =
+        build_undefined_union(types, context, builder, function_value, module, &reg_name);
+    let undefined_block = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block);
+
+    builder.position_at_end(&element_block);
+    let reg_name = function_value.get_unique_reg_name();
+    let element = build_load_element(slot, base_type, context, builder, function_value, &reg_name);
+    let reg_name = function_value.get_unique_reg_name();
+    let boxed = build_union_conversion(
+        element,
+        base_type,
+        &data_type,
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+    let element_block = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block);
+
+    builder.position_at_end(&end_block);
+    return builder.build_phi(
+        get_union_pointer_type(context).as_basic_type_enum(),
+        &[
+            (undefined.as_basic_value_enum(), &undefined_block),
+            (boxed, &element_block),
+        ],
+        name,
+    );
+}
+
+/*
+ * Slot the runtime returns for an element which does not exist, it holds undefined
+ * when the element type does, e.g. `(number | undefined)[]`. In strict mode there is
+ * no such element to read, the runtime throws a RangeError instead
+ *
+ * */
+fn get_undefined_slot<'a>(
+    base_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    // The global is a whole slot, so the runtime returns it like the slot of any other element
+    let slot_type = get_byte_pointer_type(context);
+    let global = match module.get_global(UNDEFINED_SLOT) {
+        Some(global) => global,
+        None => {
+            let global = module.add_global(slot_type.clone(), UNDEFINED_SLOT);
+            global.set_initializer(&slot_type.const_null());
+            global.set_linkage(&Linkage::Private);
+            global
+        }
+    };
+
+    /*
+     * Slot is null for any other element type, which is what undefined of
+     * `undefined[]` is and for the rest the slot is only compared with
+     *
+     * */
+    match base_type {
+        DataType::UnionType { types } if base_type.accepts_nullish(&DataType::Undefined) => {
+            let reg_name = function_value.get_unique_reg_name();
+            let undefined =
+                build_undefined_union(types, context, builder, function_value, module, &reg_name);
+            let slot = builder.build_bitcast(
+                global.as_pointer_value(),
+                undefined.get_type().ptr_type(AddressSpace::Generic),
+                &function_value.get_unique_reg_name(),
+            );
+            builder.build_store(slot, undefined);
+        }
+        _ => {}
+    }
+
+    return builder.build_bitcast(
+        global.as_pointer_value(),
+        get_byte_pointer_type(context),
+        &function_value.get_unique_reg_name(),
+    );
+}
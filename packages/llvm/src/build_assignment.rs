@@ -2,11 +2,15 @@ use ast::declaration::VariableAssignmentOperator;
 use inkwell::{
     builder::Builder,
     context::Context,
+    module::Module,
     types::traits::BasicTypeTrait,
     values::{enums::BasicValueEnum, fn_value::FunctionValue, ptr_value::PointerValue},
 };
 
-use crate::symbol_table::SymbolTable;
+use crate::{
+    build_number::build_number_operation, build_string::build_string_concat,
+    runtime::string::get_string_pointer_type, symbol_table::SymbolTable,
+};
 
 /*
  * When given a pointerValue, assignmentOperator, BasicValue
@@ -23,6 +27,7 @@ pub(crate) fn build_assignment<'a>(
     builder: &'a Builder,
     function_value: &mut FunctionValue,
     _symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
 ) {
     let var_ptr = var_ptr.clone();
     let value = value.clone();
@@ -32,37 +37,26 @@ pub(crate) fn build_assignment<'a>(
             builder.build_store(var_ptr, value);
         }
 
-        VariableAssignmentOperator::PlusAssign => {
-            let load_value = builder.build_load(
-                var_ptr,
-                context.f64_type().as_basic_type_enum(),
-                function_value.get_unique_reg_name().as_str(),
-            );
-
-            if let BasicValueEnum::FloatValue(lhs) = load_value {
-                if let BasicValueEnum::FloatValue(rhs) = value {
-                    let result_value = builder.build_float_add(
-                        lhs,
-                        rhs,
-                        function_value.get_unique_reg_name().as_str(),
-                    );
-                    builder.build_store(var_ptr, result_value);
-                }
-            }
-        }
-
-        VariableAssignmentOperator::MinusAssign => {
+        /*
+         * Parser makes sure the value has the same data type as the variable, so a
+         * pointer value means both of them are strings
+         *
+         * */
+        VariableAssignmentOperator::PlusAssign if is_string(&value) => {
             let load_value = builder.build_load(
                 var_ptr,
-                context.f64_type().as_basic_type_enum(),
+                get_string_pointer_type(context).as_basic_type_enum(),
                 function_value.get_unique_reg_name().as_str(),
             );
 
-            if let BasicValueEnum::FloatValue(lhs) = load_value {
-                if let BasicValueEnum::FloatValue(rhs) = value {
-                    let result_value = builder.build_float_sub(
+            if let BasicValueEnum::PointerValue(lhs) = load_value {
+                if let BasicValueEnum::PointerValue(rhs) = value {
+                    let result_value = build_string_concat(
                         lhs,
                         rhs,
+                        context,
+                        builder,
+                        module,
                         function_value.get_unique_reg_name().as_str(),
                     );
                     builder.build_store(var_ptr, result_value);
@@ -70,42 +64,38 @@ pub(crate) fn build_assignment<'a>(
             }
         }
 
-        VariableAssignmentOperator::StarAssign => {
+        _ => {
             let load_value = builder.build_load(
                 var_ptr,
                 context.f64_type().as_basic_type_enum(),
                 function_value.get_unique_reg_name().as_str(),
             );
+            let binary_operator = operator.get_binary_operator().unwrap();
 
             if let BasicValueEnum::FloatValue(lhs) = load_value {
                 if let BasicValueEnum::FloatValue(rhs) = value {
-                    let result_value = builder.build_float_mul(
+                    let reg_name = function_value.get_unique_reg_name();
+                    let result_value = build_number_operation(
+                        &binary_operator,
                         lhs,
                         rhs,
-                        function_value.get_unique_reg_name().as_str(),
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
                     );
                     builder.build_store(var_ptr, result_value);
                 }
             }
         }
-
-        VariableAssignmentOperator::SlashAssign => {
-            let load_value = builder.build_load(
-                var_ptr,
-                context.f64_type().as_basic_type_enum(),
-                function_value.get_unique_reg_name().as_str(),
-            );
-
-            if let BasicValueEnum::FloatValue(lhs) = load_value {
-                if let BasicValueEnum::FloatValue(rhs) = value {
-                    let result_value = builder.build_float_div(
-                        lhs,
-                        rhs,
-                        function_value.get_unique_reg_name().as_str(),
-                    );
-                    builder.build_store(var_ptr, result_value);
-                }
-            }
-        } // _ => todo!(),
     };
 }
+
+fn is_string(value: &BasicValueEnum) -> bool {
+    if let BasicValueEnum::PointerValue(_) = value {
+        return true;
+    } else {
+        return false;
+    }
+}
@@ -0,0 +1,610 @@
+use std::convert::TryInto;
+
+use ast::{
+    class::{Class, ClassMethod},
+    data_type::DataType,
+    expression::Expression,
+};
+use either::Either;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::Module,
+    types::{
+        enums::{AddressSpace, BasicTypeEnum},
+        ptr_type::PointerType,
+        struct_type::StructType,
+        traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, global_value::GlobalValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_any_object::build_any_object_vtable,
+    build_closure::build_closure_object,
+    build_exception::build_call_or_invoke,
+    build_expression::{build_expression, get_object_layout},
+    build_union::build_union_dispatch,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        build_runtime_call_returning_int, expect_int, expect_pointer, gc::build_gc_alloc,
+        get_byte_pointer_type, IS_INSTANCE,
+    },
+    symbol_table::SymbolTable,
+    utils::convert_function_data_type_to_llvm_function_type,
+};
+
+/*
+ * Instance of a class is an i8* pointing to the packed struct
+ *
+ *      { i8** vtable, <field_0>, <field_1>, ... }
+ *
+ * which it is cast to whenever a field is used. The vtable is a global holding
+ * the vtable of the parent class followed by the functions of the methods in the
+ * order of Class::methods, so a method is called through the same slot whichever
+ * subclass the instance is of
+ *
+ *      [ i8* <vtable of the parent or null>, i8* <method_0>, i8* <method_1>, ... ]
+ *
+ * */
+pub(crate) fn get_class_struct_type<'a>(class: &Class, context: &'a Context) -> StructType<'a> {
+    let mut field_types: Vec<BasicTypeEnum> =
+        vec![get_vtable_pointer_type(context).as_basic_type_enum()];
+
+    for field in class.fields.values() {
+        field_types.push(field.data_type.force_to_basic_type(context));
+    }
+
+    return context.struct_type(&field_types, true);
+}
+
+fn get_vtable_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return get_byte_pointer_type(context).ptr_type(AddressSpace::Generic);
+}
+
+// Slot of the first method in the vtable, the vtable of the parent comes before it
+const METHODS_SLOT: usize = 1;
+
+/*
+ * Vtable of the class, declared by the first use when the class is defined later
+ * in the module or in another module
+ *
+ * */
+fn get_vtable<'a>(class: &Class, context: &'a Context, module: &'a Module) -> GlobalValue<'a> {
+    let name = class.get_vtable_name();
+
+    return module.get_global(&name).unwrap_or_else(|| {
+        let vtable_type =
+            get_byte_pointer_type(context).array_type((class.methods.len() + METHODS_SLOT) as u32);
+        module.add_global(vtable_type, &name)
+    });
+}
+
+// Class of the instance, the parser makes sure the data type is DataType::ClassType
+pub(crate) fn get_class<'b>(data_type: &DataType, symbol_table: &'b SymbolTable) -> &'b Class {
+    if let DataType::ClassType { name, parents: _ } = data_type {
+        return symbol_table
+            .classes
+            .get(name)
+            .unwrap_or_else(|| panic!("Class {} is not declared in the module", name));
+    } else {
+        panic!(
+            "Expected data type to be DataType::ClassType but got {:?}",
+            data_type
+        );
+    }
+}
+
+// Function of the method takes the instance as the first argument
+fn get_method_data_type(method: &ClassMethod, this_type: &DataType) -> DataType {
+    if let DataType::FunctionType {
+        arguments,
+        return_type,
+    } = &method.data_type
+    {
+        let mut arguments_with_this = vec![this_type.clone()];
+        arguments_with_this.extend(arguments.iter().cloned());
+
+        return DataType::FunctionType {
+            arguments: arguments_with_this,
+            return_type: return_type.clone(),
+        };
+    } else {
+        unreachable!();
+    }
+}
+
+/*
+ * Declares the functions of the members the class declares itself along with its
+ * allocator, inherited ones are declared by the parent class
+ *
+ * Every function is declared before any of them is generated, so a method can
+ * call the others and create instances of the class
+ *
+ * */
+pub(crate) fn declare_class<'a>(
+    class: &Class,
+    context: &'a Context,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let this_type = class.get_data_type();
+
+    let methods = class
+        .methods
+        .values()
+        .chain(std::iter::once(&class.constructor))
+        .filter(|method| method.owner == class.llvm_name);
+
+    for method in methods {
+        let data_type = get_method_data_type(method, &this_type);
+        declare_function(&method.function, &data_type, context, symbol_table, module);
+    }
+
+    let static_methods = class
+        .static_methods
+        .values()
+        .filter(|method| method.owner == class.llvm_name);
+
+    for method in static_methods {
+        declare_function(
+            &method.function,
+            &method.data_type,
+            context,
+            symbol_table,
+            module,
+        );
+    }
+
+    // Allocator takes the arguments of the constructor and returns the instance
+    if let DataType::FunctionType { arguments, .. } = &class.constructor.data_type {
+        let data_type = DataType::FunctionType {
+            arguments: arguments.clone(),
+            return_type: Box::new(this_type),
+        };
+        declare_function(
+            &class.get_allocator_name(),
+            &data_type,
+            context,
+            symbol_table,
+            module,
+        );
+    }
+
+    symbol_table
+        .classes
+        .insert(class.llvm_name.clone(), class.clone());
+}
+
+fn declare_function<'a>(
+    name: &str,
+    data_type: &DataType,
+    context: &'a Context,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let fn_value = module.get_function(name).unwrap_or_else(|| {
+        let fn_type = convert_function_data_type_to_llvm_function_type(data_type, context);
+        module.add_function(name, fn_type, None)
+    });
+
+    symbol_table.insert_global(name.to_string(), fn_value.to_pointer_value());
+}
+
+/*
+ * Generates the vtable and the allocator of a class declared in the module, its
+ * functions have to be declared by declare_class first
+ *
+ *      <instance> = gc_alloc <size of the struct>
+ *      <instance>.vtable = <vtable>
+ *      call <constructor>(<instance>, <arguments>)
+ *      ret <instance>
+ *
+ * */
+pub(crate) fn build_class_definition<'a>(
+    class: &Class,
+    context: &'a Context,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let byte_pointer_type = get_byte_pointer_type(context);
+
+    let parent_vtable = match class.parents.first() {
+        Some(parent) => {
+            let parent = symbol_table.classes.get(parent).unwrap();
+            get_vtable(parent, context, module)
+                .as_pointer_value()
+                .const_bitcast(byte_pointer_type.clone())
+        }
+        None => byte_pointer_type.const_null(),
+    };
+
+    let functions: Vec<PointerValue> = class
+        .methods
+        .values()
+        .map(|method| {
+            let fn_value = module.get_function(&method.function).unwrap();
+            return fn_value
+                .to_pointer_value()
+                .const_bitcast(byte_pointer_type.clone());
+        })
+        .collect();
+
+    let mut slots = vec![parent_vtable];
+    slots.extend(functions);
+
+    let vtable = get_vtable(class, context, module);
+    vtable.set_initializer(&byte_pointer_type.const_array(&slots));
+    vtable.set_constant(true);
+
+    let allocator = module.get_function(&class.get_allocator_name()).unwrap();
+    let builder = context.create_builder();
+    let entry_block = context.append_basic_block(&allocator, "entry");
+    builder.position_at_end(&entry_block);
+
+    // Vtable does not point to memory of the garbage collector
+    let fields = class
+        .fields
+        .iter()
+        .map(|(name, field)| (name.clone(), field.data_type.clone()))
+        .collect();
+    let (size, pointer_offsets) = get_object_layout(&fields);
+    let pointer_offsets: Vec<u64> = pointer_offsets.iter().map(|offset| offset + 8).collect();
+
+    let instance = build_gc_alloc(
+        context.i64_type().const_int(size + 8, false),
+        &pointer_offsets,
+        context,
+        &builder,
+        module,
+        "instance",
+    );
+
+    let struct_type = get_class_struct_type(class, context);
+    let struct_pointer = builder.build_bitcast(
+        instance,
+        struct_type.ptr_type(AddressSpace::Generic),
+        "fields",
+    );
+    let vtable_slot = builder.build_gep_2(
+        struct_type,
+        &struct_pointer,
+        &[
+            context.i32_type().const_int(0, true),
+            context.i32_type().const_int(0, true),
+        ],
+        "vtable_slot",
+    );
+    let vtable_pointer = builder.build_bitcast(
+        vtable.as_pointer_value(),
+        get_vtable_pointer_type(context),
+        "vtable",
+    );
+    builder.build_store(vtable_slot, vtable_pointer);
+
+    let argument_count = match &class.constructor.data_type {
+        DataType::FunctionType { arguments, .. } => arguments.len(),
+        _ => unreachable!(),
+    };
+    let mut arguments = vec![instance.as_basic_value_enum()];
+    for i in 0..argument_count {
+        arguments.push(allocator.get_nth_param(i as u32).unwrap());
+    }
+
+    let constructor = module.get_function(&class.constructor.function).unwrap();
+    builder.build_call2(Either::Left(&constructor), &arguments, "");
+    builder.build_return(Some(&instance.as_basic_value_enum()));
+}
+
+/*
+ * Pointer to the field of the instance along with the type of the field, fields
+ * come after the vtable
+ *
+ * */
+pub(crate) fn build_field_pointer<'a>(
+    object_value: BasicValueEnum<'a>,
+    object_data_type: &DataType,
+    field: &str,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+) -> (PointerValue<'a>, BasicTypeEnum<'a>) {
+    let class = get_class(object_data_type, symbol_table);
+    let (index, _, class_field) = class.fields.get_full(field).unwrap();
+    let index = index + 1;
+
+    let struct_type = get_class_struct_type(class, context);
+    let struct_pointer = builder.build_bitcast(
+        expect_pointer(object_value),
+        struct_type.ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let field_pointer = builder.build_gep_2(
+        struct_type,
+        &struct_pointer,
+        &[
+            context.i32_type().const_int(0, true),
+            context
+                .i32_type()
+                .const_int(index.try_into().unwrap(), true),
+        ],
+        &function_value.get_unique_reg_name(),
+    );
+
+    return (
+        field_pointer,
+        class_field.data_type.force_to_basic_type(context),
+    );
+}
+
+// Vtable of the instance, it is the first field of an instance of any class
+pub(crate) fn build_load_vtable<'a>(
+    instance: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> PointerValue<'a> {
+    let vtable_slot = builder.build_bitcast(
+        instance,
+        get_vtable_pointer_type(context).ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+    let vtable = builder.build_load(
+        vtable_slot,
+        get_vtable_pointer_type(context).as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    return expect_pointer(vtable);
+}
+
+// Function of the method at the index in the vtable of the instance, as i8*
+fn build_vtable_load<'a>(
+    instance: PointerValue<'a>,
+    index: usize,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> PointerValue<'a> {
+    let vtable = build_load_vtable(instance, context, builder, function_value);
+
+    let byte_pointer_type = get_byte_pointer_type(context);
+    let function_slot = builder.build_gep_2(
+        byte_pointer_type.clone(),
+        &vtable,
+        &[context
+            .i64_type()
+            .const_int((index + METHODS_SLOT) as u64, false)],
+        &function_value.get_unique_reg_name(),
+    );
+    let function = builder.build_load(
+        function_slot,
+        byte_pointer_type.as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    return expect_pointer(function);
+}
+
+/*
+ * Closure of the method bound to the instance, see build_structural_conversion.
+ * The function of the method takes `this` first like the function of a closure
+ * takes its environment, so the instance is the environment
+ *
+ * */
+pub(crate) fn build_method_closure<'a>(
+    instance: PointerValue<'a>,
+    class: &Class,
+    method: &str,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let index = class.methods.get_index_of(method).unwrap();
+    let function = build_vtable_load(instance, index, context, builder, function_value);
+
+    let environment = builder.build_bitcast(
+        instance,
+        get_byte_pointer_type(context),
+        &format!("{}_environment", name),
+    );
+    return build_closure_object(function, environment, context, builder, module, name);
+}
+
+/*
+ * Method is looked up in the vtable of the instance, so the method of the class
+ * the instance was created with is called
+ *
+ *      <vtable> = load <instance>.vtable
+ *      <function> = load <vtable>[<index of the method>]
+ *      call <function>(<instance>, <arguments>)
+ *
+ * */
+pub(crate) fn build_method_call<'a>(
+    object: &Expression,
+    method: &str,
+    arguments: &[Expression],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> Result<Option<BasicValueEnum<'a>>, Diagnostic> {
+    let object_value = build_expression(
+        object,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    let mut args: Vec<BasicValueEnum> = vec![object_value.clone()];
+    for argument in arguments {
+        let value = build_expression(
+            argument,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            None,
+        )?
+        .unwrap();
+        args.push(value);
+    }
+
+    let class = get_class(&object.get_data_type(), symbol_table);
+    let (index, _, class_method) = class.methods.get_full(method).unwrap();
+    let method_data_type = get_method_data_type(class_method, &class.get_data_type());
+
+    let function = build_vtable_load(
+        expect_pointer(object_value),
+        index,
+        context,
+        builder,
+        function_value,
+    );
+
+    let fn_type = convert_function_data_type_to_llvm_function_type(&method_data_type, context);
+    let function = builder.build_bitcast(
+        function,
+        fn_type.ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+
+    return Ok(build_call_or_invoke(
+        Either::Right(&function),
+        &args,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        name,
+    ));
+}
+
+/*
+ * Whether the value is an instance of the class or of one of its subclasses
+ *
+ * Data type of the value decides it unless the value is an instance of an
+ * ancestor of the class or an `object`, then the vtable of the instance and the
+ * vtables of its ancestors are compared with the vtable of the class. A union
+ * checks the member it holds
+ *
+ * */
+pub(crate) fn build_instanceof<'a>(
+    value: BasicValueEnum<'a>,
+    data_type: &DataType,
+    class: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    let (class_name, class_parents) = match class {
+        DataType::ClassType { name, parents } => (name, parents),
+        _ => unreachable!(),
+    };
+
+    match data_type {
+        DataType::ClassType { name, parents }
+            if name == class_name || parents.contains(class_name) =>
+        {
+            return context.i1_type().const_int(1, false);
+        }
+
+        DataType::ClassType { name, parents: _ } => {
+            if !class_parents.contains(name) {
+                return context.i1_type().const_int(0, false);
+            }
+
+            let byte_pointer_type = get_byte_pointer_type(context);
+            let vtable = build_load_vtable(expect_pointer(value), context, builder, function_value);
+            let vtable = builder.build_bitcast(
+                vtable,
+                byte_pointer_type.clone(),
+                &function_value.get_unique_reg_name(),
+            );
+            let class_vtable = get_vtable(get_class(class, symbol_table), context, module)
+                .as_pointer_value()
+                .const_bitcast(byte_pointer_type);
+
+            return build_runtime_call_returning_int(
+                IS_INSTANCE,
+                &[
+                    vtable.as_basic_value_enum(),
+                    class_vtable.as_basic_value_enum(),
+                ],
+                context,
+                builder,
+                module,
+                name,
+            );
+        }
+
+        // Record of anything but an instance holds null, see build_any_object
+        DataType::Object => {
+            let reg_name = function_value.get_unique_reg_name();
+            let vtable =
+                build_any_object_vtable(expect_pointer(value), context, builder, &reg_name);
+            let class_vtable = get_vtable(get_class(class, symbol_table), context, module)
+                .as_pointer_value()
+                .const_bitcast(get_byte_pointer_type(context));
+
+            return build_runtime_call_returning_int(
+                IS_INSTANCE,
+                &[
+                    vtable.as_basic_value_enum(),
+                    class_vtable.as_basic_value_enum(),
+                ],
+                context,
+                builder,
+                module,
+                name,
+            );
+        }
+
+        DataType::UnionType { types } => {
+            let is_instance = build_union_dispatch(
+                expect_pointer(value),
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |payload, member, function_value| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    return build_instanceof(
+                        payload,
+                        member,
+                        class,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        &reg_name,
+                    )
+                    .as_basic_value_enum();
+                },
+            );
+            return expect_int(is_instance);
+        }
+
+        _ => return context.i1_type().const_int(0, false),
+    }
+}
@@ -0,0 +1,712 @@
+use ast::{data_type::DataType, declaration::Declaration, Ast};
+use either::Either;
+use indexmap::IndexMap;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::Linkage,
+    module::Module,
+    types::{
+        enums::{AddressSpace, BasicTypeEnum},
+        fn_type::FunctionType,
+        ptr_type::PointerType,
+        struct_type::StructType,
+        traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, global_value::GlobalValue,
+        ptr_value::PointerValue,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_array::holds_pointers,
+    build_conversion::build_structural_conversion,
+    build_exception::build_call_or_invoke,
+    gc_frame::GcFrame,
+    gen_ast::consume_function_declaration::consume_function_declaration,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        expect_pointer,
+        gc::{build_gc_alloc, build_gc_alloc_slots, build_static_gc_object},
+        get_byte_pointer_type,
+    },
+    symbol_table::SymbolTable,
+};
+
+/*
+ * Function value is a pointer to a closure, an object of the garbage collector
+ * holding the function along with its environment
+ *
+ *      { i8* function, i8* environment }
+ *
+ * function takes the environment as its first parameter followed by the
+ * parameters of the function type. Environment is an array of pointers to the
+ * storage of the variables captured by the function, in the order of its
+ * captures, or null when it captures nothing
+ *
+ * Storage of a captured array or object is the array or object itself, any other
+ * captured variable lives in a cell so the closure and the function declaring the
+ * variable share its value
+ *
+ * */
+const CLOSURE_FUNCTION_FIELD: u64 = 0;
+const CLOSURE_ENVIRONMENT_FIELD: u64 = 1;
+
+// Environment is the only field pointing to memory of the garbage collector
+const CLOSURE_SIZE: u64 = 16;
+const CLOSURE_ENVIRONMENT_OFFSET: u64 = 8;
+
+fn get_closure_type<'a>(context: &'a Context) -> StructType<'a> {
+    let field_types = vec![
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        get_byte_pointer_type(context).as_basic_type_enum(),
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+pub(crate) fn get_closure_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return get_closure_type(context).ptr_type(AddressSpace::Generic);
+}
+
+// Type of the function stored in the closure, it takes the environment first
+fn get_closure_function_type<'a>(data_type: &DataType, context: &'a Context) -> FunctionType<'a> {
+    if let DataType::FunctionType {
+        arguments,
+        return_type,
+    } = data_type
+    {
+        let mut param_types = vec![get_byte_pointer_type(context).as_basic_type_enum()];
+        for argument in arguments {
+            param_types.push(argument.force_to_basic_type(context));
+        }
+
+        return return_type
+            .force_to_basic_type(context)
+            .fn_type(&param_types, false);
+    } else {
+        panic!(
+            "Expected data type to be DataType::FunctionType but got {:?}",
+            data_type
+        );
+    }
+}
+
+// Pointer to the storage of a captured variable, see build_cell
+fn get_storage_pointer_type<'a>(data_type: &DataType, context: &'a Context) -> PointerType<'a> {
+    let value_type = data_type.force_to_basic_type(context);
+
+    match (data_type, value_type) {
+        (
+            DataType::ArrayType { .. } | DataType::ObjectType { .. },
+            BasicTypeEnum::PointerType(pointer_type),
+        ) => return pointer_type,
+        (_, value_type) => return value_type.ptr_type(AddressSpace::Generic),
+    }
+}
+
+/*
+ * Allocates the cell holding a captured variable, returns pointer to the value
+ * inside of it. The cell is rooted till the function returns, closures created
+ * after that keep it alive through their environment
+ *
+ * */
+pub(crate) fn build_cell<'a>(
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let pointer_offsets: &[u64] = if holds_pointers(data_type) { &[0] } else { &[] };
+
+    let cell = build_gc_alloc(
+        context.i64_type().const_int(8, false),
+        pointer_offsets,
+        context,
+        builder,
+        module,
+        &format!("{}_cell", name),
+    );
+    symbol_table
+        .get_gc_frame()
+        .build_root(cell, context, builder, name);
+
+    return builder.build_bitcast(cell, get_storage_pointer_type(data_type, context), name);
+}
+
+/*
+ * Arrays and objects are never assigned again, so they are shared with the closure
+ * as they are
+ *
+ * */
+pub(crate) fn is_stored_in_cell(data_type: &DataType) -> bool {
+    return !matches!(
+        data_type,
+        DataType::ArrayType { .. } | DataType::ObjectType { .. }
+    );
+}
+
+/*
+ * Expression::Closure holds the FunctionDeclaration of its body, the function is
+ * generated the first time the closure is created
+ *
+ * */
+pub(crate) fn build_closure_expression<'a>(
+    function: &Ast,
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> Result<PointerValue<'a>, Diagnostic> {
+    if let Ast::Declaration(Declaration::FunctionDeclaration {
+        arguments,
+        blocks,
+        ident_name,
+        return_type,
+        captures,
+        span: _,
+    }) = function
+    {
+        let closure_function = match module.get_function(ident_name) {
+            Some(closure_function) => closure_function,
+            None => consume_function_declaration(
+                arguments,
+                blocks,
+                ident_name,
+                return_type,
+                captures,
+                true,
+                context,
+                module,
+                symbol_table,
+            )?,
+        };
+
+        return Ok(build_closure(
+            closure_function,
+            captures,
+            context,
+            builder,
+            symbol_table,
+            module,
+            name,
+        ));
+    } else {
+        panic!(
+            "Expected closure to hold a function declaration but got {:?}",
+            function
+        );
+    }
+}
+
+/*
+ * Creates the closure of the function, its environment is filled with the storage
+ * of the captured variables found in the symbol table of the function being
+ * generated
+ *
+ * */
+fn build_closure<'a>(
+    closure_function: FunctionValue<'a>,
+    captures: &IndexMap<String, DataType>,
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let i64_type = context.i64_type();
+    let byte_pointer_type = get_byte_pointer_type(context);
+
+    let environment = {
+        if captures.is_empty() {
+            byte_pointer_type.const_null()
+        } else {
+            let environment = build_gc_alloc_slots(
+                i64_type.const_int(captures.len() as u64, false),
+                context.i1_type().const_int(1, false),
+                context,
+                builder,
+                module,
+                &format!("{}_environment", name),
+            );
+            let slots = builder.build_bitcast(
+                environment,
+                byte_pointer_type.ptr_type(AddressSpace::Generic),
+                &format!("{}_environment_slots", name),
+            );
+
+            for (i, capture_name) in captures.keys().enumerate() {
+                let storage = *symbol_table.get(capture_name).unwrap();
+                let storage = builder.build_bitcast(
+                    storage,
+                    byte_pointer_type.clone(),
+                    &format!("{}_storage", capture_name),
+                );
+
+                let slot = builder.build_gep_2(
+                    byte_pointer_type.clone(),
+                    &slots,
+                    &[i64_type.const_int(i as u64, false)],
+                    &format!("{}_slot", capture_name),
+                );
+                builder.build_store(slot, storage);
+            }
+
+            environment
+        }
+    };
+
+    // Allocating does not collect, so the environment does not have to be rooted
+    return build_closure_object(
+        closure_function.to_pointer_value(),
+        environment,
+        context,
+        builder,
+        module,
+        name,
+    );
+}
+
+// Closure of the function, which takes the environment as its first argument
+pub(crate) fn build_closure_object<'a>(
+    closure_function: PointerValue<'a>,
+    environment: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let raw_closure = build_gc_alloc(
+        context.i64_type().const_int(CLOSURE_SIZE, false),
+        &[CLOSURE_ENVIRONMENT_OFFSET],
+        context,
+        builder,
+        module,
+        name,
+    );
+    let closure = builder.build_bitcast(raw_closure, get_closure_pointer_type(context), name);
+
+    let function_pointer = builder.build_bitcast(
+        closure_function,
+        get_byte_pointer_type(context),
+        &format!("{}_function", name),
+    );
+    builder.build_store(
+        build_closure_field_pointer(closure, CLOSURE_FUNCTION_FIELD, context, builder, name),
+        function_pointer,
+    );
+    builder.build_store(
+        build_closure_field_pointer(closure, CLOSURE_ENVIRONMENT_FIELD, context, builder, name),
+        environment,
+    );
+
+    return closure;
+}
+
+/*
+ * Closure of a function value converted to a function type whose parameters or
+ * return value have another layout, see build_structural_conversion. Its
+ * environment is the closure being converted
+ *
+ * */
+pub(crate) fn build_thunk_closure<'a>(
+    closure: PointerValue<'a>,
+    from_data_type: &DataType,
+    to_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &SymbolTable,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let thunk = get_thunk(from_data_type, to_data_type, context, symbol_table, module);
+
+    let environment = builder.build_bitcast(
+        closure,
+        get_byte_pointer_type(context),
+        &format!("{}_environment", name),
+    );
+    return build_closure_object(
+        thunk.to_pointer_value(),
+        environment,
+        context,
+        builder,
+        module,
+        name,
+    );
+}
+
+/*
+ * Function of the closure built by build_thunk_closure, it takes the arguments of
+ * `to_data_type` and calls the closure in its environment
+ *
+ *      <closure> = <environment> as closure
+ *      <argument_i> = convert <parameter_i> to the argument of <from_data_type>
+ *      <value> = call <closure>(<argument_0>, <argument_1>, ...)
+ *      ret convert <value> to the return type of <to_data_type>
+ *
+ * Converted arguments are rooted in its own frame, since the function being
+ * called expects the frame of the caller to keep them alive
+ *
+ * */
+fn get_thunk<'a>(
+    from_data_type: &DataType,
+    to_data_type: &DataType,
+    context: &'a Context,
+    symbol_table: &SymbolTable,
+    module: &'a Module,
+) -> FunctionValue<'a> {
+    let thunk_name = format!("|thunk|{:?}|{:?}", from_data_type, to_data_type).replace('"', "");
+    if let Some(thunk) = module.get_function(&thunk_name) {
+        return thunk;
+    }
+
+    let (arguments, return_type, to_arguments, to_return_type) =
+        match (from_data_type, to_data_type) {
+            (
+                DataType::FunctionType {
+                    return_type,
+                    arguments,
+                },
+                DataType::FunctionType {
+                    return_type: to_return_type,
+                    arguments: to_arguments,
+                },
+            ) => (arguments, return_type, to_arguments, to_return_type),
+            _ => unreachable!(),
+        };
+
+    let mut thunk = module.add_function(
+        &thunk_name,
+        get_closure_function_type(to_data_type, context),
+        Some(Linkage::Private),
+    );
+    thunk.set_reg_counter(thunk.count_params() as usize);
+
+    let classes = symbol_table.classes.clone();
+    let mut symbol_table = SymbolTable::new();
+    symbol_table.classes = classes;
+    symbol_table.gc_frame = Some(GcFrame::new(context, &thunk, module));
+    let body_block = context.append_basic_block(&thunk, "main");
+    let builder = context.create_builder();
+    builder.position_at_end(&body_block);
+
+    let closure = builder.build_bitcast(
+        expect_pointer(thunk.get_nth_param(0).unwrap()),
+        get_closure_pointer_type(context),
+        "closure",
+    );
+
+    let mut values: Vec<BasicValueEnum> = vec![];
+    for (i, (argument, to_argument)) in arguments.iter().zip(to_arguments.iter()).enumerate() {
+        let parameter = thunk.get_nth_param(i as u32 + 1).unwrap();
+
+        let reg_name = thunk.get_unique_reg_name();
+        let value = build_structural_conversion(
+            parameter,
+            to_argument,
+            argument,
+            context,
+            &builder,
+            &mut thunk,
+            &symbol_table,
+            module,
+            &reg_name,
+        );
+
+        if let BasicValueEnum::PointerValue(pointer) = value {
+            symbol_table
+                .get_gc_frame()
+                .build_root(pointer, context, &builder, "argument");
+        }
+        values.push(value);
+    }
+
+    let reg_name = thunk.get_unique_reg_name();
+    let value = build_closure_call(
+        closure,
+        from_data_type,
+        values,
+        context,
+        &builder,
+        &mut thunk,
+        &symbol_table,
+        &reg_name,
+    );
+
+    symbol_table
+        .get_gc_frame()
+        .build_end(context, &builder, module);
+
+    match value {
+        Some(value) if to_return_type.as_ref() != &DataType::Void => {
+            let reg_name = thunk.get_unique_reg_name();
+            let value = build_structural_conversion(
+                value,
+                return_type,
+                to_return_type,
+                context,
+                &builder,
+                &mut thunk,
+                &symbol_table,
+                module,
+                &reg_name,
+            );
+            builder.build_return(Some(&value));
+        }
+        _ => {
+            builder.build_return(None);
+        }
+    }
+
+    symbol_table
+        .get_gc_frame()
+        .build_reserve(&body_block, context, module);
+
+    return thunk;
+}
+
+/*
+ * Function declared at the top of the file or imported takes no environment, its
+ * closure holds an adapter which drops the environment and calls the function.
+ * Every use of the function shares one static closure, so the function is always
+ * equal to itself
+ *
+ * */
+pub(crate) fn build_function_closure<'a>(
+    fn_name: &str,
+    fn_pointer: PointerValue<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let adapter = get_adapter(fn_name, fn_pointer, data_type, context, module);
+
+    let raw_closure = build_static_gc_object(
+        &format!("|static_closure|{}", fn_name),
+        CLOSURE_SIZE,
+        context,
+        builder,
+        module,
+        name,
+    );
+    let closure = builder.build_bitcast(raw_closure, get_closure_pointer_type(context), name);
+
+    let function_pointer = builder.build_bitcast(
+        adapter.to_pointer_value(),
+        get_byte_pointer_type(context),
+        &format!("{}_function", name),
+    );
+    builder.build_store(
+        build_closure_field_pointer(closure, CLOSURE_FUNCTION_FIELD, context, builder, name),
+        function_pointer,
+    );
+
+    return closure;
+}
+
+fn get_adapter<'a>(
+    fn_name: &str,
+    fn_pointer: PointerValue<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    module: &'a Module,
+) -> FunctionValue<'a> {
+    let adapter_name = format!("|adapter|{}", fn_name);
+    if let Some(adapter) = module.get_function(&adapter_name) {
+        return adapter;
+    }
+
+    let adapter = module.add_function(
+        &adapter_name,
+        get_closure_function_type(data_type, context),
+        Some(Linkage::Private),
+    );
+    let entry = context.append_basic_block(&adapter, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let arguments: Vec<BasicValueEnum> = (1..adapter.count_params())
+        .map(|i| adapter.get_nth_param(i).unwrap())
+        .collect();
+    let value = builder.build_call2(Either::Right(&fn_pointer), &arguments, "value");
+
+    if value.is_void() {
+        builder.build_return(None);
+    } else {
+        builder.build_return(Some(&value.to_basic_value_enum().unwrap()));
+    }
+
+    return adapter;
+}
+
+/*
+ * Calls the function of the closure with its environment followed by the
+ * arguments, returns None if the function returns void
+ *
+ * */
+pub(crate) fn build_closure_call<'a>(
+    closure: PointerValue<'a>,
+    data_type: &DataType,
+    arguments: Vec<BasicValueEnum<'a>>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    name: &str,
+) -> Option<BasicValueEnum<'a>> {
+    let byte_pointer_type = get_byte_pointer_type(context).as_basic_type_enum();
+
+    let function_pointer = builder.build_load(
+        build_closure_field_pointer(closure, CLOSURE_FUNCTION_FIELD, context, builder, name),
+        byte_pointer_type.clone(),
+        &function_value.get_unique_reg_name(),
+    );
+    let function_pointer = builder.build_bitcast(
+        expect_pointer(function_pointer),
+        get_closure_function_type(data_type, context).ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let environment = builder.build_load(
+        build_closure_field_pointer(closure, CLOSURE_ENVIRONMENT_FIELD, context, builder, name),
+        byte_pointer_type,
+        &function_value.get_unique_reg_name(),
+    );
+
+    let mut args = vec![environment];
+    args.extend(arguments);
+
+    return build_call_or_invoke(
+        Either::Right(&function_pointer),
+        &args,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        name,
+    );
+}
+
+fn build_closure_field_pointer<'a>(
+    closure: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &'a Builder,
+    name: &str,
+) -> PointerValue<'a> {
+    let indices = [
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(
+        get_closure_type(context),
+        &closure,
+        &indices,
+        &format!("{}_field_{}", name, field),
+    );
+}
+
+/*
+ * Function declared at the top of the file is called without an environment, the
+ * storage of the variables of main it captures is kept in globals which are set
+ * where the function is declared
+ *
+ * */
+fn get_capture_global<'a>(
+    capture_name: &str,
+    context: &'a Context,
+    module: &'a Module,
+) -> GlobalValue<'a> {
+    let global_name = format!("|capture|{}", capture_name);
+
+    match module.get_global(&global_name) {
+        Some(global) => return global,
+        None => {
+            let byte_pointer_type = get_byte_pointer_type(context);
+
+            let global = module.add_global(byte_pointer_type.clone(), &global_name);
+            global.set_initializer(&byte_pointer_type.const_null());
+            global.set_linkage(&Linkage::Private);
+            return global;
+        }
+    }
+}
+
+pub(crate) fn build_store_captures_in_globals<'a>(
+    captures: &IndexMap<String, DataType>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+) {
+    for capture_name in captures.keys() {
+        let storage = *symbol_table.get(capture_name).unwrap();
+        let storage = builder.build_bitcast(
+            storage,
+            get_byte_pointer_type(context),
+            &format!("{}_storage", capture_name),
+        );
+
+        let global = get_capture_global(capture_name, context, module);
+        builder.build_store(global.as_pointer_value(), storage);
+    }
+}
+
+/*
+ * Inserts the storage of the captured variables into the symbol table of the
+ * function being generated, it is read from the environment of a closure and
+ * from the globals otherwise
+ *
+ * */
+pub(crate) fn build_load_captures<'a>(
+    captures: &IndexMap<String, DataType>,
+    environment: Option<PointerValue<'a>>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let byte_pointer_type = get_byte_pointer_type(context);
+
+    for (i, (capture_name, data_type)) in captures.iter().enumerate() {
+        let storage_pointer = match environment {
+            Some(environment) => {
+                let slots = builder.build_bitcast(
+                    environment,
+                    byte_pointer_type.ptr_type(AddressSpace::Generic),
+                    "environment_slots",
+                );
+
+                builder.build_gep_2(
+                    byte_pointer_type.clone(),
+                    &slots,
+                    &[context.i64_type().const_int(i as u64, false)],
+                    &format!("{}_slot", capture_name),
+                )
+            }
+            None => get_capture_global(capture_name, context, module).as_pointer_value(),
+        };
+
+        let storage = builder.build_load(
+            storage_pointer,
+            byte_pointer_type.as_basic_type_enum(),
+            &format!("{}_storage", capture_name),
+        );
+        let storage = builder.build_bitcast(
+            expect_pointer(storage),
+            get_storage_pointer_type(data_type, context),
+            capture_name,
+        );
+
+        symbol_table.insert_local(capture_name.to_string(), storage);
+    }
+}
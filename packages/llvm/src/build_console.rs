@@ -0,0 +1,691 @@
+use std::convert::TryInto;
+
+use ast::{
+    data_type::{get_source_name, DataType},
+    expression::{ConsoleMethod, Expression},
+};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage},
+    module::Module,
+    types::{enums::AddressSpace, traits::BasicTypeTrait},
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, ptr_value::PointerValue,
+        traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_any_object::{
+        build_any_object_inspect, get_inspect_function_type, get_value_pointer_type,
+    },
+    build_class::{build_field_pointer, get_class},
+    build_expression::build_expression,
+    build_string::{build_string_concat, build_string_literal, build_to_string},
+    build_union::build_union_dispatch,
+    gc_frame::GcFrame,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        array::{build_array_slot, build_load_array_length},
+        build_runtime_call, expect_int, expect_pointer,
+        string::get_string_pointer_type,
+        CONSOLE_WRITE, STDERR_FD, STDOUT_FD,
+    },
+    symbol_table::SymbolTable,
+};
+
+// Same as the default depth of node's util.inspect
+const MAX_INSPECT_DEPTH: usize = 2;
+
+/*
+ * Part of the formatted output, adjacent texts are merged before creating runtime
+ * strings so `{ a: 1 }` needs only the strings "{ a: ", "1" and " }"
+ *
+ * */
+enum Piece<'a> {
+    Text(String),
+    Value(PointerValue<'a>),
+}
+
+/*
+ * Formats every argument like node does and writes them separated by a space
+ * followed by a new line, console.log writes to stdout and console.error to stderr
+ *
+ * Unlike node the output is always on a single line
+ *
+ * */
+pub(crate) fn build_console_call<'a>(
+    method: &ConsoleMethod,
+    arguments: &[Expression],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    /*
+     * Every argument is evaluated before any string is created, so the pieces do
+     * not have to be rooted while an argument calls a function
+     *
+     * */
+    let values: Vec<Option<BasicValueEnum>> = arguments
+        .iter()
+        .map(|argument| {
+            return build_expression(
+                argument,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            );
+        })
+        .collect::<Result<_, Diagnostic>>()?;
+
+    let mut pieces: Vec<Piece> = vec![];
+
+    for (i, (argument, value)) in arguments.iter().zip(values).enumerate() {
+        if i != 0 {
+            pieces.push(Piece::Text(" ".to_string()));
+        }
+
+        // Narrowed variable is wrapped in a conversion
+        let variable = match argument {
+            Expression::UnionConversion {
+                exp,
+                data_type: _,
+                span: _,
+            } => exp.as_ref(),
+            argument => argument,
+        };
+
+        let fn_name = match variable {
+            Expression::IdentExp {
+                name,
+                data_type: _,
+                span: _,
+            } => Some(get_source_name(name)),
+            _ => None,
+        };
+
+        build_inspect(
+            value,
+            &argument.get_data_type(),
+            0,
+            fn_name,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            &mut pieces,
+        );
+    }
+
+    let output = build_pieces(pieces, context, builder, function_value, module);
+
+    let fd = match method {
+        ConsoleMethod::Log => STDOUT_FD,
+        ConsoleMethod::Error => STDERR_FD,
+    };
+
+    build_runtime_call(
+        CONSOLE_WRITE,
+        &[
+            context
+                .i32_type()
+                .const_int(fd, false)
+                .as_basic_value_enum(),
+            output.as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        "",
+    );
+
+    return Ok(());
+}
+
+/*
+ * Strings are written as is at the top level and quoted when nested inside
+ * arrays or objects, objects nested deeper than MAX_INSPECT_DEPTH are shown as
+ * [Object] and instances of a class as [<class name>]
+ *
+ * */
+fn build_inspect<'a>(
+    value: Option<BasicValueEnum<'a>>,
+    data_type: &DataType,
+    depth: usize,
+    fn_name: Option<&str>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    pieces: &mut Vec<Piece<'a>>,
+) {
+    let value = match (data_type, value) {
+        (DataType::Void | DataType::Undefined, _) | (_, None) => {
+            pieces.push(Piece::Text("undefined".to_string()));
+            return;
+        }
+        (DataType::Null, _) => {
+            pieces.push(Piece::Text("null".to_string()));
+            return;
+        }
+        (_, Some(value)) => value,
+    };
+
+    match data_type {
+        DataType::String if depth == 0 => {
+            pieces.push(Piece::Value(expect_pointer(value)));
+        }
+
+        DataType::String => {
+            pieces.push(Piece::Text("'".to_string()));
+            pieces.push(Piece::Value(expect_pointer(value)));
+            pieces.push(Piece::Text("'".to_string()));
+        }
+
+        DataType::Float | DataType::Boolean => {
+            pieces.push(Piece::Value(build_to_string(
+                value,
+                data_type,
+                context,
+                builder,
+                function_value,
+                module,
+            )));
+        }
+
+        DataType::ArrayType { base_type } => {
+            if depth > MAX_INSPECT_DEPTH {
+                pieces.push(Piece::Text("[Array]".to_string()));
+                return;
+            }
+
+            // Type of elements is unknown only for `[]`
+            if **base_type == DataType::Unknown {
+                pieces.push(Piece::Text("[]".to_string()));
+                return;
+            }
+
+            pieces.push(Piece::Value(build_array_inspect(
+                expect_pointer(value),
+                base_type,
+                depth,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )));
+        }
+
+        DataType::ObjectType { entries } => {
+            if depth > MAX_INSPECT_DEPTH {
+                pieces.push(Piece::Text("[Object]".to_string()));
+                return;
+            }
+
+            if entries.is_empty() {
+                pieces.push(Piece::Text("{}".to_string()));
+                return;
+            }
+
+            let pointer = expect_pointer(value);
+            let struct_type = pointer.get_type().into_struct_type().unwrap();
+
+            pieces.push(Piece::Text("{ ".to_string()));
+            for (i, (key, field_data_type)) in entries.iter().enumerate() {
+                if i != 0 {
+                    pieces.push(Piece::Text(", ".to_string()));
+                }
+                pieces.push(Piece::Text(format!("{}: ", key)));
+
+                let indices = vec![
+                    context.i32_type().const_int(0, true),
+                    context.i32_type().const_int(i.try_into().unwrap(), true),
+                ];
+                let field_pointer = builder.build_gep_2(
+                    struct_type,
+                    &pointer,
+                    &indices,
+                    &function_value.get_unique_reg_name(),
+                );
+                let field = builder.build_load(
+                    field_pointer,
+                    struct_type.get_field_type(i),
+                    &function_value.get_unique_reg_name(),
+                );
+
+                build_inspect(
+                    Some(field),
+                    field_data_type,
+                    depth + 1,
+                    None,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                    pieces,
+                );
+            }
+            pieces.push(Piece::Text(" }".to_string()));
+        }
+
+        // Shown like node, the name of the class followed by its fields
+        DataType::ClassType { .. } => {
+            let class = get_class(data_type, symbol_table).clone();
+
+            if depth > MAX_INSPECT_DEPTH {
+                pieces.push(Piece::Text(format!("[{}]", class.name)));
+                return;
+            }
+
+            if class.fields.is_empty() {
+                pieces.push(Piece::Text(format!("{} {{}}", class.name)));
+                return;
+            }
+
+            pieces.push(Piece::Text(format!("{} {{ ", class.name)));
+            for (i, (name, field)) in class.fields.iter().enumerate() {
+                if i != 0 {
+                    pieces.push(Piece::Text(", ".to_string()));
+                }
+                pieces.push(Piece::Text(format!("{}: ", name)));
+
+                let (field_pointer, field_type) = build_field_pointer(
+                    value.clone(),
+                    data_type,
+                    name,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                );
+                let field_value = builder.build_load(
+                    field_pointer,
+                    field_type,
+                    &function_value.get_unique_reg_name(),
+                );
+
+                build_inspect(
+                    Some(field_value),
+                    &field.data_type,
+                    depth + 1,
+                    None,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                    pieces,
+                );
+            }
+            pieces.push(Piece::Text(" }".to_string()));
+        }
+
+        DataType::FunctionType {
+            arguments: _,
+            return_type: _,
+        } => match fn_name {
+            Some(name) => pieces.push(Piece::Text(format!("[Function: {}]", name))),
+            None => pieces.push(Piece::Text("[Function (anonymous)]".to_string())),
+        },
+
+        /*
+         * Data type is only known at runtime, so the value is inspected by the
+         * function the record holds. It counts the depth from the value itself,
+         * which never is a string
+         *
+         * */
+        DataType::Object => {
+            if depth > MAX_INSPECT_DEPTH {
+                pieces.push(Piece::Text("[Object]".to_string()));
+                return;
+            }
+
+            let reg_name = function_value.get_unique_reg_name();
+            pieces.push(Piece::Value(build_any_object_inspect(
+                expect_pointer(value),
+                context,
+                builder,
+                &reg_name,
+            )));
+        }
+
+        // Member is only known at runtime, so it is inspected into a string of its own
+        DataType::UnionType { types } => {
+            let reg_name = function_value.get_unique_reg_name();
+            let string = build_union_dispatch(
+                expect_pointer(value),
+                types,
+                context,
+                builder,
+                function_value,
+                &reg_name,
+                |value, member, function_value| {
+                    let mut member_pieces: Vec<Piece> = vec![];
+                    build_inspect(
+                        Some(value),
+                        member,
+                        depth,
+                        fn_name,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        &mut member_pieces,
+                    );
+
+                    let string =
+                        build_pieces(member_pieces, context, builder, function_value, module);
+                    return string.as_basic_value_enum();
+                },
+            );
+
+            pieces.push(Piece::Value(expect_pointer(string)));
+        }
+
+        _ => panic!(
+            "console cannot print value of data type {:?} yet",
+            data_type
+        ),
+    }
+}
+
+/*
+ * Function which inspects a value of the data type passed as i8*, it is held by
+ * every `object` created from the data type, see build_any_object
+ *
+ *      <value> = <parameter> as <data_type>
+ *      ret build_inspect(<value>)
+ *
+ * Inspecting never collects, so the strings are not rooted
+ *
+ * */
+pub(crate) fn get_inspect_function<'a>(
+    data_type: &DataType,
+    context: &'a Context,
+    symbol_table: &SymbolTable,
+    module: &'a Module,
+) -> FunctionValue<'a> {
+    let inspect_name = format!("|inspect|{:?}", data_type).replace('"', "");
+    if let Some(inspect) = module.get_function(&inspect_name) {
+        return inspect;
+    }
+
+    let mut inspect = module.add_function(
+        &inspect_name,
+        get_inspect_function_type(context),
+        Some(Linkage::Private),
+    );
+    inspect.set_reg_counter(inspect.count_params() as usize);
+
+    let classes = symbol_table.classes.clone();
+    let mut symbol_table = SymbolTable::new();
+    symbol_table.classes = classes;
+    symbol_table.gc_frame = Some(GcFrame::new(context, &inspect, module));
+    let body_block = context.append_basic_block(&inspect, "main");
+    let builder = context.create_builder();
+    builder.position_at_end(&body_block);
+
+    let value = builder.build_bitcast(
+        expect_pointer(inspect.get_nth_param(0).unwrap()),
+        get_value_pointer_type(data_type, context),
+        "value",
+    );
+
+    let mut pieces: Vec<Piece> = vec![];
+    build_inspect(
+        Some(value.as_basic_value_enum()),
+        data_type,
+        0,
+        None,
+        context,
+        &builder,
+        &mut inspect,
+        &mut symbol_table,
+        module,
+        &mut pieces,
+    );
+    let text = build_pieces(pieces, context, &builder, &mut inspect, module);
+
+    symbol_table
+        .get_gc_frame()
+        .build_end(context, &builder, module);
+    builder.build_return(Some(&text));
+
+    symbol_table
+        .get_gc_frame()
+        .build_reserve(&body_block, context, module);
+
+    return inspect;
+}
+
+/*
+ * Length of an array is known only at runtime, so its elements are inspected in
+ * a loop which appends them to a string
+ *
+ *      "[ " + element_0 + ", " + element_1 + ... + " ]"
+ *
+ * Arguments are evaluated before the loop and creating strings does not collect,
+ * so none of the strings have to be rooted
+ *
+ * */
+fn build_array_inspect<'a>(
+    array: PointerValue<'a>,
+    base_type: &DataType,
+    depth: usize,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let i64_type = context.i64_type();
+    let string_pointer_type = get_string_pointer_type(context);
+
+    let literal = |text: &str, function_value: &mut FunctionValue| {
+        let reg_name = function_value.get_unique_reg_name();
+        return build_string_literal(text, context, builder, function_value, module, &reg_name);
+    };
+    let open = literal("[ ", function_value);
+    let separator = literal(", ", function_value);
+    let no_separator = literal("", function_value);
+    let close = literal(" ]", function_value);
+    let empty = literal("[]", function_value);
+
+    let reg_name = function_value.get_unique_reg_name();
+    let length = build_load_array_length(array, context, builder, &reg_name);
+
+    let gc_frame = symbol_table.get_gc_frame();
+    let index_pointer =
+        gc_frame.build_alloca(i64_type, context, &function_value.get_unique_reg_name());
+    let result_pointer = gc_frame.build_alloca(
+        string_pointer_type.clone(),
+        context,
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_store(index_pointer, i64_type.const_int(0, false));
+    builder.build_store(result_pointer, open);
+
+    let condition_block_name = function_value.get_unique_block_name();
+    let condition_block = context.append_basic_block(function_value, &condition_block_name);
+    let body_block_name = function_value.get_unique_block_name();
+    let body_block = context.append_basic_block(function_value, &body_block_name);
+    let exit_block_name = function_value.get_unique_block_name();
+    let exit_block = context.append_basic_block(function_value, &exit_block_name);
+
+    builder.build_unconditional_branch(&condition_block);
+    builder.position_at_end(&condition_block);
+    let index = expect_int(builder.build_load(
+        index_pointer,
+        i64_type.as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+    let is_in_range = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        index,
+        length,
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_conditional_branch(is_in_range, &body_block, &exit_block);
+
+    builder.position_at_end(&body_block);
+    let reg_name = function_value.get_unique_reg_name();
+    let slot = build_array_slot(array, index, context, builder, &reg_name);
+    let element_pointer = builder.build_bitcast(
+        slot,
+        base_type
+            .force_to_basic_type(context)
+            .ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+    let element = builder.build_load(
+        element_pointer,
+        base_type.force_to_basic_type(context),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let mut element_pieces: Vec<Piece> = vec![];
+    build_inspect(
+        Some(element),
+        base_type,
+        depth + 1,
+        None,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        &mut element_pieces,
+    );
+    let element_string = build_pieces(element_pieces, context, builder, function_value, module);
+
+    let is_first = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        index,
+        i64_type.const_int(0, false),
+        &function_value.get_unique_reg_name(),
+    );
+    let element_separator = expect_pointer(builder.build_select(
+        is_first,
+        no_separator.as_basic_value_enum(),
+        separator.as_basic_value_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+    let result = expect_pointer(builder.build_load(
+        result_pointer,
+        string_pointer_type.as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+    let reg_name = function_value.get_unique_reg_name();
+    let result = build_string_concat(
+        result,
+        element_separator,
+        context,
+        builder,
+        module,
+        &reg_name,
+    );
+    let reg_name = function_value.get_unique_reg_name();
+    let result = build_string_concat(result, element_string, context, builder, module, &reg_name);
+    builder.build_store(result_pointer, result);
+
+    let next_index = builder.build_int_add(
+        index,
+        i64_type.const_int(1, false),
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_store(index_pointer, next_index);
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&exit_block);
+    let result = expect_pointer(builder.build_load(
+        result_pointer,
+        string_pointer_type.as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+    let reg_name = function_value.get_unique_reg_name();
+    let result = build_string_concat(result, close, context, builder, module, &reg_name);
+
+    let is_empty = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        length,
+        i64_type.const_int(0, false),
+        &function_value.get_unique_reg_name(),
+    );
+    return expect_pointer(builder.build_select(
+        is_empty,
+        empty.as_basic_value_enum(),
+        result.as_basic_value_enum(),
+        &function_value.get_unique_reg_name(),
+    ));
+}
+
+/*
+ * Concatenates the pieces into a single runtime string
+ *
+ * */
+fn build_pieces<'a>(
+    pieces: Vec<Piece<'a>>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let mut strings: Vec<PointerValue> = vec![];
+    let mut text = String::new();
+
+    for piece in pieces {
+        match piece {
+            Piece::Text(value) => text.push_str(&value),
+            Piece::Value(string) => {
+                if !text.is_empty() {
+                    let reg_name = function_value.get_unique_reg_name();
+                    strings.push(build_string_literal(
+                        &text,
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
+                    ));
+                    text.clear();
+                }
+
+                strings.push(string);
+            }
+        }
+    }
+
+    if !text.is_empty() || strings.is_empty() {
+        let reg_name = function_value.get_unique_reg_name();
+        strings.push(build_string_literal(
+            &text,
+            context,
+            builder,
+            function_value,
+            module,
+            &reg_name,
+        ));
+    }
+
+    let mut strings = strings.into_iter();
+    let mut result = strings.next().unwrap();
+
+    for string in strings {
+        let reg_name = function_value.get_unique_reg_name();
+        result = build_string_concat(result, string, context, builder, module, &reg_name);
+    }
+
+    return result;
+}
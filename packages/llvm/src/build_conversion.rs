@@ -0,0 +1,303 @@
+use ast::data_type::DataType;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::Module,
+    values::{enums::BasicValueEnum, fn_value::FunctionValue, traits::BasicValueTrait},
+};
+
+use crate::{
+    build_any_object::build_any_object,
+    build_class::{build_method_closure, get_class},
+    build_closure::build_thunk_closure,
+    build_expression::{build_field_access, build_object},
+    build_union::{build_union_conversion, build_union_dispatch},
+    runtime::get_byte_pointer_type,
+    symbol_table::SymbolTable,
+};
+
+/*
+ * Converts the value into the layout of the data type it is assigned to, see
+ * Expression::StructuralConversion
+ *
+ * Arrays and objects are never copied except a new object literal, so the value is
+ * returned as is when both layouts are the same. The parser makes sure any other
+ * pair of data types is one of the cases below
+ *
+ * */
+pub(crate) fn build_structural_conversion<'a>(
+    value: BasicValueEnum<'a>,
+    from_data_type: &DataType,
+    to_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    if from_data_type.has_same_layout(to_data_type) {
+        return value;
+    }
+
+    match (from_data_type, to_data_type, value) {
+        (DataType::UnionType { types }, to_data_type, BasicValueEnum::PointerValue(union)) => {
+            return build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |payload, member, function_value| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    return build_structural_conversion(
+                        payload,
+                        member,
+                        to_data_type,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        &reg_name,
+                    );
+                },
+            );
+        }
+
+        (from_data_type, DataType::UnionType { types }, value) => {
+            let member = from_data_type.find_assignable_member(types).unwrap();
+
+            let reg_name = function_value.get_unique_reg_name();
+            let value = build_structural_conversion(
+                value,
+                from_data_type,
+                member,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                &reg_name,
+            );
+
+            return build_union_conversion(
+                value,
+                member,
+                to_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        /*
+         * Only a new object literal with more properties than the data type is
+         * copied, see convert_object_literal_with_more_keys of the parser. Property
+         * missing from the object is optional in the data type it is converted to,
+         * so it is undefined there
+         *
+         * */
+        (
+            DataType::ObjectType { entries },
+            DataType::ObjectType {
+                entries: to_entries,
+            },
+            value,
+        ) => {
+            let values: Vec<BasicValueEnum> = to_entries
+                .iter()
+                .map(|(key, to_entry)| {
+                    let (field, entry) = match entries.get(key) {
+                        Some(entry) => {
+                            let reg_name = function_value.get_unique_reg_name();
+                            let field = build_field_access(
+                                value.clone(),
+                                from_data_type,
+                                key,
+                                context,
+                                builder,
+                                function_value,
+                                &reg_name,
+                            );
+                            (field, entry)
+                        }
+                        None => (
+                            BasicValueEnum::PointerValue(
+                                get_byte_pointer_type(context).const_null(),
+                            ),
+                            &DataType::Undefined,
+                        ),
+                    };
+
+                    let reg_name = function_value.get_unique_reg_name();
+                    return build_structural_conversion(
+                        field,
+                        entry,
+                        to_entry,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        &reg_name,
+                    );
+                })
+                .collect();
+
+            let object = build_object(
+                values,
+                to_entries,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return object.as_basic_value_enum();
+        }
+
+        /*
+         * Closures of every function type have the same layout, the function is
+         * only called with more arguments than it takes or its return value is
+         * ignored, which the calling convention allows
+         *
+         * Otherwise the closure is wrapped in a thunk which converts the arguments
+         * and the return value
+         *
+         * */
+        (
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            },
+            DataType::FunctionType {
+                return_type: to_return_type,
+                arguments: to_arguments,
+            },
+            BasicValueEnum::PointerValue(closure),
+        ) => {
+            let has_same_arguments = arguments
+                .iter()
+                .zip(to_arguments.iter())
+                .all(|(argument, to_argument)| argument.has_same_layout(to_argument));
+            let has_same_return_type = to_return_type.as_ref() == &DataType::Void
+                || return_type.has_same_layout(to_return_type);
+
+            if has_same_arguments && has_same_return_type {
+                return closure.as_basic_value_enum();
+            }
+
+            let thunk = build_thunk_closure(
+                closure,
+                from_data_type,
+                to_data_type,
+                context,
+                builder,
+                symbol_table,
+                module,
+                name,
+            );
+            return thunk.as_basic_value_enum();
+        }
+
+        (
+            DataType::ArrayType { .. } | DataType::ObjectType { .. } | DataType::ClassType { .. },
+            DataType::Object,
+            value,
+        ) => {
+            let record = build_any_object(
+                value,
+                from_data_type,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            );
+            return record.as_basic_value_enum();
+        }
+
+        // Instance of a subclass starts with the vtable and the fields of its parent
+        (DataType::ClassType { .. }, DataType::ClassType { .. }, value) => {
+            return value;
+        }
+
+        /*
+         * Instance of a class becomes an object of its methods bound to the
+         * instance, the parser allows no fields since they would be copied. Property
+         * missing from the class is optional in the data type, so it is undefined
+         *
+         * */
+        (
+            DataType::ClassType { .. },
+            DataType::ObjectType {
+                entries: to_entries,
+            },
+            BasicValueEnum::PointerValue(instance),
+        ) => {
+            let class = get_class(from_data_type, symbol_table);
+
+            let values: Vec<BasicValueEnum> = to_entries
+                .iter()
+                .map(|(key, to_entry)| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    let (value, data_type) = match class.methods.get(key) {
+                        Some(method) => {
+                            let closure = build_method_closure(
+                                instance,
+                                class,
+                                key,
+                                context,
+                                builder,
+                                function_value,
+                                module,
+                                &reg_name,
+                            );
+                            (closure.as_basic_value_enum(), &method.data_type)
+                        }
+                        None => (
+                            BasicValueEnum::PointerValue(
+                                get_byte_pointer_type(context).const_null(),
+                            ),
+                            &DataType::Undefined,
+                        ),
+                    };
+
+                    let reg_name = function_value.get_unique_reg_name();
+                    return build_structural_conversion(
+                        value,
+                        data_type,
+                        to_entry,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        &reg_name,
+                    );
+                })
+                .collect();
+
+            let object = build_object(
+                values,
+                to_entries,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return object.as_basic_value_enum();
+        }
+
+        (from_data_type, to_data_type, _) => panic!(
+            "Cannot convert data type {:?} to {:?}",
+            from_data_type, to_data_type
+        ),
+    }
+}
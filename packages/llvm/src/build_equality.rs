@@ -0,0 +1,507 @@
+use ast::{data_type::DataType, expression::BinaryOperator};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, RealCompareOperator},
+    module::Module,
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_string::{build_string_comparison, build_string_literal},
+    build_union::build_union_dispatch,
+    runtime::{build_runtime_call, expect_int, STRING_TO_NUMBER},
+};
+
+/*
+ * Abstract equality of ECMAScript for the data types supported by the compiler
+ *
+ *      same data type          compared like ===, objects and arrays by reference
+ *      boolean and other       boolean is converted to a number first
+ *      number and string       string is converted to a number
+ *      object and primitive    object is converted to "[object Object]"
+ *      null or undefined       only equal to null and undefined
+ *
+ * Parser rejects comparing an array or a function with a primitive
+ *
+ * */
+pub(crate) fn build_loose_equality<'a>(
+    operator: &BinaryOperator,
+    left: BasicValueEnum<'a>,
+    left_data_type: &DataType,
+    right: BasicValueEnum<'a>,
+    right_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    match operator {
+        BinaryOperator::Equality => {
+            return build_abstract_equality(
+                left,
+                left_data_type,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        BinaryOperator::NotEqual => {
+            let reg_name = function_value.get_unique_reg_name();
+            let is_equal = build_abstract_equality(
+                left,
+                left_data_type,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+
+            return builder.build_xor(is_equal, context.i1_type().const_int(1, false), name);
+        }
+
+        _ => panic!("Operator {:?} is not a loose equality operator", operator),
+    }
+}
+
+fn build_abstract_equality<'a>(
+    left: BasicValueEnum<'a>,
+    left_data_type: &DataType,
+    right: BasicValueEnum<'a>,
+    right_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    match (left_data_type, left, right_data_type, right) {
+        // Union is compared by the member it holds
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union), _, right) => {
+            let is_equal = build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |left, member, function_value| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    let is_equal = build_abstract_equality(
+                        left,
+                        member,
+                        right.clone(),
+                        right_data_type,
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
+                    );
+
+                    return is_equal.as_basic_value_enum();
+                },
+            );
+
+            return expect_int(is_equal);
+        }
+
+        (_, left, DataType::UnionType { .. }, right) => {
+            return build_abstract_equality(
+                right,
+                right_data_type,
+                left,
+                left_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        _ if left_data_type.is_nullish() || right_data_type.is_nullish() => {
+            let is_equal = left_data_type.is_nullish() && right_data_type.is_nullish();
+            return context.i1_type().const_int(is_equal as u64, false);
+        }
+
+        (
+            DataType::Float,
+            BasicValueEnum::FloatValue(left),
+            DataType::Float,
+            BasicValueEnum::FloatValue(right),
+        ) => return builder.build_float_compare(RealCompareOperator::Equal, left, right, name),
+
+        (
+            DataType::String,
+            BasicValueEnum::PointerValue(left),
+            DataType::String,
+            BasicValueEnum::PointerValue(right),
+        ) => {
+            return build_string_comparison(
+                &BinaryOperator::StrictEquality,
+                left,
+                right,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (
+            DataType::Boolean,
+            BasicValueEnum::IntValue(left),
+            DataType::Boolean,
+            BasicValueEnum::IntValue(right),
+        ) => return builder.build_int_compare(IntCompareOperator::Equal, left, right, name),
+
+        // true is 1 and false is 0
+        (DataType::Boolean, BasicValueEnum::IntValue(left), right_data_type, right) => {
+            let left = builder.build_ui_to_fp(
+                left,
+                context.f64_type(),
+                &function_value.get_unique_reg_name(),
+            );
+
+            return build_abstract_equality(
+                left.as_basic_value_enum(),
+                &DataType::Float,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (DataType::String, BasicValueEnum::PointerValue(left), DataType::Float, right) => {
+            let left = build_string_to_number(left, context, builder, function_value, module);
+
+            return build_abstract_equality(
+                left.as_basic_value_enum(),
+                &DataType::Float,
+                right,
+                &DataType::Float,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (
+            DataType::ObjectType { .. },
+            BasicValueEnum::PointerValue(_),
+            DataType::Float | DataType::String,
+            right,
+        ) => {
+            let reg_name = function_value.get_unique_reg_name();
+            let left = build_string_literal(
+                "[object Object]",
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+
+            return build_abstract_equality(
+                left.as_basic_value_enum(),
+                &DataType::String,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (_, BasicValueEnum::PointerValue(left), _, BasicValueEnum::PointerValue(right))
+            if !is_primitive(left_data_type) && !is_primitive(right_data_type) =>
+        {
+            return build_reference_equality(
+                &BinaryOperator::StrictEquality,
+                left,
+                right,
+                context,
+                builder,
+                function_value,
+                name,
+            );
+        }
+
+        // Every rule is symmetric, so the operands are swapped
+        (_, left, DataType::Boolean, right)
+        | (DataType::Float, left, DataType::String, right)
+        | (DataType::Float | DataType::String, left, DataType::ObjectType { .. }, right) => {
+            return build_abstract_equality(
+                right,
+                right_data_type,
+                left,
+                left_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (left_data_type, _, right_data_type, _) => panic!(
+            "Cannot compare data type {:?} with {:?} using ==",
+            left_data_type, right_data_type
+        ),
+    }
+}
+
+/*
+ * Strict equality when at least one of the values is a union, the data types are
+ * only known at runtime then
+ *
+ *      different typeof        never equal
+ *      same typeof             compared like === of that data type
+ *
+ * */
+pub(crate) fn build_strict_equality<'a>(
+    operator: &BinaryOperator,
+    left: BasicValueEnum<'a>,
+    left_data_type: &DataType,
+    right: BasicValueEnum<'a>,
+    right_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    match operator {
+        BinaryOperator::StrictEquality => {
+            return build_same_value(
+                left,
+                left_data_type,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        BinaryOperator::StrictNotEqual => {
+            let reg_name = function_value.get_unique_reg_name();
+            let is_equal = build_same_value(
+                left,
+                left_data_type,
+                right,
+                right_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+
+            return builder.build_xor(is_equal, context.i1_type().const_int(1, false), name);
+        }
+
+        _ => panic!("Operator {:?} is not a strict equality operator", operator),
+    }
+}
+
+fn build_same_value<'a>(
+    left: BasicValueEnum<'a>,
+    left_data_type: &DataType,
+    right: BasicValueEnum<'a>,
+    right_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    match (left_data_type, left, right_data_type, right) {
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union), _, right) => {
+            let is_equal = build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |left, member, function_value| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    let is_equal = build_same_value(
+                        left,
+                        member,
+                        right.clone(),
+                        right_data_type,
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
+                    );
+
+                    return is_equal.as_basic_value_enum();
+                },
+            );
+
+            return expect_int(is_equal);
+        }
+
+        (_, left, DataType::UnionType { .. }, right) => {
+            return build_same_value(
+                right,
+                right_data_type,
+                left,
+                left_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        _ if left_data_type.get_typeof_name() != right_data_type.get_typeof_name() => {
+            return context.i1_type().const_int(0, false);
+        }
+
+        (
+            DataType::Float,
+            BasicValueEnum::FloatValue(left),
+            DataType::Float,
+            BasicValueEnum::FloatValue(right),
+        ) => return builder.build_float_compare(RealCompareOperator::Equal, left, right, name),
+
+        (
+            DataType::Boolean,
+            BasicValueEnum::IntValue(left),
+            DataType::Boolean,
+            BasicValueEnum::IntValue(right),
+        ) => return builder.build_int_compare(IntCompareOperator::Equal, left, right, name),
+
+        (
+            DataType::String,
+            BasicValueEnum::PointerValue(left),
+            DataType::String,
+            BasicValueEnum::PointerValue(right),
+        ) => {
+            return build_string_comparison(
+                &BinaryOperator::StrictEquality,
+                left,
+                right,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        (_, BasicValueEnum::PointerValue(left), _, BasicValueEnum::PointerValue(right)) => {
+            return build_reference_equality(
+                &BinaryOperator::StrictEquality,
+                left,
+                right,
+                context,
+                builder,
+                function_value,
+                name,
+            );
+        }
+
+        (left_data_type, _, right_data_type, _) => panic!(
+            "Cannot compare data type {:?} with {:?} using ===",
+            left_data_type, right_data_type
+        ),
+    }
+}
+
+/*
+ * Objects, arrays and functions are equal only when they are the same value
+ *
+ * */
+pub(crate) fn build_reference_equality<'a>(
+    operator: &BinaryOperator,
+    left: PointerValue<'a>,
+    right: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> IntValue<'a> {
+    let left_address = builder.build_ptr_to_int(
+        left,
+        context.i64_type(),
+        &function_value.get_unique_reg_name(),
+    );
+    let right_address = builder.build_ptr_to_int(
+        right,
+        context.i64_type(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let compare_operator = match operator {
+        BinaryOperator::StrictEquality | BinaryOperator::Equality => IntCompareOperator::Equal,
+        BinaryOperator::StrictNotEqual | BinaryOperator::NotEqual => IntCompareOperator::NotEqual,
+
+        _ => panic!(
+            "Operator {:?} cannot be used to compare references",
+            operator
+        ),
+    };
+
+    return builder.build_int_compare(compare_operator, left_address, right_address, name);
+}
+
+fn build_string_to_number<'a>(
+    string: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+) -> FloatValue<'a> {
+    let number = build_runtime_call(
+        STRING_TO_NUMBER,
+        &[string.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    );
+
+    if let Some(BasicValueEnum::FloatValue(number)) = number {
+        return number;
+    } else {
+        unreachable!();
+    }
+}
+
+fn is_primitive(data_type: &DataType) -> bool {
+    return matches!(
+        data_type,
+        DataType::Float | DataType::String | DataType::Boolean
+    );
+}
@@ -0,0 +1,132 @@
+use either::Either;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    module::Module,
+    types::traits::BasicTypeTrait,
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, ptr_value::PointerValue,
+        traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    runtime::{
+        build_runtime_call_returning_pointer, get_byte_pointer_type, get_runtime_fn, CATCH,
+        PERSONALITY, THROW,
+    },
+    symbol_table::SymbolTable,
+};
+
+/*
+ * Inside of a try the call is an invoke, so an exception thrown by the callee
+ * unwinds to the landing pad of the try. Returns None if the function returns void
+ *
+ * Runtime functions never throw except THROW itself and the array functions which
+ * throw a RangeError, so the others are called directly
+ *
+ * */
+pub(crate) fn build_call_or_invoke<'a>(
+    callable: Either<&FunctionValue<'a>, &PointerValue<'a>>,
+    args: &[BasicValueEnum<'a>],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    name: &str,
+) -> Option<BasicValueEnum<'a>> {
+    let value = match symbol_table.get_landing_pad() {
+        Some(landing_pad) => {
+            let normal_block_name = function_value.get_unique_block_name();
+            let normal_block = context.append_basic_block(function_value, &normal_block_name);
+
+            let value = builder.build_invoke_2(callable, args, &normal_block, landing_pad, name);
+            builder.position_at_end(&normal_block);
+            value
+        }
+
+        None => builder.build_call2(callable, args, name),
+    };
+
+    if value.is_void() {
+        return None;
+    } else {
+        return Some(value.to_basic_value_enum().unwrap());
+    }
+}
+
+/*
+ * Has to be the first thing built in a landing pad, returns the thrown value
+ *
+ *      landingpad { i8*, i32 } catch i8* null
+ *
+ * catches every exception
+ *
+ * */
+pub(crate) fn build_landing_pad<'a>(
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let personality = get_runtime_fn(PERSONALITY, context, module);
+    function_value.set_personality_fn(&personality);
+
+    let byte_pointer_type = get_byte_pointer_type(context);
+    let landing_pad_type = context.struct_type(
+        &vec![
+            byte_pointer_type.as_basic_type_enum(),
+            context.i32_type().as_basic_type_enum(),
+        ],
+        false,
+    );
+    let landing_pad = builder.build_landing_pad(
+        &landing_pad_type.as_basic_type_enum(),
+        &personality,
+        &[byte_pointer_type.const_null().as_basic_value_enum()],
+        false,
+        &function_value.get_unique_reg_name(),
+    );
+
+    symbol_table
+        .get_gc_frame()
+        .build_restore(context, builder, module);
+
+    let exception =
+        builder.build_extract_value(landing_pad, 0, &function_value.get_unique_reg_name());
+    return build_runtime_call_returning_pointer(
+        CATCH,
+        &[exception],
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    );
+}
+
+/*
+ * Throws the union, the block is terminated since the throw never returns
+ *
+ * */
+pub(crate) fn build_throw<'a>(
+    value: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let throw = get_runtime_fn(THROW, context, module);
+
+    build_call_or_invoke(
+        Either::Left(&throw),
+        &[value.as_basic_value_enum()],
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        "",
+    );
+    builder.build_unreachable();
+}
@@ -2,7 +2,7 @@ use std::convert::TryInto;
 
 use ast::{
     data_type::DataType,
-    expression::{BinaryOperator, Expression, UnaryOperator},
+    expression::{ArrayMethod, BinaryOperator, Expression, UnaryOperator},
 };
 use either::Either;
 use indexmap::IndexMap;
@@ -12,16 +12,46 @@ use inkwell::{
     enums::{IntCompareOperator, RealCompareOperator},
     module::Module,
     types::{
-        array_type::ArrayType, enums::BasicTypeEnum, struct_type::StructType,
+        enums::{AddressSpace, BasicTypeEnum},
+        struct_type::StructType,
         traits::BasicTypeTrait,
     },
-    values::{enums::BasicValueEnum, fn_value::FunctionValue},
+    values::{enums::BasicValueEnum, fn_value::FunctionValue, ptr_value::PointerValue},
 };
+use lexer::diagnostic::Diagnostic;
 
-use crate::{llvm_utils::LLVMUtils, symbol_table::SymbolTable};
+use crate::{
+    build_array::{
+        build_array_length, build_array_literal, build_array_member_access,
+        build_array_method_call, build_union_index_of,
+    },
+    build_class::{build_field_pointer, build_instanceof, build_method_call},
+    build_closure::{
+        build_closure_call, build_closure_expression, build_function_closure,
+        get_closure_pointer_type,
+    },
+    build_console::build_console_call,
+    build_conversion::build_structural_conversion,
+    build_equality::{build_loose_equality, build_reference_equality, build_strict_equality},
+    build_exception::build_call_or_invoke,
+    build_logical::{build_conditional_expression, build_logical_expression, build_truthiness},
+    build_number::{build_bitwise_not, build_number_operation, build_update_expression},
+    build_string::{
+        build_string_comparison, build_string_concat, build_string_literal, build_to_string,
+    },
+    build_union::{build_is_nullish, build_typeof, build_union_conversion, get_union_pointer_type},
+    gen_ast::consume_function_declaration::consume_generic_instance,
+    llvm_utils::LLVMUtils,
+    runtime::{gc::build_gc_alloc, get_byte_pointer_type, string::get_string_pointer_type},
+    symbol_table::SymbolTable,
+};
 
 /*
  * It will return None if expression is Void
+ *
+ * Strings, arrays and objects created by the expression are rooted in the gc frame
+ * of the function, so they stay alive while the rest of the statement is evaluated
+ * even if it calls a function which collects, e.g. the left side of `a + f()`
  *  */
 pub(crate) fn build_expression<'a>(
     expression: &Expression,
@@ -31,7 +61,63 @@ pub(crate) fn build_expression<'a>(
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
     name: Option<String>,
-) -> Option<BasicValueEnum<'a>> {
+) -> Result<Option<BasicValueEnum<'a>>, Diagnostic> {
+    let value = build_unrooted_expression(
+        expression,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        name,
+    )?;
+
+    let creates_reference = matches!(
+        expression,
+        Expression::StringLiteralExp { .. }
+            | Expression::TemplateLiteral { .. }
+            | Expression::BinaryExp { .. }
+            | Expression::ArrayLiteral { .. }
+            | Expression::ArrayMethodCall { .. }
+            | Expression::ObjectLiteral { .. }
+            | Expression::FunctionCall { .. }
+            | Expression::MethodCall { .. }
+            | Expression::UnionConversion { .. }
+            | Expression::StructuralConversion { .. }
+            | Expression::UnaryExp { .. }
+            | Expression::Closure { .. }
+    );
+    let is_gc_data_type = matches!(
+        expression.get_data_type(),
+        DataType::String
+            | DataType::ArrayType { .. }
+            | DataType::ObjectType { .. }
+            | DataType::UnionType { .. }
+            | DataType::FunctionType { .. }
+            | DataType::ClassType { .. }
+            | DataType::Object
+    );
+
+    if creates_reference && is_gc_data_type {
+        if let Some(BasicValueEnum::PointerValue(pointer)) = value {
+            symbol_table
+                .get_gc_frame()
+                .build_root(pointer, context, builder, "temp");
+        }
+    }
+
+    return Ok(value);
+}
+
+fn build_unrooted_expression<'a>(
+    expression: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: Option<String>,
+) -> Result<Option<BasicValueEnum<'a>>, Diagnostic> {
     let name = match name {
         Some(name) => name,
         None => function_value.get_unique_reg_name(),
@@ -40,45 +126,106 @@ pub(crate) fn build_expression<'a>(
     let name = name.as_str();
 
     match expression {
-        Expression::FloatLiteralExp { name: _, value } => {
+        Expression::FloatLiteralExp {
+            name: _,
+            value,
+            span: _,
+        } => {
             let double_value = context.f64_type().const_float(*value);
-            return Some(BasicValueEnum::FloatValue(double_value));
+            return Ok(Some(BasicValueEnum::FloatValue(double_value)));
         }
 
-        Expression::BooleanLiteralExp { name: _, value } => {
+        Expression::BooleanLiteralExp {
+            name: _,
+            value,
+            span: _,
+        } => {
             let bool_as_int_value = context.i1_type().const_int(*value as u64, false);
-            return Some(BasicValueEnum::IntValue(bool_as_int_value));
+            return Ok(Some(BasicValueEnum::IntValue(bool_as_int_value)));
+        }
+
+        Expression::NullLiteralExp { span: _ } | Expression::UndefinedLiteralExp { span: _ } => {
+            let pointer = get_byte_pointer_type(context).const_null();
+            return Ok(Some(BasicValueEnum::PointerValue(pointer)));
         }
 
-        Expression::StringLiteralExp { value } => {
-            let size_of_string = value.len() as u32;
-            let string_array_type = context.i8_type().array_type(size_of_string);
+        Expression::StringLiteralExp { value, span: _ } => {
+            let string =
+                build_string_literal(value, context, builder, function_value, module, name);
+            return Ok(Some(BasicValueEnum::PointerValue(string)));
+        }
 
-            let base_pointer = builder.build_alloca(string_array_type, name);
+        /*
+         * Lowered to concatenation of the quasis and the value of substitutions
+         * converted to string
+         *
+         * Substitutions are evaluated before any string is created, so the strings
+         * do not have to be rooted while a substitution calls a function
+         *
+         * */
+        Expression::TemplateLiteral {
+            quasis,
+            expressions,
+            span: _,
+        } => {
+            let values: Vec<BasicValueEnum> = expressions
+                .iter()
+                .map(|exp| {
+                    return Ok(build_expression(
+                        exp,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        None,
+                    )?
+                    .unwrap());
+                })
+                .collect::<Result<_, Diagnostic>>()?;
+
+            let mut strings: Vec<PointerValue> = vec![];
+
+            for (i, quasi) in quasis.iter().enumerate() {
+                if !quasi.is_empty() {
+                    let reg_name = function_value.get_unique_reg_name();
+                    strings.push(build_string_literal(
+                        quasi,
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
+                    ));
+                }
 
-            for (i, c) in value.chars().enumerate() {
-                let indices = vec![
-                    context.i64_type().const_int(0, true),
-                    context.i64_type().const_int(i.try_into().unwrap(), true),
-                ];
+                if let (Some(exp), Some(value)) = (expressions.get(i), values.get(i)) {
+                    strings.push(build_to_string(
+                        value.clone(),
+                        &exp.get_data_type(),
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                    ));
+                }
+            }
 
-                let index_pointer = builder.build_gep_2(
-                    string_array_type,
-                    &base_pointer,
-                    &indices,
-                    &function_value.get_unique_reg_name(),
-                );
+            let mut strings = strings.into_iter();
+            let mut result = match strings.next() {
+                Some(string) => string,
+                None => build_string_literal("", context, builder, function_value, module, name),
+            };
 
-                let char_value = context.i8_type().const_int(c as u64, false);
-                builder.build_store(index_pointer, char_value);
+            for string in strings {
+                result = build_string_concat(result, string, context, builder, module, name);
             }
 
-            return Some(BasicValueEnum::PointerValue(base_pointer));
+            return Ok(Some(BasicValueEnum::PointerValue(result)));
         }
 
         /*
          * param 'name' is not used if the datatype of IdentExp is one of
-         *          - String
          *          - ArrayType
          *
          *
@@ -86,6 +233,7 @@ pub(crate) fn build_expression<'a>(
         Expression::IdentExp {
             name: variable_name,
             data_type,
+            span: _,
         } => {
             if let Some(pointer) = symbol_table.get(variable_name) {
                 let load_value = match data_type {
@@ -99,22 +247,109 @@ pub(crate) fn build_expression<'a>(
                         context.i1_type().as_basic_type_enum(),
                         name,
                     ),
-                    DataType::String => BasicValueEnum::PointerValue(pointer.clone()),
+                    DataType::String => builder.build_load(
+                        pointer.to_owned(),
+                        get_string_pointer_type(context).as_basic_type_enum(),
+                        name,
+                    ),
+                    DataType::UnionType { types: _ } => builder.build_load(
+                        pointer.to_owned(),
+                        get_union_pointer_type(context).as_basic_type_enum(),
+                        name,
+                    ),
+                    DataType::Null | DataType::Undefined | DataType::ClassType { .. } | DataType::Object => builder.build_load(
+                        pointer.to_owned(),
+                        get_byte_pointer_type(context).as_basic_type_enum(),
+                        name,
+                    ),
 
                     DataType::ArrayType{base_type : _ } => BasicValueEnum::PointerValue(pointer.clone()),
                     DataType::ObjectType { entries : _ } => BasicValueEnum::PointerValue(pointer.clone()),
-                    DataType::FunctionType{arguments : _, return_type : _} => BasicValueEnum::PointerValue(pointer.clone()),
+                    // Function declared at the top of the file is not a closure yet
+                    DataType::FunctionType{arguments : _, return_type : _} if !symbol_table.local_symbols.contains_key(variable_name) => {
+                        let closure = build_function_closure(variable_name, pointer.to_owned(), data_type, context, builder, module, name);
+                        BasicValueEnum::PointerValue(closure)
+                    }
+                    DataType::FunctionType{arguments : _, return_type : _} => builder.build_load(
+                        pointer.to_owned(),
+                        get_closure_pointer_type(context).as_basic_type_enum(),
+                        name,
+                    ),
 
                     _ => panic!("Update Function build_expression -> Expression::IdentExp, Unsupported datatype"),
                 };
 
-                return Some(load_value);
+                return Ok(Some(load_value));
             } else {
                 panic!("Unknown variable")
             }
         }
 
-        Expression::UnaryExp { operator, argument } => {
+        Expression::UnionConversion {
+            exp,
+            data_type,
+            span: _,
+        } => {
+            let value = build_expression(
+                exp.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+
+            let value = build_union_conversion(
+                value,
+                &exp.get_data_type(),
+                data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return Ok(Some(value));
+        }
+
+        Expression::StructuralConversion {
+            exp,
+            data_type,
+            span: _,
+        } => {
+            let value = build_expression(
+                exp.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+
+            let value = build_structural_conversion(
+                value,
+                &exp.get_data_type(),
+                data_type,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            );
+            return Ok(Some(value));
+        }
+
+        Expression::UnaryExp {
+            operator,
+            argument,
+            span: _,
+        } => {
+            // Argument of typeof can be a call returning void
             let arg_value = build_expression(
                 argument.as_ref(),
                 context,
@@ -123,34 +358,52 @@ pub(crate) fn build_expression<'a>(
                 symbol_table,
                 module,
                 None,
-            )
-            .unwrap();
+            )?;
+
+            if let UnaryOperator::Typeof = operator {
+                let typeof_name = build_typeof(
+                    arg_value,
+                    &argument.get_data_type(),
+                    context,
+                    builder,
+                    function_value,
+                    module,
+                    name,
+                );
+                return Ok(Some(BasicValueEnum::PointerValue(typeof_name)));
+            }
+
+            let arg_value = arg_value.unwrap();
+
+            if let UnaryOperator::Bang = operator {
+                let is_truthy = build_truthiness(
+                    arg_value,
+                    &argument.get_data_type(),
+                    context,
+                    builder,
+                    function_value,
+                );
+                let value =
+                    builder.build_xor(is_truthy, context.i1_type().const_int(1, false), name);
+                return Ok(Some(BasicValueEnum::IntValue(value)));
+            }
 
             match arg_value {
                 BasicValueEnum::FloatValue(value) => {
                     let evaluated_float_value = match operator {
                         UnaryOperator::Minus => builder.build_float_neg(value, name),
                         UnaryOperator::Plus => value,
-
-                        _ => todo!(),
-                    };
-
-                    return Some(BasicValueEnum::FloatValue(evaluated_float_value));
-                }
-
-                BasicValueEnum::IntValue(value) => {
-                    let evaluated_int_value = match operator {
-                        UnaryOperator::Bang => {
-                            builder.build_xor(value, context.i1_type().const_int(1, false), name)
+                        UnaryOperator::Tilde => {
+                            build_bitwise_not(value, context, builder, function_value, name)
                         }
 
-                        _ => todo!(),
+                        _ => return Err(get_unsupported_operands_error(expression)),
                     };
 
-                    return Some(BasicValueEnum::IntValue(evaluated_int_value));
+                    return Ok(Some(BasicValueEnum::FloatValue(evaluated_float_value)));
                 }
 
-                _ => todo!(),
+                _ => return Err(get_unsupported_operands_error(expression)),
             }
         }
 
@@ -158,6 +411,7 @@ pub(crate) fn build_expression<'a>(
             operator,
             left,
             right,
+            span: _,
         } => {
             let left_value = build_expression(
                 left.as_ref(),
@@ -167,7 +421,7 @@ pub(crate) fn build_expression<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
             let right_value = build_expression(
                 right.as_ref(),
@@ -177,17 +431,101 @@ pub(crate) fn build_expression<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
 
+            let left_data_type = left.get_data_type();
+            let right_data_type = right.get_data_type();
+
+            if let BinaryOperator::Equality | BinaryOperator::NotEqual = operator {
+                let int_value = build_loose_equality(
+                    operator,
+                    left_value,
+                    &left_data_type,
+                    right_value,
+                    &right_data_type,
+                    context,
+                    builder,
+                    function_value,
+                    module,
+                    name,
+                );
+                return Ok(Some(BasicValueEnum::IntValue(int_value)));
+            }
+
+            // null and undefined are compared by their data type alone
+            let has_union = matches!(left_data_type, DataType::UnionType { .. })
+                || matches!(right_data_type, DataType::UnionType { .. })
+                || left_data_type.is_nullish()
+                || right_data_type.is_nullish();
+
+            if let (true, BinaryOperator::StrictEquality | BinaryOperator::StrictNotEqual) =
+                (has_union, operator)
+            {
+                let int_value = build_strict_equality(
+                    operator,
+                    left_value,
+                    &left_data_type,
+                    right_value,
+                    &right_data_type,
+                    context,
+                    builder,
+                    function_value,
+                    module,
+                    name,
+                );
+                return Ok(Some(BasicValueEnum::IntValue(int_value)));
+            }
+
+            if left_data_type == DataType::String || right_data_type == DataType::String {
+                match operator {
+                    BinaryOperator::Plus => {
+                        let lhs = build_to_string(
+                            left_value,
+                            &left_data_type,
+                            context,
+                            builder,
+                            function_value,
+                            module,
+                        );
+                        let rhs = build_to_string(
+                            right_value,
+                            &right_data_type,
+                            context,
+                            builder,
+                            function_value,
+                            module,
+                        );
+
+                        let string = build_string_concat(lhs, rhs, context, builder, module, name);
+                        return Ok(Some(BasicValueEnum::PointerValue(string)));
+                    }
+
+                    _ => {
+                        if let BasicValueEnum::PointerValue(lhs) = left_value {
+                            if let BasicValueEnum::PointerValue(rhs) = right_value {
+                                let int_value = build_string_comparison(
+                                    operator,
+                                    lhs,
+                                    rhs,
+                                    context,
+                                    builder,
+                                    function_value,
+                                    module,
+                                    name,
+                                );
+                                return Ok(Some(BasicValueEnum::IntValue(int_value)));
+                            }
+                        }
+
+                        return Err(get_unsupported_operands_error(expression));
+                    }
+                }
+            }
+
             if let BasicValueEnum::FloatValue(lhs) = left_value {
                 if let BasicValueEnum::FloatValue(rhs) = right_value {
                     let evaluated_float_value = match operator {
-                        BinaryOperator::Plus => builder.build_float_add(lhs, rhs, name),
-                        BinaryOperator::Minus => builder.build_float_sub(lhs, rhs, name),
-                        BinaryOperator::Star => builder.build_float_mul(lhs, rhs, name),
-                        BinaryOperator::Slash => builder.build_float_div(lhs, rhs, name),
-
                         BinaryOperator::StrictEquality
                         | BinaryOperator::StrictNotEqual
                         | BinaryOperator::LessThan
@@ -239,15 +577,24 @@ pub(crate) fn build_expression<'a>(
                                 _ => unreachable!(),
                             };
 
-                            return Some(BasicValueEnum::IntValue(int_value));
+                            return Ok(Some(BasicValueEnum::IntValue(int_value)));
                         }
 
-                        _ => todo!(),
+                        _ => build_number_operation(
+                            operator,
+                            lhs,
+                            rhs,
+                            context,
+                            builder,
+                            function_value,
+                            module,
+                            name,
+                        ),
                     };
 
-                    return Some(BasicValueEnum::FloatValue(evaluated_float_value));
+                    return Ok(Some(BasicValueEnum::FloatValue(evaluated_float_value)));
                 } else {
-                    todo!()
+                    return Err(get_unsupported_operands_error(expression));
                 }
             } else {
                 if let BasicValueEnum::IntValue(lhs) = left_value {
@@ -263,63 +610,131 @@ pub(crate) fn build_expression<'a>(
                                 name,
                             ),
 
-                            _ => todo!(),
+                            _ => return Err(get_unsupported_operands_error(expression)),
                         };
 
-                        return Some(BasicValueEnum::IntValue(evaluated_int_value));
+                        return Ok(Some(BasicValueEnum::IntValue(evaluated_int_value)));
                     } else {
-                        todo!()
+                        return Err(get_unsupported_operands_error(expression));
                     }
+                } else if let (
+                    BasicValueEnum::PointerValue(lhs),
+                    BasicValueEnum::PointerValue(rhs),
+                ) = (left_value, right_value)
+                {
+                    let int_value = build_reference_equality(
+                        operator,
+                        lhs,
+                        rhs,
+                        context,
+                        builder,
+                        function_value,
+                        name,
+                    );
+                    return Ok(Some(BasicValueEnum::IntValue(int_value)));
                 } else {
-                    todo!()
+                    return Err(get_unsupported_operands_error(expression));
                 }
             }
         }
 
-        Expression::ArrayLiteral {
-            expression,
-            expression_data_type,
+        Expression::UpdateExp {
+            operator,
+            prefix,
+            argument,
+            span: _,
         } => {
-            let array_type = convert_data_type_to_array_type(
-                expression_data_type,
+            let value = build_update_expression(
+                operator,
+                *prefix,
+                argument,
                 context,
-                expression.len() as u32,
-            )
-            .unwrap();
-
-            let base_pointer = builder.build_alloca(array_type, name);
-
-            for (i, exp) in expression.iter().enumerate() {
-                let value = build_expression(
-                    exp,
-                    context,
-                    builder,
-                    function_value,
-                    symbol_table,
-                    module,
-                    None,
-                )
-                .unwrap();
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?;
+            return Ok(Some(BasicValueEnum::FloatValue(value)));
+        }
 
-                let indices = vec![
-                    context.i64_type().const_int(0, true),
-                    context.i64_type().const_int(i.try_into().unwrap(), true),
-                ];
+        Expression::LogicalExp {
+            operator,
+            left,
+            right,
+            span: _,
+        } => {
+            let value = build_logical_expression(
+                operator,
+                left,
+                right,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            )?;
+            return Ok(Some(value));
+        }
 
-                let index_pointer = builder.build_gep_2(
-                    array_type,
-                    &base_pointer,
-                    &indices,
-                    &function_value.get_unique_reg_name(),
-                );
+        Expression::ConditionalExp {
+            test,
+            consequent,
+            alternate,
+            span: _,
+        } => {
+            let value = build_conditional_expression(
+                test,
+                consequent,
+                alternate,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            )?;
+            return Ok(Some(value));
+        }
 
-                builder.build_store(index_pointer, value);
-            }
+        Expression::ArrayLiteral {
+            expression,
+            expression_data_type,
+            span: _,
+        } => {
+            let values: Vec<BasicValueEnum> = expression
+                .iter()
+                .map(|exp| {
+                    return Ok(build_expression(
+                        exp,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        None,
+                    )?
+                    .unwrap());
+                })
+                .collect::<Result<_, Diagnostic>>()?;
 
-            return Some(BasicValueEnum::PointerValue(base_pointer));
+            let array = build_array_literal(
+                values,
+                expression_data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return Ok(Some(BasicValueEnum::PointerValue(array)));
         }
 
-        Expression::ArrayMemberAccess { array, argument } => {
+        Expression::ArrayMemberAccess {
+            array,
+            argument,
+            span: _,
+        } => {
             let array_value = build_expression(
                 array.as_ref(),
                 context,
@@ -328,7 +743,7 @@ pub(crate) fn build_expression<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
 
             if let BasicValueEnum::PointerValue(pointer) = array_value {
@@ -340,28 +755,23 @@ pub(crate) fn build_expression<'a>(
                     symbol_table,
                     module,
                     None,
-                )
+                )?
                 .unwrap();
 
                 if let BasicValueEnum::FloatValue(float_value) = member_access_value {
-                    let converted_int_value = builder.build_fp_to_si(
+                    let base_type = get_array_base_type(array);
+                    let loaded_value = build_array_member_access(
+                        pointer,
                         float_value,
-                        context.i64_type(),
-                        function_value.get_unique_reg_name().as_str(),
-                    );
-                    let indices = vec![context.i64_type().const_int(0, true), converted_int_value];
-
-                    let array_type = pointer.get_type().into_array_type().unwrap();
-                    let index_pointer = builder.build_gep_2(
-                        array_type,
-                        &pointer,
-                        &indices,
-                        function_value.get_unique_reg_name().as_str(),
+                        &base_type,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        name,
                     );
-
-                    let element_type = array_type.get_element_type();
-                    let loaded_value = builder.build_load(index_pointer, element_type, name);
-                    return Some(loaded_value);
+                    return Ok(Some(loaded_value));
                 } else {
                     panic!("Expected building expression in field 'argument' to give BasicValueEnum::FloatValue but got {:?}", member_access_value);
                 }
@@ -370,44 +780,129 @@ pub(crate) fn build_expression<'a>(
             }
         }
 
-        Expression::ObjectLiteral {
-            data_type,
-            expression,
-        } => {
-            if let DataType::ObjectType { entries } = data_type {
-                let struct_type = convert_index_map_to_struct_type(entries, context).unwrap();
+        Expression::ArrayLength { array, span: _ } => {
+            let array_value = build_expression(
+                array.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
 
-                let base_pointer = builder.build_alloca(struct_type.clone(), name);
+            if let BasicValueEnum::PointerValue(pointer) = array_value {
+                let length = build_array_length(pointer, context, builder, function_value, name);
+                return Ok(Some(BasicValueEnum::FloatValue(length)));
+            } else {
+                panic!("Expected building expression in field 'array' to give BasicValueEnum::PointerValue but got {:?}", array_value);
+            }
+        }
+
+        Expression::ArrayMethodCall {
+            array,
+            method,
+            arguments,
+            span: _,
+        } => {
+            let array_value = build_expression(
+                array.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
 
-                for (i, (k, _)) in entries.iter().enumerate() {
-                    let corresponding_exp = expression.get(k).unwrap();
-                    let exp = build_expression(
-                        corresponding_exp,
+            let values: Vec<BasicValueEnum> = arguments
+                .iter()
+                .map(|exp| {
+                    return Ok(build_expression(
+                        exp,
                         context,
                         builder,
                         function_value,
                         symbol_table,
                         module,
                         None,
-                    )
-                    .unwrap();
-
-                    let indices = vec![
-                        context.i32_type().const_int(1, true),
-                        context.i32_type().const_int(i.try_into().unwrap(), true),
-                    ];
-
-                    let index_pointer = builder.build_gep_2(
-                        struct_type,
-                        &base_pointer,
-                        &indices,
-                        function_value.get_unique_reg_name().as_str(),
-                    );
+                    )?
+                    .unwrap());
+                })
+                .collect::<Result<_, Diagnostic>>()?;
+
+            if let BasicValueEnum::PointerValue(pointer) = array_value {
+                let base_type = get_array_base_type(array);
 
-                    builder.build_store(index_pointer, exp);
+                if let (ArrayMethod::IndexOf, DataType::UnionType { .. }) = (method, &base_type) {
+                    let position = build_union_index_of(
+                        pointer,
+                        values.into_iter().next().unwrap(),
+                        &base_type,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                        name,
+                    );
+                    return Ok(Some(BasicValueEnum::FloatValue(position)));
                 }
 
-                return Some(BasicValueEnum::PointerValue(base_pointer));
+                let value = build_array_method_call(
+                    pointer,
+                    method,
+                    values,
+                    &base_type,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                    name,
+                );
+                return Ok(Some(value));
+            } else {
+                panic!("Expected building expression in field 'array' to give BasicValueEnum::PointerValue but got {:?}", array_value);
+            }
+        }
+
+        Expression::ObjectLiteral {
+            data_type,
+            expression,
+            span: _,
+        } => {
+            if let DataType::ObjectType { entries } = data_type {
+                let values: Vec<BasicValueEnum> = entries
+                    .keys()
+                    .map(|k| {
+                        let corresponding_exp = expression.get(k).unwrap();
+                        return Ok(build_expression(
+                            corresponding_exp,
+                            context,
+                            builder,
+                            function_value,
+                            symbol_table,
+                            module,
+                            None,
+                        )?
+                        .unwrap());
+                    })
+                    .collect::<Result<_, Diagnostic>>()?;
+
+                let base_pointer = build_object(
+                    values,
+                    entries,
+                    context,
+                    builder,
+                    function_value,
+                    module,
+                    name,
+                );
+
+                return Ok(Some(BasicValueEnum::PointerValue(base_pointer)));
             } else {
                 unreachable!();
             }
@@ -416,6 +911,7 @@ pub(crate) fn build_expression<'a>(
         Expression::DotMemberAccess {
             argument,
             container,
+            span: _,
         } => {
             let container_value = build_expression(
                 container.as_ref(),
@@ -425,61 +921,108 @@ pub(crate) fn build_expression<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
 
-            if let BasicValueEnum::PointerValue(container_pointer) = container_value {
-                let container_data_type = container.get_data_type();
-                if let DataType::ObjectType { entries } = container_data_type {
-                    let index = entries.get_index_of(argument).unwrap();
-
-                    let structure_type = container_pointer.get_type().into_struct_type().unwrap();
-                    let indices = vec![
-                        context.i32_type().const_int(1, true),
-                        context
-                            .i32_type()
-                            .const_int(index.try_into().unwrap(), true),
-                    ];
-
-                    let member_pointer = builder.build_gep_2(
-                        structure_type,
-                        &container_pointer,
-                        &indices,
-                        &function_value.get_unique_reg_name(),
-                    );
-
-                    let field_type = structure_type.get_field_type(index);
-                    let loaded_value = builder.build_load(member_pointer, field_type, name);
-                    return Some(loaded_value);
-                } else {
-                    unreachable!();
-                }
-            } else {
-                unreachable!();
-            }
+            let loaded_value = build_field_access(
+                container_value,
+                &container.get_data_type(),
+                argument,
+                context,
+                builder,
+                function_value,
+                name,
+            );
+            return Ok(Some(loaded_value));
         }
 
-        Expression::FunctionCall {
-            parameters,
-            fn_exp,
-            return_type: _,
+        Expression::OptionalDotMemberAccess {
+            container,
+            argument,
+            data_type,
+            span: _,
         } => {
-            let fn_value = build_expression(
-                fn_exp.as_ref(),
+            let container_value = build_expression(
+                container.as_ref(),
                 context,
                 builder,
                 function_value,
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
 
+            let value = build_optional_field_access(
+                container_value,
+                &container.get_data_type(),
+                argument,
+                data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return Ok(Some(value));
+        }
+
+        Expression::FunctionCall {
+            parameters,
+            fn_exp,
+            return_type: _,
+            span: _,
+        } => {
+            /*
+             * Function declared at the top of the file or imported is called
+             * directly, so is the instance of a generic function. Any other
+             * function value is a closure
+             *
+             * */
+            let global_function = match fn_exp.as_ref() {
+                Expression::GenericInstance {
+                    name: fn_name,
+                    type_arguments,
+                    data_type: _,
+                    span: _,
+                } => {
+                    let instance = consume_generic_instance(
+                        fn_name,
+                        type_arguments,
+                        context,
+                        module,
+                        symbol_table,
+                    )?;
+                    Some(instance.to_pointer_value())
+                }
+                Expression::IdentExp {
+                    name: fn_name,
+                    data_type: _,
+                    span: _,
+                } if !symbol_table.local_symbols.contains_key(fn_name) => {
+                    symbol_table.global_variables.get(fn_name).cloned()
+                }
+                _ => None,
+            };
+
+            let fn_value = match global_function {
+                Some(fn_pointer) => BasicValueEnum::PointerValue(fn_pointer),
+                None => build_expression(
+                    fn_exp.as_ref(),
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                    None,
+                )?
+                .unwrap(),
+            };
+
             if let BasicValueEnum::PointerValue(fn_pointer) = fn_value {
                 let args: Vec<BasicValueEnum> = parameters
                     .iter()
-                    .enumerate()
-                    .map(|(i, exp)| {
+                    .map(|exp| {
                         let value = build_expression(
                             exp,
                             context,
@@ -488,46 +1031,35 @@ pub(crate) fn build_expression<'a>(
                             symbol_table,
                             module,
                             None,
-                        )
+                        )?
                         .unwrap();
 
-                        let argument_data_type = parameters[i].get_data_type();
-
-                        if let DataType::String = argument_data_type {
-                            if let BasicValueEnum::PointerValue(value) = value {
-                                let pointer_array_type =
-                                    value.get_type().into_array_type().unwrap();
-                                let indicies = &[
-                                    context.i64_type().const_int(0, true),
-                                    context.i64_type().const_int(0, true),
-                                ];
-
-                                let value = builder.build_gep_2(
-                                    pointer_array_type,
-                                    &value,
-                                    indicies,
-                                    function_value.get_unique_reg_name().as_str(),
-                                );
-
-                                return BasicValueEnum::PointerValue(value);
-                            }
-                        }
-
-                        return value;
+                        return Ok(value);
                     })
-                    .collect();
-
-                let value = builder.build_call2(Either::Right(&fn_pointer), &args, name);
+                    .collect::<Result<_, Diagnostic>>()?;
 
-                // let basic_value = value.try_as_basic_value().unwrap();
-
-                if value.is_void() {
-                    return None;
-                } else {
-                    return Some(value.to_basic_value_enum().unwrap());
+                if global_function.is_none() {
+                    return Ok(build_closure_call(
+                        fn_pointer,
+                        &fn_exp.get_data_type(),
+                        args,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        name,
+                    ));
                 }
 
-                // return value;
+                return Ok(build_call_or_invoke(
+                    Either::Right(&fn_pointer),
+                    &args,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    name,
+                ));
             } else {
                 let fn_exp_data_type = fn_exp.get_data_type();
 
@@ -543,32 +1075,356 @@ pub(crate) fn build_expression<'a>(
             }
         }
 
+        Expression::FieldAccess {
+            object,
+            field,
+            data_type: _,
+            span: _,
+        } => {
+            let object_value = build_expression(
+                object.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+
+            let (field_pointer, field_type) = build_field_pointer(
+                object_value,
+                &object.get_data_type(),
+                field,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+            );
+            return Ok(Some(builder.build_load(field_pointer, field_type, name)));
+        }
+
+        Expression::MethodCall {
+            object,
+            method,
+            arguments,
+            return_type: _,
+            span: _,
+        } => {
+            return build_method_call(
+                object,
+                method,
+                arguments,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            );
+        }
+
+        Expression::InstanceofExp {
+            exp,
+            class,
+            span: _,
+        } => {
+            let value = build_expression(
+                exp.as_ref(),
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+
+            let is_instance = build_instanceof(
+                value,
+                &exp.get_data_type(),
+                class,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                name,
+            );
+            return Ok(Some(BasicValueEnum::IntValue(is_instance)));
+        }
+
+        Expression::Closure {
+            function,
+            data_type: _,
+            span: _,
+        } => {
+            let closure =
+                build_closure_expression(function, context, builder, symbol_table, module, name)?;
+            return Ok(Some(BasicValueEnum::PointerValue(closure)));
+        }
+
+        // Replaced by the value of the member before codegen, see erase_enums
+        Expression::EnumMember { .. } => unreachable!(),
+
+        Expression::ConsoleCall {
+            method,
+            arguments,
+            span: _,
+        } => {
+            build_console_call(
+                method,
+                arguments,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?;
+            return Ok(None);
+        }
+
+        // Parser only lets a generic function be called, see the FunctionCall above
+        Expression::GenericInstance { name, .. } => {
+            panic!("Generic function {} can only be called", name)
+        }
+
         Expression::NAExp => {
             panic!("Expression::NAExp should not be present in Ast passed to llvm")
         }
     }
 }
 
-fn convert_data_type_to_array_type<'a>(
+/*
+ * Allocates the object and stores the already evaluated values of the fields in
+ * the order of the entries
+ *
+ * */
+pub(crate) fn build_object<'a>(
+    values: Vec<BasicValueEnum<'a>>,
+    entries: &IndexMap<String, DataType>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let struct_type = convert_index_map_to_struct_type(entries, context).unwrap();
+
+    let (size, pointer_offsets) = get_object_layout(entries);
+    let raw_pointer = build_gc_alloc(
+        context.i64_type().const_int(size, false),
+        &pointer_offsets,
+        context,
+        builder,
+        module,
+        name,
+    );
+    let base_pointer = builder.build_bitcast(
+        raw_pointer,
+        struct_type.ptr_type(AddressSpace::Generic),
+        name,
+    );
+
+    for (i, exp) in values.into_iter().enumerate() {
+        let indices = vec![
+            context.i32_type().const_int(0, true),
+            context.i32_type().const_int(i.try_into().unwrap(), true),
+        ];
+
+        let index_pointer = builder.build_gep_2(
+            struct_type,
+            &base_pointer,
+            &indices,
+            function_value.get_unique_reg_name().as_str(),
+        );
+
+        builder.build_store(index_pointer, exp);
+    }
+
+    return base_pointer;
+}
+
+pub(crate) fn build_field_access<'a>(
+    container_value: BasicValueEnum<'a>,
+    container_data_type: &DataType,
+    argument: &str,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    if let BasicValueEnum::PointerValue(container_pointer) = container_value {
+        if let DataType::ObjectType { entries } = container_data_type {
+            let index = entries.get_index_of(argument).unwrap();
+
+            let structure_type = container_pointer.get_type().into_struct_type().unwrap();
+            let indices = vec![
+                context.i32_type().const_int(0, true),
+                context
+                    .i32_type()
+                    .const_int(index.try_into().unwrap(), true),
+            ];
+
+            let member_pointer = builder.build_gep_2(
+                structure_type,
+                &container_pointer,
+                &indices,
+                &function_value.get_unique_reg_name(),
+            );
+
+            let field_type = structure_type.get_field_type(index);
+            return builder.build_load(member_pointer, field_type, name);
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+}
+
+/*
+ *  entry:
+ *      br <container> is null or undefined, nullish, object
+ *
+ *  nullish:
+ *      <undefined> = box undefined
+ *      br end
+ *
+ *  object:
+ *      <field> = box <container>.<argument>
+ *      br end
+ *
+ *  end:
+ *      phi [<undefined>, nullish], [<field>, object]
+ *
+ * Parser only creates Expression::OptionalDotMemberAccess for a union of an object
+ * with null or undefined, the result is a union holding undefined
+ *
+ * */
+fn build_optional_field_access<'a>(
+    container_value: BasicValueEnum<'a>,
+    container_data_type: &DataType,
+    argument: &str,
     data_type: &DataType,
     context: &'a Context,
-    size: u32,
-) -> Result<ArrayType<'a>, String> {
-    let array_type = match data_type {
-        DataType::Boolean => context.i1_type().array_type(size),
-        DataType::Float => context.f64_type().array_type(size),
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let (union, types, object_data_type) = match (container_data_type, &container_value) {
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union)) => {
+            let object_data_type = types
+                .iter()
+                .find(|member| !member.is_nullish())
+                .unwrap();
 
-        _ => {
-            return Err(format!(
-                "Not possible to create array type for this dataType"
-            ))
+            (*union, types, object_data_type)
         }
+        _ => panic!(
+            "Expected container of optional chaining to be a union with null or undefined but got {:?}",
+            container_data_type
+        ),
     };
 
-    return Ok(array_type);
+    let reg_name = function_value.get_unique_reg_name();
+    let is_nullish = build_is_nullish(union, types, context, builder, function_value, &reg_name);
+
+    let nullish_block_name = function_value.get_unique_block_name();
+    let nullish_block_bb = context.append_basic_block(function_value, nullish_block_name.as_str());
+
+    let object_block_name = function_value.get_unique_block_name();
+    let object_block_bb = context.append_basic_block(function_value, object_block_name.as_str());
+
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block_bb = context.append_basic_block(function_value, end_block_name.as_str());
+
+    builder.build_conditional_branch(is_nullish, &nullish_block_bb, &object_block_bb);
+
+    builder.position_at_end(&nullish_block_bb);
+    let reg_name = function_value.get_unique_reg_name();
+    let undefined = build_union_conversion(
+        BasicValueEnum::PointerValue(get_byte_pointer_type(context).const_null()),
+        &DataType::Undefined,
+        data_type,
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+    let nullish_block_bb = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block_bb);
+
+    builder.position_at_end(&object_block_bb);
+    let reg_name = function_value.get_unique_reg_name();
+    let object = build_union_conversion(
+        container_value,
+        container_data_type,
+        object_data_type,
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+
+    let reg_name = function_value.get_unique_reg_name();
+    let field = build_field_access(
+        object,
+        object_data_type,
+        argument,
+        context,
+        builder,
+        function_value,
+        &reg_name,
+    );
+
+    // Field which can already be undefined is a union of the same members
+    let field_data_type = match object_data_type {
+        DataType::ObjectType { entries } => entries[argument].clone(),
+        _ => unreachable!(),
+    };
+    let field = match field_data_type {
+        DataType::UnionType { .. } => field,
+        field_data_type => {
+            let reg_name = function_value.get_unique_reg_name();
+            build_union_conversion(
+                field,
+                &field_data_type,
+                data_type,
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            )
+        }
+    };
+    let object_block_bb = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block_bb);
+
+    builder.position_at_end(&end_block_bb);
+    return builder.build_phi(
+        get_union_pointer_type(context).as_basic_type_enum(),
+        &[(undefined, &nullish_block_bb), (field, &object_block_bb)],
+        name,
+    );
 }
 
-fn convert_index_map_to_struct_type<'a>(
+pub(crate) fn get_array_base_type(array: &Expression) -> DataType {
+    if let DataType::ArrayType { base_type } = array.get_data_type() {
+        return *base_type;
+    } else {
+        panic!(
+            "Expected expression {:?} to be of data type DataType::ArrayType",
+            array
+        );
+    }
+}
+
+pub(crate) fn convert_index_map_to_struct_type<'a>(
     index_map: &IndexMap<String, DataType>,
     context: &'a Context,
 ) -> Result<StructType<'a>, String> {
@@ -578,14 +1434,20 @@ fn convert_index_map_to_struct_type<'a>(
         match data_type {
             DataType::Boolean => all_field.push(context.i1_type().as_basic_type_enum()),
             DataType::Float => all_field.push(context.f64_type().as_basic_type_enum()),
-            DataType::ObjectType { entries: _ } => {
-                all_field.push(data_type.force_to_basic_type(context))
-            }
+            DataType::ArrayType { base_type: _ }
+            | DataType::ObjectType { entries: _ }
+            | DataType::ClassType { .. }
+            | DataType::Object => all_field.push(data_type.force_to_basic_type(context)),
             DataType::FunctionType {
                 arguments: _,
                 return_type: _,
             } => all_field.push(data_type.force_to_basic_type(context)),
-            // DataType::String => all_field.push(data_type.force_to_basic_type(context)),
+            DataType::String | DataType::UnionType { types: _ } => {
+                all_field.push(data_type.force_to_basic_type(context))
+            }
+            DataType::Null | DataType::Undefined => {
+                all_field.push(data_type.force_to_basic_type(context))
+            }
             _ => {
                 return Err(format!(
                     "It is not supported to create a struct field with this data_type {:?}",
@@ -597,3 +1459,68 @@ fn convert_index_map_to_struct_type<'a>(
 
     return Ok(context.struct_type(&all_field, true));
 }
+
+/*
+ * Size of the packed struct created by convert_index_map_to_struct_type and the
+ * offsets of the fields which point to memory owned by the garbage collector
+ *
+ * */
+pub(crate) fn get_object_layout(index_map: &IndexMap<String, DataType>) -> (u64, Vec<u64>) {
+    let mut size = 0;
+    let mut pointer_offsets = vec![];
+
+    for (_, data_type) in index_map {
+        match data_type {
+            DataType::Boolean => size += 1,
+            DataType::String
+            | DataType::ArrayType { base_type: _ }
+            | DataType::ObjectType { entries: _ }
+            | DataType::UnionType { types: _ }
+            | DataType::ClassType { .. }
+            | DataType::Object
+            | DataType::FunctionType {
+                arguments: _,
+                return_type: _,
+            } => {
+                pointer_offsets.push(size);
+                size += 8;
+            }
+            _ => size += 8,
+        }
+    }
+
+    return (size, pointer_offsets);
+}
+
+/*
+ * Parser rejects operands which an operator does not support, so this is only
+ * reached by a combination it lets through by mistake
+ *
+ * */
+fn get_unsupported_operands_error(expression: &Expression) -> Diagnostic {
+    let message = match expression {
+        Expression::UnaryExp {
+            operator, argument, ..
+        } => format!(
+            "Operator {:?} is not supported on data type {:?}",
+            operator,
+            argument.get_data_type()
+        ),
+
+        Expression::BinaryExp {
+            operator,
+            left,
+            right,
+            ..
+        } => format!(
+            "Operator {:?} is not supported between data type {:?} and {:?}",
+            operator,
+            left.get_data_type(),
+            right.get_data_type()
+        ),
+
+        _ => unreachable!(),
+    };
+
+    return Diagnostic::error(&message).with_primary(expression.get_span(), "");
+}
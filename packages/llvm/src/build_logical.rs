@@ -0,0 +1,411 @@
+use ast::{
+    data_type::DataType,
+    expression::{Expression, LogicalOperator},
+};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, RealCompareOperator},
+    module::Module,
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_expression::build_expression,
+    build_union::{build_is_nullish, build_union_conversion, build_union_dispatch},
+    runtime::string::build_load_string_length,
+    symbol_table::SymbolTable,
+};
+
+/*
+ *  left:
+ *      br <left> is truthy, right, end         // for ||: end, right
+ *
+ *  right:
+ *      br end
+ *
+ *  end:
+ *      phi [<left>, left], [<right>, right]
+ *
+ * Right side is only evaluated when the left side does not decide the result
+ *
+ * */
+pub(crate) fn build_logical_expression<'a>(
+    operator: &LogicalOperator,
+    left: &Expression,
+    right: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> Result<BasicValueEnum<'a>, Diagnostic> {
+    let left_value = build_expression(
+        left,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    if let LogicalOperator::NullishCoalescing = operator {
+        return build_nullish_coalescing(
+            left_value,
+            left,
+            right,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            name,
+        );
+    }
+
+    let is_truthy = build_truthiness(
+        left_value.clone(),
+        &left.get_data_type(),
+        context,
+        builder,
+        function_value,
+    );
+    let left_block_bb = builder.get_insert_block();
+
+    let right_block_name = function_value.get_unique_block_name();
+    let right_block_bb = context.append_basic_block(function_value, right_block_name.as_str());
+
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block_bb = context.append_basic_block(function_value, end_block_name.as_str());
+
+    match operator {
+        LogicalOperator::And => {
+            builder.build_conditional_branch(is_truthy, &right_block_bb, &end_block_bb)
+        }
+        _ => builder.build_conditional_branch(is_truthy, &end_block_bb, &right_block_bb),
+    };
+
+    builder.position_at_end(&right_block_bb);
+    let right_value = build_expression(
+        right,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+    // Right side can create blocks of its own, e.g. a || b || c
+    let right_block_bb = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block_bb);
+
+    builder.position_at_end(&end_block_bb);
+    return Ok(builder.build_phi(
+        left_value.get_type(),
+        &[(left_value, &left_block_bb), (right_value, &right_block_bb)],
+        name,
+    ));
+}
+
+/*
+ *  left:
+ *      br <left> is null or undefined, right, value
+ *
+ *  right:
+ *      br end
+ *
+ *  value:
+ *      <value> = <left> converted to the data type of the result
+ *      br end
+ *
+ *  end:
+ *      phi [<right>, right], [<value>, value]
+ *
+ * Right side of `??` already has the data type of the result, see
+ * Expression::LogicalExp
+ *
+ * */
+fn build_nullish_coalescing<'a>(
+    left_value: BasicValueEnum<'a>,
+    left: &Expression,
+    right: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> Result<BasicValueEnum<'a>, Diagnostic> {
+    let left_data_type = left.get_data_type();
+
+    let (union, types) = match (&left_data_type, &left_value) {
+        // Left side which is always null or undefined is never the result
+        (left_data_type, _) if left_data_type.is_nullish() => {
+            return Ok(build_expression(
+                right,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                Some(name.to_string()),
+            )?
+            .unwrap());
+        }
+
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union))
+            if types.iter().any(|member| member.is_nullish()) =>
+        {
+            (*union, types)
+        }
+
+        // Left side which is never null or undefined is always the result
+        _ => return Ok(left_value),
+    };
+
+    let reg_name = function_value.get_unique_reg_name();
+    let is_nullish = build_is_nullish(union, types, context, builder, function_value, &reg_name);
+
+    let right_block_name = function_value.get_unique_block_name();
+    let right_block_bb = context.append_basic_block(function_value, right_block_name.as_str());
+
+    let value_block_name = function_value.get_unique_block_name();
+    let value_block_bb = context.append_basic_block(function_value, value_block_name.as_str());
+
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block_bb = context.append_basic_block(function_value, end_block_name.as_str());
+
+    builder.build_conditional_branch(is_nullish, &right_block_bb, &value_block_bb);
+
+    builder.position_at_end(&right_block_bb);
+    let right_value = build_expression(
+        right,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+    let right_block_bb = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block_bb);
+
+    builder.position_at_end(&value_block_bb);
+    let reg_name = function_value.get_unique_reg_name();
+    let value = build_union_conversion(
+        left_value,
+        &left_data_type,
+        &right.get_data_type(),
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+    let value_block_bb = builder.get_insert_block();
+    builder.build_unconditional_branch(&end_block_bb);
+
+    builder.position_at_end(&end_block_bb);
+    return Ok(builder.build_phi(
+        right_value.get_type(),
+        &[(right_value, &right_block_bb), (value, &value_block_bb)],
+        name,
+    ));
+}
+
+/*
+ *  test:
+ *      br <test> is truthy, consequent, alternate
+ *
+ *  consequent:
+ *      br end
+ *
+ *  alternate:
+ *      br end
+ *
+ *  end:
+ *      phi [<consequent>, consequent], [<alternate>, alternate]
+ *
+ * */
+pub(crate) fn build_conditional_expression<'a>(
+    test: &Expression,
+    consequent: &Expression,
+    alternate: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+    name: &str,
+) -> Result<BasicValueEnum<'a>, Diagnostic> {
+    let test_value = build_expression(
+        test,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+    let is_truthy = build_truthiness(
+        test_value,
+        &test.get_data_type(),
+        context,
+        builder,
+        function_value,
+    );
+
+    let consequent_block_name = function_value.get_unique_block_name();
+    let consequent_block_bb =
+        context.append_basic_block(function_value, consequent_block_name.as_str());
+
+    let alternate_block_name = function_value.get_unique_block_name();
+    let alternate_block_bb =
+        context.append_basic_block(function_value, alternate_block_name.as_str());
+
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block_bb = context.append_basic_block(function_value, end_block_name.as_str());
+
+    builder.build_conditional_branch(is_truthy, &consequent_block_bb, &alternate_block_bb);
+
+    let mut incoming = vec![];
+    for (expression, block_bb) in [
+        (consequent, consequent_block_bb),
+        (alternate, alternate_block_bb),
+    ] {
+        builder.position_at_end(&block_bb);
+        let value = build_expression(
+            expression,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            None,
+        )?
+        .unwrap();
+
+        incoming.push((value, builder.get_insert_block()));
+        builder.build_unconditional_branch(&end_block_bb);
+    }
+
+    builder.position_at_end(&end_block_bb);
+    let incoming: Vec<(BasicValueEnum, &_)> = incoming
+        .iter()
+        .map(|(value, block_bb)| (value.clone(), block_bb))
+        .collect();
+    return Ok(builder.build_phi(incoming[0].0.get_type(), &incoming, name));
+}
+
+/*
+ * Condition of if, while and for is tested for its truthiness like in js, so
+ * it is not limited to booleans
+ *
+ * */
+pub(crate) fn build_condition<'a>(
+    condition: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<IntValue<'a>, Diagnostic> {
+    let value = build_expression(
+        condition,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    return Ok(build_truthiness(
+        value,
+        &condition.get_data_type(),
+        context,
+        builder,
+        function_value,
+    ));
+}
+
+/*
+ * false, 0, NaN, "", null and undefined are falsy, everything else is truthy like
+ * in js
+ *
+ * */
+pub(crate) fn build_truthiness<'a>(
+    value: BasicValueEnum<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> IntValue<'a> {
+    match (data_type, value) {
+        (DataType::Boolean, BasicValueEnum::IntValue(value)) => return value,
+
+        // Ordered comparison is false for NaN
+        (DataType::Float, BasicValueEnum::FloatValue(value)) => {
+            return builder.build_float_compare(
+                RealCompareOperator::NotEqual,
+                value,
+                context.f64_type().const_float(0.0),
+                &function_value.get_unique_reg_name(),
+            );
+        }
+
+        (DataType::String, BasicValueEnum::PointerValue(value)) => {
+            let length = build_load_string_length(
+                value,
+                context,
+                builder,
+                &function_value.get_unique_reg_name(),
+            );
+
+            return builder.build_int_compare(
+                IntCompareOperator::NotEqual,
+                length,
+                context.i64_type().const_int(0, false),
+                &function_value.get_unique_reg_name(),
+            );
+        }
+
+        (DataType::Null | DataType::Undefined, _) => return context.i1_type().const_int(0, false),
+
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union)) => {
+            let reg_name = function_value.get_unique_reg_name();
+            let is_truthy = build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                &reg_name,
+                |value, member, function_value| {
+                    let is_truthy =
+                        build_truthiness(value, member, context, builder, function_value);
+                    return is_truthy.as_basic_value_enum();
+                },
+            );
+
+            if let BasicValueEnum::IntValue(is_truthy) = is_truthy {
+                return is_truthy;
+            } else {
+                unreachable!();
+            }
+        }
+
+        _ => return context.i1_type().const_int(1, false),
+    }
+}
@@ -0,0 +1,378 @@
+use ast::{
+    data_type::DataType,
+    expression::{BinaryOperator, Expression, UpdateOperator},
+};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::RealCompareOperator,
+    module::Module,
+    types::traits::BasicTypeTrait,
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_array::build_assignable_element_pointer,
+    build_class::build_field_pointer,
+    build_expression::build_expression,
+    runtime::{build_runtime_call, POW},
+    symbol_table::SymbolTable,
+};
+
+// Bitwise operators work on the number modulo 2^32
+const TWO_TO_THE_32: f64 = 4294967296.0;
+
+/*
+ * Evaluates the arithmetic and bitwise binary operators on numbers, comparison
+ * operators are handled by the caller
+ *
+ * */
+pub(crate) fn build_number_operation<'a>(
+    operator: &BinaryOperator,
+    lhs: FloatValue<'a>,
+    rhs: FloatValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> FloatValue<'a> {
+    match operator {
+        BinaryOperator::Plus => return builder.build_float_add(lhs, rhs, name),
+        BinaryOperator::Minus => return builder.build_float_sub(lhs, rhs, name),
+        BinaryOperator::Star => return builder.build_float_mul(lhs, rhs, name),
+        BinaryOperator::Slash => return builder.build_float_div(lhs, rhs, name),
+
+        // Same as fmod, the result has the sign of the dividend
+        BinaryOperator::Percent => return builder.build_float_rem(lhs, rhs, name),
+
+        BinaryOperator::StarStar => {
+            return build_power(lhs, rhs, context, builder, function_value, module, name)
+        }
+
+        _ => {}
+    }
+
+    let lhs = build_to_int32(lhs, context, builder, function_value);
+    let rhs = build_to_int32(rhs, context, builder, function_value);
+    let reg_name = function_value.get_unique_reg_name();
+
+    let int_value = match operator {
+        BinaryOperator::Ampersand => builder.build_and(lhs, rhs, &reg_name),
+        BinaryOperator::VerticalBar => builder.build_or(lhs, rhs, &reg_name),
+        BinaryOperator::Caret => builder.build_xor(lhs, rhs, &reg_name),
+
+        BinaryOperator::LeftShift
+        | BinaryOperator::RightShift
+        | BinaryOperator::UnsignedRightShift => {
+            // Only the lowest 5 bits of the shift count are used
+            let shift_count =
+                builder.build_and(rhs, context.i32_type().const_int(31, false), &reg_name);
+            let shift_name = function_value.get_unique_reg_name();
+
+            match operator {
+                BinaryOperator::LeftShift => {
+                    builder.build_left_shift(lhs, shift_count, &shift_name)
+                }
+                BinaryOperator::RightShift => {
+                    builder.build_right_shift(lhs, shift_count, true, &shift_name)
+                }
+
+                // Result of >>> is unsigned, e.g. -1 >>> 0 is 4294967295
+                _ => {
+                    let int_value = builder.build_right_shift(lhs, shift_count, false, &shift_name);
+                    return builder.build_ui_to_fp(int_value, context.f64_type(), name);
+                }
+            }
+        }
+
+        _ => panic!("Operator {:?} is not an arithmetic operator", operator),
+    };
+
+    return builder.build_si_to_fp(int_value, context.f64_type(), name);
+}
+
+pub(crate) fn build_bitwise_not<'a>(
+    value: FloatValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> FloatValue<'a> {
+    let i32_type = context.i32_type();
+    let int_value = build_to_int32(value, context, builder, function_value);
+    let int_value = builder.build_xor(
+        int_value,
+        i32_type.const_int(u32::MAX as u64, false),
+        &function_value.get_unique_reg_name(),
+    );
+
+    return builder.build_si_to_fp(int_value, context.f64_type(), name);
+}
+
+/*
+ * ToInt32 of ECMAScript, NaN and Infinity become 0, anything else is truncated and
+ * wrapped into 32 bits
+ *
+ * The remainder is smaller than 2^32, so it converts to an i64 exactly and
+ * truncating that to an i32 wraps it
+ *
+ * */
+fn build_to_int32<'a>(
+    value: FloatValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> IntValue<'a> {
+    let f64_type = context.f64_type();
+
+    // Remainder of NaN and Infinity is NaN
+    let remainder = builder.build_float_rem(
+        value,
+        f64_type.const_float(TWO_TO_THE_32),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_finite = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        remainder,
+        remainder,
+        &function_value.get_unique_reg_name(),
+    );
+    let remainder = builder.build_select(
+        is_finite,
+        remainder.as_basic_value_enum(),
+        f64_type.const_float(0.0).as_basic_value_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    if let BasicValueEnum::FloatValue(remainder) = remainder {
+        let int_value = builder.build_fp_to_si(
+            remainder,
+            context.i64_type(),
+            &function_value.get_unique_reg_name(),
+        );
+
+        return builder.build_int_truncate(
+            int_value,
+            context.i32_type(),
+            &function_value.get_unique_reg_name(),
+        );
+    } else {
+        unreachable!();
+    }
+}
+
+/*
+ * pow of C differs from ** when the base is 1 or -1 and the exponent is NaN or
+ * Infinity, e.g. pow(1, NaN) is 1 but 1 ** NaN is NaN
+ *
+ * */
+fn build_power<'a>(
+    base: FloatValue<'a>,
+    exponent: FloatValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> FloatValue<'a> {
+    let f64_type = context.f64_type();
+
+    let power = build_runtime_call(
+        POW,
+        &[base.as_basic_value_enum(), exponent.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    )
+    .unwrap();
+
+    let is_base_one = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        base,
+        f64_type.const_float(1.0),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_base_minus_one = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        base,
+        f64_type.const_float(-1.0),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_base_one = builder.build_or(
+        is_base_one,
+        is_base_minus_one,
+        &function_value.get_unique_reg_name(),
+    );
+
+    // x - x is NaN for NaN and Infinity and 0 for every other number
+    let difference =
+        builder.build_float_sub(exponent, exponent, &function_value.get_unique_reg_name());
+    let is_exponent_finite = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        difference,
+        f64_type.const_float(0.0),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_exponent_not_finite = builder.build_xor(
+        is_exponent_finite,
+        context.i1_type().const_int(1, false),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let is_nan = builder.build_and(
+        is_base_one,
+        is_exponent_not_finite,
+        &function_value.get_unique_reg_name(),
+    );
+    let power = builder.build_select(
+        is_nan,
+        f64_type.const_float(f64::NAN).as_basic_value_enum(),
+        power,
+        name,
+    );
+
+    if let BasicValueEnum::FloatValue(power) = power {
+        return power;
+    } else {
+        unreachable!();
+    }
+}
+
+/*
+ * Stores the incremented or decremented number back into the variable or the array
+ * element, the argument is evaluated only once
+ *
+ * */
+pub(crate) fn build_update_expression<'a>(
+    operator: &UpdateOperator,
+    prefix: bool,
+    argument: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<FloatValue<'a>, Diagnostic> {
+    let pointer = build_update_target(
+        argument,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+    )?;
+
+    let old_value = builder.build_load(
+        pointer,
+        context.f64_type().as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    if let BasicValueEnum::FloatValue(old_value) = old_value {
+        let one = context.f64_type().const_float(1.0);
+        let reg_name = function_value.get_unique_reg_name();
+        let new_value = match operator {
+            UpdateOperator::Increment => builder.build_float_add(old_value, one, &reg_name),
+            UpdateOperator::Decrement => builder.build_float_sub(old_value, one, &reg_name),
+        };
+        builder.build_store(pointer, new_value);
+
+        if prefix {
+            return Ok(new_value);
+        } else {
+            return Ok(old_value);
+        }
+    } else {
+        unreachable!();
+    }
+}
+
+fn build_update_target<'a>(
+    argument: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<PointerValue<'a>, Diagnostic> {
+    match argument {
+        Expression::IdentExp { name, .. } => return Ok(*symbol_table.get(name).unwrap()),
+
+        Expression::ArrayMemberAccess {
+            array,
+            argument,
+            span: _,
+        } => {
+            let array_value = build_expression(
+                array,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?;
+            let index_value = build_expression(
+                argument,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?;
+
+            if let (
+                Some(BasicValueEnum::PointerValue(array_value)),
+                Some(BasicValueEnum::FloatValue(index_value)),
+            ) = (array_value, index_value)
+            {
+                return Ok(build_assignable_element_pointer(
+                    array_value,
+                    index_value,
+                    &DataType::Float,
+                    true,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                ));
+            } else {
+                unreachable!();
+            }
+        }
+
+        Expression::FieldAccess { object, field, .. } => {
+            let object_value = build_expression(
+                object,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+
+            let (field_pointer, _) = build_field_pointer(
+                object_value,
+                &object.get_data_type(),
+                field,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+            );
+            return Ok(field_pointer);
+        }
+
+        _ => panic!("Cannot update expression {:?}", argument),
+    }
+}
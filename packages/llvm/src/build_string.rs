@@ -0,0 +1,222 @@
+use ast::{data_type::DataType, expression::BinaryOperator};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::IntCompareOperator,
+    module::Module,
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_union::build_union_dispatch,
+    runtime::{
+        build_constant_bytes, build_runtime_call, STRING_COMPARE, STRING_CONCAT, STRING_FROM_BYTES,
+        STRING_FROM_NUMBER,
+    },
+};
+
+/*
+ * Copies the utf-8 bytes of the literal into a new runtime string
+ *
+ * */
+pub(crate) fn build_string_literal<'a>(
+    value: &str,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let bytes_name = format!("{}_bytes", function_value.get_unique_reg_name());
+    let bytes = build_constant_bytes(value.as_bytes(), context, builder, module, &bytes_name);
+    let length = context.i64_type().const_int(value.len() as u64, false);
+
+    let string = build_runtime_call(
+        STRING_FROM_BYTES,
+        &[bytes.as_basic_value_enum(), length.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        name,
+    );
+
+    return expect_string(string);
+}
+
+pub(crate) fn build_string_concat<'a>(
+    left: PointerValue<'a>,
+    right: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let string = build_runtime_call(
+        STRING_CONCAT,
+        &[left.as_basic_value_enum(), right.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        name,
+    );
+
+    return expect_string(string);
+}
+
+/*
+ * Converts value of given data type to string the same way javascript does when
+ * the value is concatenated with a string
+ *
+ * */
+pub(crate) fn build_to_string<'a>(
+    value: BasicValueEnum<'a>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    match (data_type, value) {
+        (DataType::String, BasicValueEnum::PointerValue(string)) => return string,
+
+        (DataType::Float, BasicValueEnum::FloatValue(number)) => {
+            let string = build_runtime_call(
+                STRING_FROM_NUMBER,
+                &[number.as_basic_value_enum()],
+                context,
+                builder,
+                module,
+                &function_value.get_unique_reg_name(),
+            );
+
+            return expect_string(string);
+        }
+
+        (DataType::Boolean, BasicValueEnum::IntValue(boolean)) => {
+            let reg_name = function_value.get_unique_reg_name();
+            let true_string =
+                build_string_literal("true", context, builder, function_value, module, &reg_name);
+
+            let reg_name = function_value.get_unique_reg_name();
+            let false_string =
+                build_string_literal("false", context, builder, function_value, module, &reg_name);
+
+            let string = builder.build_select(
+                boolean,
+                true_string.as_basic_value_enum(),
+                false_string.as_basic_value_enum(),
+                &function_value.get_unique_reg_name(),
+            );
+
+            return expect_string(Some(string));
+        }
+
+        (DataType::Null, _) => {
+            let reg_name = function_value.get_unique_reg_name();
+            return build_string_literal(
+                "null",
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+        }
+
+        (DataType::Undefined, _) => {
+            let reg_name = function_value.get_unique_reg_name();
+            return build_string_literal(
+                "undefined",
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+        }
+
+        (DataType::UnionType { types }, BasicValueEnum::PointerValue(union)) => {
+            let reg_name = function_value.get_unique_reg_name();
+            let string = build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                &reg_name,
+                |value, member, function_value| {
+                    let string =
+                        build_to_string(value, member, context, builder, function_value, module);
+                    return string.as_basic_value_enum();
+                },
+            );
+
+            return expect_string(Some(string));
+        }
+
+        // Parser only lets primitives be converted, see can_convert_to_string
+        (data_type, value) => unreachable!(
+            "Converting value {:?} of data type {:?} to string is not supported",
+            value, data_type
+        ),
+    }
+}
+
+/*
+ * Compares strings by their utf-8 bytes, which gives the same order as javascript
+ * for strings without surrogate pairs
+ *
+ * */
+pub(crate) fn build_string_comparison<'a>(
+    operator: &BinaryOperator,
+    left: PointerValue<'a>,
+    right: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    let order = build_runtime_call(
+        STRING_COMPARE,
+        &[left.as_basic_value_enum(), right.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    );
+
+    let order = match order {
+        Some(BasicValueEnum::IntValue(order)) => order,
+        _ => panic!("Expected string comparison to return an integer"),
+    };
+
+    let compare_operator = match operator {
+        BinaryOperator::StrictEquality => IntCompareOperator::Equal,
+        BinaryOperator::StrictNotEqual => IntCompareOperator::NotEqual,
+        BinaryOperator::LessThan => IntCompareOperator::SignedLessThan,
+        BinaryOperator::LessThanOrEqual => IntCompareOperator::SignedLessThanOrEqual,
+        BinaryOperator::GreaterThan => IntCompareOperator::SignedGreaterThan,
+        BinaryOperator::GreaterThanOrEqual => IntCompareOperator::SignedGreaterThanOrEqual,
+
+        _ => panic!("Operator {:?} cannot be used to compare strings", operator),
+    };
+
+    return builder.build_int_compare(
+        compare_operator,
+        order,
+        context.i32_type().const_int(0, false),
+        name,
+    );
+}
+
+fn expect_string<'a>(value: Option<BasicValueEnum<'a>>) -> PointerValue<'a> {
+    if let Some(BasicValueEnum::PointerValue(string)) = value {
+        return string;
+    } else {
+        panic!("Expected runtime string to be BasicValueEnum::PointerValue");
+    }
+}
@@ -0,0 +1,566 @@
+use ast::data_type::{is_assignable, DataType};
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    enums::IntCompareOperator,
+    module::Module,
+    types::{
+        enums::{AddressSpace, BasicTypeEnum},
+        ptr_type::PointerType,
+        struct_type::StructType,
+        traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_any_object::build_any_object_value,
+    build_array::holds_pointers,
+    build_string::build_string_literal,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        expect_pointer,
+        gc::{build_gc_alloc, build_static_gc_object},
+    },
+};
+
+const UNDEFINED_BOX: &str = "|union|undefined_box|";
+
+pub(crate) const TAG_FIELD: u64 = 0;
+pub(crate) const PAYLOAD_FIELD: u64 = 1;
+
+// Size of the box and offset of the payload in bytes
+pub(crate) const BOX_SIZE: u64 = 16;
+pub(crate) const PAYLOAD_OFFSET: u64 = 8;
+
+/*
+ * Value of a union is a pointer to a box holding the tag of the member and the
+ * value of the member
+ *
+ *      { i64 tag, i64 payload }
+ *
+ * Tag is the index of the member in the union, see get_tag. Payload is stored as
+ * the data type of the member, every member fits in 8 bytes
+ *
+ * */
+pub(crate) fn get_union_type<'a>(context: &'a Context) -> StructType<'a> {
+    let field_types = vec![
+        context.i64_type().as_basic_type_enum(),
+        context.i64_type().as_basic_type_enum(),
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+pub(crate) fn get_union_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return get_union_type(context).ptr_type(AddressSpace::Generic);
+}
+
+/*
+ * Index of the member in the union, members of every union are sorted the same
+ * way so the unions of the same members agree on the tags, see create_union
+ *
+ * */
+pub(crate) fn get_tag(member: &DataType, types: &[DataType]) -> u64 {
+    return types
+        .iter()
+        .position(|other| other.has_same_layout(member))
+        .unwrap_or_else(|| panic!("Data type {:?} is not a member of {:?}", member, types))
+        as u64;
+}
+
+/*
+ * Converts the value between a union and its members, see Expression::UnionConversion
+ *
+ * */
+pub(crate) fn build_union_conversion<'a>(
+    value: BasicValueEnum<'a>,
+    from_data_type: &DataType,
+    to_data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    match (from_data_type, to_data_type, value) {
+        (DataType::UnionType { .. }, DataType::UnionType { .. }, value)
+            if from_data_type.has_same_layout(to_data_type) =>
+        {
+            return value;
+        }
+
+        (
+            DataType::UnionType { types },
+            DataType::UnionType { types: to_types },
+            BasicValueEnum::PointerValue(union),
+        ) => {
+            return build_union_retag(
+                union,
+                types,
+                to_types,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+
+        /*
+         * Union narrowed by `instanceof` to the class can hold it as an `object`,
+         * see narrowing of the parser
+         *
+         * */
+        (DataType::UnionType { types }, member, BasicValueEnum::PointerValue(union))
+            if types.contains(&DataType::Object)
+                && matches!(member, DataType::ClassType { .. }) =>
+        {
+            return build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |payload, from_member, function_value| match from_member {
+                    DataType::Object => {
+                        let reg_name = function_value.get_unique_reg_name();
+                        return build_any_object_value(
+                            expect_pointer(payload),
+                            member,
+                            context,
+                            builder,
+                            &reg_name,
+                        );
+                    }
+                    _ => {
+                        let reg_name = function_value.get_unique_reg_name();
+                        return build_union_unbox(
+                            union,
+                            member,
+                            context,
+                            builder,
+                            function_value,
+                            &reg_name,
+                        );
+                    }
+                },
+            );
+        }
+
+        (DataType::UnionType { .. }, member, BasicValueEnum::PointerValue(union)) => {
+            return build_union_unbox(union, member, context, builder, function_value, name);
+        }
+
+        (member, DataType::UnionType { types }, value) => {
+            let union = build_union_box(
+                value,
+                member,
+                types,
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+            return union.as_basic_value_enum();
+        }
+
+        (from_data_type, to_data_type, _) => panic!(
+            "Cannot convert data type {:?} to {:?}, neither of them is a union",
+            from_data_type, to_data_type
+        ),
+    }
+}
+
+/*
+ * Boxes the members of one union into another union, the tag of a member can be
+ * another one there
+ *
+ * Union narrowed by `instanceof` holds the checked class where the other union
+ * holds its ancestor or an `object`, see narrowing of the parser. A member the
+ * narrowed union does not have cannot be held by the value, the box is returned
+ * as is for it
+ *
+ * */
+fn build_union_retag<'a>(
+    union: PointerValue<'a>,
+    types: &[DataType],
+    to_types: &[DataType],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    return build_union_dispatch(
+        union,
+        types,
+        context,
+        builder,
+        function_value,
+        name,
+        |payload, member, function_value| {
+            // The checked class is the one the other classes it narrows to extend
+            let to_member = to_types
+                .iter()
+                .find(|to_member| to_member.has_same_layout(member))
+                .or_else(|| {
+                    let to_members: Vec<&DataType> = to_types
+                        .iter()
+                        .filter(|to_member| is_assignable(to_member, member))
+                        .collect();
+
+                    return to_members.iter().copied().find(|to_member| {
+                        to_members
+                            .iter()
+                            .all(|other| is_assignable(other, to_member))
+                    });
+                });
+
+            let to_member = match to_member {
+                Some(to_member) => to_member,
+                None => return union.as_basic_value_enum(),
+            };
+
+            let value = match member {
+                DataType::Object if to_member != member => {
+                    let reg_name = function_value.get_unique_reg_name();
+                    build_any_object_value(
+                        expect_pointer(payload),
+                        to_member,
+                        context,
+                        builder,
+                        &reg_name,
+                    )
+                }
+                _ => payload,
+            };
+
+            let reg_name = function_value.get_unique_reg_name();
+            let union = build_union_box(
+                value,
+                to_member,
+                to_types,
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            );
+            return union.as_basic_value_enum();
+        },
+    );
+}
+
+fn build_union_box<'a>(
+    value: BasicValueEnum<'a>,
+    member: &DataType,
+    types: &[DataType],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let pointer_offsets = if holds_pointers(member) {
+        vec![PAYLOAD_OFFSET]
+    } else {
+        vec![]
+    };
+
+    let raw_pointer = build_gc_alloc(
+        context.i64_type().const_int(BOX_SIZE, false),
+        &pointer_offsets,
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    );
+    let union = builder.build_bitcast(raw_pointer, get_union_pointer_type(context), name);
+
+    let tag_pointer = build_field_pointer(union, TAG_FIELD, context, builder, function_value);
+    builder.build_store(
+        tag_pointer,
+        context.i64_type().const_int(get_tag(member, types), false),
+    );
+
+    let payload_pointer = build_payload_pointer(union, member, context, builder, function_value);
+    builder.build_store(payload_pointer, value);
+
+    return union;
+}
+
+/*
+ * Box holding undefined which is shared by every union with undefined at the same
+ * index, for places which need the value without allocating it, e.g. reading past
+ * the end of an array
+ *
+ * */
+pub(crate) fn build_undefined_union<'a>(
+    types: &[DataType],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let tag = get_tag(&DataType::Undefined, types);
+    let raw_pointer = build_static_gc_object(
+        &format!("{}{}|", UNDEFINED_BOX, tag),
+        BOX_SIZE,
+        context,
+        builder,
+        module,
+        &function_value.get_unique_reg_name(),
+    );
+    let union = builder.build_bitcast(raw_pointer, get_union_pointer_type(context), name);
+
+    // Global of the box starts zeroed, payload of undefined is never read
+    let tag_pointer = build_field_pointer(union, TAG_FIELD, context, builder, function_value);
+    builder.build_store(tag_pointer, context.i64_type().const_int(tag, false));
+
+    return union;
+}
+
+/*
+ * Parser only unboxes a union narrowed to the member, so the box is known to hold it
+ *
+ * */
+fn build_union_unbox<'a>(
+    union: PointerValue<'a>,
+    member: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> BasicValueEnum<'a> {
+    let payload_pointer = build_payload_pointer(union, member, context, builder, function_value);
+
+    return builder.build_load(payload_pointer, member.force_to_basic_type(context), name);
+}
+
+/*
+ *  entry:
+ *      switch <tag>, member_0, [<tag_1>, member_1], ...
+ *
+ *  member_i:
+ *      <value_i> = build_member(<payload as member_i>, member_i)
+ *      br end
+ *
+ *  end:
+ *      phi [<value_0>, member_0], [<value_1>, member_1], ...
+ *
+ * Every value built by build_member has to be of the same type
+ *
+ * */
+pub(crate) fn build_union_dispatch<'a, F>(
+    union: PointerValue<'a>,
+    types: &[DataType],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+    mut build_member: F,
+) -> BasicValueEnum<'a>
+where
+    F: FnMut(BasicValueEnum<'a>, &DataType, &mut FunctionValue) -> BasicValueEnum<'a>,
+{
+    let tag = build_load_tag(union, context, builder, function_value);
+
+    let member_blocks: Vec<BasicBlock> = types
+        .iter()
+        .map(|_| {
+            let member_block_name = function_value.get_unique_block_name();
+            return context.append_basic_block(function_value, member_block_name.as_str());
+        })
+        .collect();
+
+    let end_block_name = function_value.get_unique_block_name();
+    let end_block_bb = context.append_basic_block(function_value, end_block_name.as_str());
+
+    // Tag is always one of the members, so the first member doubles as the default
+    let cases: Vec<(IntValue, &BasicBlock)> = types
+        .iter()
+        .zip(member_blocks.iter())
+        .skip(1)
+        .map(|(member, member_block)| {
+            return (
+                context.i64_type().const_int(get_tag(member, types), false),
+                member_block,
+            );
+        })
+        .collect();
+    builder.build_switch(tag, &member_blocks[0], &cases);
+
+    let mut incoming: Vec<(BasicValueEnum, BasicBlock)> = vec![];
+    for (member, member_block) in types.iter().zip(member_blocks.iter()) {
+        builder.position_at_end(member_block);
+
+        let reg_name = function_value.get_unique_reg_name();
+        let payload = build_union_unbox(union, member, context, builder, function_value, &reg_name);
+        let value = build_member(payload, member, function_value);
+
+        // Building the member can create blocks of its own
+        incoming.push((value, builder.get_insert_block()));
+        builder.build_unconditional_branch(&end_block_bb);
+    }
+
+    builder.position_at_end(&end_block_bb);
+    let incoming: Vec<(BasicValueEnum, &BasicBlock)> = incoming
+        .iter()
+        .map(|(value, block_bb)| (value.clone(), block_bb))
+        .collect();
+    let value_type: BasicTypeEnum = incoming[0].0.get_type();
+    return builder.build_phi(value_type, &incoming, name);
+}
+
+/*
+ * Whether the union holds null or undefined, used by `??` and `?.`
+ *
+ * */
+pub(crate) fn build_is_nullish<'a>(
+    union: PointerValue<'a>,
+    types: &[DataType],
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    name: &str,
+) -> IntValue<'a> {
+    let tag = build_load_tag(union, context, builder, function_value);
+
+    let mut is_nullish = context.i1_type().const_int(0, false);
+    for member in types.iter().filter(|member| member.is_nullish()) {
+        let is_member = builder.build_int_compare(
+            IntCompareOperator::Equal,
+            tag,
+            context.i64_type().const_int(get_tag(member, types), false),
+            &function_value.get_unique_reg_name(),
+        );
+        is_nullish = builder.build_or(is_nullish, is_member, name);
+    }
+
+    return is_nullish;
+}
+
+/*
+ * `typeof` of a union depends on the member it holds, for anything else it is
+ * known at compile time
+ *
+ * */
+pub(crate) fn build_typeof<'a>(
+    value: Option<BasicValueEnum<'a>>,
+    data_type: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    match (data_type, value) {
+        (DataType::UnionType { types }, Some(BasicValueEnum::PointerValue(union))) => {
+            let typeof_name = build_union_dispatch(
+                union,
+                types,
+                context,
+                builder,
+                function_value,
+                name,
+                |_, member, function_value| {
+                    let reg_name = function_value.get_unique_reg_name();
+                    let typeof_name = build_string_literal(
+                        member.get_typeof_name().unwrap(),
+                        context,
+                        builder,
+                        function_value,
+                        module,
+                        &reg_name,
+                    );
+
+                    return typeof_name.as_basic_value_enum();
+                },
+            );
+
+            if let BasicValueEnum::PointerValue(typeof_name) = typeof_name {
+                return typeof_name;
+            } else {
+                unreachable!();
+            }
+        }
+
+        (data_type, _) => {
+            return build_string_literal(
+                data_type.get_typeof_name().unwrap(),
+                context,
+                builder,
+                function_value,
+                module,
+                name,
+            );
+        }
+    }
+}
+
+fn build_load_tag<'a>(
+    union: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> IntValue<'a> {
+    let tag_pointer = build_field_pointer(union, TAG_FIELD, context, builder, function_value);
+    let tag = builder.build_load(
+        tag_pointer,
+        context.i64_type().as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    if let BasicValueEnum::IntValue(tag) = tag {
+        return tag;
+    } else {
+        unreachable!();
+    }
+}
+
+fn build_field_pointer<'a>(
+    union: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> PointerValue<'a> {
+    let indices = vec![
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(
+        get_union_type(context),
+        &union,
+        &indices,
+        &function_value.get_unique_reg_name(),
+    );
+}
+
+fn build_payload_pointer<'a>(
+    union: PointerValue<'a>,
+    member: &DataType,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> PointerValue<'a> {
+    let payload_pointer =
+        build_field_pointer(union, PAYLOAD_FIELD, context, builder, function_value);
+
+    return builder.build_bitcast(
+        payload_pointer,
+        member
+            .force_to_basic_type(context)
+            .ptr_type(AddressSpace::Generic),
+        &function_value.get_unique_reg_name(),
+    );
+}
@@ -3,7 +3,13 @@ use inkwell::{
     builder::Builder, context::Context, module::Module, values::fn_value::FunctionValue,
 };
 
-use crate::{gen_ast::consume_ast_in_module, symbol_table::SymbolTable};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    gc_frame::{build_gc_safepoint, GcFrame},
+    gen_ast::consume_ast_in_module,
+    symbol_table::SymbolTable,
+};
 
 pub struct Codegen<'a> {
     content: &'a Vec<AstPtr>,
@@ -24,7 +30,13 @@ impl<'a> Codegen<'a> {
         builder: &'a Builder,
         module: &'a Module,
         function_value: &mut FunctionValue,
-    ) {
+    ) -> Result<(), Diagnostic> {
+        let gc_frame = GcFrame::new(context, function_value, module);
+        let body = context.append_basic_block(function_value, "body");
+        builder.position_at_end(&body);
+        build_gc_safepoint(context, builder, module);
+        self.symbol_table.gc_frame = Some(gc_frame);
+
         consume_ast_in_module(
             self.content,
             context,
@@ -32,6 +44,13 @@ impl<'a> Codegen<'a> {
             function_value,
             &mut self.symbol_table,
             module,
-        );
+        )?;
+
+        let gc_frame = self.symbol_table.get_gc_frame();
+        gc_frame.build_end(context, builder, module);
+        builder.build_return(Some(&context.i32_type().const_int(0, false)));
+        gc_frame.build_reserve(&body, context, module);
+
+        return Ok(());
     }
 }
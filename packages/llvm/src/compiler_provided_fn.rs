@@ -3,13 +3,24 @@ use inkwell::{
     context::Context,
     enums::InlineAsmSyntax,
     module::Module,
-    types::{enums::AddressSpace, traits::BasicTypeTrait},
+    types::traits::BasicTypeTrait,
     values::{enums::BasicValueEnum, traits::BasicValueTrait},
 };
 
-pub fn get_compiler_provided_module<'a>(context: &'a Context) -> Module<'a> {
+use crate::runtime::{
+    build_runtime,
+    string::{build_load_string_buffer, get_string_pointer_type},
+};
+
+/*
+ * In strict mode reading an element past the end of an array is a RangeError
+ * instead of reading as undefined
+ *
+ * */
+pub fn get_compiler_provided_module<'a>(context: &'a Context, strict_mode: bool) -> Module<'a> {
     let module = context.create_module("compilerInternal");
     build_syscall_print(context, &module);
+    build_runtime(context, &module, strict_mode);
 
     return module;
 }
@@ -18,10 +29,7 @@ fn build_syscall_print<'a>(context: &'a Context, module: &'a Module<'a>) {
     let fn_type = context.void_type().fn_type(
         &[
             context.f64_type().as_basic_type_enum(),
-            context
-                .i8_type()
-                .ptr_type(AddressSpace::Generic)
-                .as_basic_type_enum(),
+            get_string_pointer_type(context).as_basic_type_enum(),
             context.f64_type().as_basic_type_enum(),
         ],
         false,
@@ -44,12 +52,13 @@ fn build_syscall_print<'a>(context: &'a Context, module: &'a Module<'a>) {
                     builder.build_float_to_signed_int(param_1, context.i64_type(), "rdi_int");
                 let size_int =
                     builder.build_float_to_signed_int(param_3, context.i64_type(), "size_int");
+                let buffer = build_load_string_buffer(param_2, context, &builder, "buffer");
 
                 let asm_type = context.void_type().fn_type(
                     &[
                         context.i64_type().as_basic_type_enum(),
                         context.i64_type().as_basic_type_enum(),
-                        param_2.get_type().as_basic_type_enum(),
+                        buffer.get_type().as_basic_type_enum(),
                         context.i64_type().as_basic_type_enum(),
                     ],
                     false,
@@ -68,7 +77,7 @@ fn build_syscall_print<'a>(context: &'a Context, module: &'a Module<'a>) {
                     &[
                         context.i64_type().const_int(1, true).as_basic_value_enum(),
                         rdi_int.as_basic_value_enum(),
-                        param_2.as_basic_value_enum(),
+                        buffer.as_basic_value_enum(),
                         size_int.as_basic_value_enum(),
                     ],
                     "",
@@ -0,0 +1,230 @@
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    module::Module,
+    types::{enums::AddressSpace, ptr_type::PointerType, traits::BasicTypeTrait},
+    values::{fn_value::FunctionValue, ptr_value::PointerValue, traits::BasicValueTrait},
+};
+
+use crate::runtime::{
+    build_runtime_call, build_runtime_call_returning_pointer, get_byte_pointer_type, GC_FRAME_END,
+    GC_FRAME_RESERVE, GC_FRAME_START, GC_SAFEPOINT,
+};
+
+/*
+ * Root slots of a single function call on the shadow stack of the garbage collector
+ *
+ * Every function starts with a prologue block which holds all the allocas and the
+ * slots of the frame, so a variable declared inside a loop reuses the same stack
+ * memory and the same slot on every iteration. Every string, array and object the
+ * function holds, including the intermediate values of expressions, is stored
+ * into its own slot before anything which could collect runs
+ *
+ * The number of slots is known only after the whole body is generated, so the
+ * prologue is finished by `build_reserve` at the very end
+ *
+ * */
+#[derive(Debug)]
+pub(crate) struct GcFrame<'a> {
+    prologue: BasicBlock<'a>,
+    frame: PointerValue<'a>,
+    slot_count: u64,
+    // Holds the end of the frame once build_restore needs it
+    top: Option<PointerValue<'a>>,
+}
+
+impl<'a> GcFrame<'a> {
+    /*
+     * Appends the prologue block to the function, the body of the function has to
+     * be generated in a block appended after it
+     *
+     * */
+    pub(crate) fn new(
+        context: &'a Context,
+        function_value: &FunctionValue,
+        module: &'a Module,
+    ) -> Self {
+        let prologue = context.append_basic_block(function_value, "entry");
+        let builder = context.create_builder();
+        builder.position_at_end(&prologue);
+
+        let frame = build_runtime_call_returning_pointer(
+            GC_FRAME_START,
+            &[],
+            context,
+            &builder,
+            module,
+            "gc_frame",
+        );
+
+        return GcFrame {
+            prologue,
+            frame,
+            slot_count: 0,
+            top: None,
+        };
+    }
+
+    fn create_prologue_builder(&self, context: &'a Context) -> Builder<'a> {
+        let builder = context.create_builder();
+        builder.position_at_end(&self.prologue);
+
+        return builder;
+    }
+
+    /*
+     * Allocates stack memory once per call instead of once per execution of the
+     * declaration
+     *
+     * */
+    pub(crate) fn build_alloca<T: BasicTypeTrait<'a>>(
+        &self,
+        ty: T,
+        context: &'a Context,
+        name: &str,
+    ) -> PointerValue<'a> {
+        return self.create_prologue_builder(context).build_alloca(ty, name);
+    }
+
+    /*
+     * Returns pointer to a new slot which holds values of `pointer_type`
+     *
+     * */
+    pub(crate) fn build_root_slot(
+        &mut self,
+        pointer_type: PointerType<'a>,
+        context: &'a Context,
+        name: &str,
+    ) -> PointerValue<'a> {
+        let builder = self.create_prologue_builder(context);
+
+        let slot = builder.build_gep_2(
+            get_byte_pointer_type(context),
+            &self.frame,
+            &[context.i64_type().const_int(self.slot_count, false)],
+            &format!("{}_slot", name),
+        );
+        self.slot_count += 1;
+
+        return builder.build_bitcast(
+            slot,
+            pointer_type.ptr_type(AddressSpace::Generic),
+            &format!("{}_slot", name),
+        );
+    }
+
+    /*
+     * Keeps the value alive till the function returns
+     *
+     * */
+    pub(crate) fn build_root(
+        &mut self,
+        value: PointerValue<'a>,
+        context: &'a Context,
+        builder: &Builder<'a>,
+        name: &str,
+    ) {
+        let slot = self.build_root_slot(value.get_type(), context, name);
+        builder.build_store(slot, value);
+    }
+
+    /*
+     * Releases the frame, must be called before every return of the function
+     *
+     * */
+    pub(crate) fn build_end(
+        &self,
+        context: &'a Context,
+        builder: &Builder<'a>,
+        module: &'a Module,
+    ) {
+        build_runtime_call(
+            GC_FRAME_END,
+            &[self.frame.as_basic_value_enum()],
+            context,
+            builder,
+            module,
+            "",
+        );
+    }
+
+    /*
+     * Releases the frames of the calls an exception unwound through, must be called
+     * at the start of every landing pad. The end of this frame is known only after
+     * the whole body is generated, so build_reserve stores it for the landing pads
+     *
+     * */
+    pub(crate) fn build_restore(
+        &mut self,
+        context: &'a Context,
+        builder: &Builder<'a>,
+        module: &'a Module,
+    ) {
+        let root_pointer_type = get_byte_pointer_type(context).ptr_type(AddressSpace::Generic);
+
+        let top = match self.top {
+            Some(top) => top,
+            None => {
+                let top = self.build_alloca(root_pointer_type.clone(), context, "gc_frame_top");
+                self.top = Some(top);
+                top
+            }
+        };
+
+        let top = builder.build_load(top, root_pointer_type.as_basic_type_enum(), "gc_frame_top");
+        build_runtime_call(GC_FRAME_END, &[top], context, builder, module, "");
+    }
+
+    /*
+     * Reserves every slot handed out and jumps from the prologue to the body
+     *
+     * */
+    pub(crate) fn build_reserve(
+        &self,
+        body: &BasicBlock<'a>,
+        context: &'a Context,
+        module: &'a Module,
+    ) {
+        let builder = self.create_prologue_builder(context);
+
+        build_runtime_call(
+            GC_FRAME_RESERVE,
+            &[
+                self.frame.as_basic_value_enum(),
+                context
+                    .i64_type()
+                    .const_int(self.slot_count, false)
+                    .as_basic_value_enum(),
+            ],
+            context,
+            &builder,
+            module,
+            "",
+        );
+
+        if let Some(top) = self.top {
+            let end = builder.build_gep_2(
+                get_byte_pointer_type(context),
+                &self.frame,
+                &[context.i64_type().const_int(self.slot_count, false)],
+                "gc_frame_end",
+            );
+            builder.build_store(top, end);
+        }
+        builder.build_unconditional_branch(body);
+    }
+}
+
+/*
+ * Gives the collector a chance to run, emitted at the start of every function body
+ * and every loop iteration so a long running loop cannot outgrow the heap
+ *
+ * */
+pub(crate) fn build_gc_safepoint<'a>(
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+) {
+    build_runtime_call(GC_SAFEPOINT, &[], context, builder, module, "");
+}
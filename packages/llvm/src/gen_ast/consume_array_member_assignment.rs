@@ -5,10 +5,11 @@ use inkwell::{
     module::Module,
     values::{enums::BasicValueEnum, fn_value::FunctionValue},
 };
+use lexer::diagnostic::Diagnostic;
 
 use crate::{
-    build_assignment::build_assignment, build_expression::build_expression,
-    symbol_table::SymbolTable,
+    build_array::build_assignable_element_pointer, build_assignment::build_assignment,
+    build_expression::build_expression, symbol_table::SymbolTable,
 };
 
 pub(crate) fn consume_array_member_assignments<'a>(
@@ -21,9 +22,8 @@ pub(crate) fn consume_array_member_assignments<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     let var_ptr = symbol_table.get(ident_name).unwrap().clone();
-    let array_data_type = var_ptr.get_type().into_array_type().unwrap();
 
     let member_access_value = build_expression(
         member_access_exp,
@@ -33,27 +33,15 @@ pub(crate) fn consume_array_member_assignments<'a>(
         symbol_table,
         module,
         None,
-    )
+    )?
     .unwrap();
 
     if let BasicValueEnum::FloatValue(member_access_value) = member_access_value {
-        let converted_member_access_value = builder.build_fp_to_si(
-            member_access_value,
-            context.i64_type(),
-            function_value.get_unique_reg_name().as_str(),
-        );
-
-        let indices = vec![
-            context.i64_type().const_int(0, true),
-            converted_member_access_value,
-        ];
-
-        let member_var_ptr = builder.build_gep_2(
-            array_data_type,
-            &var_ptr,
-            &indices,
-            function_value.get_unique_reg_name().as_str(),
-        );
+        /*
+         * The value is evaluated before the slot is looked up, as assigning at the
+         * length grows the array and the value could read the array
+         *
+         * */
         let value = build_expression(
             exp,
             context,
@@ -62,9 +50,22 @@ pub(crate) fn consume_array_member_assignments<'a>(
             symbol_table,
             module,
             None,
-        )
+        )?
         .unwrap();
 
+        let is_compound = !matches!(operator, VariableAssignmentOperator::Assign);
+        let member_var_ptr = build_assignable_element_pointer(
+            var_ptr,
+            member_access_value,
+            &exp.get_data_type(),
+            is_compound,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        );
+
         build_assignment(
             &member_var_ptr,
             &value,
@@ -73,8 +74,11 @@ pub(crate) fn consume_array_member_assignments<'a>(
             builder,
             function_value,
             symbol_table,
+            module,
         );
     } else {
         unreachable!();
     }
+
+    return Ok(());
 }
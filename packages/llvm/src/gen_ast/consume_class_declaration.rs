@@ -0,0 +1,57 @@
+use ast::{class::Class, declaration::Declaration, Ast, AstPtr};
+use inkwell::{builder::Builder, context::Context, module::Module};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_class::{build_class_definition, declare_class},
+    build_closure::build_store_captures_in_globals,
+    symbol_table::SymbolTable,
+};
+
+use super::consume_function_declaration::consume_function_declaration;
+
+/*
+ * Functions of the class are declared first, as the constructor and the methods
+ * can call each other and create instances of the class
+ *
+ * */
+pub(crate) fn consume_class_declaration<'a>(
+    class: &Class,
+    functions: &[AstPtr],
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    declare_class(class, context, symbol_table, module);
+    build_class_definition(class, context, symbol_table, module);
+
+    for function in functions {
+        if let Ast::Declaration(Declaration::FunctionDeclaration {
+            arguments,
+            blocks,
+            ident_name,
+            return_type,
+            captures,
+            span: _,
+        }) = function
+        {
+            consume_function_declaration(
+                arguments,
+                blocks,
+                ident_name,
+                return_type,
+                captures,
+                false,
+                context,
+                module,
+                symbol_table,
+            )?;
+            build_store_captures_in_globals(captures, context, builder, symbol_table, module);
+        } else {
+            unreachable!();
+        }
+    }
+
+    return Ok(());
+}
@@ -1,12 +1,12 @@
 use ast::{expression::Expression, AstPtr};
 use inkwell::{
-    builder::Builder,
-    context::Context,
-    module::Module,
-    values::{enums::BasicValueEnum, fn_value::FunctionValue},
+    builder::Builder, context::Context, module::Module, values::fn_value::FunctionValue,
 };
+use lexer::diagnostic::Diagnostic;
 
-use crate::{build_expression::build_expression, symbol_table::SymbolTable};
+use crate::{
+    build_logical::build_condition, gc_frame::build_gc_safepoint, symbol_table::SymbolTable,
+};
 
 use super::consume_ast_in_loop;
 
@@ -18,7 +18,7 @@ pub(crate) fn consume_do_while_loop<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     let do_while_block_name = function_value.get_unique_block_name();
     let do_while_block = context.append_basic_block(function_value, &do_while_block_name);
 
@@ -31,6 +31,7 @@ pub(crate) fn consume_do_while_loop<'a>(
 
     builder.build_unconditional_branch(&do_while_block);
     builder.position_at_end(&do_while_block);
+    build_gc_safepoint(context, builder, module);
 
     consume_ast_in_loop(
         block,
@@ -41,25 +42,21 @@ pub(crate) fn consume_do_while_loop<'a>(
         &exit_block_bb,
         &condition_checker_block_bb,
         module,
-    );
+    )?;
     builder.build_unconditional_branch(&condition_checker_block_bb);
 
     builder.position_at_end(&condition_checker_block_bb);
-    let condition_value = build_expression(
+    let cond_value = build_condition(
         condition,
         context,
         builder,
         function_value,
         symbol_table,
         module,
-        None,
-    )
-    .unwrap();
+    )?;
+
+    builder.build_conditional_branch(cond_value, &do_while_block, &exit_block_bb);
+    builder.position_at_end(&exit_block_bb);
 
-    if let BasicValueEnum::IntValue(cond_value) = condition_value {
-        builder.build_conditional_branch(cond_value, &do_while_block, &exit_block_bb);
-        builder.position_at_end(&exit_block_bb);
-    } else {
-        todo!()
-    }
+    return Ok(());
 }
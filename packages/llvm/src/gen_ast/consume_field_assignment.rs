@@ -0,0 +1,68 @@
+use ast::{declaration::VariableAssignmentOperator, expression::Expression};
+use inkwell::{
+    builder::Builder, context::Context, module::Module, values::fn_value::FunctionValue,
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_assignment::build_assignment, build_class::build_field_pointer,
+    build_expression::build_expression, symbol_table::SymbolTable,
+};
+
+// Object is evaluated before the value, like for `<object>.<field> = <exp>` in js
+pub(crate) fn consume_field_assignment<'a>(
+    object: &Expression,
+    field: &str,
+    operator: &VariableAssignmentOperator,
+    exp: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let object_value = build_expression(
+        object,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    let value = build_expression(
+        exp,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    let (field_pointer, _) = build_field_pointer(
+        object_value,
+        &object.get_data_type(),
+        field,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+    );
+
+    build_assignment(
+        &field_pointer,
+        &value,
+        operator,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+    );
+
+    return Ok(());
+}
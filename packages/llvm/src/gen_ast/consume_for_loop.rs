@@ -0,0 +1,649 @@
+use ast::{
+    data_type::DataType,
+    declaration::{Declaration, VariableDeclarationKind},
+    expression::Expression,
+    Ast, AstPtr,
+};
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    enums::IntCompareOperator,
+    module::Module,
+    types::traits::BasicTypeTrait,
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_array::{build_array_element, build_array_literal},
+    build_closure::{build_cell, get_closure_pointer_type, is_stored_in_cell},
+    build_expression::build_expression,
+    build_logical::build_condition,
+    build_string::build_string_literal,
+    build_union::get_union_pointer_type,
+    gc_frame::build_gc_safepoint,
+    llvm_utils::LLVMUtils,
+    runtime::{
+        array::build_load_array_length,
+        build_runtime_call_returning_pointer, get_byte_pointer_type,
+        string::{build_load_string_length, get_string_pointer_type},
+        STRING_CODE_POINT_AT,
+    },
+    symbol_table::SymbolTable,
+};
+
+use super::{consume_ast_in_loop, consume_single_ast};
+
+/*
+ *  <initializer>
+ *  br condition
+ *
+ *  condition:
+ *      br <condition>, body, exit
+ *
+ *  body:
+ *      <block>
+ *      br update
+ *
+ *  update:
+ *      <update>
+ *      br condition
+ *
+ *  exit:
+ *
+ * `continue` jumps to the update so it still runs before the next check
+ *
+ * Variable declared by `let` in the initializer is a new variable in every
+ * iteration. When a closure captures it the cell of the current iteration is kept
+ * in a slot and loaded by the condition, the update copies the value to a fresh
+ * cell so that closures created by the iteration keep the value they saw
+ *
+ * */
+pub(crate) fn consume_for_loop<'a>(
+    initializer: &Option<AstPtr>,
+    condition: &Option<Expression>,
+    update: &Option<AstPtr>,
+    block: &Vec<AstPtr>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    if let Some(initializer) = initializer {
+        consume_single_ast(
+            initializer,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?;
+    }
+
+    let captured_variable = match initializer {
+        Some(Ast::Declaration(Declaration::VariableDeclaration {
+            ident_name,
+            exp,
+            kind: VariableDeclarationKind::Let,
+            is_captured: true,
+            span: _,
+        })) if is_stored_in_cell(&exp.get_data_type()) => {
+            let cell = *symbol_table.get(ident_name).unwrap();
+            let cell_slot =
+                symbol_table
+                    .get_gc_frame()
+                    .build_root_slot(cell.get_type(), context, ident_name);
+            builder.build_store(cell_slot, cell);
+
+            Some((ident_name, exp.get_data_type(), cell_slot))
+        }
+        _ => None,
+    };
+
+    let condition_block_name = function_value.get_unique_block_name();
+    let condition_block_bb =
+        context.append_basic_block(function_value, condition_block_name.as_str());
+
+    let for_block_name = function_value.get_unique_block_name();
+    let for_block_bb = context.append_basic_block(function_value, for_block_name.as_str());
+
+    let update_block_name = function_value.get_unique_block_name();
+    let update_block_bb = context.append_basic_block(function_value, update_block_name.as_str());
+
+    let exit_block_name = function_value.get_unique_block_name();
+    let exit_block_bb = context.append_basic_block(function_value, exit_block_name.as_str());
+
+    builder.build_unconditional_branch(&condition_block_bb);
+    builder.position_at_end(&condition_block_bb);
+    build_gc_safepoint(context, builder, module);
+
+    if let Some((ident_name, _, cell_slot)) = &captured_variable {
+        let cell_type = symbol_table.get(ident_name).unwrap().get_type();
+        let cell = builder.build_load(*cell_slot, cell_type.as_basic_type_enum(), ident_name);
+
+        if let BasicValueEnum::PointerValue(cell) = cell {
+            symbol_table.insert_local(ident_name.to_string(), cell);
+        } else {
+            unreachable!();
+        }
+    }
+
+    match condition {
+        Some(condition) => {
+            let cond_value = build_condition(
+                condition,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?;
+
+            builder.build_conditional_branch(cond_value, &for_block_bb, &exit_block_bb);
+        }
+
+        // `for (;;)` only exits by break
+        None => {
+            builder.build_unconditional_branch(&for_block_bb);
+        }
+    }
+
+    builder.position_at_end(&for_block_bb);
+    consume_ast_in_loop(
+        block,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        &exit_block_bb,
+        &update_block_bb,
+        module,
+    )?;
+    builder.build_unconditional_branch(&update_block_bb);
+
+    builder.position_at_end(&update_block_bb);
+    if let Some((ident_name, data_type, cell_slot)) = &captured_variable {
+        let cell = *symbol_table.get(ident_name).unwrap();
+        let value = builder.build_load(
+            cell,
+            data_type.force_to_basic_type(context),
+            &function_value.get_unique_reg_name(),
+        );
+
+        let next_cell = build_cell(
+            data_type,
+            context,
+            builder,
+            symbol_table,
+            module,
+            ident_name,
+        );
+        builder.build_store(next_cell, value);
+        builder.build_store(*cell_slot, next_cell);
+        symbol_table.insert_local(ident_name.to_string(), next_cell);
+    }
+
+    if let Some(update) = update {
+        consume_single_ast(
+            update,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?;
+    }
+    builder.build_unconditional_branch(&condition_block_bb);
+
+    builder.position_at_end(&exit_block_bb);
+
+    return Ok(());
+}
+
+/*
+ * Arrays are walked by index and strings by the byte offset of every character, the
+ * length is read again before every iteration so elements pushed by the block are
+ * visited too
+ *
+ * */
+pub(crate) fn consume_for_of_loop<'a>(
+    ident_name: &String,
+    iterable: &Expression,
+    block: &Vec<AstPtr>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let iterable_value = build_expression(
+        iterable,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    let iterable_pointer = if let BasicValueEnum::PointerValue(pointer) = iterable_value {
+        pointer
+    } else {
+        panic!("Expected iterable of for...of to be BasicValueEnum::PointerValue")
+    };
+
+    /*
+     * The iterable may be a temporary, e.g. `for (const x of [1, 2])`, which has to
+     * outlive the safepoint of every iteration
+     *
+     * */
+    symbol_table
+        .get_gc_frame()
+        .build_root(iterable_pointer, context, builder, "iterable");
+
+    match iterable.get_data_type() {
+        DataType::ArrayType { base_type } => consume_array_iteration(
+            ident_name,
+            iterable_pointer,
+            &base_type,
+            block,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?,
+
+        DataType::String => consume_string_iteration(
+            ident_name,
+            iterable_pointer,
+            block,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?,
+
+        data_type => panic!("for...of is not supported for data type {:?}", data_type),
+    }
+
+    return Ok(());
+}
+
+/*
+ * Keys of an object are known from its data type, so they are put in an array of
+ * strings in the order of declaration which is then iterated like for...of
+ *
+ * */
+pub(crate) fn consume_for_in_loop<'a>(
+    ident_name: &String,
+    object: &Expression,
+    block: &Vec<AstPtr>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let entries = match object.get_data_type() {
+        DataType::ObjectType { entries } => entries,
+        data_type => panic!("for...in is not supported for data type {:?}", data_type),
+    };
+
+    // Object itself is never read, it is only evaluated for its side effects
+    build_expression(
+        object,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?;
+
+    let mut keys = vec![];
+    for key in entries.keys() {
+        let reg_name = function_value.get_unique_reg_name();
+        let key_value =
+            build_string_literal(key, context, builder, function_value, module, &reg_name);
+
+        // Every key has to stay alive while the next one is allocated
+        symbol_table
+            .get_gc_frame()
+            .build_root(key_value, context, builder, "key");
+        keys.push(key_value.as_basic_value_enum());
+    }
+
+    let reg_name = function_value.get_unique_reg_name();
+    let keys_array = build_array_literal(
+        keys,
+        &DataType::String,
+        context,
+        builder,
+        function_value,
+        module,
+        &reg_name,
+    );
+    symbol_table
+        .get_gc_frame()
+        .build_root(keys_array, context, builder, "keys");
+
+    consume_array_iteration(
+        ident_name,
+        keys_array,
+        &DataType::String,
+        block,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+    )?;
+
+    return Ok(());
+}
+
+/*
+ *  br condition
+ *
+ *  condition:
+ *      br index < length, body, exit
+ *
+ *  body:
+ *      <ident_name> = array[index]
+ *      index = index + 1
+ *      <block>
+ *      br condition
+ *
+ *  exit:
+ *
+ * Index is moved past the element before the block, so `continue` can jump straight
+ * to the condition
+ *
+ * */
+fn consume_array_iteration<'a>(
+    ident_name: &String,
+    array: PointerValue<'a>,
+    base_type: &DataType,
+    block: &Vec<AstPtr>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let i64_type = context.i64_type();
+
+    let index_pointer = symbol_table
+        .get_gc_frame()
+        .build_alloca(i64_type, context, "index");
+    builder.build_store(index_pointer, i64_type.const_int(0, false));
+
+    let variable_pointer = build_loop_variable(ident_name, base_type, context, symbol_table);
+
+    let (condition_block_bb, for_block_bb, exit_block_bb) =
+        build_iteration_blocks(context, builder, function_value, module);
+
+    let index = build_load_int(index_pointer, context, builder, function_value);
+    let length = build_load_array_length(
+        array,
+        context,
+        builder,
+        &function_value.get_unique_reg_name(),
+    );
+    let has_next = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        index,
+        length,
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_conditional_branch(has_next, &for_block_bb, &exit_block_bb);
+
+    builder.position_at_end(&for_block_bb);
+    let index = build_load_int(index_pointer, context, builder, function_value);
+    let element = build_array_element(
+        array,
+        index,
+        base_type,
+        context,
+        builder,
+        function_value,
+        ident_name,
+    );
+    let next_index = builder.build_int_add(
+        index,
+        i64_type.const_int(1, false),
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_store(index_pointer, next_index);
+
+    bind_loop_variable(
+        ident_name,
+        variable_pointer,
+        element,
+        context,
+        builder,
+        symbol_table,
+    );
+
+    consume_ast_in_loop(
+        block,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        &exit_block_bb,
+        &condition_block_bb,
+        module,
+    )?;
+    builder.build_unconditional_branch(&condition_block_bb);
+
+    builder.position_at_end(&exit_block_bb);
+
+    return Ok(());
+}
+
+/*
+ * Same as consume_array_iteration but `offset` is the byte offset of the next
+ * character, every character is a new string of one code point so multi byte
+ * characters are never split
+ *
+ * */
+fn consume_string_iteration<'a>(
+    ident_name: &String,
+    string: PointerValue<'a>,
+    block: &Vec<AstPtr>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let i64_type = context.i64_type();
+
+    let offset_pointer = symbol_table
+        .get_gc_frame()
+        .build_alloca(i64_type, context, "offset");
+    builder.build_store(offset_pointer, i64_type.const_int(0, false));
+
+    let variable_pointer =
+        build_loop_variable(ident_name, &DataType::String, context, symbol_table);
+
+    let (condition_block_bb, for_block_bb, exit_block_bb) =
+        build_iteration_blocks(context, builder, function_value, module);
+
+    let offset = build_load_int(offset_pointer, context, builder, function_value);
+    let length = build_load_string_length(
+        string,
+        context,
+        builder,
+        &function_value.get_unique_reg_name(),
+    );
+    let has_next = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        offset,
+        length,
+        &function_value.get_unique_reg_name(),
+    );
+    builder.build_conditional_branch(has_next, &for_block_bb, &exit_block_bb);
+
+    builder.position_at_end(&for_block_bb);
+    let offset = build_load_int(offset_pointer, context, builder, function_value);
+    let character = build_runtime_call_returning_pointer(
+        STRING_CODE_POINT_AT,
+        &[string.as_basic_value_enum(), offset.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        ident_name,
+    );
+    let width = build_load_string_length(
+        character,
+        context,
+        builder,
+        &function_value.get_unique_reg_name(),
+    );
+    let next_offset = builder.build_int_add(offset, width, &function_value.get_unique_reg_name());
+    builder.build_store(offset_pointer, next_offset);
+
+    bind_loop_variable(
+        ident_name,
+        variable_pointer,
+        character.as_basic_value_enum(),
+        context,
+        builder,
+        symbol_table,
+    );
+
+    consume_ast_in_loop(
+        block,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        &exit_block_bb,
+        &condition_block_bb,
+        module,
+    )?;
+    builder.build_unconditional_branch(&condition_block_bb);
+
+    builder.position_at_end(&exit_block_bb);
+
+    return Ok(());
+}
+
+/*
+ * Appends the condition, body and exit blocks of the iteration and leaves the
+ * builder in the condition block after its safepoint
+ *
+ * */
+fn build_iteration_blocks<'a>(
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    module: &'a Module,
+) -> (BasicBlock<'a>, BasicBlock<'a>, BasicBlock<'a>) {
+    let condition_block_name = function_value.get_unique_block_name();
+    let condition_block_bb =
+        context.append_basic_block(function_value, condition_block_name.as_str());
+
+    let for_block_name = function_value.get_unique_block_name();
+    let for_block_bb = context.append_basic_block(function_value, for_block_name.as_str());
+
+    let exit_block_name = function_value.get_unique_block_name();
+    let exit_block_bb = context.append_basic_block(function_value, exit_block_name.as_str());
+
+    builder.build_unconditional_branch(&condition_block_bb);
+    builder.position_at_end(&condition_block_bb);
+    build_gc_safepoint(context, builder, module);
+
+    return (condition_block_bb, for_block_bb, exit_block_bb);
+}
+
+/*
+ * Numbers, booleans, strings, unions, functions and instances of a class live in a
+ * slot of the frame which is stored to on every iteration like a variable
+ * declaration, arrays and objects are bound to the loaded pointer itself so None is
+ * returned for them
+ *
+ * */
+fn build_loop_variable<'a>(
+    ident_name: &String,
+    data_type: &DataType,
+    context: &'a Context,
+    symbol_table: &mut SymbolTable<'a>,
+) -> Option<PointerValue<'a>> {
+    let gc_frame = symbol_table.get_gc_frame();
+
+    let pointer = match data_type {
+        DataType::Float => gc_frame.build_alloca(context.f64_type(), context, ident_name),
+        DataType::Boolean => gc_frame.build_alloca(context.i1_type(), context, ident_name),
+        DataType::String => {
+            gc_frame.build_root_slot(get_string_pointer_type(context), context, ident_name)
+        }
+        DataType::UnionType { types: _ } => {
+            gc_frame.build_root_slot(get_union_pointer_type(context), context, ident_name)
+        }
+        DataType::FunctionType {
+            arguments: _,
+            return_type: _,
+        } => gc_frame.build_root_slot(get_closure_pointer_type(context), context, ident_name),
+        DataType::ClassType { .. } | DataType::Object => {
+            gc_frame.build_root_slot(get_byte_pointer_type(context), context, ident_name)
+        }
+        _ => return None,
+    };
+
+    symbol_table.insert_local(ident_name.to_owned(), pointer);
+    return Some(pointer);
+}
+
+fn bind_loop_variable<'a>(
+    ident_name: &String,
+    variable_pointer: Option<PointerValue<'a>>,
+    value: BasicValueEnum<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    symbol_table: &mut SymbolTable<'a>,
+) {
+    match (variable_pointer, value) {
+        (Some(pointer), value) => {
+            builder.build_store(pointer, value);
+        }
+
+        (None, BasicValueEnum::PointerValue(pointer)) => {
+            symbol_table
+                .get_gc_frame()
+                .build_root(pointer, context, builder, ident_name);
+            symbol_table.insert_local(ident_name.to_owned(), pointer);
+        }
+
+        (None, value) => panic!("Expected loop variable {:?} to be a pointer", value),
+    }
+}
+
+fn build_load_int<'a>(
+    pointer: PointerValue<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> IntValue<'a> {
+    let value = builder.build_load(
+        pointer,
+        context.i64_type().as_basic_type_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    if let BasicValueEnum::IntValue(value) = value {
+        return value;
+    } else {
+        unreachable!();
+    }
+}
@@ -1,36 +1,67 @@
 use ast::{data_type::DataType, declaration::Declaration, Ast, AstPtr};
 use indexmap::IndexMap;
 use inkwell::{
-    context::Context, module::Module, types::enums::BasicTypeEnum, values::enums::BasicValueEnum,
+    context::Context,
+    enums::Linkage,
+    module::Module,
+    types::{enums::BasicTypeEnum, traits::BasicTypeTrait},
+    values::{enums::BasicValueEnum, fn_value::FunctionValue},
 };
+use lexer::diagnostic::Diagnostic;
 
-use crate::{build_expression::build_expression, llvm_utils::LLVMUtils, symbol_table::SymbolTable};
+use crate::{
+    build_closure::build_load_captures,
+    build_exception::build_throw,
+    build_expression::build_expression,
+    gc_frame::{build_gc_safepoint, GcFrame},
+    llvm_utils::LLVMUtils,
+    runtime::get_byte_pointer_type,
+    symbol_table::SymbolTable,
+    utils::convert_function_data_type_to_llvm_function_type,
+};
 
 use super::consume_single_ast;
 
+/*
+ * Function of a closure takes its environment as the first parameter, see
+ * build_closure.rs. It is private to the module and only reachable through the
+ * closures created for it
+ *
+ * */
 pub(crate) fn consume_function_declaration<'a>(
     arguments: &IndexMap<String, DataType>,
     blocks: &Vec<AstPtr>,
     ident_name: &String,
     return_type: &DataType,
+    captures: &IndexMap<String, DataType>,
+    is_closure: bool,
     context: &'a Context,
     module: &'a Module,
     symbol_table: &mut SymbolTable<'a>,
-) {
+) -> Result<FunctionValue<'a>, Diagnostic> {
     let mut number_of_arguments = 0;
     let llvm_return_type = return_type.force_to_basic_type(context);
-    let param_types: Vec<BasicTypeEnum> = arguments
-        .iter()
-        .map(|(_, data_type)| {
-            number_of_arguments += 1;
-            return data_type.force_to_basic_type(context);
-        })
-        .collect();
+    let mut param_types: Vec<BasicTypeEnum> = vec![];
+    if is_closure {
+        number_of_arguments += 1;
+        param_types.push(get_byte_pointer_type(context).as_basic_type_enum());
+    }
+    for (_, data_type) in arguments {
+        number_of_arguments += 1;
+        param_types.push(data_type.force_to_basic_type(context));
+    }
     let fn_type = llvm_return_type.fn_type(&param_types, false);
 
-    let mut function_value = module.add_function(ident_name, fn_type, None);
-
-    symbol_table.insert_global(ident_name.to_string(), function_value.to_pointer_value());
+    // Functions of a class are declared before any of them is generated
+    let mut function_value = if is_closure {
+        module.add_function(ident_name, fn_type, Some(Linkage::Private))
+    } else {
+        let function_value = module
+            .get_function(ident_name)
+            .unwrap_or_else(|| module.add_function(ident_name, fn_type, None));
+        symbol_table.insert_global(ident_name.to_string(), function_value.to_pointer_value());
+        function_value
+    };
 
     /*
      * When we declare a function with arguments llvm assigns registers with name from 0 to ...
@@ -44,28 +75,58 @@ pub(crate) fn consume_function_declaration<'a>(
      * */
     function_value.set_reg_counter(number_of_arguments);
 
+    let gc_frame = GcFrame::new(context, &function_value, module);
     let entry_block = context.append_basic_block(&function_value, "main");
     let builder = context.create_builder();
     builder.position_at_end(&entry_block);
 
     let mut new_symbol_table = SymbolTable::new();
     new_symbol_table.global_variables = symbol_table.global_variables.clone();
+    new_symbol_table.classes = symbol_table.classes.clone();
+    new_symbol_table.generic_functions = symbol_table.generic_functions.clone();
+    new_symbol_table.gc_frame = Some(gc_frame);
 
     /*
      * We have to store parameters in stack so that it can be
      * manipulated just like any other variables
      *
      * */
+    let first_argument = number_of_arguments - arguments.len();
     for (i, (name, data_type)) in arguments.iter().enumerate() {
         let llvm_type = data_type.force_to_basic_type(context);
+        let i = first_argument + i;
 
-        if let BasicTypeEnum::PointerType(_) = llvm_type {
+        /*
+         * String, union, function, instance of a class and `object` are pointers as
+         * well but the variable holding them can be reassigned, so they are stored
+         * in a root slot of the gc frame
+         *
+         * null and undefined are loaded from the variable like them
+         *
+         * */
+        let is_reassignable_reference = matches!(
+            data_type,
+            DataType::String
+                | DataType::UnionType { .. }
+                | DataType::FunctionType { .. }
+                | DataType::ClassType { .. }
+                | DataType::Object
+                | DataType::Null
+                | DataType::Undefined
+        );
+        let is_reference = {
+            if let BasicTypeEnum::PointerType(_) = llvm_type {
+                !is_reassignable_reference
+            } else {
+                false
+            }
+        };
+
+        if is_reference {
             /*
              * Since typescript does not have pointers, pointers in parameters corresponds to
-             *    => String
              *    => Object
              *    => Array
-             *    => Function
              *
              * types in typescript
              *
@@ -84,8 +145,22 @@ pub(crate) fn consume_function_declaration<'a>(
             } else {
                 todo!();
             }
+        } else if let (true, BasicTypeEnum::PointerType(pointer_type)) =
+            (is_reassignable_reference, llvm_type.clone())
+        {
+            let arg_pointer =
+                new_symbol_table
+                    .get_gc_frame()
+                    .build_root_slot(pointer_type, context, name);
+
+            let param_value = function_value.get_nth_param(i as u32).unwrap();
+            builder.build_store(arg_pointer, param_value);
+
+            new_symbol_table.insert_local(name.to_string(), arg_pointer);
         } else {
-            let arg_pointer = builder.build_alloca(llvm_type, name);
+            let arg_pointer = new_symbol_table
+                .get_gc_frame()
+                .build_alloca(llvm_type, context, name);
 
             let param_value = function_value.get_nth_param(i as u32).unwrap();
             builder.build_store(arg_pointer, param_value);
@@ -94,10 +169,36 @@ pub(crate) fn consume_function_declaration<'a>(
         }
     }
 
+    let environment = {
+        if is_closure {
+            match function_value.get_nth_param(0) {
+                Some(BasicValueEnum::PointerValue(environment)) => Some(environment),
+                _ => unreachable!(),
+            }
+        } else {
+            None
+        }
+    };
+    build_load_captures(
+        captures,
+        environment,
+        context,
+        &builder,
+        &mut new_symbol_table,
+        module,
+    );
+
+    // Arguments are kept alive by the frame of the caller so it is safe to collect
+    build_gc_safepoint(context, &builder, module);
+
+    let mut has_returned = false;
     for cur_ast in blocks {
         if let Ast::Declaration(dec) = cur_ast {
             match dec {
-                Declaration::ReturnStatement { return_exp } => {
+                Declaration::ReturnStatement {
+                    return_exp,
+                    span: _,
+                } => {
                     // If the return_exp is Option::None then we have to return void
 
                     if let Some(return_exp) = return_exp {
@@ -109,14 +210,44 @@ pub(crate) fn consume_function_declaration<'a>(
                             &mut new_symbol_table,
                             module,
                             None,
-                        );
+                        )?;
+                        new_symbol_table
+                            .get_gc_frame()
+                            .build_end(context, &builder, module);
                         match value {
                             Some(value) => builder.build_return(Some(&value)),
                             None => builder.build_return(None),
                         };
                     } else {
+                        new_symbol_table
+                            .get_gc_frame()
+                            .build_end(context, &builder, module);
                         builder.build_return(None);
                     }
+                    has_returned = true;
+                }
+
+                Declaration::ThrowStatement { exp, span: _ } => {
+                    let value = build_expression(
+                        exp,
+                        context,
+                        &builder,
+                        &mut function_value,
+                        &mut new_symbol_table,
+                        module,
+                        None,
+                    )?;
+                    if let Some(BasicValueEnum::PointerValue(value)) = value {
+                        build_throw(
+                            value,
+                            context,
+                            &builder,
+                            &mut function_value,
+                            &new_symbol_table,
+                            module,
+                        );
+                    }
+                    has_returned = true;
                 }
 
                 _ => consume_single_ast(
@@ -126,8 +257,81 @@ pub(crate) fn consume_function_declaration<'a>(
                     &mut function_value,
                     &mut new_symbol_table,
                     module,
-                ),
+                )?,
             }
         }
     }
+
+    /*
+     * Function returning void is allowed to reach the end of its body without a
+     * return statement
+     *
+     * */
+    if !has_returned && *return_type == DataType::Void {
+        new_symbol_table
+            .get_gc_frame()
+            .build_end(context, &builder, module);
+        builder.build_return(None);
+    }
+
+    new_symbol_table
+        .get_gc_frame()
+        .build_reserve(&entry_block, context, module);
+
+    return Ok(function_value);
+}
+
+/*
+ * Function generated for the type arguments of a call to a generic function, it is
+ * generated the first time the module calls it with them and reused after that
+ *
+ * It is private so that every module using the generic function has its own copy
+ *
+ * */
+pub(crate) fn consume_generic_instance<'a>(
+    name: &str,
+    type_arguments: &[DataType],
+    context: &'a Context,
+    module: &'a Module,
+    symbol_table: &mut SymbolTable<'a>,
+) -> Result<FunctionValue<'a>, Diagnostic> {
+    let generic = symbol_table.generic_functions.get(name).unwrap().clone();
+    let instance_name = generic.get_instance_name(type_arguments);
+
+    if let Some(function_value) = module.get_function(&instance_name) {
+        return Ok(function_value);
+    }
+
+    let instance = generic.instantiate(type_arguments);
+    if let Ast::Declaration(Declaration::FunctionDeclaration {
+        arguments,
+        blocks,
+        ident_name,
+        return_type,
+        captures,
+        span: _,
+    }) = instance
+    {
+        // Declared before its body is generated so that it can call itself
+        let data_type = DataType::FunctionType {
+            arguments: arguments.values().cloned().collect(),
+            return_type: Box::new(return_type.clone()),
+        };
+        let fn_type = convert_function_data_type_to_llvm_function_type(&data_type, context);
+        module.add_function(ident_name, fn_type, Some(Linkage::Private));
+
+        return consume_function_declaration(
+            arguments,
+            blocks,
+            ident_name,
+            return_type,
+            captures,
+            false,
+            context,
+            module,
+            symbol_table,
+        );
+    } else {
+        unreachable!();
+    }
 }
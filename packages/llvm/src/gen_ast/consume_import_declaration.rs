@@ -1,15 +1,34 @@
-use ast::data_type::DataType;
+use ast::{class::Class, data_type::DataType, generic::GenericFunction};
 use indexmap::IndexMap;
 use inkwell::{context::Context, module::Module};
+use lexer::{diagnostic::Diagnostic, token::Span};
 
-use crate::{symbol_table::SymbolTable, utils::convert_function_data_type_to_llvm_function_type};
+use crate::{
+    build_class::declare_class, symbol_table::SymbolTable,
+    utils::convert_function_data_type_to_llvm_function_type,
+};
 
 pub(crate) fn consume_import_declaration<'a>(
     idents: &IndexMap<String, DataType>,
+    classes: &[Class],
+    generic_functions: &[GenericFunction],
+    span: Span,
     context: &'a Context,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
+    // Functions of the classes are defined by the module declaring them
+    for class in classes {
+        declare_class(class, context, symbol_table, module);
+    }
+
+    // Their instances are generated by the module calling them
+    for generic in generic_functions {
+        symbol_table
+            .generic_functions
+            .insert(generic.llvm_name.clone(), generic.clone());
+    }
+
     for (name, data_type) in idents {
         if let DataType::FunctionType {
             arguments: _,
@@ -17,10 +36,19 @@ pub(crate) fn consume_import_declaration<'a>(
         } = data_type
         {
             let fn_type = convert_function_data_type_to_llvm_function_type(data_type, context);
-            let fn_value = module.add_function(name, fn_type, None);
+            // Functions used by the imported generic functions may be imported twice
+            let fn_value = module
+                .get_function(name)
+                .unwrap_or_else(|| module.add_function(name, fn_type, None));
             symbol_table.insert_global(name.to_string(), fn_value.to_pointer_value());
         } else {
-            panic!("Currently it is only supported to import functions");
+            return Err(Diagnostic::error(&format!(
+                "Importing {} is not supported, only functions can be imported",
+                name
+            ))
+            .with_primary(span, ""));
         }
     }
+
+    return Ok(());
 }
@@ -0,0 +1,346 @@
+use ast::{
+    data_type::DataType,
+    declaration::SwitchCase,
+    expression::{BinaryOperator, Expression, UnaryOperator},
+};
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, RealCompareOperator},
+    module::Module,
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, traits::BasicValueTrait,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_expression::build_expression, build_string::build_string_comparison,
+    symbol_table::SymbolTable,
+};
+
+use super::consume_generic_ast;
+
+// Every integer up to 2^53 is exactly representable as a number
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/*
+ * Every case gets a block and the block of a case falls through to the block of the
+ * next case in the order of the source, `break` jumps to the exit block
+ *
+ * Jumping to the block of the matching case is done by an llvm `switch` if every
+ * test is an integer constant, otherwise the tests are compared one by one in order
+ * and the first one equal to the discriminant wins like in js
+ *
+ * */
+pub(crate) fn consume_switch<'a>(
+    discriminant: &Expression,
+    cases: &Vec<SwitchCase>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let discriminant_value = build_expression(
+        discriminant,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?
+    .unwrap();
+
+    let case_blocks: Vec<BasicBlock> = cases
+        .iter()
+        .map(|_| {
+            let case_block_name = function_value.get_unique_block_name();
+            return context.append_basic_block(function_value, case_block_name.as_str());
+        })
+        .collect();
+
+    let exit_block_name = function_value.get_unique_block_name();
+    let exit_block_bb = context.append_basic_block(function_value, exit_block_name.as_str());
+
+    let default_block_bb = match cases.iter().position(|case| case.test.is_none()) {
+        Some(i) => case_blocks[i].clone(),
+        None => exit_block_bb.clone(),
+    };
+
+    let integer_tests: Option<Vec<i64>> = cases
+        .iter()
+        .filter_map(|case| case.test.as_ref())
+        .map(get_integer_constant)
+        .collect();
+
+    match (discriminant_value, integer_tests) {
+        (BasicValueEnum::FloatValue(discriminant_value), Some(integer_tests)) => {
+            build_integer_switch(
+                discriminant_value,
+                &integer_tests,
+                cases,
+                &case_blocks,
+                &default_block_bb,
+                context,
+                builder,
+                function_value,
+            )?;
+        }
+
+        (discriminant_value, _) => {
+            build_compare_chain(
+                discriminant_value,
+                &discriminant.get_data_type(),
+                cases,
+                &case_blocks,
+                &default_block_bb,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?;
+        }
+    }
+
+    symbol_table.push_switch_target(&exit_block_bb);
+    for (i, case) in cases.iter().enumerate() {
+        builder.position_at_end(&case_blocks[i]);
+        consume_generic_ast(
+            &case.block,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?;
+
+        // Falls through to the next case
+        match case_blocks.get(i + 1) {
+            Some(next_case_block) => builder.build_unconditional_branch(next_case_block),
+            None => builder.build_unconditional_branch(&exit_block_bb),
+        };
+    }
+    symbol_table.pop_loop_target();
+
+    builder.position_at_end(&exit_block_bb);
+
+    return Ok(());
+}
+
+/*
+ * Numbers are doubles, so the discriminant is only converted to an integer for the
+ * `switch` if it holds an integer which is in range. Anything else, e.g. `1.5` or
+ * NaN, cannot equal any of the tests and goes to the default case
+ *
+ * */
+fn build_integer_switch<'a>(
+    discriminant_value: FloatValue<'a>,
+    integer_tests: &[i64],
+    cases: &[SwitchCase],
+    case_blocks: &[BasicBlock<'a>],
+    default_block: &BasicBlock<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+) -> Result<(), Diagnostic> {
+    let f64_type = context.f64_type();
+    let i64_type = context.i64_type();
+
+    let is_above_min = builder.build_float_compare(
+        RealCompareOperator::GreaterThanOrEqual,
+        discriminant_value,
+        f64_type.const_float(-MAX_SAFE_INTEGER),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_below_max = builder.build_float_compare(
+        RealCompareOperator::LessThanOrEqual,
+        discriminant_value,
+        f64_type.const_float(MAX_SAFE_INTEGER),
+        &function_value.get_unique_reg_name(),
+    );
+    let is_in_range = builder.build_and(
+        is_above_min,
+        is_below_max,
+        &function_value.get_unique_reg_name(),
+    );
+
+    /*
+     * Conversion of a number out of range is poison, the select makes sure that the
+     * branch never depends on it
+     *
+     * */
+    let integer = builder.build_fp_to_si(
+        discriminant_value,
+        i64_type,
+        &function_value.get_unique_reg_name(),
+    );
+    let converted_back =
+        builder.build_si_to_fp(integer, f64_type, &function_value.get_unique_reg_name());
+    let is_same = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        converted_back,
+        discriminant_value,
+        &function_value.get_unique_reg_name(),
+    );
+    let is_integer = builder.build_select(
+        is_in_range,
+        is_same.as_basic_value_enum(),
+        context.i1_type().const_int(0, false).as_basic_value_enum(),
+        &function_value.get_unique_reg_name(),
+    );
+
+    let switch_block_name = function_value.get_unique_block_name();
+    let switch_block_bb = context.append_basic_block(function_value, switch_block_name.as_str());
+
+    if let BasicValueEnum::IntValue(is_integer) = is_integer {
+        builder.build_conditional_branch(is_integer, &switch_block_bb, default_block);
+    } else {
+        unreachable!();
+    }
+
+    builder.position_at_end(&switch_block_bb);
+
+    // Values of llvm `switch` have to be unique, the first case with a value wins
+    let mut switch_cases: Vec<(IntValue, &BasicBlock)> = vec![];
+    let mut seen_tests: Vec<i64> = vec![];
+    let case_blocks_with_test = cases
+        .iter()
+        .zip(case_blocks.iter())
+        .filter(|(case, _)| case.test.is_some())
+        .map(|(_, case_block)| case_block);
+
+    for (test, case_block) in integer_tests.iter().zip(case_blocks_with_test) {
+        if !seen_tests.contains(test) {
+            seen_tests.push(*test);
+            switch_cases.push((i64_type.const_int(*test as u64, true), case_block));
+        }
+    }
+
+    builder.build_switch(integer, default_block, &switch_cases);
+
+    return Ok(());
+}
+
+/*
+ *  check_0:
+ *      br <discriminant> === <test_0>, case_0, check_1
+ *
+ *  check_1:
+ *      ...
+ *      br <discriminant> === <test_n>, case_n, default
+ *
+ * Tests are evaluated only until one of them matches
+ *
+ * */
+fn build_compare_chain<'a>(
+    discriminant_value: BasicValueEnum<'a>,
+    data_type: &DataType,
+    cases: &[SwitchCase],
+    case_blocks: &[BasicBlock<'a>],
+    default_block: &BasicBlock<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    for (i, case) in cases.iter().enumerate() {
+        let test = match &case.test {
+            Some(test) => test,
+            None => continue,
+        };
+
+        let test_value = build_expression(
+            test,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            None,
+        )?
+        .unwrap();
+
+        let reg_name = function_value.get_unique_reg_name();
+        let is_equal = match (data_type, discriminant_value.clone(), test_value) {
+            (
+                DataType::Float,
+                BasicValueEnum::FloatValue(discriminant_value),
+                BasicValueEnum::FloatValue(test_value),
+            ) => builder.build_float_compare(
+                RealCompareOperator::Equal,
+                discriminant_value,
+                test_value,
+                &reg_name,
+            ),
+
+            (
+                DataType::Boolean,
+                BasicValueEnum::IntValue(discriminant_value),
+                BasicValueEnum::IntValue(test_value),
+            ) => builder.build_int_compare(
+                IntCompareOperator::Equal,
+                discriminant_value,
+                test_value,
+                &reg_name,
+            ),
+
+            (
+                DataType::String,
+                BasicValueEnum::PointerValue(discriminant_value),
+                BasicValueEnum::PointerValue(test_value),
+            ) => build_string_comparison(
+                &BinaryOperator::StrictEquality,
+                discriminant_value,
+                test_value,
+                context,
+                builder,
+                function_value,
+                module,
+                &reg_name,
+            ),
+
+            (data_type, _, _) => panic!("switch is not supported for data type {:?}", data_type),
+        };
+
+        let check_block_name = function_value.get_unique_block_name();
+        let check_block_bb = context.append_basic_block(function_value, check_block_name.as_str());
+
+        builder.build_conditional_branch(is_equal, &case_blocks[i], &check_block_bb);
+        builder.position_at_end(&check_block_bb);
+    }
+
+    builder.build_unconditional_branch(default_block);
+
+    return Ok(());
+}
+
+/*
+ * Value of a test written as an integer literal, e.g. `3` or `-1`
+ *
+ * */
+fn get_integer_constant(test: &Expression) -> Option<i64> {
+    let value = match test {
+        Expression::FloatLiteralExp { value, .. } => *value,
+        Expression::UnaryExp {
+            operator: UnaryOperator::Minus,
+            argument,
+            ..
+        } => match argument.as_ref() {
+            Expression::FloatLiteralExp { value, .. } => -value,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER {
+        return Some(value as i64);
+    } else {
+        return None;
+    }
+}
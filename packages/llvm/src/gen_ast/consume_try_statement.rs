@@ -0,0 +1,326 @@
+use ast::{declaration::CatchClause, expression::Expression, AstPtr};
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    module::Module,
+    types::traits::BasicTypeTrait,
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        ptr_value::PointerValue,
+    },
+};
+use lexer::diagnostic::Diagnostic;
+
+use crate::{
+    build_exception::{build_landing_pad, build_throw},
+    build_expression::build_expression,
+    build_union::get_union_pointer_type,
+    symbol_table::SymbolTable,
+};
+
+use super::{consume_generic_ast, consume_loop_control_flow};
+
+/*
+ * How control reached the finally block, `break` and `continue` which leave the
+ * try get completions from JUMP_COMPLETION_START on
+ *
+ * */
+const NORMAL_COMPLETION: u64 = 0;
+const THROW_COMPLETION: u64 = 1;
+pub(super) const JUMP_COMPLETION_START: u64 = 2;
+
+/*
+ * Completion and the pending exception of a try with finally block
+ *
+ * */
+struct Finally<'a> {
+    block: BasicBlock<'a>,
+    completion: PointerValue<'a>,
+    exception: PointerValue<'a>,
+}
+
+fn append_block<'a>(context: &'a Context, function_value: &mut FunctionValue) -> BasicBlock<'a> {
+    let block_name = function_value.get_unique_block_name();
+    return context.append_basic_block(function_value, block_name.as_str());
+}
+
+/*
+ *      <try_block>                 calls invoke, unwinding to landing_pad
+ *      br finally
+ *
+ *  landing_pad:
+ *      <binding> = landingpad
+ *      <catch_block>               calls unwind to catch_landing_pad
+ *      br finally
+ *
+ *  catch_landing_pad:
+ *      exception = landingpad
+ *      completion = THROW_COMPLETION
+ *      br finally
+ *
+ *  finally:
+ *      <finally_block>
+ *      switch completion, end, [THROW_COMPLETION, rethrow], [<jump>, jump_i] ...
+ *
+ *  rethrow:
+ *      throw exception
+ *
+ *  jump_i:
+ *      break or continue
+ *
+ * Without finally block both the try and the catch block branch to end. Without
+ * catch clause the landing pad is the catch_landing_pad
+ *
+ * */
+pub(crate) fn consume_try_statement<'a>(
+    try_block: &Vec<AstPtr>,
+    catch_clause: &Option<CatchClause>,
+    finally_block: &Option<Vec<AstPtr>>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let i64_type = context.i64_type();
+
+    let finally = finally_block.as_ref().map(|_| {
+        let gc_frame = symbol_table.get_gc_frame();
+        let completion = gc_frame.build_alloca(i64_type, context, "completion");
+        let exception =
+            gc_frame.build_root_slot(get_union_pointer_type(context), context, "exception");
+
+        return Finally {
+            block: append_block(context, function_value),
+            completion,
+            exception,
+        };
+    });
+    let landing_pad_block = append_block(context, function_value);
+    let end_block = append_block(context, function_value);
+    let exit_block = finally
+        .as_ref()
+        .map_or(end_block.clone(), |finally| finally.block.clone());
+
+    if let Some(finally) = &finally {
+        builder.build_store(
+            finally.completion,
+            i64_type.const_int(NORMAL_COMPLETION, false),
+        );
+        symbol_table.push_finally_target(finally.completion, &finally.block);
+    }
+
+    symbol_table.push_landing_pad(&landing_pad_block);
+    consume_generic_ast(
+        try_block,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+    )?;
+    symbol_table.pop_landing_pad();
+    builder.build_unconditional_branch(&exit_block);
+
+    builder.position_at_end(&landing_pad_block);
+    match (catch_clause, &finally) {
+        (Some(catch_clause), _) => {
+            let value = build_landing_pad(context, builder, function_value, symbol_table, module);
+
+            if let Some(binding) = &catch_clause.binding {
+                let binding_pointer = symbol_table.get_gc_frame().build_root_slot(
+                    get_union_pointer_type(context),
+                    context,
+                    binding,
+                );
+                builder.build_store(binding_pointer, value);
+                symbol_table.insert_local(binding.to_string(), binding_pointer);
+            }
+
+            let catch_landing_pad_block = finally
+                .as_ref()
+                .map(|_| append_block(context, function_value));
+
+            if let Some(catch_landing_pad_block) = &catch_landing_pad_block {
+                symbol_table.push_landing_pad(catch_landing_pad_block);
+            }
+            consume_generic_ast(
+                &catch_clause.block,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?;
+            if catch_landing_pad_block.is_some() {
+                symbol_table.pop_landing_pad();
+            }
+            builder.build_unconditional_branch(&exit_block);
+
+            if let (Some(catch_landing_pad_block), Some(finally)) =
+                (catch_landing_pad_block, &finally)
+            {
+                builder.position_at_end(&catch_landing_pad_block);
+                build_pending_exception(
+                    finally,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                );
+            }
+        }
+
+        (None, Some(finally)) => build_pending_exception(
+            finally,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        ),
+
+        (None, None) => unreachable!("Expected try to have catch clause or finally block"),
+    }
+
+    if let (Some(finally), Some(finally_block)) = (finally, finally_block) {
+        // Finally block runs outside of the try, jumps in it do not come back to it
+        let jumps = symbol_table.pop_finally_target().jumps;
+
+        builder.position_at_end(&finally.block);
+        consume_generic_ast(
+            finally_block,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?;
+
+        let completion = builder.build_load(
+            finally.completion,
+            i64_type.as_basic_type_enum(),
+            &function_value.get_unique_reg_name(),
+        );
+        let completion = match completion {
+            BasicValueEnum::IntValue(completion) => completion,
+            _ => unreachable!(),
+        };
+
+        let rethrow_block = append_block(context, function_value);
+        let jump_blocks: Vec<BasicBlock> = jumps
+            .iter()
+            .map(|_| append_block(context, function_value))
+            .collect();
+
+        let mut cases: Vec<(IntValue, &BasicBlock)> =
+            vec![(i64_type.const_int(THROW_COMPLETION, false), &rethrow_block)];
+        for (index, jump_block) in jump_blocks.iter().enumerate() {
+            let jump_completion = JUMP_COMPLETION_START + index as u64;
+            cases.push((i64_type.const_int(jump_completion, false), jump_block));
+        }
+        builder.build_switch(completion, &end_block, &cases);
+
+        builder.position_at_end(&rethrow_block);
+        let exception = builder.build_load(
+            finally.exception,
+            get_union_pointer_type(context).as_basic_type_enum(),
+            &function_value.get_unique_reg_name(),
+        );
+        if let BasicValueEnum::PointerValue(exception) = exception {
+            build_throw(
+                exception,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            );
+        }
+
+        // Jump is repeated so it runs the finally blocks of the outer tries as well
+        for ((keyword, label), jump_block) in jumps.iter().zip(jump_blocks.iter()) {
+            builder.position_at_end(jump_block);
+            consume_loop_control_flow(
+                keyword,
+                label,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+            )?;
+            builder.build_unreachable();
+        }
+    }
+
+    builder.position_at_end(&end_block);
+
+    return Ok(());
+}
+
+/*
+ * Exception thrown where only the finally block handles it is kept till the
+ * finally block is done and then thrown again
+ *
+ * */
+fn build_pending_exception<'a>(
+    finally: &Finally<'a>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) {
+    let value = build_landing_pad(context, builder, function_value, symbol_table, module);
+
+    builder.build_store(finally.exception, value);
+    builder.build_store(
+        finally.completion,
+        context.i64_type().const_int(THROW_COMPLETION, false),
+    );
+    builder.build_unconditional_branch(&finally.block);
+}
+
+/*
+ * Code following the throw is unreachable, it gets a block of its own like the
+ * code following `break`
+ *
+ * */
+pub(crate) fn consume_throw_statement<'a>(
+    exp: &Expression,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    let value = build_expression(
+        exp,
+        context,
+        builder,
+        function_value,
+        symbol_table,
+        module,
+        None,
+    )?;
+
+    if let Some(BasicValueEnum::PointerValue(value)) = value {
+        build_throw(
+            value,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        );
+    } else {
+        panic!("Expected thrown value to be a union");
+    }
+
+    let unreachable_block = append_block(context, function_value);
+    builder.position_at_end(&unreachable_block);
+
+    return Ok(());
+}
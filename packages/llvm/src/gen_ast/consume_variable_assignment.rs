@@ -2,6 +2,7 @@ use ast::{declaration::VariableAssignmentOperator, expression::Expression};
 use inkwell::{
     builder::Builder, context::Context, module::Module, values::fn_value::FunctionValue,
 };
+use lexer::diagnostic::Diagnostic;
 
 use crate::{
     build_assignment::build_assignment, build_expression::build_expression,
@@ -17,7 +18,7 @@ pub(crate) fn consume_variable_assignment<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     let var_ptr = symbol_table.get(ident_name).unwrap().clone();
     let value = build_expression(
         exp,
@@ -27,7 +28,7 @@ pub(crate) fn consume_variable_assignment<'a>(
         symbol_table,
         module,
         None,
-    )
+    )?
     .unwrap();
 
     build_assignment(
@@ -38,5 +39,8 @@ pub(crate) fn consume_variable_assignment<'a>(
         builder,
         function_value,
         symbol_table,
+        module,
     );
+
+    return Ok(());
 }
@@ -5,21 +5,28 @@ use inkwell::{
     builder::Builder,
     context::Context,
     module::Module,
-    types::traits::BasicTypeTrait,
     values::{enums::BasicValueEnum, fn_value::FunctionValue},
 };
+use lexer::diagnostic::Diagnostic;
 
-use crate::{build_expression::build_expression, symbol_table::SymbolTable};
+use crate::{
+    build_closure::{build_cell, get_closure_pointer_type, is_stored_in_cell},
+    build_expression::build_expression,
+    build_union::get_union_pointer_type,
+    runtime::{get_byte_pointer_type, string::get_string_pointer_type},
+    symbol_table::SymbolTable,
+};
 
 pub(crate) fn consume_variable_declaration<'a>(
     ident_name: &String,
     exp: &Expression,
+    is_captured: bool,
     context: &'a Context,
     builder: &'a Builder,
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     let data_type = exp.get_data_type();
 
     /*
@@ -38,13 +45,47 @@ pub(crate) fn consume_variable_declaration<'a>(
             function_value,
             symbol_table,
             module,
+        )?;
+        return Ok(());
+    }
+
+    /*
+     * Variable used by a closure lives in a cell, which is created before the
+     * initializer is evaluated so that a nested function can call itself
+     *
+     * */
+    if is_captured && is_stored_in_cell(&data_type) {
+        let cell = build_cell(
+            &data_type,
+            context,
+            builder,
+            symbol_table,
+            module,
+            ident_name.as_str(),
         );
-        return;
+        symbol_table.insert_local(ident_name.to_owned(), cell);
+
+        let value_of_exp = build_expression(
+            exp,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+            None,
+        )?
+        .unwrap();
+        builder.build_store(cell, value_of_exp);
+        return Ok(());
     }
 
     let pointer = match data_type {
         DataType::Float => {
-            let pointer = builder.build_alloca(context.f64_type(), ident_name.as_str());
+            let pointer = symbol_table.get_gc_frame().build_alloca(
+                context.f64_type(),
+                context,
+                ident_name.as_str(),
+            );
             let value_of_exp = build_expression(
                 exp,
                 context,
@@ -53,7 +94,7 @@ pub(crate) fn consume_variable_declaration<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
             builder.build_store(pointer.clone(), value_of_exp);
 
@@ -61,7 +102,11 @@ pub(crate) fn consume_variable_declaration<'a>(
         }
 
         DataType::Boolean => {
-            let pointer = builder.build_alloca(context.i1_type(), ident_name.as_str());
+            let pointer = symbol_table.get_gc_frame().build_alloca(
+                context.i1_type(),
+                context,
+                ident_name.as_str(),
+            );
             let value_of_exp = build_expression(
                 exp,
                 context,
@@ -70,7 +115,7 @@ pub(crate) fn consume_variable_declaration<'a>(
                 symbol_table,
                 module,
                 None,
-            )
+            )?
             .unwrap();
             builder.build_store(pointer.clone(), value_of_exp);
 
@@ -78,59 +123,88 @@ pub(crate) fn consume_variable_declaration<'a>(
         }
 
         DataType::String => {
-            let value = build_expression(
+            let pointer = symbol_table.get_gc_frame().build_root_slot(
+                get_string_pointer_type(context),
+                context,
+                ident_name.as_str(),
+            );
+            let value_of_exp = build_expression(
                 exp,
                 context,
                 builder,
                 function_value,
                 symbol_table,
                 module,
-                Some(ident_name.to_string()),
-            )
+                None,
+            )?
             .unwrap();
-            if let BasicValueEnum::PointerValue(pointer) = value {
-                if let Expression::StringLiteralExp { value: _ } = exp {
-                    pointer
-                } else {
-                    let string_type = pointer.get_type().into_array_type().unwrap();
-                    let size = string_type.get_length();
-
-                    let new_pointer = builder.build_alloca(string_type, &ident_name);
-
-                    for x in 0..size {
-                        let indices = vec![
-                            context.i64_type().const_int(0, true),
-                            context.i64_type().const_int(x.into(), true),
-                        ];
-
-                        let original_index_pointer = builder.build_gep_2(
-                            string_type,
-                            &pointer,
-                            &indices,
-                            &function_value.get_unique_reg_name(),
-                        );
-
-                        let new_index_pointer = builder.build_gep_2(
-                            string_type,
-                            &new_pointer,
-                            &indices,
-                            &function_value.get_unique_reg_name(),
-                        );
-
-                        let char_value = builder.build_load(
-                            original_index_pointer,
-                            context.i8_type().as_basic_type_enum(),
-                            &function_value.get_unique_reg_name(),
-                        );
-
-                        builder.build_store(new_index_pointer, char_value);
-                    }
-
-                    new_pointer
-                }
-            } else {
-                todo!();
-            }
+            builder.build_store(pointer.clone(), value_of_exp);
+
+            pointer
+        }
+
+        DataType::UnionType { types: _ } => {
+            let pointer = symbol_table.get_gc_frame().build_root_slot(
+                get_union_pointer_type(context),
+                context,
+                ident_name.as_str(),
+            );
+            let value_of_exp = build_expression(
+                exp,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+            builder.build_store(pointer.clone(), value_of_exp);
+
+            pointer
+        }
+
+        DataType::ClassType { .. } | DataType::Object => {
+            let pointer = symbol_table.get_gc_frame().build_root_slot(
+                get_byte_pointer_type(context),
+                context,
+                ident_name.as_str(),
+            );
+            let value_of_exp = build_expression(
+                exp,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+            builder.build_store(pointer.clone(), value_of_exp);
+
+            pointer
+        }
+
+        // Never points to memory of the garbage collector, so it is not rooted
+        DataType::Null | DataType::Undefined => {
+            let pointer = symbol_table.get_gc_frame().build_alloca(
+                get_byte_pointer_type(context),
+                context,
+                ident_name.as_str(),
+            );
+            let value_of_exp = build_expression(
+                exp,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+                None,
+            )?
+            .unwrap();
+            builder.build_store(pointer.clone(), value_of_exp);
+
+            pointer
         }
 
         DataType::ArrayType { base_type: _ } => {
@@ -142,7 +216,7 @@ pub(crate) fn consume_variable_declaration<'a>(
                 symbol_table,
                 module,
                 Some(ident_name.to_string()),
-            )
+            )?
             .unwrap();
 
             if let BasicValueEnum::PointerValue(pointer) = value {
@@ -161,7 +235,7 @@ pub(crate) fn consume_variable_declaration<'a>(
                 symbol_table,
                 module,
                 Some(ident_name.to_string()),
-            )
+            )?
             .unwrap();
 
             if let BasicValueEnum::PointerValue(pointer) = value {
@@ -177,29 +251,35 @@ pub(crate) fn consume_variable_declaration<'a>(
             arguments: _,
             return_type: _,
         } => {
-            let value = build_expression(
+            let pointer = symbol_table.get_gc_frame().build_root_slot(
+                get_closure_pointer_type(context),
+                context,
+                ident_name.as_str(),
+            );
+            let value_of_exp = build_expression(
                 exp,
                 context,
                 builder,
                 function_value,
                 symbol_table,
                 module,
-                Some(ident_name.to_string()),
-            )
+                None,
+            )?
             .unwrap();
+            builder.build_store(pointer.clone(), value_of_exp);
 
-            if let BasicValueEnum::PointerValue(pointer) = value {
-                pointer
-            } else {
-                panic!("Expected function build_expression for DataType::FunctionType to return BasicValueEnum::PointerValue")
-            }
+            pointer
         }
 
         DataType::Unknown => unreachable!(),
         DataType::NA => unreachable!(),
+        DataType::TypeParameter { .. } => unreachable!(),
+        DataType::EnumType { .. } => unreachable!(),
     };
 
     symbol_table.insert_local(ident_name.to_owned(), pointer);
+
+    return Ok(());
 }
 
 fn consume_fn_with_return_type_void<'a>(
@@ -210,7 +290,7 @@ fn consume_fn_with_return_type_void<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     build_expression(
         exp,
         context,
@@ -219,5 +299,7 @@ fn consume_fn_with_return_type_void<'a>(
         symbol_table,
         module,
         Some(ident_name.to_string()),
-    );
+    )?;
+
+    return Ok(());
 }
@@ -1,8 +1,13 @@
 mod consume_array_member_assignment;
+mod consume_class_declaration;
 mod consume_do_while_loop;
-mod consume_function_declaration;
+mod consume_field_assignment;
+mod consume_for_loop;
+pub(crate) mod consume_function_declaration;
 mod consume_if_block;
 mod consume_import_declaration;
+mod consume_switch;
+mod consume_try_statement;
 mod consume_variable_assignment;
 mod consume_variable_declaration;
 mod consume_while_loop;
@@ -12,12 +17,21 @@ use inkwell::{
     basic_block::BasicBlock, builder::Builder, context::Context, module::Module,
     values::fn_value::FunctionValue,
 };
-use lexer::token::KeywordKind;
+use lexer::{diagnostic::Diagnostic, token::KeywordKind};
 
 use crate::{
+    build_closure::build_store_captures_in_globals,
     gen_ast::{
         consume_array_member_assignment::consume_array_member_assignments,
-        consume_do_while_loop::consume_do_while_loop, consume_if_block::consume_if_block,
+        consume_class_declaration::consume_class_declaration,
+        consume_do_while_loop::consume_do_while_loop,
+        consume_field_assignment::consume_field_assignment,
+        consume_for_loop::{consume_for_in_loop, consume_for_loop, consume_for_of_loop},
+        consume_if_block::consume_if_block,
+        consume_switch::consume_switch,
+        consume_try_statement::{
+            consume_throw_statement, consume_try_statement, JUMP_COMPLETION_START,
+        },
         consume_variable_assignment::consume_variable_assignment,
         consume_variable_declaration::consume_variable_declaration,
         consume_while_loop::consume_while_loop,
@@ -37,23 +51,26 @@ pub(crate) fn consume_single_ast<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     match ast {
         Ast::Declaration(dec) => match dec {
             Declaration::VariableDeclaration {
                 ident_name,
                 exp,
                 kind: _,
+                is_captured,
+                span: _,
             } => {
                 consume_variable_declaration(
                     ident_name,
                     exp,
+                    *is_captured,
                     context,
                     builder,
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
             }
 
             // Does not do typechecking
@@ -61,6 +78,7 @@ pub(crate) fn consume_single_ast<'a>(
                 ident_name,
                 exp,
                 operator,
+                span: _,
             } => {
                 consume_variable_assignment(
                     ident_name,
@@ -71,13 +89,34 @@ pub(crate) fn consume_single_ast<'a>(
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
+            }
+
+            Declaration::FieldAssignment {
+                object,
+                field,
+                operator,
+                exp,
+                span: _,
+            } => {
+                consume_field_assignment(
+                    object,
+                    field,
+                    operator,
+                    exp,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
             }
 
             Declaration::NewIfBlockDeclaration {
                 else_block,
                 else_if_block,
                 if_block,
+                span: _,
             } => {
                 consume_if_block(
                     if_block,
@@ -88,10 +127,14 @@ pub(crate) fn consume_single_ast<'a>(
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
             }
 
-            Declaration::WhileLoopDeclaration { block, condition } => {
+            Declaration::WhileLoopDeclaration {
+                block,
+                condition,
+                span: _,
+            } => {
                 consume_while_loop(
                     block,
                     condition,
@@ -100,10 +143,14 @@ pub(crate) fn consume_single_ast<'a>(
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
             }
 
-            Declaration::DoWhileLoopDeclaration { block, condition } => {
+            Declaration::DoWhileLoopDeclaration {
+                block,
+                condition,
+                span: _,
+            } => {
                 consume_do_while_loop(
                     block,
                     condition,
@@ -112,7 +159,65 @@ pub(crate) fn consume_single_ast<'a>(
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
+            }
+
+            Declaration::ForLoopDeclaration {
+                initializer,
+                condition,
+                update,
+                block,
+                span: _,
+            } => {
+                consume_for_loop(
+                    initializer,
+                    condition,
+                    update,
+                    block,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            Declaration::ForOfLoopDeclaration {
+                ident_name,
+                kind: _,
+                iterable,
+                block,
+                span: _,
+            } => {
+                consume_for_of_loop(
+                    ident_name,
+                    iterable,
+                    block,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            Declaration::ForInLoopDeclaration {
+                ident_name,
+                kind: _,
+                object,
+                block,
+                span: _,
+            } => {
+                consume_for_in_loop(
+                    ident_name,
+                    object,
+                    block,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
             }
 
             Declaration::ArrayMemberAssignment {
@@ -120,6 +225,7 @@ pub(crate) fn consume_single_ast<'a>(
                 member_access_exp,
                 operator,
                 exp,
+                span: _,
             } => {
                 consume_array_member_assignments(
                     ident_name,
@@ -131,14 +237,118 @@ pub(crate) fn consume_single_ast<'a>(
                     function_value,
                     symbol_table,
                     module,
-                );
+                )?;
+            }
+
+            Declaration::SwitchDeclaration {
+                discriminant,
+                cases,
+                span: _,
+            } => {
+                consume_switch(
+                    discriminant,
+                    cases,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            Declaration::LoopControlFlow {
+                keyword,
+                label,
+                span: _,
+            } => {
+                consume_loop_control_flow(
+                    keyword,
+                    label,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                )?;
+            }
+
+            Declaration::TryStatement {
+                try_block,
+                catch_clause,
+                finally_block,
+                span: _,
+            } => {
+                consume_try_statement(
+                    try_block,
+                    catch_clause,
+                    finally_block,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            Declaration::ThrowStatement { exp, span: _ } => {
+                consume_throw_statement(
+                    exp,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            Declaration::LabeledStatement {
+                label,
+                statement,
+                span: _,
+            } => {
+                symbol_table.set_loop_label(label.to_string());
+                consume_single_ast(
+                    statement,
+                    context,
+                    builder,
+                    function_value,
+                    symbol_table,
+                    module,
+                )?;
+            }
+
+            // Types only exist for the parser
+            Declaration::TypeDeclaration { .. } => {}
+
+            // Enums are declared at the top of the file, see consume_top_level_ast
+            Declaration::EnumDeclaration { .. } => unreachable!(),
+
+            /*
+             * Return statement is generated by consume_function_declaration, only
+             * when it is a statement of the body itself
+             *
+             * */
+            Declaration::ReturnStatement { span, .. } => {
+                return Err(Diagnostic::error(
+                    "Return is only supported as a statement of the function body",
+                )
+                .with_primary(*span, "nested in a block"));
             }
 
-            _ => panic!("Unknown declaration {:?}", dec),
+            _ => {
+                return Err(Diagnostic::error("Declaration is not supported here")
+                    .with_primary(dec.get_span(), ""));
+            }
         },
 
-        _ => todo!(),
+        Ast::Expression(exp) => {
+            return Err(
+                Diagnostic::error("Expression is not supported as a statement")
+                    .with_primary(exp.get_span(), ""),
+            );
+        }
     }
+
+    return Ok(());
 }
 
 pub(crate) fn consume_generic_ast<'a>(
@@ -148,7 +358,7 @@ pub(crate) fn consume_generic_ast<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     for cur_ast in asts.iter() {
         consume_single_ast(
             cur_ast,
@@ -157,44 +367,86 @@ pub(crate) fn consume_generic_ast<'a>(
             function_value,
             symbol_table,
             module,
-        );
+        )?;
     }
+
+    return Ok(());
 }
 
+/*
+ * `break` and `continue` anywhere in the body of the loop jump to exit_block and
+ * continue_block, unless they name the label of an outer loop
+ *
+ * A jump which leaves a try with finally block goes through the finally block,
+ * which repeats the jump once it is done
+ *
+ * */
 pub(crate) fn consume_ast_in_loop<'a>(
     asts: &Vec<AstPtr>,
     context: &'a Context,
     builder: &'a Builder,
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
-    exit_block: &BasicBlock,
-    continue_block: &BasicBlock,
+    exit_block: &BasicBlock<'a>,
+    continue_block: &BasicBlock<'a>,
     module: &'a Module,
-) {
-    for ast in asts.iter() {
-        if let Ast::Declaration(dec) = ast {
-            match dec {
-                Declaration::LoopControlFlow { keyword } => match keyword {
-                    KeywordKind::Break => {
-                        builder.build_unconditional_branch(exit_block);
-                    }
-                    KeywordKind::Continue => {
-                        builder.build_unconditional_branch(continue_block);
-                    }
-
-                    _ => panic!("Unexpected keyword {:?}", keyword),
-                },
-
-                _ => {
-                    consume_single_ast(ast, context, builder, function_value, symbol_table, module)
-                }
-            }
-        } else {
-            todo!()
+) -> Result<(), Diagnostic> {
+    symbol_table.push_loop_target(exit_block, continue_block);
+    consume_generic_ast(asts, context, builder, function_value, symbol_table, module)?;
+    symbol_table.pop_loop_target();
+
+    return Ok(());
+}
+
+pub(super) fn consume_loop_control_flow<'a>(
+    keyword: &KeywordKind,
+    label: &Option<String>,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+) -> Result<(), Diagnostic> {
+    let finally_target = symbol_table
+        .route_through_finally(keyword, label)
+        .map(|(target, index)| (target.completion, target.finally_block.clone(), index));
+
+    match (keyword, finally_target) {
+        (_, Some((completion, finally_block, index))) => {
+            let completion_value = JUMP_COMPLETION_START + index as u64;
+            builder.build_store(
+                completion,
+                context.i64_type().const_int(completion_value, false),
+            );
+            builder.build_unconditional_branch(&finally_block)
         }
-    }
+        (KeywordKind::Break, None) => {
+            builder.build_unconditional_branch(symbol_table.get_break_block(label))
+        }
+        (KeywordKind::Continue, None) => {
+            builder.build_unconditional_branch(symbol_table.get_continue_block(label))
+        }
+
+        (_, None) => panic!("Unexpected keyword {:?}", keyword),
+    };
+
+    /*
+     * Code following the jump is unreachable, it gets a block of its own so the
+     * jump stays the last instruction of the current block
+     *
+     * */
+    let unreachable_block_name = function_value.get_unique_block_name();
+    let unreachable_block_bb =
+        context.append_basic_block(function_value, unreachable_block_name.as_str());
+    builder.position_at_end(&unreachable_block_bb);
+
+    return Ok(());
 }
 
+/*
+ * Codegen trusts the parser to hand over only well typed ast, constructs which it
+ * does not support yet are reported as a Diagnostic pointing at them
+ *
+ * */
 pub(crate) fn consume_ast_in_module<'a>(
     asts: &Vec<AstPtr>,
     context: &'a Context,
@@ -202,40 +454,124 @@ pub(crate) fn consume_ast_in_module<'a>(
     function_value: &mut FunctionValue,
     symbol_table: &mut SymbolTable<'a>,
     module: &'a Module,
-) {
+) -> Result<(), Diagnostic> {
     for cur_ast in asts.iter() {
-        if let Ast::Declaration(dec) = cur_ast {
-            match dec {
-                Declaration::FunctionDeclaration {
+        consume_top_level_ast(
+            cur_ast,
+            context,
+            builder,
+            function_value,
+            symbol_table,
+            module,
+        )?;
+    }
+
+    return Ok(());
+}
+
+fn consume_top_level_ast<'a>(
+    cur_ast: &AstPtr,
+    context: &'a Context,
+    builder: &'a Builder,
+    function_value: &mut FunctionValue,
+    symbol_table: &mut SymbolTable<'a>,
+    module: &'a Module,
+) -> Result<(), Diagnostic> {
+    if let Ast::Declaration(dec) = cur_ast {
+        match dec {
+            Declaration::FunctionDeclaration {
+                arguments,
+                blocks,
+                ident_name,
+                return_type,
+                captures,
+                span: _,
+            } => {
+                consume_function_declaration(
                     arguments,
                     blocks,
                     ident_name,
                     return_type,
-                } => {
-                    consume_function_declaration(
-                        arguments,
-                        blocks,
-                        ident_name,
-                        return_type,
+                    captures,
+                    false,
+                    context,
+                    module,
+                    symbol_table,
+                )?;
+                build_store_captures_in_globals(captures, context, builder, symbol_table, module);
+            }
+
+            Declaration::ImportDeclaration {
+                from: _,
+                ident,
+                classes,
+                generic_functions,
+                span,
+            } => consume_import_declaration(
+                ident,
+                classes,
+                generic_functions,
+                *span,
+                context,
+                symbol_table,
+                module,
+            )?,
+
+            // Instances are generated where it is called, see consume_generic_instance
+            Declaration::GenericFunctionDeclaration { function, span: _ } => {
+                symbol_table
+                    .generic_functions
+                    .insert(function.llvm_name.clone(), function.clone());
+
+                if let Some(Ast::Declaration(Declaration::FunctionDeclaration {
+                    captures, ..
+                })) = function.function
+                {
+                    build_store_captures_in_globals(
+                        captures,
                         context,
-                        module,
+                        builder,
                         symbol_table,
+                        module,
                     );
                 }
+            }
 
-                Declaration::ImportDeclaration { from: _, ident } => {
-                    consume_import_declaration(ident, context, symbol_table, module)
-                }
+            Declaration::ClassDeclaration {
+                class,
+                functions,
+                span: _,
+            } => {
+                consume_class_declaration(class, functions, context, builder, symbol_table, module)?
+            }
 
-                _ => consume_single_ast(
-                    cur_ast,
-                    context,
-                    builder,
-                    function_value,
-                    symbol_table,
-                    module,
-                ),
+            Declaration::EnumDeclaration {
+                enumeration: _,
+                reverse_mapping,
+                span: _,
+            } => {
+                if let Some(reverse_mapping) = reverse_mapping {
+                    consume_top_level_ast(
+                        reverse_mapping,
+                        context,
+                        builder,
+                        function_value,
+                        symbol_table,
+                        module,
+                    )?;
+                }
             }
+
+            _ => consume_single_ast(
+                cur_ast,
+                context,
+                builder,
+                function_value,
+                symbol_table,
+                module,
+            )?,
         }
     }
+
+    return Ok(());
 }
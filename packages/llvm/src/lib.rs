@@ -1,3 +1,6 @@
+// Codegen returns a Diagnostic by value from every function which may fail
+#![allow(clippy::result_large_err)]
+
 use std::path::Path;
 
 use ast::AstPtr;
@@ -11,14 +14,29 @@ use inkwell::{
     },
     types::enums::OptimizationLevel,
 };
+use lexer::diagnostic::Diagnostic;
 
+mod build_any_object;
+mod build_array;
 mod build_assignment;
+mod build_class;
+mod build_closure;
+mod build_console;
+mod build_conversion;
+mod build_equality;
+mod build_exception;
 mod build_expression;
+mod build_logical;
+mod build_number;
+mod build_string;
+mod build_union;
 mod codegen;
 pub mod compiler_provided_fn;
 mod enums;
+mod gc_frame;
 mod gen_ast;
 mod llvm_utils;
+mod runtime;
 mod symbol_table;
 #[cfg(test)]
 mod tests;
@@ -26,7 +44,8 @@ mod utils;
 
 pub fn compile_to_llvm_ir(content: Vec<AstPtr>) -> String {
     let context = Context::create();
-    let module = compile_to_llvm_module(content, &context, "main", true);
+    let module = compile_to_llvm_module(content, &context, "main", true)
+        .unwrap_or_else(|diagnostic| panic!("{:?}", diagnostic));
     return module.get_string_representation().to_string();
 }
 
@@ -35,11 +54,12 @@ pub fn compile_to_llvm_module<'a>(
     context: &'a Context,
     module_name: &str,
     is_main_file: bool,
-) -> Module<'a> {
+) -> Result<Module<'a>, Diagnostic> {
     let mut codegen = Codegen::new(&content);
     let module = context.create_module(module_name);
     let builder = context.create_builder();
-    let main_fn_type = context.void_type().fn_type(&[], false);
+    // Returns the exit status, a normal exit is 0 and an uncaught exception exits with 1
+    let main_fn_type = context.i32_type().fn_type(&[], false);
     let linkage_type = {
         if is_main_file {
             None
@@ -49,18 +69,14 @@ pub fn compile_to_llvm_module<'a>(
     };
     let mut main_fn = module.add_function("main", main_fn_type, linkage_type);
 
-    let entry = context.append_basic_block(&main_fn, "entry");
-    builder.position_at_end(&entry);
-
-    codegen.consume(&context, &builder, &module, &mut main_fn);
-    builder.build_return(None);
+    codegen.consume(&context, &builder, &module, &mut main_fn)?;
 
     if cfg!(test) {
         if let Err(err_str) = module.verify() {
             println!("{}", err_str.to_string());
         }
     }
-    return module;
+    return Ok(module);
 }
 
 // pub fn link_llvm_module_resolver<'a>(resolver: Resolver<Module<'a>>) -> Module<'a> {
@@ -81,7 +97,12 @@ pub fn write_assembly_file_to_path<'a>(module: &Module<'a>, path: &Path) {
     Target::initialize_x86(&InitializationConfig::default());
 
     let opt = OptimizationLevel::Default;
-    let reloc = RelocMode::Default;
+    /*
+     * gcc links position independent executables by default, so globals such as
+     * string constants have to be addressed relative to the instruction pointer
+     *
+     * */
+    let reloc = RelocMode::PIC;
     let model = CodeModel::Default;
     let target = Target::from_triple(&TargetTriple::get_default_triple()).unwrap();
     let target_machine = target
@@ -7,6 +7,14 @@ use inkwell::{
     },
 };
 
+use crate::{
+    build_closure::get_closure_pointer_type,
+    build_union::get_union_pointer_type,
+    runtime::{
+        array::get_array_pointer_type, get_byte_pointer_type, string::get_string_pointer_type,
+    },
+};
+
 pub(crate) trait LLVMUtils<'a> {
     fn force_to_basic_type(&self, context: &'a Context) -> BasicTypeEnum<'a>;
 }
@@ -19,29 +27,23 @@ impl<'a> LLVMUtils<'a> for DataType {
      *   DataType::Boolean => BasicType::Int
      *   DataType::Void => BasicType::Void
      *   DataType::FunctionType => BasicType::Pointer
+     *   DataType::ArrayType => BasicType::Pointer
      *   DataType::ObjectType :: BasicType::Pointer
-     *   DataType::
+     *   DataType::String => BasicType::Pointer
+     *   DataType::UnionType => BasicType::Pointer
+     *   DataType::Null => BasicType::Pointer
+     *   DataType::Undefined => BasicType::Pointer
+     *   DataType::ClassType => BasicType::Pointer
+     *   DataType::Object => BasicType::Pointer
      *  */
     fn force_to_basic_type(&self, context: &'a Context) -> BasicTypeEnum<'a> {
         match self {
             DataType::Float => context.f64_type().as_basic_type_enum(),
             DataType::Boolean => context.i1_type().as_basic_type_enum(),
             DataType::Void => context.void_type().as_basic_type_enum(),
-            DataType::FunctionType {
-                return_type,
-                arguments,
-            } => {
-                let return_type = return_type.force_to_basic_type(context);
-                let arguments: Vec<BasicTypeEnum> = arguments
-                    .iter()
-                    .map(|data_type| {
-                        return data_type.force_to_basic_type(context);
-                    })
-                    .collect();
-
-                let fn_type = return_type.fn_type(&arguments, false);
-                let ptr_type = fn_type.ptr_type(AddressSpace::Generic);
-                return ptr_type.as_basic_type_enum();
+            // Function values are closures, see build_closure
+            DataType::FunctionType { .. } => {
+                return get_closure_pointer_type(context).as_basic_type_enum();
             }
 
             DataType::ObjectType { entries } => {
@@ -57,10 +59,24 @@ impl<'a> LLVMUtils<'a> for DataType {
                     .ptr_type(AddressSpace::Generic)
                     .as_basic_type_enum();
             }
-            DataType::String => context
-                .i8_type()
-                .ptr_type(AddressSpace::Generic)
-                .as_basic_type_enum(),
+            DataType::String => get_string_pointer_type(context).as_basic_type_enum(),
+            DataType::ArrayType { base_type: _ } => {
+                get_array_pointer_type(context).as_basic_type_enum()
+            }
+            DataType::UnionType { types: _ } => {
+                get_union_pointer_type(context).as_basic_type_enum()
+            }
+
+            // Instance is cast to the struct of its class when it is used, see build_class
+            DataType::ClassType { .. } => get_byte_pointer_type(context).as_basic_type_enum(),
+
+            // Record is cast to its struct when it is used, see build_any_object
+            DataType::Object => get_byte_pointer_type(context).as_basic_type_enum(),
+
+            // Both of them have a single value, which is always a null pointer
+            DataType::Null | DataType::Undefined => {
+                get_byte_pointer_type(context).as_basic_type_enum()
+            }
 
             _ => panic!("Cannot convert data_type {:?} to BasicTypeEnum", self),
         }
@@ -0,0 +1,816 @@
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage, RealCompareOperator},
+    module::Module,
+    types::{
+        enums::AddressSpace, ptr_type::PointerType, struct_type::StructType, traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use super::{
+    build_counted_loop, build_is_null, build_runtime_call, build_runtime_call_returning_int,
+    build_runtime_call_returning_pointer,
+    exception::build_throw_error,
+    expect_float, expect_int, expect_pointer,
+    gc::{build_gc_alloc, build_gc_alloc_slots},
+    get_byte_pointer_type, get_nth_float_param, get_nth_int_param, get_nth_pointer_param,
+    get_runtime_fn,
+    string::get_string_pointer_type,
+    ARRAY_GET_SLOT, ARRAY_GROW, ARRAY_INDEX_OF_BITS, ARRAY_INDEX_OF_NUMBER, ARRAY_INDEX_OF_STRING,
+    ARRAY_NEW, ARRAY_POP_SLOT, ARRAY_PUSH_SLOT, ARRAY_SET_SLOT, ARRAY_SLICE, MEMCPY,
+    STRING_COMPARE,
+};
+
+/*
+ * At runtime an array is a pointer to
+ *
+ *      { i64, i64, i8* }
+ *
+ * where the fields are the length, the capacity and the heap allocated buffer with
+ * room for `capacity` elements. Every element takes a slot of 8 bytes whatever its
+ * type, so the runtime can move elements around without knowing their type and
+ * booleans only use the first byte of their slot
+ *
+ * Pushing past the capacity moves the elements to a buffer twice as big, the
+ * header never moves so it is what variables and other arrays hold
+ *
+ * Buffers of strings, arrays and objects are allocated as arrays of pointers, so
+ * the collector traces every slot including the ones past the length, which are
+ * either null or still hold a popped element
+ *
+ * */
+// Size of `{ i64, i64, i8* }` on x86_64
+const ARRAY_HEADER_SIZE: u64 = 24;
+const ARRAY_BUFFER_OFFSET: u64 = 16;
+
+const ARRAY_MIN_CAPACITY: u64 = 4;
+
+const ARRAY_LENGTH_FIELD: u64 = 0;
+const ARRAY_CAPACITY_FIELD: u64 = 1;
+const ARRAY_BUFFER_FIELD: u64 = 2;
+
+/*
+ * When true reading an element which does not exist is a RangeError instead of
+ * undefined, see build_return_undefined_slot
+ *
+ * */
+const ARRAY_STRICT_MODE: &str = "|runtime|array_strict_mode|";
+
+const OUT_OF_BOUNDS_MESSAGE: &str = "RangeError: Array index out of bounds";
+
+pub(crate) fn get_array_type<'a>(context: &'a Context) -> StructType<'a> {
+    let field_types = vec![
+        context.i64_type().as_basic_type_enum(),
+        context.i64_type().as_basic_type_enum(),
+        get_byte_pointer_type(context).as_basic_type_enum(),
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+pub(crate) fn get_array_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return get_array_type(context).ptr_type(AddressSpace::Generic);
+}
+
+fn build_array_field_pointer<'a>(
+    array: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let indices = [
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(get_array_type(context), &array, &indices, reg_name);
+}
+
+fn build_load_array_int<'a>(
+    array: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let field_pointer = build_array_field_pointer(
+        array,
+        field,
+        context,
+        builder,
+        &format!("{}_pointer", reg_name),
+    );
+    let value = builder.build_load(
+        field_pointer,
+        context.i64_type().as_basic_type_enum(),
+        reg_name,
+    );
+
+    return expect_int(value);
+}
+
+pub(crate) fn build_load_array_length<'a>(
+    array: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    return build_load_array_int(array, ARRAY_LENGTH_FIELD, context, builder, reg_name);
+}
+
+fn build_load_array_buffer<'a>(
+    array: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let field_pointer = build_array_field_pointer(
+        array,
+        ARRAY_BUFFER_FIELD,
+        context,
+        builder,
+        &format!("{}_pointer", reg_name),
+    );
+    let buffer = builder.build_load(
+        field_pointer,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        reg_name,
+    );
+
+    return expect_pointer(buffer);
+}
+
+fn build_store_array_field<'a>(
+    array: PointerValue<'a>,
+    field: u64,
+    value: &dyn BasicValueTrait<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+) {
+    let field_pointer = build_array_field_pointer(array, field, context, builder, "field_pointer");
+    builder.build_store(field_pointer, value.as_basic_value_enum());
+}
+
+/*
+ * Returns pointer to the slot of the element at `index` without checking the
+ * bounds, the caller casts it to a pointer of the element type
+ *
+ * */
+pub(crate) fn build_array_slot<'a>(
+    array: PointerValue<'a>,
+    index: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let buffer = build_load_array_buffer(array, context, builder, &format!("{}_buffer", reg_name));
+    let slots = builder.build_bitcast(
+        buffer,
+        context.i64_type().ptr_type(AddressSpace::Generic),
+        &format!("{}_slots", reg_name),
+    );
+    let slot = builder.build_gep_2(context.i64_type(), &slots, &[index], reg_name);
+
+    return builder.build_bitcast(slot, get_byte_pointer_type(context), reg_name);
+}
+
+pub(crate) fn build_array_runtime<'a>(context: &'a Context, module: &'a Module, strict_mode: bool) {
+    let strict_mode_global = module.add_global(context.i1_type(), ARRAY_STRICT_MODE);
+    strict_mode_global.set_initializer(&context.i1_type().const_int(strict_mode as u64, false));
+    strict_mode_global.set_constant(true);
+    strict_mode_global.set_linkage(&Linkage::Private);
+
+    build_array_new(context, module);
+    build_array_grow(context, module);
+    build_array_get_slot(context, module);
+    build_array_set_slot(context, module);
+    build_array_push_slot(context, module);
+    build_array_pop_slot(context, module);
+    build_array_slice(context, module);
+    build_array_index_of(
+        ARRAY_INDEX_OF_NUMBER,
+        context,
+        module,
+        |builder, slot, value| {
+            let slot = builder.build_bitcast(
+                slot,
+                context.f64_type().ptr_type(AddressSpace::Generic),
+                "number_slot",
+            );
+            let element =
+                builder.build_load(slot, context.f64_type().as_basic_type_enum(), "element");
+
+            if let (BasicValueEnum::FloatValue(element), BasicValueEnum::FloatValue(value)) =
+                (element, value)
+            {
+                return builder.build_float_compare(
+                    RealCompareOperator::Equal,
+                    element,
+                    value,
+                    "is_equal",
+                );
+            } else {
+                unreachable!();
+            }
+        },
+    );
+    build_array_index_of(
+        ARRAY_INDEX_OF_STRING,
+        context,
+        module,
+        |builder, slot, value| {
+            let string_pointer_type = get_string_pointer_type(context);
+            let slot = builder.build_bitcast(
+                slot,
+                string_pointer_type.ptr_type(AddressSpace::Generic),
+                "string_slot",
+            );
+            let element =
+                builder.build_load(slot, string_pointer_type.as_basic_type_enum(), "element");
+            let order = build_runtime_call_returning_int(
+                STRING_COMPARE,
+                &[element, value],
+                context,
+                builder,
+                module,
+                "order",
+            );
+
+            return builder.build_int_compare(
+                IntCompareOperator::Equal,
+                order,
+                context.i32_type().const_int(0, false),
+                "is_equal",
+            );
+        },
+    );
+    build_array_index_of(
+        ARRAY_INDEX_OF_BITS,
+        context,
+        module,
+        |builder, slot, value| {
+            let slot = builder.build_bitcast(
+                slot,
+                context.i64_type().ptr_type(AddressSpace::Generic),
+                "bits_slot",
+            );
+            let element =
+                builder.build_load(slot, context.i64_type().as_basic_type_enum(), "element");
+
+            return builder.build_int_compare(
+                IntCompareOperator::Equal,
+                expect_int(element),
+                expect_int(value),
+                "is_equal",
+            );
+        },
+    );
+}
+
+/*
+ * Allocates an array of `length` zeroed elements
+ *
+ * */
+fn build_array_new<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_NEW, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let length = get_nth_int_param(&fn_value, 0);
+    let holds_pointers = get_nth_int_param(&fn_value, 1);
+
+    let raw_header = build_gc_alloc(
+        context.i64_type().const_int(ARRAY_HEADER_SIZE, false),
+        &[ARRAY_BUFFER_OFFSET],
+        context,
+        &builder,
+        module,
+        "raw_header",
+    );
+    let header = builder.build_bitcast(raw_header, get_array_pointer_type(context), "header");
+    let buffer = build_gc_alloc_slots(length, holds_pointers, context, &builder, module, "buffer");
+
+    build_store_array_field(header, ARRAY_LENGTH_FIELD, &length, context, &builder);
+    build_store_array_field(header, ARRAY_CAPACITY_FIELD, &length, context, &builder);
+    build_store_array_field(header, ARRAY_BUFFER_FIELD, &buffer, context, &builder);
+
+    builder.build_return(Some(&header));
+}
+
+/*
+ * Makes room for at least `min_capacity` elements, the capacity at least doubles
+ * every time the buffer is moved so pushing is amortized constant time
+ *
+ * */
+fn build_array_grow<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_GROW, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let grow_block = context.append_basic_block(&fn_value, "grow");
+    let done_block = context.append_basic_block(&fn_value, "done");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let min_capacity = get_nth_int_param(&fn_value, 1);
+    let holds_pointers = get_nth_int_param(&fn_value, 2);
+
+    let capacity = build_load_array_int(array, ARRAY_CAPACITY_FIELD, context, &builder, "capacity");
+    let is_enough = builder.build_int_compare(
+        IntCompareOperator::SignedLessThanOrEqual,
+        min_capacity,
+        capacity,
+        "is_enough",
+    );
+    builder.build_conditional_branch(is_enough, &done_block, &grow_block);
+
+    builder.position_at_end(&grow_block);
+    let doubled = builder.build_int_mul(capacity, i64_type.const_int(2, false), "doubled");
+    let new_capacity = build_int_max(doubled, min_capacity, &builder, "new_capacity");
+    let new_capacity = build_int_max(
+        new_capacity,
+        i64_type.const_int(ARRAY_MIN_CAPACITY, false),
+        &builder,
+        "new_capacity",
+    );
+
+    let new_buffer = build_gc_alloc_slots(
+        new_capacity,
+        holds_pointers,
+        context,
+        &builder,
+        module,
+        "new_buffer",
+    );
+    let buffer = build_load_array_buffer(array, context, &builder, "buffer");
+    let length = build_load_array_length(array, context, &builder, "length");
+    let size = builder.build_int_mul(length, i64_type.const_int(8, false), "size");
+    build_runtime_call(
+        MEMCPY,
+        &[
+            new_buffer.as_basic_value_enum(),
+            buffer.as_basic_value_enum(),
+            size.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    build_store_array_field(
+        array,
+        ARRAY_CAPACITY_FIELD,
+        &new_capacity,
+        context,
+        &builder,
+    );
+    build_store_array_field(array, ARRAY_BUFFER_FIELD, &new_buffer, context, &builder);
+    builder.build_unconditional_branch(&done_block);
+
+    builder.position_at_end(&done_block);
+    builder.build_return(None);
+}
+
+/*
+ * Returns the slot of element at `index`, when there is no such element the
+ * `undefined_slot` passed by the caller is returned instead
+ *
+ * */
+fn build_array_get_slot<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_GET_SLOT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let in_bounds_block = context.append_basic_block(&fn_value, "in_bounds");
+    let out_of_bounds_block = context.append_basic_block(&fn_value, "out_of_bounds");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let index = get_nth_float_param(&fn_value, 1);
+    let undefined_slot = get_nth_pointer_param(&fn_value, 2);
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    let index = build_index_check(
+        index,
+        length,
+        &in_bounds_block,
+        &out_of_bounds_block,
+        context,
+        &builder,
+        &fn_value,
+    );
+
+    builder.position_at_end(&in_bounds_block);
+    let slot = build_array_slot(array, index, context, &builder, "slot");
+    builder.build_return(Some(&slot));
+
+    builder.position_at_end(&out_of_bounds_block);
+    build_return_undefined_slot(undefined_slot, context, &builder, module, &fn_value);
+}
+
+/*
+ * Returns the slot to assign the element at `index`, assigning right after the
+ * last element appends it. Arrays cannot have holes, so assigning anywhere else is
+ * a RangeError
+ *
+ * */
+fn build_array_set_slot<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_SET_SLOT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let push_block = context.append_basic_block(&fn_value, "push");
+    let check_block = context.append_basic_block(&fn_value, "check");
+    let in_bounds_block = context.append_basic_block(&fn_value, "in_bounds");
+    let out_of_bounds_block = context.append_basic_block(&fn_value, "out_of_bounds");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let index = get_nth_float_param(&fn_value, 1);
+    let holds_pointers = get_nth_int_param(&fn_value, 2);
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    let float_length = builder.build_si_to_fp(length, context.f64_type(), "float_length");
+    let is_end =
+        builder.build_float_compare(RealCompareOperator::Equal, index, float_length, "is_end");
+    builder.build_conditional_branch(is_end, &push_block, &check_block);
+
+    builder.position_at_end(&push_block);
+    let slot = build_runtime_call_returning_pointer(
+        ARRAY_PUSH_SLOT,
+        &[
+            array.as_basic_value_enum(),
+            holds_pointers.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "slot",
+    );
+    builder.build_return(Some(&slot));
+
+    builder.position_at_end(&check_block);
+    let index = build_index_check(
+        index,
+        length,
+        &in_bounds_block,
+        &out_of_bounds_block,
+        context,
+        &builder,
+        &fn_value,
+    );
+
+    builder.position_at_end(&in_bounds_block);
+    let slot = build_array_slot(array, index, context, &builder, "slot");
+    builder.build_return(Some(&slot));
+
+    builder.position_at_end(&out_of_bounds_block);
+    build_throw_error(OUT_OF_BOUNDS_MESSAGE, context, &builder, module);
+}
+
+/*
+ * Appends an element and returns its slot
+ *
+ * */
+fn build_array_push_slot<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_PUSH_SLOT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let holds_pointers = get_nth_int_param(&fn_value, 1);
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    let new_length =
+        builder.build_int_add(length, context.i64_type().const_int(1, false), "new_length");
+    build_runtime_call(
+        ARRAY_GROW,
+        &[
+            array.as_basic_value_enum(),
+            new_length.as_basic_value_enum(),
+            holds_pointers.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    build_store_array_field(array, ARRAY_LENGTH_FIELD, &new_length, context, &builder);
+
+    let slot = build_array_slot(array, length, context, &builder, "slot");
+    builder.build_return(Some(&slot));
+}
+
+/*
+ * Removes the last element and returns its slot, which stays valid till the next
+ * push. An empty array returns the `undefined_slot` passed by the caller
+ *
+ * */
+fn build_array_pop_slot<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_POP_SLOT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let pop_block = context.append_basic_block(&fn_value, "pop");
+    let empty_block = context.append_basic_block(&fn_value, "empty");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let undefined_slot = get_nth_pointer_param(&fn_value, 1);
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    let is_empty = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        length,
+        context.i64_type().const_int(0, false),
+        "is_empty",
+    );
+    builder.build_conditional_branch(is_empty, &empty_block, &pop_block);
+
+    builder.position_at_end(&pop_block);
+    let new_length =
+        builder.build_int_sub(length, context.i64_type().const_int(1, false), "new_length");
+    build_store_array_field(array, ARRAY_LENGTH_FIELD, &new_length, context, &builder);
+    let slot = build_array_slot(array, new_length, context, &builder, "slot");
+    builder.build_return(Some(&slot));
+
+    builder.position_at_end(&empty_block);
+    build_return_undefined_slot(undefined_slot, context, &builder, module, &fn_value);
+}
+
+/*
+ * Copies the elements from `start` till `end` into a new array, negative indices
+ * count from the end and both of them are clamped to the array like
+ * Array.prototype.slice does
+ *
+ * */
+fn build_array_slice<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(ARRAY_SLICE, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let start = get_nth_float_param(&fn_value, 1);
+    let end = get_nth_float_param(&fn_value, 2);
+    let holds_pointers = get_nth_int_param(&fn_value, 3);
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    let start = build_relative_index(start, length, context, &builder, "start");
+    let end = build_relative_index(end, length, context, &builder, "end");
+
+    let count = builder.build_int_sub(end, start, "count");
+    let count = build_int_max(count, i64_type.const_int(0, false), &builder, "count");
+
+    let sliced = build_runtime_call_returning_pointer(
+        ARRAY_NEW,
+        &[
+            count.as_basic_value_enum(),
+            holds_pointers.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "sliced",
+    );
+    let sliced_buffer = build_load_array_buffer(sliced, context, &builder, "sliced_buffer");
+    let start_slot = build_array_slot(array, start, context, &builder, "start_slot");
+    let size = builder.build_int_mul(count, i64_type.const_int(8, false), "size");
+    build_runtime_call(
+        MEMCPY,
+        &[
+            sliced_buffer.as_basic_value_enum(),
+            start_slot.as_basic_value_enum(),
+            size.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    builder.build_return(Some(&sliced));
+}
+
+/*
+ * Returns the position of the first element for which `is_equal` holds as a
+ * number, or -1 if there is none
+ *
+ * */
+fn build_array_index_of<'a, F>(name: &str, context: &'a Context, module: &'a Module, is_equal: F)
+where
+    F: Fn(&Builder<'a>, PointerValue<'a>, BasicValueEnum<'a>) -> IntValue<'a>,
+{
+    let fn_value = get_runtime_fn(name, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let array = get_nth_pointer_param(&fn_value, 0);
+    let value = fn_value.get_nth_param(1).unwrap();
+
+    let length = build_load_array_length(array, context, &builder, "length");
+    build_counted_loop(length, context, &builder, &fn_value, "elements", |index| {
+        let slot = build_array_slot(array, index, context, &builder, "slot");
+        let is_found = is_equal(&builder, slot, value.clone());
+
+        let found_block = context.append_basic_block(&fn_value, "found");
+        let next_block = context.append_basic_block(&fn_value, "next");
+        builder.build_conditional_branch(is_found, &found_block, &next_block);
+
+        builder.position_at_end(&found_block);
+        let position = builder.build_si_to_fp(index, context.f64_type(), "position");
+        builder.build_return(Some(&position));
+
+        builder.position_at_end(&next_block);
+    });
+
+    builder.build_return(Some(&context.f64_type().const_float(-1.0)));
+}
+
+/*
+ * Branches to `in_bounds_block` when `index` is an integer in [0, length) and to
+ * `out_of_bounds_block` otherwise, returns the index converted to an integer which
+ * can be used only in `in_bounds_block`
+ *
+ * The checks are done on the number itself, because converting NaN or a number
+ * which does not fit in i64 to an integer gives a meaningless value
+ *
+ * */
+fn build_index_check<'a>(
+    index: FloatValue<'a>,
+    length: IntValue<'a>,
+    in_bounds_block: &BasicBlock<'a>,
+    out_of_bounds_block: &BasicBlock<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &FunctionValue<'a>,
+) -> IntValue<'a> {
+    let below_length_block = context.append_basic_block(fn_value, "below_length");
+    let is_integer_block = context.append_basic_block(fn_value, "is_integer");
+
+    let is_not_negative = builder.build_float_compare(
+        RealCompareOperator::GreaterThanOrEqual,
+        index,
+        context.f64_type().const_float(0.0),
+        "is_not_negative",
+    );
+    builder.build_conditional_branch(is_not_negative, &below_length_block, out_of_bounds_block);
+
+    builder.position_at_end(&below_length_block);
+    let float_length = builder.build_si_to_fp(length, context.f64_type(), "float_length");
+    let is_below_length = builder.build_float_compare(
+        RealCompareOperator::LessThan,
+        index,
+        float_length,
+        "is_below_length",
+    );
+    builder.build_conditional_branch(is_below_length, &is_integer_block, out_of_bounds_block);
+
+    builder.position_at_end(&is_integer_block);
+    let int_index = builder.build_fp_to_si(index, context.i64_type(), "int_index");
+    let truncated = builder.build_si_to_fp(int_index, context.f64_type(), "truncated");
+    let is_integer =
+        builder.build_float_compare(RealCompareOperator::Equal, truncated, index, "is_integer");
+    builder.build_conditional_branch(is_integer, in_bounds_block, out_of_bounds_block);
+
+    return int_index;
+}
+
+/*
+ * Returns the `undefined_slot` passed by generated code for an element which does
+ * not exist. Compound assignment has no element to update and passes null, for it
+ * and for every read in strict mode it is a RangeError
+ *
+ * */
+fn build_return_undefined_slot<'a>(
+    undefined_slot: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    fn_value: &FunctionValue<'a>,
+) {
+    let check_null_block = context.append_basic_block(fn_value, "check_null");
+    let undefined_block = context.append_basic_block(fn_value, "undefined");
+    let error_block = context.append_basic_block(fn_value, "error");
+
+    let strict_mode = builder.build_load(
+        module
+            .get_global(ARRAY_STRICT_MODE)
+            .unwrap()
+            .as_pointer_value(),
+        context.i1_type().as_basic_type_enum(),
+        "strict_mode",
+    );
+    builder.build_conditional_branch(expect_int(strict_mode), &error_block, &check_null_block);
+
+    builder.position_at_end(&check_null_block);
+    let is_null = build_is_null(undefined_slot, context, builder, "is_null");
+    builder.build_conditional_branch(is_null, &error_block, &undefined_block);
+
+    builder.position_at_end(&undefined_block);
+    builder.build_return(Some(&undefined_slot));
+
+    builder.position_at_end(&error_block);
+    build_throw_error(OUT_OF_BOUNDS_MESSAGE, context, builder, module);
+}
+
+/*
+ * Converts the argument of slice to an index in [0, length], NaN is 0 and negative
+ * numbers count from the end
+ *
+ * */
+fn build_relative_index<'a>(
+    value: FloatValue<'a>,
+    length: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let f64_type = context.f64_type();
+    let float_length = builder.build_si_to_fp(length, f64_type, "float_length");
+    let negative_length = builder.build_float_neg(float_length, "negative_length");
+
+    // Clamped to [-length, length] first so the conversion to an integer is exact
+    let is_above = builder.build_float_compare(
+        RealCompareOperator::GreaterThan,
+        value,
+        float_length,
+        "is_above",
+    );
+    let value = builder.build_select(
+        is_above,
+        float_length.as_basic_value_enum(),
+        value.as_basic_value_enum(),
+        reg_name,
+    );
+    let value = expect_float(value);
+    let is_below = builder.build_float_compare(
+        RealCompareOperator::LessThan,
+        value,
+        negative_length,
+        "is_below",
+    );
+    let value = builder.build_select(
+        is_below,
+        negative_length.as_basic_value_enum(),
+        value.as_basic_value_enum(),
+        reg_name,
+    );
+    let value = expect_float(value);
+    let is_number =
+        builder.build_float_compare(RealCompareOperator::Equal, value, value, "is_number");
+    let value = builder.build_select(
+        is_number,
+        value.as_basic_value_enum(),
+        f64_type.const_float(0.0).as_basic_value_enum(),
+        reg_name,
+    );
+
+    let index = builder.build_fp_to_si(expect_float(value), context.i64_type(), reg_name);
+    let from_end = builder.build_int_add(index, length, "from_end");
+    let is_negative = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        index,
+        context.i64_type().const_int(0, false),
+        "is_negative",
+    );
+    let index = builder.build_select(
+        is_negative,
+        from_end.as_basic_value_enum(),
+        index.as_basic_value_enum(),
+        reg_name,
+    );
+
+    return expect_int(index);
+}
+
+fn build_int_max<'a>(
+    lhs: IntValue<'a>,
+    rhs: IntValue<'a>,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let is_greater = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        lhs,
+        rhs,
+        "is_greater",
+    );
+    let max = builder.build_select(
+        is_greater,
+        lhs.as_basic_value_enum(),
+        rhs.as_basic_value_enum(),
+        reg_name,
+    );
+
+    return expect_int(max);
+}
@@ -0,0 +1,87 @@
+use inkwell::{
+    context::Context,
+    enums::IntCompareOperator,
+    module::Module,
+    types::{enums::AddressSpace, traits::BasicTypeTrait},
+};
+
+use super::{
+    build_is_null, expect_pointer, get_byte_pointer_type, get_nth_pointer_param, get_runtime_fn,
+    IS_INSTANCE,
+};
+
+pub(crate) fn build_class_runtime<'a>(context: &'a Context, module: &'a Module) {
+    build_is_instance(context, module);
+}
+
+/*
+ * Whether the vtable is the vtable of the class or of one of its subclasses, the
+ * first slot of a vtable holds the vtable of the parent class or null
+ *
+ *      while (vtable != null) {
+ *          if (vtable == class_vtable) return true;
+ *          vtable = vtable[0];
+ *      }
+ *      return false;
+ *
+ * */
+fn build_is_instance<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(IS_INSTANCE, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let byte_pointer_type = get_byte_pointer_type(context);
+    let vtable = get_nth_pointer_param(&fn_value, 0);
+    let class_vtable = get_nth_pointer_param(&fn_value, 1);
+
+    let cursor = builder.build_alloca(byte_pointer_type.clone(), "cursor");
+    builder.build_store(cursor, vtable);
+
+    let condition_block = context.append_basic_block(&fn_value, "condition");
+    let check_block = context.append_basic_block(&fn_value, "check");
+    let parent_block = context.append_basic_block(&fn_value, "parent");
+    let found_block = context.append_basic_block(&fn_value, "found");
+    let not_found_block = context.append_basic_block(&fn_value, "not_found");
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&condition_block);
+    let vtable = expect_pointer(builder.build_load(
+        cursor,
+        byte_pointer_type.as_basic_type_enum(),
+        "vtable",
+    ));
+    let is_null = build_is_null(vtable, context, &builder, "is_null");
+    builder.build_conditional_branch(is_null, &not_found_block, &check_block);
+
+    builder.position_at_end(&check_block);
+    let address = builder.build_ptr_to_int(vtable, context.i64_type(), "address");
+    let class_address = builder.build_ptr_to_int(class_vtable, context.i64_type(), "class_address");
+    let is_class = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        address,
+        class_address,
+        "is_class",
+    );
+    builder.build_conditional_branch(is_class, &found_block, &parent_block);
+
+    builder.position_at_end(&parent_block);
+    let parent_slot = builder.build_bitcast(
+        vtable,
+        byte_pointer_type.ptr_type(AddressSpace::Generic),
+        "parent_slot",
+    );
+    let parent = builder.build_load(
+        parent_slot,
+        byte_pointer_type.as_basic_type_enum(),
+        "parent",
+    );
+    builder.build_store(cursor, parent);
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&found_block);
+    builder.build_return(Some(&context.i1_type().const_int(1, false)));
+
+    builder.position_at_end(&not_found_block);
+    builder.build_return(Some(&context.i1_type().const_int(0, false)));
+}
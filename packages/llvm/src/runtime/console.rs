@@ -0,0 +1,56 @@
+use inkwell::{context::Context, module::Module, values::traits::BasicValueTrait};
+
+use super::{
+    build_constant_bytes, build_runtime_call, get_nth_pointer_param, get_runtime_fn,
+    string::{build_load_string_buffer, build_load_string_length},
+    CONSOLE_WRITE, WRITE,
+};
+
+pub(crate) fn build_console_runtime<'a>(context: &'a Context, module: &'a Module) {
+    build_console_write(context, module);
+}
+
+/*
+ * Writes the string followed by a new line to the file descriptor
+ *
+ * */
+fn build_console_write<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(CONSOLE_WRITE, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let fd = fn_value.get_nth_param(0).unwrap();
+    let string = get_nth_pointer_param(&fn_value, 1);
+
+    let length = build_load_string_length(string, context, &builder, "length");
+    let buffer = build_load_string_buffer(string, context, &builder, "buffer");
+    build_runtime_call(
+        WRITE,
+        &[
+            fd.clone(),
+            buffer.as_basic_value_enum(),
+            length.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    let new_line = build_constant_bytes(b"\n", context, &builder, module, "new_line");
+    build_runtime_call(
+        WRITE,
+        &[
+            fd,
+            new_line.as_basic_value_enum(),
+            context.i64_type().const_int(1, false).as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    builder.build_return(None);
+}
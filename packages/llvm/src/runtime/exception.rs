@@ -0,0 +1,850 @@
+use ast::data_type::{get_exception_type, DataType};
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage},
+    module::Module,
+    types::{enums::AddressSpace, traits::BasicTypeTrait},
+    values::{
+        enums::BasicValueEnum, fn_value::FunctionValue, int_value::IntValue,
+        ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use crate::{
+    build_any_object::build_any_object_inspect,
+    build_union::{
+        get_tag, get_union_pointer_type, get_union_type, BOX_SIZE, PAYLOAD_FIELD, PAYLOAD_OFFSET,
+        TAG_FIELD,
+    },
+    llvm_utils::LLVMUtils,
+};
+
+use super::{
+    build_constant_bytes, build_is_null, build_runtime_call, build_runtime_call_returning_int,
+    build_runtime_call_returning_pointer, expect_int, expect_pointer, gc::build_gc_alloc,
+    get_byte_pointer_type, get_nth_int_param, get_nth_pointer_param, get_runtime_fn,
+    string::get_string_pointer_type, CATCH, CONSOLE_WRITE, EXIT, FIND_LANDING_PAD, PERSONALITY,
+    READ_ULEB128, STDERR_FD, STRING_CONCAT, STRING_FROM_BYTES, STRING_FROM_NUMBER, THROW,
+    UNWIND_GET_IP, UNWIND_GET_LANGUAGE_SPECIFIC_DATA, UNWIND_GET_REGION_START,
+    UNWIND_RAISE_EXCEPTION, UNWIND_SET_GR, UNWIND_SET_IP,
+};
+
+/*
+ * Exceptions are raised by the unwinder of the system, the same one C++ uses
+ *
+ * A throw stores the value into the single static exception
+ *
+ *      { i64 class, i8* cleanup, i64 private_1, i64 private_2, union* value }
+ *
+ * whose first 32 bytes are the `_Unwind_Exception` the unwinder works with. Only
+ * one exception is in flight at a time, every landing pad reads the value back by
+ * CATCH before anything else can throw
+ *
+ * The unwinder asks PERSONALITY of every frame whether the frame catches the
+ * exception. Every landing pad generated for a try catches everything, so the
+ * personality only has to find the landing pad of the call in the call site table
+ * LLVM emits for every function with an invoke
+ *
+ * */
+const EXCEPTION: &str = "|runtime|exception|";
+const EXCEPTION_CLASS: u64 = u64::from_be_bytes(*b"TSLLVM\0\0");
+const EXCEPTION_ALIGNMENT: u32 = 16;
+const EXCEPTION_HEADER_SIZE: u64 = 32;
+const EXCEPTION_SIZE: u32 = 40;
+
+// Values of `_Unwind_Action` and `_Unwind_Reason_Code`
+const UA_SEARCH_PHASE: u64 = 1;
+const URC_HANDLER_FOUND: u64 = 6;
+const URC_INSTALL_CONTEXT: u64 = 7;
+const URC_CONTINUE_UNWIND: u64 = 8;
+
+// Registers of x86_64 which hand the exception and the selector to a landing pad
+const EXCEPTION_REGISTER: u64 = 0;
+const SELECTOR_REGISTER: u64 = 1;
+
+// Encodings of the pointers in the language specific data area
+const DW_EH_PE_OMIT: u64 = 0xff;
+const DW_EH_PE_UDATA4: u64 = 0x03;
+
+pub(crate) fn build_exception_runtime<'a>(context: &'a Context, module: &'a Module) {
+    let exception_type = context.i64_type().array_type(EXCEPTION_SIZE / 8);
+
+    let exception = module.add_global(exception_type, EXCEPTION);
+    exception.set_initializer(&exception_type.const_zero());
+    exception.set_linkage(&Linkage::Private);
+    exception.set_alignment(EXCEPTION_ALIGNMENT);
+
+    build_throw(context, module);
+    build_catch(context, module);
+    build_personality(context, module);
+    build_find_landing_pad(context, module);
+    build_read_uleb128(context, module);
+}
+
+fn build_value_pointer<'a>(
+    exception: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+) -> PointerValue<'a> {
+    let value_pointer = builder.build_gep_2(
+        context.i8_type(),
+        &exception,
+        &[context.i64_type().const_int(EXCEPTION_HEADER_SIZE, false)],
+        "value_pointer",
+    );
+
+    return builder.build_bitcast(
+        value_pointer,
+        get_union_pointer_type(context).ptr_type(AddressSpace::Generic),
+        "value_pointer",
+    );
+}
+
+/*
+ * Raises the exception holding the union, when no frame catches it the value is
+ * written to stderr and the process exits with code 1
+ *
+ * */
+fn build_throw<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(THROW, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let value = get_nth_pointer_param(&fn_value, 0);
+
+    let exception = builder.build_bitcast(
+        module.get_global(EXCEPTION).unwrap().as_pointer_value(),
+        get_byte_pointer_type(context),
+        "exception",
+    );
+    builder.build_store(build_value_pointer(exception, context, &builder), value);
+
+    let class_pointer = builder.build_bitcast(
+        exception,
+        context.i64_type().ptr_type(AddressSpace::Generic),
+        "class_pointer",
+    );
+    builder.build_store(
+        class_pointer,
+        context.i64_type().const_int(EXCEPTION_CLASS, false),
+    );
+
+    build_runtime_call(
+        UNWIND_RAISE_EXCEPTION,
+        &[exception.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "reason",
+    );
+
+    // Unwinder returns only when no frame catches the exception
+    let prefix = build_string_from_text("Uncaught ", context, &builder, module, "prefix");
+    let text = build_value_text(value, context, &builder, &fn_value, module);
+    let message = build_runtime_call(
+        STRING_CONCAT,
+        &[prefix.as_basic_value_enum(), text.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "message",
+    )
+    .unwrap();
+
+    build_runtime_call(
+        CONSOLE_WRITE,
+        &[
+            context
+                .i32_type()
+                .const_int(STDERR_FD, false)
+                .as_basic_value_enum(),
+            message,
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    build_runtime_call(
+        EXIT,
+        &[context.i32_type().const_int(1, false).as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_return(None);
+}
+
+/*
+ * Throws the message as a string like `throw "RangeError: ..."` does, so errors of
+ * the runtime can be caught by the program. The block is terminated since the
+ * throw never returns
+ *
+ * */
+pub(super) fn build_throw_error<'a>(
+    message: &str,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+) {
+    let members = match get_exception_type() {
+        DataType::UnionType { types } => types,
+        _ => unreachable!(),
+    };
+
+    let text = build_string_from_text(message, context, builder, module, "error_text");
+    let raw_pointer = build_gc_alloc(
+        context.i64_type().const_int(BOX_SIZE, false),
+        &[PAYLOAD_OFFSET],
+        context,
+        builder,
+        module,
+        "error",
+    );
+    let error = builder.build_bitcast(raw_pointer, get_union_pointer_type(context), "error");
+
+    let field_pointer = |field: u64, name: &str| {
+        let indices = vec![
+            context.i32_type().const_int(0, false),
+            context.i32_type().const_int(field, false),
+        ];
+
+        return builder.build_gep_2(get_union_type(context), &error, &indices, name);
+    };
+    builder.build_store(
+        field_pointer(TAG_FIELD, "error_tag"),
+        context
+            .i64_type()
+            .const_int(get_tag(&DataType::String, &members), false),
+    );
+    let payload_pointer = builder.build_bitcast(
+        field_pointer(PAYLOAD_FIELD, "error_payload"),
+        get_string_pointer_type(context).ptr_type(AddressSpace::Generic),
+        "error_payload",
+    );
+    builder.build_store(payload_pointer, text);
+
+    build_runtime_call(
+        THROW,
+        &[error.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        "",
+    );
+    builder.build_unreachable();
+}
+
+fn build_string_from_text<'a>(
+    text: &str,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let bytes = build_constant_bytes(text.as_bytes(), context, builder, module, name);
+
+    return build_runtime_call_returning_pointer(
+        STRING_FROM_BYTES,
+        &[
+            bytes.as_basic_value_enum(),
+            context
+                .i64_type()
+                .const_int(text.len() as u64, false)
+                .as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        name,
+    );
+}
+
+/*
+ * Converts the thrown value to string the way `String(value)` does, an `object` is
+ * shown like console.log shows it
+ *
+ * */
+fn build_value_text<'a>(
+    value: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &FunctionValue<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let field_pointer = |field: u64, name: &str| {
+        let indices = vec![
+            context.i32_type().const_int(0, false),
+            context.i32_type().const_int(field, false),
+        ];
+
+        return builder.build_gep_2(get_union_type(context), &value, &indices, name);
+    };
+    let payload_pointer = |data_type: &DataType, name: &str| {
+        return builder.build_bitcast(
+            field_pointer(PAYLOAD_FIELD, name),
+            data_type
+                .force_to_basic_type(context)
+                .ptr_type(AddressSpace::Generic),
+            name,
+        );
+    };
+
+    let tag = expect_int(builder.build_load(
+        field_pointer(TAG_FIELD, "tag_pointer"),
+        context.i64_type().as_basic_type_enum(),
+        "tag",
+    ));
+
+    let members = match get_exception_type() {
+        DataType::UnionType { types } => types,
+        _ => unreachable!(),
+    };
+    let member_blocks: Vec<BasicBlock> = members
+        .iter()
+        .map(|member| context.append_basic_block(fn_value, &format!("{:?}", member)))
+        .collect();
+    let end_block = context.append_basic_block(fn_value, "text_end");
+
+    // Tag is always one of the members, so the last member doubles as the default
+    let cases: Vec<(IntValue, &BasicBlock)> = members
+        .iter()
+        .zip(member_blocks.iter())
+        .take(members.len() - 1)
+        .map(|(member, member_block)| {
+            return (
+                context
+                    .i64_type()
+                    .const_int(get_tag(member, &members), false),
+                member_block,
+            );
+        })
+        .collect();
+    builder.build_switch(tag, &member_blocks[members.len() - 1], &cases);
+
+    let mut incoming: Vec<(BasicValueEnum, BasicBlock)> = vec![];
+    for (member, member_block) in members.iter().zip(member_blocks.iter()) {
+        builder.position_at_end(member_block);
+
+        let text = match member {
+            DataType::Float => {
+                let number = builder.build_load(
+                    payload_pointer(member, "number_pointer"),
+                    member.force_to_basic_type(context),
+                    "number",
+                );
+                build_runtime_call_returning_pointer(
+                    STRING_FROM_NUMBER,
+                    &[number],
+                    context,
+                    builder,
+                    module,
+                    "text",
+                )
+            }
+
+            DataType::String => expect_pointer(builder.build_load(
+                payload_pointer(member, "string_pointer"),
+                member.force_to_basic_type(context),
+                "text",
+            )),
+
+            DataType::Boolean => {
+                let boolean = expect_int(builder.build_load(
+                    payload_pointer(member, "boolean_pointer"),
+                    member.force_to_basic_type(context),
+                    "boolean",
+                ));
+                let true_text = build_string_from_text("true", context, builder, module, "true");
+                let false_text = build_string_from_text("false", context, builder, module, "false");
+
+                expect_pointer(builder.build_select(
+                    boolean,
+                    true_text.as_basic_value_enum(),
+                    false_text.as_basic_value_enum(),
+                    "text",
+                ))
+            }
+
+            DataType::Null => build_string_from_text("null", context, builder, module, "text"),
+
+            DataType::Object => {
+                let record = expect_pointer(builder.build_load(
+                    payload_pointer(member, "object_pointer"),
+                    member.force_to_basic_type(context),
+                    "object",
+                ));
+                build_any_object_inspect(record, context, builder, "text")
+            }
+
+            _ => build_string_from_text("undefined", context, builder, module, "text"),
+        };
+
+        incoming.push((text.as_basic_value_enum(), builder.get_insert_block()));
+        builder.build_unconditional_branch(&end_block);
+    }
+
+    builder.position_at_end(&end_block);
+    let incoming: Vec<(BasicValueEnum, &BasicBlock)> = incoming
+        .iter()
+        .map(|(value, block)| (value.clone(), block))
+        .collect();
+
+    return expect_pointer(builder.build_phi(
+        get_string_pointer_type(context).as_basic_type_enum(),
+        &incoming,
+        "text",
+    ));
+}
+
+/*
+ * Returns the value held by the exception the landing pad received
+ *
+ * */
+fn build_catch<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(CATCH, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let exception = get_nth_pointer_param(&fn_value, 0);
+
+    let value = builder.build_load(
+        build_value_pointer(exception, context, &builder),
+        get_union_pointer_type(context).as_basic_type_enum(),
+        "value",
+    );
+    builder.build_return(Some(&value));
+}
+
+/*
+ * Called by the unwinder for every frame, first to search for a frame which
+ * catches the exception and then to jump to its landing pad
+ *
+ *      personality(version, actions, class, exception, unwind_context)
+ *
+ * */
+fn build_personality<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(PERSONALITY, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+
+    let actions = get_nth_int_param(&fn_value, 1);
+    let exception = get_nth_pointer_param(&fn_value, 3);
+    let unwind_context = get_nth_pointer_param(&fn_value, 4);
+
+    let landing_pad = build_runtime_call_returning_int(
+        FIND_LANDING_PAD,
+        &[unwind_context.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "landing_pad",
+    );
+    let is_caught = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        landing_pad,
+        i64_type.const_int(0, false),
+        "is_caught",
+    );
+
+    let caught_block = context.append_basic_block(&fn_value, "caught");
+    let continue_block = context.append_basic_block(&fn_value, "continue");
+    builder.build_conditional_branch(is_caught, &caught_block, &continue_block);
+
+    builder.position_at_end(&continue_block);
+    builder.build_return(Some(&i32_type.const_int(URC_CONTINUE_UNWIND, false)));
+
+    builder.position_at_end(&caught_block);
+    let search_phase = builder.build_and(
+        actions,
+        i32_type.const_int(UA_SEARCH_PHASE, false),
+        "search_phase",
+    );
+    let is_search_phase = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        search_phase,
+        i32_type.const_int(0, false),
+        "is_search_phase",
+    );
+
+    let found_block = context.append_basic_block(&fn_value, "found");
+    let install_block = context.append_basic_block(&fn_value, "install");
+    builder.build_conditional_branch(is_search_phase, &found_block, &install_block);
+
+    builder.position_at_end(&found_block);
+    builder.build_return(Some(&i32_type.const_int(URC_HANDLER_FOUND, false)));
+
+    builder.position_at_end(&install_block);
+    let exception_address = builder.build_ptr_to_int(exception, i64_type, "exception_address");
+    let registers = [
+        (EXCEPTION_REGISTER, exception_address),
+        (SELECTOR_REGISTER, i64_type.const_int(0, false)),
+    ];
+    for (register, value) in registers {
+        build_runtime_call(
+            UNWIND_SET_GR,
+            &[
+                unwind_context.as_basic_value_enum(),
+                i32_type.const_int(register, false).as_basic_value_enum(),
+                value.as_basic_value_enum(),
+            ],
+            context,
+            &builder,
+            module,
+            "",
+        );
+    }
+    build_runtime_call(
+        UNWIND_SET_IP,
+        &[
+            unwind_context.as_basic_value_enum(),
+            landing_pad.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_return(Some(&i32_type.const_int(URC_INSTALL_CONTEXT, false)));
+}
+
+/*
+ * Returns address of the landing pad of the call the frame is executing, or 0 when
+ * the call has none. The language specific data area emitted by LLVM is
+ *
+ *      u8 landing_pad_start_encoding, always omitted
+ *      u8 type_table_encoding, followed by uleb128 offset unless omitted
+ *      u8 call_site_encoding
+ *      uleb128 call_site_table_length
+ *      call sites { start, length, landing_pad, uleb128 action }
+ *
+ * where start and landing_pad are offsets from the start of the function
+ *
+ * */
+fn build_find_landing_pad<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(FIND_LANDING_PAD, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let unwind_context = get_nth_pointer_param(&fn_value, 0);
+
+    let lsda = build_runtime_call_returning_pointer(
+        UNWIND_GET_LANGUAGE_SPECIFIC_DATA,
+        &[unwind_context.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "lsda",
+    );
+    let is_lsda_null = build_is_null(lsda, context, &builder, "is_lsda_null");
+
+    let none_block = context.append_basic_block(&fn_value, "none");
+    let header_block = context.append_basic_block(&fn_value, "header");
+    builder.build_conditional_branch(is_lsda_null, &none_block, &header_block);
+
+    builder.position_at_end(&none_block);
+    builder.build_return(Some(&i64_type.const_int(0, false)));
+
+    builder.position_at_end(&header_block);
+    // Address after the call, which may already belong to the next call site
+    let ip = build_runtime_call_returning_int(
+        UNWIND_GET_IP,
+        &[unwind_context.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "ip",
+    );
+    let ip = builder.build_int_sub(ip, i64_type.const_int(1, false), "ip");
+    let function_start = build_runtime_call_returning_int(
+        UNWIND_GET_REGION_START,
+        &[unwind_context.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "function_start",
+    );
+
+    let cursor = builder.build_alloca(get_byte_pointer_type(context), "cursor");
+    let after_landing_pad_start = builder.build_gep_2(
+        context.i8_type(),
+        &lsda,
+        &[i64_type.const_int(1, false)],
+        "after_landing_pad_start",
+    );
+    builder.build_store(cursor, after_landing_pad_start);
+
+    let type_table_encoding = build_read_byte(cursor, context, &builder, "type_table_encoding");
+    let has_type_table = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        type_table_encoding,
+        i64_type.const_int(DW_EH_PE_OMIT, false),
+        "has_type_table",
+    );
+
+    let type_table_block = context.append_basic_block(&fn_value, "type_table");
+    let call_sites_block = context.append_basic_block(&fn_value, "call_sites");
+    builder.build_conditional_branch(has_type_table, &type_table_block, &call_sites_block);
+
+    builder.position_at_end(&type_table_block);
+    build_read_uleb128_call(cursor, context, &builder, module, "type_table_offset");
+    builder.build_unconditional_branch(&call_sites_block);
+
+    builder.position_at_end(&call_sites_block);
+    let call_site_encoding = build_read_byte(cursor, context, &builder, "call_site_encoding");
+    let table_length = build_read_uleb128_call(cursor, context, &builder, module, "table_length");
+    let table_start = builder.build_ptr_to_int(
+        build_load_cursor(cursor, context, &builder),
+        i64_type,
+        "table_start",
+    );
+    let table_end = builder.build_int_add(table_start, table_length, "table_end");
+
+    let condition_block = context.append_basic_block(&fn_value, "condition");
+    let call_site_block = context.append_basic_block(&fn_value, "call_site");
+    let matched_block = context.append_basic_block(&fn_value, "matched");
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&condition_block);
+    let position = builder.build_ptr_to_int(
+        build_load_cursor(cursor, context, &builder),
+        i64_type,
+        "position",
+    );
+    let has_call_site = builder.build_int_compare(
+        IntCompareOperator::UnsignedLessThan,
+        position,
+        table_end,
+        "has_call_site",
+    );
+    builder.build_conditional_branch(has_call_site, &call_site_block, &none_block);
+
+    builder.position_at_end(&call_site_block);
+    let start = build_read_encoded(
+        cursor,
+        call_site_encoding,
+        context,
+        &builder,
+        &fn_value,
+        module,
+        "start",
+    );
+    let start = builder.build_int_add(function_start, start, "start");
+    let length = build_read_encoded(
+        cursor,
+        call_site_encoding,
+        context,
+        &builder,
+        &fn_value,
+        module,
+        "length",
+    );
+    let end = builder.build_int_add(start, length, "end");
+    let landing_pad = build_read_encoded(
+        cursor,
+        call_site_encoding,
+        context,
+        &builder,
+        &fn_value,
+        module,
+        "landing_pad",
+    );
+    build_read_uleb128_call(cursor, context, &builder, module, "action");
+
+    let is_after_start = builder.build_int_compare(
+        IntCompareOperator::UnsignedGreaterThanOrEqual,
+        ip,
+        start,
+        "",
+    );
+    let is_before_end =
+        builder.build_int_compare(IntCompareOperator::UnsignedLessThan, ip, end, "");
+    let is_in_call_site = builder.build_and(is_after_start, is_before_end, "is_in_call_site");
+    builder.build_conditional_branch(is_in_call_site, &matched_block, &condition_block);
+
+    // Call site without landing pad has nothing to run in this frame
+    builder.position_at_end(&matched_block);
+    let has_landing_pad = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        landing_pad,
+        i64_type.const_int(0, false),
+        "has_landing_pad",
+    );
+    let landing_pad_address =
+        builder.build_int_add(function_start, landing_pad, "landing_pad_address");
+    let landing_pad = builder.build_select(
+        has_landing_pad,
+        landing_pad_address.as_basic_value_enum(),
+        i64_type.const_int(0, false).as_basic_value_enum(),
+        "landing_pad",
+    );
+    builder.build_return(Some(&landing_pad));
+}
+
+fn build_load_cursor<'a>(
+    cursor: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+) -> PointerValue<'a> {
+    return expect_pointer(builder.build_load(
+        cursor,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        "position",
+    ));
+}
+
+// Reads the byte at cursor as i64 and moves cursor past it
+fn build_read_byte<'a>(
+    cursor: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    name: &str,
+) -> IntValue<'a> {
+    let position = build_load_cursor(cursor, context, builder);
+    let byte =
+        expect_int(builder.build_load(position, context.i8_type().as_basic_type_enum(), name));
+
+    let next_position = builder.build_gep_2(
+        context.i8_type(),
+        &position,
+        &[context.i64_type().const_int(1, false)],
+        "next_position",
+    );
+    builder.build_store(cursor, next_position);
+
+    return builder.build_int_z_extend(byte, context.i64_type(), name);
+}
+
+fn build_read_uleb128_call<'a>(
+    cursor: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    return build_runtime_call_returning_int(
+        READ_ULEB128,
+        &[cursor.as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        name,
+    );
+}
+
+/*
+ * Reads a field of the call site table, LLVM encodes them either as udata4 or as
+ * uleb128. The udata4 is read byte by byte since it is not aligned
+ *
+ * */
+fn build_read_encoded<'a>(
+    cursor: PointerValue<'a>,
+    encoding: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &FunctionValue<'a>,
+    module: &'a Module,
+    name: &str,
+) -> IntValue<'a> {
+    let i64_type = context.i64_type();
+
+    let is_udata4 = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        encoding,
+        i64_type.const_int(DW_EH_PE_UDATA4, false),
+        "is_udata4",
+    );
+
+    let udata4_block = context.append_basic_block(fn_value, "udata4");
+    let uleb128_block = context.append_basic_block(fn_value, "uleb128");
+    let end_block = context.append_basic_block(fn_value, "encoded_end");
+    builder.build_conditional_branch(is_udata4, &udata4_block, &uleb128_block);
+
+    builder.position_at_end(&udata4_block);
+    let mut udata4 = i64_type.const_int(0, false);
+    for index in 0..4 {
+        let byte = build_read_byte(cursor, context, builder, "byte");
+        let byte = builder.build_left_shift(byte, i64_type.const_int(index * 8, false), "byte");
+        udata4 = builder.build_or(udata4, byte, "udata4");
+    }
+    builder.build_unconditional_branch(&end_block);
+
+    builder.position_at_end(&uleb128_block);
+    let uleb128 = build_read_uleb128_call(cursor, context, builder, module, "uleb128");
+    builder.build_unconditional_branch(&end_block);
+
+    builder.position_at_end(&end_block);
+    let value = builder.build_phi(
+        i64_type.as_basic_type_enum(),
+        &[
+            (udata4.as_basic_value_enum(), &udata4_block),
+            (uleb128.as_basic_value_enum(), &uleb128_block),
+        ],
+        name,
+    );
+
+    return expect_int(value);
+}
+
+/*
+ * Reads the unsigned LEB128 number at cursor and moves cursor past it, every byte
+ * holds 7 bits of the number and the high bit is set on every byte but the last
+ *
+ * */
+fn build_read_uleb128<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(READ_ULEB128, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let cursor = get_nth_pointer_param(&fn_value, 0);
+
+    let result_pointer = builder.build_alloca(i64_type, "result_pointer");
+    builder.build_store(result_pointer, i64_type.const_int(0, false));
+    let shift_pointer = builder.build_alloca(i64_type, "shift_pointer");
+    builder.build_store(shift_pointer, i64_type.const_int(0, false));
+
+    let byte_block = context.append_basic_block(&fn_value, "byte");
+    let end_block = context.append_basic_block(&fn_value, "end");
+    builder.build_unconditional_branch(&byte_block);
+
+    builder.position_at_end(&byte_block);
+    let byte = build_read_byte(cursor, context, &builder, "byte");
+    let shift =
+        expect_int(builder.build_load(shift_pointer, i64_type.as_basic_type_enum(), "shift"));
+    let result =
+        expect_int(builder.build_load(result_pointer, i64_type.as_basic_type_enum(), "result"));
+
+    let bits = builder.build_and(byte, i64_type.const_int(0x7f, false), "bits");
+    let bits = builder.build_left_shift(bits, shift, "bits");
+    builder.build_store(result_pointer, builder.build_or(result, bits, "result"));
+    builder.build_store(
+        shift_pointer,
+        builder.build_int_add(shift, i64_type.const_int(7, false), "shift"),
+    );
+
+    let continuation = builder.build_and(byte, i64_type.const_int(0x80, false), "continuation");
+    let has_more = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        continuation,
+        i64_type.const_int(0, false),
+        "has_more",
+    );
+    builder.build_conditional_branch(has_more, &byte_block, &end_block);
+
+    builder.position_at_end(&end_block);
+    let result = builder.build_load(result_pointer, i64_type.as_basic_type_enum(), "result");
+    builder.build_return(Some(&result));
+}
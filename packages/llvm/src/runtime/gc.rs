@@ -0,0 +1,806 @@
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage},
+    module::Module,
+    types::{enums::AddressSpace, struct_type::StructType, traits::BasicTypeTrait},
+    values::{int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait},
+};
+
+use super::{
+    build_counted_loop, build_fatal_error, build_is_null, build_runtime_call,
+    build_runtime_call_returning_pointer, expect_int, expect_pointer, get_byte_pointer_type,
+    get_nth_int_param, get_nth_pointer_param, get_runtime_fn, CALLOC, FREE, GC_ALLOC, GC_COLLECT,
+    GC_FRAME_END, GC_FRAME_RESERVE, GC_FRAME_START, GC_MARK, GC_SAFEPOINT, MEMSET,
+};
+
+/*
+ * Mark and sweep garbage collector for strings, arrays and objects
+ *
+ * Every allocation is prefixed by the header
+ *
+ *      { i8* next, i64 marked, i64 size, i64 pointer_count }
+ *
+ * followed by `size` bytes of payload and `pointer_count` i64 offsets of the
+ * payload fields which hold pointers to other allocations. When `pointer_count`
+ * is ALL_POINTERS the whole payload is an array of pointers. Generated code only
+ * ever sees the pointer to the payload
+ *
+ * Every allocation is linked from GC_HEAP so the sweep can visit them all
+ *
+ * Roots live on a shadow stack, each function reserves a frame of slots on entry
+ * and stores every reference it holds into its own slot, see gc_frame.rs. The
+ * collector runs only at safepoints, the runtime functions never collect so the
+ * pointers they juggle internally do not need to be rooted
+ *
+ * */
+const GC_HEAP: &str = "|runtime|gc_heap|";
+const GC_ALLOCATED: &str = "|runtime|gc_allocated|";
+const GC_THRESHOLD: &str = "|runtime|gc_threshold|";
+const GC_ROOTS: &str = "|runtime|gc_roots|";
+const GC_ROOT_COUNT: &str = "|runtime|gc_root_count|";
+
+pub(crate) const ALL_POINTERS: u64 = u64::MAX;
+
+// Size of the header on x86_64
+const GC_HEADER_SIZE: u64 = 32;
+
+const GC_NEXT_FIELD: u64 = 0;
+const GC_MARKED_FIELD: u64 = 1;
+const GC_SIZE_FIELD: u64 = 2;
+const GC_POINTER_COUNT_FIELD: u64 = 3;
+
+// Bytes allocated since the last collection before the next collection is due
+const GC_MIN_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+// Slots of the shadow stack, running out of them means the recursion is too deep
+const GC_MAX_ROOTS: u32 = 1 << 20;
+
+fn get_gc_header_type<'a>(context: &'a Context) -> StructType<'a> {
+    let field_types = vec![
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        context.i64_type().as_basic_type_enum(),
+        context.i64_type().as_basic_type_enum(),
+        context.i64_type().as_basic_type_enum(),
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+/*
+ * Allocates zeroed payload of `size` bytes, the offsets of the pointers inside
+ * the payload are stored right away so the allocation is ready to be traced
+ *
+ * */
+pub(crate) fn build_gc_alloc<'a>(
+    size: IntValue<'a>,
+    pointer_offsets: &[u64],
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let i64_type = context.i64_type();
+
+    let payload = build_runtime_call_returning_pointer(
+        GC_ALLOC,
+        &[
+            size.as_basic_value_enum(),
+            i64_type
+                .const_int(pointer_offsets.len() as u64, false)
+                .as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        name,
+    );
+
+    if !pointer_offsets.is_empty() {
+        let offsets = builder.build_gep_2(
+            context.i8_type(),
+            &payload,
+            &[size],
+            &format!("{}_offsets", name),
+        );
+        let offsets = builder.build_bitcast(
+            offsets,
+            i64_type.ptr_type(AddressSpace::Generic),
+            &format!("{}_offsets", name),
+        );
+
+        for (i, offset) in pointer_offsets.iter().enumerate() {
+            let offset_pointer = builder.build_gep_2(
+                i64_type,
+                &offsets,
+                &[i64_type.const_int(i as u64, false)],
+                &format!("{}_offset_{}", name, i),
+            );
+            builder.build_store(offset_pointer, i64_type.const_int(*offset, false));
+        }
+    }
+
+    return payload;
+}
+
+/*
+ * Allocates zeroed payload of `slot_count` 8 byte slots, when `holds_pointers` is
+ * true every slot is traced as a pointer
+ *
+ * */
+pub(crate) fn build_gc_alloc_slots<'a>(
+    slot_count: IntValue<'a>,
+    holds_pointers: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let i64_type = context.i64_type();
+
+    let size = builder.build_int_mul(
+        slot_count,
+        i64_type.const_int(8, false),
+        &format!("{}_size", name),
+    );
+    let pointer_count = builder.build_select(
+        holds_pointers,
+        i64_type
+            .const_int(ALL_POINTERS, false)
+            .as_basic_value_enum(),
+        i64_type.const_int(0, false).as_basic_value_enum(),
+        &format!("{}_pointer_count", name),
+    );
+
+    return build_runtime_call_returning_pointer(
+        GC_ALLOC,
+        &[size.as_basic_value_enum(), pointer_count],
+        context,
+        builder,
+        module,
+        name,
+    );
+}
+
+/*
+ * Object which is never freed, laid out like an allocation with nothing to trace
+ * so the collector can mark it like any other. It is not linked to the heap, so
+ * the sweep never visits it
+ *
+ * Returns pointer to the zeroed payload of `size` bytes
+ *
+ * */
+pub(crate) fn build_static_gc_object<'a>(
+    global_name: &str,
+    size: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let i64_type = context.i64_type();
+    let global_type = i64_type.array_type(((GC_HEADER_SIZE + size) / 8) as u32);
+
+    let global = match module.get_global(global_name) {
+        Some(global) => global,
+        None => {
+            let global = module.add_global(global_type, global_name);
+            global.set_initializer(&global_type.const_zero());
+            global.set_linkage(&Linkage::Private);
+            global
+        }
+    };
+
+    let raw_header = builder.build_bitcast(
+        global.as_pointer_value(),
+        get_byte_pointer_type(context),
+        &format!("{}_header", name),
+    );
+    return builder.build_gep_2(
+        context.i8_type(),
+        &raw_header,
+        &[i64_type.const_int(GC_HEADER_SIZE, false)],
+        name,
+    );
+}
+
+pub(crate) fn build_gc_runtime<'a>(context: &'a Context, module: &'a Module) {
+    let i64_type = context.i64_type();
+    let byte_pointer_type = get_byte_pointer_type(context);
+
+    let heap = module.add_global(byte_pointer_type.clone(), GC_HEAP);
+    heap.set_initializer(&byte_pointer_type.const_null());
+
+    let allocated = module.add_global(i64_type, GC_ALLOCATED);
+    allocated.set_initializer(&i64_type.const_int(0, false));
+
+    let threshold = module.add_global(i64_type, GC_THRESHOLD);
+    threshold.set_initializer(&i64_type.const_int(GC_MIN_THRESHOLD, false));
+
+    let roots_type = byte_pointer_type.array_type(GC_MAX_ROOTS);
+    let roots = module.add_global(roots_type, GC_ROOTS);
+    roots.set_initializer(&roots_type.const_zero());
+
+    let root_count = module.add_global(i64_type, GC_ROOT_COUNT);
+    root_count.set_initializer(&i64_type.const_int(0, false));
+
+    for global in [heap, allocated, threshold, roots, root_count] {
+        global.set_linkage(&Linkage::Private);
+    }
+
+    build_gc_alloc_fn(context, module);
+    build_gc_frame_start(context, module);
+    build_gc_frame_reserve(context, module);
+    build_gc_frame_end(context, module);
+    build_gc_safepoint(context, module);
+    build_gc_collect(context, module);
+    build_gc_mark(context, module);
+}
+
+fn get_global_pointer<'a>(name: &str, module: &'a Module) -> PointerValue<'a> {
+    return module.get_global(name).unwrap().as_pointer_value();
+}
+
+fn build_load_global_int<'a>(
+    name: &str,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let value = builder.build_load(
+        get_global_pointer(name, module),
+        context.i64_type().as_basic_type_enum(),
+        reg_name,
+    );
+
+    return expect_int(value);
+}
+
+fn build_header_field_pointer<'a>(
+    header: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let indices = [
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(get_gc_header_type(context), &header, &indices, reg_name);
+}
+
+fn build_load_header_int<'a>(
+    header: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let field_pointer = build_header_field_pointer(
+        header,
+        field,
+        context,
+        builder,
+        &format!("{}_pointer", reg_name),
+    );
+    let value = builder.build_load(
+        field_pointer,
+        context.i64_type().as_basic_type_enum(),
+        reg_name,
+    );
+
+    return expect_int(value);
+}
+
+/*
+ * Size of the whole allocation including the header and the pointer offsets
+ *
+ * */
+fn build_allocation_size<'a>(
+    size: IntValue<'a>,
+    pointer_count: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+) -> IntValue<'a> {
+    let i64_type = context.i64_type();
+
+    let is_pointer_array = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        pointer_count,
+        i64_type.const_int(ALL_POINTERS, false),
+        "is_pointer_array",
+    );
+    let offsets_size =
+        builder.build_int_mul(pointer_count, i64_type.const_int(8, false), "offsets_size");
+    let offsets_size = builder.build_select(
+        is_pointer_array,
+        i64_type.const_int(0, false).as_basic_value_enum(),
+        offsets_size.as_basic_value_enum(),
+        "offsets_size",
+    );
+
+    let payload_size = builder.build_int_add(size, expect_int(offsets_size), "payload_size");
+    return builder.build_int_add(
+        payload_size,
+        i64_type.const_int(GC_HEADER_SIZE, false),
+        "allocation_size",
+    );
+}
+
+fn build_header_from_payload<'a>(
+    payload: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+) -> PointerValue<'a> {
+    let raw_header = builder.build_gep_2(
+        context.i8_type(),
+        &payload,
+        &[context
+            .i64_type()
+            .const_int(-(GC_HEADER_SIZE as i64) as u64, true)],
+        "raw_header",
+    );
+
+    return builder.build_bitcast(
+        raw_header,
+        get_gc_header_type(context).ptr_type(AddressSpace::Generic),
+        "header",
+    );
+}
+
+/*
+ * Allocates the header, payload and pointer offsets in a single zeroed block and
+ * links it to the heap, returns pointer to the payload
+ *
+ * */
+fn build_gc_alloc_fn<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_ALLOC, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let size = get_nth_int_param(&fn_value, 0);
+    let pointer_count = get_nth_int_param(&fn_value, 1);
+
+    let allocation_size = build_allocation_size(size, pointer_count, context, &builder);
+    let raw_header = build_runtime_call_returning_pointer(
+        CALLOC,
+        &[
+            i64_type.const_int(1, false).as_basic_value_enum(),
+            allocation_size.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "raw_header",
+    );
+    let header = builder.build_bitcast(
+        raw_header,
+        get_gc_header_type(context).ptr_type(AddressSpace::Generic),
+        "header",
+    );
+
+    let heap = get_global_pointer(GC_HEAP, module);
+    let head = builder.build_load(
+        heap,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        "head",
+    );
+    let next_pointer =
+        build_header_field_pointer(header, GC_NEXT_FIELD, context, &builder, "next_pointer");
+    builder.build_store(next_pointer, head);
+    builder.build_store(heap, raw_header);
+
+    let size_pointer =
+        build_header_field_pointer(header, GC_SIZE_FIELD, context, &builder, "size_pointer");
+    builder.build_store(size_pointer, size);
+    let pointer_count_pointer = build_header_field_pointer(
+        header,
+        GC_POINTER_COUNT_FIELD,
+        context,
+        &builder,
+        "pointer_count_pointer",
+    );
+    builder.build_store(pointer_count_pointer, pointer_count);
+
+    let allocated = build_load_global_int(GC_ALLOCATED, context, &builder, module, "allocated");
+    let allocated = builder.build_int_add(allocated, allocation_size, "allocated");
+    builder.build_store(get_global_pointer(GC_ALLOCATED, module), allocated);
+
+    let payload = builder.build_gep_2(
+        context.i8_type(),
+        &raw_header,
+        &[i64_type.const_int(GC_HEADER_SIZE, false)],
+        "payload",
+    );
+    builder.build_return(Some(&payload));
+}
+
+fn build_roots_slot<'a>(
+    index: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let roots_type = get_byte_pointer_type(context).array_type(GC_MAX_ROOTS);
+    let indices = [context.i64_type().const_int(0, false), index];
+
+    return builder.build_gep_2(
+        roots_type,
+        &get_global_pointer(GC_ROOTS, module),
+        &indices,
+        reg_name,
+    );
+}
+
+/*
+ * Returns pointer to the first unused root slot, the frame of the caller starts
+ * there
+ *
+ * */
+fn build_gc_frame_start<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_FRAME_START, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let root_count = build_load_global_int(GC_ROOT_COUNT, context, &builder, module, "count");
+    let frame = build_roots_slot(root_count, context, &builder, module, "frame");
+    builder.build_return(Some(&frame));
+}
+
+/*
+ * Clears `slot_count` slots of the frame and marks them as used, exits the process
+ * when the shadow stack overflows
+ *
+ * */
+fn build_gc_frame_reserve<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_FRAME_RESERVE, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let overflow_block = context.append_basic_block(&fn_value, "overflow");
+    let reserve_block = context.append_basic_block(&fn_value, "reserve");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let frame = get_nth_pointer_param(&fn_value, 0);
+    let slot_count = get_nth_int_param(&fn_value, 1);
+
+    let root_count = build_load_global_int(GC_ROOT_COUNT, context, &builder, module, "count");
+    let new_root_count = builder.build_int_add(root_count, slot_count, "new_count");
+    let is_overflow = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        new_root_count,
+        i64_type.const_int(GC_MAX_ROOTS as u64, false),
+        "is_overflow",
+    );
+    builder.build_conditional_branch(is_overflow, &overflow_block, &reserve_block);
+
+    builder.position_at_end(&overflow_block);
+    build_fatal_error(
+        "RangeError: Maximum call stack size exceeded",
+        context,
+        &builder,
+        module,
+    );
+    builder.build_return(None);
+
+    builder.position_at_end(&reserve_block);
+    let raw_frame = builder.build_bitcast(frame, get_byte_pointer_type(context), "raw_frame");
+    let frame_size = builder.build_int_mul(slot_count, i64_type.const_int(8, false), "size");
+    build_runtime_call(
+        MEMSET,
+        &[
+            raw_frame.as_basic_value_enum(),
+            context.i32_type().const_int(0, false).as_basic_value_enum(),
+            frame_size.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_store(get_global_pointer(GC_ROOT_COUNT, module), new_root_count);
+    builder.build_return(None);
+}
+
+/*
+ * Releases the frame and every frame reserved after it
+ *
+ * */
+fn build_gc_frame_end<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_FRAME_END, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let frame = get_nth_pointer_param(&fn_value, 0);
+
+    let roots_start = build_roots_slot(
+        i64_type.const_int(0, false),
+        context,
+        &builder,
+        module,
+        "roots_start",
+    );
+    let roots_start = builder.build_ptr_to_int(roots_start, i64_type, "roots_start_address");
+    let frame = builder.build_ptr_to_int(frame, i64_type, "frame_address");
+    let frame_offset = builder.build_int_sub(frame, roots_start, "frame_offset");
+    let root_count =
+        builder.build_int_unsigned_div(frame_offset, i64_type.const_int(8, false), "count");
+
+    builder.build_store(get_global_pointer(GC_ROOT_COUNT, module), root_count);
+    builder.build_return(None);
+}
+
+/*
+ * Collects when enough bytes were allocated since the last collection
+ *
+ * */
+fn build_gc_safepoint<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_SAFEPOINT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let collect_block = context.append_basic_block(&fn_value, "collect");
+    let done_block = context.append_basic_block(&fn_value, "done");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let allocated = build_load_global_int(GC_ALLOCATED, context, &builder, module, "allocated");
+    let threshold = build_load_global_int(GC_THRESHOLD, context, &builder, module, "threshold");
+    let is_due = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThanOrEqual,
+        allocated,
+        threshold,
+        "is_due",
+    );
+    builder.build_conditional_branch(is_due, &collect_block, &done_block);
+
+    builder.position_at_end(&collect_block);
+    build_runtime_call(GC_COLLECT, &[], context, &builder, module, "");
+    builder.build_unconditional_branch(&done_block);
+
+    builder.position_at_end(&done_block);
+    builder.build_return(None);
+}
+
+/*
+ * Marks everything reachable from the roots, then frees every allocation which
+ * was not marked and clears the marks of the rest
+ *
+ * The next collection is due once the program allocates as many bytes as
+ * survived this one, so the heap stays within twice the size of the live data
+ *
+ * */
+fn build_gc_collect<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_COLLECT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let byte_pointer_type = get_byte_pointer_type(context);
+    let link_type = byte_pointer_type.ptr_type(AddressSpace::Generic);
+
+    let link_pointer = builder.build_alloca(link_type.clone(), "link_pointer");
+    let live_pointer = builder.build_alloca(i64_type, "live_pointer");
+
+    let root_count = build_load_global_int(GC_ROOT_COUNT, context, &builder, module, "count");
+    build_counted_loop(root_count, context, &builder, &fn_value, "roots", |index| {
+        let slot = build_roots_slot(index, context, &builder, module, "slot");
+        let root = builder.build_load(slot, byte_pointer_type.as_basic_type_enum(), "root");
+        build_runtime_call(GC_MARK, &[root], context, &builder, module, "");
+    });
+
+    /*
+     * Link is the field pointing to the current allocation, either the heap head or
+     * the `next` field of the previous allocation which survived
+     *
+     * */
+    let sweep_block = context.append_basic_block(&fn_value, "sweep");
+    let visit_block = context.append_basic_block(&fn_value, "visit");
+    let keep_block = context.append_basic_block(&fn_value, "keep");
+    let release_block = context.append_basic_block(&fn_value, "release");
+    let finish_block = context.append_basic_block(&fn_value, "finish");
+
+    builder.build_store(link_pointer, get_global_pointer(GC_HEAP, module));
+    builder.build_store(live_pointer, i64_type.const_int(0, false));
+    builder.build_unconditional_branch(&sweep_block);
+
+    builder.position_at_end(&sweep_block);
+    let link =
+        expect_pointer(builder.build_load(link_pointer, link_type.as_basic_type_enum(), "link"));
+    let raw_header = expect_pointer(builder.build_load(
+        link,
+        byte_pointer_type.as_basic_type_enum(),
+        "raw_header",
+    ));
+    let is_end = build_is_null(raw_header, context, &builder, "is_end");
+    builder.build_conditional_branch(is_end, &finish_block, &visit_block);
+
+    builder.position_at_end(&visit_block);
+    let header = builder.build_bitcast(
+        raw_header,
+        get_gc_header_type(context).ptr_type(AddressSpace::Generic),
+        "header",
+    );
+    let marked = build_load_header_int(header, GC_MARKED_FIELD, context, &builder, "marked");
+    let is_marked = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        marked,
+        i64_type.const_int(0, false),
+        "is_marked",
+    );
+    builder.build_conditional_branch(is_marked, &keep_block, &release_block);
+
+    builder.position_at_end(&keep_block);
+    let marked_pointer =
+        build_header_field_pointer(header, GC_MARKED_FIELD, context, &builder, "marked_pointer");
+    builder.build_store(marked_pointer, i64_type.const_int(0, false));
+
+    let size = build_load_header_int(header, GC_SIZE_FIELD, context, &builder, "size");
+    let pointer_count = build_load_header_int(
+        header,
+        GC_POINTER_COUNT_FIELD,
+        context,
+        &builder,
+        "pointer_count",
+    );
+    let allocation_size = build_allocation_size(size, pointer_count, context, &builder);
+    let live = expect_int(builder.build_load(live_pointer, i64_type.as_basic_type_enum(), "live"));
+    let live = builder.build_int_add(live, allocation_size, "live");
+    builder.build_store(live_pointer, live);
+
+    let next_pointer =
+        build_header_field_pointer(header, GC_NEXT_FIELD, context, &builder, "next_pointer");
+    builder.build_store(link_pointer, next_pointer);
+    builder.build_unconditional_branch(&sweep_block);
+
+    builder.position_at_end(&release_block);
+    let next_pointer =
+        build_header_field_pointer(header, GC_NEXT_FIELD, context, &builder, "next_pointer");
+    let next = builder.build_load(next_pointer, byte_pointer_type.as_basic_type_enum(), "next");
+    builder.build_store(link, next);
+    build_runtime_call(
+        FREE,
+        &[raw_header.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_unconditional_branch(&sweep_block);
+
+    builder.position_at_end(&finish_block);
+    let live = expect_int(builder.build_load(live_pointer, i64_type.as_basic_type_enum(), "live"));
+    let is_above_min = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        live,
+        i64_type.const_int(GC_MIN_THRESHOLD, false),
+        "is_above_min",
+    );
+    let threshold = builder.build_select(
+        is_above_min,
+        live.as_basic_value_enum(),
+        i64_type
+            .const_int(GC_MIN_THRESHOLD, false)
+            .as_basic_value_enum(),
+        "threshold",
+    );
+    builder.build_store(get_global_pointer(GC_THRESHOLD, module), threshold);
+    builder.build_store(
+        get_global_pointer(GC_ALLOCATED, module),
+        i64_type.const_int(0, false),
+    );
+    builder.build_return(None);
+}
+
+/*
+ * Marks the allocation and everything reachable from it
+ *
+ * */
+fn build_gc_mark<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(GC_MARK, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let not_null_block = context.append_basic_block(&fn_value, "not_null");
+    let unmarked_block = context.append_basic_block(&fn_value, "unmarked");
+    let pointer_array_block = context.append_basic_block(&fn_value, "pointer_array");
+    let pointer_offsets_block = context.append_basic_block(&fn_value, "pointer_offsets");
+    let done_block = context.append_basic_block(&fn_value, "done");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i64_type = context.i64_type();
+    let byte_pointer_type = get_byte_pointer_type(context);
+    let payload = get_nth_pointer_param(&fn_value, 0);
+
+    let is_null = build_is_null(payload, context, &builder, "is_null");
+    builder.build_conditional_branch(is_null, &done_block, &not_null_block);
+
+    builder.position_at_end(&not_null_block);
+    let header = build_header_from_payload(payload, context, &builder);
+    let marked = build_load_header_int(header, GC_MARKED_FIELD, context, &builder, "marked");
+    let is_marked = builder.build_int_compare(
+        IntCompareOperator::NotEqual,
+        marked,
+        i64_type.const_int(0, false),
+        "is_marked",
+    );
+    builder.build_conditional_branch(is_marked, &done_block, &unmarked_block);
+
+    builder.position_at_end(&unmarked_block);
+    let marked_pointer =
+        build_header_field_pointer(header, GC_MARKED_FIELD, context, &builder, "marked_pointer");
+    builder.build_store(marked_pointer, i64_type.const_int(1, false));
+
+    let size = build_load_header_int(header, GC_SIZE_FIELD, context, &builder, "size");
+    let pointer_count = build_load_header_int(
+        header,
+        GC_POINTER_COUNT_FIELD,
+        context,
+        &builder,
+        "pointer_count",
+    );
+    let is_pointer_array = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        pointer_count,
+        i64_type.const_int(ALL_POINTERS, false),
+        "is_pointer_array",
+    );
+    builder.build_conditional_branch(
+        is_pointer_array,
+        &pointer_array_block,
+        &pointer_offsets_block,
+    );
+
+    builder.position_at_end(&pointer_array_block);
+    let slots = builder.build_bitcast(
+        payload,
+        byte_pointer_type.ptr_type(AddressSpace::Generic),
+        "slots",
+    );
+    let slot_count =
+        builder.build_int_unsigned_div(size, i64_type.const_int(8, false), "slot_count");
+    build_counted_loop(slot_count, context, &builder, &fn_value, "slots", |index| {
+        let slot = builder.build_gep_2(byte_pointer_type.clone(), &slots, &[index], "slot");
+        let child = builder.build_load(slot, byte_pointer_type.as_basic_type_enum(), "child");
+        build_runtime_call(GC_MARK, &[child], context, &builder, module, "");
+    });
+    builder.build_unconditional_branch(&done_block);
+
+    builder.position_at_end(&pointer_offsets_block);
+    let offsets = builder.build_gep_2(context.i8_type(), &payload, &[size], "offsets");
+    let offsets =
+        builder.build_bitcast(offsets, i64_type.ptr_type(AddressSpace::Generic), "offsets");
+    build_counted_loop(
+        pointer_count,
+        context,
+        &builder,
+        &fn_value,
+        "offsets",
+        |index| {
+            let offset_pointer =
+                builder.build_gep_2(i64_type, &offsets, &[index], "offset_pointer");
+            let offset =
+                builder.build_load(offset_pointer, i64_type.as_basic_type_enum(), "offset");
+            let field =
+                builder.build_gep_2(context.i8_type(), &payload, &[expect_int(offset)], "field");
+            let field = builder.build_bitcast(
+                field,
+                byte_pointer_type.ptr_type(AddressSpace::Generic),
+                "field",
+            );
+            let child = builder.build_load(field, byte_pointer_type.as_basic_type_enum(), "child");
+            build_runtime_call(GC_MARK, &[child], context, &builder, module, "");
+        },
+    );
+    builder.build_unconditional_branch(&done_block);
+
+    builder.position_at_end(&done_block);
+    builder.build_return(None);
+}
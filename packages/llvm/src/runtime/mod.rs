@@ -0,0 +1,558 @@
+use either::Either;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, Linkage},
+    module::Module,
+    types::{
+        enums::AddressSpace, fn_type::FunctionType, ptr_type::PointerType, traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+pub(crate) mod array;
+pub(crate) mod class;
+pub(crate) mod console;
+pub(crate) mod exception;
+pub(crate) mod gc;
+pub(crate) mod string;
+
+use crate::build_union::get_union_pointer_type;
+
+use self::{array::get_array_pointer_type, string::get_string_pointer_type};
+
+/*
+ * Functions the generated code relies on at runtime, they are defined only in the
+ * module `compilerInternal` and every other module just declares them
+ *
+ * */
+pub(crate) const STRING_FROM_BYTES: &str = "|runtime|string_from_bytes|";
+pub(crate) const STRING_CONCAT: &str = "|runtime|string_concat|";
+pub(crate) const STRING_COMPARE: &str = "|runtime|string_compare|";
+pub(crate) const STRING_FROM_NUMBER: &str = "|runtime|string_from_number|";
+pub(crate) const STRING_TO_NUMBER: &str = "|runtime|string_to_number|";
+pub(crate) const STRING_CODE_POINT_AT: &str = "|runtime|string_code_point_at|";
+pub(crate) const CONSOLE_WRITE: &str = "|runtime|console_write|";
+pub(crate) const ARRAY_NEW: &str = "|runtime|array_new|";
+pub(crate) const ARRAY_GET_SLOT: &str = "|runtime|array_get_slot|";
+pub(crate) const ARRAY_SET_SLOT: &str = "|runtime|array_set_slot|";
+pub(crate) const ARRAY_PUSH_SLOT: &str = "|runtime|array_push_slot|";
+pub(crate) const ARRAY_POP_SLOT: &str = "|runtime|array_pop_slot|";
+pub(crate) const ARRAY_SLICE: &str = "|runtime|array_slice|";
+pub(crate) const ARRAY_INDEX_OF_NUMBER: &str = "|runtime|array_index_of_number|";
+pub(crate) const ARRAY_INDEX_OF_STRING: &str = "|runtime|array_index_of_string|";
+pub(crate) const ARRAY_INDEX_OF_BITS: &str = "|runtime|array_index_of_bits|";
+const ARRAY_GROW: &str = "|runtime|array_grow|";
+pub(crate) const GC_ALLOC: &str = "|runtime|gc_alloc|";
+pub(crate) const GC_FRAME_START: &str = "|runtime|gc_frame_start|";
+pub(crate) const GC_FRAME_RESERVE: &str = "|runtime|gc_frame_reserve|";
+pub(crate) const GC_FRAME_END: &str = "|runtime|gc_frame_end|";
+pub(crate) const GC_SAFEPOINT: &str = "|runtime|gc_safepoint|";
+const GC_COLLECT: &str = "|runtime|gc_collect|";
+const GC_MARK: &str = "|runtime|gc_mark|";
+pub(crate) const IS_INSTANCE: &str = "|runtime|is_instance|";
+pub(crate) const THROW: &str = "|runtime|throw|";
+pub(crate) const CATCH: &str = "|runtime|catch|";
+pub(crate) const PERSONALITY: &str = "|runtime|personality|";
+const FIND_LANDING_PAD: &str = "|runtime|find_landing_pad|";
+const READ_ULEB128: &str = "|runtime|read_uleb128|";
+
+const CALLOC: &str = "calloc";
+const FREE: &str = "free";
+const MEMSET: &str = "memset";
+const MEMCPY: &str = "memcpy";
+const MEMCMP: &str = "memcmp";
+const SNPRINTF: &str = "snprintf";
+const STRTOD: &str = "strtod";
+const STRLEN: &str = "strlen";
+const WRITE: &str = "write";
+const EXIT: &str = "exit";
+pub(crate) const POW: &str = "pow";
+const UNWIND_RAISE_EXCEPTION: &str = "_Unwind_RaiseException";
+const UNWIND_GET_LANGUAGE_SPECIFIC_DATA: &str = "_Unwind_GetLanguageSpecificData";
+const UNWIND_GET_REGION_START: &str = "_Unwind_GetRegionStart";
+const UNWIND_GET_IP: &str = "_Unwind_GetIP";
+const UNWIND_SET_IP: &str = "_Unwind_SetIP";
+const UNWIND_SET_GR: &str = "_Unwind_SetGR";
+
+pub(crate) const STDOUT_FD: u64 = 1;
+pub(crate) const STDERR_FD: u64 = 2;
+
+pub(crate) fn get_byte_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return context.i8_type().ptr_type(AddressSpace::Generic);
+}
+
+fn get_fn_type<'a>(name: &str, context: &'a Context) -> FunctionType<'a> {
+    let byte_pointer = get_byte_pointer_type(context).as_basic_type_enum();
+    let string_pointer = get_string_pointer_type(context).as_basic_type_enum();
+    let root_pointer = get_byte_pointer_type(context)
+        .ptr_type(AddressSpace::Generic)
+        .as_basic_type_enum();
+    let array_pointer = get_array_pointer_type(context).as_basic_type_enum();
+    let i1_type = context.i1_type().as_basic_type_enum();
+    let i64_type = context.i64_type().as_basic_type_enum();
+    let f64_type = context.f64_type().as_basic_type_enum();
+
+    match name {
+        STRING_FROM_BYTES => return string_pointer.fn_type(&[byte_pointer, i64_type], false),
+        STRING_CONCAT => {
+            return string_pointer.fn_type(&[string_pointer.clone(), string_pointer.clone()], false)
+        }
+        STRING_COMPARE => {
+            return context
+                .i32_type()
+                .fn_type(&[string_pointer.clone(), string_pointer], false)
+        }
+        STRING_FROM_NUMBER => return string_pointer.fn_type(&[f64_type], false),
+        STRING_TO_NUMBER => return f64_type.fn_type(&[string_pointer], false),
+        STRING_CODE_POINT_AT => {
+            return string_pointer.fn_type(&[string_pointer.clone(), i64_type], false)
+        }
+        CONSOLE_WRITE => {
+            return context.void_type().fn_type(
+                &[context.i32_type().as_basic_type_enum(), string_pointer],
+                false,
+            )
+        }
+        ARRAY_NEW => return array_pointer.fn_type(&[i64_type.clone(), i1_type], false),
+        ARRAY_GET_SLOT => {
+            return byte_pointer.fn_type(&[array_pointer, f64_type, byte_pointer.clone()], false);
+        }
+        ARRAY_POP_SLOT => {
+            return byte_pointer.fn_type(&[array_pointer, byte_pointer.clone()], false);
+        }
+        ARRAY_SET_SLOT => {
+            return byte_pointer.fn_type(&[array_pointer, f64_type, i1_type], false);
+        }
+        ARRAY_PUSH_SLOT => return byte_pointer.fn_type(&[array_pointer, i1_type], false),
+        ARRAY_SLICE => {
+            return array_pointer.fn_type(
+                &[array_pointer.clone(), f64_type.clone(), f64_type, i1_type],
+                false,
+            )
+        }
+        ARRAY_INDEX_OF_NUMBER => {
+            return f64_type.fn_type(&[array_pointer, f64_type.clone()], false);
+        }
+        ARRAY_INDEX_OF_STRING => {
+            return f64_type.fn_type(&[array_pointer, string_pointer], false);
+        }
+        ARRAY_INDEX_OF_BITS => return f64_type.fn_type(&[array_pointer, i64_type], false),
+        ARRAY_GROW => {
+            return context
+                .void_type()
+                .fn_type(&[array_pointer, i64_type, i1_type], false)
+        }
+        GC_ALLOC => return byte_pointer.fn_type(&[i64_type.clone(), i64_type], false),
+        GC_FRAME_START => return root_pointer.fn_type(&[], false),
+        GC_FRAME_RESERVE => {
+            return context
+                .void_type()
+                .fn_type(&[root_pointer, i64_type], false)
+        }
+        GC_FRAME_END => return context.void_type().fn_type(&[root_pointer], false),
+        GC_SAFEPOINT | GC_COLLECT => return context.void_type().fn_type(&[], false),
+        GC_MARK => return context.void_type().fn_type(&[byte_pointer], false),
+        IS_INSTANCE => return i1_type.fn_type(&[byte_pointer.clone(), byte_pointer], false),
+        THROW => {
+            return context.void_type().fn_type(
+                &[get_union_pointer_type(context).as_basic_type_enum()],
+                false,
+            )
+        }
+        CATCH => {
+            return get_union_pointer_type(context)
+                .as_basic_type_enum()
+                .fn_type(&[byte_pointer], false)
+        }
+        PERSONALITY => {
+            let i32_type = context.i32_type().as_basic_type_enum();
+            return i32_type.fn_type(
+                &[
+                    i32_type.clone(),
+                    i32_type.clone(),
+                    i64_type,
+                    byte_pointer.clone(),
+                    byte_pointer,
+                ],
+                false,
+            );
+        }
+        FIND_LANDING_PAD => return i64_type.fn_type(&[byte_pointer], false),
+        READ_ULEB128 => return i64_type.fn_type(&[root_pointer], false),
+
+        CALLOC => return byte_pointer.fn_type(&[i64_type.clone(), i64_type], false),
+        FREE => return context.void_type().fn_type(&[byte_pointer], false),
+        MEMSET => {
+            return byte_pointer.fn_type(
+                &[
+                    byte_pointer.clone(),
+                    context.i32_type().as_basic_type_enum(),
+                    i64_type,
+                ],
+                false,
+            )
+        }
+        MEMCPY => {
+            return byte_pointer.fn_type(
+                &[byte_pointer.clone(), byte_pointer.clone(), i64_type],
+                false,
+            )
+        }
+        MEMCMP => {
+            return context
+                .i32_type()
+                .fn_type(&[byte_pointer.clone(), byte_pointer, i64_type], false)
+        }
+        SNPRINTF => {
+            return context
+                .i32_type()
+                .fn_type(&[byte_pointer.clone(), i64_type, byte_pointer], true)
+        }
+        STRTOD => {
+            let end_pointer = get_byte_pointer_type(context)
+                .ptr_type(AddressSpace::Generic)
+                .as_basic_type_enum();
+            return context
+                .f64_type()
+                .fn_type(&[byte_pointer, end_pointer], false);
+        }
+        STRLEN => return context.i64_type().fn_type(&[byte_pointer], false),
+        WRITE => {
+            return context.i64_type().fn_type(
+                &[
+                    context.i32_type().as_basic_type_enum(),
+                    byte_pointer,
+                    i64_type,
+                ],
+                false,
+            )
+        }
+        POW => {
+            return f64_type
+                .clone()
+                .fn_type(&[f64_type.clone(), f64_type], false)
+        }
+        UNWIND_RAISE_EXCEPTION => {
+            return context
+                .i32_type()
+                .as_basic_type_enum()
+                .fn_type(&[byte_pointer], false)
+        }
+        UNWIND_GET_LANGUAGE_SPECIFIC_DATA => {
+            return byte_pointer.clone().fn_type(&[byte_pointer], false)
+        }
+        UNWIND_GET_REGION_START | UNWIND_GET_IP => return i64_type.fn_type(&[byte_pointer], false),
+        UNWIND_SET_IP => {
+            return context
+                .void_type()
+                .fn_type(&[byte_pointer, i64_type], false)
+        }
+        UNWIND_SET_GR => {
+            return context.void_type().fn_type(
+                &[
+                    byte_pointer,
+                    context.i32_type().as_basic_type_enum(),
+                    i64_type,
+                ],
+                false,
+            )
+        }
+        EXIT => {
+            return context
+                .void_type()
+                .fn_type(&[context.i32_type().as_basic_type_enum()], false)
+        }
+
+        _ => panic!("Unknown runtime function {}", name),
+    }
+}
+
+/*
+ * Returns the runtime function with given name, declaring it in the module if the
+ * module does not have it yet
+ *
+ * */
+pub(crate) fn get_runtime_fn<'a>(
+    name: &str,
+    context: &'a Context,
+    module: &'a Module,
+) -> FunctionValue<'a> {
+    if let Some(fn_value) = module.get_function(name) {
+        return fn_value;
+    }
+
+    let fn_type = get_fn_type(name, context);
+    return module.add_function(name, fn_type, None);
+}
+
+pub(crate) fn build_runtime_call<'a>(
+    name: &str,
+    args: &[BasicValueEnum<'a>],
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    reg_name: &str,
+) -> Option<BasicValueEnum<'a>> {
+    let fn_value = get_runtime_fn(name, context, module);
+    let value = builder.build_call2(Either::Left(&fn_value), args, reg_name);
+
+    if value.is_void() {
+        return None;
+    } else {
+        return Some(value.to_basic_value_enum().unwrap());
+    }
+}
+
+pub(crate) fn build_runtime_call_returning_pointer<'a>(
+    name: &str,
+    args: &[BasicValueEnum<'a>],
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let value = build_runtime_call(name, args, context, builder, module, reg_name);
+
+    if let Some(BasicValueEnum::PointerValue(pointer)) = value {
+        return pointer;
+    } else {
+        panic!("Expected runtime function {} to return a pointer", name);
+    }
+}
+
+pub(crate) fn build_runtime_call_returning_int<'a>(
+    name: &str,
+    args: &[BasicValueEnum<'a>],
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let value = build_runtime_call(name, args, context, builder, module, reg_name);
+
+    if let Some(BasicValueEnum::IntValue(int_value)) = value {
+        return int_value;
+    } else {
+        panic!("Expected runtime function {} to return an integer", name);
+    }
+}
+
+fn get_nth_pointer_param<'a>(fn_value: &FunctionValue<'a>, nth: u32) -> PointerValue<'a> {
+    if let Some(BasicValueEnum::PointerValue(param)) = fn_value.get_nth_param(nth) {
+        return param;
+    } else {
+        panic!("Expected param {} to be a pointer", nth);
+    }
+}
+
+fn get_nth_int_param<'a>(fn_value: &FunctionValue<'a>, nth: u32) -> IntValue<'a> {
+    if let Some(BasicValueEnum::IntValue(param)) = fn_value.get_nth_param(nth) {
+        return param;
+    } else {
+        panic!("Expected param {} to be an integer", nth);
+    }
+}
+
+fn get_nth_float_param<'a>(fn_value: &FunctionValue<'a>, nth: u32) -> FloatValue<'a> {
+    if let Some(BasicValueEnum::FloatValue(param)) = fn_value.get_nth_param(nth) {
+        return param;
+    } else {
+        panic!("Expected param {} to be a float", nth);
+    }
+}
+
+pub(crate) fn expect_int(value: BasicValueEnum) -> IntValue {
+    if let BasicValueEnum::IntValue(int_value) = value {
+        return int_value;
+    } else {
+        panic!(
+            "Expected value to be BasicValueEnum::IntValue but got {:?}",
+            value
+        );
+    }
+}
+
+pub(crate) fn expect_pointer(value: BasicValueEnum) -> PointerValue {
+    if let BasicValueEnum::PointerValue(pointer) = value {
+        return pointer;
+    } else {
+        panic!(
+            "Expected value to be BasicValueEnum::PointerValue but got {:?}",
+            value
+        );
+    }
+}
+
+fn expect_float(value: BasicValueEnum) -> FloatValue {
+    if let BasicValueEnum::FloatValue(float_value) = value {
+        return float_value;
+    } else {
+        panic!("Expected value to be BasicValueEnum::FloatValue");
+    }
+}
+
+/*
+ * Stores the bytes in a private constant global and returns pointer to its first
+ * element, unlike a stack allocated copy it costs nothing when executed in a loop
+ *
+ * */
+pub(crate) fn build_constant_bytes<'a>(
+    bytes: &[u8],
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+    name: &str,
+) -> PointerValue<'a> {
+    let bytes_array_type = context.i8_type().array_type(bytes.len() as u32);
+
+    let global = module.add_global(bytes_array_type, name);
+    global.set_initializer(&context.const_string(bytes));
+    global.set_constant(true);
+    global.set_linkage(&Linkage::Private);
+
+    let indices = vec![
+        context.i64_type().const_int(0, true),
+        context.i64_type().const_int(0, true),
+    ];
+
+    return builder.build_gep_2(
+        bytes_array_type,
+        &global.as_pointer_value(),
+        &indices,
+        &format!("{}_start", name),
+    );
+}
+
+fn build_is_null<'a>(
+    pointer: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let address = builder.build_ptr_to_int(pointer, context.i64_type(), "address");
+
+    return builder.build_int_compare(
+        IntCompareOperator::Equal,
+        address,
+        context.i64_type().const_int(0, false),
+        reg_name,
+    );
+}
+
+/*
+ * Writes the message followed by a new line to stderr and exits with code 1, the
+ * block still has to be terminated by the caller
+ *
+ * */
+fn build_fatal_error<'a>(
+    message: &str,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+) {
+    let message = format!("{}\n", message);
+    let message_bytes =
+        build_constant_bytes(message.as_bytes(), context, builder, module, "message");
+    build_runtime_call(
+        WRITE,
+        &[
+            context
+                .i32_type()
+                .const_int(STDERR_FD, false)
+                .as_basic_value_enum(),
+            message_bytes.as_basic_value_enum(),
+            context
+                .i64_type()
+                .const_int(message.len() as u64, false)
+                .as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        "",
+    );
+    build_runtime_call(
+        EXIT,
+        &[context.i32_type().const_int(1, false).as_basic_value_enum()],
+        context,
+        builder,
+        module,
+        "",
+    );
+}
+
+/*
+ * Builds the loop
+ *
+ *      for (index = 0; index < count; index++) body(index)
+ *
+ * and leaves the builder positioned after the loop
+ *
+ * */
+fn build_counted_loop<'a, F>(
+    count: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &FunctionValue<'a>,
+    name: &str,
+    mut body: F,
+) where
+    F: FnMut(IntValue<'a>),
+{
+    let i64_type = context.i64_type();
+
+    let index_pointer = builder.build_alloca(i64_type, &format!("{}_index_pointer", name));
+    builder.build_store(index_pointer, i64_type.const_int(0, false));
+
+    let condition_block = context.append_basic_block(fn_value, &format!("{}_condition", name));
+    let body_block = context.append_basic_block(fn_value, &format!("{}_body", name));
+    let end_block = context.append_basic_block(fn_value, &format!("{}_end", name));
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&condition_block);
+    let index = expect_int(builder.build_load(
+        index_pointer,
+        i64_type.as_basic_type_enum(),
+        &format!("{}_index", name),
+    ));
+    let is_in_range = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        index,
+        count,
+        &format!("{}_is_in_range", name),
+    );
+    builder.build_conditional_branch(is_in_range, &body_block, &end_block);
+
+    builder.position_at_end(&body_block);
+    body(index);
+    let next_index = builder.build_int_add(
+        index,
+        i64_type.const_int(1, false),
+        &format!("{}_next_index", name),
+    );
+    builder.build_store(index_pointer, next_index);
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&end_block);
+}
+
+/*
+ * Defines every runtime function in the module, should be called only on the
+ * module `compilerInternal`
+ *
+ * In strict mode reading an element past the end of an array is a RangeError
+ * instead of reading as undefined
+ *
+ * */
+pub(crate) fn build_runtime<'a>(context: &'a Context, module: &'a Module, strict_mode: bool) {
+    gc::build_gc_runtime(context, module);
+    string::build_string_runtime(context, module);
+    console::build_console_runtime(context, module);
+    array::build_array_runtime(context, module, strict_mode);
+    class::build_class_runtime(context, module);
+    exception::build_exception_runtime(context, module);
+}
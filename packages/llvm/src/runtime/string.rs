@@ -0,0 +1,1397 @@
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    enums::{IntCompareOperator, RealCompareOperator},
+    module::Module,
+    types::{
+        enums::AddressSpace, ptr_type::PointerType, struct_type::StructType, traits::BasicTypeTrait,
+    },
+    values::{
+        enums::BasicValueEnum, float_value::FloatValue, fn_value::FunctionValue,
+        int_value::IntValue, ptr_value::PointerValue, traits::BasicValueTrait,
+    },
+};
+
+use super::{
+    build_constant_bytes, build_counted_loop, build_runtime_call, build_runtime_call_returning_int,
+    build_runtime_call_returning_pointer, expect_float, expect_int, expect_pointer,
+    gc::build_gc_alloc, get_byte_pointer_type, get_nth_pointer_param, get_runtime_fn, CALLOC, FREE,
+    MEMCMP, MEMCPY, SNPRINTF, STRING_CODE_POINT_AT, STRING_COMPARE, STRING_CONCAT,
+    STRING_FROM_BYTES, STRING_FROM_NUMBER, STRING_TO_NUMBER, STRLEN, STRTOD,
+};
+
+/*
+ * At runtime a string is a pointer to
+ *
+ *      { i64, i8* }
+ *
+ * where the first field is the length of the string in bytes and the second field
+ * is the heap allocated buffer holding the utf-8 bytes. Buffer is not null terminated
+ * and strings are never mutated once created so the same pointer can be shared freely
+ *
+ * Both the header and the buffer are owned by the garbage collector, the header
+ * tells the collector where the pointer to the buffer is
+ *
+ * */
+// Size of `{ i64, i8* }` on x86_64
+const STRING_HEADER_SIZE: u64 = 16;
+const STRING_BUFFER_OFFSET: u64 = 8;
+
+// Big enough to hold any number formatted by build_string_from_number
+const NUMBER_BUFFER_SIZE: u32 = 32;
+
+// Only spelling of infinity which javascript reads as a number
+const INFINITY: &[u8] = b"Infinity";
+
+const STRING_LENGTH_FIELD: u64 = 0;
+const STRING_BUFFER_FIELD: u64 = 1;
+
+pub(crate) fn get_string_type<'a>(context: &'a Context) -> StructType<'a> {
+    let field_types = vec![
+        context.i64_type().as_basic_type_enum(),
+        get_byte_pointer_type(context).as_basic_type_enum(),
+    ];
+
+    return context.struct_type(&field_types, false);
+}
+
+pub(crate) fn get_string_pointer_type<'a>(context: &'a Context) -> PointerType<'a> {
+    return get_string_type(context).ptr_type(AddressSpace::Generic);
+}
+
+fn build_string_field_pointer<'a>(
+    string: PointerValue<'a>,
+    field: u64,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let indices = [
+        context.i32_type().const_int(0, false),
+        context.i32_type().const_int(field, false),
+    ];
+
+    return builder.build_gep_2(get_string_type(context), &string, &indices, reg_name);
+}
+
+pub(crate) fn build_load_string_length<'a>(
+    string: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let field_pointer = build_string_field_pointer(
+        string,
+        STRING_LENGTH_FIELD,
+        context,
+        builder,
+        &format!("{}_pointer", reg_name),
+    );
+    let length = builder.build_load(
+        field_pointer,
+        context.i64_type().as_basic_type_enum(),
+        reg_name,
+    );
+
+    if let BasicValueEnum::IntValue(length) = length {
+        return length;
+    } else {
+        unreachable!();
+    }
+}
+
+pub(crate) fn build_load_string_buffer<'a>(
+    string: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> PointerValue<'a> {
+    let field_pointer = build_string_field_pointer(
+        string,
+        STRING_BUFFER_FIELD,
+        context,
+        builder,
+        &format!("{}_pointer", reg_name),
+    );
+    let buffer = builder.build_load(
+        field_pointer,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        reg_name,
+    );
+
+    if let BasicValueEnum::PointerValue(buffer) = buffer {
+        return buffer;
+    } else {
+        unreachable!();
+    }
+}
+
+pub(crate) fn build_string_runtime<'a>(context: &'a Context, module: &'a Module) {
+    build_string_from_bytes(context, module);
+    build_string_concat(context, module);
+    build_string_compare(context, module);
+    build_string_from_number(context, module);
+    build_string_to_number(context, module);
+    build_string_code_point_at(context, module);
+}
+
+/*
+ * Allocates the header `{ i64, i8* }` for an already filled buffer
+ *
+ * */
+fn build_string_header<'a>(
+    buffer: PointerValue<'a>,
+    length: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+) -> PointerValue<'a> {
+    let raw_header = build_gc_alloc(
+        context.i64_type().const_int(STRING_HEADER_SIZE, false),
+        &[STRING_BUFFER_OFFSET],
+        context,
+        builder,
+        module,
+        "raw_header",
+    );
+    let header = builder.build_bitcast(raw_header, get_string_pointer_type(context), "header");
+
+    let length_pointer = build_string_field_pointer(
+        header,
+        STRING_LENGTH_FIELD,
+        context,
+        builder,
+        "length_pointer",
+    );
+    builder.build_store(length_pointer, length);
+
+    let buffer_pointer = build_string_field_pointer(
+        header,
+        STRING_BUFFER_FIELD,
+        context,
+        builder,
+        "buffer_pointer",
+    );
+    builder.build_store(buffer_pointer, buffer);
+
+    return header;
+}
+
+/*
+ * Copies `length` bytes into a new heap buffer
+ *
+ * */
+fn build_string_from_bytes<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(STRING_FROM_BYTES, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let bytes = get_nth_pointer_param(&fn_value, 0);
+    let length = match fn_value.get_nth_param(1).unwrap() {
+        BasicValueEnum::IntValue(length) => length,
+        _ => unreachable!(),
+    };
+
+    let buffer = build_gc_alloc(length, &[], context, &builder, module, "buffer");
+    build_runtime_call(
+        MEMCPY,
+        &[
+            buffer.as_basic_value_enum(),
+            bytes.as_basic_value_enum(),
+            length.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    let header = build_string_header(buffer, length, context, &builder, module);
+    builder.build_return(Some(&header));
+}
+
+fn build_string_concat<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(STRING_CONCAT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let left = get_nth_pointer_param(&fn_value, 0);
+    let right = get_nth_pointer_param(&fn_value, 1);
+
+    let left_length = build_load_string_length(left, context, &builder, "left_length");
+    let right_length = build_load_string_length(right, context, &builder, "right_length");
+    let left_buffer = build_load_string_buffer(left, context, &builder, "left_buffer");
+    let right_buffer = build_load_string_buffer(right, context, &builder, "right_buffer");
+
+    let length = builder.build_int_add(left_length, right_length, "length");
+    let buffer = build_gc_alloc(length, &[], context, &builder, module, "buffer");
+
+    build_runtime_call(
+        MEMCPY,
+        &[
+            buffer.as_basic_value_enum(),
+            left_buffer.as_basic_value_enum(),
+            left_length.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    let tail = builder.build_gep_2(context.i8_type(), &buffer, &[left_length], "tail");
+    build_runtime_call(
+        MEMCPY,
+        &[
+            tail.as_basic_value_enum(),
+            right_buffer.as_basic_value_enum(),
+            right_length.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    let header = build_string_header(buffer, length, context, &builder, module);
+    builder.build_return(Some(&header));
+}
+
+/*
+ * Returns the character whose utf-8 encoding starts at byte `offset` as a new
+ * string, the number of bytes is found from the leading byte
+ *
+ *      0xxxxxxx => 1, 110xxxxx => 2, 1110xxxx => 3, 11110xxx => 4
+ *
+ * A stray continuation byte is returned on its own and a character cut off by the
+ * end of the string only takes the remaining bytes
+ *
+ * */
+fn build_string_code_point_at<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(STRING_CODE_POINT_AT, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i8_type = context.i8_type();
+    let i64_type = context.i64_type();
+
+    let string = get_nth_pointer_param(&fn_value, 0);
+    let offset = match fn_value.get_nth_param(1).unwrap() {
+        BasicValueEnum::IntValue(offset) => offset,
+        _ => unreachable!(),
+    };
+
+    let length = build_load_string_length(string, context, &builder, "length");
+    let buffer = build_load_string_buffer(string, context, &builder, "buffer");
+    let bytes = builder.build_gep_2(i8_type, &buffer, &[offset], "bytes");
+    let lead = match builder.build_load(bytes, i8_type.as_basic_type_enum(), "lead") {
+        BasicValueEnum::IntValue(lead) => lead,
+        _ => unreachable!(),
+    };
+
+    // Leading bytes compared as signed i8, 0xC0 is -64, 0xE0 is -32 and 0xF0 is -16
+    let mut width = i64_type.const_int(1, false);
+    for (lead_min, lead_width) in [(-64, 2), (-32, 3), (-16, 4)] {
+        let is_wider = builder.build_int_compare(
+            IntCompareOperator::SignedGreaterThanOrEqual,
+            lead,
+            i8_type.const_int(lead_min as u64, true),
+            "is_wider",
+        );
+        width = expect_int(builder.build_select(
+            is_wider,
+            i64_type.const_int(lead_width, false).as_basic_value_enum(),
+            width.as_basic_value_enum(),
+            "width",
+        ));
+    }
+
+    let is_ascii = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThanOrEqual,
+        lead,
+        i8_type.const_int(0, false),
+        "is_ascii",
+    );
+    let width = expect_int(builder.build_select(
+        is_ascii,
+        i64_type.const_int(1, false).as_basic_value_enum(),
+        width.as_basic_value_enum(),
+        "width",
+    ));
+
+    let remaining = builder.build_int_sub(length, offset, "remaining");
+    let is_cut_off = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        remaining,
+        width,
+        "is_cut_off",
+    );
+    let width = builder.build_select(
+        is_cut_off,
+        remaining.as_basic_value_enum(),
+        width.as_basic_value_enum(),
+        "width",
+    );
+
+    let character = build_runtime_call_returning_pointer(
+        STRING_FROM_BYTES,
+        &[bytes.as_basic_value_enum(), width],
+        context,
+        &builder,
+        module,
+        "character",
+    );
+    builder.build_return(Some(&character));
+}
+
+/*
+ * Compares strings byte by byte, returns a negative number if left string comes
+ * before right string, zero if both are equal and a positive number otherwise
+ *
+ * */
+fn build_string_compare<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(STRING_COMPARE, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let left = get_nth_pointer_param(&fn_value, 0);
+    let right = get_nth_pointer_param(&fn_value, 1);
+
+    let left_length = build_load_string_length(left, context, &builder, "left_length");
+    let right_length = build_load_string_length(right, context, &builder, "right_length");
+    let left_buffer = build_load_string_buffer(left, context, &builder, "left_buffer");
+    let right_buffer = build_load_string_buffer(right, context, &builder, "right_buffer");
+
+    let is_left_shorter = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        left_length,
+        right_length,
+        "is_left_shorter",
+    );
+    let is_left_longer = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        left_length,
+        right_length,
+        "is_left_longer",
+    );
+
+    let common_length = builder.build_select(
+        is_left_shorter,
+        left_length.as_basic_value_enum(),
+        right_length.as_basic_value_enum(),
+        "common_length",
+    );
+
+    let common_order = build_runtime_call_returning_int(
+        MEMCMP,
+        &[
+            left_buffer.as_basic_value_enum(),
+            right_buffer.as_basic_value_enum(),
+            common_length,
+        ],
+        context,
+        &builder,
+        module,
+        "common_order",
+    );
+
+    /*
+     * When the common part is equal the shorter string comes first
+     *
+     * */
+    let i32_type = context.i32_type();
+    let longer_order = builder.build_select(
+        is_left_longer,
+        i32_type.const_int(1, false).as_basic_value_enum(),
+        i32_type.const_int(0, false).as_basic_value_enum(),
+        "longer_order",
+    );
+    let length_order = builder.build_select(
+        is_left_shorter,
+        i32_type.const_int(-1i64 as u64, true).as_basic_value_enum(),
+        longer_order,
+        "length_order",
+    );
+
+    let is_common_equal = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        common_order,
+        i32_type.const_int(0, false),
+        "is_common_equal",
+    );
+    let order = builder.build_select(
+        is_common_equal,
+        length_order,
+        common_order.as_basic_value_enum(),
+        "order",
+    );
+
+    builder.build_return(Some(&order));
+}
+
+/*
+ * Converts number to string the way javascript does, see Number::toString of
+ * ecmascript
+ *
+ *      NaN, Infinity and -Infinity are spelled out
+ *      integers below 2^53 are printed with all of their digits
+ *      anything else is printed with the fewest digits which parse back to the
+ *      same number, in exponent form only below 1e-6 and from 1e21 on
+ *
+ * */
+fn build_string_from_number<'a>(context: &'a Context, module: &'a Module) {
+    let mut fn_value = get_runtime_fn(STRING_FROM_NUMBER, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let f64_type = context.f64_type();
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let buffer_size = i64_type
+        .const_int(NUMBER_BUFFER_SIZE as u64, false)
+        .as_basic_value_enum();
+
+    let number = match fn_value.get_nth_param(0).unwrap() {
+        BasicValueEnum::FloatValue(number) => number,
+        _ => unreachable!(),
+    };
+
+    let number_buffer_type = context.i8_type().array_type(NUMBER_BUFFER_SIZE);
+    let number_buffer = builder.build_alloca(number_buffer_type, "number_buffer");
+    let number_buffer = builder.build_gep_2(
+        number_buffer_type,
+        &number_buffer,
+        &[i64_type.const_int(0, false), i64_type.const_int(0, false)],
+        "number_buffer_start",
+    );
+    let digits_buffer = builder.build_alloca(number_buffer_type, "digits_buffer");
+    let digits_buffer = builder.build_gep_2(
+        number_buffer_type,
+        &digits_buffer,
+        &[i64_type.const_int(0, false), i64_type.const_int(0, false)],
+        "digits_buffer_start",
+    );
+    let precision_pointer = builder.build_alloca(i32_type, "precision_pointer");
+
+    let nan_block = context.append_basic_block(&fn_value, "nan");
+    let not_nan_block = context.append_basic_block(&fn_value, "not_nan");
+    let infinity_block = context.append_basic_block(&fn_value, "infinity");
+    let not_infinity_block = context.append_basic_block(&fn_value, "not_infinity");
+    let negative_infinity_block = context.append_basic_block(&fn_value, "negative_infinity");
+    let finite_block = context.append_basic_block(&fn_value, "finite");
+    let in_range_block = context.append_basic_block(&fn_value, "in_range");
+    let integer_block = context.append_basic_block(&fn_value, "integer");
+    let shortest_block = context.append_basic_block(&fn_value, "shortest");
+    let format_block = context.append_basic_block(&fn_value, "format");
+    let next_precision_block = context.append_basic_block(&fn_value, "next_precision");
+    let digits_block = context.append_basic_block(&fn_value, "digits");
+    let exponent_form_block = context.append_basic_block(&fn_value, "exponent_form");
+    let decimal_form_block = context.append_basic_block(&fn_value, "decimal_form");
+    let whole_block = context.append_basic_block(&fn_value, "whole");
+    let fraction_block = context.append_basic_block(&fn_value, "fraction");
+    let formatted_block = context.append_basic_block(&fn_value, "formatted");
+
+    // NaN is the only value which is not equal to itself
+    let is_not_nan =
+        builder.build_float_compare(RealCompareOperator::Equal, number, number, "is_not_nan");
+    builder.build_conditional_branch(is_not_nan, &not_nan_block, &nan_block);
+
+    builder.position_at_end(&nan_block);
+    build_return_constant_string("NaN", context, &builder, &mut fn_value, module);
+
+    builder.position_at_end(&not_nan_block);
+    let is_infinity = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        number,
+        f64_type.const_float(f64::INFINITY),
+        "is_infinity",
+    );
+    builder.build_conditional_branch(is_infinity, &infinity_block, &not_infinity_block);
+
+    builder.position_at_end(&infinity_block);
+    build_return_constant_string("Infinity", context, &builder, &mut fn_value, module);
+
+    builder.position_at_end(&not_infinity_block);
+    let is_negative_infinity = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        number,
+        f64_type.const_float(f64::NEG_INFINITY),
+        "is_negative_infinity",
+    );
+    builder.build_conditional_branch(
+        is_negative_infinity,
+        &negative_infinity_block,
+        &finite_block,
+    );
+
+    builder.position_at_end(&negative_infinity_block);
+    build_return_constant_string("-Infinity", context, &builder, &mut fn_value, module);
+
+    /*
+     * Adding zero turns -0 into 0, javascript prints both of them as "0". Digits
+     * are found for the magnitude and the sign is printed in front of them
+     *
+     * */
+    builder.position_at_end(&finite_block);
+    let number = builder.build_float_add(number, f64_type.const_float(0.0), "number");
+    let is_negative = builder.build_float_compare(
+        RealCompareOperator::LessThan,
+        number,
+        f64_type.const_float(0.0),
+        "is_negative",
+    );
+    let negated_number = builder.build_float_neg(number, "negated_number");
+    let magnitude = expect_float(builder.build_select(
+        is_negative,
+        negated_number.as_basic_value_enum(),
+        number.as_basic_value_enum(),
+        "magnitude",
+    ));
+    let minus_sign = build_constant_bytes(b"-\0", context, &builder, module, "minus_sign");
+    let no_sign = build_constant_bytes(b"\0", context, &builder, module, "no_sign");
+    let sign = builder.build_select(
+        is_negative,
+        minus_sign.as_basic_value_enum(),
+        no_sign.as_basic_value_enum(),
+        "sign",
+    );
+
+    // Every integer below 2^53 is exact, so all of its digits are the shortest ones
+    let is_in_range = builder.build_float_compare(
+        RealCompareOperator::LessThan,
+        magnitude,
+        f64_type.const_float(9007199254740992.0),
+        "is_in_range",
+    );
+    builder.build_conditional_branch(is_in_range, &in_range_block, &shortest_block);
+
+    builder.position_at_end(&in_range_block);
+    let integer = builder.build_fp_to_si(number, i64_type, "integer");
+    let integer_as_number = builder.build_si_to_fp(integer, f64_type, "integer_as_number");
+    let is_integer = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        integer_as_number,
+        number,
+        "is_integer",
+    );
+    builder.build_conditional_branch(is_integer, &integer_block, &shortest_block);
+
+    builder.position_at_end(&integer_block);
+    let integer_format =
+        build_constant_bytes(b"%lld\0", context, &builder, module, "integer_format");
+    build_runtime_call(
+        SNPRINTF,
+        &[
+            number_buffer.as_basic_value_enum(),
+            buffer_size.clone(),
+            integer_format.as_basic_value_enum(),
+            integer.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_unconditional_branch(&formatted_block);
+
+    /*
+     * Tries 1 till 17 significant digits, 17 are always enough for the number to
+     * parse back to itself
+     *
+     * */
+    builder.position_at_end(&shortest_block);
+    builder.build_store(precision_pointer, i32_type.const_int(1, false));
+    let digits_format = build_constant_bytes(b"%.*e\0", context, &builder, module, "digits_format");
+    builder.build_unconditional_branch(&format_block);
+
+    builder.position_at_end(&format_block);
+    let precision = expect_int(builder.build_load(
+        precision_pointer,
+        i32_type.as_basic_type_enum(),
+        "precision",
+    ));
+    let fraction_digits =
+        builder.build_int_sub(precision, i32_type.const_int(1, false), "fraction_digits");
+    build_runtime_call(
+        SNPRINTF,
+        &[
+            digits_buffer.as_basic_value_enum(),
+            buffer_size.clone(),
+            digits_format.as_basic_value_enum(),
+            fraction_digits.as_basic_value_enum(),
+            magnitude.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    let parsed_number = build_strtod_call(digits_buffer, context, &builder, module);
+    let is_exact = builder.build_float_compare(
+        RealCompareOperator::Equal,
+        parsed_number,
+        magnitude,
+        "is_exact",
+    );
+    builder.build_conditional_branch(is_exact, &digits_block, &next_precision_block);
+
+    builder.position_at_end(&next_precision_block);
+    let next_precision =
+        builder.build_int_add(precision, i32_type.const_int(1, false), "next_precision");
+    builder.build_store(precision_pointer, next_precision);
+    builder.build_unconditional_branch(&format_block);
+
+    /*
+     * Digits buffer holds `d.ddde+x`, or `de+x` for a single digit. With k digits
+     * and the decimal point n places after the first one, javascript prints
+     *
+     *      ddd000          when k <= n <= 21
+     *      dd.d            when 0 < n < k
+     *      0.000ddd        when -6 < n <= 0
+     *      d.ddde+x        otherwise, where x is n - 1 without padding
+     *
+     * */
+    builder.position_at_end(&digits_block);
+    let is_single_digit = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        precision,
+        i32_type.const_int(1, false),
+        "is_single_digit",
+    );
+    let mantissa_length =
+        builder.build_int_add(precision, i32_type.const_int(1, false), "mantissa_length");
+    let mantissa_length = expect_int(builder.build_select(
+        is_single_digit,
+        i32_type.const_int(1, false).as_basic_value_enum(),
+        mantissa_length.as_basic_value_enum(),
+        "mantissa_length",
+    ));
+    let exponent_start = builder.build_int_add(
+        mantissa_length,
+        i32_type.const_int(1, false),
+        "exponent_start",
+    );
+    let exponent_start = builder.build_int_z_extend(exponent_start, i64_type, "exponent_start");
+    let exponent_pointer = builder.build_gep_2(
+        context.i8_type(),
+        &digits_buffer,
+        &[exponent_start],
+        "exponent_pointer",
+    );
+    let exponent = build_strtod_call(exponent_pointer, context, &builder, module);
+    let exponent = builder.build_fp_to_si(exponent, i32_type, "exponent");
+    let point_position =
+        builder.build_int_add(exponent, i32_type.const_int(1, false), "point_position");
+
+    let is_large = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        point_position,
+        i32_type.const_int(21, false),
+        "is_large",
+    );
+    let is_small = builder.build_int_compare(
+        IntCompareOperator::SignedLessThanOrEqual,
+        point_position,
+        i32_type.const_int(-6i64 as u64, true),
+        "is_small",
+    );
+    let is_exponent_form = builder.build_or(is_large, is_small, "is_exponent_form");
+    builder.build_conditional_branch(is_exponent_form, &exponent_form_block, &decimal_form_block);
+
+    builder.position_at_end(&exponent_form_block);
+    let exponent_format = build_constant_bytes(
+        b"%s%.*se%+d\0",
+        context,
+        &builder,
+        module,
+        "exponent_format",
+    );
+    build_runtime_call(
+        SNPRINTF,
+        &[
+            number_buffer.as_basic_value_enum(),
+            buffer_size.clone(),
+            exponent_format.as_basic_value_enum(),
+            sign.clone(),
+            mantissa_length.as_basic_value_enum(),
+            digits_buffer.as_basic_value_enum(),
+            exponent.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_unconditional_branch(&formatted_block);
+
+    builder.position_at_end(&decimal_form_block);
+    let is_whole = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThanOrEqual,
+        point_position,
+        precision,
+        "is_whole",
+    );
+    builder.build_conditional_branch(is_whole, &whole_block, &fraction_block);
+
+    // Digits are followed by zeros, the digits after the point are at offset 2
+    builder.position_at_end(&whole_block);
+    let whole_format =
+        build_constant_bytes(b"%s%c%.*s%.*s\0", context, &builder, module, "whole_format");
+    let zeros = build_constant_bytes(&[b'0'; 22], context, &builder, module, "zeros");
+    let first_digit = expect_int(builder.build_load(
+        digits_buffer,
+        context.i8_type().as_basic_type_enum(),
+        "first_digit",
+    ));
+    let first_digit = builder.build_int_z_extend(first_digit, i32_type, "first_digit");
+    let other_digits = builder.build_gep_2(
+        context.i8_type(),
+        &digits_buffer,
+        &[i64_type.const_int(2, false)],
+        "other_digits",
+    );
+    let zero_count = builder.build_int_sub(point_position, precision, "zero_count");
+    build_runtime_call(
+        SNPRINTF,
+        &[
+            number_buffer.as_basic_value_enum(),
+            buffer_size.clone(),
+            whole_format.as_basic_value_enum(),
+            sign.clone(),
+            first_digit.as_basic_value_enum(),
+            fraction_digits.as_basic_value_enum(),
+            other_digits.as_basic_value_enum(),
+            zero_count.as_basic_value_enum(),
+            zeros.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_unconditional_branch(&formatted_block);
+
+    /*
+     * Rounding to the position of the last digit gives the same digits, the number
+     * is closer to them than to any other number with as many digits
+     *
+     * */
+    builder.position_at_end(&fraction_block);
+    let fraction_format =
+        build_constant_bytes(b"%s%.*f\0", context, &builder, module, "fraction_format");
+    let decimals = builder.build_int_sub(precision, point_position, "decimals");
+    build_runtime_call(
+        SNPRINTF,
+        &[
+            number_buffer.as_basic_value_enum(),
+            buffer_size.clone(),
+            fraction_format.as_basic_value_enum(),
+            sign.clone(),
+            decimals.as_basic_value_enum(),
+            magnitude.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    builder.build_unconditional_branch(&formatted_block);
+
+    builder.position_at_end(&formatted_block);
+    let length = build_runtime_call(
+        STRLEN,
+        &[number_buffer.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "length",
+    )
+    .unwrap();
+    let string = build_runtime_call_returning_pointer(
+        STRING_FROM_BYTES,
+        &[number_buffer.as_basic_value_enum(), length],
+        context,
+        &builder,
+        module,
+        "string",
+    );
+    builder.build_return(Some(&string));
+}
+
+// Number at the start of the null terminated bytes
+fn build_strtod_call<'a>(
+    bytes: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    module: &'a Module,
+) -> FloatValue<'a> {
+    let number = build_runtime_call(
+        STRTOD,
+        &[
+            bytes.as_basic_value_enum(),
+            get_byte_pointer_type(context)
+                .ptr_type(AddressSpace::Generic)
+                .const_null()
+                .as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        "parsed_number",
+    );
+
+    return expect_float(number.unwrap());
+}
+
+/*
+ * Converts string to number the way javascript does
+ *
+ *      surrounding whitespace is ignored and an empty string is 0
+ *      Infinity, +Infinity and -Infinity are spelled out
+ *      0x, 0o and 0b prefixes read the digits in base 16, 8 and 2
+ *      anything else has to be a decimal literal, which is read by strtod
+ *
+ * Every other string is NaN. Only ascii whitespace is ignored
+ *
+ * */
+fn build_string_to_number<'a>(context: &'a Context, module: &'a Module) {
+    let fn_value = get_runtime_fn(STRING_TO_NUMBER, context, module);
+    let entry = context.append_basic_block(&fn_value, "entry");
+    let builder = context.create_builder();
+    builder.position_at_end(&entry);
+
+    let i8_type = context.i8_type();
+    let i64_type = context.i64_type();
+    let f64_type = context.f64_type();
+    let nan = f64_type.const_float(f64::NAN);
+
+    let string = get_nth_pointer_param(&fn_value, 0);
+    let length = build_load_string_length(string, context, &builder, "length");
+    let buffer = build_load_string_buffer(string, context, &builder, "buffer");
+
+    let result_pointer = builder.build_alloca(f64_type, "result_pointer");
+    let start_pointer = builder.build_alloca(i64_type, "start_pointer");
+    let end_pointer = builder.build_alloca(i64_type, "end_pointer");
+    let value_pointer = builder.build_alloca(f64_type, "value_pointer");
+    let is_valid_pointer = builder.build_alloca(context.i1_type(), "is_valid_pointer");
+    let parse_end_pointer =
+        builder.build_alloca(get_byte_pointer_type(context), "parse_end_pointer");
+    builder.build_store(start_pointer, i64_type.const_int(0, false));
+    builder.build_store(end_pointer, length);
+
+    build_skip_whitespace(
+        start_pointer,
+        end_pointer,
+        true,
+        buffer,
+        context,
+        &builder,
+        &fn_value,
+        "trim_start",
+    );
+    build_skip_whitespace(
+        start_pointer,
+        end_pointer,
+        false,
+        buffer,
+        context,
+        &builder,
+        &fn_value,
+        "trim_end",
+    );
+
+    let infinity_block = context.append_basic_block(&fn_value, "infinity");
+    let infinity_compare_block = context.append_basic_block(&fn_value, "infinity_compare");
+    let radix_block = context.append_basic_block(&fn_value, "radix");
+    let radix_digits_block = context.append_basic_block(&fn_value, "radix_digits");
+    let decimal_block = context.append_basic_block(&fn_value, "decimal");
+    let decimal_parse_block = context.append_basic_block(&fn_value, "decimal_parse");
+    let done_block = context.append_basic_block(&fn_value, "done");
+
+    /*
+     * Trimmed string is copied into a null terminated buffer for strtod, the second
+     * null byte makes it safe to read the byte after a sign
+     *
+     * */
+    let start =
+        expect_int(builder.build_load(start_pointer, i64_type.as_basic_type_enum(), "start"));
+    let end = expect_int(builder.build_load(end_pointer, i64_type.as_basic_type_enum(), "end"));
+    let trimmed_length = builder.build_int_sub(end, start, "trimmed_length");
+    let text = builder.build_gep_2(i8_type, &buffer, &[start], "text");
+    let copy = build_runtime_call_returning_pointer(
+        CALLOC,
+        &[
+            builder
+                .build_int_add(trimmed_length, i64_type.const_int(2, false), "copy_size")
+                .as_basic_value_enum(),
+            i64_type.const_int(1, false).as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "copy",
+    );
+    build_runtime_call(
+        MEMCPY,
+        &[
+            copy.as_basic_value_enum(),
+            text.as_basic_value_enum(),
+            trimmed_length.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "",
+    );
+
+    builder.build_store(result_pointer, f64_type.const_float(0.0));
+    let is_empty = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        trimmed_length,
+        i64_type.const_int(0, false),
+        "is_empty",
+    );
+    builder.build_conditional_branch(is_empty, &done_block, &infinity_block);
+
+    builder.position_at_end(&infinity_block);
+    let first = build_load_byte(
+        copy,
+        i64_type.const_int(0, false),
+        context,
+        &builder,
+        "first",
+    );
+    let is_plus = build_is_byte(first, b'+', context, &builder, "is_plus");
+    let is_minus = build_is_byte(first, b'-', context, &builder, "is_minus");
+    let has_sign = builder.build_or(is_plus, is_minus, "has_sign");
+    let body_offset = expect_int(builder.build_select(
+        has_sign,
+        i64_type.const_int(1, false).as_basic_value_enum(),
+        i64_type.const_int(0, false).as_basic_value_enum(),
+        "body_offset",
+    ));
+    let body = builder.build_gep_2(i8_type, &copy, &[body_offset], "body");
+    let body_length = builder.build_int_sub(trimmed_length, body_offset, "body_length");
+    let is_infinity_length = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        body_length,
+        i64_type.const_int(INFINITY.len() as u64, false),
+        "is_infinity_length",
+    );
+    builder.build_conditional_branch(is_infinity_length, &infinity_compare_block, &radix_block);
+
+    builder.position_at_end(&infinity_compare_block);
+    let infinity_bytes = build_constant_bytes(INFINITY, context, &builder, module, "infinity");
+    let order = build_runtime_call_returning_int(
+        MEMCMP,
+        &[
+            body.as_basic_value_enum(),
+            infinity_bytes.as_basic_value_enum(),
+            i64_type
+                .const_int(INFINITY.len() as u64, false)
+                .as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "order",
+    );
+    let is_infinity = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        order,
+        context.i32_type().const_int(0, false),
+        "is_infinity",
+    );
+    let infinity = builder.build_select(
+        is_minus,
+        f64_type
+            .const_float(f64::NEG_INFINITY)
+            .as_basic_value_enum(),
+        f64_type.const_float(f64::INFINITY).as_basic_value_enum(),
+        "infinity",
+    );
+    builder.build_store(result_pointer, infinity);
+    builder.build_conditional_branch(is_infinity, &done_block, &radix_block);
+
+    // Prefix has to be followed by at least one digit and cannot have a sign
+    builder.position_at_end(&radix_block);
+    let second = build_load_byte(
+        copy,
+        i64_type.const_int(1, false),
+        context,
+        &builder,
+        "second",
+    );
+    let second = build_to_ascii_lowercase(second, context, &builder, "second_lowercase");
+    let is_hex = build_is_byte(second, b'x', context, &builder, "is_hex");
+    let is_octal = build_is_byte(second, b'o', context, &builder, "is_octal");
+    let is_binary = build_is_byte(second, b'b', context, &builder, "is_binary");
+    let is_prefix = builder.build_or(is_hex, is_octal, "is_prefix");
+    let is_prefix = builder.build_or(is_prefix, is_binary, "is_prefix");
+    let is_zero = build_is_byte(first, b'0', context, &builder, "is_zero");
+    let is_prefix = builder.build_and(is_prefix, is_zero, "is_prefix");
+    let has_digits = builder.build_int_compare(
+        IntCompareOperator::SignedGreaterThan,
+        trimmed_length,
+        i64_type.const_int(2, false),
+        "has_digits",
+    );
+    let is_radix = builder.build_and(is_prefix, has_digits, "is_radix");
+    builder.build_conditional_branch(is_radix, &radix_digits_block, &decimal_block);
+
+    builder.position_at_end(&radix_digits_block);
+    let radix = builder.build_select(
+        is_octal,
+        i8_type.const_int(8, false).as_basic_value_enum(),
+        i8_type.const_int(2, false).as_basic_value_enum(),
+        "radix",
+    );
+    let radix = expect_int(builder.build_select(
+        is_hex,
+        i8_type.const_int(16, false).as_basic_value_enum(),
+        radix,
+        "radix",
+    ));
+    let radix_number = builder.build_ui_to_fp(radix, f64_type, "radix_number");
+    builder.build_store(value_pointer, f64_type.const_float(0.0));
+    builder.build_store(is_valid_pointer, context.i1_type().const_int(1, false));
+
+    let digits = builder.build_gep_2(i8_type, &copy, &[i64_type.const_int(2, false)], "digits");
+    let digit_count =
+        builder.build_int_sub(trimmed_length, i64_type.const_int(2, false), "digit_count");
+    build_counted_loop(
+        digit_count,
+        context,
+        &builder,
+        &fn_value,
+        "digits",
+        |index| {
+            let byte = build_load_byte(digits, index, context, &builder, "byte");
+
+            // Subtraction wraps around, so bytes before '0' and 'a' are not below 10 and 26
+            let decimal_digit =
+                builder.build_int_sub(byte, i8_type.const_int(b'0' as u64, false), "decimal_digit");
+            let is_decimal_digit = builder.build_int_compare(
+                IntCompareOperator::UnsignedLessThan,
+                decimal_digit,
+                i8_type.const_int(10, false),
+                "is_decimal_digit",
+            );
+            let lowercase = build_to_ascii_lowercase(byte, context, &builder, "lowercase");
+            let letter_index = builder.build_int_sub(
+                lowercase,
+                i8_type.const_int(b'a' as u64, false),
+                "letter_index",
+            );
+            let is_letter = builder.build_int_compare(
+                IntCompareOperator::UnsignedLessThan,
+                letter_index,
+                i8_type.const_int(26, false),
+                "is_letter",
+            );
+            let letter_digit =
+                builder.build_int_add(letter_index, i8_type.const_int(10, false), "letter_digit");
+
+            let digit = builder.build_select(
+                is_letter,
+                letter_digit.as_basic_value_enum(),
+                i8_type
+                    .const_int(u8::MAX as u64, false)
+                    .as_basic_value_enum(),
+                "digit",
+            );
+            let digit = expect_int(builder.build_select(
+                is_decimal_digit,
+                decimal_digit.as_basic_value_enum(),
+                digit,
+                "digit",
+            ));
+            let is_digit_valid = builder.build_int_compare(
+                IntCompareOperator::UnsignedLessThan,
+                digit,
+                radix,
+                "is_digit_valid",
+            );
+
+            let is_valid = expect_int(builder.build_load(
+                is_valid_pointer,
+                context.i1_type().as_basic_type_enum(),
+                "is_valid",
+            ));
+            let is_valid = builder.build_and(is_valid, is_digit_valid, "is_valid");
+            builder.build_store(is_valid_pointer, is_valid);
+
+            let value = expect_float(builder.build_load(
+                value_pointer,
+                f64_type.as_basic_type_enum(),
+                "value",
+            ));
+            let value = builder.build_float_mul(value, radix_number, "value");
+            let digit_number = builder.build_ui_to_fp(digit, f64_type, "digit_number");
+            let value = builder.build_float_add(value, digit_number, "value");
+            builder.build_store(value_pointer, value);
+        },
+    );
+    let is_valid = expect_int(builder.build_load(
+        is_valid_pointer,
+        context.i1_type().as_basic_type_enum(),
+        "is_valid",
+    ));
+    let value = builder.build_load(value_pointer, f64_type.as_basic_type_enum(), "value");
+    let result = builder.build_select(is_valid, value, nan.as_basic_value_enum(), "result");
+    builder.build_store(result_pointer, result);
+    builder.build_unconditional_branch(&done_block);
+
+    /*
+     * strtod also reads inf, nan and hexadecimal numbers with a sign or a
+     * fraction, so the body has to start like a decimal literal
+     *
+     * */
+    builder.position_at_end(&decimal_block);
+    let body_first = build_load_byte(
+        body,
+        i64_type.const_int(0, false),
+        context,
+        &builder,
+        "body_first",
+    );
+    let body_digit = builder.build_int_sub(
+        body_first,
+        i8_type.const_int(b'0' as u64, false),
+        "body_digit",
+    );
+    let is_digit = builder.build_int_compare(
+        IntCompareOperator::UnsignedLessThan,
+        body_digit,
+        i8_type.const_int(10, false),
+        "is_digit",
+    );
+    let is_dot = build_is_byte(body_first, b'.', context, &builder, "is_dot");
+    let is_decimal = builder.build_or(is_digit, is_dot, "is_decimal");
+    let body_second = build_load_byte(
+        body,
+        i64_type.const_int(1, false),
+        context,
+        &builder,
+        "body_second",
+    );
+    let body_second =
+        build_to_ascii_lowercase(body_second, context, &builder, "body_second_lowercase");
+    let is_body_hex = build_is_byte(body_second, b'x', context, &builder, "is_body_hex");
+    let is_not_body_hex = builder.build_xor(
+        is_body_hex,
+        context.i1_type().const_int(1, false),
+        "is_not_body_hex",
+    );
+    let is_decimal = builder.build_and(is_decimal, is_not_body_hex, "is_decimal");
+    builder.build_store(result_pointer, nan);
+    builder.build_conditional_branch(is_decimal, &decimal_parse_block, &done_block);
+
+    // Whole string has to be read for it to be a number
+    builder.position_at_end(&decimal_parse_block);
+    let number = build_runtime_call(
+        STRTOD,
+        &[
+            copy.as_basic_value_enum(),
+            parse_end_pointer.as_basic_value_enum(),
+        ],
+        context,
+        &builder,
+        module,
+        "number",
+    )
+    .unwrap();
+    let parse_end = builder.build_load(
+        parse_end_pointer,
+        get_byte_pointer_type(context).as_basic_type_enum(),
+        "parse_end",
+    );
+    let parse_end =
+        builder.build_ptr_to_int(expect_pointer(parse_end), i64_type, "parse_end_address");
+    let copy_address = builder.build_ptr_to_int(copy, i64_type, "copy_address");
+    let parsed_length = builder.build_int_sub(parse_end, copy_address, "parsed_length");
+    let is_parsed = builder.build_int_compare(
+        IntCompareOperator::Equal,
+        parsed_length,
+        trimmed_length,
+        "is_parsed",
+    );
+    let result = builder.build_select(is_parsed, number, nan.as_basic_value_enum(), "result");
+    builder.build_store(result_pointer, result);
+    builder.build_unconditional_branch(&done_block);
+
+    builder.position_at_end(&done_block);
+    build_runtime_call(
+        FREE,
+        &[copy.as_basic_value_enum()],
+        context,
+        &builder,
+        module,
+        "",
+    );
+    let result = builder.build_load(result_pointer, f64_type.as_basic_type_enum(), "result");
+    builder.build_return(Some(&result));
+}
+
+/*
+ * Moves the start forward or the end backward while it is on a whitespace
+ *
+ *  condition:
+ *      br start < end, check, end
+ *
+ *  check:
+ *      br <byte> is whitespace, next, end
+ *
+ *  next:
+ *      start += 1 or end -= 1
+ *      br condition
+ *
+ * */
+fn build_skip_whitespace<'a>(
+    start_pointer: PointerValue<'a>,
+    end_pointer: PointerValue<'a>,
+    forward: bool,
+    buffer: PointerValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &FunctionValue<'a>,
+    name: &str,
+) {
+    let i64_type = context.i64_type();
+
+    let condition_block = context.append_basic_block(fn_value, &format!("{}_condition", name));
+    let check_block = context.append_basic_block(fn_value, &format!("{}_check", name));
+    let next_block = context.append_basic_block(fn_value, &format!("{}_next", name));
+    let end_block = context.append_basic_block(fn_value, &format!("{}_end", name));
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&condition_block);
+    let start = expect_int(builder.build_load(
+        start_pointer,
+        i64_type.as_basic_type_enum(),
+        &format!("{}_start", name),
+    ));
+    let end = expect_int(builder.build_load(
+        end_pointer,
+        i64_type.as_basic_type_enum(),
+        &format!("{}_end", name),
+    ));
+    let is_in_range = builder.build_int_compare(
+        IntCompareOperator::SignedLessThan,
+        start,
+        end,
+        &format!("{}_is_in_range", name),
+    );
+    builder.build_conditional_branch(is_in_range, &check_block, &end_block);
+
+    builder.position_at_end(&check_block);
+    let (index_pointer, index) = if forward {
+        (start_pointer, start)
+    } else {
+        let last =
+            builder.build_int_sub(end, i64_type.const_int(1, false), &format!("{}_last", name));
+        (end_pointer, last)
+    };
+    let byte = build_load_byte(buffer, index, context, builder, &format!("{}_byte", name));
+
+    // Space, or one of \t \n \v \f \r which are 9 to 13
+    let is_space = build_is_byte(byte, b' ', context, builder, &format!("{}_is_space", name));
+    let control_index = builder.build_int_sub(
+        byte,
+        context.i8_type().const_int(b'\t' as u64, false),
+        &format!("{}_control_index", name),
+    );
+    let is_control = builder.build_int_compare(
+        IntCompareOperator::UnsignedLessThan,
+        control_index,
+        context.i8_type().const_int(5, false),
+        &format!("{}_is_control", name),
+    );
+    let is_whitespace = builder.build_or(is_space, is_control, &format!("{}_is_whitespace", name));
+    builder.build_conditional_branch(is_whitespace, &next_block, &end_block);
+
+    builder.position_at_end(&next_block);
+    let next_index = if forward {
+        builder.build_int_add(
+            start,
+            i64_type.const_int(1, false),
+            &format!("{}_next", name),
+        )
+    } else {
+        index
+    };
+    builder.build_store(index_pointer, next_index);
+    builder.build_unconditional_branch(&condition_block);
+
+    builder.position_at_end(&end_block);
+}
+
+fn build_load_byte<'a>(
+    bytes: PointerValue<'a>,
+    index: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    let i8_type = context.i8_type();
+    let byte_pointer =
+        builder.build_gep_2(i8_type, &bytes, &[index], &format!("{}_pointer", reg_name));
+
+    return expect_int(builder.build_load(byte_pointer, i8_type.as_basic_type_enum(), reg_name));
+}
+
+fn build_is_byte<'a>(
+    byte: IntValue<'a>,
+    expected: u8,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    return builder.build_int_compare(
+        IntCompareOperator::Equal,
+        byte,
+        context.i8_type().const_int(expected as u64, false),
+        reg_name,
+    );
+}
+
+// Setting 0x20 turns ascii upper case letters into lower case letters
+fn build_to_ascii_lowercase<'a>(
+    byte: IntValue<'a>,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    reg_name: &str,
+) -> IntValue<'a> {
+    return builder.build_or(byte, context.i8_type().const_int(0x20, false), reg_name);
+}
+
+fn build_return_constant_string<'a>(
+    value: &str,
+    context: &'a Context,
+    builder: &Builder<'a>,
+    fn_value: &mut FunctionValue<'a>,
+    module: &'a Module,
+) {
+    let reg_name = fn_value.get_unique_reg_name();
+    let bytes = build_constant_bytes(value.as_bytes(), context, builder, module, &reg_name);
+    let string = build_runtime_call_returning_pointer(
+        STRING_FROM_BYTES,
+        &[
+            bytes.as_basic_value_enum(),
+            context
+                .i64_type()
+                .const_int(value.len() as u64, false)
+                .as_basic_value_enum(),
+        ],
+        context,
+        builder,
+        module,
+        &fn_value.get_unique_reg_name(),
+    );
+    builder.build_return(Some(&string));
+}
@@ -1,11 +1,56 @@
 use std::collections::HashMap;
 
-use inkwell::values::ptr_value::PointerValue;
+use ast::{class::Class, generic::GenericFunction};
+use inkwell::{basic_block::BasicBlock, values::ptr_value::PointerValue};
+use lexer::token::KeywordKind;
+
+use crate::gc_frame::GcFrame;
+
+/*
+ * Blocks which `break` and `continue` of a loop jump to, a switch only has the
+ * block for `break`
+ *
+ * */
+#[derive(Debug)]
+struct LoopTarget<'a> {
+    label: Option<String>,
+    exit_block: BasicBlock<'a>,
+    continue_block: Option<BasicBlock<'a>>,
+}
+
+/*
+ * Finally block of a try being generated, `break` and `continue` which leave the
+ * try jump to it with a completion of their own and the finally block repeats the
+ * jump after it is done, see consume_try_statement.rs
+ *
+ * */
+#[derive(Debug)]
+pub(crate) struct FinallyTarget<'a> {
+    pub(crate) completion: PointerValue<'a>,
+    pub(crate) finally_block: BasicBlock<'a>,
+    pub(crate) jumps: Vec<(KeywordKind, Option<String>)>,
+    // Number of loops and switches around the try
+    loop_depth: usize,
+}
 
 #[derive(Debug)]
 pub struct SymbolTable<'a> {
     pub global_variables: HashMap<String, PointerValue<'a>>,
     pub local_symbols: HashMap<String, PointerValue<'a>>,
+    pub(crate) gc_frame: Option<GcFrame<'a>>,
+    // Classes declared in or imported into the module by their llvm name
+    pub(crate) classes: HashMap<String, Class>,
+    // Generic functions declared in or imported into the module by their llvm name
+    pub(crate) generic_functions: HashMap<String, GenericFunction>,
+
+    // Loops and switches being generated, innermost last
+    loop_targets: Vec<LoopTarget<'a>>,
+    // Label of the loop which is about to be generated
+    loop_label: Option<String>,
+    // Landing pads of the tries being generated, innermost last
+    landing_pads: Vec<BasicBlock<'a>>,
+    // Tries with a finally block being generated, innermost last
+    finally_targets: Vec<FinallyTarget<'a>>,
 }
 
 impl<'a> SymbolTable<'a> {
@@ -13,9 +58,24 @@ impl<'a> SymbolTable<'a> {
         return SymbolTable {
             global_variables: HashMap::new(),
             local_symbols: HashMap::new(),
+            gc_frame: None,
+            classes: HashMap::new(),
+            generic_functions: HashMap::new(),
+            loop_targets: vec![],
+            loop_label: None,
+            landing_pads: vec![],
+            finally_targets: vec![],
         };
     }
 
+    // Every function being generated has its own frame
+    pub(crate) fn get_gc_frame(&mut self) -> &mut GcFrame<'a> {
+        return self
+            .gc_frame
+            .as_mut()
+            .expect("Expected the function being generated to have a gc frame");
+    }
+
     pub fn insert_local(&mut self, name: String, value: PointerValue<'a>) {
         self.local_symbols.insert(name, value);
     }
@@ -31,4 +91,122 @@ impl<'a> SymbolTable<'a> {
             return self.global_variables.get(name);
         }
     }
+
+    /*
+     * The next loop pushed with push_loop_target takes the label
+     *
+     * */
+    pub(crate) fn set_loop_label(&mut self, label: String) {
+        self.loop_label = Some(label);
+    }
+
+    pub(crate) fn push_loop_target(
+        &mut self,
+        exit_block: &BasicBlock<'a>,
+        continue_block: &BasicBlock<'a>,
+    ) {
+        self.loop_targets.push(LoopTarget {
+            label: self.loop_label.take(),
+            exit_block: exit_block.clone(),
+            continue_block: Some(continue_block.clone()),
+        });
+    }
+
+    pub(crate) fn push_switch_target(&mut self, exit_block: &BasicBlock<'a>) {
+        self.loop_targets.push(LoopTarget {
+            label: None,
+            exit_block: exit_block.clone(),
+            continue_block: None,
+        });
+    }
+
+    pub(crate) fn pop_loop_target(&mut self) {
+        self.loop_targets.pop();
+    }
+
+    /*
+     * Block of the innermost loop or switch if label is None, parser makes sure
+     * that it exists
+     *
+     * */
+    pub(crate) fn get_break_block(&self, label: &Option<String>) -> &BasicBlock<'a> {
+        let index = self.find_loop_target(&KeywordKind::Break, label);
+
+        return &self.loop_targets[index].exit_block;
+    }
+
+    // `continue` skips the switches around it
+    pub(crate) fn get_continue_block(&self, label: &Option<String>) -> &BasicBlock<'a> {
+        let index = self.find_loop_target(&KeywordKind::Continue, label);
+
+        return self.loop_targets[index].continue_block.as_ref().unwrap();
+    }
+
+    // Index of the loop target which `break` or `continue` jumps to
+    fn find_loop_target(&self, keyword: &KeywordKind, label: &Option<String>) -> usize {
+        let index = self.loop_targets.iter().rposition(|target| match label {
+            None => *keyword == KeywordKind::Break || target.continue_block.is_some(),
+            Some(label) => target.label.as_ref() == Some(label),
+        });
+
+        return index.expect("Expected break or continue to be inside of a loop");
+    }
+
+    /*
+     * Every call inside of the try unwinds to the landing pad
+     *
+     * */
+    pub(crate) fn push_landing_pad(&mut self, landing_pad: &BasicBlock<'a>) {
+        self.landing_pads.push(landing_pad.clone());
+    }
+
+    pub(crate) fn pop_landing_pad(&mut self) {
+        self.landing_pads.pop();
+    }
+
+    pub(crate) fn get_landing_pad(&self) -> Option<&BasicBlock<'a>> {
+        return self.landing_pads.last();
+    }
+
+    pub(crate) fn push_finally_target(
+        &mut self,
+        completion: PointerValue<'a>,
+        finally_block: &BasicBlock<'a>,
+    ) {
+        self.finally_targets.push(FinallyTarget {
+            completion,
+            finally_block: finally_block.clone(),
+            jumps: vec![],
+            loop_depth: self.loop_targets.len(),
+        });
+    }
+
+    pub(crate) fn pop_finally_target(&mut self) -> FinallyTarget<'a> {
+        return self
+            .finally_targets
+            .pop()
+            .expect("Expected a try with finally block to be generated");
+    }
+
+    /*
+     * Innermost finally block between the `break` or `continue` and its loop, the
+     * jump is recorded in it and its index among the jumps is returned
+     *
+     * */
+    pub(crate) fn route_through_finally(
+        &mut self,
+        keyword: &KeywordKind,
+        label: &Option<String>,
+    ) -> Option<(&FinallyTarget<'a>, usize)> {
+        let loop_index = self.find_loop_target(keyword, label);
+
+        let finally_target = self
+            .finally_targets
+            .iter_mut()
+            .rev()
+            .find(|finally_target| finally_target.loop_depth > loop_index)?;
+
+        finally_target.jumps.push((keyword.clone(), label.clone()));
+        return Some((finally_target, finally_target.jumps.len() - 1));
+    }
 }
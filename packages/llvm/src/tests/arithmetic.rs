@@ -0,0 +1,35 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_remainder_and_power() {
+    let input = "
+    const x = 7 % 3 + 2 ** 10;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn test_bitwise_operators() {
+    let input = "
+    const x = (5 | 3) >>> 1;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn test_update_expression() {
+    let input = "
+    let x = 1;
+    const y = x++;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
@@ -0,0 +1,18 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_array_methods() {
+    let input = "
+    const numbers = [1, 2];
+    numbers.push(3);
+    const last = numbers.pop();
+    const first = numbers[0];
+    const count = numbers.length;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
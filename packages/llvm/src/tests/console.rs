@@ -0,0 +1,24 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_console_log() {
+    let input = "
+    console.log(\"a\", 1, [true]);";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn test_console_error() {
+    let input = "
+    console.error({ a: 1 });";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
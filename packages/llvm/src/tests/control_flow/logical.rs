@@ -0,0 +1,29 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_logical_expression() {
+    let input = "
+    const x = 1;
+    const y = x > 0 && x < 10;
+    const z = \"\" || \"default\";
+    ";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_conditional_expression() {
+    let input = "
+    const x = 1;
+    const y = x > 0 ? x : -x;
+    ";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
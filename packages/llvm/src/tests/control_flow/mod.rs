@@ -1 +1,3 @@
 mod if_block;
+mod logical;
+mod switch;
@@ -0,0 +1,46 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_switch_on_integers() {
+    let input = "
+    let x = 0;
+    const value = 2;
+
+    switch (value) {
+        case 1:
+            x = 1;
+        case -2:
+            x = 2;
+            break;
+        default:
+            x = 3;
+    }
+    ";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_switch_on_strings() {
+    let input = "
+    let x = 0;
+    const value = \"b\";
+
+    switch (value) {
+        case \"a\":
+            x = 1;
+            break;
+        case \"b\":
+            x = 2;
+    }
+    ";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
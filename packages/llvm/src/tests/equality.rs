@@ -0,0 +1,26 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_loose_equality() {
+    let input = "
+    const x = \"1\" == 1;
+    const y = true != 1;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn test_reference_equality() {
+    let input = "
+    const x = { a: 1 };
+    const y = x === x;";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
@@ -0,0 +1,35 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_roots_in_loop() {
+    let input = "
+    let text = \"\";
+    let i = 0;
+    while (i < 3) {
+        const point = { x: i, label: \"point\" };
+        text = text + point.label;
+        i += 1;
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn test_roots_in_function() {
+    let input = "
+    function greet(name : string) : string {
+        const numbers = [1, 2];
+        return \"hello \" + name;
+    }
+
+    const greeting = greet(\"world\");";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
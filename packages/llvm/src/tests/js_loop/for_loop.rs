@@ -0,0 +1,70 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn for_loop() {
+    let input = "
+    let total = 0;
+
+    for (let i = 0; i < 3; i += 1) {
+        total += i;
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn for_loop_with_continue() {
+    let input = "
+    for (let i = 0; i < 3; i += 1) {
+        continue
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
+
+#[test]
+fn for_of_loop() {
+    let input = "
+    let total = 0;
+
+    for (const x of [1, 2, 3]) {
+        total += x;
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn for_of_loop_over_string() {
+    let input = "
+    for (const character of \"héllo\") {
+        console.log(character);
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn for_in_loop() {
+    let input = "
+    const point = { x: 1, y: 2 };
+
+    for (const key in point) {
+        console.log(key);
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
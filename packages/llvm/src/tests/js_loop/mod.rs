@@ -1,2 +1,3 @@
 mod do_while_loop;
+mod for_loop;
 mod while_loop;
@@ -81,3 +81,23 @@ fn test_function_call_inside_while_loop() {
 
     insta::assert_snapshot!(input, output);
 }
+
+#[test]
+fn test_labeled_while_loop() {
+    let input = "
+    let x = 0;
+
+    outer: while (x < 10) {
+        x += 1;
+        do {
+            if (x === 5) {
+                break outer;
+            }
+            continue outer;
+        } while (true)
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(output);
+}
@@ -1,7 +1,15 @@
+mod arithmetic;
+mod arrays;
 mod compiler_provided_fn;
+mod console;
 mod control_flow;
+mod equality;
 mod functions;
+mod gc;
 mod js_loop;
 mod modules;
 mod naked_expression;
+mod nullable;
+mod strings;
+mod unions;
 mod variable_declaration;
@@ -0,0 +1,16 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_nullish_coalescing_and_optional_chaining() {
+    let input = "
+    const o: { a: number } | null = null;
+    const x = o?.a ?? 1;
+    ";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
@@ -0,0 +1,17 @@
+use lexer::convert_to_token;
+use parser::convert_to_ast;
+
+use crate::compile_to_llvm_ir;
+
+#[test]
+fn test_union_narrowing() {
+    let input = "
+    const x: number | string = 1;
+    if (typeof x === \"number\") {
+        const y = x + 1;
+    }";
+
+    let output = compile_to_llvm_ir(convert_to_ast(convert_to_token(input)));
+
+    insta::assert_snapshot!(input, output);
+}
@@ -1,5 +1,11 @@
+mod narrowing;
 mod parse_block;
+mod parse_class;
+mod parse_closure;
+mod parse_enum;
 mod parse_expression;
+mod parse_generic;
+mod parse_try;
 mod parse_type_expression;
 mod parser;
 pub mod symbol_table;
@@ -12,29 +18,40 @@ mod tests;
 use std::collections::HashMap;
 
 use crate::{parser::Parser, symbol_table::SymbolContext};
-use ast::AstPtr;
-use lexer::token::Token;
-use symbol_table::SymbolMetaInsert;
+use ast::{enumeration::erase_enums, AstPtr};
+use lexer::{diagnostic::Diagnostic, token::SpannedToken};
+use symbol_table::{SymbolMetaInsert, TypeSymbol};
 use traits::{DummyImportResolver, ImportResolver};
 
-pub fn convert_to_ast(input: Vec<Token>) -> Vec<AstPtr> {
+pub fn convert_to_ast(input: Vec<SpannedToken>) -> Vec<AstPtr> {
     let resolver = &mut DummyImportResolver::new();
-    return consume_token(input, resolver, None).0;
+
+    match consume_token(input, resolver, None) {
+        Ok((asts, _, _)) => return asts,
+        Err(diagnostics) => panic!("{:?}", diagnostics),
+    }
 }
 
+// Asts of the file along with its global variables and types
+pub type ParsedFile = (
+    Vec<AstPtr>,
+    HashMap<String, SymbolMetaInsert>,
+    HashMap<String, TypeSymbol>,
+);
+
 pub fn consume_token<'a, R: ImportResolver>(
-    input: Vec<Token>,
+    input: Vec<SpannedToken>,
     resolver: &mut R,
     file_name: Option<&str>,
-) -> (Vec<AstPtr>, HashMap<String, SymbolMetaInsert>) {
+) -> Result<ParsedFile, Vec<Diagnostic>> {
     let mut parser = Parser::new(&input, resolver, file_name);
     let mut context = SymbolContext::create_global_context();
 
-    parser.compile(&mut context);
+    parser.compile(&mut context)?;
 
-    let asts = parser.get_compiled_ast();
+    let asts = erase_enums(&parser.get_compiled_ast());
 
-    return (asts, context.global_symbols);
+    return Ok((asts, context.global_symbols, context.global_types));
 }
 
 #[cfg(test)]
@@ -42,11 +59,11 @@ mod test {
     use std::collections::HashMap;
 
     use ast::{
-        data_type::DataType, declaration::VariableDeclarationKind, expression::Expression, Ast,
-        AstPtr,
+        data_type::DataType, declaration::VariableDeclarationKind, erase_spans,
+        expression::Expression, Ast, AstPtr,
     };
     use indexmap::indexmap;
-    use lexer::convert_to_token;
+    use lexer::{convert_to_token, token::Span};
 
     use crate::convert_to_ast;
 
@@ -63,6 +80,7 @@ mod test {
             Expression::FloatLiteralExp {
                 name: "1".to_string(),
                 value: 1.0,
+                span: Span::default(),
             },
         );
         exp_hash_map.insert(
@@ -70,6 +88,7 @@ mod test {
             Expression::FloatLiteralExp {
                 name: "2".to_string(),
                 value: 2.0,
+                span: Span::default(),
             },
         );
 
@@ -81,8 +100,10 @@ mod test {
                     data_type: DataType::ObjectType {
                         entries: indexmap! {"a".to_string() => DataType::Float, "b".to_string() => DataType::Float},
                     },
+                    span: Span::default(),
                 },
                 VariableDeclarationKind::Const,
+                Span::default(),
             ),
             Ast::new_variable_declaration(
                 "y|_|",
@@ -92,16 +113,22 @@ mod test {
                             entries: indexmap! {"a".to_string() => DataType::Float, "b".to_string() => DataType::Float},
                         },
                         name: "x|_|".to_string(),
+                        span: Span::default(),
                     }),
                     argument: "a".to_string(),
+                    span: Span::default(),
                 },
                 VariableDeclarationKind::Const,
+                Span::default(),
             ),
         ];
 
         let actual_output = convert_to_ast(convert_to_token(input));
 
-        assert_eq!(expected_output, actual_output);
+        assert_eq!(
+            erase_spans(&expected_output),
+            erase_spans(&actual_output)
+        );
     }
 
     #[test]
@@ -116,14 +143,18 @@ mod test {
         let expected_output: Vec<AstPtr> = vec![
             Ast::new_function_declaration(
                 indexmap! {"x|_|".to_string() => DataType::Float},
-                vec![Ast::new_return_statement(Some(
-                    Expression::FloatLiteralExp {
+                vec![Ast::new_return_statement(
+                    Some(Expression::FloatLiteralExp {
                         name: "1".to_string(),
                         value: 1.0,
-                    },
-                ))],
+                        span: Span::default(),
+                    }),
+                    Span::default(),
+                )],
                 "foo|_|".to_string(),
                 DataType::Float,
+                indexmap! {},
+                Span::default(),
             ),
             Ast::new_variable_declaration(
                 "y|_|",
@@ -134,20 +165,27 @@ mod test {
                             return_type: Box::new(DataType::Float),
                         },
                         name: "foo|_|".to_string(),
+                        span: Span::default(),
                     }),
                     //  "foo|_|".to_string(),
                     parameters: vec![Expression::FloatLiteralExp {
                         name: "4".to_string(),
                         value: 4.0,
+                        span: Span::default(),
                     }],
                     return_type: DataType::Float,
+                    span: Span::default(),
                 },
                 VariableDeclarationKind::Const,
+                Span::default(),
             ),
         ];
 
         let actual_output = convert_to_ast(convert_to_token(input));
 
-        assert_eq!(expected_output, actual_output);
+        assert_eq!(
+            erase_spans(&expected_output),
+            erase_spans(&actual_output)
+        );
     }
 }
@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+
+use ast::{
+    data_type::DataType,
+    declaration::VariableAssignmentOperator,
+    expression::{BinaryOperator, Expression, LogicalOperator, UnaryOperator},
+};
+use lexer::token::Token;
+
+use crate::{parser::Parser, traits::ImportResolver, utils::create_union_type};
+
+// Narrowed data types keyed by the name of the variable
+pub(crate) type Narrowings = HashMap<String, DataType>;
+
+/*
+ * Narrowings of the union variables checked by the condition, the first one holds
+ * while the condition is true and the second one while it is false
+ *
+ *      typeof x === "number"        x is number / x is the rest of the union
+ *      x === null                   x is null / x is the rest of the union
+ *      x == null                    x is null or undefined / x is the rest
+ *      x                            x is not null or undefined / no narrowing
+ *      x instanceof C               x is C / x is the rest of the union
+ *      typeof x !== "number"        swapped, same for !== and !=
+ *      !<condition>                 swapped
+ *      <left> && <right>            both hold while it is true
+ *      <left> || <right>            both hold while it is false
+ *
+ * */
+pub(crate) fn get_narrowings(condition: &Expression) -> (Narrowings, Narrowings) {
+    match condition {
+        Expression::BinaryExp {
+            operator,
+            left,
+            right,
+            span: _,
+        } => {
+            let (is_equality, is_strict) = match operator {
+                BinaryOperator::StrictEquality => (true, true),
+                BinaryOperator::Equality => (true, false),
+                BinaryOperator::StrictNotEqual => (false, true),
+                BinaryOperator::NotEqual => (false, false),
+                _ => return (Narrowings::new(), Narrowings::new()),
+            };
+
+            let narrowings = get_typeof_check(left, right)
+                .or_else(|| get_typeof_check(right, left))
+                .map(|(name, types, typeof_name)| {
+                    return partition_members(name, types, |member| {
+                        member.get_typeof_name() == Some(typeof_name.as_str())
+                    });
+                })
+                .or_else(|| {
+                    let (name, types, nullish) = get_nullish_check(left, right)
+                        .or_else(|| get_nullish_check(right, left))?;
+
+                    // `== null` is also true for undefined and the other way around
+                    return Some(partition_members(name, types, |member| {
+                        if is_strict {
+                            member == &nullish
+                        } else {
+                            member.is_nullish()
+                        }
+                    }));
+                });
+
+            let (when_equal, when_not_equal) = match narrowings {
+                Some(narrowings) => narrowings,
+                None => return (Narrowings::new(), Narrowings::new()),
+            };
+
+            if is_equality {
+                return (when_equal, when_not_equal);
+            } else {
+                return (when_not_equal, when_equal);
+            }
+        }
+
+        Expression::UnaryExp {
+            operator: UnaryOperator::Bang,
+            argument,
+            span: _,
+        } => {
+            let (when_true, when_false) = get_narrowings(argument);
+            return (when_false, when_true);
+        }
+
+        /*
+         * Right side is parsed with the narrowings of the left side, so the data
+         * types it narrows to are already narrowed by the left side
+         *
+         * */
+        Expression::LogicalExp {
+            operator,
+            left,
+            right,
+            span: _,
+        } => {
+            let (left_when_true, left_when_false) = get_narrowings(left);
+            let (right_when_true, right_when_false) = get_narrowings(right);
+
+            match operator {
+                LogicalOperator::And => {
+                    let mut when_true = left_when_true;
+                    when_true.extend(right_when_true);
+                    return (when_true, Narrowings::new());
+                }
+
+                LogicalOperator::Or => {
+                    let mut when_false = left_when_false;
+                    when_false.extend(right_when_false);
+                    return (Narrowings::new(), when_false);
+                }
+
+                LogicalOperator::NullishCoalescing => {
+                    return (Narrowings::new(), Narrowings::new())
+                }
+            }
+        }
+
+        Expression::InstanceofExp {
+            exp,
+            class,
+            span: _,
+        } => match get_narrowable_variable(exp) {
+            Some((name, types)) => return partition_instances(name, types, class),
+            None => return (Narrowings::new(), Narrowings::new()),
+        },
+
+        /*
+         * Truthy value is neither null nor undefined, a falsy one can still be any
+         * member e.g. 0 or ""
+         *
+         * */
+        condition => match get_narrowable_variable(condition) {
+            Some((name, types)) if types.iter().any(|member| member.is_nullish()) => {
+                let (_, when_not_nullish) =
+                    partition_members(name, types, |member| member.is_nullish());
+
+                return (when_not_nullish, Narrowings::new());
+            }
+            _ => return (Narrowings::new(), Narrowings::new()),
+        },
+    }
+}
+
+/*
+ * Splits the members of the union into the ones matching the check and the rest
+ *
+ * No member left means the branch cannot run, the variable keeps its data type
+ * there as there is no data type for it
+ *
+ * */
+fn partition_members<F: Fn(&DataType) -> bool>(
+    name: String,
+    types: Vec<DataType>,
+    is_match: F,
+) -> (Narrowings, Narrowings) {
+    let (match_types, not_match_types): (Vec<DataType>, Vec<DataType>) =
+        types.into_iter().partition(|member| is_match(member));
+
+    return create_narrowings(name, match_types, not_match_types);
+}
+
+/*
+ * Splits the members of the union into the instances of the class and the rest
+ *
+ * Instance of an ancestor of the class or an `object` may or may not be an
+ * instance of the class, so the class is a member when it matches and the
+ * ancestor or `object` stays in the rest
+ *
+ * */
+fn partition_instances(
+    name: String,
+    types: Vec<DataType>,
+    class: &DataType,
+) -> (Narrowings, Narrowings) {
+    let (class_name, class_parents) = match class {
+        DataType::ClassType { name, parents } => (name, parents),
+        _ => unreachable!(),
+    };
+
+    let mut match_types: Vec<DataType> = vec![];
+    let mut not_match_types: Vec<DataType> = vec![];
+
+    for member in types {
+        match &member {
+            DataType::ClassType { name, parents }
+                if name == class_name || parents.contains(class_name) =>
+            {
+                match_types.push(member);
+            }
+
+            DataType::ClassType { name, parents: _ } if class_parents.contains(name) => {
+                match_types.push(class.clone());
+                not_match_types.push(member);
+            }
+
+            DataType::Object => {
+                match_types.push(class.clone());
+                not_match_types.push(member);
+            }
+
+            _ => not_match_types.push(member),
+        }
+    }
+
+    return create_narrowings(name, match_types, not_match_types);
+}
+
+fn create_narrowings(
+    name: String,
+    match_types: Vec<DataType>,
+    not_match_types: Vec<DataType>,
+) -> (Narrowings, Narrowings) {
+    let mut when_match = Narrowings::new();
+    let mut when_not_match = Narrowings::new();
+
+    for (narrowings, types) in [
+        (&mut when_match, match_types),
+        (&mut when_not_match, not_match_types),
+    ] {
+        if !types.is_empty() {
+            narrowings.insert(name.clone(), create_union_type(types).unwrap());
+        }
+    }
+
+    return (when_match, when_not_match);
+}
+
+/*
+ * Name of the variable, members of its union and the compared typeof name for
+ *
+ *      typeof <variable> === "<typeof name>"
+ *
+ * */
+fn get_typeof_check(
+    typeof_exp: &Expression,
+    literal: &Expression,
+) -> Option<(String, Vec<DataType>, String)> {
+    let typeof_name = match literal {
+        Expression::StringLiteralExp { value, span: _ } => value.clone(),
+        _ => return None,
+    };
+
+    let argument = match typeof_exp {
+        Expression::UnaryExp {
+            operator: UnaryOperator::Typeof,
+            argument,
+            span: _,
+        } => argument.as_ref(),
+        _ => return None,
+    };
+
+    let (name, types) = get_narrowable_variable(argument)?;
+    return Some((name, types, typeof_name));
+}
+
+/*
+ * Name of the variable, members of its union and the data type of the literal for
+ *
+ *      <variable> === null
+ *      <variable> === undefined
+ *
+ * */
+fn get_nullish_check(
+    variable: &Expression,
+    literal: &Expression,
+) -> Option<(String, Vec<DataType>, DataType)> {
+    match literal {
+        Expression::NullLiteralExp { span: _ } | Expression::UndefinedLiteralExp { span: _ } => {
+            let (name, types) = get_narrowable_variable(variable)?;
+            return Some((name, types, literal.get_data_type()));
+        }
+        _ => return None,
+    }
+}
+
+/*
+ * Name of the variable and the members of its union when the expression is a
+ * variable holding a union
+ *
+ * */
+fn get_narrowable_variable(exp: &Expression) -> Option<(String, Vec<DataType>)> {
+    // Variable already narrowed by an enclosing check is wrapped in a conversion
+    let (variable, data_type) = match exp {
+        Expression::UnionConversion {
+            exp,
+            data_type,
+            span: _,
+        } => (exp.as_ref(), data_type.clone()),
+        exp => (exp, exp.get_data_type()),
+    };
+
+    let name = match variable {
+        // IdentExp holds the llvm name `<name>|<suffix>|` of the variable
+        Expression::IdentExp { name, .. } if !name.starts_with('|') => {
+            name.split('|').next().unwrap().to_string()
+        }
+        _ => return None,
+    };
+
+    match data_type {
+        DataType::UnionType { types } => return Some((name, types)),
+        _ => return None,
+    }
+}
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Assumes the current token to be `{`, returns the names of the variables which
+     * are assigned, incremented or decremented anywhere till the matching `}`
+     *
+     * Narrowing of such a variable would not hold after the assignment, so it is
+     * not narrowed in the block at all
+     *
+     * */
+    pub(crate) fn get_reassigned_variables(&self) -> HashSet<String> {
+        let mut names: HashSet<String> = HashSet::new();
+        let mut depth = 0;
+
+        let start = match self.cur_pos {
+            Some(start) => start,
+            None => return names,
+        };
+
+        for (i, spanned_token) in self.content.iter().enumerate().skip(start) {
+            match &spanned_token.token {
+                Token::AngleOpenBracket => depth += 1,
+                Token::AngleCloseBracket => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Eof => break,
+
+                Token::Ident { name } => {
+                    let is_next_reassigning = match self.content.get(i + 1) {
+                        Some(next) => {
+                            VariableAssignmentOperator::is_lexer_assignment_operator(&next.token)
+                                || matches!(next.token, Token::Increment | Token::Decrement)
+                        }
+                        None => false,
+                    };
+                    let is_prev_reassigning = i > 0
+                        && matches!(
+                            self.content[i - 1].token,
+                            Token::Increment | Token::Decrement
+                        );
+
+                    if is_next_reassigning || is_prev_reassigning {
+                        names.insert(name.clone());
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        return names;
+    }
+}
@@ -1,7 +1,13 @@
 use ast::{declaration::BlockWithCondition, AstPtr};
 use lexer::token::Token;
 
-use crate::{parser::Parser, symbol_table::SymbolContext, traits::ImportResolver};
+use crate::{
+    narrowing::{get_narrowings, Narrowings},
+    parse_closure::mark_captured_variables,
+    parser::Parser,
+    symbol_table::SymbolContext,
+    traits::ImportResolver,
+};
 
 impl<'a, R: ImportResolver> Parser<'a, R> {
     /*
@@ -38,6 +44,75 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         return Ok(block_with_condition);
     }
 
+    /*
+     * Same as parse_block_with_condition but the condition is parsed with the given
+     * narrowings and the block with the narrowings of the condition added to them,
+     * used for the branches of an if statement
+     *
+     * Returns the narrowings holding after the condition turned out to be false
+     *
+     * */
+    pub(crate) fn parse_narrowed_block_with_condition(
+        &mut self,
+        narrowings: &Narrowings,
+        context: &mut SymbolContext,
+    ) -> Result<(BlockWithCondition, Narrowings), String> {
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let condition = {
+            let mut condition_context = context.create_child_context(context.suffix.clone());
+            for (name, data_type) in narrowings {
+                condition_context.narrow(name, data_type.clone());
+            }
+
+            self.parse_expression(1, &condition_context)?
+        };
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        let (when_true, when_false) = get_narrowings(&condition);
+
+        let mut block_narrowings = narrowings.clone();
+        block_narrowings.extend(when_true);
+        let ast_block = self.parse_narrowed_block(&block_narrowings, context)?;
+
+        let mut else_narrowings = narrowings.clone();
+        else_narrowings.extend(when_false);
+
+        let block_with_condition = BlockWithCondition::new(condition, ast_block);
+        return Ok((block_with_condition, else_narrowings));
+    }
+
+    /*
+     * Same as parse_block but the variables have the narrowed data types inside of
+     * the block, except the ones the block reassigns
+     *
+     * */
+    pub(crate) fn parse_narrowed_block(
+        &mut self,
+        narrowings: &Narrowings,
+        context: &mut SymbolContext,
+    ) -> Result<Vec<AstPtr>, String> {
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+
+        let reassigned_variables = self.get_reassigned_variables();
+
+        let cur_value = context.counter;
+        let suffix = format!("{}{}", context.suffix, cur_value);
+        context.counter += 1;
+
+        let mut child_context = context.create_child_context(suffix);
+        for (name, data_type) in narrowings {
+            if !reassigned_variables.contains(name) {
+                child_context.narrow(name, data_type.clone());
+            }
+        }
+
+        return self.parse_block_with_context(&mut child_context);
+    }
+
     /*
      * Assumes the current token to be `{` in
      *
@@ -93,13 +168,17 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
         let mut ast_block: Vec<AstPtr> = vec![];
 
-        while self.get_cur_token().unwrap() != &Token::AngleCloseBracket {
-            let ast = self.next_ast_in_context(context)?;
-            ast_block.push(ast);
+        while self.get_cur_token()? != &Token::AngleCloseBracket {
+            self.assert_not_eof()?;
+
+            if let Some(ast) = self.next_ast(context) {
+                ast_block.push(ast);
+            }
         }
 
         self.next(); // consumes }
 
+        mark_captured_variables(&mut ast_block, &self.captured_variables);
         return Ok(ast_block);
     }
 }
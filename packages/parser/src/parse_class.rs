@@ -0,0 +1,1454 @@
+use ast::{
+    class::{AccessModifier, Class, ClassField, ClassMethod},
+    data_type::DataType,
+    declaration::{Declaration, VariableAssignmentOperator},
+    expression::Expression,
+    Ast, AstPtr,
+};
+use indexmap::IndexMap;
+use lexer::token::{KeywordKind, Span, Token};
+
+use crate::{
+    parse_closure::mark_captured_variables,
+    parser::Parser,
+    symbol_table::{SymbolContext, TypeSymbol},
+    traits::ImportResolver,
+    utils::{convert_index_map_to_vec, convert_to_data_type},
+};
+
+/*
+ * Member found by the first pass over the body of the class, bodies of the methods
+ * and initializers of the fields are parsed once every member is known. Positions
+ * are the ones of the name of the member, of `{` of the body and of the token after
+ * `=` of the initializer
+ *
+ * */
+enum ClassMember {
+    Field {
+        name: String,
+        position: usize,
+        data_type: DataType,
+        access: AccessModifier,
+        initializer: Option<usize>,
+    },
+    Method {
+        name: String,
+        position: usize,
+        arguments: IndexMap<String, DataType>,
+        return_type: DataType,
+        access: AccessModifier,
+        is_static: bool,
+        body: usize,
+        start: Span,
+    },
+    Constructor {
+        position: usize,
+        arguments: IndexMap<String, DataType>,
+        access: AccessModifier,
+        body: usize,
+        start: Span,
+    },
+}
+
+impl ClassMember {
+    fn get_position(&self) -> usize {
+        return match self {
+            ClassMember::Field { position, .. }
+            | ClassMember::Method { position, .. }
+            | ClassMember::Constructor { position, .. } => *position,
+        };
+    }
+}
+
+// Name in the source of the class with the llvm name
+fn get_class_name(llvm_name: &str, context: &SymbolContext) -> String {
+    return match context.get_class(llvm_name) {
+        Some(class) => class.name.clone(),
+        None => llvm_name.to_string(),
+    };
+}
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Assumes the current token to be `keyword class` in
+     *
+     *      class <name> extends <parent> {
+     *          <modifiers> <field>?: <type> = <initializer>;
+     *
+     *          <modifiers> constructor(a: <type>) {
+     *              super(<arguments>);
+     *              <block>
+     *          }
+     *
+     *          <modifiers> <method>(a: <type>): <return_type> {
+     *              <block>
+     *          }
+     *      }
+     *
+     * Modifiers are `public`, `protected`, `private` and `static`. Fields need a
+     * type annotation and methods a return type, so that the class is known before
+     * any body is parsed
+     *
+     * A constructor is generated for a class without one, it passes its arguments
+     * to the constructor of the parent class. Initializers of the fields are part
+     * of the constructor and run right after `super(...)`
+     *
+     * Consumes till token `}`
+     *
+     * */
+    pub(crate) fn parse_class_declaration(
+        &mut self,
+        context: &mut SymbolContext,
+        can_export: bool,
+    ) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Class))?;
+        let start = self.get_cur_span()?;
+
+        if !context.is_global() {
+            return Err(format!(
+                "Classes can only be declared at the top of the file"
+            ));
+        }
+
+        let name = self.next().get_ident_name()?.clone(); // consumes class
+        let name_position = self.cur_pos.unwrap();
+        self.next(); // consumes ident
+
+        let llvm_name = self.get_llvm_var_name(&name, context, can_export);
+        let mut class = Class {
+            name: name.clone(),
+            llvm_name: llvm_name.clone(),
+            parents: vec![],
+            fields: IndexMap::new(),
+            methods: IndexMap::new(),
+            static_methods: IndexMap::new(),
+            constructor: ClassMethod {
+                data_type: DataType::FunctionType {
+                    arguments: vec![],
+                    return_type: Box::new(DataType::Void),
+                },
+                access: AccessModifier::Public,
+                owner: llvm_name.clone(),
+                function: self.get_llvm_var_name(
+                    &format!("{}.constructor", name),
+                    context,
+                    can_export,
+                ),
+            },
+        };
+
+        // Members of the parent class are inherited as they are
+        if self.get_cur_token()? == &Token::Keyword(KeywordKind::Extends) {
+            let parent_name = self.next().get_ident_name()?.clone(); // consumes extends
+
+            let parent = match context.get_type(&parent_name) {
+                Some(TypeSymbol {
+                    class: Some(parent),
+                    ..
+                }) => parent.clone(),
+                _ => {
+                    return Err(format!(
+                        "Class {} can only extend a class but {} is not one",
+                        name, parent_name
+                    ))
+                }
+            };
+            self.next(); // consumes ident
+
+            class.parents = vec![parent.llvm_name.clone()];
+            class.parents.extend(parent.parents);
+            class.fields = parent.fields;
+            class.methods = parent.methods;
+            class.static_methods = parent.static_methods;
+            class.constructor.data_type = parent.constructor.data_type;
+        }
+
+        // Class is known by its name while the types of its members are parsed
+        context.insert_type(&name, TypeSymbol::create_class(class.clone(), can_export))?;
+        context.insert_class(class.clone());
+
+        let members = self.parse_class_members(&name, context)?;
+        let end = self.cur_pos;
+
+        // Invalid member is recorded and left out, the error points at its name
+        let mut valid_members: Vec<ClassMember> = vec![];
+        for member in members {
+            match self.add_class_member(&mut class, &member, context, can_export) {
+                Ok(()) => valid_members.push(member),
+                Err(err) => {
+                    self.cur_pos = Some(member.get_position());
+                    let diagnostic = self.to_diagnostic(err);
+                    self.diagnostics.push(diagnostic);
+                }
+            }
+        }
+        let members = valid_members;
+
+        // Class was inserted above, so the complete one replaces it
+        context.global_types.insert(
+            name.clone(),
+            TypeSymbol::create_class(class.clone(), can_export),
+        );
+        context.insert_class(class.clone());
+
+        /*
+         * Error inside of a body is recorded and the parser moves on to the next
+         * member, the other members can still be checked as the class is known
+         *
+         * */
+        let cur_class = self.cur_class.replace(llvm_name);
+        let mut functions: Vec<AstPtr> = vec![];
+
+        let has_constructor = members
+            .iter()
+            .any(|member| matches!(member, ClassMember::Constructor { .. }));
+
+        // Error of the generated constructor points at the name of the class
+        if !has_constructor {
+            let result = self.parse_constructor(&class, &members, None, start, context);
+            if result.is_err() {
+                self.cur_pos = Some(name_position);
+            }
+            self.push_member_result(result, &mut functions);
+        }
+
+        for member in members.iter() {
+            let result = match member {
+                ClassMember::Field { .. } => continue,
+
+                ClassMember::Constructor { body, start, .. } => {
+                    self.parse_constructor(&class, &members, Some(*body), *start, context)
+                }
+
+                ClassMember::Method {
+                    name,
+                    arguments,
+                    return_type,
+                    is_static,
+                    body,
+                    start,
+                    ..
+                } => {
+                    let mut arguments_with_this = IndexMap::new();
+
+                    let method = if *is_static {
+                        &class.static_methods[name]
+                    } else {
+                        arguments_with_this.insert("this".to_string(), class.get_data_type());
+                        &class.methods[name]
+                    };
+                    arguments_with_this.extend(arguments.clone());
+
+                    self.cur_pos = Some(*body);
+                    self.parse_function_body(
+                        &method.function,
+                        arguments_with_this,
+                        Some(return_type.clone()),
+                        String::from("_"),
+                        *start,
+                        context,
+                    )
+                    .map(|(function, _)| function)
+                }
+            };
+            self.push_member_result(result, &mut functions);
+        }
+
+        self.cur_class = cur_class;
+        self.cur_pos = end;
+
+        return Ok(Ast::new_class_declaration(
+            class,
+            functions,
+            self.get_span_from(&start),
+        ));
+    }
+
+    fn push_member_result(&mut self, result: Result<AstPtr, String>, functions: &mut Vec<AstPtr>) {
+        match result {
+            Ok(function) => functions.push(function),
+            Err(err) => {
+                let diagnostic = self.to_diagnostic(err);
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    /*
+     * Assumes the current token to be `{` of the body of the class, finds the
+     * members without parsing the bodies of the methods and the initializers
+     *
+     * Consumes till token `}`
+     *
+     * */
+    fn parse_class_members(
+        &mut self,
+        class_name: &str,
+        context: &SymbolContext,
+    ) -> Result<Vec<ClassMember>, String> {
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+        self.next(); // consumes {
+
+        let mut members: Vec<ClassMember> = vec![];
+
+        // Invalid member is recorded and skipped, so the rest of the class is still checked
+        while self.get_cur_token()? != &Token::AngleCloseBracket {
+            self.assert_not_eof()?;
+
+            match self.parse_member_declaration(class_name, &members, context) {
+                Ok(member) => members.push(member),
+                Err(err) => {
+                    let diagnostic = self.to_diagnostic(err);
+                    self.diagnostics.push(diagnostic);
+                    self.skip_class_member()?;
+                }
+            }
+        }
+
+        self.next(); // consumes }
+
+        return Ok(members);
+    }
+
+    fn parse_member_declaration(
+        &mut self,
+        class_name: &str,
+        members: &[ClassMember],
+        context: &SymbolContext,
+    ) -> Result<ClassMember, String> {
+        let start = self.get_cur_span()?;
+        let (access, is_static) = self.parse_class_modifiers()?;
+
+        let name = self.get_cur_token()?.get_ident_name()?.clone();
+        let position = self.cur_pos.unwrap();
+        self.next(); // consumes ident
+
+        let member = match self.get_cur_token()? {
+            Token::CurveOpenBracket if name == "constructor" && !is_static => {
+                let has_constructor = members
+                    .iter()
+                    .any(|member| matches!(member, ClassMember::Constructor { .. }));
+
+                if has_constructor {
+                    return Err(format!(
+                        "Class {} can only have one constructor",
+                        class_name
+                    ));
+                }
+
+                let arguments = self.parse_parameters(context)?;
+
+                ClassMember::Constructor {
+                    position,
+                    arguments,
+                    access,
+                    body: self.skip_class_block()?,
+                    start,
+                }
+            }
+
+            Token::CurveOpenBracket => {
+                let arguments = self.parse_parameters(context)?;
+
+                if self.get_cur_token()? != &Token::Colon {
+                    return Err(format!(
+                        "Method {} of class {} needs a return type",
+                        name, class_name
+                    ));
+                }
+                self.next(); // consumes :
+
+                let return_type = self.parse_type_declaration(1, context)?;
+
+                ClassMember::Method {
+                    name,
+                    position,
+                    arguments,
+                    return_type,
+                    access,
+                    is_static,
+                    body: self.skip_class_block()?,
+                    start,
+                }
+            }
+
+            Token::Colon | Token::QuestionMark => {
+                if is_static {
+                    return Err(format!(
+                        "Static field {} of class {} is not supported, only static methods are",
+                        name, class_name
+                    ));
+                }
+
+                let data_type = self.parse_optional_type_annotation(context)?;
+
+                let initializer = match self.get_cur_token()? {
+                    Token::Assign => {
+                        self.next(); // consumes =
+                        let initializer = self.cur_pos;
+                        self.skip_field_initializer()?;
+                        initializer
+                    }
+                    _ => None,
+                };
+                self.skip_semicolon()?;
+
+                ClassMember::Field {
+                    name,
+                    position,
+                    data_type,
+                    access,
+                    initializer,
+                }
+            }
+
+            _ => {
+                return Err(format!(
+                    "Field {} of class {} needs a type annotation",
+                    name, class_name
+                ))
+            }
+        };
+
+        return Ok(member);
+    }
+
+    /*
+     * Skips the rest of an invalid member, it ends with `;`, with the block of a
+     * method or with `}` of the body of the class which is not consumed
+     *
+     * */
+    fn skip_class_member(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+
+        loop {
+            self.assert_not_eof()?;
+
+            match self.get_cur_token()? {
+                Token::SemiColon if depth == 0 => {
+                    self.next(); // consumes ;
+                    return Ok(());
+                }
+                Token::AngleCloseBracket if depth == 0 => return Ok(()),
+                Token::AngleCloseBracket if depth == 1 => {
+                    self.next(); // consumes }
+                    return Ok(());
+                }
+
+                Token::CurveOpenBracket | Token::BoxOpenBracket | Token::AngleOpenBracket => {
+                    depth += 1
+                }
+
+                Token::CurveCloseBracket | Token::BoxCloseBracket | Token::AngleCloseBracket => {
+                    depth -= 1
+                }
+
+                _ => {}
+            }
+            self.next();
+        }
+    }
+
+    /*
+     * Modifiers are plain idents, so `static` or `private` followed by anything
+     * but an ident is the name of the member
+     *
+     * */
+    fn parse_class_modifiers(&mut self) -> Result<(AccessModifier, bool), String> {
+        let mut access: Option<AccessModifier> = None;
+        let mut is_static = false;
+
+        while let Token::Ident { name } = self.get_cur_token()?.clone() {
+            let mut lookup_parser = self.lookup_parser();
+            lookup_parser.next(); // consumes ident
+
+            if !matches!(lookup_parser.get_cur_token()?, Token::Ident { .. }) {
+                break;
+            }
+
+            let modifier = match name.as_str() {
+                "public" => AccessModifier::Public,
+                "protected" => AccessModifier::Protected,
+                "private" => AccessModifier::Private,
+
+                "static" if is_static => return Err(format!("Modifier static is repeated")),
+                "static" => {
+                    is_static = true;
+                    self.next(); // consumes static
+                    continue;
+                }
+
+                _ => break,
+            };
+
+            if access.is_some() {
+                return Err(format!("Member can only have one access modifier"));
+            }
+            access = Some(modifier);
+            self.next(); // consumes modifier
+        }
+
+        return Ok((access.unwrap_or(AccessModifier::Public), is_static));
+    }
+
+    // Assumes the current token to be `{`, returns its position and consumes till `}`
+    fn skip_class_block(&mut self) -> Result<usize, String> {
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+        let position = self.cur_pos.unwrap();
+        let mut depth = 0;
+
+        loop {
+            self.assert_not_eof()?;
+
+            match self.get_cur_token()? {
+                Token::AngleOpenBracket => depth += 1,
+                Token::AngleCloseBracket if depth == 1 => break,
+                Token::AngleCloseBracket => depth -= 1,
+                _ => {}
+            }
+            self.next();
+        }
+
+        self.next(); // consumes }
+
+        return Ok(position);
+    }
+
+    // Initializer ends with `;` or with `}` of the body of the class
+    fn skip_field_initializer(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+
+        loop {
+            self.assert_not_eof()?;
+
+            match self.get_cur_token()? {
+                Token::SemiColon | Token::AngleCloseBracket if depth == 0 => return Ok(()),
+
+                Token::CurveOpenBracket | Token::BoxOpenBracket | Token::AngleOpenBracket => {
+                    depth += 1
+                }
+
+                Token::CurveCloseBracket | Token::BoxCloseBracket | Token::AngleCloseBracket => {
+                    depth -= 1
+                }
+
+                _ => {}
+            }
+            self.next();
+        }
+    }
+
+    /*
+     * A field cannot take the name of another member. A method can only take the
+     * name of an inherited method to override it, the override keeps the data type
+     * and the access of the inherited one
+     *
+     * */
+    fn add_class_member(
+        &self,
+        class: &mut Class,
+        member: &ClassMember,
+        context: &SymbolContext,
+        can_export: bool,
+    ) -> Result<(), String> {
+        match member {
+            ClassMember::Field {
+                name,
+                data_type,
+                access,
+                ..
+            } => {
+                let existing_owner = match (class.fields.get(name), class.methods.get(name)) {
+                    (Some(field), _) => Some(&field.owner),
+                    (None, Some(method)) => Some(&method.owner),
+                    (None, None) => None,
+                };
+
+                match existing_owner {
+                    None => {}
+
+                    Some(owner) if owner == &class.llvm_name => {
+                        return Err(format!("Duplicate member {} in class {}", name, class.name))
+                    }
+
+                    Some(owner) => {
+                        return Err(format!(
+                            "Class {} cannot declare field {} as class {} already declares it",
+                            class.name,
+                            name,
+                            get_class_name(owner, context)
+                        ))
+                    }
+                }
+
+                class.fields.insert(
+                    name.clone(),
+                    ClassField {
+                        data_type: data_type.clone(),
+                        access: access.clone(),
+                        owner: class.llvm_name.clone(),
+                    },
+                );
+            }
+
+            ClassMember::Method {
+                name,
+                arguments,
+                return_type,
+                access,
+                is_static,
+                ..
+            } => {
+                let data_type = DataType::FunctionType {
+                    arguments: convert_index_map_to_vec(arguments),
+                    return_type: Box::new(return_type.clone()),
+                };
+
+                // Static methods are not inherited through the vtable, they are simply replaced
+                if *is_static {
+                    if let Some(existing) = class.static_methods.get(name) {
+                        if existing.owner == class.llvm_name {
+                            return Err(format!(
+                                "Duplicate member {} in class {}",
+                                name, class.name
+                            ));
+                        }
+                    }
+
+                    let function = format!("{}.{}", class.name, name);
+                    class.static_methods.insert(
+                        name.clone(),
+                        ClassMethod {
+                            data_type,
+                            access: access.clone(),
+                            owner: class.llvm_name.clone(),
+                            function: self.get_llvm_var_name(&function, context, can_export),
+                        },
+                    );
+                    return Ok(());
+                }
+
+                if let Some(field) = class.fields.get(name) {
+                    return Err(format!(
+                        "Class {} cannot declare method {} as class {} already declares it as a field",
+                        class.name,
+                        name,
+                        get_class_name(&field.owner, context)
+                    ));
+                }
+
+                if let Some(inherited) = class.methods.get(name) {
+                    let parent_name = get_class_name(&inherited.owner, context);
+
+                    if inherited.owner == class.llvm_name {
+                        return Err(format!("Duplicate member {} in class {}", name, class.name));
+                    }
+
+                    if inherited.access == AccessModifier::Private {
+                        return Err(format!(
+                            "Method {} of class {} cannot override the private method of class {}",
+                            name, class.name, parent_name
+                        ));
+                    }
+
+                    if &inherited.access != access {
+                        return Err(format!(
+                            "Method {} of class {} has to keep the access of the method of class {} it overrides",
+                            name, class.name, parent_name
+                        ));
+                    }
+
+                    if !inherited.data_type.has_same_layout(&data_type) {
+                        return Err(format!(
+                            "Method {} of class {} has to be of datatype {} like the method of class {} it overrides but is of datatype {}",
+                            name, class.name, inherited.data_type, parent_name, data_type
+                        ));
+                    }
+                }
+
+                // Overriding method takes the slot of the inherited one
+                let function = format!("{}.prototype.{}", class.name, name);
+                class.methods.insert(
+                    name.clone(),
+                    ClassMethod {
+                        data_type,
+                        access: access.clone(),
+                        owner: class.llvm_name.clone(),
+                        function: self.get_llvm_var_name(&function, context, can_export),
+                    },
+                );
+            }
+
+            ClassMember::Constructor {
+                arguments, access, ..
+            } => {
+                class.constructor.data_type = DataType::FunctionType {
+                    arguments: convert_index_map_to_vec(arguments),
+                    return_type: Box::new(DataType::Void),
+                };
+                class.constructor.access = access.clone();
+            }
+        }
+
+        return Ok(());
+    }
+
+    /*
+     * Constructor is a function taking `this` like a method, body is the position
+     * of its `{` or None for the constructor generated for a class without one
+     *
+     * */
+    fn parse_constructor(
+        &mut self,
+        class: &Class,
+        members: &[ClassMember],
+        body: Option<usize>,
+        start: Span,
+        context: &SymbolContext,
+    ) -> Result<AstPtr, String> {
+        let own_arguments: IndexMap<String, DataType> = match body {
+            Some(_) => members
+                .iter()
+                .find_map(|member| match member {
+                    ClassMember::Constructor { arguments, .. } => Some(arguments.clone()),
+                    _ => None,
+                })
+                .unwrap(),
+
+            // Arguments of the constructor of the parent class are passed on as they are
+            None => match &class.constructor.data_type {
+                DataType::FunctionType { arguments, .. } => arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(index, data_type)| (format!("|argument{}", index), data_type.clone()))
+                    .collect(),
+                _ => unreachable!(),
+            },
+        };
+
+        let mut arguments: IndexMap<String, DataType> = IndexMap::new();
+        arguments.insert("this".to_string(), class.get_data_type());
+        arguments.extend(own_arguments.clone());
+
+        if let Some(body) = body {
+            self.cur_pos = Some(body);
+        }
+
+        let (function, _) = self.parse_function_body_with(
+            &class.constructor.function,
+            arguments,
+            Some(DataType::Void),
+            String::from("_"),
+            start,
+            context,
+            |parser, function_context| {
+                parser.parse_constructor_block(
+                    class,
+                    members,
+                    body.is_some(),
+                    &own_arguments,
+                    start,
+                    function_context,
+                )
+            },
+        )?;
+
+        return Ok(function);
+    }
+
+    /*
+     * Block of the constructor is
+     *
+     *      super(<arguments>);
+     *      this.<field> = <initializer>;
+     *      <block>
+     *
+     * Consumes till token `}` when the constructor has a body
+     *
+     * */
+    fn parse_constructor_block(
+        &mut self,
+        class: &Class,
+        members: &[ClassMember],
+        has_body: bool,
+        arguments: &IndexMap<String, DataType>,
+        start: Span,
+        context: &mut SymbolContext,
+    ) -> Result<Vec<AstPtr>, String> {
+        let this = self.get_this(start, context)?;
+        let mut block: Vec<AstPtr> = vec![];
+
+        if has_body {
+            self.assert_cur_token(&Token::AngleOpenBracket)?;
+            self.next(); // consumes {
+        }
+
+        if let Some(parent) = class.parents.first() {
+            let parent = context.get_class(parent).unwrap().clone();
+
+            let super_call = if has_body {
+                if self.get_cur_token()? != &Token::Keyword(KeywordKind::Super) {
+                    return Err(format!(
+                        "Constructor of class {} has to call super(...) before anything else",
+                        class.name
+                    ));
+                }
+
+                let super_call = self.parse_super_call(&parent, this.clone(), context)?;
+                self.skip_semicolon()?;
+                super_call
+            } else {
+                self.check_member_access(
+                    "constructor",
+                    &parent.constructor.access,
+                    &parent.llvm_name,
+                    context,
+                )?;
+
+                let parameters = arguments
+                    .iter()
+                    .map(|(name, data_type)| Expression::IdentExp {
+                        name: format!("{}|{}|", name, context.get_suffix(name)),
+                        data_type: data_type.clone(),
+                        span: start,
+                    })
+                    .collect();
+                create_direct_method_call(
+                    &parent.constructor,
+                    this.clone(),
+                    parameters,
+                    start,
+                    context,
+                )
+            };
+
+            block.push(self.bind_to_temp_variable(super_call, context)?);
+        }
+
+        // Initializers are parsed where they are written and come back to the block
+        let block_pos = self.cur_pos;
+
+        for member in members {
+            if let ClassMember::Field {
+                name,
+                data_type,
+                initializer,
+                ..
+            } = member
+            {
+                let exp = match initializer {
+                    Some(initializer) => {
+                        self.cur_pos = Some(*initializer);
+
+                        let exp = self.parse_expression(1, context)?;
+                        let exp = convert_to_data_type(exp, data_type, context);
+
+                        if !exp.get_data_type().has_same_layout(data_type) {
+                            return Err(format!(
+                                "Cannot initialize field {} of datatype {} with datatype {}",
+                                name,
+                                data_type,
+                                exp.get_data_type()
+                            ));
+                        }
+                        exp
+                    }
+
+                    // Optional field starts out undefined
+                    None if data_type.accepts_nullish(&DataType::Undefined) => {
+                        convert_to_data_type(
+                            Expression::UndefinedLiteralExp { span: start },
+                            data_type,
+                            context,
+                        )
+                    }
+
+                    None => continue,
+                };
+
+                let span = exp.get_span();
+                block.push(Ast::new_field_assignment(
+                    this.clone(),
+                    name,
+                    VariableAssignmentOperator::Assign,
+                    exp,
+                    span,
+                ));
+            }
+        }
+
+        self.cur_pos = block_pos;
+
+        if has_body {
+            while self.get_cur_token()? != &Token::AngleCloseBracket {
+                self.assert_not_eof()?;
+
+                if let Some(ast) = self.next_ast(context) {
+                    block.push(ast);
+                }
+            }
+
+            self.next(); // consumes }
+        }
+
+        mark_captured_variables(&mut block, &self.captured_variables);
+
+        // Field without an initializer has to be assigned by the constructor itself
+        let this_name = match &this {
+            Expression::IdentExp { name, .. } => name.clone(),
+            _ => unreachable!(),
+        };
+
+        for member in members {
+            if let ClassMember::Field {
+                name,
+                initializer: None,
+                ..
+            } = member
+            {
+                let is_assigned = block.iter().any(|ast| {
+                    matches!(
+                        &**ast,
+                        Ast::Declaration(Declaration::FieldAssignment {
+                            object,
+                            field,
+                            operator: VariableAssignmentOperator::Assign,
+                            ..
+                        }) if field == name && matches!(
+                            object,
+                            Expression::IdentExp { name: object_name, .. } if object_name == &this_name
+                        )
+                    )
+                });
+
+                if !is_assigned
+                    && !class.fields[name]
+                        .data_type
+                        .accepts_nullish(&DataType::Undefined)
+                {
+                    return Err(format!(
+                        "Field {} of class {} has no initializer and is not assigned in the constructor",
+                        name, class.name
+                    ));
+                }
+            }
+        }
+
+        return Ok(block);
+    }
+
+    /*
+     * Assumes the current token to be `keyword super` in
+     *
+     *      super(<arguments>)
+     *
+     * as the first statement of the constructor
+     *
+     * Consumes till token `)`
+     *
+     * */
+    fn parse_super_call(
+        &mut self,
+        parent: &Class,
+        this: Expression,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        self.check_member_access(
+            "constructor",
+            &parent.constructor.access,
+            &parent.llvm_name,
+            context,
+        )?;
+
+        self.next(); // consumes super
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let arguments = match &parent.constructor.data_type {
+            DataType::FunctionType { arguments, .. } => arguments.clone(),
+            _ => unreachable!(),
+        };
+        let parameters = self.parse_call_arguments(&arguments, context)?;
+
+        return Ok(create_direct_method_call(
+            &parent.constructor,
+            this,
+            parameters,
+            self.get_span_from(&start),
+            context,
+        ));
+    }
+
+    // Assumes the current token to be `keyword this`, consumes it
+    pub(crate) fn parse_this(&mut self, context: &SymbolContext) -> Result<Expression, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::This))?;
+
+        let this = self.get_this(self.get_cur_span()?, context)?;
+        self.next(); // consumes this
+
+        return Ok(this);
+    }
+
+    // `this` is the first argument of methods and constructors
+    fn get_this(&mut self, span: Span, context: &SymbolContext) -> Result<Expression, String> {
+        match self.get_variable("this", context) {
+            Some(sym_meta) => {
+                return Ok(Expression::IdentExp {
+                    name: format!("this|{}|", context.get_suffix("this")),
+                    data_type: sym_meta.data_type,
+                    span,
+                })
+            }
+
+            None => {
+                return Err(format!(
+                    "Cannot use this outside of a method or a constructor"
+                ))
+            }
+        }
+    }
+
+    /*
+     * Assumes the current token to be `keyword super` in
+     *
+     *      super.<method>(<arguments>)
+     *
+     * The method of the parent class is called directly instead of through the
+     * vtable, so an overriding method can call the one it overrides
+     *
+     * Consumes till token `)`
+     *
+     * */
+    pub(crate) fn parse_super_member(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        self.next(); // consumes super
+
+        let parent = self
+            .cur_class
+            .as_ref()
+            .and_then(|cur_class| context.get_class(cur_class))
+            .and_then(|cur_class| cur_class.parents.first())
+            .and_then(|parent| context.get_class(parent))
+            .cloned();
+
+        let parent = match parent {
+            Some(parent) => parent,
+            None => {
+                return Err(format!(
+                    "super can only be used inside of a class which extends another class"
+                ))
+            }
+        };
+
+        if self.get_cur_token()? == &Token::CurveOpenBracket {
+            return Err(format!(
+                "super(...) can only be called as the first statement of the constructor of a class which extends another class"
+            ));
+        }
+
+        self.assert_cur_token(&Token::Dot)?;
+        let name = self.next().get_ident_name()?.clone(); // consumes .
+
+        let method = match parent.methods.get(&name) {
+            Some(method) => method.clone(),
+            None => {
+                return Err(format!(
+                    "Only methods of class {} can be used through super but {} is not one",
+                    parent.name, name
+                ))
+            }
+        };
+        self.check_member_access(&name, &method.access, &method.owner, context)?;
+
+        let this = self.get_this(start, context)?;
+
+        self.next(); // consumes ident
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let arguments = match &method.data_type {
+            DataType::FunctionType { arguments, .. } => arguments.clone(),
+            _ => unreachable!(),
+        };
+        let parameters = self.parse_call_arguments(&arguments, context)?;
+
+        return Ok(create_direct_method_call(
+            &method,
+            this,
+            parameters,
+            self.get_span_from(&start),
+            context,
+        ));
+    }
+
+    /*
+     * Assumes the current token to be `keyword new` in
+     *
+     *      new <class>(<arguments>)
+     *
+     * which calls the allocator of the class
+     *
+     * Consumes till token `)`
+     *
+     * */
+    pub(crate) fn parse_new_expression(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::New))?;
+        let start = self.get_cur_span()?;
+
+        let name = self.next().get_ident_name()?.clone(); // consumes new
+
+        let class = match context.get_type(&name) {
+            Some(TypeSymbol {
+                class: Some(class), ..
+            }) => class.clone(),
+            _ => {
+                let message = format!("Cannot use new on {} as it is not a class", name);
+                return Err(self.dependent_error(&name, message));
+            }
+        };
+        self.check_member_access(
+            "constructor",
+            &class.constructor.access,
+            &class.constructor.owner,
+            context,
+        )?;
+
+        self.next(); // consumes ident
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let arguments = match &class.constructor.data_type {
+            DataType::FunctionType { arguments, .. } => arguments.clone(),
+            _ => unreachable!(),
+        };
+        let parameters = self.parse_call_arguments(&arguments, context)?;
+
+        let data_type = class.get_data_type();
+        return Ok(Expression::FunctionCall {
+            fn_exp: Box::new(Expression::IdentExp {
+                name: class.get_allocator_name(),
+                data_type: DataType::FunctionType {
+                    arguments,
+                    return_type: Box::new(data_type.clone()),
+                },
+                span: start,
+            }),
+            parameters,
+            return_type: data_type,
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Assumes the current token to be `keyword instanceof` in
+     *
+     *      <left> instanceof <class>
+     *
+     * left has to be an instance of a class or a union holding one
+     *
+     * Consumes till the name of the class
+     *
+     * */
+    pub(crate) fn parse_instanceof_expression(
+        &mut self,
+        left: Expression,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Instanceof))?;
+
+        let name = self.next().get_ident_name()?.clone(); // consumes instanceof
+
+        let class = match context.get_type(&name) {
+            Some(TypeSymbol {
+                class: Some(class), ..
+            }) => class.clone(),
+            _ => {
+                let message = format!("Cannot use instanceof on {} as it is not a class", name);
+                return Err(self.dependent_error(&name, message));
+            }
+        };
+
+        let data_type = left.get_data_type();
+        let holds_instance = match &data_type {
+            DataType::ClassType { .. } | DataType::Object => true,
+            DataType::UnionType { types } => types
+                .iter()
+                .any(|member| matches!(member, DataType::ClassType { .. } | DataType::Object)),
+            _ => false,
+        };
+        if !holds_instance {
+            return Err(format!(
+                "Left side of instanceof has to be an instance of a class but got {}",
+                data_type
+            ));
+        }
+
+        self.next(); // consumes ident
+
+        let span = self.get_span_from(&left.get_span());
+        return Ok(Expression::InstanceofExp {
+            exp: Box::new(left),
+            class: class.get_data_type(),
+            span,
+        });
+    }
+
+    /*
+     * Assumes the current token to be the name of the class in
+     *
+     *      <class>.<static_method>
+     *
+     * Consumes till the name of the method
+     *
+     * */
+    pub(crate) fn parse_static_member(
+        &mut self,
+        class: Class,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+
+        self.next(); // consumes ident
+        self.assert_cur_token(&Token::Dot)?;
+        let name = self.next().get_ident_name()?.clone(); // consumes .
+
+        let method = match class.static_methods.get(&name) {
+            Some(method) => method,
+            None => {
+                return Err(format!(
+                    "Class {} does not have a static method {}",
+                    class.name, name
+                ))
+            }
+        };
+        self.check_member_access(&name, &method.access, &method.owner, context)?;
+
+        self.next(); // consumes ident
+
+        return Ok(Expression::IdentExp {
+            name: method.function.clone(),
+            data_type: method.data_type.clone(),
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Assumes the current token to be the name of the member in
+     *
+     *      <object>.<field>
+     *
+     *      <object>.<method>(<arguments>)
+     *
+     * where object is an instance of the class with the llvm name
+     *
+     * Consumes till the name of the field or token `)`
+     *
+     * */
+    pub(crate) fn parse_class_member(
+        &mut self,
+        object: Expression,
+        class_name: &str,
+        name: &str,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let class = context.get_class(class_name).unwrap().clone();
+        let start = object.get_span();
+
+        if let Some(field) = class.fields.get(name) {
+            self.check_member_access(name, &field.access, &field.owner, context)?;
+            self.next(); // consumes ident
+
+            return Ok(Expression::FieldAccess {
+                object: Box::new(object),
+                field: name.to_string(),
+                data_type: field.data_type.clone(),
+                span: self.get_span_from(&start),
+            });
+        }
+
+        if let Some(method) = class.methods.get(name) {
+            self.check_member_access(name, &method.access, &method.owner, context)?;
+            self.next(); // consumes ident
+
+            if self.get_cur_token()? != &Token::CurveOpenBracket {
+                return Err(format!(
+                    "Method {} of class {} can only be called",
+                    name, class.name
+                ));
+            }
+            self.next(); // consumes (
+
+            let (arguments, return_type) = match &method.data_type {
+                DataType::FunctionType {
+                    arguments,
+                    return_type,
+                } => (arguments, return_type.as_ref().clone()),
+                _ => unreachable!(),
+            };
+            let arguments = self.parse_call_arguments(arguments, context)?;
+
+            return Ok(Expression::MethodCall {
+                object: Box::new(object),
+                method: name.to_string(),
+                arguments,
+                return_type,
+                span: self.get_span_from(&start),
+            });
+        }
+
+        return Err(format!(
+            "Property {} does not exist on class {}",
+            name, class.name
+        ));
+    }
+
+    /*
+     * Private member is accessible inside of the class declaring it, a protected
+     * one also inside of its subclasses
+     *
+     * */
+    fn check_member_access(
+        &self,
+        name: &str,
+        access: &AccessModifier,
+        owner: &str,
+        context: &SymbolContext,
+    ) -> Result<(), String> {
+        let cur_class = self
+            .cur_class
+            .as_ref()
+            .and_then(|cur_class| context.get_class(cur_class));
+
+        let is_accessible = match access {
+            AccessModifier::Public => true,
+            AccessModifier::Protected => {
+                cur_class.map_or(false, |class| class.is_subclass_of(owner))
+            }
+            AccessModifier::Private => cur_class.map_or(false, |class| class.llvm_name == owner),
+        };
+
+        if is_accessible {
+            return Ok(());
+        }
+
+        let owner_name = get_class_name(owner, context);
+
+        match access {
+            AccessModifier::Protected => {
+                return Err(format!(
+                    "{} is protected and only accessible inside of class {} and its subclasses",
+                    name, owner_name
+                ))
+            }
+
+            _ => {
+                return Err(format!(
+                    "{} is private and only accessible inside of class {}",
+                    name, owner_name
+                ))
+            }
+        }
+    }
+
+    /*
+     * Assumes the current token to be the assignment operator in
+     *
+     *      <object>.<field> = <expression>;
+     *
+     * where target is the expression before the operator, only a field can be
+     * assigned to this way
+     *
+     * Consumes till token `;`
+     *
+     * */
+    pub(crate) fn parse_field_assignment(
+        &mut self,
+        target: Expression,
+        context: &SymbolContext,
+    ) -> Result<AstPtr, String> {
+        let (object, field, data_type) = match target {
+            Expression::FieldAccess {
+                object,
+                field,
+                data_type,
+                ..
+            } => (*object, field, data_type),
+            _ => return Err(format!("Invalid left-hand side in assignment")),
+        };
+        let start = object.get_span();
+
+        let operator = self.parse_assignment_operator(&data_type)?;
+        self.next(); // consumes VariableAssignmentOperator
+
+        let exp = self.parse_expression(1, context)?;
+        let exp = convert_to_data_type(exp, &data_type, context);
+
+        if !exp.get_data_type().has_same_layout(&data_type) {
+            return Err(format!(
+                "Assigning datatype {} to field {} whose datatype is {}",
+                exp.get_data_type(),
+                field,
+                data_type
+            ));
+        }
+
+        self.skip_semicolon()?;
+
+        return Ok(Ast::new_field_assignment(
+            object,
+            &field,
+            operator,
+            exp,
+            self.get_span_from(&start),
+        ));
+    }
+}
+
+/*
+ * Calls the function of the method without going through the vtable, `this` is
+ * converted to the class declaring the method
+ *
+ * */
+fn create_direct_method_call(
+    method: &ClassMethod,
+    this: Expression,
+    parameters: Vec<Expression>,
+    span: Span,
+    context: &SymbolContext,
+) -> Expression {
+    let (arguments, return_type) = match &method.data_type {
+        DataType::FunctionType {
+            arguments,
+            return_type,
+        } => (arguments.clone(), return_type.as_ref().clone()),
+        _ => unreachable!(),
+    };
+
+    let this_type = context.get_class(&method.owner).unwrap().get_data_type();
+    let mut arguments_with_this = vec![this_type.clone()];
+    arguments_with_this.extend(arguments);
+
+    let mut parameters_with_this = vec![convert_to_data_type(this, &this_type, context)];
+    parameters_with_this.extend(parameters);
+
+    return Expression::FunctionCall {
+        fn_exp: Box::new(Expression::IdentExp {
+            name: method.function.clone(),
+            data_type: DataType::FunctionType {
+                arguments: arguments_with_this,
+                return_type: Box::new(return_type.clone()),
+            },
+            span,
+        }),
+        parameters: parameters_with_this,
+        return_type,
+        span,
+    };
+}
@@ -0,0 +1,433 @@
+use std::collections::HashSet;
+
+use ast::{
+    data_type::DataType,
+    declaration::{Declaration, VariableDeclarationKind},
+    expression::Expression,
+    Ast, AstPtr,
+};
+use indexmap::IndexMap;
+use lexer::token::{KeywordKind, Span, Token};
+
+use crate::{
+    parser::Parser,
+    symbol_table::{FunctionSymbol, SymbolContext, SymbolMeta, SymbolMetaInsert},
+    traits::ImportResolver,
+    utils::{convert_index_map_to_vec, convert_to_data_type},
+};
+
+/*
+ * Function whose body is being parsed, one for every function context around the
+ * current one
+ *
+ * */
+#[derive(Debug, Default)]
+pub(crate) struct FunctionScope {
+    // Variables of the enclosing functions used by the body, keyed by their llvm name
+    captures: IndexMap<String, DataType>,
+    // Data type of the first return statement when the return type is left out
+    return_type: Option<DataType>,
+}
+
+/*
+ * Variable declarations are parsed before the closures using them, so they are
+ * marked once the whole block is parsed
+ *
+ * */
+pub(crate) fn mark_captured_variables(block: &mut [AstPtr], captured_variables: &HashSet<String>) {
+    for ast in block.iter_mut() {
+        if let Ast::Declaration(Declaration::VariableDeclaration {
+            ident_name,
+            is_captured,
+            ..
+        }) = &mut **ast
+        {
+            *is_captured = captured_variables.contains(ident_name);
+        }
+    }
+}
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Same as SymbolContext::get but a variable declared outside of the function
+     * being parsed is recorded as captured by it, and by every function between it
+     * and the one declaring the variable
+     *
+     * Functions declared at the top of a file are recorded for the generic function
+     * being parsed, see GenericFunction::functions
+     *
+     * */
+    pub(crate) fn get_variable(
+        &mut self,
+        name: &str,
+        context: &SymbolContext,
+    ) -> Option<SymbolMeta> {
+        let sym_meta = context.get(name)?;
+
+        if let (Some(cur_generic), true) = (&mut self.cur_generic, context.is_global_variable(name))
+        {
+            let llvm_name = match &sym_meta.external_data {
+                Some(external_data) => format!(
+                    "|fn:{}|{}|{}|",
+                    external_data.file_id_no, name, sym_meta.suffix
+                ),
+                None => format!("{}|{}|", name, sym_meta.suffix),
+            };
+
+            cur_generic
+                .functions
+                .insert(llvm_name, sym_meta.data_type.clone());
+        }
+
+        if let Some(function_depth) = sym_meta.captured_from {
+            let llvm_name = format!("{}|{}|", name, sym_meta.suffix);
+
+            for scope in self.function_scopes.iter_mut().skip(function_depth) {
+                scope
+                    .captures
+                    .insert(llvm_name.clone(), sym_meta.data_type.clone());
+            }
+            self.captured_variables.insert(llvm_name);
+        }
+
+        return Some(sym_meta);
+    }
+
+    /*
+     * Assumes the current token to be `(`, finds out if it starts an arrow function
+     * instead of a grouped expression by looking for `=>` after the matching `)`
+     * or after the return type
+     *
+     * */
+    pub(crate) fn is_arrow_function(&mut self, context: &SymbolContext) -> Result<bool, String> {
+        let mut look_up_parser = self.lookup_parser();
+        let mut depth = 0;
+
+        loop {
+            look_up_parser.assert_not_eof()?;
+
+            match look_up_parser.get_cur_token()? {
+                Token::CurveOpenBracket => depth += 1,
+                Token::CurveCloseBracket if depth == 1 => break,
+                Token::CurveCloseBracket => depth -= 1,
+                _ => {}
+            }
+            look_up_parser.next();
+        }
+
+        look_up_parser.next(); // consumes )
+
+        match look_up_parser.get_cur_token()? {
+            Token::FunctionArrow => return Ok(true),
+
+            // Can also be the `:` of a conditional expression
+            Token::Colon => {
+                look_up_parser.next(); // consumes :
+
+                let is_return_type = look_up_parser.parse_type_declaration(1, context).is_ok()
+                    && look_up_parser.get_cur_token()? == &Token::FunctionArrow;
+                return Ok(is_return_type);
+            }
+
+            _ => return Ok(false),
+        }
+    }
+
+    /*
+     * Assumes the current token to be `(` in
+     *
+     *      (a: <type>, b?: <type>): <return_type> => <expression>
+     *
+     *      (a: <type>, b?: <type>): <return_type> => {
+     *          <block>
+     *      }
+     *
+     * Return type can be left out, it is then inferred from the body
+     *
+     * Consumes till the end of the expression or token `}`
+     *
+     * */
+    pub(crate) fn parse_arrow_function(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+
+        let arguments = self.parse_parameters(context)?;
+        let return_type = self.parse_optional_return_type(context)?;
+
+        self.assert_cur_token(&Token::FunctionArrow)?;
+        self.next(); // consumes =>
+
+        return self.parse_closure("arrow", arguments, return_type, start, context);
+    }
+
+    /*
+     * Assumes the current token to be `keyword function` in
+     *
+     *      function <name>(a: <type>, b?: <type>): <return_type> {
+     *          <block>
+     *      }
+     *
+     * used as an expression. Name and return type are optional, the name is not
+     * visible inside of the body
+     *
+     * Consumes till token `}`
+     *
+     * */
+    pub(crate) fn parse_function_expression(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Function))?;
+        let start = self.get_cur_span()?;
+        self.next(); // consumes keyword function
+
+        let name = match self.get_cur_token()?.clone() {
+            Token::Ident { name } => {
+                self.next(); // consumes ident
+                name
+            }
+            _ => "anonymous".to_string(),
+        };
+
+        let arguments = self.parse_parameters(context)?;
+        let return_type = self.parse_optional_return_type(context)?;
+
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+
+        return self.parse_closure(&name, arguments, return_type, start, context);
+    }
+
+    /*
+     * Parses the body of the closure starting at the current token, the closure
+     * holds the FunctionDeclaration of the body which gets a suffix of its own
+     *
+     * */
+    pub(crate) fn parse_closure(
+        &mut self,
+        name: &str,
+        arguments: IndexMap<String, DataType>,
+        return_type: Option<DataType>,
+        start: Span,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        // Closure number ends with f so the suffixes of its blocks stay unique
+        let suffix = format!("{}f{}f", context.suffix, self.closure_counter);
+        self.closure_counter += 1;
+
+        let (function, data_type) = self.parse_function_body(
+            &format!("|closure|{}|{}|", name, suffix),
+            arguments,
+            return_type,
+            suffix,
+            start,
+            context,
+        )?;
+
+        return Ok(Expression::Closure {
+            function,
+            data_type,
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Parses the body of a function in a context of its own and returns its
+     * FunctionDeclaration along with the data type of the function. The body is a
+     * block, or for an arrow function also the expression it returns
+     *
+     * Return type left out is the data type of the first return statement, or void
+     * when there is none
+     *
+     * */
+    pub(crate) fn parse_function_body(
+        &mut self,
+        ident_name: &str,
+        arguments: IndexMap<String, DataType>,
+        return_type: Option<DataType>,
+        suffix: String,
+        start: Span,
+        context: &SymbolContext,
+    ) -> Result<(AstPtr, DataType), String> {
+        return self.parse_function_body_with(
+            ident_name,
+            arguments,
+            return_type,
+            suffix,
+            start,
+            context,
+            Self::parse_function_block,
+        );
+    }
+
+    // Same as parse_function_body but the block is parsed by parse_block
+    pub(crate) fn parse_function_body_with(
+        &mut self,
+        ident_name: &str,
+        arguments: IndexMap<String, DataType>,
+        return_type: Option<DataType>,
+        suffix: String,
+        start: Span,
+        context: &SymbolContext,
+        parse_block: impl FnOnce(&mut Self, &mut SymbolContext) -> Result<Vec<AstPtr>, String>,
+    ) -> Result<(AstPtr, DataType), String> {
+        let function_symbol = FunctionSymbol::new(return_type.clone().unwrap_or(DataType::Unknown));
+        let mut function_context = context.create_function_context(function_symbol, suffix);
+
+        let mut llvm_arguments: IndexMap<String, DataType> = IndexMap::new();
+        for (name, data_type) in arguments {
+            let sym_meta = SymbolMetaInsert::create(data_type.clone(), false, false, None);
+            function_context.insert(&name, sym_meta)?;
+
+            let llvm_name = self.get_llvm_var_name(&name, &function_context, false);
+            llvm_arguments.insert(llvm_name, data_type);
+        }
+
+        self.function_scopes.push(FunctionScope::default());
+        let blocks = parse_block(self, &mut function_context);
+        let scope = self.function_scopes.pop().unwrap();
+        let mut blocks = blocks?;
+
+        let return_type = match (return_type, scope.return_type) {
+            (Some(return_type), _) | (None, Some(return_type)) => return_type,
+            (None, None) => DataType::Void,
+        };
+
+        let arguments: IndexMap<String, DataType> = llvm_arguments
+            .into_iter()
+            .map(|(name, data_type)| {
+                let binding_name =
+                    self.copy_captured_binding(&name, &data_type, &mut blocks, start);
+                return (binding_name, data_type);
+            })
+            .collect();
+
+        let data_type = DataType::FunctionType {
+            arguments: convert_index_map_to_vec(&arguments),
+            return_type: Box::new(return_type.clone()),
+        };
+
+        let function = Ast::new_function_declaration(
+            arguments,
+            blocks,
+            ident_name.to_string(),
+            return_type,
+            scope.captures,
+            self.get_span_from(&start),
+        );
+        return Ok((function, data_type));
+    }
+
+    // Body of an arrow function which is not a block returns the expression
+    fn parse_function_block(&mut self, context: &mut SymbolContext) -> Result<Vec<AstPtr>, String> {
+        if self.get_cur_token()? == &Token::AngleOpenBracket {
+            return self.parse_block_with_context(context);
+        }
+
+        let start = self.get_cur_span()?;
+        let return_exp = self.parse_expression(1, context)?;
+        let return_exp = self.check_return_expression(Some(return_exp), context)?;
+
+        return Ok(vec![Ast::new_return_statement(
+            return_exp,
+            self.get_span_from(&start),
+        )]);
+    }
+
+    fn parse_optional_return_type(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Option<DataType>, String> {
+        if self.get_cur_token()? != &Token::Colon {
+            return Ok(None);
+        }
+
+        self.next(); // consumes :
+        return Ok(Some(self.parse_type_declaration(1, context)?));
+    }
+
+    /*
+     * Converts the value returned to the return type of the function, a function
+     * without one returns the data type of its first return statement
+     *
+     * */
+    pub(crate) fn check_return_expression(
+        &mut self,
+        return_exp: Option<Expression>,
+        context: &SymbolContext,
+    ) -> Result<Option<Expression>, String> {
+        let expected_data_type = match context.get_return_type() {
+            None => {
+                return Err(format!(
+                    "Cannot use return keyword outside of function declaration"
+                ))
+            }
+
+            Some(DataType::Unknown) => {
+                let scope = self.function_scopes.last_mut().unwrap();
+                let inferred_type = scope.return_type.get_or_insert_with(|| match &return_exp {
+                    Some(exp) => exp.get_data_type(),
+                    None => DataType::Void,
+                });
+                inferred_type.clone()
+            }
+
+            Some(data_type) => data_type.clone(),
+        };
+
+        let return_exp =
+            return_exp.map(|exp| convert_to_data_type(exp, &expected_data_type, context));
+
+        let actual_data_type = {
+            if let Some(exp) = &return_exp {
+                exp.get_data_type()
+            } else {
+                DataType::Void
+            }
+        };
+
+        if !actual_data_type.has_same_layout(&expected_data_type) {
+            return Err(format!("Expected the return_type declared in function declaration does not match the data_type of expression which the function is returning"));
+        }
+
+        return Ok(return_exp);
+    }
+
+    /*
+     * Parameter or loop variable used by a closure is bound to another name and
+     * copied at the start of the block into a variable of its own name, which
+     * lives in a cell like any other captured variable
+     *
+     * Returns the name the value has to be bound to
+     *
+     * */
+    pub(crate) fn copy_captured_binding(
+        &self,
+        llvm_name: &str,
+        data_type: &DataType,
+        block: &mut Vec<AstPtr>,
+        span: Span,
+    ) -> String {
+        if !self.captured_variables.contains(llvm_name) {
+            return llvm_name.to_string();
+        }
+
+        let binding_name = format!("|binding|{}", llvm_name);
+        let copy = Ast::new_variable_declaration(
+            llvm_name,
+            Expression::IdentExp {
+                name: binding_name.clone(),
+                data_type: data_type.clone(),
+                span,
+            },
+            VariableDeclarationKind::Let,
+            span,
+        );
+
+        block.insert(0, copy);
+        mark_captured_variables(&mut block[..1], &self.captured_variables);
+
+        return binding_name;
+    }
+}
@@ -0,0 +1,387 @@
+use ast::{
+    data_type::DataType,
+    enumeration::{Enum, EnumValue},
+    expression::{BinaryOperator, Expression},
+    Ast, AstPtr,
+};
+use indexmap::IndexMap;
+use lexer::token::{KeywordKind, LiteralKind, Span, Token};
+
+use crate::{
+    parser::Parser,
+    symbol_table::{SymbolContext, TypeSymbol},
+    traits::ImportResolver,
+};
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Assumes the current token to be `keyword enum` or `keyword const` in
+     *
+     *      const enum <name> {
+     *          <member>,
+     *          <member> = <initializer>,
+     *      }
+     *
+     * Initializer is a number or a string literal, a member without one takes the
+     * value of the member before it plus one. The reverse mapping of a numeric enum
+     * which is not a const enum is declared as a function along with it
+     *
+     * Consumes till token `}`
+     *
+     * */
+    pub(crate) fn parse_enum_declaration(
+        &mut self,
+        context: &mut SymbolContext,
+        can_export: bool,
+    ) -> Result<AstPtr, String> {
+        let start = self.get_cur_span()?;
+
+        let is_const = self.get_cur_token()? == &Token::Keyword(KeywordKind::Const);
+        if is_const {
+            self.next(); // consumes const
+        }
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Enum))?;
+
+        if !context.is_global() {
+            return Err("Enums can only be declared at the top of the file".to_string());
+        }
+
+        let name = self.next().get_ident_name()?.clone(); // consumes enum
+        self.next(); // consumes ident
+
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+        self.next(); // consumes {
+
+        let mut members: IndexMap<String, EnumValue> = IndexMap::new();
+        // Value of the next member without an initializer, there is none after a string
+        let mut next_value = Some(0.0);
+
+        while self.get_cur_token()? != &Token::AngleCloseBracket {
+            self.assert_not_eof()?;
+            let position = self.cur_pos;
+
+            match self.parse_enum_member(&name, &members, next_value) {
+                Ok((member, value)) => {
+                    next_value = match &value {
+                        EnumValue::Number(value) => Some(value + 1.0),
+                        EnumValue::String(_) => None,
+                    };
+                    members.insert(member, value);
+                }
+
+                /*
+                 * Invalid member is recorded and left out, the error points at its name.
+                 * Uses of the member are not reported again, see dependent_error
+                 *
+                 * */
+                Err(err) => {
+                    self.cur_pos = position;
+                    let diagnostic = self.to_diagnostic(err);
+                    self.diagnostics.push(diagnostic);
+
+                    if let Ok(member) = self.get_cur_token()?.get_ident_name() {
+                        let member_path = format!("{}.{}", name, member);
+                        self.unbound_names.insert(member_path);
+                    }
+
+                    while !matches!(
+                        self.get_cur_token()?,
+                        Token::Comma | Token::AngleCloseBracket
+                    ) {
+                        self.assert_not_eof()?;
+                        self.next();
+                    }
+                }
+            }
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                self.assert_cur_token(&Token::AngleCloseBracket)?;
+            }
+        }
+
+        self.next(); // consumes }
+
+        let enumeration = Enum {
+            name: name.clone(),
+            llvm_name: self.get_llvm_var_name(&name, context, can_export),
+            members,
+            is_const,
+        };
+
+        context.insert_type(
+            &name,
+            TypeSymbol::create_enum(enumeration.clone(), can_export),
+        )?;
+        context.insert_enum(enumeration.clone());
+
+        let span = self.get_span_from(&start);
+        let reverse_mapping = if enumeration.has_reverse_mapping() {
+            Some(create_reverse_mapping(&enumeration, span))
+        } else {
+            None
+        };
+
+        return Ok(Ast::new_enum_declaration(
+            enumeration,
+            reverse_mapping,
+            span,
+        ));
+    }
+
+    /*
+     * Assumes the current token to be the name of the member in
+     *
+     *      <member> = <initializer>
+     *
+     * next_value is the value of a member without an initializer
+     *
+     * Consumes till the name of the member or the end of the initializer
+     *
+     * */
+    fn parse_enum_member(
+        &mut self,
+        name: &str,
+        members: &IndexMap<String, EnumValue>,
+        next_value: Option<f64>,
+    ) -> Result<(String, EnumValue), String> {
+        let member = self.get_cur_token()?.get_ident_name()?.clone();
+        if members.contains_key(&member) {
+            return Err(format!("Duplicate member {} in enum {}", member, name));
+        }
+        self.next(); // consumes ident
+
+        let value = if self.get_cur_token()? == &Token::Assign {
+            self.next(); // consumes =
+            self.parse_enum_initializer(&member)?
+        } else {
+            match next_value {
+                Some(value) => EnumValue::Number(value),
+                None => {
+                    return Err(format!(
+                    "Enum member {} needs an initializer since the member before it is a string",
+                    member
+                ))
+                }
+            }
+        };
+
+        if let Some(first) = members.values().next() {
+            if matches!(
+                (first, &value),
+                (EnumValue::Number(_), EnumValue::String(_))
+                    | (EnumValue::String(_), EnumValue::Number(_))
+            ) {
+                return Err(format!(
+                    "Members of enum {} have to be all numbers or all strings",
+                    name
+                ));
+            }
+        }
+
+        return Ok((member, value));
+    }
+
+    /*
+     * Assumes the current token to be the start of the initializer in
+     *
+     *      <member> = <initializer>
+     *
+     * Consumes till the end of the initializer
+     *
+     * */
+    fn parse_enum_initializer(&mut self, member: &str) -> Result<EnumValue, String> {
+        let is_negative = self.get_cur_token()? == &Token::Minus;
+        if is_negative {
+            self.next(); // consumes -
+        }
+
+        let value = match self.get_cur_token()? {
+            Token::Literal(LiteralKind::Float { name: _, value }) if is_negative => {
+                EnumValue::Number(-*value)
+            }
+            Token::Literal(LiteralKind::Float { name: _, value }) => EnumValue::Number(*value),
+            Token::Literal(LiteralKind::String { name }) if !is_negative => {
+                EnumValue::String(name.clone())
+            }
+
+            _ => {
+                return Err(format!(
+                    "Initializer of enum member {} has to be a number or a string literal",
+                    member
+                ))
+            }
+        };
+        self.next(); // consumes literal
+
+        return Ok(value);
+    }
+
+    /*
+     * Assumes the current token to be the name of the enum in
+     *
+     *      <enum>.<member>
+     *
+     *      <enum>[<value>]
+     *
+     * Indexing by a value calls the reverse mapping of the enum, which gives the
+     * name of the member with the value or undefined
+     *
+     * Consumes till the name of the member or token `]`
+     *
+     * */
+    pub(crate) fn parse_enum_access(
+        &mut self,
+        enumeration: Enum,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        self.next(); // consumes ident
+
+        match self.get_cur_token()? {
+            Token::Dot => {
+                let member = self.next().get_ident_name()?.clone(); // consumes .
+
+                let value = match enumeration.members.get(&member) {
+                    Some(value) => value.clone(),
+                    None => {
+                        let message = format!("Enum {} has no member {}", enumeration.name, member);
+                        let member_path = format!("{}.{}", enumeration.name, member);
+                        return Err(self.dependent_error(&member_path, message));
+                    }
+                };
+                self.next(); // consumes ident
+
+                return Ok(Expression::EnumMember {
+                    member,
+                    value,
+                    data_type: enumeration.get_data_type(),
+                    span: self.get_span_from(&start),
+                });
+            }
+
+            Token::BoxOpenBracket => {
+                if !enumeration.has_reverse_mapping() {
+                    return Err(format!(
+                        "Enum {} can not be indexed, only numeric enums which are not const have a reverse mapping",
+                        enumeration.name
+                    ));
+                }
+                self.next(); // consumes [
+
+                let value = self.parse_expression(1, context)?;
+                if !matches!(
+                    value.get_data_type(),
+                    DataType::Float
+                        | DataType::EnumType {
+                            is_numeric: true,
+                            ..
+                        }
+                ) {
+                    return Err(format!(
+                        "Enum {} can only be indexed by a number but got {}",
+                        enumeration.name,
+                        value.get_data_type()
+                    ));
+                }
+
+                self.assert_cur_token(&Token::BoxCloseBracket)?;
+                self.next(); // consumes ]
+
+                let span = self.get_span_from(&start);
+                let data_type = Enum::get_reverse_mapping_type();
+                let return_type = match &data_type {
+                    DataType::FunctionType { return_type, .. } => *return_type.clone(),
+                    _ => unreachable!(),
+                };
+
+                return Ok(Expression::FunctionCall {
+                    fn_exp: Box::new(Expression::IdentExp {
+                        name: enumeration.get_reverse_mapping_name(),
+                        data_type,
+                        span,
+                    }),
+                    parameters: vec![value],
+                    return_type,
+                    span,
+                });
+            }
+
+            _ => {
+                return Err(format!(
+                    "Enum {} can only be used to access its members",
+                    enumeration.name
+                ))
+            }
+        }
+    }
+}
+
+/*
+ * Declaration of the reverse mapping of the numeric enum
+ *
+ *      function <reverse_mapping>(value: number): string | undefined {
+ *          return value === 1 ? "B" : value === 0 ? "A" : undefined;
+ *      }
+ *
+ * When members share a value the last one of them is the name, as in js
+ *
+ * */
+fn create_reverse_mapping(enumeration: &Enum, span: Span) -> AstPtr {
+    let return_type = DataType::UnionType {
+        types: vec![DataType::String, DataType::Undefined],
+    };
+
+    let mut exp = Expression::UnionConversion {
+        exp: Box::new(Expression::UndefinedLiteralExp { span }),
+        data_type: return_type.clone(),
+        span,
+    };
+
+    for (member, value) in enumeration.members.iter() {
+        let value = match value {
+            EnumValue::Number(value) => *value,
+            EnumValue::String(_) => unreachable!(),
+        };
+
+        exp = Expression::ConditionalExp {
+            test: Box::new(Expression::BinaryExp {
+                operator: BinaryOperator::StrictEquality,
+                left: Box::new(Expression::IdentExp {
+                    name: "value".to_string(),
+                    data_type: DataType::Float,
+                    span,
+                }),
+                right: Box::new(Expression::FloatLiteralExp {
+                    name: value.to_string(),
+                    value,
+                    span,
+                }),
+                span,
+            }),
+            consequent: Box::new(Expression::UnionConversion {
+                exp: Box::new(Expression::StringLiteralExp {
+                    value: member.clone(),
+                    span,
+                }),
+                data_type: return_type.clone(),
+                span,
+            }),
+            alternate: Box::new(exp),
+            span,
+        };
+    }
+
+    let mut arguments = IndexMap::new();
+    arguments.insert("value".to_string(), DataType::Float);
+
+    return Ast::new_function_declaration(
+        arguments,
+        vec![Ast::new_return_statement(Some(exp), span)],
+        enumeration.get_reverse_mapping_name(),
+        return_type,
+        IndexMap::new(),
+        span,
+    );
+}
@@ -1,14 +1,23 @@
 use std::collections::HashMap;
 
-use ast::{data_type::DataType, expression::Expression};
+use ast::{
+    data_type::{is_assignable, DataType},
+    expression::{ArrayMethod, ConsoleMethod, Expression},
+};
 use indexmap::IndexMap;
-use lexer::token::{KeywordKind, LiteralKind, Token};
+use lexer::token::{KeywordKind, LiteralKind, Span, Token};
 
 use crate::{
+    narrowing::{get_narrowings, Narrowings},
     parser::Parser,
-    symbol_table::SymbolContext,
+    symbol_table::{SymbolContext, TypeSymbol},
     traits::ImportResolver,
-    utils::{convert_token_to_binary_operator, convert_token_to_unary_operator},
+    utils::{
+        can_convert_to_string, check_not_nullish, convert_to_data_type,
+        convert_token_to_binary_operator, convert_token_to_logical_operator,
+        convert_token_to_unary_operator, convert_token_to_update_operator, create_union_type,
+        get_members, is_primitive_union, remove_nullish,
+    },
 };
 
 impl<'a, R: ImportResolver> Parser<'a, R> {
@@ -18,7 +27,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         context: &SymbolContext,
     ) -> Result<Expression, String> {
         let mut prefix_fun = self.get_prefix_exp(context)?;
-        let next_token = self.get_cur_token()?.clone();
+        let mut next_token = self.get_cur_token()?.clone();
 
         while next_token != Token::SemiColon
             && precedence < self.get_non_prefix_precedence(&next_token)
@@ -34,6 +43,8 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     break;
                 }
             }
+
+            next_token = self.get_cur_token()?.clone();
         }
 
         return Ok(prefix_fun);
@@ -41,12 +52,22 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
     pub(crate) fn get_prefix_exp(&mut self, context: &SymbolContext) -> Result<Expression, String> {
         let cur_token = &self.get_cur_token()?.clone();
+        let start = self.get_cur_span()?;
 
         match cur_token {
-            Token::Plus | Token::Minus | Token::Bang => {
+            Token::Plus | Token::Minus | Token::Bang | Token::Tilde => {
                 return self.parse_generic_unary_expression(context);
             }
 
+            Token::Increment | Token::Decrement => {
+                self.next(); // consumes ++ or --
+
+                let argument =
+                    self.parse_expression(self.get_prefix_precedence(cur_token), context)?;
+                let span = self.get_span_from(&start);
+                return self.create_update_expression(cur_token, true, argument, span, context);
+            }
+
             Token::Literal(literal_kind) => match literal_kind {
                 LiteralKind::Float { name, value } => {
                     let name = name.to_string();
@@ -54,7 +75,11 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                     self.next(); // consumes Float
 
-                    return Ok(Expression::FloatLiteralExp { name, value });
+                    return Ok(Expression::FloatLiteralExp {
+                        name,
+                        value,
+                        span: start,
+                    });
                 }
 
                 LiteralKind::String { name } => {
@@ -62,10 +87,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                     self.next(); // consumes string
 
-                    return Ok(Expression::StringLiteralExp { value: name });
+                    return Ok(Expression::StringLiteralExp {
+                        value: name,
+                        span: start,
+                    });
+                }
+
+                LiteralKind::BigInt { name, value: _ } => {
+                    return Err(format!("BigInt literal {} is not supported yet", name));
                 }
             },
 
+            Token::TemplateHead { name: _ } => {
+                return self.parse_template_literal(context);
+            }
+
             Token::Keyword(keyword_kind) => match keyword_kind {
                 KeywordKind::True => {
                     self.next(); // consumes true
@@ -73,6 +109,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     return Ok(Expression::BooleanLiteralExp {
                         name: "true".to_string(),
                         value: true,
+                        span: start,
                     });
                 }
 
@@ -82,9 +119,42 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     return Ok(Expression::BooleanLiteralExp {
                         name: "false".to_string(),
                         value: false,
+                        span: start,
                     });
                 }
 
+                KeywordKind::Null => {
+                    self.next(); // consumes null
+
+                    return Ok(Expression::NullLiteralExp { span: start });
+                }
+
+                KeywordKind::Undefined => {
+                    self.next(); // consumes undefined
+
+                    return Ok(Expression::UndefinedLiteralExp { span: start });
+                }
+
+                KeywordKind::Typeof => {
+                    return self.parse_generic_unary_expression(context);
+                }
+
+                KeywordKind::Function => {
+                    return self.parse_function_expression(context);
+                }
+
+                KeywordKind::This => {
+                    return self.parse_this(context);
+                }
+
+                KeywordKind::Super => {
+                    return self.parse_super_member(context);
+                }
+
+                KeywordKind::New => {
+                    return self.parse_new_expression(context);
+                }
+
                 _ => {
                     return Err(format!(
                         "Given keyword does not have a prefix function {:?}",
@@ -94,7 +164,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             },
 
             Token::Ident { name } => {
-                if let Some(sym_meta) = context.get(&name) {
+                let mut lookup_parser = self.lookup_parser();
+                lookup_parser.next(); // consumes ident
+                if lookup_parser.get_cur_token()? == &Token::FunctionArrow {
+                    return Err(format!(
+                        "Parameter {} of arrow function needs a type annotation",
+                        name
+                    ));
+                }
+
+                if context.get_generic_function(name).is_some() {
+                    let name = name.clone();
+                    return self.parse_generic_call(&name, context);
+                }
+
+                if let Some(sym_meta) = self.get_variable(name, context) {
                     let llvm_var_name = {
                         if let Some(ext_data) = sym_meta.external_data {
                             format!(
@@ -108,18 +192,44 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         }
                     };
 
-                    let exp = Ok(Expression::IdentExp {
+                    let mut exp = Expression::IdentExp {
                         name: llvm_var_name,
                         data_type: sym_meta.data_type.clone(),
-                    });
+                        span: start,
+                    };
+
+                    // Union checked by an enclosing `typeof` or null check is narrowed
+                    if let DataType::UnionType { types: _ } = &sym_meta.data_type {
+                        if let Some(narrowed_type) = context.get_narrowed_type(name) {
+                            exp = Expression::UnionConversion {
+                                exp: Box::new(exp),
+                                data_type: narrowed_type,
+                                span: start,
+                            };
+                        }
+                    }
 
                     self.next(); // Consumes ident
 
-                    return exp;
+                    return Ok(exp);
+                } else if name == "console" {
+                    return self.parse_console_call(context);
+                } else if let Some(TypeSymbol {
+                    class: Some(class), ..
+                }) = context.get_type(name)
+                {
+                    return self.parse_static_member(class.clone(), context);
+                } else if let Some(TypeSymbol {
+                    enumeration: Some(enumeration),
+                    ..
+                }) = context.get_type(name)
+                {
+                    return self.parse_enum_access(enumeration.clone(), context);
                 } else {
                     let exp = Expression::IdentExp {
                         name: name.to_string(),
                         data_type: DataType::NA,
+                        span: start,
                     };
                     // self.na_dep.insert(NaDepCondition::GlobalVariable{parser_name : name.to_string()}, &mut exp);
 
@@ -129,13 +239,15 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             }
 
             Token::CurveOpenBracket => {
+                if self.is_arrow_function(context)? {
+                    return self.parse_arrow_function(context);
+                }
+
                 self.next(); // consume (
 
                 let grouped_exp = self.parse_expression(1, context)?;
 
-                let cur_tok = self.get_cur_token().unwrap();
-
-                assert_eq!(cur_tok, &Token::CurveCloseBracket);
+                self.assert_cur_token(&Token::CurveCloseBracket)?;
                 self.next(); // consumes )
 
                 return Ok(grouped_exp);
@@ -149,7 +261,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                 let mut there_is_comma = true;
 
-                while self.get_cur_token()? != &Token::AngleCloseBracket && there_is_comma {
+                while self.get_cur_token()? != &Token::BoxCloseBracket && there_is_comma {
                     let item = self.parse_expression(1, context)?;
 
                     let tok = self.get_cur_token()?;
@@ -170,10 +282,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     self.next(); // consumes ]
                 }
 
-                if expressions.len() <= 0 {
-                    return Err(format!("Creating array with 0 items is not yet supported"));
-                }
-
+                // Type of `[]` is inferred later from where it is used, see infer_empty_array
                 let mut data_type = DataType::Unknown;
 
                 let matched = expressions.iter().enumerate().all(|(i, exp)| {
@@ -185,15 +294,44 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     return data_type == exp.get_data_type();
                 });
 
+                /*
+                 * Elements of different data types make an array of their union, e.g.
+                 * `[1, "a"]` is an array of `number | string`, unless one of them can
+                 * hold all the others like a class the other elements extend
+                 *
+                 * */
                 if !matched {
-                    return Err(format!(
-                        "Expected all expressions to have same datatype in array"
-                    ));
+                    let data_types: Vec<DataType> =
+                        expressions.iter().map(|exp| exp.get_data_type()).collect();
+
+                    let common_type = data_types.iter().find(|target| {
+                        data_types
+                            .iter()
+                            .all(|source| is_assignable(source, target))
+                    });
+
+                    data_type = match common_type {
+                        Some(common_type) => common_type.clone(),
+                        None => match create_union_type(data_types) {
+                            Ok(data_type) => data_type,
+                            Err(_) => {
+                                return Err(format!(
+                                    "Expected all expressions to have same datatype in array"
+                                ))
+                            }
+                        },
+                    };
+
+                    expressions = expressions
+                        .into_iter()
+                        .map(|exp| convert_to_data_type(exp, &data_type, context))
+                        .collect();
                 }
 
                 return Ok(Expression::ArrayLiteral {
                     expression: Box::new(expressions),
                     expression_data_type: data_type,
+                    span: self.get_span_from(&start),
                 });
             }
 
@@ -236,6 +374,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         entries: datatype_entries,
                     },
                     expression: expression_entries,
+                    span: self.get_span_from(&start),
                 });
             }
 
@@ -253,14 +392,345 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         context: &SymbolContext,
     ) -> Result<Expression, String> {
         let cur_token = self.get_cur_token()?.clone();
+        let start = self.get_cur_span()?;
         let precedence = self.get_prefix_precedence(&cur_token);
 
         self.next(); // consumes cur_token
 
         let arg_exp = self.parse_expression(precedence, context)?;
+
+        if let Token::Plus | Token::Minus | Token::Tilde = cur_token {
+            check_union_operand(&cur_token, &arg_exp.get_data_type())?;
+            check_operand_type(&cur_token, &arg_exp.get_data_type())?;
+        }
+
+        // -2 ** 2 could either mean (-2) ** 2 or -(2 ** 2)
+        if self.get_cur_token()? == &Token::StarStar {
+            return Err(format!(
+                "Unary operator used immediately before exponentiation expression, parenthesis must be used to disambiguate operator precedence"
+            ));
+        }
+
         return Ok(Expression::UnaryExp {
             operator: convert_token_to_unary_operator(&cur_token),
             argument: Box::new(arg_exp),
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Assumes the current token to be TemplateHead in
+     *
+     * `a${<expression>}b${<expression>}c`
+     *
+     * Consumes till TemplateTail
+     *
+     * */
+    pub(crate) fn parse_template_literal(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        let mut quasis: Vec<String> = vec![];
+        let mut expressions: Vec<Expression> = vec![];
+
+        if let Token::TemplateHead { name } = self.get_cur_token()? {
+            quasis.push(name.clone());
+        } else {
+            return Err(format!(
+                "Expected token to be TemplateHead but got {:?}",
+                self.get_cur_token()?
+            ));
+        }
+        self.next(); // consumes TemplateHead
+
+        loop {
+            let exp = self.parse_expression(1, context)?;
+
+            let data_type = exp.get_data_type();
+            if let DataType::UnionType { types: _ } = data_type {
+                if !is_primitive_union(&data_type) {
+                    return Err(format!(
+                        "Cannot convert {} to string in template literal, narrow it with typeof first",
+                        data_type
+                    ));
+                }
+            }
+
+            if !can_convert_to_string(&data_type) && data_type != DataType::NA {
+                return Err(format!(
+                    "Cannot convert {} to string in template literal, only primitives are supported",
+                    data_type
+                ));
+            }
+            expressions.push(exp);
+
+            match self.get_cur_token()?.clone() {
+                Token::TemplateMiddle { name } => {
+                    quasis.push(name);
+                    self.next(); // consumes TemplateMiddle
+                }
+
+                Token::TemplateTail { name } => {
+                    quasis.push(name);
+                    self.next(); // consumes TemplateTail
+                    break;
+                }
+
+                tok => {
+                    return Err(format!(
+                        "Expected `}}` to close substitution in template literal but got {:?}",
+                        tok
+                    ))
+                }
+            }
+        }
+
+        return Ok(Expression::TemplateLiteral {
+            quasis,
+            expressions,
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Assumes the current token to be Ident console in
+     *
+     * console.log(<expression>, <expression>, ...)
+     *
+     * Consumes till token ')'
+     *
+     * */
+    pub(crate) fn parse_console_call(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        self.next(); // consumes console
+
+        self.assert_cur_token(&Token::Dot)?;
+        self.next(); // consumes .
+
+        let method = match self.get_cur_token()? {
+            Token::Ident { name } if name == "log" => ConsoleMethod::Log,
+            Token::Ident { name } if name == "error" => ConsoleMethod::Error,
+            tok => {
+                return Err(format!(
+                "console can only be used as console.log(...) or console.error(...) but got {:?}",
+                tok
+            ))
+            }
+        };
+        self.next(); // consumes log or error
+
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let mut arguments: Vec<Expression> = vec![];
+
+        while self.get_cur_token()? != &Token::CurveCloseBracket {
+            let argument = self.parse_expression(1, context)?;
+
+            if let Expression::IdentExp {
+                name,
+                data_type: DataType::NA,
+                span: _,
+            } = &argument
+            {
+                return Err(self.unknown_variable(name));
+            }
+            arguments.push(argument);
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                self.assert_cur_token(&Token::CurveCloseBracket)?;
+            }
+        }
+        self.next(); // consumes )
+
+        return Ok(Expression::ConsoleCall {
+            method,
+            arguments,
+            span: self.get_span_from(&start),
+        });
+    }
+
+    /*
+     * Assumes the current token to be the Ident after the dot in
+     *
+     * arr.length
+     * arr.push(<expression>, <expression>, ...)
+     * arr.pop()
+     * arr.slice(<expression>?, <expression>?)
+     * arr.indexOf(<expression>)
+     *
+     * Consumes till the Ident for length and till token ')' for the methods
+     *
+     * */
+    pub(crate) fn parse_array_member(
+        &mut self,
+        array: Expression,
+        name: &str,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let base_type = match array.get_data_type() {
+            DataType::ArrayType { base_type } => base_type.as_ref().clone(),
+            data_type => {
+                return Err(format!(
+                    "Expected expression of datatype array but got {}",
+                    data_type
+                ))
+            }
+        };
+
+        let method = match name {
+            "length" => {
+                self.next(); // consumes length
+
+                let span = self.get_span_from(&array.get_span());
+                return Ok(Expression::ArrayLength {
+                    array: Box::new(array),
+                    span,
+                });
+            }
+            "push" => ArrayMethod::Push,
+            "pop" => ArrayMethod::Pop,
+            "slice" => ArrayMethod::Slice,
+            "indexOf" => ArrayMethod::IndexOf,
+            _ => return Err(format!("Array does not have a property named {}", name)),
+        };
+        self.next(); // consumes method name
+
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let mut arguments: Vec<Expression> = vec![];
+
+        while self.get_cur_token()? != &Token::CurveCloseBracket {
+            let argument = self.parse_expression(1, context)?;
+            let expected_data_type = match method {
+                ArrayMethod::Slice => DataType::Float,
+                _ => base_type.clone(),
+            };
+            let argument = convert_to_data_type(argument, &expected_data_type, context);
+
+            if !argument
+                .get_data_type()
+                .has_same_layout(&expected_data_type)
+            {
+                return Err(format!(
+                    "Expected argument of {} to be of datatype {} but got {}",
+                    name,
+                    expected_data_type,
+                    argument.get_data_type()
+                ));
+            }
+            arguments.push(argument);
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                self.assert_cur_token(&Token::CurveCloseBracket)?;
+            }
+        }
+        self.next(); // consumes )
+
+        let (min_arguments, max_arguments) = match method {
+            ArrayMethod::Push => (1, usize::MAX),
+            ArrayMethod::Pop => (0, 0),
+            ArrayMethod::Slice => (0, 2),
+            ArrayMethod::IndexOf => (1, 1),
+        };
+
+        if arguments.len() < min_arguments || arguments.len() > max_arguments {
+            return Err(format!(
+                "{} does not take {} arguments",
+                name,
+                arguments.len()
+            ));
+        }
+
+        let span = self.get_span_from(&array.get_span());
+        return Ok(Expression::ArrayMethodCall {
+            array: Box::new(array),
+            method,
+            arguments,
+            span,
+        });
+    }
+
+    /*
+     * Assumes the current token to be ?. in
+     *
+     * <container>?.<ident>
+     *
+     * Consumes till the Ident, only property access of an object can be optional
+     *
+     * */
+    pub(crate) fn parse_optional_chain(
+        &mut self,
+        container: Expression,
+    ) -> Result<Expression, String> {
+        self.next(); // consumes ?.
+
+        let name = match self.get_cur_token()? {
+            Token::Ident { name } => name.clone(),
+            tok => {
+                return Err(format!(
+                    "Optional chaining is only supported for property access but got {:?}",
+                    tok
+                ))
+            }
+        };
+
+        let container_data_type = container.get_data_type();
+
+        let entries = match remove_nullish(&container_data_type) {
+            Some(DataType::ObjectType { entries }) => entries,
+            Some(data_type) => {
+                return Err(format!(
+                    "Optional chaining can be only used on expression whose datatype is object but used on data_type {}",
+                    data_type
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Optional chaining cannot be used on {} which is always null or undefined",
+                    container_data_type
+                ))
+            }
+        };
+
+        let field_data_type = match entries.get(&name) {
+            Some(data_type) => data_type.clone(),
+            None => {
+                return Err(format!(
+                    "There is no member with name {} on Datatype {}",
+                    name, container_data_type
+                ))
+            }
+        };
+        self.next(); // consumes Ident
+
+        let span = self.get_span_from(&container.get_span());
+
+        // Container which is never null or undefined is accessed like with `.`
+        if !container_data_type.accepts_nullish(&DataType::Null)
+            && !container_data_type.accepts_nullish(&DataType::Undefined)
+        {
+            return Ok(Expression::DotMemberAccess {
+                container: Box::new(container),
+                argument: name,
+                span,
+            });
+        }
+
+        return Ok(Expression::OptionalDotMemberAccess {
+            container: Box::new(container),
+            argument: name,
+            data_type: create_union_type(vec![field_data_type, DataType::Undefined])?,
+            span,
         });
     }
 
@@ -276,11 +746,18 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             | Token::Minus
             | Token::Star
             | Token::Slash
+            | Token::Percent
+            | Token::StarStar
             | Token::VerticalBar
             | Token::Caret
             | Token::Ampersand
+            | Token::LeftShift
+            | Token::RightShift
+            | Token::UnsignedRightShift
             | Token::StrictEquality
             | Token::StrictNotEqual
+            | Token::Equality
+            | Token::NotEqual
             | Token::LessThan
             | Token::LessThanOrEqual
             | Token::GreaterThan
@@ -289,6 +766,63 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 return Ok(Ok(exp));
             }
 
+            Token::Increment | Token::Decrement => {
+                let operator_token = non_prefix_token.clone();
+                self.next(); // consumes ++ or --
+
+                let span = self.get_span_from(&left.get_span());
+                let exp =
+                    self.create_update_expression(&operator_token, false, left, span, context)?;
+                return Ok(Ok(exp));
+            }
+
+            Token::LogicalAnd | Token::LogicalOr | Token::NullishCoalescing => {
+                let exp = self.parse_logical_expression(left, context)?;
+                return Ok(Ok(exp));
+            }
+
+            Token::Keyword(KeywordKind::Instanceof) => {
+                let exp = self.parse_instanceof_expression(left, context)?;
+                return Ok(Ok(exp));
+            }
+
+            // Non-null assertion `x!` is not checked at runtime, like in TypeScript
+            Token::Bang => {
+                self.next(); // consumes !
+
+                let data_type = left.get_data_type();
+                let span = self.get_span_from(&left.get_span());
+
+                match remove_nullish(&data_type) {
+                    Some(non_nullish) if non_nullish == data_type => return Ok(Ok(left)),
+
+                    Some(non_nullish) => {
+                        return Ok(Ok(Expression::UnionConversion {
+                            exp: Box::new(left),
+                            data_type: non_nullish,
+                            span,
+                        }));
+                    }
+
+                    None => {
+                        return Err(format!(
+                        "Non-null assertion cannot be used on {} which is always null or undefined",
+                        data_type
+                    ))
+                    }
+                }
+            }
+
+            Token::QuestionDot => {
+                let exp = self.parse_optional_chain(left)?;
+                return Ok(Ok(exp));
+            }
+
+            Token::QuestionMark => {
+                let exp = self.parse_conditional_expression(left, context)?;
+                return Ok(Ok(exp));
+            }
+
             Token::BoxOpenBracket => {
                 self.next(); // consumes [
 
@@ -297,27 +831,34 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 self.assert_cur_token(&Token::BoxCloseBracket)?;
                 self.next(); // consumes ]
 
+                check_not_nullish(&left.get_data_type())?;
+
                 let data_type = member_access_exp.get_data_type();
 
                 match data_type {
-                    DataType::Float => {
+                    DataType::Float
+                    | DataType::EnumType {
+                        is_numeric: true, ..
+                    } => {
                         let left_data_type = left.get_data_type();
 
                         if let DataType::ArrayType { base_type: _ } = left_data_type {
+                            let span = self.get_span_from(&left.get_span());
                             let exp = Expression::ArrayMemberAccess {
                                 array: Box::new(left),
                                 argument: Box::new(member_access_exp),
+                                span,
                             };
 
                             return Ok(Ok(exp));
                         } else {
-                            return Err(format!("Expected the argument for left parameter to be expression of datatype ArrayType but got {:?}", left_data_type));
+                            return Err(format!("Expected the argument for left parameter to be expression of datatype array but got {}", left_data_type));
                         }
                     }
 
                     _ => {
                         return Err(format!(
-                            "Expected datatype of member_access_exp to be Float but got {:?}",
+                            "Expected datatype of member_access_exp to be number but got {}",
                             data_type
                         ))
                     }
@@ -329,16 +870,32 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                 if let Token::Ident { name } = self.get_cur_token()?.clone() {
                     let data_type = left.get_data_type();
+                    check_not_nullish(&data_type)?;
+
+                    if let DataType::ClassType {
+                        name: class_name, ..
+                    } = &data_type
+                    {
+                        let exp = self.parse_class_member(left, class_name, &name, context)?;
+                        return Ok(Ok(exp));
+                    }
+
+                    if let DataType::ArrayType { base_type: _ } = data_type {
+                        let exp = self.parse_array_member(left, &name, context)?;
+                        return Ok(Ok(exp));
+                    }
 
                     if let DataType::ObjectType { entries: _ } = data_type {
                         self.next(); // consumes Ident
 
+                        let span = self.get_span_from(&left.get_span());
                         return Ok(Ok(Expression::DotMemberAccess {
                             container: Box::new(left),
                             argument: name.clone(),
+                            span,
                         }));
                     } else {
-                        return Err(format!("Dot member access can be only used on expression whose datatype is ObjectType but used on data_type {:?}", data_type));
+                        return Err(format!("Dot member access can be only used on expression whose datatype is object but used on data_type {}", data_type));
                     }
                 } else {
                     return Err(format!(
@@ -352,51 +909,24 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 self.next(); // consumes (
 
                 let left_data_type = left.get_data_type();
+                check_not_nullish(&left_data_type)?;
 
                 if let DataType::FunctionType {
                     arguments,
                     return_type,
                 } = left_data_type
                 {
-                    let mut function_parameters: Vec<Expression> = vec![];
-                    let mut index = 0;
-
-                    while self.get_cur_token()?.clone() != Token::CurveCloseBracket {
-                        let parameter = self.parse_expression(1, context)?;
-                        let argument_index = arguments.get(index);
-
-                        match argument_index {
-                            None => return Err(format!("Function only takes only {} arguments but you are passing more than that", index)),
-
-                            Some(data_type) => {
-                                let parameter_data_type = parameter.get_data_type();
-
-                                if parameter_data_type == *data_type {
-                                    function_parameters.push(parameter);
-
-                                    if let Token::Comma = self.get_cur_token()?.clone() {
-                                        self.next(); // consumes ,
-                                    } else {
-                                        self.assert_cur_token(&Token::CurveCloseBracket)?;
-                                    }
-
-                                } else {
-                                    return Err( format!("The datatype for {} argument is {:?} but got {:?}", index, data_type, parameter_data_type ));
-                                }
-                            }
-                        }
-
-                        index += 1;
-                    }
-                    self.next(); // consumes )
+                    let function_parameters = self.parse_call_arguments(&arguments, context)?;
 
+                    let span = self.get_span_from(&left.get_span());
                     return Ok(Ok(Expression::FunctionCall {
                         fn_exp: Box::new(left),
                         parameters: function_parameters,
                         return_type: return_type.as_ref().clone(),
+                        span,
                     }));
                 } else {
-                    return Err(format!("Can only use function call on expressions with datatype DataType::FunctionType not on datatype {:?}", left_data_type));
+                    return Err(format!("Can only use function call on expressions with datatype function not on datatype {}", left_data_type));
                 }
             }
 
@@ -404,28 +934,372 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         }
     }
 
+    /*
+     * Assumes the current token to be the one after `(` of a call, parses the
+     * parameters and converts them to the data types of the arguments
+     *
+     * Consumes till token `)`
+     *
+     * */
+    pub(crate) fn parse_call_arguments(
+        &mut self,
+        arguments: &[DataType],
+        context: &SymbolContext,
+    ) -> Result<Vec<Expression>, String> {
+        let mut function_parameters: Vec<Expression> = vec![];
+        let mut index = 0;
+
+        while self.get_cur_token()?.clone() != Token::CurveCloseBracket {
+            let parameter = self.parse_expression(1, context)?;
+            let argument_index = arguments.get(index);
+
+            match argument_index {
+                None => {
+                    return Err(format!(
+                        "Function only takes only {} arguments but you are passing more than that",
+                        index
+                    ))
+                }
+
+                Some(data_type) => {
+                    let parameter = convert_to_data_type(parameter, data_type, context);
+                    let parameter_data_type = parameter.get_data_type();
+
+                    if parameter_data_type.has_same_layout(data_type) {
+                        function_parameters.push(parameter);
+
+                        if let Token::Comma = self.get_cur_token()?.clone() {
+                            self.next(); // consumes ,
+                        } else {
+                            self.assert_cur_token(&Token::CurveCloseBracket)?;
+                        }
+                    } else {
+                        return Err(format!(
+                            "The datatype for {} argument is {} but got {}",
+                            index, data_type, parameter_data_type
+                        ));
+                    }
+                }
+            }
+
+            index += 1;
+        }
+
+        // Optional parameters left out of the call are undefined
+        let missing_arguments = &arguments[index.min(arguments.len())..];
+        let are_optional = missing_arguments
+            .iter()
+            .all(|data_type| data_type.accepts_nullish(&DataType::Undefined));
+
+        if are_optional {
+            for data_type in missing_arguments {
+                let parameter = Expression::UndefinedLiteralExp {
+                    span: self.get_cur_span()?,
+                };
+                function_parameters.push(convert_to_data_type(parameter, data_type, context));
+            }
+        }
+        self.next(); // consumes )
+
+        return Ok(function_parameters);
+    }
+
     pub(crate) fn parse_generic_binary_expression(
         &mut self,
         left: Expression,
         context: &SymbolContext,
     ) -> Result<Expression, String> {
         let cur_tok = self.get_cur_token()?.clone();
+        let operator_span = self.get_cur_span()?;
 
         let precedence = self.get_non_prefix_precedence(&cur_tok);
 
         self.next(); // consumes cur_tok which is binary_tok
 
-        let right_exp = Box::new(self.parse_expression(precedence, context)?);
+        // ** is right associative, a ** b ** c is a ** (b ** c)
+        let right_precedence = match cur_tok {
+            Token::StarStar => precedence - 1,
+            _ => precedence,
+        };
+
+        let right_exp = Box::new(self.parse_expression(right_precedence, context)?);
+        let span = left.get_span().to(&right_exp.get_span());
+
+        if let Token::Equality | Token::NotEqual = cur_tok {
+            self.check_loose_equality(&cur_tok, &left, &right_exp, operator_span)?;
+        }
+
+        check_union_operands(&cur_tok, &left.get_data_type(), &right_exp.get_data_type())?;
+        check_operand_types(&cur_tok, &left.get_data_type(), &right_exp.get_data_type())?;
+
         return Ok(Expression::BinaryExp {
             operator: convert_token_to_binary_operator(&cur_tok),
             left: Box::new(left),
             right: right_exp,
+            span,
+        });
+    }
+
+    /*
+     * Comparing with `==` converts an object to a primitive, for arrays that is
+     * joining the elements which is not supported
+     *
+     * */
+    fn check_loose_equality(
+        &mut self,
+        operator_token: &Token,
+        left: &Expression,
+        right: &Expression,
+        operator_span: Span,
+    ) -> Result<(), String> {
+        let (operator, strict_operator) = match operator_token {
+            Token::Equality => ("==", "==="),
+            _ => ("!=", "!=="),
+        };
+
+        // Every member of a union can meet every member of the other side
+        let left_data_types = get_members(left.get_data_type());
+        let right_data_types = get_members(right.get_data_type());
+
+        let pairs = left_data_types.iter().flat_map(|left_data_type| {
+            return right_data_types
+                .iter()
+                .map(move |right_data_type| (left_data_type, right_data_type));
+        });
+
+        for (data_type, other_data_type) in pairs
+            .clone()
+            .chain(pairs.map(|(left_data_type, right_data_type)| (right_data_type, left_data_type)))
+        {
+            let is_other_primitive = matches!(
+                other_data_type,
+                DataType::Float | DataType::String | DataType::Boolean
+            );
+
+            match data_type {
+                DataType::Void => {
+                    return Err(format!(
+                        "Expected both sides of {} to have a value but got {}",
+                        operator, data_type
+                    ));
+                }
+
+                DataType::ArrayType { .. } | DataType::FunctionType { .. }
+                    if is_other_primitive =>
+                {
+                    return Err(format!(
+                        "Cannot compare {} with {} using {}, converting an array or a function to a primitive is not supported",
+                        data_type, other_data_type, operator
+                    ));
+                }
+
+                _ => {}
+            }
+        }
+
+        // `x == null` is the usual way to check for both null and undefined
+        let is_nullish_check = [left, right].iter().any(|exp| {
+            matches!(
+                exp,
+                Expression::NullLiteralExp { .. } | Expression::UndefinedLiteralExp { .. }
+            )
+        });
+
+        if !is_nullish_check {
+            self.warn_in_strict_mode(
+                &format!(
+                    "Use {} instead of {} which converts operands of different data types",
+                    strict_operator, operator
+                ),
+                operator_span,
+            );
+        }
+
+        return Ok(());
+    }
+
+    /*
+     * Only variables, array elements and fields holding numbers can be incremented
+     * or decremented
+     *
+     * */
+    pub(crate) fn create_update_expression(
+        &self,
+        operator_token: &Token,
+        prefix: bool,
+        argument: Expression,
+        span: Span,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let position = if prefix { "prefix" } else { "postfix" };
+
+        match &argument {
+            Expression::IdentExp { name, .. } => {
+                // IdentExp holds the llvm name `<name>|<suffix>|` of the variable
+                let source_name = name.split('|').next().unwrap_or(name);
+
+                if let Some(sym_meta) = context.get(source_name) {
+                    if sym_meta.is_const {
+                        return Err(format!("Cannot reassign a const variable"));
+                    }
+                }
+            }
+
+            Expression::ArrayMemberAccess { .. } | Expression::FieldAccess { .. } => {}
+
+            _ => {
+                return Err(format!(
+                    "Invalid left-hand side expression in {} operation",
+                    position
+                ))
+            }
+        }
+
+        // Element is assigned like by `+=`, so it has to be of the element type
+        let data_type = match &argument {
+            Expression::ArrayMemberAccess { array, .. } => match array.get_data_type() {
+                DataType::ArrayType { base_type } => *base_type,
+                _ => unreachable!(),
+            },
+            argument => argument.get_data_type(),
+        };
+
+        if data_type != DataType::Float {
+            return Err(format!(
+                "Expected operand of {} operation to be of datatype number but got {}",
+                position, data_type
+            ));
+        }
+
+        return Ok(Expression::UpdateExp {
+            operator: convert_token_to_update_operator(operator_token),
+            prefix,
+            argument: Box::new(argument),
+            span,
+        });
+    }
+
+    /*
+     * Both sides have to be of the same datatype, as the result is one of them
+     *
+     * `??` only picks the right side when the left side is null or undefined, so
+     * null and undefined of the left side are not part of the result
+     *
+     * */
+    pub(crate) fn parse_logical_expression(
+        &mut self,
+        left: Expression,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let cur_tok = self.get_cur_token()?.clone();
+
+        let precedence = self.get_non_prefix_precedence(&cur_tok);
+
+        self.next(); // consumes cur_tok which is logical operator
+
+        let label = match cur_tok {
+            Token::LogicalAnd => "&&",
+            Token::LogicalOr => "||",
+            _ => "??",
+        };
+
+        /*
+         * Right side of `&&` is only evaluated when the left side is true and the
+         * right side of `||` when it is false
+         *
+         * */
+        let right = {
+            let (when_true, when_false) = get_narrowings(&left);
+            let narrowings = match cur_tok {
+                Token::LogicalAnd => when_true,
+                Token::LogicalOr => when_false,
+                _ => Narrowings::new(),
+            };
+
+            let mut right_context = context.create_child_context(context.suffix.clone());
+            for (name, data_type) in narrowings {
+                right_context.narrow(&name, data_type);
+            }
+
+            self.parse_expression(precedence, &right_context)?
+        };
+        let (left, right) = match cur_tok {
+            Token::NullishCoalescing => unify_nullish_coalescing(left, right, context)?,
+            _ => unify_branches(left, right, label, context)?,
+        };
+
+        let span = left.get_span().to(&right.get_span());
+        return Ok(Expression::LogicalExp {
+            operator: convert_token_to_logical_operator(&cur_tok),
+            left: Box::new(left),
+            right: Box::new(right),
+            span,
+        });
+    }
+
+    /*
+     * Assumes the current token to be ? in
+     *
+     * <test> ? <consequent> : <alternate>
+     *
+     * Alternate is parsed with a lower precedence so that
+     * a ? b : c ? d : e is parsed as a ? b : (c ? d : e)
+     *
+     * */
+    pub(crate) fn parse_conditional_expression(
+        &mut self,
+        test: Expression,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let precedence = self.get_non_prefix_precedence(&Token::QuestionMark);
+
+        self.next(); // consumes ?
+
+        let (when_true, when_false) = get_narrowings(&test);
+
+        let mut consequent_context = context.create_child_context(context.suffix.clone());
+        for (name, data_type) in when_true {
+            consequent_context.narrow(&name, data_type);
+        }
+        let consequent = self.parse_expression(1, &consequent_context)?;
+
+        self.assert_cur_token(&Token::Colon)?;
+        self.next(); // consumes :
+
+        let mut alternate_context = context.create_child_context(context.suffix.clone());
+        for (name, data_type) in when_false {
+            alternate_context.narrow(&name, data_type);
+        }
+        let alternate = self.parse_expression(precedence - 1, &alternate_context)?;
+
+        if let DataType::Void = test.get_data_type() {
+            return Err(format!(
+                "Expected test of conditional expression to have a value but got {}",
+                DataType::Void
+            ));
+        }
+
+        let (consequent, alternate) =
+            unify_branches(consequent, alternate, "conditional expression", context)?;
+
+        let span = test.get_span().to(&alternate.get_span());
+        return Ok(Expression::ConditionalExp {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+            span,
         });
     }
 
     pub(crate) fn get_prefix_precedence(&self, token: &Token) -> usize {
         match token {
-            Token::Plus | Token::Minus | Token::Bang => return 17,
+            Token::Plus
+            | Token::Minus
+            | Token::Bang
+            | Token::Tilde
+            | Token::Increment
+            | Token::Decrement
+            | Token::Keyword(KeywordKind::Typeof) => return 17,
 
             _ => return 1,
         }
@@ -433,23 +1307,286 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
     pub(crate) fn get_non_prefix_precedence(&self, token: &Token) -> usize {
         match token {
-            Token::BoxOpenBracket | Token::Dot | Token::CurveOpenBracket => 20,
+            Token::BoxOpenBracket
+            | Token::Dot
+            | Token::QuestionDot
+            | Token::CurveOpenBracket
+            | Token::Bang => 20,
 
-            Token::Star | Token::Slash => return 15,
+            // Postfix ++ and --
+            Token::Increment | Token::Decrement => return 18,
+
+            Token::StarStar => return 16,
+            Token::Star | Token::Slash | Token::Percent => return 15,
 
             Token::Plus | Token::Minus => return 14,
 
+            Token::LeftShift | Token::RightShift | Token::UnsignedRightShift => return 13,
+
             Token::LessThan
             | Token::LessThanOrEqual
             | Token::GreaterThan
-            | Token::GreaterThanOrEqual => return 12,
+            | Token::GreaterThanOrEqual
+            | Token::Keyword(KeywordKind::Instanceof) => return 12,
 
-            Token::StrictEquality | Token::StrictNotEqual => return 11,
+            Token::StrictEquality | Token::StrictNotEqual | Token::Equality | Token::NotEqual => {
+                return 11
+            }
             Token::Ampersand => return 10,
             Token::Caret => return 9,
             Token::VerticalBar => return 8,
+            Token::LogicalAnd => return 7,
+            Token::LogicalOr | Token::NullishCoalescing => return 6,
+            Token::QuestionMark => return 4,
 
             _ => return 1,
         }
     }
 }
+
+/*
+ * Either of the expressions can be the result, so both need the same datatype,
+ * an empty array takes the datatype of the other expression
+ *
+ * */
+fn unify_branches(
+    left: Expression,
+    right: Expression,
+    label: &str,
+    context: &SymbolContext,
+) -> Result<(Expression, Expression), String> {
+    let left = convert_to_data_type(left, &right.get_data_type(), context);
+    let right = convert_to_data_type(right, &left.get_data_type(), context);
+
+    let left_data_type = left.get_data_type();
+    let right_data_type = right.get_data_type();
+
+    if left_data_type == DataType::Void {
+        return Err(format!(
+            "Expected both sides of {} to have a value but got {}",
+            label, left_data_type
+        ));
+    }
+
+    if !left_data_type.has_same_layout(&right_data_type) {
+        return Err(format!(
+            "Expected both sides of {} to have same datatype but got {} and {}",
+            label, left_data_type, right_data_type
+        ));
+    }
+
+    return Ok((left, right));
+}
+
+/*
+ * Right side of `??` is converted to the data type of the result, which is the left
+ * side without null and undefined together with the right side
+ *
+ *      number | undefined ?? string        number | string
+ *
+ * Left side which is never null or undefined needs the same datatype as the right
+ * side like the other logical operators
+ *
+ * */
+fn unify_nullish_coalescing(
+    left: Expression,
+    right: Expression,
+    context: &SymbolContext,
+) -> Result<(Expression, Expression), String> {
+    let left_data_type = left.get_data_type();
+
+    let non_nullish = match remove_nullish(&left_data_type) {
+        Some(non_nullish) if non_nullish == left_data_type => {
+            return unify_branches(left, right, "??", context)
+        }
+        non_nullish => non_nullish,
+    };
+
+    let right = match &non_nullish {
+        Some(non_nullish) => convert_to_data_type(right, non_nullish, context),
+        None => right,
+    };
+
+    let mut types = vec![right.get_data_type()];
+    types.extend(non_nullish);
+
+    let data_type = match create_union_type(types) {
+        Ok(data_type) => data_type,
+        Err(message) => {
+            return Err(format!(
+                "Cannot use {} and {} as both sides of ??, {}",
+                left_data_type,
+                right.get_data_type(),
+                message
+            ))
+        }
+    };
+    let right = convert_to_data_type(right, &data_type, context);
+
+    if !right.get_data_type().has_same_layout(&data_type) {
+        return Err(format!(
+            "Expected right side of ?? to be of datatype {} but got {}",
+            data_type,
+            right.get_data_type()
+        ));
+    }
+
+    return Ok((left, right));
+}
+
+/*
+ * Value of a union can only be compared, concatenated to a string or tested for
+ * truthiness, anything else depends on the member it holds so the union has to be
+ * narrowed by a `typeof` check first
+ *
+ * Same goes for null and undefined, which have to be ruled out by a null check
+ *
+ * */
+fn check_union_operand(operator_token: &Token, data_type: &DataType) -> Result<(), String> {
+    check_not_nullish(data_type)?;
+
+    if let DataType::UnionType { types: _ } = data_type {
+        return Err(format!(
+            "Operator {:?} cannot be used on {}, narrow it with typeof first",
+            operator_token, data_type
+        ));
+    }
+
+    return Ok(());
+}
+
+fn check_union_operands(
+    operator_token: &Token,
+    left_data_type: &DataType,
+    right_data_type: &DataType,
+) -> Result<(), String> {
+    if let Token::StrictEquality | Token::StrictNotEqual | Token::Equality | Token::NotEqual =
+        operator_token
+    {
+        return Ok(());
+    }
+
+    for (data_type, other_data_type) in [
+        (left_data_type, right_data_type),
+        (right_data_type, left_data_type),
+    ] {
+        let is_concatenation = operator_token == &Token::Plus
+            && other_data_type == &DataType::String
+            && (is_primitive_union(data_type) || data_type.is_nullish());
+
+        if !is_concatenation {
+            check_union_operand(operator_token, data_type)?;
+        }
+    }
+
+    return Ok(());
+}
+
+fn is_number(data_type: &DataType) -> bool {
+    return matches!(
+        data_type,
+        DataType::Float
+            | DataType::EnumType {
+                is_numeric: true,
+                ..
+            }
+    );
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    return matches!(
+        data_type,
+        DataType::String
+            | DataType::EnumType {
+                is_numeric: false,
+                ..
+            }
+    );
+}
+
+// Unary `+`, `-` and `~` only work on numbers, e.g. `+"5"` is not supported
+fn check_operand_type(operator_token: &Token, data_type: &DataType) -> Result<(), String> {
+    if let Token::Plus | Token::Minus | Token::Tilde = operator_token {
+        if !is_number(data_type) && data_type != &DataType::NA {
+            return Err(format!(
+                "Operator {:?} can only be used on datatype number but used on datatype {}",
+                operator_token, data_type
+            ));
+        }
+    }
+
+    return Ok(());
+}
+
+/*
+ * Data types of the operands which are supported besides unions, null and
+ * undefined, see check_union_operands
+ *
+ *      <number> + <number>, <string> + <primitive>, <primitive> + <string>
+ *      <number> - <number>, same for *, /, %, **, |, ^, &, <<, >> and >>>
+ *      <number> < <number>, <string> < <string>, same for <=, > and >=
+ *      <a> === <b> where either of a and b is assignable to the other
+ *
+ * Member of a numeric enum is a number and of a string enum is a string
+ *
+ * */
+fn check_operand_types(
+    operator_token: &Token,
+    left_data_type: &DataType,
+    right_data_type: &DataType,
+) -> Result<(), String> {
+    // Variable which is not declared yet is reported once its declaration is missing
+    if left_data_type == &DataType::NA || right_data_type == &DataType::NA {
+        return Ok(());
+    }
+
+    let is_union_or_nullish = |data_type: &DataType| {
+        return matches!(data_type, DataType::UnionType { .. }) || data_type.is_nullish();
+    };
+
+    let is_supported = match operator_token {
+        Token::Plus => {
+            (is_string(left_data_type) && can_convert_to_string(right_data_type))
+                || (is_string(right_data_type) && can_convert_to_string(left_data_type))
+                || (is_number(left_data_type) && is_number(right_data_type))
+        }
+
+        Token::Minus
+        | Token::Star
+        | Token::Slash
+        | Token::Percent
+        | Token::StarStar
+        | Token::VerticalBar
+        | Token::Caret
+        | Token::Ampersand
+        | Token::LeftShift
+        | Token::RightShift
+        | Token::UnsignedRightShift => is_number(left_data_type) && is_number(right_data_type),
+
+        Token::LessThan
+        | Token::LessThanOrEqual
+        | Token::GreaterThan
+        | Token::GreaterThanOrEqual => {
+            (is_number(left_data_type) && is_number(right_data_type))
+                || (is_string(left_data_type) && is_string(right_data_type))
+        }
+
+        Token::StrictEquality | Token::StrictNotEqual => {
+            is_union_or_nullish(left_data_type)
+                || is_union_or_nullish(right_data_type)
+                || is_assignable(left_data_type, right_data_type)
+                || is_assignable(right_data_type, left_data_type)
+        }
+
+        _ => true,
+    };
+
+    if !is_supported {
+        return Err(format!(
+            "Operator {:?} cannot be used on datatypes {} and {}",
+            operator_token, left_data_type, right_data_type
+        ));
+    }
+
+    return Ok(());
+}
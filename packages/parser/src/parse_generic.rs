@@ -0,0 +1,424 @@
+use std::collections::{HashMap, HashSet};
+
+use ast::{
+    data_type::{create_union, is_assignable, DataType},
+    declaration::Declaration,
+    expression::Expression,
+    generic::GenericFunction,
+    Ast, AstPtr,
+};
+use lexer::token::{Span, Token};
+
+use crate::{
+    parser::Parser,
+    symbol_table::{SymbolContext, SymbolMetaInsert, TypeSymbol},
+    traits::ImportResolver,
+    utils::{check_union_members, convert_index_map_to_vec, get_members},
+};
+
+// Context in which the type parameters are types of their own, see DataType::TypeParameter
+pub(crate) fn create_generic_context<'a>(
+    type_parameters: &[String],
+    context: &'a SymbolContext<'a>,
+) -> Result<SymbolContext<'a>, String> {
+    let mut generic_context = context.create_child_context(context.suffix.clone());
+
+    for name in type_parameters {
+        let data_type = DataType::TypeParameter { name: name.clone() };
+        generic_context.insert_type(name, TypeSymbol::create(data_type, false, false))?;
+    }
+
+    return Ok(generic_context);
+}
+
+/*
+ * Type arguments of a call to a generic function found by matching the data types
+ * of its parameters against the ones of the arguments, e.g. `T[]` matched against
+ * `number[]` makes T a number
+ *
+ * A type parameter matched more than once takes the data type which the others
+ * are assignable to
+ *
+ * */
+fn infer_type_arguments(
+    name: &str,
+    type_parameters: &[String],
+    parameters: &[DataType],
+    arguments: &[DataType],
+) -> Result<Vec<DataType>, String> {
+    let mut inferred: HashMap<String, DataType> = HashMap::new();
+
+    for (parameter, argument) in parameters.iter().zip(arguments.iter()) {
+        infer_type_argument(parameter, argument, &mut inferred)?;
+    }
+
+    return type_parameters
+        .iter()
+        .map(|type_parameter| {
+            inferred.remove(type_parameter).ok_or_else(|| {
+                format!(
+                    "Cannot infer type argument {} of generic function {}, pass it as {}<...>(...)",
+                    type_parameter, name, name
+                )
+            })
+        })
+        .collect();
+}
+
+fn infer_type_argument(
+    parameter: &DataType,
+    argument: &DataType,
+    inferred: &mut HashMap<String, DataType>,
+) -> Result<(), String> {
+    match (parameter, argument) {
+        // Element type of `[]` is only known from where it is used
+        (_, DataType::Unknown) | (_, DataType::NA) => return Ok(()),
+
+        (DataType::TypeParameter { name }, argument) => {
+            match inferred.get(name) {
+                Some(existing) if is_assignable(argument, existing) => {}
+
+                Some(existing) if !is_assignable(existing, argument) => {
+                    return Err(format!(
+                        "Type argument {} is inferred as both {} and {}",
+                        name, existing, argument
+                    ));
+                }
+
+                _ => {
+                    inferred.insert(name.clone(), argument.clone());
+                }
+            }
+            return Ok(());
+        }
+
+        /*
+         * Members of the argument which are not members of the parameter are left
+         * for its type parameter, e.g. `number | null` matched against `T | null`
+         * makes T a number
+         *
+         * */
+        (DataType::UnionType { types }, argument) => {
+            let (type_parameters, members): (Vec<&DataType>, Vec<&DataType>) = types
+                .iter()
+                .partition(|member| matches!(member, DataType::TypeParameter { .. }));
+
+            if let [type_parameter] = type_parameters.as_slice() {
+                let rest: Vec<DataType> = get_members(argument.clone())
+                    .into_iter()
+                    .filter(|member| !members.contains(&member))
+                    .collect();
+
+                if !rest.is_empty() {
+                    return infer_type_argument(type_parameter, &create_union(rest), inferred);
+                }
+            }
+            return Ok(());
+        }
+
+        (
+            DataType::ArrayType { base_type },
+            DataType::ArrayType {
+                base_type: argument_base_type,
+            },
+        ) => return infer_type_argument(base_type, argument_base_type, inferred),
+
+        (
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            },
+            DataType::FunctionType {
+                return_type: argument_return_type,
+                arguments: argument_arguments,
+            },
+        ) => {
+            for (parameter, argument) in arguments.iter().zip(argument_arguments.iter()) {
+                infer_type_argument(parameter, argument, inferred)?;
+            }
+            return infer_type_argument(return_type, argument_return_type, inferred);
+        }
+
+        (
+            DataType::ObjectType { entries },
+            DataType::ObjectType {
+                entries: argument_entries,
+            },
+        ) => {
+            for (key, entry) in entries {
+                if let Some(argument_entry) = argument_entries.get(key) {
+                    infer_type_argument(entry, argument_entry, inferred)?;
+                }
+            }
+            return Ok(());
+        }
+
+        _ => return Ok(()),
+    }
+}
+
+/*
+ * Generic functions of another file which llvm needs to generate the imported
+ * ones, the imported ones along with every generic function they call
+ *
+ * The generic functions they call have to be declared in the same file, and none
+ * of them can use the variables declared at the top of it since those are private
+ * to its module
+ *
+ * */
+pub(crate) fn collect_generic_functions(
+    imported: Vec<GenericFunction>,
+    external_file_symbols: &HashMap<String, SymbolMetaInsert>,
+    file_name: &str,
+) -> Result<Vec<GenericFunction>, String> {
+    let declared: HashMap<&String, (&String, &GenericFunction)> = external_file_symbols
+        .iter()
+        .filter(|(_, symbol_meta)| symbol_meta.external_data.is_none())
+        .filter_map(|(name, symbol_meta)| {
+            let generic = symbol_meta.generic.as_ref()?;
+            return Some((&generic.llvm_name, (name, generic)));
+        })
+        .collect();
+
+    let mut visited: HashSet<String> = imported
+        .iter()
+        .map(|generic| generic.llvm_name.clone())
+        .collect();
+    let mut generic_functions = imported;
+    let mut index = 0;
+
+    while index < generic_functions.len() {
+        let generic = generic_functions[index].clone();
+        let name = match declared.get(&generic.llvm_name) {
+            Some((name, _)) => name.as_str(),
+            None => generic.llvm_name.as_str(),
+        };
+
+        if let Some(Ast::Declaration(Declaration::FunctionDeclaration { captures, .. })) =
+            generic.function
+        {
+            if !captures.is_empty() {
+                return Err(format!(
+                    "Generic function {} cannot be used outside of {} since it uses the variables declared at the top of it",
+                    name, file_name
+                ));
+            }
+        }
+
+        for llvm_name in generic.generic_functions.iter() {
+            if visited.contains(llvm_name) {
+                continue;
+            }
+
+            match declared.get(llvm_name) {
+                Some((_, called)) => {
+                    visited.insert(llvm_name.clone());
+                    generic_functions.push((*called).clone());
+                }
+
+                None => {
+                    return Err(format!(
+                        "Generic function {} cannot be used outside of {} since it calls a generic function imported from another file",
+                        name, file_name
+                    ))
+                }
+            }
+        }
+
+        index += 1;
+    }
+
+    return Ok(generic_functions);
+}
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Assumes the current token to be `<` after the name in
+     *
+     *      function <name><T, U>(a: T, b: U): <return_type> {
+     *          <block>
+     *      }
+     *
+     * The body is parsed once with the type parameters as types of their own, llvm
+     * generates it for the type arguments of every call. Consumes till `}`
+     *
+     * */
+    pub(crate) fn parse_generic_function_declaration(
+        &mut self,
+        name: &str,
+        start: Span,
+        context: &mut SymbolContext,
+        can_export: bool,
+    ) -> Result<AstPtr, String> {
+        if !context.is_global() {
+            return Err(format!(
+                "Generic function {} has to be declared at the top of the file",
+                name
+            ));
+        }
+
+        let type_parameters = self.parse_type_parameters()?;
+
+        let (arguments, return_type) = {
+            let generic_context = create_generic_context(&type_parameters, context)?;
+            let arguments = self.parse_parameters(&generic_context)?;
+
+            self.assert_cur_token(&Token::Colon)?;
+            self.next(); // consumes :
+
+            let return_type = self.parse_type_declaration(1, &generic_context)?;
+            (arguments, return_type)
+        };
+
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+
+        let data_type = DataType::FunctionType {
+            arguments: convert_index_map_to_vec(&arguments),
+            return_type: Box::new(return_type.clone()),
+        };
+        let llvm_name = self.get_llvm_var_name(name, context, can_export);
+        let generic = GenericFunction::new(llvm_name.clone(), type_parameters.clone());
+
+        context.insert_global_variable(
+            name,
+            SymbolMetaInsert::create_generic(data_type, generic.clone(), can_export, None),
+        )?;
+
+        let body = {
+            let generic_context = create_generic_context(&type_parameters, context)?;
+
+            self.cur_generic = Some(generic);
+            self.parse_function_body(
+                &llvm_name,
+                arguments,
+                Some(return_type),
+                String::from("_"),
+                start,
+                &generic_context,
+            )
+        };
+        let generic = self.cur_generic.take();
+        let (function, _) = body?;
+
+        let mut generic = generic.unwrap();
+        generic.function = Some(function);
+
+        // Other files get the body along with the symbol, see collect_generic_functions
+        context.global_symbols.get_mut(name).unwrap().generic = Some(generic.clone());
+
+        self.skip_semicolon()?;
+        return Ok(Ast::new_generic_function_declaration(
+            generic,
+            self.get_span_from(&start),
+        ));
+    }
+
+    /*
+     * Assumes the current token to be the name of a generic function in
+     *
+     *      <name>(a, b)
+     *      <name><number, string>(a, b)
+     *
+     * Type arguments left out are inferred from the data types of the arguments,
+     * see infer_type_arguments. Consumes till `)`
+     *
+     * */
+    pub(crate) fn parse_generic_call(
+        &mut self,
+        name: &str,
+        context: &SymbolContext,
+    ) -> Result<Expression, String> {
+        let start = self.get_cur_span()?;
+        let generic = context.get_generic_function(name).unwrap().clone();
+        let data_type = context.get(name).unwrap().data_type;
+        self.next(); // consumes ident
+
+        let type_arguments = if self.get_cur_token()? == &Token::LessThan {
+            let type_arguments = self.parse_type_arguments(context)?;
+
+            if type_arguments.len() != generic.type_parameters.len() {
+                return Err(format!(
+                    "Generic function {} takes {} type arguments but got {}",
+                    name,
+                    generic.type_parameters.len(),
+                    type_arguments.len()
+                ));
+            }
+            Some(type_arguments)
+        } else {
+            None
+        };
+
+        if self.get_cur_token()? != &Token::CurveOpenBracket {
+            return Err(format!(
+                "Generic function {} can only be called, it cannot be used as a value",
+                name
+            ));
+        }
+
+        let type_arguments = match type_arguments {
+            Some(type_arguments) => type_arguments,
+            None => {
+                let argument_types = self.lookup_parser().parse_argument_types(context)?;
+                let parameters = match &data_type {
+                    DataType::FunctionType { arguments, .. } => arguments,
+                    _ => unreachable!(),
+                };
+
+                infer_type_arguments(name, &generic.type_parameters, parameters, &argument_types)?
+            }
+        };
+
+        let data_type = data_type.substitute(&generic.get_type_argument_map(&type_arguments));
+        check_union_members(&data_type)?;
+        let (arguments, return_type) = match &data_type {
+            DataType::FunctionType {
+                arguments,
+                return_type,
+            } => (arguments.clone(), return_type.as_ref().clone()),
+            _ => unreachable!(),
+        };
+
+        self.next(); // consumes (
+        let parameters = self.parse_call_arguments(&arguments, context)?;
+
+        if let Some(cur_generic) = &mut self.cur_generic {
+            if !cur_generic.generic_functions.contains(&generic.llvm_name) {
+                cur_generic
+                    .generic_functions
+                    .push(generic.llvm_name.clone());
+            }
+        }
+
+        return Ok(Expression::FunctionCall {
+            fn_exp: Box::new(Expression::GenericInstance {
+                name: generic.llvm_name,
+                type_arguments,
+                data_type,
+                span: start,
+            }),
+            parameters,
+            return_type,
+            span: self.get_span_from(&start),
+        });
+    }
+
+    // Data types of the arguments of a call, assumes the current token to be `(`
+    fn parse_argument_types(&mut self, context: &SymbolContext) -> Result<Vec<DataType>, String> {
+        self.next(); // consumes (
+
+        let mut argument_types: Vec<DataType> = vec![];
+
+        while self.get_cur_token()? != &Token::CurveCloseBracket {
+            argument_types.push(self.parse_expression(1, context)?.get_data_type());
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                self.assert_cur_token(&Token::CurveCloseBracket)?;
+            }
+        }
+
+        return Ok(argument_types);
+    }
+}
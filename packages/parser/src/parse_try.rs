@@ -0,0 +1,160 @@
+use ast::{data_type::get_exception_type, declaration::CatchClause, Ast, AstPtr};
+use lexer::token::{KeywordKind, Token};
+
+use crate::{
+    parser::Parser,
+    symbol_table::{SymbolContext, SymbolMetaInsert},
+    traits::ImportResolver,
+    utils::convert_to_data_type,
+};
+
+impl<'a, R: ImportResolver> Parser<'a, R> {
+    /*
+     * Assumes the current token to be `keyword try` in
+     *
+     *   try {
+     *       <block>
+     *   } catch (<binding>) {
+     *       <block>
+     *   } finally {
+     *       <block>
+     *   }
+     *
+     * Consumes till the last `}`, the binding and either one of catch or finally
+     * can be left out
+     *
+     * */
+    pub(crate) fn parse_try_statement(
+        &mut self,
+        context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Try))?;
+
+        let start = self.get_cur_span()?;
+        self.next(); // consumes try
+
+        let try_block = self.parse_block(context)?;
+
+        let catch_clause = {
+            if self.get_cur_token()? == &Token::Keyword(KeywordKind::Catch) {
+                Some(self.parse_catch_clause(context)?)
+            } else {
+                None
+            }
+        };
+
+        let finally_block = {
+            if self.get_cur_token()? == &Token::Keyword(KeywordKind::Finally) {
+                self.next(); // consumes finally
+                Some(self.parse_block(context)?)
+            } else {
+                None
+            }
+        };
+
+        if catch_clause.is_none() && finally_block.is_none() {
+            return Err(format!(
+                "Expected try block to be followed by catch or finally"
+            ));
+        }
+
+        return Ok(Ast::new_try_statement(
+            try_block,
+            catch_clause,
+            finally_block,
+            self.get_span_from(&start),
+        ));
+    }
+
+    /*
+     * Assumes the current token to be `keyword catch` in
+     *
+     *   catch (<binding>: <type>) {
+     *       <block>
+     *   }
+     *
+     * Like `unknown` in TypeScript the binding can only be annotated with the data
+     * type of every thrown value, see get_exception_type
+     *
+     * */
+    fn parse_catch_clause(&mut self, context: &mut SymbolContext) -> Result<CatchClause, String> {
+        let start = self.get_cur_span()?;
+        self.next(); // consumes catch
+
+        let cur_value = context.counter;
+        let suffix = format!("{}{}", context.suffix, cur_value);
+        context.counter += 1;
+
+        let mut catch_context = context.create_child_context(suffix);
+
+        if self.get_cur_token()? != &Token::CurveOpenBracket {
+            let block = self.parse_block(&mut catch_context)?;
+            return Ok(CatchClause::new(None, block));
+        }
+
+        self.next(); // consumes (
+        let name = self.get_cur_token()?.get_ident_name()?.clone();
+        self.next(); // consumes ident
+
+        let data_type = get_exception_type();
+
+        if self.get_cur_token()? == &Token::Colon {
+            self.next(); // consumes :
+
+            let annotation = self.parse_type_declaration(1, &catch_context)?;
+            if annotation != data_type {
+                return Err(format!(
+                    "Catch binding {} can only be annotated with {} but got {}",
+                    name, data_type, annotation
+                ));
+            }
+        }
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        let sym_meta = SymbolMetaInsert::create(data_type.clone(), false, false, None);
+        catch_context.insert(name.as_str(), sym_meta)?;
+
+        let llvm_var_name = self.get_llvm_var_name(name.as_str(), &catch_context, false);
+        let mut block = self.parse_block(&mut catch_context)?;
+        let llvm_var_name =
+            self.copy_captured_binding(llvm_var_name.as_str(), &data_type, &mut block, start);
+
+        return Ok(CatchClause::new(Some(llvm_var_name), block));
+    }
+
+    /*
+     * Assumes the current token to be `keyword throw` in
+     *
+     *   throw <exp>;
+     *
+     * Consumes till `;`
+     *
+     * */
+    pub(crate) fn parse_throw_statement(
+        &mut self,
+        context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Throw))?;
+
+        let start = self.get_cur_span()?;
+        self.next(); // consumes throw
+
+        let exp = self.parse_expression(1, context)?;
+        let data_type = exp.get_data_type();
+
+        let exception_type = get_exception_type();
+        let exp = convert_to_data_type(exp, &exception_type, context);
+
+        if !exp.get_data_type().has_same_layout(&exception_type) {
+            return Err(format!(
+                "Only numbers, strings, booleans, null, undefined and objects can be thrown but got {}",
+                data_type
+            ));
+        }
+
+        self.skip_semicolon()?;
+        return Ok(Ast::new_throw_statement(exp, self.get_span_from(&start)));
+    }
+}
@@ -1,17 +1,26 @@
 use ast::data_type::DataType;
 use indexmap::IndexMap;
-use lexer::token::Token;
+use lexer::token::{KeywordKind, Token};
 
-use crate::{parser::Parser, traits::ImportResolver, utils::convert_index_map_to_vec};
+use crate::{
+    parser::Parser,
+    symbol_table::{SymbolContext, TypeSymbol},
+    traits::ImportResolver,
+    utils::{check_union_members, convert_index_map_to_vec, create_union_type},
+};
 
 impl<'a, R: ImportResolver> Parser<'a, R> {
-    pub(crate) fn parse_type_declaration(&mut self, precedence: usize) -> Result<DataType, String> {
-        let mut prefix_data_type = self.get_prefix_type()?;
+    pub(crate) fn parse_type_declaration(
+        &mut self,
+        precedence: usize,
+        context: &SymbolContext,
+    ) -> Result<DataType, String> {
+        let mut prefix_data_type = self.get_prefix_type(context)?;
 
-        let next_token = self.get_cur_token()?.clone();
+        let mut next_token = self.get_cur_token()?.clone();
 
         while precedence < self.get_type_non_prefix_precedence(&next_token) {
-            let non_prefix_data_type = self.get_non_prefix_type(prefix_data_type)?;
+            let non_prefix_data_type = self.get_non_prefix_type(prefix_data_type, context)?;
 
             match non_prefix_data_type {
                 Ok(data) => prefix_data_type = data,
@@ -20,12 +29,14 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                     break;
                 }
             }
+
+            next_token = self.get_cur_token()?.clone();
         }
 
         return Ok(prefix_data_type);
     }
 
-    pub(crate) fn get_prefix_type(&mut self) -> Result<DataType, String> {
+    pub(crate) fn get_prefix_type(&mut self, context: &SymbolContext) -> Result<DataType, String> {
         let cur_token = self.get_cur_token()?;
 
         match cur_token {
@@ -39,8 +50,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         DataType::Float
                     } else if name == "void" {
                         DataType::Void
+                    } else if name == "object" {
+                        DataType::Object
+                    } else if let Some(type_symbol) = context.get_type(name) {
+                        if !type_symbol.type_parameters.is_empty() {
+                            let (name, type_symbol) = (name.clone(), type_symbol.clone());
+                            self.next(); // consumes ident
+
+                            return self.parse_generic_type(&name, &type_symbol, context);
+                        }
+
+                        type_symbol.data_type.clone()
                     } else {
-                        return Err(format!("Unknown ident name : {:?}", name));
+                        let message = format!("Unknown ident name : {:?}", name);
+                        let name = name.clone();
+                        return Err(self.dependent_error(&name, message));
                     }
                 };
 
@@ -48,10 +72,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 return Ok(data_type);
             }
 
+            Token::Keyword(KeywordKind::Null) => {
+                self.next(); // consumes null
+                return Ok(DataType::Null);
+            }
+
+            Token::Keyword(KeywordKind::Undefined) => {
+                self.next(); // consumes undefined
+                return Ok(DataType::Undefined);
+            }
+
             Token::CurveOpenBracket => {
                 let is_grouped_expression = {
                     let mut look_up_parser = self.lookup_parser();
                     while look_up_parser.get_cur_token()? != &Token::CurveCloseBracket {
+                        look_up_parser.assert_not_eof()?;
                         look_up_parser.next();
                     }
 
@@ -67,105 +102,345 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 if is_grouped_expression {
                     self.next(); // consumes (
 
-                    let grouped_data_type = self.parse_type_declaration(1)?;
+                    let grouped_data_type = self.parse_type_declaration(1, context)?;
 
                     self.assert_cur_token(&Token::CurveCloseBracket)?;
 
                     self.next(); // consumes )
                     return Ok(grouped_data_type);
                 } else {
-                    self.next(); // consumes (
-
-                    let mut arguments: IndexMap<String, DataType> = IndexMap::new();
-
-                    let mut can_continue = true;
-
-                    while self.get_cur_token()? != &Token::CurveCloseBracket && can_continue {
-                        if let Token::Ident { name: arg_name } = self.get_cur_token()?.clone() {
-                            self.next(); // consumes Ident
-
-                            self.assert_cur_token(&Token::Colon)?;
-                            self.next(); // consumes :
-
-                            let arg_type = self.parse_type_declaration(1)?;
-
-                            if arguments.contains_key(&arg_name) {
-                                return Err(format!("In function declaration each argument must have different names but name : {} is repeated", arg_name));
-                            } else {
-                                arguments.insert(arg_name.to_string(), arg_type);
-                            }
-
-                            if let Token::Comma = self.get_cur_token()? {
-                                self.next(); // consumes ,
-                            } else {
-                                can_continue = false;
-                            }
-                        } else {
-                            return Err(format!(
-                                "Expected tok to be Ident but got {:?}",
-                                self.get_cur_token()?
-                            ));
-                        }
-                    }
-
-                    self.assert_cur_token(&Token::CurveCloseBracket)?;
-                    self.next(); // consumes )
+                    let arguments = self.parse_parameter_types(context)?;
 
                     self.assert_cur_token(&Token::FunctionArrow)?;
                     self.next(); // consumes =>
 
-                    let return_type = Box::new(self.parse_type_declaration(1)?);
+                    let return_type = Box::new(self.parse_type_declaration(1, context)?);
 
                     return Ok(DataType::FunctionType {
-                        arguments: convert_index_map_to_vec(&arguments),
+                        arguments,
                         return_type,
                     });
                 }
             }
 
             Token::AngleOpenBracket => {
-                self.next(); // consumes {
+                let entries = self.parse_object_type_entries(context)?;
+                return Ok(DataType::ObjectType { entries });
+            }
+
+            _ => {
+                return Err(format!(
+                    "Token {:?} does not have associated type prefix function",
+                    cur_token
+                ))
+            }
+        }
+    }
+
+    /*
+     * Assumes the current token to be `<` after the name of a generic type alias
+     *
+     *      Pair<number, string>
+     *
+     * Consumes till `>`
+     *
+     * */
+    fn parse_generic_type(
+        &mut self,
+        name: &str,
+        type_symbol: &TypeSymbol,
+        context: &SymbolContext,
+    ) -> Result<DataType, String> {
+        let type_parameters = &type_symbol.type_parameters;
+
+        if self.get_cur_token()? != &Token::LessThan {
+            return Err(format!(
+                "Generic type {} requires {} type arguments",
+                name,
+                type_parameters.len()
+            ));
+        }
 
-                let mut data_type_entries: IndexMap<String, DataType> = IndexMap::new();
+        let type_arguments = self.parse_type_arguments(context)?;
 
-                while self.get_cur_token()? != &Token::AngleCloseBracket {
-                    if let Token::Ident { name } = self.get_cur_token()?.clone() {
-                        self.next(); // consumes Ident;
+        if type_arguments.len() != type_parameters.len() {
+            return Err(format!(
+                "Generic type {} requires {} type arguments but got {}",
+                name,
+                type_parameters.len(),
+                type_arguments.len()
+            ));
+        }
 
-                        self.assert_cur_token(&Token::Colon)?;
-                        self.next();
+        let type_arguments = type_parameters
+            .iter()
+            .cloned()
+            .zip(type_arguments)
+            .collect();
+        let data_type = type_symbol.data_type.substitute(&type_arguments);
+        check_union_members(&data_type)?;
 
-                        let entry_data_type = self.parse_type_declaration(1)?;
+        return Ok(data_type);
+    }
 
-                        data_type_entries.insert(name, entry_data_type);
+    /*
+     * Assumes the current token to be `<` in
+     *
+     *      <T, U>
+     *
+     * after the name of a generic function or type alias, consumes till `>`
+     *
+     * */
+    pub(crate) fn parse_type_parameters(&mut self) -> Result<Vec<String>, String> {
+        self.assert_cur_token(&Token::LessThan)?;
+        self.next(); // consumes <
 
-                        if self.get_cur_token()? == &Token::Comma {
-                            self.next(); // consume ,
-                        } else {
-                            self.assert_cur_token(&Token::AngleCloseBracket)?;
-                        }
-                    }
-                }
+        let mut type_parameters: Vec<String> = vec![];
 
-                self.next(); // consumes }
+        loop {
+            let name = self.get_cur_token()?.get_ident_name()?.clone();
 
-                return Ok(DataType::ObjectType {
-                    entries: data_type_entries,
-                });
+            if type_parameters.contains(&name) {
+                return Err(format!(
+                    "Type parameter {} is declared more than once",
+                    name
+                ));
             }
+            type_parameters.push(name);
+            self.next(); // consumes ident
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                break;
+            }
+        }
+
+        self.consume_closing_angle()?;
+
+        return Ok(type_parameters);
+    }
+
+    /*
+     * Assumes the current token to be `<` in
+     *
+     *      <number, string[]>
+     *
+     * Consumes till `>`
+     *
+     * */
+    pub(crate) fn parse_type_arguments(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Vec<DataType>, String> {
+        self.assert_cur_token(&Token::LessThan)?;
+        self.next(); // consumes <
+
+        let mut type_arguments: Vec<DataType> = vec![];
+
+        loop {
+            type_arguments.push(self.parse_type_declaration(1, context)?);
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                break;
+            }
+        }
+
+        self.consume_closing_angle()?;
 
+        return Ok(type_arguments);
+    }
+
+    /*
+     * `>` closing type arguments can be the start of a token like `>>` in
+     * `Box<Box<number>>`, then only the `>` is consumed and the rest is the
+     * current token till next is called
+     *
+     * */
+    pub(crate) fn consume_closing_angle(&mut self) -> Result<(), String> {
+        let rest = match self.get_cur_token()? {
+            Token::GreaterThan => None,
+            Token::RightShift => Some(Token::GreaterThan),
+            Token::UnsignedRightShift => Some(Token::RightShift),
+            Token::GreaterThanOrEqual => Some(Token::Assign),
+            Token::RightShiftAssign => Some(Token::GreaterThanOrEqual),
+            Token::UnsignedRightShiftAssign => Some(Token::RightShiftAssign),
+            token => return Err(format!("Expected > but got {:?}", token)),
+        };
+
+        match (rest, self.cur_pos) {
+            (Some(rest), Some(cur_pos)) => self.split_token = Some((cur_pos, rest)),
             _ => {
+                self.next(); // consumes >
+            }
+        }
+
+        return Ok(());
+    }
+
+    /*
+     * Assumes the current token to be `:` or `?` after the name of a property or a
+     * parameter in
+     *
+     *      a: <type>
+     *      a?: <type>
+     *
+     * An optional property or parameter can be left out, so undefined is added to
+     * its data type
+     *
+     * */
+    pub(crate) fn parse_optional_type_annotation(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<DataType, String> {
+        let is_optional = self.get_cur_token()? == &Token::QuestionMark;
+
+        if is_optional {
+            self.next(); // consumes ?
+        }
+
+        self.assert_cur_token(&Token::Colon)?;
+        self.next(); // consumes :
+
+        let data_type = self.parse_type_declaration(1, context)?;
+
+        if is_optional {
+            return create_union_type(vec![data_type, DataType::Undefined]);
+        }
+
+        return Ok(data_type);
+    }
+
+    /*
+     * Assumes the current token to be `(` in
+     *
+     *      (a: <type>, b?: <type>)
+     *
+     * Consumes till `)`
+     *
+     * */
+    pub(crate) fn parse_parameter_types(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<Vec<DataType>, String> {
+        let arguments = self.parse_parameters(context)?;
+
+        return Ok(convert_index_map_to_vec(&arguments));
+    }
+
+    /*
+     * Same as parse_parameter_types but keeps the names of the parameters, used by
+     * function declarations and closures
+     *
+     * */
+    pub(crate) fn parse_parameters(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<IndexMap<String, DataType>, String> {
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let mut arguments: IndexMap<String, DataType> = IndexMap::new();
+
+        while self.get_cur_token()? != &Token::CurveCloseBracket {
+            if let Token::Ident { name: arg_name } = self.get_cur_token()?.clone() {
+                self.next(); // consumes Ident
+
+                let arg_type = self.parse_optional_type_annotation(context)?;
+
+                if arguments.contains_key(&arg_name) {
+                    return Err(format!("In function declaration each argument must have different names but name : {} is repeated", arg_name));
+                } else {
+                    arguments.insert(arg_name.to_string(), arg_type);
+                }
+
+                if let Token::Comma = self.get_cur_token()? {
+                    self.next(); // consumes ,
+                } else {
+                    self.assert_cur_token(&Token::CurveCloseBracket)?;
+                }
+            } else {
                 return Err(format!(
-                    "Token {:?} does not have associated type prefix function",
-                    cur_token
-                ))
+                    "Expected tok to be Ident but got {:?}",
+                    self.get_cur_token()?
+                ));
+            }
+        }
+
+        self.next(); // consumes )
+
+        return Ok(arguments);
+    }
+
+    /*
+     * Assumes the current token to be `{` in the object type or the body of an
+     * interface
+     *
+     *      {
+     *          a: <type>,
+     *          b?: <type>;
+     *          c(d: <type>): <type>
+     *      }
+     *
+     * Properties are separated by `,` or `;`, a method is a property holding a
+     * function. Consumes till `}`
+     *
+     * */
+    pub(crate) fn parse_object_type_entries(
+        &mut self,
+        context: &SymbolContext,
+    ) -> Result<IndexMap<String, DataType>, String> {
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+        self.next(); // consumes {
+
+        let mut entries: IndexMap<String, DataType> = IndexMap::new();
+
+        while self.get_cur_token()? != &Token::AngleCloseBracket {
+            let name = match self.get_cur_token()? {
+                Token::Ident { name } => name.clone(),
+                tok => return Err(format!("Expected tok to be Ident but got {:?}", tok)),
+            };
+            self.next(); // consumes Ident
+
+            let entry_data_type = if self.get_cur_token()? == &Token::CurveOpenBracket {
+                let arguments = self.parse_parameter_types(context)?;
+
+                self.assert_cur_token(&Token::Colon)?;
+                self.next(); // consumes :
+
+                let return_type = Box::new(self.parse_type_declaration(1, context)?);
+                DataType::FunctionType {
+                    arguments,
+                    return_type,
+                }
+            } else {
+                self.parse_optional_type_annotation(context)?
+            };
+
+            if entries.contains_key(&name) {
+                return Err(format!("Duplicate property {} in object type", name));
+            }
+            entries.insert(name, entry_data_type);
+
+            match self.get_cur_token()? {
+                Token::Comma | Token::SemiColon => {
+                    self.next(); // consumes , or ;
+                }
+                _ => self.assert_cur_token(&Token::AngleCloseBracket)?,
             }
         }
+
+        self.next(); // consumes }
+
+        return Ok(entries);
     }
 
     pub(crate) fn get_non_prefix_type(
         &mut self,
         left: DataType,
+        context: &SymbolContext,
     ) -> Result<Result<DataType, DataType>, String> {
         // There is no non_prefix_type when left is Datatype::Void
         if left == DataType::Void {
@@ -185,6 +460,16 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 }));
             }
 
+            // `number | string[]` is a union of number and string[]
+            Token::VerticalBar => {
+                self.next(); // consumes |
+
+                let precedence = self.get_type_non_prefix_precedence(&Token::VerticalBar);
+                let right = self.parse_type_declaration(precedence, context)?;
+
+                return Ok(Ok(create_union_type(vec![left, right])?));
+            }
+
             _ => return Ok(Err(left)),
         }
     }
@@ -198,6 +483,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
     pub(crate) fn get_type_non_prefix_precedence(&self, token: &Token) -> usize {
         match token {
             Token::BoxOpenBracket => 5,
+            Token::VerticalBar => 2,
             _ => 1,
         }
     }
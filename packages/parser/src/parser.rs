@@ -1,36 +1,67 @@
-use core::panic;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+};
 
 use ast::{
+    class::Class,
     data_type::DataType,
     declaration::{
-        BlockWithCondition, Declaration, VariableAssignmentOperator, VariableDeclarationKind,
+        BlockWithCondition, SwitchCase, VariableAssignmentOperator, VariableDeclarationKind,
     },
-    leak_ast, Ast, AstPtr,
+    enumeration::Enum,
+    expression::Expression,
+    generic::GenericFunction,
+    Ast, AstPtr,
 };
 use indexmap::IndexMap;
-use lexer::token::{KeywordKind, LiteralKind, Token};
+use lexer::{
+    diagnostic::Diagnostic,
+    token::{KeywordKind, LiteralKind, Span, SpannedToken, Token},
+};
 
 use crate::{
-    symbol_table::{ExternalVariableData, FunctionSymbol, SymbolContext, SymbolMetaInsert},
+    narrowing::Narrowings,
+    parse_closure::{mark_captured_variables, FunctionScope},
+    parse_generic::{collect_generic_functions, create_generic_context},
+    symbol_table::{
+        merge_object_types, ExternalVariableData, SymbolContext, SymbolMetaInsert, TypeSymbol,
+    },
     traits::ImportResolver,
-    utils::convert_index_map_to_vec,
+    utils::{convert_index_map_to_vec, convert_to_data_type, convert_token_to_assignment_operator},
 };
 
 pub struct Parser<'a, R: ImportResolver> {
-    pub(crate) content: &'a Vec<Token>,
+    pub(crate) content: &'a Vec<SpannedToken>,
     pub(crate) cur_pos: Option<usize>,
     resolver: &'a mut R,
     cur_file_path: Option<PathBuf>, // Absolute path of file which we are parsing
     compiled_ast: Vec<AstPtr>,
-
-    ast_id_counter : usize,
-    ast_registar : HashMap<usize, AstPtr>
+    pub(crate) diagnostics: Vec<Diagnostic>,
+
+    // Functions being parsed, outermost first, see parse_function_body
+    pub(crate) function_scopes: Vec<FunctionScope>,
+    // Llvm names of the variables used by closures, see mark_captured_variables
+    pub(crate) captured_variables: HashSet<String>,
+    pub(crate) closure_counter: usize,
+    // Llvm name of the class whose body is being parsed, see check_member_access
+    pub(crate) cur_class: Option<String>,
+    // Generic function whose body is being parsed, see parse_generic_function_declaration
+    pub(crate) cur_generic: Option<GenericFunction>,
+    // Rest of a token like `>>` whose first `>` closed type arguments, see consume_closing_angle
+    pub(crate) split_token: Option<(usize, Token)>,
+    // Names declared by the statements which failed, see next_ast
+    pub(crate) unbound_names: HashSet<String>,
+    // Error of the statement being parsed is caused by an earlier one, see unknown_variable
+    is_dependent_error: bool,
+
+    ast_id_counter: usize,
+    ast_registar: HashMap<usize, AstPtr>,
 }
 
 impl<'a, R: ImportResolver> Parser<'a, R> {
     pub fn new(
-        content: &'a Vec<Token>,
+        content: &'a Vec<SpannedToken>,
         resolver: &'a mut R,
         cur_file_name: Option<&str>,
     ) -> Parser<'a, R> {
@@ -56,8 +87,17 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             resolver,
             cur_file_path,
             compiled_ast: Vec::new(),
-            ast_id_counter : 0,
-            ast_registar : HashMap::new(),
+            diagnostics: Vec::new(),
+            function_scopes: Vec::new(),
+            captured_variables: HashSet::new(),
+            closure_counter: 0,
+            cur_class: None,
+            cur_generic: None,
+            split_token: None,
+            unbound_names: HashSet::new(),
+            is_dependent_error: false,
+            ast_id_counter: 0,
+            ast_registar: HashMap::new(),
         };
 
         parser.next();
@@ -65,19 +105,266 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         return parser;
     }
 
-    pub fn compile(&mut self, global_context: &mut SymbolContext) {
-        while *self.get_cur_token().unwrap() != Token::Eof {
-            let next_ast = self.next_ast(global_context);
-            self.compiled_ast.push(next_ast);
+    /*
+     * Parses the whole file, an error in a statement does not stop the parser
+     * instead it is recorded and parsing continues from the next statement
+     *
+     * Returns all the recorded diagnostics if there was any error, otherwise the
+     * warnings are handed over to the resolver
+     *
+     * */
+    pub fn compile(&mut self, global_context: &mut SymbolContext) -> Result<(), Vec<Diagnostic>> {
+        while self.get_cur_token() != Ok(&Token::Eof) {
+            if let Some(next_ast) = self.next_ast(global_context) {
+                self.compiled_ast.push(next_ast);
+            }
+        }
+
+        mark_captured_variables(&mut self.compiled_ast, &self.captured_variables);
+
+        if self
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.is_error())
+        {
+            return Err(self.diagnostics.clone());
         }
+
+        if !self.diagnostics.is_empty() {
+            let cur_file_name = self
+                .cur_file_path
+                .as_ref()
+                .map(|cur_file_path| cur_file_path.to_str().unwrap().to_string());
+            let warnings = std::mem::take(&mut self.diagnostics);
+
+            self.resolver
+                .report_warnings(cur_file_name.as_deref(), warnings);
+        }
+
+        return Ok(());
     }
 
     pub fn get_compiled_ast(self) -> Vec<AstPtr> {
         return self.compiled_ast;
     }
 
-    pub(crate) fn next_ast(&mut self, global_context: &mut SymbolContext) -> AstPtr {
-        return self.next_ast_in_context(global_context).unwrap();
+    /*
+     * Parses a single statement, on error the diagnostic is recorded and the
+     * parser is synchronized to the start of next statement
+     *
+     * Names the failed statement declares are left unbound, an error caused only
+     * by using one of them later is not recorded since the failed statement
+     * already was
+     *
+     * */
+    pub(crate) fn next_ast(&mut self, context: &mut SymbolContext) -> Option<AstPtr> {
+        let start_pos = self.cur_pos;
+        let declared_names = self.get_declared_names();
+        self.is_dependent_error = false;
+
+        match self.next_ast_in_context(context) {
+            Ok(ast) => return Some(ast),
+            Err(err) => {
+                if !self.is_dependent_error {
+                    let diagnostic = self.to_diagnostic(err);
+                    self.diagnostics.push(diagnostic);
+                }
+
+                self.unbound_names.extend(declared_names);
+                self.synchronize(start_pos);
+                return None;
+            }
+        }
+    }
+
+    /*
+     * Names declared by the statement starting at the current token
+     *
+     *      import { <name>, <name> } from <file>;
+     *      const <name> = ...;
+     *      function <name>(...) ...
+     *      class <name> ...
+     *      enum <name> ...
+     *      type <name> = ...;
+     *      interface <name> ...
+     *
+     * */
+    fn get_declared_names(&mut self) -> Vec<String> {
+        let mut lookup_parser = self.lookup_parser();
+        if lookup_parser.get_cur_token() == Ok(&Token::Keyword(KeywordKind::Export)) {
+            lookup_parser.next(); // consumes export
+        }
+
+        match lookup_parser.get_cur_token() {
+            Ok(Token::Keyword(KeywordKind::Import)) => {
+                let mut names: Vec<String> = vec![];
+
+                loop {
+                    match lookup_parser.next() {
+                        Token::Ident { name } => names.push(name.clone()),
+                        Token::AngleCloseBracket | Token::Eof => return names,
+                        _ => {}
+                    }
+                }
+            }
+
+            Ok(Token::Keyword(
+                KeywordKind::Const
+                | KeywordKind::Let
+                | KeywordKind::Function
+                | KeywordKind::Class
+                | KeywordKind::Enum,
+            )) => {
+                let mut tok = lookup_parser.next(); // consumes keyword
+                if tok == &Token::Keyword(KeywordKind::Enum) {
+                    tok = lookup_parser.next(); // consumes enum of const enum
+                }
+
+                match tok {
+                    Token::Ident { name } => return vec![name.clone()],
+                    _ => return vec![],
+                }
+            }
+
+            Ok(Token::Ident { name }) if name == "type" || name == "interface" => {
+                match lookup_parser.next() {
+                    Token::Ident { name } => return vec![name.clone()],
+                    _ => return vec![],
+                }
+            }
+
+            _ => return vec![],
+        }
+    }
+
+    // Error for a variable which is not declared, see next_ast
+    pub(crate) fn unknown_variable(&mut self, name: &str) -> String {
+        return self.dependent_error(name, format!("Unknown variable {}", name));
+    }
+
+    /*
+     * Error caused by using the variable or type `name`, it is not recorded when
+     * the declaration of `name` failed as that is already, see next_ast
+     *
+     * */
+    pub(crate) fn dependent_error(&mut self, name: &str, message: String) -> String {
+        if self.unbound_names.contains(name) {
+            self.is_dependent_error = true;
+        }
+
+        return message;
+    }
+
+    /*
+     * Skips tokens till the end of statement in which the error occured that is
+     * till one of
+     *
+     * - `;` which is consumed
+     * - `}` which closes the block containing the statement, it is not consumed
+     * - keyword which starts a new declaration or statement
+     * - Eof
+     *
+     * Blocks opened after the error are skipped as a whole. At least one token is
+     * consumed if the statement failed without consuming anything so that the
+     * parser does not get stuck on the same token
+     *
+     * */
+    pub(crate) fn synchronize(&mut self, start_pos: Option<usize>) {
+        if self.cur_pos == start_pos && self.get_cur_token() != Ok(&Token::Eof) {
+            self.next();
+        }
+
+        let mut depth = 0;
+
+        loop {
+            let cur_tok = match self.get_cur_token() {
+                Ok(tok) => tok,
+                Err(_) => return,
+            };
+
+            match cur_tok {
+                Token::Eof => return,
+
+                Token::SemiColon if depth == 0 => {
+                    self.next(); // consumes ;
+                    return;
+                }
+
+                Token::AngleOpenBracket => depth += 1,
+
+                Token::AngleCloseBracket => {
+                    if depth == 0 {
+                        return;
+                    }
+
+                    depth -= 1;
+
+                    if depth == 0 {
+                        self.next(); // consumes }
+                        return;
+                    }
+                }
+
+                Token::Keyword(keyword_kind) if depth == 0 => match keyword_kind {
+                    KeywordKind::Const
+                    | KeywordKind::Let
+                    | KeywordKind::If
+                    | KeywordKind::While
+                    | KeywordKind::Do
+                    | KeywordKind::For
+                    | KeywordKind::Switch
+                    | KeywordKind::Try
+                    | KeywordKind::Throw
+                    | KeywordKind::Break
+                    | KeywordKind::Continue
+                    | KeywordKind::Function
+                    | KeywordKind::Class
+                    | KeywordKind::Enum
+                    | KeywordKind::Return
+                    | KeywordKind::Import
+                    | KeywordKind::Export => return,
+
+                    _ => {}
+                },
+
+                _ => {}
+            }
+
+            self.next();
+        }
+    }
+
+    /*
+     * Errors inside the parser are plain String messages, they are turned into
+     * Diagnostic pointing at the token where the parser gave up
+     *
+     * */
+    pub(crate) fn to_diagnostic(&self, message: String) -> Diagnostic {
+        let diagnostic = Diagnostic::error(&message);
+
+        match self.get_cur_span() {
+            Ok(span) => return diagnostic.with_primary(span, ""),
+            Err(_) => return diagnostic,
+        }
+    }
+
+    /*
+     * Records a warning, a statement can be parsed more than once so the same
+     * warning is recorded only once
+     *
+     * */
+    pub(crate) fn warn(&mut self, message: &str, span: Span) {
+        let diagnostic = Diagnostic::warning(message).with_primary(span, "");
+        if !self.diagnostics.contains(&diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    // Records a lint which is only reported in strict mode
+    pub(crate) fn warn_in_strict_mode(&mut self, message: &str, span: Span) {
+        if self.resolver.is_strict_mode() {
+            self.warn(message, span);
+        }
     }
 
     pub(crate) fn next_ast_in_context(
@@ -89,6 +376,9 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         match first_token {
             Token::Keyword(keyword_kind) => match keyword_kind {
                 KeywordKind::Const | KeywordKind::Let => {
+                    if self.is_const_enum()? {
+                        return self.parse_enum_declaration(context, false);
+                    }
                     return self.parse_variable_declaration(context, false);
                 }
 
@@ -98,11 +388,14 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                 KeywordKind::Export => {
                     self.next(); // consumes export
-                    let cur_tok = self.get_cur_token()?;
+                    let cur_tok = self.get_cur_token()?.clone();
 
-                    if let Token::Keyword(keyword_kind) = cur_tok {
+                    if let Token::Keyword(keyword_kind) = &cur_tok {
                         match keyword_kind {
                             KeywordKind::Const | KeywordKind::Let => {
+                                if self.is_const_enum()? {
+                                    return self.parse_enum_declaration(context, true);
+                                }
                                 return self.parse_variable_declaration(context, true);
                             }
 
@@ -110,11 +403,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                                 return self.parse_function_declaration(context, true);
                             }
 
-                            _ => return Err(format!("Expected tok next to export to be `const` or `let` or `function` but instead got {:?}", cur_tok))
+                            KeywordKind::Class => {
+                                return self.parse_class_declaration(context, true);
+                            }
+
+                            KeywordKind::Enum => {
+                                return self.parse_enum_declaration(context, true);
+                            }
+
+                            _ => return Err(format!("Expected tok next to export to be `const` or `let` or `function` or `class` or `enum` or `type` or `interface` but instead got {:?}", cur_tok))
 
                         }
+                    } else if self.is_type_declaration()? {
+                        return self.parse_type_declaration_statement(context, true);
                     } else {
-                        return Err(format!("Expected tok next to export to be `const` or `let` or `function` but instead got {:?}", cur_tok));
+                        return Err(format!("Expected tok next to export to be `const` or `let` or `function` or `class` or `enum` or `type` or `interface` but instead got {:?}", cur_tok));
                     }
                 }
 
@@ -124,36 +427,56 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                 }
 
                 KeywordKind::While => {
-                    let ast = self.parse_while_loop(context)?;
+                    let ast = self.parse_while_loop(None, context)?;
                     return Ok(ast);
                 }
 
                 KeywordKind::Do => {
-                    let ast = self.parse_do_while_loop(context)?;
+                    let ast = self.parse_do_while_loop(None, context)?;
                     return Ok(ast);
                 }
 
-                KeywordKind::Break => {
-                    self.next(); // consumes break
-                    self.skip_semicolon()?;
-                    return Ok(leak_ast(Ast::Declaration(Declaration::LoopControlFlow {
-                        keyword: KeywordKind::Break,
-                    })));
+                KeywordKind::For => {
+                    let ast = self.parse_for_loop(None, context)?;
+                    return Ok(ast);
                 }
 
-                KeywordKind::Continue => {
-                    self.next(); // consumes continue
-                    self.skip_semicolon()?;
-                    return Ok(leak_ast(Ast::Declaration(Declaration::LoopControlFlow {
-                        keyword: KeywordKind::Continue,
-                    })));
+                KeywordKind::Switch => {
+                    let ast = self.parse_switch(context)?;
+                    return Ok(ast);
+                }
+
+                KeywordKind::Try => {
+                    let ast = self.parse_try_statement(context)?;
+                    return Ok(ast);
+                }
+
+                KeywordKind::Throw => {
+                    return self.parse_throw_statement(context);
+                }
+
+                KeywordKind::Break | KeywordKind::Continue => {
+                    return self.parse_loop_control_flow(context);
                 }
 
                 KeywordKind::Function => {
                     return self.parse_function_declaration(context, false);
                 }
 
+                KeywordKind::Class => {
+                    return self.parse_class_declaration(context, false);
+                }
+
+                KeywordKind::Enum => {
+                    return self.parse_enum_declaration(context, false);
+                }
+
+                KeywordKind::This | KeywordKind::Super | KeywordKind::New => {
+                    return self.parse_naked_expression(context);
+                }
+
                 KeywordKind::Return => {
+                    let start = self.get_cur_span()?;
                     self.next(); // consumes return
 
                     let return_exp = {
@@ -172,32 +495,13 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         }
                     };
 
-                    let expected_data_type = {
-                        let return_type = context.get_return_type();
-                        match return_type {
-                            None => {
-                                return Err(format!(
-                                    "Cannot use return keyword outside of function declaration"
-                                ))
-                            }
-                            Some(d) => d,
-                        }
-                    };
-
-                    let actual_data_type = {
-                        if let Some(exp) = &return_exp {
-                            exp.get_data_type()
-                        } else {
-                            DataType::Void
-                        }
-                    };
-
-                    if expected_data_type != &actual_data_type {
-                        return Err(format!("Expected the return_type declared in function declaration does not match the data_type of expression which the function is returning"));
-                    }
+                    let return_exp = self.check_return_expression(return_exp, context)?;
 
                     self.skip_semicolon()?;
-                    return Ok(Ast::new_return_statement(return_exp));
+                    return Ok(Ast::new_return_statement(
+                        return_exp,
+                        self.get_span_from(&start),
+                    ));
                 }
 
                 _ => {
@@ -209,10 +513,31 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             },
 
             Token::Ident { name } => {
-                if let Some(_) = context.get(name) {
+                let name = name.clone();
+
+                let mut lookup_parser = self.lookup_parser();
+                lookup_parser.next(); // consumes ident
+
+                if lookup_parser.get_cur_token()? == &Token::Colon {
+                    return self.parse_labeled_statement(context);
+                } else if self.is_type_declaration()? {
+                    return self.parse_type_declaration_statement(context, false);
+                } else if let Some(_) = context.get(&name) {
                     return self.parse_naked_ident(context);
+                } else if name == "console" {
+                    return self.parse_naked_expression(context);
+                } else if let Some(TypeSymbol { class: Some(_), .. }) = context.get_type(&name) {
+                    // Static method of the class
+                    return self.parse_naked_expression(context);
+                } else if let Some(TypeSymbol {
+                    enumeration: Some(_),
+                    ..
+                }) = context.get_type(&name)
+                {
+                    // Member of the enum
+                    return self.parse_naked_expression(context);
                 } else {
-                    return Err(format!("Unknown variable {}", name));
+                    return Err(self.unknown_variable(&name));
                 }
             }
 
@@ -241,10 +566,16 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
         match cur_tok {
             Token::Keyword(KeywordKind::If) => {
+                let start = self.get_cur_span()?;
                 self.next(); // consumes if
 
-                self.assert_cur_token(&Token::CurveOpenBracket)?;
-                let if_block = self.parse_block_with_condition(context)?;
+                /*
+                 * Every branch runs only when the conditions before it were false, so
+                 * the narrowings of those are carried to the later branches
+                 *
+                 * */
+                let (if_block, mut narrowings) =
+                    self.parse_narrowed_block_with_condition(&Narrowings::new(), context)?;
                 let mut else_if_block: Vec<BlockWithCondition> = vec![];
                 let mut else_block: Option<Vec<AstPtr>> = None;
 
@@ -260,15 +591,22 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                             match cur_tok {
                                 Token::Keyword(KeywordKind::If) => {
                                     self.next(); // consumes keyword if
-                                    let single_else_if_block = self.parse_block_with_condition(context)?;
+                                    let (single_else_if_block, else_narrowings) =
+                                        self.parse_narrowed_block_with_condition(&narrowings, context)?;
                                     else_if_block.push(single_else_if_block);
+                                    narrowings = else_narrowings;
                                     continue;
                                 },
 
                                 Token::AngleOpenBracket => {
-                                    let ast_block = self.parse_block(context)?;
+                                    let ast_block = self.parse_narrowed_block(&narrowings, context)?;
                                     else_block = Some(ast_block);
-                                    return Ok(Ast::new_if_block(if_block, else_if_block, else_block));
+                                    return Ok(Ast::new_if_block(
+                                        if_block,
+                                        else_if_block,
+                                        else_block,
+                                        self.get_span_from(&start),
+                                    ));
 
                                 },
 
@@ -276,14 +614,24 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                             }
                         }
 
-                        _ => return Ok(Ast::new_if_block(if_block, else_if_block, else_block)),
+                        _ => {
+                            return Ok(Ast::new_if_block(
+                                if_block,
+                                else_if_block,
+                                else_block,
+                                self.get_span_from(&start),
+                            ))
+                        }
                     }
                 }
             }
 
-            _ => panic!(
-                "Expected parser_if_block to be called only when the cur_token is of Keyword if"
-            ),
+            tok => {
+                return Err(format!(
+                    "Expected parser_if_block to be called only when the cur_token is of Keyword if but got {:?}",
+                    tok
+                ))
+            }
         }
     }
 
@@ -305,39 +653,551 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
      * */
     pub(crate) fn parse_while_loop(
         &mut self,
+        label: Option<String>,
         context: &mut SymbolContext,
     ) -> Result<AstPtr, String> {
         self.assert_cur_token(&Token::Keyword(KeywordKind::While))?;
 
+        let start = self.get_cur_span()?;
         self.next(); // consumes while
 
-        let block_with_condition = self.parse_block_with_condition(context)?;
-        return Ok(Ast::new_while_loop(block_with_condition));
-    }
+        context.enter_loop(label)?;
+        let block_with_condition = self.parse_block_with_condition(context);
+        context.exit_loop();
+
+        let block_with_condition = block_with_condition?;
+        return Ok(Ast::new_while_loop(
+            block_with_condition,
+            self.get_span_from(&start),
+        ));
+    }
+
+    pub(crate) fn parse_do_while_loop(
+        &mut self,
+        label: Option<String>,
+        context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Do))?;
+
+        let start = self.get_cur_span()?;
+        self.next(); // consumes do
+
+        context.enter_loop(label)?;
+        let block = self.parse_block(context);
+        context.exit_loop();
+
+        let block = block?;
+
+        self.assert_cur_token(&Token::Keyword(KeywordKind::While))?;
+        self.next(); // consumes while
+
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let condition = self.parse_expression(1, context)?;
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        let block_with_condition = BlockWithCondition::new(condition, block);
+        return Ok(Ast::new_do_while_loop(
+            block_with_condition,
+            self.get_span_from(&start),
+        ));
+    }
+
+    /*
+     * Assumes the current token to be `keyword for` in any one of
+     *
+     * for (<initializer>; <condition>; <update>) {
+     *     <block>
+     * }
+     *
+     * for (const <ident> of <iterable>) {
+     *     <block>
+     * }
+     *
+     * for (const <ident> in <object>) {
+     *     <block>
+     * }
+     *
+     * Consumes till token `}`
+     *
+     * Variables declared inside the brackets are visible only inside the loop, so
+     * they are inserted into a child context which is also the parent of the block
+     *
+     * */
+    pub(crate) fn parse_for_loop(
+        &mut self,
+        label: Option<String>,
+        context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::For))?;
+
+        let start = self.get_cur_span()?;
+        self.next(); // consumes for
+
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let cur_value = context.counter;
+        let suffix = format!("{}{}", context.suffix, cur_value);
+        context.counter += 1;
+
+        let mut loop_context = context.create_child_context(suffix);
+        loop_context.enter_loop(label)?;
+
+        if let Token::Keyword(KeywordKind::Const | KeywordKind::Let) = self.get_cur_token()? {
+            let mut lookup_parser = self.lookup_parser();
+            lookup_parser.next(); // consumes const
+            lookup_parser.next(); // consumes ident
+
+            match lookup_parser.get_cur_token()? {
+                Token::Keyword(KeywordKind::Of) | Token::Keyword(KeywordKind::In) => {
+                    return self.parse_for_of_or_in_loop(start, &mut loop_context);
+                }
+
+                _ => {}
+            }
+        }
+
+        let mut initializer = match self.get_cur_token()? {
+            Token::SemiColon => {
+                self.next(); // consumes ;
+                None
+            }
+
+            Token::Keyword(KeywordKind::Const | KeywordKind::Let) => {
+                let ast = self.parse_variable_declaration(&mut loop_context, false)?;
+                self.assert_prev_token(&Token::SemiColon)?;
+                Some(ast)
+            }
+
+            Token::Keyword(keyword_kind) => {
+                return Err(format!(
+                    "Expected initializer of for loop to be a variable declaration, an assignment or an expression but got keyword {:?}",
+                    keyword_kind
+                ))
+            }
+
+            _ => {
+                let ast = self.next_ast_in_context(&mut loop_context)?;
+                self.assert_prev_token(&Token::SemiColon)?;
+                Some(ast)
+            }
+        };
+
+        let condition = match self.get_cur_token()? {
+            Token::SemiColon => None,
+            _ => {
+                let condition = self.parse_expression(1, &mut loop_context)?;
+
+                if condition.get_data_type() != DataType::Boolean {
+                    return Err(format!(
+                        "Expected condition of for loop to be of data type boolean but got {}",
+                        condition.get_data_type()
+                    ));
+                }
+
+                Some(condition)
+            }
+        };
+
+        self.assert_cur_token(&Token::SemiColon)?;
+        self.next(); // consumes ;
+
+        let update = match self.get_cur_token()? {
+            Token::CurveCloseBracket => None,
+
+            Token::Keyword(keyword_kind) => {
+                return Err(format!(
+                    "Expected update of for loop to be an assignment or an expression but got keyword {:?}",
+                    keyword_kind
+                ))
+            }
+
+            _ => Some(self.next_ast_in_context(&mut loop_context)?),
+        };
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        let block = self.parse_block(&mut loop_context)?;
+
+        /*
+         * Variable declared by the initializer lives in one cell for the whole loop,
+         * unlike js there is no copy of it for every iteration
+         *
+         * */
+        if let Some(initializer) = &mut initializer {
+            mark_captured_variables(std::slice::from_mut(initializer), &self.captured_variables);
+        }
+
+        return Ok(Ast::new_for_loop(
+            initializer,
+            condition,
+            update,
+            block,
+            self.get_span_from(&start),
+        ));
+    }
+
+    /*
+     * Assumes the current token to be `keyword const` or `keyword let` in
+     *
+     * for (const <ident> of <iterable>) {
+     *     <block>
+     * }
+     *
+     * where iterable is an array or a string, or in
+     *
+     * for (const <ident> in <object>) {
+     *     <block>
+     * }
+     *
+     * Consumes till token `}`
+     *
+     * Pass the child context created for the loop
+     *
+     * */
+    fn parse_for_of_or_in_loop(
+        &mut self,
+        start: Span,
+        loop_context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
+        let (is_const, kind) = match self.get_cur_token()? {
+            Token::Keyword(KeywordKind::Const) => (true, VariableDeclarationKind::Const),
+            Token::Keyword(KeywordKind::Let) => (false, VariableDeclarationKind::Let),
+            tok => {
+                return Err(format!(
+                    "Expected token to be keyword const or keyword let but got {:?}",
+                    tok
+                ))
+            }
+        };
+
+        let name = self.next().get_ident_name()?.clone(); // consumes const
+        self.next(); // consumes ident
+
+        let is_for_of = match self.get_cur_token()? {
+            Token::Keyword(KeywordKind::Of) => true,
+            Token::Keyword(KeywordKind::In) => false,
+            tok => {
+                return Err(format!(
+                    "Expected token to be keyword of or keyword in but got {:?}",
+                    tok
+                ))
+            }
+        };
+        self.next(); // consumes of or in
+
+        let expression = self.parse_expression(1, loop_context)?;
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        let data_type = match (is_for_of, expression.get_data_type()) {
+            (true, DataType::ArrayType { base_type }) if *base_type != DataType::Unknown => {
+                *base_type
+            }
+            (true, DataType::String) => DataType::String,
+            (true, data_type) => {
+                return Err(format!(
+                    "for...of can only iterate over arrays and strings but got {}",
+                    data_type
+                ))
+            }
+
+            // Keys of objects are always strings
+            (false, DataType::ObjectType { entries: _ }) => DataType::String,
+            (false, data_type) => {
+                return Err(format!(
+                    "for...in can only iterate over the keys of objects but got {}",
+                    data_type
+                ))
+            }
+        };
+
+        let sym_meta = SymbolMetaInsert::create(data_type.clone(), is_const, false, None);
+        loop_context.insert(name.as_str(), sym_meta)?;
+
+        let llvm_var_name = self.get_llvm_var_name(name.as_str(), loop_context, false);
+        let mut block = self.parse_block(loop_context)?;
+        let llvm_var_name =
+            self.copy_captured_binding(llvm_var_name.as_str(), &data_type, &mut block, start);
+
+        if is_for_of {
+            return Ok(Ast::new_for_of_loop(
+                llvm_var_name.as_str(),
+                kind,
+                expression,
+                block,
+                self.get_span_from(&start),
+            ));
+        } else {
+            return Ok(Ast::new_for_in_loop(
+                llvm_var_name.as_str(),
+                kind,
+                expression,
+                block,
+                self.get_span_from(&start),
+            ));
+        }
+    }
+
+    /*
+     * Assumes the current token to be `keyword switch` in
+     *
+     * switch (<discriminant>) {
+     *     case <test>:
+     *         <block>
+     *     default:
+     *         <block>
+     * }
+     *
+     * Consumes till token `}`
+     *
+     * Blocks of all cases share one child context like the body of a switch in js,
+     * tests have to be of the same data type as the discriminant
+     *
+     * Switch on an enum has to handle every member of it unless there is a default
+     * case
+     *
+     * */
+    pub(crate) fn parse_switch(&mut self, context: &mut SymbolContext) -> Result<AstPtr, String> {
+        self.assert_cur_token(&Token::Keyword(KeywordKind::Switch))?;
+
+        let start = self.get_cur_span()?;
+        self.next(); // consumes switch
+
+        self.assert_cur_token(&Token::CurveOpenBracket)?;
+        self.next(); // consumes (
+
+        let discriminant = self.parse_expression(1, context)?;
+        let data_type = discriminant.get_data_type();
+
+        if !matches!(
+            data_type,
+            DataType::Float | DataType::String | DataType::Boolean | DataType::EnumType { .. }
+        ) {
+            return Err(format!(
+                "switch can only be used on numbers, strings and booleans or members of enums but got {}",
+                data_type
+            ));
+        }
+
+        self.assert_cur_token(&Token::CurveCloseBracket)?;
+        self.next(); // consumes )
+
+        self.assert_cur_token(&Token::AngleOpenBracket)?;
+        self.next(); // consumes {
+
+        let cur_value = context.counter;
+        let suffix = format!("{}{}", context.suffix, cur_value);
+        context.counter += 1;
+
+        let mut switch_context = context.create_child_context(suffix);
+        switch_context.enter_switch();
+
+        let mut cases: Vec<SwitchCase> = vec![];
+        let mut has_default = false;
+        let mut handled_members: Vec<String> = vec![];
+
+        while self.get_cur_token()? != &Token::AngleCloseBracket {
+            self.assert_not_eof()?;
+
+            let test = match self.get_cur_token()? {
+                Token::Keyword(KeywordKind::Case) => {
+                    self.next(); // consumes case
+
+                    let test = self.parse_expression(1, &mut switch_context)?;
+                    if test.get_data_type() != data_type {
+                        return Err(format!(
+                            "Expected case of switch to be of data type {} but got {}",
+                            data_type,
+                            test.get_data_type()
+                        ));
+                    }
+
+                    if let Expression::EnumMember { member, .. } = &test {
+                        handled_members.push(member.clone());
+                    }
+
+                    Some(test)
+                }
+
+                Token::Keyword(KeywordKind::Default) => {
+                    if has_default {
+                        return Err(format!("switch can only have one default case"));
+                    }
+                    has_default = true;
+
+                    self.next(); // consumes default
+                    None
+                }
+
+                tok => {
+                    return Err(format!(
+                        "Expected token to be keyword case or keyword default but got {:?}",
+                        tok
+                    ))
+                }
+            };
+
+            self.assert_cur_token(&Token::Colon)?;
+            self.next(); // consumes :
+
+            let mut block: Vec<AstPtr> = vec![];
+            loop {
+                match self.get_cur_token()? {
+                    Token::Keyword(KeywordKind::Case | KeywordKind::Default)
+                    | Token::AngleCloseBracket => break,
+
+                    _ => {
+                        self.assert_not_eof()?;
+                        block.push(self.next_ast_in_context(&mut switch_context)?);
+                    }
+                }
+            }
+
+            cases.push(SwitchCase::new(test, block));
+        }
+
+        self.next(); // consumes }
+
+        /*
+         * Switch on an enum without a default case is expected to handle every
+         * member, a member it misses is reported as a warning pointing at the switch
+         *
+         * */
+        if let (DataType::EnumType { name, .. }, false) = (&data_type, has_default) {
+            let enumeration = context.get_enum(name).unwrap();
+            let missing_members: Vec<&str> = enumeration
+                .members
+                .keys()
+                .filter(|member| !handled_members.contains(member))
+                .map(|member| member.as_str())
+                .collect();
+
+            if !missing_members.is_empty() {
+                let message = format!(
+                    "switch on enum {} does not handle {}, add the missing cases or a default case",
+                    enumeration.name,
+                    missing_members.join(", ")
+                );
+                self.warn(&message, start);
+            }
+        }
+
+        for case in cases.iter_mut() {
+            mark_captured_variables(&mut case.block, &self.captured_variables);
+        }
+
+        return Ok(Ast::new_switch(
+            discriminant,
+            cases,
+            self.get_span_from(&start),
+        ));
+    }
+
+    /*
+     * Assumes the current token to be the label in
+     *
+     * <label>: <loop>
+     *
+     * where loop is a while, do...while or for loop, consumes till the end of the loop
+     *
+     * */
+    fn parse_labeled_statement(&mut self, context: &mut SymbolContext) -> Result<AstPtr, String> {
+        let start = self.get_cur_span()?;
+        let label = self.get_cur_token()?.get_ident_name()?.clone();
+        self.next(); // consumes label
+
+        self.assert_cur_token(&Token::Colon)?;
+        self.next(); // consumes :
 
-    pub(crate) fn parse_do_while_loop(
-        &mut self,
-        context: &mut SymbolContext,
-    ) -> Result<AstPtr, String> {
-        self.assert_cur_token(&Token::Keyword(KeywordKind::Do))?;
+        let statement = match self.get_cur_token()? {
+            Token::Keyword(KeywordKind::While) => {
+                self.parse_while_loop(Some(label.clone()), context)?
+            }
+            Token::Keyword(KeywordKind::Do) => {
+                self.parse_do_while_loop(Some(label.clone()), context)?
+            }
+            Token::Keyword(KeywordKind::For) => {
+                self.parse_for_loop(Some(label.clone()), context)?
+            }
+            tok => {
+                return Err(format!(
+                    "Expected label {} to be followed by a loop but got {:?}",
+                    label, tok
+                ))
+            }
+        };
 
-        self.next(); // consumes do
+        return Ok(Ast::new_labeled_statement(
+            label.as_str(),
+            statement,
+            self.get_span_from(&start),
+        ));
+    }
 
-        let block = self.parse_block(context)?;
+    /*
+     * Assumes the current token to be `keyword break` or `keyword continue` in
+     *
+     * break <label>;
+     *
+     * where label is optional, consumes till token `;`
+     *
+     * */
+    fn parse_loop_control_flow(&mut self, context: &mut SymbolContext) -> Result<AstPtr, String> {
+        let start = self.get_cur_span()?;
+        let keyword = match self.get_cur_token()? {
+            Token::Keyword(keyword @ (KeywordKind::Break | KeywordKind::Continue)) => {
+                keyword.clone()
+            }
+            tok => {
+                return Err(format!(
+                    "Expected token to be keyword break or keyword continue but got {:?}",
+                    tok
+                ))
+            }
+        };
+        self.next(); // consumes break or continue
 
-        self.assert_cur_token(&Token::Keyword(KeywordKind::While))?;
-        self.next(); // consumes while
+        let keyword_name = match keyword {
+            KeywordKind::Break => "break",
+            _ => "continue",
+        };
 
-        self.assert_cur_token(&Token::CurveOpenBracket)?;
-        self.next(); // consumes (
+        match keyword {
+            KeywordKind::Break if !context.can_break() => {
+                return Err(format!("Cannot use break outside of a loop or switch"));
+            }
+            KeywordKind::Continue if !context.is_in_loop() => {
+                return Err(format!("Cannot use continue outside of a loop"));
+            }
+            _ => {}
+        }
 
-        let condition = self.parse_expression(1, context)?;
+        let label = match self.get_cur_token()? {
+            Token::Ident { name } => {
+                let label = name.clone();
+                if !context.has_loop_label(&label) {
+                    return Err(format!("Unknown label {} used by {}", label, keyword_name));
+                }
 
-        self.assert_cur_token(&Token::CurveCloseBracket)?;
-        self.next(); // consumes )
+                self.next(); // consumes label
+                Some(label)
+            }
+            _ => None,
+        };
 
-        let block_with_condition = BlockWithCondition::new(condition, block);
-        return Ok(Ast::new_do_while_loop(block_with_condition));
+        self.skip_semicolon()?;
+        return Ok(Ast::new_loop_control_flow(
+            keyword,
+            label,
+            self.get_span_from(&start),
+        ));
     }
 
     pub(crate) fn parse_variable_declaration(
@@ -346,6 +1206,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         can_export: bool,
     ) -> Result<AstPtr, String> {
         let cur_tok = self.get_cur_token()?;
+        let start = self.get_cur_span()?;
 
         match cur_tok {
             Token::Keyword(keyword_kind) => {
@@ -373,7 +1234,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         let expected_data_type = match self.get_cur_token()? {
                             Token::Colon => {
                                 self.next(); // consumes :
-                                let data_type = self.parse_type_declaration(1)?;
+                                let data_type = self.parse_type_declaration(1, context)?;
 
                                 if data_type == DataType::Void {
                                     return Err(format!("Void type can be only used as return type in function but ident {:?} is explicitly declared as void", name));
@@ -385,14 +1246,37 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                             _ => DataType::Unknown,
                         };
 
-                        self.assert_cur_token(&Token::Assign)?;
+                        // `let x: number | undefined;` starts out as undefined
+                        let is_uninitialized = !is_const
+                            && self.get_cur_token()? != &Token::Assign
+                            && expected_data_type.accepts_nullish(&DataType::Undefined);
 
-                        self.next(); // consumes =
+                        let expression = if is_uninitialized {
+                            Expression::UndefinedLiteralExp {
+                                span: self.get_span_from(&start),
+                            }
+                        } else {
+                            self.assert_cur_token(&Token::Assign)?;
+                            self.next(); // consumes =
 
-                        let expression = self.parse_expression(1, context)?;
+                            self.parse_expression(1, context)?
+                        };
+                        let expression =
+                            convert_to_data_type(expression, &expected_data_type, context);
 
                         let expression_data_type = expression.get_data_type();
 
+                        if let Expression::ArrayLiteral {
+                            expression_data_type: DataType::Unknown,
+                            ..
+                        } = expression
+                        {
+                            return Err(format!(
+                                "Cannot infer the type of empty array assigned to {}, declare it with a type like `{}: number[]`",
+                                name, name
+                            ));
+                        }
+
                         /*
                          * We cannot allow following type of code
                          *
@@ -418,10 +1302,10 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                         }
 
                         if expected_data_type != DataType::Unknown
-                            && expected_data_type != expression_data_type
+                            && !expression_data_type.has_same_layout(&expected_data_type)
                         {
                             return Err(format!(
-                                "Expected data type {:?} but got {:?}",
+                                "Expected data type {} but got {}",
                                 expected_data_type, expression_data_type
                             ));
                         }
@@ -448,6 +1332,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                             llvm_var_name.as_str(),
                             expression,
                             kind,
+                            self.get_span_from(&start),
                         ));
                     }
 
@@ -496,7 +1381,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                 if next_tok == &Token::BoxOpenBracket {
                     while lookup_parser.next() != &Token::BoxCloseBracket {
-                        continue;
+                        lookup_parser.assert_not_eof()?;
                     }
 
                     continue 'outer;
@@ -540,14 +1425,29 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         context: &mut SymbolContext,
     ) -> Result<AstPtr, String> {
         let exp = self.parse_expression(1, context)?;
+
+        // Only a field can be assigned to through an expression
+        if VariableAssignmentOperator::is_lexer_assignment_operator(self.get_cur_token()?) {
+            return self.parse_field_assignment(exp, context);
+        }
+
         self.skip_semicolon()?;
+        return self.bind_to_temp_variable(exp, context);
+    }
+
+    // Declares a temporary variable holding the value of the expression
+    pub(crate) fn bind_to_temp_variable(
+        &mut self,
+        exp: Expression,
+        context: &mut SymbolContext,
+    ) -> Result<AstPtr, String> {
         let name = context.get_temp_name();
 
         let sym_meta = SymbolMetaInsert::create(exp.get_data_type(), true, false, None);
 
         if let Err(_) = context.insert(name.as_str(), sym_meta) {
             return Err(format!(
-                "[INTERNAL ERROR](parser.parse_naked_expression) There is already a temp variable with name {}",
+                "[INTERNAL ERROR](parser.bind_to_temp_variable) There is already a temp variable with name {}",
                 name
             ));
         }
@@ -555,10 +1455,12 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         self.skip_semicolon()?;
 
         // let name_with_suffix = format!("{}{}", name, suffix);
+        let span = exp.get_span();
         return Ok(Ast::new_variable_declaration(
             name.as_str(),
             exp,
             VariableDeclarationKind::Const,
+            span,
         ));
     }
 
@@ -567,10 +1469,11 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         context: &SymbolContext,
     ) -> Result<AstPtr, String> {
         let cur_tok = &self.get_cur_token()?.clone();
+        let start = self.get_cur_span()?;
 
         match cur_tok {
             Token::Ident { name } => {
-                let sym_meta = context.get(name).unwrap();
+                let sym_meta = self.get_variable(name, context).unwrap();
 
                 self.next(); // consumes the ident
 
@@ -587,28 +1490,22 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                             if let DataType::Float = member_access_exp.get_data_type() {
                             } else {
-                                return Err(format!("Expected data_type of array member access expression to be float but got {:?}", member_access_exp.get_data_type()));
+                                return Err(format!("Expected data_type of array member access expression to be number but got {}", member_access_exp.get_data_type()));
                             }
 
                             self.assert_cur_token(&Token::BoxCloseBracket)?;
                             self.next(); // consumes ]
 
-                            let operator = match self.get_cur_token()? {
-                            Token::Assign => VariableAssignmentOperator::Assign,
-                            Token::PlusAssign => VariableAssignmentOperator::PlusAssign,
-                            Token::MinusAssign => VariableAssignmentOperator::MinusAssign,
-                            Token::StarAssign => VariableAssignmentOperator::StarAssign,
-                            Token::SlashAssign => VariableAssignmentOperator::SlashAssign,
-                            tok => return Err(format!("Expected either one of the =, +=, -=, *=, /= assignment operators but got {:?}", tok)),
-                        };
+                            let operator = self.parse_assignment_operator(base_type)?;
 
                             self.next(); // consumes VariableAssignmentOperator
 
                             let exp = self.parse_expression(1, context)?;
+                            let exp = convert_to_data_type(exp, base_type, context);
 
-                            if &exp.get_data_type() != base_type.as_ref() {
+                            if !exp.get_data_type().has_same_layout(base_type) {
                                 return Err(format!(
-                                    "Reassigning datatype {:?} to variable whose datatype is {:?}",
+                                    "Reassigning datatype {} to variable whose datatype is {}",
                                     exp.get_data_type(),
                                     base_type
                                 ));
@@ -623,10 +1520,11 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                                 member_access_exp,
                                 operator,
                                 exp,
+                                self.get_span_from(&start),
                             ));
                         } else {
                             return Err(format!(
-                                "Expected the datatype of ident {:?} to be ArrayType but got {:?}",
+                                "Expected the datatype of ident {:?} to be ArrayType but got {}",
                                 name, array_datatype
                             ));
                         }
@@ -639,22 +1537,16 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
                         let data_type = sym_meta.data_type.clone();
 
-                        let operator = match self.get_cur_token()? {
-                            Token::Assign => VariableAssignmentOperator::Assign,
-                            Token::PlusAssign => VariableAssignmentOperator::PlusAssign,
-                            Token::MinusAssign => VariableAssignmentOperator::MinusAssign,
-                            Token::StarAssign => VariableAssignmentOperator::StarAssign,
-                            Token::SlashAssign => VariableAssignmentOperator::SlashAssign,
-                            tok => return Err(format!("Expected either one of the =, +=, -=, *=, /= assignment operators but got {:?}", tok)),
-                        };
+                        let operator = self.parse_assignment_operator(&data_type)?;
 
                         self.next(); // consumes VariableAssignmentOperator
 
                         let expression = self.parse_expression(1, context)?;
+                        let expression = convert_to_data_type(expression, &data_type, context);
 
-                        if expression.get_data_type() != data_type {
+                        if !expression.get_data_type().has_same_layout(&data_type) {
                             return Err(format!(
-                                "Reassigning datatype {:?} to variable whose datatype is {:?}",
+                                "Reassigning datatype {} to variable whose datatype is {}",
                                 expression.get_data_type(),
                                 data_type
                             ));
@@ -668,6 +1560,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
                             llvm_name.as_str(),
                             operator,
                             expression,
+                            self.get_span_from(&start),
                         ));
                     }
                 }
@@ -677,10 +1570,56 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         }
     }
 
+    /*
+     * Returns the assignment operator at the current token without consuming it
+     *
+     * Compound assignment other than `+=` is only defined for numbers
+     *
+     * */
+    pub(crate) fn parse_assignment_operator(
+        &mut self,
+        data_type: &DataType,
+    ) -> Result<VariableAssignmentOperator, String> {
+        let cur_tok = self.get_cur_token()?;
+
+        let operator = match convert_token_to_assignment_operator(cur_tok) {
+            Some(operator) => operator,
+            None => {
+                return Err(format!(
+                    "Expected an assignment operator but got {:?}",
+                    cur_tok
+                ))
+            }
+        };
+
+        let is_numeric_only = !matches!(
+            operator,
+            VariableAssignmentOperator::Assign | VariableAssignmentOperator::PlusAssign
+        );
+
+        if is_numeric_only && data_type != &DataType::Float {
+            return Err(format!(
+                "Operator {:?} can only be used on datatype number but used on datatype {}",
+                cur_tok, data_type
+            ));
+        }
+
+        let is_plus_assign = operator == VariableAssignmentOperator::PlusAssign;
+
+        if is_plus_assign && !matches!(data_type, DataType::Float | DataType::String) {
+            return Err(format!(
+                "Operator {:?} can only be used on datatype number or string but used on datatype {}",
+                cur_tok, data_type
+            ));
+        }
+
+        return Ok(operator);
+    }
+
     /*
      * Assumes the current token to be `keyword functions` in
      *
-     * function name(parameter1 : type1, parameter2 : type2 ) : returnType {
+     * function name(parameter1 : type1, parameter2? : type2 ) : returnType {
      *      <block>
      * };
      *
@@ -690,7 +1629,12 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
      *
      * Pass the context no need to create child context
      *
-     * TODO: Support closures
+     * Function declared at the top of the file is global, anywhere else it is a
+     * const variable holding a closure. Either way its name is inserted before the
+     * body is parsed so that the function can call itself
+     *
+     * Type parameters after the name make it generic, see
+     * parse_generic_function_declaration
      *
      * */
     pub(crate) fn parse_function_declaration(
@@ -699,98 +1643,69 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         can_export: bool,
     ) -> Result<AstPtr, String> {
         self.assert_cur_token(&Token::Keyword(KeywordKind::Function))?;
+        let start = self.get_cur_span()?;
         self.next(); // consumes keyword function
 
         if let Token::Ident { name } = self.get_cur_token()?.clone() {
             self.next(); // consumes Ident
 
-            self.assert_cur_token(&Token::CurveOpenBracket)?;
-            self.next(); // consumes (
-
-            let mut arguments: IndexMap<String, DataType> = IndexMap::new();
-
-            while self.get_cur_token()?.clone() != Token::CurveCloseBracket {
-                if let Token::Ident { name } = self.get_cur_token()?.clone() {
-                    self.next(); // consumes Ident
-
-                    self.assert_cur_token(&Token::Colon)?;
-                    self.next(); // consumes :
-
-                    let data_type = self.parse_type_declaration(1)?;
-                    let name = self.get_llvm_var_name(name.as_str(), context, false);
-                    if arguments.contains_key(&name) {
-                        return Err(format!("In function declaration each argument must have different names but name : {} is repeated", &name));
-                    } else {
-                        arguments.insert(name, data_type);
-                    }
-
-                    if let Token::Comma = self.get_cur_token()?.clone() {
-                        self.next();
-                        continue;
-                    } else {
-                        self.assert_cur_token(&Token::CurveCloseBracket)?;
-                    }
-                } else {
-                    return Err(format!(
-                        "Expected current token to be of Ident but got {:?}",
-                        self.get_cur_token()?
-                    ));
-                }
+            if self.get_cur_token()? == &Token::LessThan {
+                return self.parse_generic_function_declaration(&name, start, context, can_export);
             }
 
-            self.next(); // consumes )
+            let arguments = self.parse_parameters(context)?;
 
             self.assert_cur_token(&Token::Colon)?;
             self.next(); // consumes :
 
-            let return_type = self.parse_type_declaration(1)?;
+            let return_type = self.parse_type_declaration(1, context)?;
 
             self.assert_cur_token(&Token::AngleOpenBracket)?;
 
-            let function_block_context =
-                &mut context.create_function_context(FunctionSymbol::new(return_type.clone()));
-
-            for (arg_name, arg_data_type) in &arguments {
-                /*
-                 * In arguments the arg_name are with the suffix but to insert into
-                 * function_block_context the name should not have suffix as it will be
-                 * added by the context itself
-                 *
-                 * So we have to remove the suffix
-                 * */
-
-                let arg_name_cloned = arg_name.clone();
-                let arg_name_without_suffix = arg_name_cloned
-                    .strip_suffix(&self.get_llvm_suffix(context))
-                    .unwrap();
+            let data_type = DataType::FunctionType {
+                arguments: convert_index_map_to_vec(&arguments),
+                return_type: Box::new(return_type.clone()),
+            };
+
+            if !context.is_global() {
+                let sym_meta = SymbolMetaInsert::create(data_type, true, false, None);
+                context.insert(name.as_str(), sym_meta)?;
+
+                let closure = self.parse_closure(
+                    name.as_str(),
+                    arguments,
+                    Some(return_type),
+                    start,
+                    context,
+                )?;
 
-                let sym_meta = SymbolMetaInsert::create(arg_data_type.clone(), false, false, None);
-                function_block_context.insert(arg_name_without_suffix, sym_meta)?;
+                self.skip_semicolon()?;
+                let llvm_name = self.get_llvm_var_name(name.as_str(), context, false);
+                return Ok(Ast::new_variable_declaration(
+                    llvm_name.as_str(),
+                    closure,
+                    VariableDeclarationKind::Const,
+                    self.get_span_from(&start),
+                ));
             }
 
-            let block = self.parse_block_with_context(function_block_context)?;
-
             context.insert_global_variable(
                 name.as_str(),
-                SymbolMetaInsert::create(
-                    DataType::FunctionType {
-                        arguments: convert_index_map_to_vec(&arguments),
-                        return_type: Box::new(return_type.clone()),
-                    },
-                    true,
-                    can_export,
-                    None,
-                ),
+                SymbolMetaInsert::create(data_type, true, can_export, None),
             )?;
 
-            self.skip_semicolon()?;
             let llvm_name = self.get_llvm_var_name(name.as_str(), context, can_export);
-            return Ok(Ast::new_function_declaration(
+            let (function, _) = self.parse_function_body(
+                llvm_name.as_str(),
                 arguments,
-                block,
-                llvm_name,
-                return_type,
-            ));
+                Some(return_type),
+                String::from("_"),
+                start,
+                context,
+            )?;
+
+            self.skip_semicolon()?;
+            return Ok(function);
         } else {
             return Err(format!(
                 "Expected the current token to be ident but got {:?}",
@@ -798,6 +1713,141 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             ));
         }
     }
+
+    /*
+     * `type` and `interface` are not keywords, they start a declaration only when
+     * followed by the name of the type e.g. `type = 1` assigns to a variable
+     *
+     * */
+    pub(crate) fn is_type_declaration(&mut self) -> Result<bool, String> {
+        match self.get_cur_token()? {
+            Token::Ident { name } if name == "type" || name == "interface" => {
+                let mut lookup_parser = self.lookup_parser();
+                lookup_parser.next(); // consumes type or interface
+
+                return Ok(matches!(
+                    lookup_parser.get_cur_token()?,
+                    Token::Ident { .. }
+                ));
+            }
+            _ => return Ok(false),
+        }
+    }
+
+    // `const` starts a const enum when followed by `enum`
+    fn is_const_enum(&mut self) -> Result<bool, String> {
+        if self.get_cur_token()? != &Token::Keyword(KeywordKind::Const) {
+            return Ok(false);
+        }
+
+        let mut lookup_parser = self.lookup_parser();
+        lookup_parser.next(); // consumes const
+
+        return Ok(lookup_parser.get_cur_token()? == &Token::Keyword(KeywordKind::Enum));
+    }
+
+    /*
+     * Assumes the current token to be `type` or `interface` in
+     *
+     *      type <name> = <type>;
+     *
+     *      type <name><T, U> = <type>;
+     *
+     *      interface <name> extends <interface>, <interface> {
+     *          <properties>
+     *      }
+     *
+     * An interface extending others has their properties followed by its own, see
+     * parse_object_type_entries for the properties
+     *
+     * Type parameters make the type alias generic, the type arguments given where it
+     * is used take their place, see parse_generic_type
+     *
+     * Consumes till `;` or `}`
+     *
+     * */
+    pub(crate) fn parse_type_declaration_statement(
+        &mut self,
+        context: &mut SymbolContext,
+        can_export: bool,
+    ) -> Result<AstPtr, String> {
+        let start = self.get_cur_span()?;
+        let is_interface = self.get_cur_token()?
+            == &Token::Ident {
+                name: "interface".to_string(),
+            };
+
+        let name = self.next().get_ident_name()?.clone(); // consumes type or interface
+        self.next(); // consumes ident
+
+        let type_parameters = if self.get_cur_token()? == &Token::LessThan {
+            if is_interface {
+                return Err(format!(
+                    "Generic interface {} is not supported, use a generic type alias instead",
+                    name
+                ));
+            }
+            self.parse_type_parameters()?
+        } else {
+            vec![]
+        };
+
+        let data_type = if is_interface {
+            let mut extended = DataType::ObjectType {
+                entries: IndexMap::new(),
+            };
+
+            if self.get_cur_token()? == &Token::Keyword(KeywordKind::Extends) {
+                self.next(); // consumes extends
+
+                loop {
+                    let base_name = self.get_cur_token()?.get_ident_name()?.clone();
+                    let base = match context.get_type(&base_name) {
+                        Some(type_symbol) => type_symbol.data_type.clone(),
+                        None => {
+                            return Err(self.dependent_error(
+                                &base_name,
+                                format!("Unknown ident name : {:?}", base_name),
+                            ))
+                        }
+                    };
+                    self.next(); // consumes ident
+
+                    extended = merge_object_types(&name, &extended, &base)?;
+
+                    if self.get_cur_token()? == &Token::Comma {
+                        self.next(); // consumes ,
+                    } else {
+                        break;
+                    }
+                }
+            }
+
+            let entries = self.parse_object_type_entries(context)?;
+            merge_object_types(&name, &extended, &DataType::ObjectType { entries })?
+        } else {
+            self.assert_cur_token(&Token::Assign)?;
+            self.next(); // consumes =
+
+            let generic_context = create_generic_context(&type_parameters, context)?;
+            self.parse_type_declaration(1, &generic_context)?
+        };
+
+        self.skip_semicolon()?;
+
+        let mut type_symbol = TypeSymbol::create(data_type, is_interface, can_export);
+        type_symbol.type_parameters = type_parameters;
+        context.insert_type(&name, type_symbol)?;
+
+        // Interface declared again has the merged properties
+        let data_type = context.get_type(&name).unwrap().data_type.clone();
+        return Ok(Ast::new_type_declaration(
+            &name,
+            data_type,
+            self.get_span_from(&start),
+        ));
+    }
+
     /*
      * Assumes the current token to be `keyword import` in
      *
@@ -811,19 +1861,21 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         context: &mut SymbolContext,
     ) -> Result<AstPtr, String> {
         self.assert_cur_token(&Token::Keyword(KeywordKind::Import))?;
+        let start = self.get_cur_span()?;
         self.next(); // consumes import
 
-        let file_name = {
+        let (file_name, file_name_pos) = {
             let mut lookup_parser = self.lookup_parser();
 
             while lookup_parser.get_cur_token()?.clone() != Token::Keyword(KeywordKind::From) {
+                lookup_parser.assert_not_eof()?;
                 lookup_parser.next();
             }
 
             lookup_parser.next(); // consumes from
 
             if let Token::Literal(LiteralKind::String { name }) = lookup_parser.get_cur_token()? {
-                name.clone()
+                (name.clone(), lookup_parser.cur_pos)
             } else {
                 return Err(format!(
                     "Expect tok to be of string literal but got {:?}",
@@ -832,63 +1884,170 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             }
         };
 
-        let external_file_symbols = &{
+        let (external_file_symbols, external_file_types) = &{
             if &file_name == "compilerInternal" {
-                self.get_internal_compiler_provider_fn()
+                (self.get_internal_compiler_provider_fn(), HashMap::new())
             } else {
-                let external_file_data = {
-                    if !self.resolver.contains(&file_name, self.get_cur_file_name()) {
-                        self.resolver
-                            .resolve(&file_name, &self.get_cur_file_name().to_string())?;
+                if !self.resolver.contains(&file_name, self.get_cur_file_name()) {
+                    let cur_file_name = self.get_cur_file_name().to_string();
+
+                    if let Err(err) = self.resolver.resolve(&file_name, &cur_file_name) {
+                        self.cur_pos = file_name_pos; // So that error points to file name
+                        return Err(err);
                     }
-                    self.resolver.get(&file_name, self.get_cur_file_name())
-                };
-                external_file_data.unwrap().clone()
+                }
+
+                let cur_file_name = self.get_cur_file_name();
+                (
+                    self.resolver
+                        .get(&file_name, cur_file_name)
+                        .unwrap()
+                        .clone(),
+                    self.resolver
+                        .get_types(&file_name, cur_file_name)
+                        .unwrap()
+                        .clone(),
+                )
             }
         };
         let external_file_id = self.get_id_for_file(&file_name);
 
+        /*
+         * Every class known to the file comes along, not only the imported ones,
+         * since an imported function or class can use any of them. llvm declares
+         * the functions of the classes which were not known before
+         *
+         * */
+        let mut classes: BTreeMap<String, Class> = BTreeMap::new();
+        for type_symbol in external_file_types.values() {
+            if let Some(class) = &type_symbol.class {
+                if context.get_class(&class.llvm_name).is_none() {
+                    classes.insert(class.llvm_name.clone(), class.clone());
+                }
+            }
+        }
+        let classes: Vec<Class> = classes.into_values().collect();
+
+        for class in classes.iter() {
+            context.insert_class(class.clone());
+        }
+
+        // Enums come along the same way, a switch needs the members of the enum
+        for type_symbol in external_file_types.values() {
+            if let Some(enumeration) = &type_symbol.enumeration {
+                if context.get_enum(&enumeration.llvm_name).is_none() {
+                    context.insert_enum(enumeration.clone());
+                }
+            }
+        }
+
         self.assert_cur_token(&Token::AngleOpenBracket)?;
         self.next(); // consumes {
 
         let mut context_data_type: IndexMap<String, DataType> = IndexMap::new();
+        let mut imported_generics: Vec<GenericFunction> = vec![];
 
         while self.get_cur_token()?.clone() != Token::AngleCloseBracket {
-            if let Token::Ident { name } = self.get_cur_token()? {
-                let symbol_meta = external_file_symbols.get(name).unwrap();
-                let can_export = symbol_meta.can_export;
-
-                if !can_export {
-                    return Err(format!(
-                        "Cannot import a variable {} from {} where it is not exported",
-                        name, file_name
-                    ));
-                }
+            let name = self.get_cur_token()?.get_ident_name()?.clone();
 
-                context.insert_global_variable(
-                    name,
-                    SymbolMetaInsert::create(
-                        symbol_meta.data_type.clone(),
-                        true,
-                        false,
-                        Some(ExternalVariableData {
-                            file_id_no: external_file_id,
-                        }),
-                    ),
-                )?;
+            // Name can belong to a variable, a type or both of them
+            let symbol_meta = external_file_symbols.get(&name);
+            let type_symbol = external_file_types.get(&name);
 
-                let llvm_name = self.get_llvm_import_name(name, &file_name);
+            if symbol_meta.is_none() && type_symbol.is_none() {
+                return Err(format!(
+                    "Cannot import a variable {} from {} where it is not declared",
+                    name, file_name
+                ));
+            }
 
-                context_data_type.insert(llvm_name, symbol_meta.data_type.clone());
+            let symbol_meta = symbol_meta.filter(|symbol_meta| symbol_meta.can_export);
+            let type_symbol = type_symbol.filter(|type_symbol| type_symbol.can_export);
 
-                self.next(); // consumes ident
+            if symbol_meta.is_none() && type_symbol.is_none() {
+                return Err(format!(
+                    "Cannot import a variable {} from {} where it is not exported",
+                    name, file_name
+                ));
+            }
 
-                if self.get_cur_token()? == &Token::Comma {
-                    self.next(); // consumes ,
+            let external_data = Some(ExternalVariableData {
+                file_id_no: external_file_id,
+            });
+
+            // Generic function is generated by the module calling it
+            if let Some(symbol_meta) = symbol_meta {
+                if let Some(generic) = &symbol_meta.generic {
+                    context.insert_global_variable(
+                        &name,
+                        SymbolMetaInsert::create_generic(
+                            symbol_meta.data_type.clone(),
+                            generic.clone(),
+                            false,
+                            external_data,
+                        ),
+                    )?;
+
+                    imported_generics.push(generic.clone());
                 } else {
-                    self.assert_cur_token(&Token::AngleCloseBracket)?;
+                    context.insert_global_variable(
+                        &name,
+                        SymbolMetaInsert::create(
+                            symbol_meta.data_type.clone(),
+                            true,
+                            false,
+                            external_data,
+                        ),
+                    )?;
+
+                    let llvm_name = self.get_llvm_import_name(&name, &file_name);
+
+                    context_data_type.insert(llvm_name, symbol_meta.data_type.clone());
                 }
             }
+
+            // Imported interface cannot be merged with a local one
+            if let Some(type_symbol) = type_symbol {
+                let type_symbol = match (&type_symbol.class, &type_symbol.enumeration) {
+                    (Some(class), _) => TypeSymbol::create_class(class.clone(), false),
+                    (None, Some(enumeration)) => {
+                        // Reverse mapping is declared like an imported function
+                        if enumeration.has_reverse_mapping() {
+                            context_data_type.insert(
+                                enumeration.get_reverse_mapping_name(),
+                                Enum::get_reverse_mapping_type(),
+                            );
+                        }
+                        TypeSymbol::create_enum(enumeration.clone(), false)
+                    }
+                    (None, None) => {
+                        let mut imported =
+                            TypeSymbol::create(type_symbol.data_type.clone(), false, false);
+                        imported.type_parameters = type_symbol.type_parameters.clone();
+                        imported
+                    }
+                };
+                context.insert_type(&name, type_symbol)?;
+            }
+
+            self.next(); // consumes ident
+
+            if self.get_cur_token()? == &Token::Comma {
+                self.next(); // consumes ,
+            } else {
+                self.assert_cur_token(&Token::AngleCloseBracket)?;
+            }
+        }
+
+        // Functions used by the generic functions are declared like the imported ones
+        let generic_functions =
+            collect_generic_functions(imported_generics, external_file_symbols, &file_name)?;
+        for generic in generic_functions.iter() {
+            for (llvm_name, data_type) in generic.functions.iter() {
+                context_data_type
+                    .entry(llvm_name.clone())
+                    .or_insert_with(|| data_type.clone());
+            }
         }
 
         self.next(); // consumes }
@@ -907,24 +2066,33 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
         self.skip_semicolon()?;
 
-        return Ok(Ast::new_import_declaration(context_data_type, &file_name));
+        return Ok(Ast::new_import_declaration(
+            context_data_type,
+            classes,
+            generic_functions,
+            &file_name,
+            self.get_span_from(&start),
+        ));
     }
 
     pub(crate) fn next(&mut self) -> &Token {
+        self.split_token = None;
+
         match self.cur_pos {
             None => {
                 self.cur_pos = Some(0);
-                return &self.content[0];
+                return &self.content[0].token;
             }
 
             Some(value) => {
+                // Stays at Eof, callers find out about end of file from the token
                 if value >= self.content.len() - 1 {
-                    panic!("cur_pos is at maximum value")
+                    return &self.content[value].token;
                 }
 
                 let next_value = value + 1;
                 self.cur_pos = Some(next_value);
-                return &self.content[next_value];
+                return &self.content[next_value].token;
             }
         }
     }
@@ -940,6 +2108,7 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             is_const: true,
             can_export: true,
             external_data: None,
+            generic: None,
         };
         internal_index_map.insert("syscallPrint".to_string(), syscall_1);
 
@@ -953,8 +2122,17 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
             resolver: self.resolver,
             cur_file_path: self.cur_file_path.clone(),
             compiled_ast: Vec::new(),
-            ast_id_counter : 0,
-            ast_registar : HashMap::new(),
+            diagnostics: Vec::new(),
+            function_scopes: Vec::new(),
+            captured_variables: HashSet::new(),
+            closure_counter: 0,
+            cur_class: self.cur_class.clone(),
+            cur_generic: None,
+            split_token: self.split_token.clone(),
+            unbound_names: self.unbound_names.clone(),
+            is_dependent_error: false,
+            ast_id_counter: 0,
+            ast_registar: HashMap::new(),
         };
     }
 
@@ -971,6 +2149,31 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         return Ok(());
     }
 
+    // Checks the last consumed token, e.g. the `;` skipped by a statement
+    pub(crate) fn assert_prev_token(&self, token_type: &Token) -> Result<(), String> {
+        let prev_token = match self.cur_pos {
+            Some(size) if size > 0 => &self.content[size - 1].token,
+            _ => return Err("There is no token before the current token".to_string()),
+        };
+
+        if prev_token != token_type {
+            return Err(format!(
+                "Expected token type to be {:?} but got {:?}",
+                token_type, prev_token
+            ));
+        }
+
+        return Ok(());
+    }
+
+    pub(crate) fn assert_not_eof(&self) -> Result<(), String> {
+        if self.get_cur_token()? == &Token::Eof {
+            return Err("Unexpected end of file".to_string());
+        }
+
+        return Ok(());
+    }
+
     pub(crate) fn skip_semicolon(&mut self) -> Result<(), String> {
         let cur_token = self.get_cur_token()?;
 
@@ -983,7 +2186,13 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
 
     pub(crate) fn get_cur_token(&self) -> Result<&Token, String> {
         if let Some(size) = self.cur_pos {
-            return Ok(&self.content[size]);
+            if let Some((split_pos, rest)) = &self.split_token {
+                if *split_pos == size {
+                    return Ok(rest);
+                }
+            }
+
+            return Ok(&self.content[size].token);
         } else {
             return Err(
                 "Next method should be called atleast one time before calling get_cur_token "
@@ -992,6 +2201,30 @@ impl<'a, R: ImportResolver> Parser<'a, R> {
         }
     }
 
+    pub(crate) fn get_cur_span(&self) -> Result<Span, String> {
+        if let Some(size) = self.cur_pos {
+            return Ok(self.content[size].span);
+        } else {
+            return Err(
+                "Next method should be called atleast one time before calling get_cur_span "
+                    .to_string(),
+            );
+        }
+    }
+
+    /*
+     * Returns the span starting from `start` till the end of the last consumed token
+     *
+     * Call it after consuming all the tokens of the ast node
+     *
+     * */
+    pub(crate) fn get_span_from(&self, start: &Span) -> Span {
+        match self.cur_pos {
+            Some(size) if size > 0 => return start.to(&self.content[size - 1].span),
+            _ => return *start,
+        }
+    }
+
     pub(crate) fn get_llvm_var_name(
         &self,
         var_name: &str,
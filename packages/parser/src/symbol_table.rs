@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use ast::data_type::DataType;
+use ast::{class::Class, data_type::DataType, enumeration::Enum, generic::GenericFunction};
+use indexmap::IndexMap;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ExternalVariableData {
@@ -14,6 +15,8 @@ pub struct SymbolMeta {
     pub suffix: String,
     pub can_export: bool,
     pub external_data: Option<ExternalVariableData>,
+    // Depth of the function declaring the variable when it is used inside of a closure
+    pub captured_from: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +26,8 @@ pub struct SymbolMetaInsert {
     pub is_const: bool,
     pub can_export: bool,
     pub external_data: Option<ExternalVariableData>,
+    // Function declared with type parameters, data_type has the type parameters in it
+    pub generic: Option<GenericFunction>,
 }
 
 impl SymbolMetaInsert {
@@ -37,9 +42,76 @@ impl SymbolMetaInsert {
             is_const,
             can_export,
             external_data,
+            generic: None,
+        };
+    }
+
+    pub fn create_generic(
+        data_type: DataType,
+        generic: GenericFunction,
+        can_export: bool,
+        external_data: Option<ExternalVariableData>,
+    ) -> Self {
+        return SymbolMetaInsert {
+            data_type,
+            is_const: true,
+            can_export,
+            external_data,
+            generic: Some(generic),
+        };
+    }
+}
+/*
+ * Data type named by a type alias, an interface, a class or an enum, only
+ * interfaces can be declared again in the same scope to add more properties to them
+ *
+ * */
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeSymbol {
+    pub data_type: DataType,
+    pub is_interface: bool,
+    pub can_export: bool,
+    pub class: Option<Class>,
+    pub enumeration: Option<Enum>,
+    // Type parameters of a generic type alias, replaced by the type arguments where it is used
+    pub type_parameters: Vec<String>,
+}
+
+impl TypeSymbol {
+    pub fn create(data_type: DataType, is_interface: bool, can_export: bool) -> Self {
+        return TypeSymbol {
+            data_type,
+            is_interface,
+            can_export,
+            class: None,
+            enumeration: None,
+            type_parameters: vec![],
+        };
+    }
+
+    pub fn create_class(class: Class, can_export: bool) -> Self {
+        return TypeSymbol {
+            data_type: class.get_data_type(),
+            is_interface: false,
+            can_export,
+            class: Some(class),
+            enumeration: None,
+            type_parameters: vec![],
+        };
+    }
+
+    pub fn create_enum(enumeration: Enum, can_export: bool) -> Self {
+        return TypeSymbol {
+            data_type: enumeration.get_data_type(),
+            is_interface: false,
+            can_export,
+            class: None,
+            enumeration: Some(enumeration),
+            type_parameters: vec![],
         };
     }
 }
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionSymbol {
     return_type: DataType,
@@ -58,9 +130,36 @@ pub struct SymbolContext<'a> {
     parent: Option<Box<&'a SymbolContext<'a>>>,
     function_symbol: Option<FunctionSymbol>,
 
+    /*
+     * Labels of the loops around the context, innermost last, unlabeled loops are
+     * None so `break` and `continue` can still check that they are inside a loop
+     *
+     * */
+    loop_labels: Vec<Option<String>>,
+    // `break` is also allowed inside of a switch
+    is_in_switch: bool,
+
+    /*
+     * Data types of union variables narrowed by a `typeof` or null check for the code
+     * inside of the context, keyed by the name of the variable
+     *
+     * */
+    narrowings: HashMap<String, DataType>,
+
+    // Types live apart from the variables, so a type and a variable can share a name
+    types: HashMap<String, TypeSymbol>,
+    pub global_types: HashMap<String, TypeSymbol>,
+
     pub suffix: String,
     pub counter: usize,
 
+    /*
+     * Number of functions around the context, a variable declared in a context of
+     * smaller depth is captured by the closure using it
+     *
+     * */
+    pub function_depth: usize,
+
     temp_counter: usize,
 }
 
@@ -71,34 +170,62 @@ impl<'a> SymbolContext<'a> {
             global_symbols: HashMap::new(),
             parent: None,
             function_symbol: None,
+            loop_labels: vec![],
+            is_in_switch: false,
+            narrowings: HashMap::new(),
+            types: HashMap::new(),
+            global_types: HashMap::new(),
 
             suffix: String::from("_"),
             counter: 0,
+            function_depth: 0,
 
             temp_counter: 0,
         };
     }
 
-    pub fn create_function_context(&self, function_symbol: FunctionSymbol) -> Self {
+    /*
+     * Variables of the enclosing contexts stay visible inside of the function, the
+     * suffix has to be unique so the names of its own variables do not clash with
+     * the captured ones
+     *
+     * */
+    pub fn create_function_context(
+        &'a self,
+        function_symbol: FunctionSymbol,
+        suffix: String,
+    ) -> Self {
         return SymbolContext {
             symbols: HashMap::new(),
             global_symbols: self.global_symbols.clone(),
-            parent: None,
+            parent: Some(Box::new(self)),
             function_symbol: Some(function_symbol),
-
-            suffix: String::from("_"),
+            // Function body cannot break out of the loop or switch it is declared in
+            loop_labels: vec![],
+            is_in_switch: false,
+            narrowings: HashMap::new(),
+            types: HashMap::new(),
+            global_types: self.global_types.clone(),
+
+            suffix,
             counter: 0,
+            function_depth: self.function_depth + 1,
 
             temp_counter: 0,
         };
     }
 
+    // Context of the top of the file, its types are global
+    pub fn is_global(&self) -> bool {
+        return self.parent.is_none() && self.function_symbol.is_none();
+    }
+
     pub fn get(&self, name: &str) -> Option<SymbolMeta> {
         let context_available = self.get_context_for_name(name);
 
         match context_available {
             None => {
-                let meta_insert = self.global_symbols.get(&name.to_string()).unwrap();
+                let meta_insert = self.global_symbols.get(&name.to_string())?;
                 let meta = SymbolMeta {
                     data_type: meta_insert.data_type.clone(),
                     is_const: true,
@@ -106,6 +233,7 @@ impl<'a> SymbolContext<'a> {
                     suffix: self.get_suffix(name),
                     can_export: false,
                     external_data: meta_insert.external_data.clone(),
+                    captured_from: None,
                 };
 
                 return Some(meta);
@@ -113,6 +241,11 @@ impl<'a> SymbolContext<'a> {
             Some(context) => {
                 let is_override = context.suffix != self.suffix;
                 let meta_insert = context.symbols.get(&name.to_string()).unwrap();
+                let captured_from = if context.function_depth < self.function_depth {
+                    Some(context.function_depth)
+                } else {
+                    None
+                };
 
                 let meta = SymbolMeta {
                     data_type: meta_insert.data_type.clone(),
@@ -121,12 +254,26 @@ impl<'a> SymbolContext<'a> {
                     suffix: self.get_suffix(name),
                     can_export: meta_insert.can_export,
                     external_data: meta_insert.external_data.clone(),
+                    captured_from,
                 };
                 return Some(meta);
             }
         }
     }
 
+    // Function declared at the top of the file or imported, unless a variable shadows it
+    pub fn is_global_variable(&self, name: &str) -> bool {
+        return self.get_context_for_name(name).is_none() && self.global_symbols.contains_key(name);
+    }
+
+    pub fn get_generic_function(&self, name: &str) -> Option<&GenericFunction> {
+        if !self.is_global_variable(name) {
+            return None;
+        }
+
+        return self.global_symbols.get(name)?.generic.as_ref();
+    }
+
     pub fn insert_global_variable(
         &mut self,
         name: &str,
@@ -156,14 +303,106 @@ impl<'a> SymbolContext<'a> {
         }
     }
 
+    /*
+     * Type declared at the top of the file is global so that functions can use it
+     * and other files can import it, anywhere else it is scoped to its block
+     *
+     * Declaring an interface again merges the properties into the existing one
+     *
+     * */
+    pub fn insert_type(&mut self, name: &str, type_symbol: TypeSymbol) -> Result<(), String> {
+        let types = if self.is_global() {
+            &mut self.global_types
+        } else {
+            &mut self.types
+        };
+
+        match types.get_mut(name) {
+            None => {
+                types.insert(name.to_string(), type_symbol);
+                return Ok(());
+            }
+
+            Some(existing) if existing.is_interface && type_symbol.is_interface => {
+                let merged_data_type =
+                    merge_object_types(name, &existing.data_type, &type_symbol.data_type)?;
+
+                existing.data_type = merged_data_type;
+                existing.can_export = existing.can_export || type_symbol.can_export;
+                return Ok(());
+            }
+
+            Some(_) => return Err(format!("There is already a type with name {}", name)),
+        }
+    }
+
+    pub fn get_type(&self, name: &str) -> Option<&TypeSymbol> {
+        let mut cur_context = self;
+
+        loop {
+            if let Some(type_symbol) = cur_context.types.get(name) {
+                return Some(type_symbol);
+            }
+
+            match &cur_context.parent {
+                Some(parent) => cur_context = parent,
+                None => return self.global_types.get(name),
+            }
+        }
+    }
+
+    /*
+     * Classes are also kept by their llvm name, which starts with the name of the
+     * class and contains `|` so it never clashes with the name of a type. The data
+     * type of an instance only has the llvm name, and the class might not be
+     * visible by its name e.g. when an imported function returns its instance
+     *
+     * */
+    pub fn insert_class(&mut self, class: Class) {
+        let type_symbol = TypeSymbol::create_class(class, false);
+        let llvm_name = type_symbol.class.as_ref().unwrap().llvm_name.clone();
+
+        self.global_types.insert(llvm_name, type_symbol);
+    }
+
+    // Class of the DataType::ClassType with the llvm name
+    pub fn get_class(&self, llvm_name: &str) -> Option<&Class> {
+        return self
+            .global_types
+            .get(llvm_name)
+            .and_then(|type_symbol| type_symbol.class.as_ref());
+    }
+
+    // Enums are kept by their llvm name for the same reason as classes
+    pub fn insert_enum(&mut self, enumeration: Enum) {
+        let llvm_name = enumeration.llvm_name.clone();
+
+        self.global_types
+            .insert(llvm_name, TypeSymbol::create_enum(enumeration, false));
+    }
+
+    // Enum of the DataType::EnumType with the llvm name
+    pub fn get_enum(&self, llvm_name: &str) -> Option<&Enum> {
+        return self
+            .global_types
+            .get(llvm_name)
+            .and_then(|type_symbol| type_symbol.enumeration.as_ref());
+    }
+
     pub fn create_child_context(&'a self, new_suffix: String) -> SymbolContext<'a> {
         let new_context = SymbolContext {
             symbols: HashMap::new(),
             global_symbols: self.global_symbols.clone(),
             parent: Some(Box::new(self)),
             function_symbol: self.function_symbol.clone(),
+            loop_labels: self.loop_labels.clone(),
+            is_in_switch: self.is_in_switch,
+            narrowings: HashMap::new(),
+            types: HashMap::new(),
+            global_types: self.global_types.clone(),
             suffix: new_suffix,
             counter: 0,
+            function_depth: self.function_depth,
             temp_counter: 0,
         };
         return new_context;
@@ -186,6 +425,86 @@ impl<'a> SymbolContext<'a> {
         }
     }
 
+    /*
+     * Child contexts created till exit_loop is called are inside the loop, the
+     * label is checked against the labels of the enclosing loops first
+     *
+     * */
+    pub fn enter_loop(&mut self, label: Option<String>) -> Result<(), String> {
+        if let Some(label) = &label {
+            if self.has_loop_label(label) {
+                return Err(format!(
+                    "Label {} is already used by an enclosing loop",
+                    label
+                ));
+            }
+        }
+
+        self.loop_labels.push(label);
+        return Ok(());
+    }
+
+    pub fn exit_loop(&mut self) {
+        self.loop_labels.pop();
+    }
+
+    pub fn is_in_loop(&self) -> bool {
+        return !self.loop_labels.is_empty();
+    }
+
+    /*
+     * Only called on the child context created for the body of the switch, so it
+     * never has to be undone
+     *
+     * */
+    pub fn enter_switch(&mut self) {
+        self.is_in_switch = true;
+    }
+
+    pub fn can_break(&self) -> bool {
+        return self.is_in_loop() || self.is_in_switch;
+    }
+
+    pub fn has_loop_label(&self, label: &str) -> bool {
+        return self
+            .loop_labels
+            .iter()
+            .any(|loop_label| loop_label.as_deref() == Some(label));
+    }
+
+    pub fn narrow(&mut self, name: &str, data_type: DataType) {
+        self.narrowings.insert(name.to_string(), data_type);
+    }
+
+    /*
+     * Narrowing of a variable stops at the context declaring it, so a variable
+     * shadowing a narrowed one has its declared data type
+     *
+     * It also stops at the function around the context, the closure can be called
+     * after the variable is assigned again
+     *
+     * */
+    pub fn get_narrowed_type(&self, name: &str) -> Option<DataType> {
+        let mut cur_context = self;
+
+        loop {
+            if cur_context.symbols.contains_key(name)
+                || cur_context.function_depth != self.function_depth
+            {
+                return None;
+            }
+
+            if let Some(data_type) = cur_context.narrowings.get(name) {
+                return Some(data_type.clone());
+            }
+
+            match &cur_context.parent {
+                Some(parent) => cur_context = parent,
+                None => return None,
+            }
+        }
+    }
+
     fn get_context_for_name(&'a self, name: &str) -> Option<&'a SymbolContext<'a>> {
         let mut cur_context = &Some(Box::new(self));
 
@@ -214,3 +533,50 @@ impl<'a> SymbolContext<'a> {
         return temp_name;
     }
 }
+
+/*
+ * Properties of `extended` followed by the ones of `extension`, a property present
+ * in both has to have the same data type
+ *
+ * */
+pub(crate) fn merge_object_types(
+    name: &str,
+    extended: &DataType,
+    extension: &DataType,
+) -> Result<DataType, String> {
+    let extended_entries = get_object_entries(name, extended)?;
+    let extension_entries = get_object_entries(name, extension)?;
+
+    let mut entries = extended_entries.clone();
+
+    for (key, data_type) in extension_entries {
+        match entries.get(key) {
+            Some(existing) if existing != data_type => {
+                return Err(format!(
+                    "Property {} of interface {} is declared as {} and again as {}",
+                    key, name, existing, data_type
+                ))
+            }
+            _ => {
+                entries.insert(key.clone(), data_type.clone());
+            }
+        }
+    }
+
+    return Ok(DataType::ObjectType { entries });
+}
+
+fn get_object_entries<'a>(
+    name: &str,
+    data_type: &'a DataType,
+) -> Result<&'a IndexMap<String, DataType>, String> {
+    match data_type {
+        DataType::ObjectType { entries } => return Ok(entries),
+        data_type => {
+            return Err(format!(
+                "Interface {} can only extend an object type but got {}",
+                name, data_type
+            ))
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use ast::{
+    declaration::{Declaration, VariableAssignmentOperator},
+    expression::{BinaryOperator, Expression, UpdateOperator},
+    Ast, AstPtr,
+};
+use lexer::convert_to_token;
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_exponent_is_right_associative() {
+    let input = "
+    const x = 2 ** 3 ** 2;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Expression::BinaryExp {
+        operator: BinaryOperator::StarStar,
+        left,
+        right,
+        ..
+    } = get_initializer(&actual_output[0])
+    {
+        assert!(matches!(left.as_ref(), Expression::FloatLiteralExp { .. }));
+        assert!(matches!(
+            right.as_ref(),
+            Expression::BinaryExp {
+                operator: BinaryOperator::StarStar,
+                ..
+            }
+        ));
+    } else {
+        panic!("Expected 2 ** 3 ** 2 to be parsed as 2 ** (3 ** 2)");
+    }
+}
+
+#[test]
+fn test_shift_precedence() {
+    let input = "
+    const x = 1 + 2 << 3 % 2;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Expression::BinaryExp {
+        operator: BinaryOperator::LeftShift,
+        left,
+        right,
+        ..
+    } = get_initializer(&actual_output[0])
+    {
+        assert!(matches!(
+            left.as_ref(),
+            Expression::BinaryExp {
+                operator: BinaryOperator::Plus,
+                ..
+            }
+        ));
+        assert!(matches!(
+            right.as_ref(),
+            Expression::BinaryExp {
+                operator: BinaryOperator::Percent,
+                ..
+            }
+        ));
+    } else {
+        panic!("Expected shift to bind looser than + and %");
+    }
+}
+
+#[test]
+fn test_unary_before_exponent() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("const x = -2 ** 2;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected -2 ** 2 to be rejected");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("Unary operator used immediately before exponentiation expression"));
+}
+
+#[test]
+fn test_update_expression() {
+    let input = "
+    let x = 1;
+    const y = x++ + ++x;
+    const numbers = [1];
+    numbers[0]--;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Expression::BinaryExp { left, right, .. } = get_initializer(&actual_output[1]) {
+        assert!(matches!(
+            left.as_ref(),
+            Expression::UpdateExp {
+                operator: UpdateOperator::Increment,
+                prefix: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            right.as_ref(),
+            Expression::UpdateExp {
+                operator: UpdateOperator::Increment,
+                prefix: true,
+                ..
+            }
+        ));
+    } else {
+        panic!("Expected x++ + ++x to be parsed as Expression::BinaryExp");
+    }
+
+    assert!(matches!(
+        get_initializer(&actual_output[3]),
+        Expression::UpdateExp {
+            operator: UpdateOperator::Decrement,
+            argument,
+            ..
+        } if matches!(argument.as_ref(), Expression::ArrayMemberAccess { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_update_expression() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("const x = 1; x++;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected update of a const variable to be rejected");
+    assert_eq!(diagnostics[0].message, "Cannot reassign a const variable");
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("let x = 1; (x + 1)++;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected update of an expression to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Invalid left-hand side expression in postfix operation"
+    );
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("let x = \"a\"; ++x;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected update of a string to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected operand of prefix operation to be of datatype number but got string"
+    );
+}
+
+#[test]
+fn test_compound_assignment() {
+    let input = "
+    let x = 1;
+    x **= 2;
+    x >>>= 1;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert!(matches!(
+        &*actual_output[1],
+        Ast::Declaration(Declaration::VariableAssignment {
+            operator: VariableAssignmentOperator::StarStarAssign,
+            ..
+        })
+    ));
+    assert!(matches!(
+        &*actual_output[2],
+        Ast::Declaration(Declaration::VariableAssignment {
+            operator: VariableAssignmentOperator::UnsignedRightShiftAssign,
+            ..
+        })
+    ));
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("let s = \"a\"; s %= \"b\";"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected %= on a string to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Operator PercentAssign can only be used on datatype number but used on datatype string"
+    );
+}
+
+#[test]
+fn test_unsupported_operands() {
+    let inputs = [
+        (
+            "const x = true + 1;",
+            "Operator Plus cannot be used on datatypes boolean and number",
+        ),
+        (
+            "const x = \"a\" - 1;",
+            "Operator Minus cannot be used on datatypes string and number",
+        ),
+        (
+            "const x = 1 < true;",
+            "Operator LessThan cannot be used on datatypes number and boolean",
+        ),
+        (
+            "const x = 1 === \"a\";",
+            "Operator StrictEquality cannot be used on datatypes number and string",
+        ),
+        (
+            "const x = \"3\" % 2;",
+            "Operator Percent cannot be used on datatypes string and number",
+        ),
+        (
+            "const x = \"3\" ** 2;",
+            "Operator StarStar cannot be used on datatypes string and number",
+        ),
+        (
+            "const x = \"3\" << 2;",
+            "Operator LeftShift cannot be used on datatypes string and number",
+        ),
+        (
+            "const x = 1 & true;",
+            "Operator Ampersand cannot be used on datatypes number and boolean",
+        ),
+        (
+            "const x = ~true;",
+            "Operator Tilde can only be used on datatype number but used on datatype boolean",
+        ),
+        (
+            "const x = -\"a\";",
+            "Operator Minus can only be used on datatype number but used on datatype string",
+        ),
+    ];
+
+    for (input, message) in inputs {
+        let resolver = &mut DummyImportResolver::new();
+        let result = consume_token(convert_to_token(input), resolver, None);
+
+        let diagnostics = result.expect_err("Expected operands to be rejected");
+        assert_eq!(diagnostics[0].message, message);
+    }
+}
+
+fn get_initializer(ast: &AstPtr) -> &Expression {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &**ast {
+        return exp;
+    }
+
+    panic!("Expected ast to be a variable declaration");
+}
@@ -0,0 +1,151 @@
+use ast::{
+    data_type::DataType,
+    declaration::Declaration,
+    expression::{ArrayMethod, Expression},
+    Ast, AstPtr,
+};
+use lexer::convert_to_token;
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_array_methods() {
+    let input = "
+    const numbers = [1, 2];
+    const length = numbers.push(3, 4);
+    const last = numbers.pop();
+    const rest = numbers.slice(1);
+    const position = numbers.indexOf(2);
+    const count = numbers.length;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let number_array = DataType::ArrayType {
+        base_type: Box::new(DataType::Float),
+    };
+
+    assert_eq!(get_data_type_of(&actual_output[1]), DataType::Float);
+    assert_eq!(
+        get_data_type_of(&actual_output[2]),
+        DataType::UnionType {
+            types: vec![DataType::Float, DataType::Undefined]
+        }
+    );
+    assert_eq!(get_data_type_of(&actual_output[3]), number_array);
+    assert_eq!(get_data_type_of(&actual_output[4]), DataType::Float);
+    assert_eq!(get_data_type_of(&actual_output[5]), DataType::Float);
+
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*actual_output[1] {
+        if let Expression::ArrayMethodCall {
+            method, arguments, ..
+        } = exp
+        {
+            assert_eq!(*method, ArrayMethod::Push);
+            assert_eq!(arguments.len(), 2);
+        } else {
+            panic!("Expected push to be parsed as Expression::ArrayMethodCall");
+        }
+    }
+}
+
+#[test]
+fn test_empty_array_takes_declared_type() {
+    let input = "
+    const words: string[] = [];
+    words.push(\"a\");
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(
+        get_data_type_of(&actual_output[0]),
+        DataType::ArrayType {
+            base_type: Box::new(DataType::String)
+        }
+    );
+}
+
+#[test]
+fn test_empty_array_without_type() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("const words = [];"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected type of empty array to be required");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("Cannot infer the type of empty array assigned to words"));
+}
+
+#[test]
+fn test_unknown_array_property() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const numbers = [1]; numbers.size;"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected unknown property to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Array does not have a property named size"
+    );
+}
+
+#[test]
+fn test_array_method_arguments() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const numbers = [1]; numbers.pop(1);"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected pop with an argument to be rejected");
+    assert_eq!(diagnostics[0].message, "pop does not take 1 arguments");
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const numbers = [1]; numbers.push(\"a\");"),
+        resolver,
+        None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_element_reads_may_be_undefined() {
+    let input = "
+    const numbers = [1, 2];
+    numbers[0] += 1;
+    numbers[1]++;
+    const first = numbers[0];
+    const total = (numbers[0] ?? 0) + 1;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(
+        get_data_type_of(&actual_output[3]),
+        DataType::UnionType {
+            types: vec![DataType::Float, DataType::Undefined]
+        }
+    );
+    assert_eq!(get_data_type_of(&actual_output[4]), DataType::Float);
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const numbers = [1]; const first: number = numbers[0];"),
+        resolver,
+        None,
+    );
+    assert!(result.is_err());
+}
+
+fn get_data_type_of(ast: &AstPtr) -> DataType {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &**ast {
+        return exp.get_data_type();
+    } else {
+        panic!("Expected ast to be a variable declaration");
+    }
+}
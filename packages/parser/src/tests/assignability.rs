@@ -0,0 +1,155 @@
+use ast::{
+    data_type::{is_assignable, DataType},
+    expression::Expression,
+};
+use lexer::convert_to_token;
+
+use super::{get_declared_exp, get_error};
+use crate::{consume_token, traits::DummyImportResolver};
+
+fn object(entries: Vec<(&str, DataType)>) -> DataType {
+    return DataType::ObjectType {
+        entries: entries
+            .into_iter()
+            .map(|(key, entry)| (key.to_string(), entry))
+            .collect(),
+    };
+}
+
+fn function(arguments: Vec<DataType>, return_type: DataType) -> DataType {
+    return DataType::FunctionType {
+        arguments,
+        return_type: Box::new(return_type),
+    };
+}
+
+#[test]
+fn test_is_assignable() {
+    let a = object(vec![("a", DataType::Float)]);
+    let ab = object(vec![("a", DataType::Float), ("b", DataType::String)]);
+    let optional_b = object(vec![
+        ("a", DataType::Float),
+        (
+            "b",
+            DataType::UnionType {
+                types: vec![DataType::String, DataType::Undefined],
+            },
+        ),
+    ]);
+
+    // Width subtyping, a missing property has to be optional
+    assert!(is_assignable(&ab, &a));
+    assert!(!is_assignable(&a, &ab));
+    assert!(is_assignable(&a, &optional_b));
+    assert!(is_assignable(&ab, &optional_b));
+
+    // Arrays are covariant
+    let array = |base_type: &DataType| DataType::ArrayType {
+        base_type: Box::new(base_type.clone()),
+    };
+    assert!(is_assignable(&array(&ab), &array(&a)));
+    assert!(!is_assignable(&array(&a), &array(&ab)));
+
+    // Parameters are contravariant, fewer parameters and a void target are fine
+    assert!(is_assignable(
+        &function(vec![a.clone()], DataType::Float),
+        &function(vec![ab.clone()], DataType::Float)
+    ));
+    assert!(!is_assignable(
+        &function(vec![ab.clone()], DataType::Float),
+        &function(vec![a.clone()], DataType::Float)
+    ));
+    assert!(is_assignable(
+        &function(vec![], DataType::Float),
+        &function(vec![DataType::Float], DataType::Void)
+    ));
+    assert!(!is_assignable(
+        &function(vec![DataType::Float, DataType::Float], DataType::Float),
+        &function(vec![DataType::Float], DataType::Float)
+    ));
+
+    let a_or_null = DataType::UnionType {
+        types: vec![DataType::Null, a.clone()],
+    };
+    let ab_or_null = DataType::UnionType {
+        types: vec![DataType::Null, ab.clone()],
+    };
+    assert!(is_assignable(&ab, &a_or_null));
+    assert!(is_assignable(&ab_or_null, &a_or_null));
+    assert!(!is_assignable(&a_or_null, &a));
+}
+
+#[test]
+fn test_structural_conversion() {
+    let input = "
+    const wide = { a: 1, b: \"b\" };
+    const literal: { a: number } = { a: 1, b: 2 };
+    const same: { a: number, b: string } = wide;
+    const widen: (x: number) => void = (x: number | string): void => {};
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    for (index, data_type) in [
+        (1, object(vec![("a", DataType::Float)])),
+        (3, function(vec![DataType::Float], DataType::Void)),
+    ] {
+        match get_declared_exp(&actual_output, index) {
+            Expression::StructuralConversion {
+                data_type: converted,
+                ..
+            } => assert!(converted.has_same_layout(&data_type)),
+            exp => panic!("Expected structural conversion but got {:?}", exp),
+        }
+    }
+
+    // Same layout is not converted
+    assert!(matches!(
+        get_declared_exp(&actual_output, 2),
+        Expression::IdentExp { .. }
+    ));
+
+    // Array or object in another layout would have to be copied
+    assert_eq!(
+        get_error("const wide = { a: 1, b: 2 }; const narrow: { a: number } = wide;"),
+        "Expected data type { a: number } but got { a: number; b: number }"
+    );
+    assert_eq!(
+        get_error("const nums = [1, 2]; const view: (number | string)[] = nums;"),
+        "Expected data type (number | string)[] but got number[]"
+    );
+}
+
+#[test]
+fn test_assignability_in_calls_and_returns() {
+    let input = "
+    interface Named { name: string }
+    function greet(named: Named): string { return named.name; }
+    function person(): Named { return { name: \"a\", age: 1 }; }
+    function each(names: Named[], callback: (named: Named, index: number) => void): void {}
+    function log(named: Named): number { return 1; }
+    const people: Named[] = [{ name: \"a\", age: 1 }];
+    greet({ name: \"b\", age: 2 });
+    each(people, log);
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+
+    assert_eq!(
+        get_error("function f(p: { a: number, b: number }): void {} f({ a: 1 });"),
+        "The datatype for 0 argument is { a: number; b: number } but got { a: number }"
+    );
+    assert_eq!(
+        get_error("const a = [{ a: 1 }]; let b: { a: string }[] = a;"),
+        "Expected data type { a: string }[] but got { a: number }[]"
+    );
+
+    // Narrower parameter cannot take the wider argument the caller passes
+    let error = get_error(
+        "function f(p: { a: number, b: number }): void {} const g: (p: { a: number }) => void = f;",
+    );
+    assert_eq!(
+        error,
+        "Expected data type (arg0: { a: number }) => void but got (arg0: { a: number; b: number }) => void"
+    );
+}
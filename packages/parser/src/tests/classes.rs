@@ -0,0 +1,266 @@
+use ast::{
+    class::AccessModifier,
+    data_type::{is_assignable, DataType},
+    declaration::Declaration,
+    expression::Expression,
+    Ast,
+};
+use lexer::convert_to_token;
+
+use super::{get_declared_exp, get_error};
+
+const ANIMALS: &str = "
+    class Animal {
+        protected name: string;
+        private legs: number = 4;
+
+        constructor(name: string) {
+            this.name = name;
+        }
+
+        speak(): string {
+            return this.name;
+        }
+
+        static create(name: string): Animal {
+            return new Animal(name);
+        }
+    }
+
+    class Dog extends Animal {
+        tricks?: number;
+
+        speak(): string {
+            return super.speak() + \" barks\";
+        }
+    }
+    ";
+
+#[test]
+fn test_class_declaration() {
+    let actual_output = crate::convert_to_ast(convert_to_token(ANIMALS));
+
+    if let Ast::Declaration(Declaration::ClassDeclaration {
+        class, functions, ..
+    }) = &actual_output[0]
+    {
+        assert_eq!(class.name, "Animal");
+        assert!(class.parents.is_empty());
+        assert_eq!(
+            class.fields.keys().collect::<Vec<_>>(),
+            vec!["name", "legs"]
+        );
+        assert_eq!(class.fields["name"].access, AccessModifier::Protected);
+        assert_eq!(class.fields["legs"].access, AccessModifier::Private);
+        assert_eq!(class.methods.keys().collect::<Vec<_>>(), vec!["speak"]);
+        assert_eq!(
+            class.static_methods.keys().collect::<Vec<_>>(),
+            vec!["create"]
+        );
+
+        // Constructor, speak and create
+        assert_eq!(functions.len(), 3);
+    } else {
+        panic!("Expected class declaration but got {:?}", actual_output[0]);
+    }
+
+    if let Ast::Declaration(Declaration::ClassDeclaration {
+        class, functions, ..
+    }) = &actual_output[1]
+    {
+        let animal = &class.parents[0];
+        assert_eq!(class.parents.len(), 1);
+
+        // Inherited fields come first and the override keeps the slot of the method
+        assert_eq!(
+            class.fields.keys().collect::<Vec<_>>(),
+            vec!["name", "legs", "tricks"]
+        );
+        assert_eq!(&class.fields["name"].owner, animal);
+        assert_eq!(class.fields["tricks"].owner, class.llvm_name);
+        assert_eq!(class.methods.keys().collect::<Vec<_>>(), vec!["speak"]);
+        assert_eq!(class.methods["speak"].owner, class.llvm_name);
+        assert_eq!(&class.static_methods["create"].owner, animal);
+
+        // Generated constructor and speak
+        assert_eq!(functions.len(), 2);
+    } else {
+        panic!("Expected class declaration but got {:?}", actual_output[1]);
+    }
+}
+
+#[test]
+fn test_instances_and_members() {
+    let input = format!(
+        "{}
+    const dog = new Dog(\"Rex\");
+    const animal: Animal = dog;
+    const sound = animal.speak();
+    const created = Animal.create(\"Cat\");
+    dog.tricks = 2;
+    ",
+        ANIMALS
+    );
+
+    let actual_output = crate::convert_to_ast(convert_to_token(&input));
+
+    // Instance of a subclass can be used as an instance of its parent but not the other way
+    let dog_type = get_declared_exp(&actual_output, 2).get_data_type();
+    let animal_type = get_declared_exp(&actual_output, 3).get_data_type();
+    assert!(is_assignable(&dog_type, &animal_type));
+    assert!(!is_assignable(&animal_type, &dog_type));
+
+    assert!(matches!(
+        get_declared_exp(&actual_output, 4),
+        Expression::MethodCall { method, return_type: DataType::String, .. } if method == "speak"
+    ));
+    assert_eq!(
+        get_declared_exp(&actual_output, 5).get_data_type(),
+        animal_type
+    );
+    assert!(matches!(
+        &actual_output[6],
+        Ast::Declaration(Declaration::FieldAssignment { field, .. }) if field == "tricks"
+    ));
+}
+
+#[test]
+fn test_instances_as_object_types() {
+    let input = "
+    class Square {
+        side: number = 1;
+        private secret(): number {
+            return 0;
+        }
+        area(): number {
+            return this.side;
+        }
+    }
+    const shape: { area(): number; label?: string } = new Square();
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    assert!(matches!(
+        get_declared_exp(&actual_output, 1),
+        Expression::StructuralConversion { .. }
+    ));
+
+    // Fields would be copied into the object, so only public methods can be used
+    let error = get_error("class A { x: number = 1; }\nconst a: { x: number } = new A();");
+    assert_eq!(error, "Expected data type { x: number } but got A");
+
+    let error = get_error(
+        "class A { private m(): number { return 1; } }\nconst a: { m(): number } = new A();",
+    );
+    assert_eq!(error, "Expected data type { m: () => number } but got A");
+
+    let error = get_error("class A { }\nconst a: number = new A();");
+    assert_eq!(error, "Expected data type number but got A");
+}
+
+#[test]
+fn test_access_modifiers() {
+    let error = get_error(&format!("{}\nconst d = new Dog(\"a\");\nd.legs;", ANIMALS));
+    assert_eq!(
+        error,
+        "legs is private and only accessible inside of class Animal"
+    );
+
+    let error = get_error(&format!("{}\nconst d = new Dog(\"a\");\nd.name;", ANIMALS));
+    assert!(error.starts_with("name is protected"), "{}", error);
+
+    let error = get_error(
+        "
+    class A {
+        private secret: number = 1;
+    }
+    class B extends A {
+        read(): number {
+            return this.secret;
+        }
+    }
+    ",
+    );
+    assert_eq!(
+        error,
+        "secret is private and only accessible inside of class A"
+    );
+
+    let error = get_error(
+        "
+    class A {
+        private constructor() {
+        }
+    }
+    const a = new A();
+    ",
+    );
+    assert!(error.contains("private"), "{}", error);
+}
+
+#[test]
+fn test_invalid_classes() {
+    let error = get_error(
+        "
+    class A {
+        x: number;
+    }
+    ",
+    );
+    assert_eq!(
+        error,
+        "Field x of class A has no initializer and is not assigned in the constructor"
+    );
+
+    let error = get_error(
+        "
+    class A {
+        constructor(x: number) {
+        }
+    }
+    class B extends A {
+        constructor() {
+            let y = 1;
+        }
+    }
+    ",
+    );
+    assert_eq!(
+        error,
+        "Constructor of class B has to call super(...) before anything else"
+    );
+
+    let error = get_error(
+        "
+    class A {
+        m(): number {
+            return 1;
+        }
+    }
+    class B extends A {
+        m(): string {
+            return \"1\";
+        }
+    }
+    ",
+    );
+    assert!(
+        error.starts_with("Method m of class B has to be of datatype"),
+        "{}",
+        error
+    );
+
+    let error = get_error("class A { static x: number; }");
+    assert_eq!(
+        error,
+        "Static field x of class A is not supported, only static methods are"
+    );
+
+    let error = get_error("class A { }\nconst a = new A();\na.missing = 1;");
+    assert_eq!(error, "Property missing does not exist on class A");
+
+    assert_eq!(
+        get_error("const self = this;"),
+        "Cannot use this outside of a method or a constructor"
+    );
+}
@@ -0,0 +1,160 @@
+use ast::{data_type::DataType, declaration::Declaration, expression::Expression, Ast, AstPtr};
+use indexmap::{indexmap, IndexMap};
+use lexer::convert_to_token;
+
+use super::get_error;
+
+fn get_function_blocks(ast: &AstPtr) -> &Vec<AstPtr> {
+    if let Ast::Declaration(Declaration::FunctionDeclaration { blocks, .. }) = ast {
+        return blocks;
+    } else {
+        panic!("Expected function declaration but got {:?}", ast);
+    }
+}
+
+// Closure declared by the variable declaration along with its captures
+fn get_closure(ast: &AstPtr) -> (DataType, &IndexMap<String, DataType>) {
+    if let Ast::Declaration(Declaration::VariableDeclaration {
+        exp:
+            Expression::Closure {
+                function,
+                data_type,
+                ..
+            },
+        ..
+    }) = ast
+    {
+        if let Ast::Declaration(Declaration::FunctionDeclaration { captures, .. }) = function {
+            return (data_type.clone(), captures);
+        }
+    }
+
+    panic!("Expected variable declaration of closure but got {:?}", ast);
+}
+
+fn is_captured(ast: &AstPtr) -> bool {
+    if let Ast::Declaration(Declaration::VariableDeclaration { is_captured, .. }) = ast {
+        return *is_captured;
+    } else {
+        panic!("Expected variable declaration but got {:?}", ast);
+    }
+}
+
+#[test]
+fn test_captured_variables() {
+    let input = "
+    function makeCounter(): () => number {
+        let count = 0;
+        let unused = 1;
+        const next = () => {
+            count += 1;
+            return count;
+        };
+        return next;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let blocks = get_function_blocks(&actual_output[0]);
+
+    assert!(is_captured(&blocks[0]));
+    assert!(!is_captured(&blocks[1]));
+
+    let (data_type, captures) = get_closure(&blocks[2]);
+    assert_eq!(
+        data_type,
+        DataType::FunctionType {
+            arguments: vec![],
+            return_type: Box::new(DataType::Float)
+        }
+    );
+    assert_eq!(
+        captures,
+        &indexmap! {"count|_|".to_string() => DataType::Float}
+    );
+}
+
+#[test]
+fn test_nested_captures() {
+    let input = "
+    function outer(x: number): number {
+        function middle(): number {
+            const inner = function (): number {
+                return x;
+            };
+            return inner();
+        }
+        return middle();
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let blocks = get_function_blocks(&actual_output[0]);
+
+    /*
+     * Parameter is copied into a captured binding, a function between the
+     * declaration and the use of a variable captures it as well
+     *
+     * */
+    assert!(is_captured(&blocks[0]));
+    let (_, captures) = get_closure(&blocks[1]);
+    assert_eq!(captures.len(), 1);
+}
+
+#[test]
+fn test_arrow_function_types() {
+    let input = "
+    const offset = 1;
+    const add = (a: number, b: number) => a + b + offset;
+    const greet = (name: string): string => \"hi \" + name;
+    const log = () => {
+        console.log(offset);
+    };
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (add, captures) = get_closure(&actual_output[1]);
+    assert_eq!(
+        add,
+        DataType::FunctionType {
+            arguments: vec![DataType::Float, DataType::Float],
+            return_type: Box::new(DataType::Float)
+        }
+    );
+    // Variables at the top of the file are locals of main, so they are captured too
+    assert_eq!(
+        captures,
+        &indexmap! {"offset|_|".to_string() => DataType::Float}
+    );
+
+    let (greet, _) = get_closure(&actual_output[2]);
+    assert_eq!(
+        greet,
+        DataType::FunctionType {
+            arguments: vec![DataType::String],
+            return_type: Box::new(DataType::String)
+        }
+    );
+
+    let (log, _) = get_closure(&actual_output[3]);
+    assert_eq!(
+        log,
+        DataType::FunctionType {
+            arguments: vec![],
+            return_type: Box::new(DataType::Void)
+        }
+    );
+}
+
+#[test]
+fn test_closure_errors() {
+    assert_eq!(
+        get_error("const double = x => x * 2;"),
+        "Parameter x of arrow function needs a type annotation"
+    );
+    assert_eq!(
+        get_error("const f = (x: number): string => x;"),
+        "Expected the return_type declared in function declaration does not match the data_type of expression which the function is returning"
+    );
+}
@@ -0,0 +1,85 @@
+use ast::{
+    data_type::DataType,
+    declaration::{Declaration, VariableDeclarationKind},
+    erase_spans,
+    expression::{ConsoleMethod, Expression},
+    Ast, AstPtr,
+};
+use lexer::{convert_to_token, token::Span};
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_console_log() {
+    let input = "console.log(1, 'a', true);";
+
+    let expected_output = vec![Ast::new_variable_declaration(
+        "|_temp0",
+        Expression::ConsoleCall {
+            method: ConsoleMethod::Log,
+            arguments: vec![
+                Expression::FloatLiteralExp {
+                    name: "1".to_string(),
+                    value: 1.0,
+                    span: Span::default(),
+                },
+                Expression::StringLiteralExp {
+                    value: "a".to_string(),
+                    span: Span::default(),
+                },
+                Expression::BooleanLiteralExp {
+                    name: "true".to_string(),
+                    value: true,
+                    span: Span::default(),
+                },
+            ],
+            span: Span::default(),
+        },
+        VariableDeclarationKind::Const,
+        Span::default(),
+    )];
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(erase_spans(&actual_output), erase_spans(&expected_output));
+}
+
+#[test]
+fn test_console_error_without_arguments() {
+    let input = "console.error();";
+
+    let expected_output = vec![Ast::new_variable_declaration(
+        "|_temp0",
+        Expression::ConsoleCall {
+            method: ConsoleMethod::Error,
+            arguments: vec![],
+            span: Span::default(),
+        },
+        VariableDeclarationKind::Const,
+        Span::default(),
+    )];
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(erase_spans(&actual_output), erase_spans(&expected_output));
+    assert_eq!(get_data_type_of_first(&actual_output), DataType::Void);
+}
+
+#[test]
+fn test_console_unknown_method() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("console.warn(1);"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected console.warn to be rejected");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("console can only be used as console.log(...) or console.error(...)"));
+}
+
+fn get_data_type_of_first(asts: &[AstPtr]) -> DataType {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*asts[0] {
+        return exp.get_data_type();
+    } else {
+        panic!("Expected console call to be bound to a temporary variable");
+    }
+}
@@ -0,0 +1,144 @@
+use lexer::{convert_to_token, diagnostic::Diagnostic, token::Position};
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+fn get_diagnostics(input: &str) -> Vec<Diagnostic> {
+    let resolver = &mut DummyImportResolver::new();
+
+    match consume_token(convert_to_token(input), resolver, None) {
+        Ok(_) => panic!("Expected input {:?} to fail parsing", input),
+        Err(diagnostics) => return diagnostics,
+    }
+}
+
+#[test]
+fn test_unknown_variable() {
+    let input = "const x = 1;\nconst y = x + z;";
+
+    let diagnostics = get_diagnostics(input);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Unknown variable z");
+
+    let span = diagnostics[0].primary.as_ref().unwrap().span;
+    assert_eq!(span.start, Position::new(27, 2, 15));
+
+    let expected_output = "error: Unknown variable z
+ --> main.ts:2:15
+  |
+2 | const y = x + z;
+  |               ^
+";
+    assert_eq!(diagnostics[0].render("main.ts", input), expected_output);
+}
+
+#[test]
+fn test_unexpected_end_of_file() {
+    let inputs = ["const x = ", "while (true) {", "let y = [1, 2"];
+
+    for input in inputs {
+        let diagnostics = get_diagnostics(input);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+    }
+}
+
+fn get_messages_with_line(input: &str) -> Vec<(usize, String)> {
+    return get_diagnostics(input)
+        .into_iter()
+        .map(|diagnostic| {
+            let line = diagnostic.primary.as_ref().unwrap().span.start.line;
+            return (line, diagnostic.message);
+        })
+        .collect();
+}
+
+#[test]
+fn test_multiple_errors() {
+    let input = "const x = 1;
+const y = x + ;
+let z : boolean = 1;
+const w = x + 2;
+w = 5;
+const v = w;";
+
+    let expected_output = vec![
+        (
+            2,
+            "Given token SemiColon does not have not a prefix function".to_string(),
+        ),
+        (3, "Expected data type boolean but got number".to_string()),
+        (5, "Cannot reassign a const variable".to_string()),
+    ];
+
+    assert_eq!(get_messages_with_line(input), expected_output);
+}
+
+#[test]
+fn test_no_errors_for_uses_of_failed_declaration() {
+    let input = "let fns: number[] = 1;
+fns.push(2);
+console.log(fns);
+const y = fns[0];
+const w = z;";
+
+    let expected_output = vec![
+        (1, "Expected data type number[] but got number".to_string()),
+        (5, "Unknown variable z".to_string()),
+    ];
+
+    assert_eq!(get_messages_with_line(input), expected_output);
+}
+
+#[test]
+fn test_errors_inside_blocks() {
+    let input = "let x = 1;
+while (x < 10) {
+    x = true;
+    let y = ;
+    x += 1;
+}
+function foo() : number {
+    const b : boolean = 1;
+    return 1;
+}
+const a = foo();";
+
+    let diagnostics = get_messages_with_line(input);
+    let lines: Vec<usize> = diagnostics.iter().map(|(line, _)| *line).collect();
+
+    assert_eq!(lines, vec![3, 4, 8]);
+}
+
+#[test]
+fn test_stray_close_bracket() {
+    let input = "const x = 1;
+}
+const y = x;
+const z = ;";
+
+    let lines: Vec<usize> = get_messages_with_line(input)
+        .iter()
+        .map(|(line, _)| *line)
+        .collect();
+
+    assert_eq!(lines, vec![2, 4]);
+}
+
+#[test]
+fn test_no_errors_for_uses_of_failed_types() {
+    let input = "type Maybe = number | Foo;
+const m: Maybe = 1;
+enum K { A = 1, B = \"x\" - 1 }
+const k = K.B;
+class C { x: Bar = 1; }
+const c = new C();
+const n: Nope = 1;";
+
+    let lines: Vec<usize> = get_messages_with_line(input)
+        .iter()
+        .map(|(line, _)| *line)
+        .collect();
+
+    assert_eq!(lines, vec![1, 3, 5, 7]);
+}
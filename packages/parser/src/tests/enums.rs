@@ -0,0 +1,227 @@
+use ast::{
+    data_type::DataType,
+    declaration::Declaration,
+    enumeration::{Enum, EnumValue},
+    expression::Expression,
+    Ast, AstPtr,
+};
+use lexer::{convert_to_token, diagnostic::Severity};
+
+use super::get_error;
+use crate::{consume_token, traits::DummyImportResolver};
+
+fn get_enum(ast: &AstPtr) -> (&Enum, &Option<AstPtr>) {
+    if let Ast::Declaration(Declaration::EnumDeclaration {
+        enumeration,
+        reverse_mapping,
+        ..
+    }) = ast
+    {
+        return (enumeration, reverse_mapping);
+    }
+
+    panic!("Expected enum declaration but got {:?}", ast);
+}
+
+fn get_initializer(ast: &AstPtr) -> &Expression {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = ast {
+        return exp;
+    }
+
+    panic!("Expected variable declaration but got {:?}", ast);
+}
+
+#[test]
+fn test_numeric_enum() {
+    let input = "
+    enum Color { Red, Green = 5, Blue }
+    let c = Color.Blue;
+    ";
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (enumeration, reverse_mapping) = get_enum(&actual_output[0]);
+    assert_eq!(
+        enumeration.members.values().cloned().collect::<Vec<_>>(),
+        vec![
+            EnumValue::Number(0.0),
+            EnumValue::Number(5.0),
+            EnumValue::Number(6.0)
+        ]
+    );
+    assert!(matches!(
+        reverse_mapping,
+        Some(Ast::Declaration(Declaration::FunctionDeclaration { .. }))
+    ));
+
+    // Member is replaced by its value
+    assert!(matches!(
+        get_initializer(&actual_output[1]),
+        Expression::FloatLiteralExp { value, .. } if *value == 6.0
+    ));
+}
+
+#[test]
+fn test_string_enum() {
+    let input = r#"
+    enum Dir { Up = "UP", Down = "DOWN" }
+    let d: string = Dir.Down + "!";
+    "#;
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (enumeration, reverse_mapping) = get_enum(&actual_output[0]);
+    assert!(!enumeration.is_numeric());
+    assert!(reverse_mapping.is_none());
+
+    assert_eq!(
+        get_initializer(&actual_output[1]).get_data_type(),
+        DataType::String
+    );
+}
+
+#[test]
+fn test_const_enum_is_inlined() {
+    let input = "
+    const enum Size { Small = -1, Large }
+    let s = Size.Large;
+    ";
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (enumeration, reverse_mapping) = get_enum(&actual_output[0]);
+    assert!(enumeration.is_const);
+    assert!(reverse_mapping.is_none());
+
+    assert!(matches!(
+        get_initializer(&actual_output[1]),
+        Expression::FloatLiteralExp { value, .. } if *value == 0.0
+    ));
+}
+
+#[test]
+fn test_reverse_mapping() {
+    let input = "
+    enum Color { Red, Green, Blue }
+    let name = Color[1];
+    ";
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (enumeration, _) = get_enum(&actual_output[0]);
+    if let Expression::FunctionCall {
+        fn_exp,
+        return_type,
+        ..
+    } = get_initializer(&actual_output[1])
+    {
+        assert!(matches!(
+            fn_exp.as_ref(),
+            Expression::IdentExp { name, .. } if *name == enumeration.get_reverse_mapping_name()
+        ));
+        assert_eq!(
+            return_type,
+            &DataType::UnionType {
+                types: vec![DataType::String, DataType::Undefined]
+            }
+        );
+    } else {
+        panic!(
+            "Expected call to the reverse mapping but got {:?}",
+            actual_output[1]
+        );
+    }
+}
+
+#[test]
+fn test_enum_assignability() {
+    let input = "
+    enum Color { Red, Green, Blue }
+    let n: number = Color.Green;
+    let c: Color = Color.Red;
+    c = Color.Blue;
+    ";
+    crate::convert_to_ast(convert_to_token(input));
+
+    // Enum is named as in the source
+    assert_eq!(
+        get_error(
+            "
+        enum Color { Red, Green, Blue }
+        let c: Color = 1;
+        "
+        ),
+        "Expected data type Color but got number"
+    );
+}
+
+#[test]
+fn test_enum_errors() {
+    assert_eq!(
+        get_error(r#"enum A { X, Y = "y" }"#),
+        "Members of enum A have to be all numbers or all strings"
+    );
+    assert_eq!(
+        get_error(r#"enum A { X = "x", Y }"#),
+        "Enum member Y needs an initializer since the member before it is a string"
+    );
+    assert_eq!(get_error("enum A { X, X }"), "Duplicate member X in enum A");
+    assert_eq!(
+        get_error("enum A { X = Y }"),
+        "Initializer of enum member X has to be a number or a string literal"
+    );
+    assert_eq!(
+        get_error("enum A { X } let y = A.Y;"),
+        "Enum A has no member Y"
+    );
+    assert_eq!(
+        get_error("enum A { X } let a = A;"),
+        "Enum A can only be used to access its members"
+    );
+    assert_eq!(
+        get_error("const enum A { X } let a = A[0];"),
+        "Enum A can not be indexed, only numeric enums which are not const have a reverse mapping"
+    );
+    assert_eq!(
+        get_error("function f(): void { enum A { X } }"),
+        "Enums can only be declared at the top of the file"
+    );
+}
+
+#[test]
+fn test_switch_on_enum_exhaustiveness() {
+    let input = r#"
+    enum Dir { Up = "UP", Down = "DOWN" }
+    let d: Dir = Dir.Up;
+    switch (d) {
+        case Dir.Up:
+            break;
+        case Dir.Down:
+            break;
+    }
+    switch (d) {
+        case Dir.Up:
+            break;
+        default:
+            break;
+    }
+    "#;
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+    assert!(resolver.warnings.is_empty());
+
+    // Missing member is only a warning, the switch still compiles
+    let input = "
+    enum Color { Red, Green, Blue }
+    let c: Color = Color.Red;
+    switch (c) {
+        case Color.Green:
+            break;
+    }
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+
+    assert_eq!(resolver.warnings.len(), 1);
+    assert_eq!(resolver.warnings[0].severity, Severity::Warning);
+    assert_eq!(
+        resolver.warnings[0].message,
+        "switch on enum Color does not handle Red, Blue, add the missing cases or a default case"
+    );
+}
@@ -0,0 +1,96 @@
+use ast::{
+    data_type::DataType,
+    declaration::Declaration,
+    expression::{BinaryOperator, Expression},
+    Ast,
+};
+use lexer::{convert_to_token, diagnostic::Severity};
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_loose_equality() {
+    let input = "
+    const x = 1 == \"1\" != true;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*actual_output[0] {
+        assert_eq!(exp.get_data_type(), DataType::Boolean);
+
+        if let Expression::BinaryExp {
+            operator: BinaryOperator::NotEqual,
+            left,
+            ..
+        } = exp
+        {
+            assert!(matches!(
+                left.as_ref(),
+                Expression::BinaryExp {
+                    operator: BinaryOperator::Equality,
+                    ..
+                }
+            ));
+        } else {
+            panic!("Expected 1 == \"1\" != true to be parsed as (1 == \"1\") != true");
+        }
+    } else {
+        panic!("Expected const declaration");
+    }
+}
+
+#[test]
+fn test_loose_equality_with_array() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("const x = [1] == 1;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected array compared with a number to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Cannot compare number[] with number using ==, converting an array or a function to a primitive is not supported"
+    );
+
+    // Arrays are compared by reference with each other
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const x = [1]; const y = x != [1];"),
+        resolver,
+        None,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_loose_equality_lint() {
+    let input = "
+    function f(a: number): boolean {
+        return a == 1;
+    }
+    const x = f(1) != false;
+    const y = 1 === 1;
+    ";
+
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+    assert!(resolver.warnings.is_empty());
+
+    let resolver = &mut DummyImportResolver::new_strict();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+
+    let messages: Vec<&str> = resolver
+        .warnings
+        .iter()
+        .map(|warning| {
+            assert_eq!(warning.severity, Severity::Warning);
+            return warning.message.as_str();
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Use === instead of == which converts operands of different data types",
+            "Use !== instead of != which converts operands of different data types"
+        ]
+    );
+}
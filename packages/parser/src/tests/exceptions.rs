@@ -0,0 +1,162 @@
+use ast::{declaration::Declaration, expression::Expression, Ast, AstPtr};
+use lexer::convert_to_token;
+
+use super::get_error;
+
+fn get_thrown_expression(ast: &AstPtr) -> &Expression {
+    if let Ast::Declaration(Declaration::ThrowStatement { exp, .. }) = ast {
+        return exp;
+    } else {
+        panic!("Expected throw statement but got {:?}", ast);
+    }
+}
+
+#[test]
+fn test_try_statement() {
+    let input = "
+    try {
+        throw 1;
+    } catch (e) {
+        let message = typeof e;
+    } finally {
+        let done = true;
+    }
+
+    try {
+        throw \"no catch\";
+    } finally {
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::TryStatement {
+        try_block,
+        catch_clause: Some(catch_clause),
+        finally_block: Some(finally_block),
+        ..
+    }) = &actual_output[0]
+    {
+        assert_eq!(try_block.len(), 1);
+        assert!(catch_clause.binding.is_some());
+        assert_eq!(catch_clause.block.len(), 1);
+        assert_eq!(finally_block.len(), 1);
+    } else {
+        panic!("Expected try statement but got {:?}", actual_output[0]);
+    }
+
+    assert!(matches!(
+        &actual_output[1],
+        Ast::Declaration(Declaration::TryStatement {
+            catch_clause: None,
+            finally_block: Some(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_thrown_value_is_boxed() {
+    let input = "
+    function fail(reason: string): void {
+        throw reason;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::FunctionDeclaration { blocks, .. }) = &actual_output[0] {
+        let exp = get_thrown_expression(&blocks[0]);
+        assert_eq!(exp.get_data_type(), ast::data_type::get_exception_type());
+    } else {
+        panic!(
+            "Expected function declaration but got {:?}",
+            actual_output[0]
+        );
+    }
+}
+
+#[test]
+fn test_catch_binding_has_to_be_narrowed() {
+    let input = "
+    try {
+        throw 1;
+    } catch (e) {
+        if (typeof e === \"number\") {
+            let n: number = e;
+        }
+    }
+    ";
+    crate::convert_to_ast(convert_to_token(input));
+
+    let input = "
+    try {
+        throw 1;
+    } catch (e) {
+        let n: number = e;
+    }
+    ";
+    let error = get_error(input);
+    assert!(error.starts_with("Expected data type number"), "{}", error);
+}
+
+#[test]
+fn test_invalid_try_and_throw() {
+    assert_eq!(
+        get_error("try { } let x = 1;"),
+        "Expected try block to be followed by catch or finally"
+    );
+
+    let error = get_error("try { } catch (e: number) { }");
+    assert!(
+        error.starts_with("Catch binding e can only be annotated with"),
+        "{}",
+        error
+    );
+
+    let error = get_error("function f(): void {} throw f;");
+    assert!(
+        error.starts_with(
+            "Only numbers, strings, booleans, null, undefined and objects can be thrown"
+        ),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_thrown_object_is_narrowed_by_instanceof() {
+    let input = "
+    class MyError {
+        message: string;
+
+        constructor(message: string) {
+            this.message = message;
+        }
+    }
+
+    try {
+        throw [1, 2];
+    } catch (e) {
+        if (e instanceof MyError) {
+            let message: string = e.message;
+        }
+    }
+    throw new MyError(\"boom\");
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let exp = get_thrown_expression(&actual_output[2]);
+    assert_eq!(exp.get_data_type(), ast::data_type::get_exception_type());
+
+    let input = "
+    try {
+        throw { code: 1 };
+    } catch (e) {
+        let code: object = e;
+    }
+    ";
+    let error = get_error(input);
+    assert!(error.starts_with("Expected data type object"), "{}", error);
+}
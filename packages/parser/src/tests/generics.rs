@@ -0,0 +1,239 @@
+use ast::{
+    data_type::DataType,
+    declaration::Declaration,
+    expression::{BinaryOperator, Expression},
+    Ast, AstPtr,
+};
+use lexer::convert_to_token;
+
+use super::get_error;
+
+// Type arguments and return type of the generic function called in the declaration
+fn get_instance(ast: &AstPtr) -> (&Vec<DataType>, &DataType) {
+    if let Ast::Declaration(Declaration::VariableDeclaration {
+        exp:
+            Expression::FunctionCall {
+                fn_exp,
+                return_type,
+                ..
+            },
+        ..
+    }) = ast
+    {
+        if let Expression::GenericInstance { type_arguments, .. } = fn_exp.as_ref() {
+            return (type_arguments, return_type);
+        }
+    }
+
+    panic!("Expected call to a generic function but got {:?}", ast);
+}
+
+const UTILS: &str = "
+    function identity<T>(x: T): T {
+        return x;
+    }
+
+    function map<T, U>(arr: T[], f: (x: T) => U): U[] {
+        let out: U[] = [];
+        for (const item of arr) {
+            out.push(f(item));
+        }
+        return out;
+    }
+    ";
+
+#[test]
+fn test_generic_function_declaration() {
+    let actual_output = crate::convert_to_ast(convert_to_token(UTILS));
+
+    if let Ast::Declaration(Declaration::GenericFunctionDeclaration { function, .. }) =
+        &actual_output[1]
+    {
+        assert_eq!(function.type_parameters, vec!["T", "U"]);
+        assert!(matches!(
+            function.function,
+            Some(Ast::Declaration(Declaration::FunctionDeclaration { .. }))
+        ));
+
+        // Every list of type arguments gets a function of its own
+        assert_ne!(
+            function.get_instance_name(&[DataType::Float, DataType::String]),
+            function.get_instance_name(&[DataType::String, DataType::Float])
+        );
+    } else {
+        panic!(
+            "Expected generic function declaration but got {:?}",
+            actual_output[1]
+        );
+    }
+}
+
+#[test]
+fn test_type_argument_inference() {
+    let input = format!(
+        "{}
+    const n = identity(1);
+    const s = identity<string>(\"a\");
+    const strings = map([1, 2], (x: number): string => \"n\" + x);
+    const v: number | string = 1;
+    const u = identity(v);
+    ",
+        UTILS
+    );
+
+    let actual_output = crate::convert_to_ast(convert_to_token(&input));
+
+    assert_eq!(
+        get_instance(&actual_output[2]),
+        (&vec![DataType::Float], &DataType::Float)
+    );
+    assert_eq!(
+        get_instance(&actual_output[3]),
+        (&vec![DataType::String], &DataType::String)
+    );
+
+    let (type_arguments, return_type) = get_instance(&actual_output[4]);
+    assert_eq!(type_arguments, &vec![DataType::Float, DataType::String]);
+    assert_eq!(
+        return_type,
+        &DataType::ArrayType {
+            base_type: Box::new(DataType::String)
+        }
+    );
+
+    let (type_arguments, _) = get_instance(&actual_output[6]);
+    assert!(matches!(type_arguments[0], DataType::UnionType { .. }));
+}
+
+#[test]
+fn test_generic_type_alias() {
+    let input = "
+    type Box<T> = { value: T };
+    type Pair<A, B> = { first: A; second: B };
+
+    function wrap<T>(x: T): Box<T> {
+        const b: Box<T> = { value: x };
+        return b;
+    }
+
+    const boxed: Box<Box<number>> = wrap(wrap(1));
+    const pair: Pair<string, Box<number>> = { first: \"a\", second: { value: 1 } };
+    let flag = 1 >> 1;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let (type_arguments, _) = get_instance(&actual_output[3]);
+    if let DataType::ObjectType { entries } = &type_arguments[0] {
+        assert_eq!(entries["value"], DataType::Float);
+    } else {
+        panic!("Expected object type but got {:?}", type_arguments[0]);
+    }
+
+    // `>>` is still a shift outside of type arguments
+    assert!(matches!(
+        &actual_output[5],
+        Ast::Declaration(Declaration::VariableDeclaration {
+            exp: Expression::BinaryExp {
+                operator: BinaryOperator::RightShift,
+                ..
+            },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_type_parameters_in_unions() {
+    let input = "
+    type Maybe<T> = T | null;
+
+    function orElse<T>(x: T | null, d: T): T {
+        let result = d;
+        if (x !== null) {
+            result = x;
+        }
+        return result;
+    }
+
+    const a: Maybe<number> = null;
+    const b = orElse(a, 1);
+    const c = orElse<null>(null, null);
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    // Members left by the other members of the parameter are the type argument
+    assert_eq!(
+        get_instance(&actual_output[3]),
+        (&vec![DataType::Float], &DataType::Float)
+    );
+
+    // Union keeps boxing its value when the type argument is one of its members
+    if let Ast::Declaration(Declaration::VariableDeclaration {
+        exp: Expression::FunctionCall { fn_exp, .. },
+        ..
+    }) = &actual_output[4]
+    {
+        if let DataType::FunctionType { arguments, .. } = fn_exp.get_data_type() {
+            assert_eq!(
+                arguments[0],
+                DataType::UnionType {
+                    types: vec![DataType::Null]
+                }
+            );
+        } else {
+            panic!(
+                "Expected function type but got {:?}",
+                fn_exp.get_data_type()
+            );
+        }
+    } else {
+        panic!("Expected call to orElse but got {:?}", actual_output[4]);
+    }
+
+    let error = get_error("type Maybe<T> = T | null;\nconst x: Maybe<void> = null;");
+    assert_eq!(error, "Data type void cannot be a member of a union type");
+}
+
+#[test]
+fn test_invalid_generics() {
+    let error = get_error(&format!("{}\nconst f = identity;", UTILS));
+    assert_eq!(
+        error,
+        "Generic function identity can only be called, it cannot be used as a value"
+    );
+
+    let error = get_error("function f<T>(): number { return 1; }\nf();");
+    assert_eq!(
+        error,
+        "Cannot infer type argument T of generic function f, pass it as f<...>(...)"
+    );
+
+    let error = get_error(&format!("{}\nidentity<number, string>(1);", UTILS));
+    assert_eq!(
+        error,
+        "Generic function identity takes 1 type arguments but got 2"
+    );
+
+    let error = get_error(
+        "
+    function outer(): number {
+        function inner<T>(x: T): T {
+            return x;
+        }
+        return 1;
+    }
+    ",
+    );
+    assert_eq!(
+        error,
+        "Generic function inner has to be declared at the top of the file"
+    );
+
+    let error = get_error("type Box<T> = { value: T };\nconst b: Box = { value: 1 };");
+    assert_eq!(error, "Generic type Box requires 1 type arguments");
+
+    let error = get_error("function f<T, T>(x: T): T { return x; }");
+    assert_eq!(error, "Type parameter T is declared more than once");
+}
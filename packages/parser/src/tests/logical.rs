@@ -0,0 +1,122 @@
+use ast::{
+    data_type::DataType,
+    declaration::Declaration,
+    expression::{BinaryOperator, Expression, LogicalOperator},
+    Ast, AstPtr,
+};
+use lexer::convert_to_token;
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_logical_precedence() {
+    let input = "
+    const x = true || false && true;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Expression::LogicalExp {
+        operator: LogicalOperator::Or,
+        right,
+        ..
+    } = get_initializer(&actual_output[0])
+    {
+        assert!(matches!(
+            right.as_ref(),
+            Expression::LogicalExp {
+                operator: LogicalOperator::And,
+                ..
+            }
+        ));
+    } else {
+        panic!("Expected && to bind tighter than ||");
+    }
+}
+
+#[test]
+fn test_conditional_expression() {
+    let input = "
+    const x = 1;
+    const kind = x > 3 ? \"big\" : x > 1 ? \"medium\" : \"small\";
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let initializer = get_initializer(&actual_output[1]);
+
+    assert_eq!(initializer.get_data_type(), DataType::String);
+
+    if let Expression::ConditionalExp {
+        test, alternate, ..
+    } = initializer
+    {
+        assert_eq!(test.get_data_type(), DataType::Boolean);
+        assert!(matches!(
+            alternate.as_ref(),
+            Expression::ConditionalExp { .. }
+        ));
+    } else {
+        panic!("Expected conditional expression to be parsed as Expression::ConditionalExp");
+    }
+}
+
+#[test]
+fn test_binary_expression_is_left_associative() {
+    let input = "
+    const x = 1 - 2 * 3 - 4;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Expression::BinaryExp {
+        operator: BinaryOperator::Minus,
+        left,
+        right,
+        ..
+    } = get_initializer(&actual_output[0])
+    {
+        assert!(matches!(
+            left.as_ref(),
+            Expression::BinaryExp {
+                operator: BinaryOperator::Minus,
+                ..
+            }
+        ));
+        assert!(matches!(right.as_ref(), Expression::FloatLiteralExp { .. }));
+    } else {
+        panic!("Expected 1 - 2 * 3 - 4 to be parsed as (1 - 2 * 3) - 4");
+    }
+}
+
+#[test]
+fn test_mismatched_branches() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const x = true ? 1 : \"one\";"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected branches of different data types to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected both sides of conditional expression to have same datatype but got number and string"
+    );
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("const x = 0 || false;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected sides of different data types to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected both sides of || to have same datatype but got number and boolean"
+    );
+}
+
+fn get_initializer(ast: &AstPtr) -> &Expression {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &**ast {
+        return exp;
+    }
+
+    panic!("Expected ast to be a variable declaration");
+}
@@ -0,0 +1,287 @@
+use ast::{data_type::DataType, declaration::Declaration, expression::Expression, Ast, AstPtr};
+use lexer::{convert_to_token, token::KeywordKind};
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_for_loop() {
+    let input = "
+    let total = 0;
+    for (let i = 0; i < 3; i += 1) {
+        total += i;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::ForLoopDeclaration {
+        initializer,
+        condition,
+        update,
+        block,
+        ..
+    }) = &*actual_output[1]
+    {
+        assert!(matches!(
+            initializer.as_ref().map(|ast| &**ast),
+            Some(Ast::Declaration(Declaration::VariableDeclaration { .. }))
+        ));
+        assert_eq!(
+            condition
+                .as_ref()
+                .map(|condition| condition.get_data_type()),
+            Some(DataType::Boolean)
+        );
+        assert!(matches!(
+            update.as_ref().map(|ast| &**ast),
+            Some(Ast::Declaration(Declaration::VariableAssignment { .. }))
+        ));
+        assert_eq!(block.len(), 1);
+    } else {
+        panic!("Expected for loop to be parsed as Declaration::ForLoopDeclaration");
+    }
+}
+
+#[test]
+fn test_for_loop_without_clauses() {
+    let input = "
+    for (;;) {
+        break;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::ForLoopDeclaration {
+        initializer,
+        condition,
+        update,
+        ..
+    }) = &*actual_output[0]
+    {
+        assert!(initializer.is_none());
+        assert!(condition.is_none());
+        assert!(update.is_none());
+    } else {
+        panic!("Expected for loop to be parsed as Declaration::ForLoopDeclaration");
+    }
+}
+
+#[test]
+fn test_for_loop_variable_is_scoped() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("for (let i = 0; i < 3; i += 1) {} const x = i;"),
+        resolver,
+        None,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_for_loop_condition_type() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("for (let i = 0; i; i += 1) {}"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected condition of data type number to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected condition of for loop to be of data type boolean but got number"
+    );
+}
+
+#[test]
+fn test_for_of_loop() {
+    let input = "
+    const words = [\"a\", \"b\"];
+    for (const word of words) {
+        console.log(word);
+    }
+    for (const character of \"hello\") {
+        console.log(character);
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    for ast in &actual_output[1..] {
+        if let Ast::Declaration(Declaration::ForOfLoopDeclaration { block, .. }) = &**ast {
+            assert_eq!(get_data_type_of_argument(&block[0]), DataType::String);
+        } else {
+            panic!("Expected for...of loop to be parsed as Declaration::ForOfLoopDeclaration");
+        }
+    }
+}
+
+#[test]
+fn test_for_in_loop() {
+    let input = "
+    const point = { x: 1, y: 2 };
+    for (const key in point) {
+        console.log(key);
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::ForInLoopDeclaration { block, .. }) = &*actual_output[1] {
+        assert_eq!(get_data_type_of_argument(&block[0]), DataType::String);
+    } else {
+        panic!("Expected for...in loop to be parsed as Declaration::ForInLoopDeclaration");
+    }
+}
+
+#[test]
+fn test_for_of_and_in_loop_iterables() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("for (const x of 1) {}"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected for...of over a number to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "for...of can only iterate over arrays and strings but got number"
+    );
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("for (const x in [1]) {}"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected for...in over an array to be rejected");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("for...in can only iterate over the keys of objects"));
+}
+
+#[test]
+fn test_for_of_const_variable() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("for (const x of [1]) { x = 2; }"),
+        resolver,
+        None,
+    );
+
+    assert!(result.is_err());
+}
+
+/*
+ * Data type of the first argument of `console.log(<argument>)`
+ *
+ * */
+fn get_data_type_of_argument(ast: &AstPtr) -> DataType {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &**ast {
+        if let Expression::ConsoleCall { arguments, .. } = exp {
+            return arguments[0].get_data_type();
+        }
+    }
+
+    panic!("Expected ast to be a console.log call");
+}
+
+#[test]
+fn test_labeled_loop() {
+    let input = "
+    outer: while (true) {
+        for (let i = 0; i < 3; i += 1) {
+            continue outer;
+        }
+        break;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::LabeledStatement {
+        label, statement, ..
+    }) = &*actual_output[0]
+    {
+        assert_eq!(label, "outer");
+
+        if let Ast::Declaration(Declaration::WhileLoopDeclaration { block, .. }) = &**statement {
+            if let Ast::Declaration(Declaration::ForLoopDeclaration { block, .. }) = &*block[0] {
+                assert!(matches!(
+                    &*block[0],
+                    Ast::Declaration(Declaration::LoopControlFlow {
+                        keyword: KeywordKind::Continue,
+                        label: Some(label),
+                        ..
+                    }) if label == "outer"
+                ));
+            } else {
+                panic!("Expected for loop inside of the labeled loop");
+            }
+
+            assert!(matches!(
+                &*block[1],
+                Ast::Declaration(Declaration::LoopControlFlow {
+                    keyword: KeywordKind::Break,
+                    label: None,
+                    ..
+                })
+            ));
+        } else {
+            panic!("Expected label to be followed by a while loop");
+        }
+    } else {
+        panic!("Expected labeled loop to be parsed as Declaration::LabeledStatement");
+    }
+}
+
+#[test]
+fn test_unknown_label() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("outer: while (true) { break inner; }"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected unknown label to be rejected");
+    assert_eq!(diagnostics[0].message, "Unknown label inner used by break");
+
+    // Label of a loop is not visible after the loop
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("outer: while (true) { break; } while (true) { continue outer; }"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected label of a finished loop to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Unknown label outer used by continue"
+    );
+}
+
+#[test]
+fn test_loop_control_flow_outside_of_loop() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("if (true) { break; }"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected break outside of a loop to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Cannot use break outside of a loop or switch"
+    );
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("while (true) { function f() { continue; } }"),
+        resolver,
+        None,
+    );
+    assert!(result.is_err());
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("outer: const x = 1;"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected label on a declaration to be rejected");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("Expected label outer to be followed by a loop"));
+}
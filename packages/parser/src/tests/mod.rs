@@ -1 +1,56 @@
+mod arithmetic;
+mod arrays;
+mod assignability;
+mod classes;
+mod closures;
+mod console;
+mod diagnostics;
+mod enums;
+mod equality;
+mod exceptions;
+mod generics;
+mod logical;
+mod loops;
+mod nullable;
 mod parsing_types;
+mod spans;
+mod switch;
+mod template_literals;
+mod type_aliases;
+mod unions;
+
+use ast::{data_type::DataType, declaration::Declaration, expression::Expression, Ast, AstPtr};
+use lexer::convert_to_token;
+
+use crate::{
+    consume_token, parser::Parser, symbol_table::SymbolContext, traits::DummyImportResolver,
+};
+
+// Helpers shared by the test modules above
+
+fn get_error(input: &str) -> String {
+    let resolver = &mut DummyImportResolver::new();
+    let diagnostics = consume_token(convert_to_token(input), resolver, None)
+        .expect_err("Expected input to be rejected");
+
+    return diagnostics[0].message.clone();
+}
+
+fn parse_type(input: &str) -> Result<DataType, String> {
+    let tokens = convert_to_token(input);
+    let mut resolver = DummyImportResolver::new();
+    let mut parser = Parser::new(&tokens, &mut resolver, None);
+    return parser.parse_type_declaration(1, &SymbolContext::create_global_context());
+}
+
+fn get_declared_exp(block: &[AstPtr], index: usize) -> Expression {
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*block[index] {
+        return exp.clone();
+    } else {
+        panic!("Expected variable declaration but got {:?}", block[index]);
+    }
+}
+
+fn get_declared_type(block: &[AstPtr], index: usize) -> DataType {
+    return get_declared_exp(block, index).get_data_type();
+}
@@ -0,0 +1,186 @@
+use ast::{
+    data_type::DataType,
+    declaration::{BlockWithCondition, Declaration},
+    expression::Expression,
+    Ast,
+};
+use indexmap::IndexMap;
+use lexer::convert_to_token;
+
+use super::{get_declared_exp, get_error, parse_type};
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_nullable_types() {
+    assert_eq!(
+        parse_type("number | null"),
+        Ok(DataType::UnionType {
+            types: vec![DataType::Float, DataType::Null]
+        })
+    );
+
+    // null is the only member allowed to share typeof "object"
+    let mut entries = IndexMap::new();
+    entries.insert("a".to_string(), DataType::Float);
+    entries.insert(
+        "b".to_string(),
+        DataType::UnionType {
+            types: vec![DataType::String, DataType::Undefined],
+        },
+    );
+    assert_eq!(
+        parse_type("{ a: number, b?: string } | null | undefined"),
+        Ok(DataType::UnionType {
+            types: vec![
+                DataType::Null,
+                DataType::ObjectType { entries },
+                DataType::Undefined
+            ]
+        })
+    );
+
+    assert_eq!(
+        parse_type("(a: number, b?: boolean) => void"),
+        Ok(DataType::FunctionType {
+            arguments: vec![
+                DataType::Float,
+                DataType::UnionType {
+                    types: vec![DataType::Boolean, DataType::Undefined]
+                }
+            ],
+            return_type: Box::new(DataType::Void)
+        })
+    );
+}
+
+#[test]
+fn test_null_narrowing() {
+    let input = "
+    const x: number | string | null = 1;
+    if (x !== null) {
+        const a = x;
+    }
+    if (x) {
+        const b = x;
+    } else {
+        const c = x;
+    }
+    const d = x ?? 0;
+    const e = x!;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let number_or_string = DataType::UnionType {
+        types: vec![DataType::Float, DataType::String],
+    };
+
+    if let Ast::Declaration(Declaration::NewIfBlockDeclaration { if_block, .. }) =
+        &*actual_output[1]
+    {
+        let BlockWithCondition { block, .. } = if_block;
+        assert_eq!(get_declared_exp(block, 0).get_data_type(), number_or_string);
+    } else {
+        panic!("Expected if block");
+    }
+
+    if let Ast::Declaration(Declaration::NewIfBlockDeclaration {
+        if_block,
+        else_block: Some(else_block),
+        ..
+    }) = &*actual_output[2]
+    {
+        let BlockWithCondition { block, .. } = if_block;
+        assert_eq!(get_declared_exp(block, 0).get_data_type(), number_or_string);
+
+        // Falsy value can still be 0 or ""
+        assert_eq!(
+            get_declared_exp(else_block, 0).get_data_type(),
+            DataType::UnionType {
+                types: vec![DataType::Float, DataType::Null, DataType::String]
+            }
+        );
+    } else {
+        panic!("Expected if block with else");
+    }
+
+    assert_eq!(
+        get_declared_exp(&actual_output, 3).get_data_type(),
+        number_or_string
+    );
+    assert_eq!(
+        get_declared_exp(&actual_output, 4).get_data_type(),
+        number_or_string
+    );
+}
+
+#[test]
+fn test_optional_properties_and_parameters() {
+    let input = "
+    const o: { a: number, b?: string } | undefined = { a: 1 };
+    const b = o?.b;
+    function f(a: number, b?: number): number {
+        return a;
+    }
+    const c = f(1);
+    let d: boolean | undefined;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(
+        get_declared_exp(&actual_output, 1).get_data_type(),
+        DataType::UnionType {
+            types: vec![DataType::String, DataType::Undefined]
+        }
+    );
+
+    if let Expression::FunctionCall { parameters, .. } = get_declared_exp(&actual_output, 3) {
+        assert_eq!(parameters.len(), 2);
+    } else {
+        panic!("Expected function call");
+    }
+
+    assert_eq!(
+        get_declared_exp(&actual_output, 4).get_data_type(),
+        DataType::UnionType {
+            types: vec![DataType::Boolean, DataType::Undefined]
+        }
+    );
+}
+
+#[test]
+fn test_strict_null_checks() {
+    assert_eq!(
+        get_error("const x: number | undefined = 1; const y = x * 2;"),
+        "Object is possibly 'undefined'"
+    );
+    assert_eq!(
+        get_error("const o: { a: number } | null = null; const a = o.a;"),
+        "Object is possibly 'null'"
+    );
+    assert_eq!(
+        get_error("const x: number[] | null | undefined = null; const y = x[0];"),
+        "Object is possibly 'null' or 'undefined'"
+    );
+    assert_eq!(
+        get_error("const y = 1 - null;"),
+        "The value 'null' cannot be used here"
+    );
+    assert_eq!(
+        get_error("const x: number | undefined = 1; const y: number = x;"),
+        "Expected data type number but got number | undefined"
+    );
+    assert_eq!(
+        get_error("const x = undefined; const y = x!;"),
+        "Non-null assertion cannot be used on undefined which is always null or undefined"
+    );
+
+    // Concatenating, comparing and checking do not need the value
+    let input = "
+    const x: number | null = null;
+    const y = \"x is \" + x + null;
+    const z = x === null || x == undefined || !x;
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+}
@@ -2,7 +2,7 @@ use ast::data_type::DataType;
 use indexmap::IndexMap;
 use lexer::convert_to_token;
 
-use crate::{parser::Parser, traits::DummyImportResolver};
+use crate::{parser::Parser, symbol_table::SymbolContext, traits::DummyImportResolver};
 
 #[test]
 fn test_string_type_declaration() {
@@ -11,7 +11,7 @@ fn test_string_type_declaration() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(data_type, Ok(DataType::String));
 }
@@ -23,7 +23,7 @@ fn test_boolean_type_declaration() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(data_type, Ok(DataType::Boolean));
 }
@@ -35,7 +35,7 @@ fn test_number_type_declaration() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(data_type, Ok(DataType::Float));
 }
@@ -47,7 +47,7 @@ fn test_void_type() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(data_type, Ok(DataType::Void));
 }
@@ -59,7 +59,7 @@ fn test_grouped_type_declaration() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(data_type, Ok(DataType::String));
 }
@@ -71,7 +71,7 @@ fn test_array_type() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     assert_eq!(
         data_type,
@@ -90,7 +90,7 @@ fn test_object_type() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     let mut data_type_entries: IndexMap<String, DataType> = IndexMap::new();
 
@@ -119,7 +119,7 @@ fn test_function_type() {
     let tokens = convert_to_token(input);
     let mut resolver = DummyImportResolver::new();
     let mut parser = Parser::new(&tokens, &mut resolver, None);
-    let data_type = parser.parse_type_declaration(1);
+    let data_type = parser.parse_type_declaration(1, &SymbolContext::create_global_context());
 
     let arguments = vec![DataType::String, DataType::String];
 
@@ -0,0 +1,76 @@
+use ast::{declaration::Declaration, expression::Expression, Ast};
+use lexer::{convert_to_token, token::Position};
+
+use crate::convert_to_ast;
+
+fn get_offsets(ast: &Ast) -> (usize, usize) {
+    let span = ast.get_span();
+    return (span.start.offset, span.end.offset);
+}
+
+#[test]
+fn test_variable_declaration_span() {
+    let input = "const x = 1 + 234;
+let y = [1, 2];";
+
+    let asts = convert_to_ast(convert_to_token(input));
+
+    assert_eq!(get_offsets(asts[0]), (0, 18));
+    assert_eq!(get_offsets(asts[1]), (19, 34));
+
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*asts[0] {
+        let span = exp.get_span();
+        assert_eq!(span.start, Position::new(10, 1, 11));
+        assert_eq!(span.end, Position::new(17, 1, 18));
+
+        if let Expression::BinaryExp { right, .. } = exp {
+            let span = right.get_span();
+            assert_eq!(span.start, Position::new(14, 1, 15));
+            assert_eq!(span.end, Position::new(17, 1, 18));
+        } else {
+            panic!("Expected expression to be BinaryExp but got {:?}", exp);
+        }
+    } else {
+        panic!(
+            "Expected ast to be VariableDeclaration but got {:?}",
+            asts[0]
+        );
+    }
+
+    if let Ast::Declaration(Declaration::VariableDeclaration { exp, .. }) = &*asts[1] {
+        let span = exp.get_span();
+        assert_eq!(span.start, Position::new(27, 2, 9));
+        assert_eq!(span.end, Position::new(33, 2, 15));
+    } else {
+        panic!(
+            "Expected ast to be VariableDeclaration but got {:?}",
+            asts[1]
+        );
+    }
+}
+
+#[test]
+fn test_block_span() {
+    let input = "
+let x = 0;
+while (x !== 10) {
+    x += 1;
+}";
+
+    let asts = convert_to_ast(convert_to_token(input));
+
+    let span = asts[1].get_span();
+    assert_eq!(span.start, Position::new(12, 3, 1));
+    assert_eq!(span.end, Position::new(44, 5, 2));
+
+    if let Ast::Declaration(Declaration::WhileLoopDeclaration { block, .. }) = &*asts[1] {
+        let span = block[0].get_span();
+        assert_eq!(span.start, Position::new(35, 4, 5));
+        assert_eq!(span.end, Position::new(42, 4, 12));
+    } else {
+        panic!(
+            "Expected ast to be WhileLoopDeclaration but got {:?}",
+            asts[1]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use ast::{declaration::Declaration, Ast};
+use lexer::{convert_to_token, token::KeywordKind};
+
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_switch() {
+    let input = "
+    const day = 2;
+    switch (day) {
+        case 1:
+        case 2:
+            console.log(\"weekday\");
+            break;
+        default:
+            console.log(\"weekend\");
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::SwitchDeclaration { cases, .. }) = &*actual_output[1] {
+        assert_eq!(cases.len(), 3);
+
+        assert!(cases[0].test.is_some());
+        assert!(cases[0].block.is_empty());
+
+        assert_eq!(cases[1].block.len(), 2);
+        assert!(matches!(
+            &*cases[1].block[1],
+            Ast::Declaration(Declaration::LoopControlFlow {
+                keyword: KeywordKind::Break,
+                label: None,
+                ..
+            })
+        ));
+
+        assert!(cases[2].test.is_none());
+        assert_eq!(cases[2].block.len(), 1);
+    } else {
+        panic!("Expected switch to be parsed as Declaration::SwitchDeclaration");
+    }
+}
+
+#[test]
+fn test_switch_cases_share_scope() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("switch (1) { case 1: const x = 1; case 2: const x = 2; }"),
+        resolver,
+        None,
+    );
+    assert!(result.is_err());
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("switch (1) { case 1: const x = 1; } const y = x;"),
+        resolver,
+        None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_switch_data_types() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("switch (\"a\") { case 1: break; }"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected case of a different data type to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected case of switch to be of data type string but got number"
+    );
+
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(convert_to_token("switch ([1]) { }"), resolver, None);
+
+    let diagnostics = result.expect_err("Expected switch on an array to be rejected");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("switch can only be used on numbers, strings and booleans"));
+}
+
+#[test]
+fn test_switch_break_and_continue() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("switch (1) { default: break; default: break; }"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected second default to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "switch can only have one default case"
+    );
+
+    // continue belongs to loops only
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("switch (1) { case 1: continue; }"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected continue inside of switch to be rejected");
+    assert_eq!(
+        diagnostics[0].message,
+        "Cannot use continue outside of a loop"
+    );
+
+    let input = "
+    while (true) {
+        switch (1) {
+            case 1:
+                continue;
+        }
+        break;
+    }
+    ";
+    crate::convert_to_ast(convert_to_token(input));
+}
@@ -0,0 +1,107 @@
+use ast::{
+    erase_expression_spans,
+    expression::{BinaryOperator, Expression},
+    AstPtr,
+};
+use lexer::{convert_to_token, token::Span};
+
+use crate::{parser::Parser, symbol_table::SymbolContext, traits::DummyImportResolver};
+
+fn parse_expression(input: &str) -> Result<Expression, String> {
+    let tokens = convert_to_token(input);
+    let mut resolver = DummyImportResolver::new();
+    let mut parser = Parser::new(&tokens, &mut resolver, None);
+    let context = SymbolContext::create_global_context();
+
+    return parser.parse_expression(1, &context);
+}
+
+#[test]
+fn test_template_literal() {
+    let input = "`a${1}b${'c' + 2}`";
+
+    let expected_output = Expression::TemplateLiteral {
+        quasis: vec!["a".to_string(), "b".to_string(), "".to_string()],
+        expressions: vec![
+            Expression::FloatLiteralExp {
+                name: "1".to_string(),
+                value: 1.0,
+                span: Span::default(),
+            },
+            Expression::BinaryExp {
+                operator: BinaryOperator::Plus,
+                left: Box::new(Expression::StringLiteralExp {
+                    value: "c".to_string(),
+                    span: Span::default(),
+                }),
+                right: Box::new(Expression::FloatLiteralExp {
+                    name: "2".to_string(),
+                    value: 2.0,
+                    span: Span::default(),
+                }),
+                span: Span::default(),
+            },
+        ],
+        span: Span::default(),
+    };
+
+    let actual_output = parse_expression(input).unwrap();
+
+    assert_eq!(
+        erase_expression_spans(&actual_output),
+        erase_expression_spans(&expected_output)
+    );
+    assert_eq!(actual_output.get_span().end.offset, input.len());
+}
+
+#[test]
+fn test_nested_template_literal() {
+    let input = "`${`in${true}`}!`";
+
+    let expected_output = Expression::TemplateLiteral {
+        quasis: vec!["".to_string(), "!".to_string()],
+        expressions: vec![Expression::TemplateLiteral {
+            quasis: vec!["in".to_string(), "".to_string()],
+            expressions: vec![Expression::BooleanLiteralExp {
+                name: "true".to_string(),
+                value: true,
+                span: Span::default(),
+            }],
+            span: Span::default(),
+        }],
+        span: Span::default(),
+    };
+
+    let actual_output = parse_expression(input).unwrap();
+
+    assert_eq!(
+        erase_expression_spans(&actual_output),
+        erase_expression_spans(&expected_output)
+    );
+}
+
+#[test]
+fn test_template_literal_in_declaration() {
+    let input = "const x = `a${1}`;
+const y : string = x;";
+
+    let asts: Vec<AstPtr> = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(asts.len(), 2);
+}
+
+#[test]
+fn test_only_primitives_convert_to_string() {
+    assert_eq!(
+        parse_expression("`${[1, 2]}`"),
+        Err(
+            "Cannot convert number[] to string in template literal, only primitives are supported"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        parse_expression("\"a\" + { a: 1 }"),
+        Err("Operator Plus cannot be used on datatypes string and { a: number }".to_string())
+    );
+    assert!(parse_expression("`${1}${true}${null}` + 1").is_ok());
+}
@@ -0,0 +1,125 @@
+use ast::{data_type::DataType, declaration::Declaration, Ast};
+use indexmap::indexmap;
+use lexer::convert_to_token;
+
+use super::{get_declared_type, get_error};
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_type_alias() {
+    let input = "
+    type Point = { x: number; y: number };
+    type Id = number | string;
+    const p: Point = { x: 1, y: 2 };
+    const ids: Id[] = [1, \"two\"];
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    let point = DataType::ObjectType {
+        entries: indexmap! {"x".to_string() => DataType::Float, "y".to_string() => DataType::Float},
+    };
+    if let Ast::Declaration(Declaration::TypeDeclaration {
+        ident_name,
+        data_type,
+        ..
+    }) = &*actual_output[0]
+    {
+        assert_eq!(ident_name, "Point");
+        assert_eq!(data_type, &point);
+    } else {
+        panic!("Expected type declaration");
+    }
+
+    assert_eq!(get_declared_type(&actual_output, 2), point);
+    assert_eq!(
+        get_declared_type(&actual_output, 3),
+        DataType::ArrayType {
+            base_type: Box::new(DataType::UnionType {
+                types: vec![DataType::Float, DataType::String]
+            })
+        }
+    );
+}
+
+#[test]
+fn test_interface_extends_and_merging() {
+    let input = "
+    interface Named { name: string }
+    interface Named { label?: string }
+    interface Shape extends Named {
+        area(): number;
+        scale(by: number): void;
+    }
+    function area(): number { return 1; }
+    function scale(by: number): void {}
+    const s: Shape = { name: \"a\", area: area, scale: scale };
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    assert_eq!(
+        get_declared_type(&actual_output, 5),
+        DataType::ObjectType {
+            entries: indexmap! {
+                "name".to_string() => DataType::String,
+                "label".to_string() => DataType::UnionType {
+                    types: vec![DataType::String, DataType::Undefined]
+                },
+                "area".to_string() => DataType::FunctionType {
+                    arguments: vec![],
+                    return_type: Box::new(DataType::Float)
+                },
+                "scale".to_string() => DataType::FunctionType {
+                    arguments: vec![DataType::Float],
+                    return_type: Box::new(DataType::Void)
+                },
+            }
+        }
+    );
+}
+
+#[test]
+fn test_type_scope() {
+    // Type declared at the top is visible inside of functions
+    let input = "
+    type Id = number;
+    function double(id: Id): Id {
+        type Twice = Id;
+        const twice: Twice = id * 2;
+        return twice;
+    }
+    const type = 1;
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+
+    assert_eq!(
+        get_error("if (true) { type Flag = boolean; } const f: Flag = true;"),
+        "Unknown ident name : \"Flag\""
+    );
+}
+
+#[test]
+fn test_type_declaration_errors() {
+    assert_eq!(
+        get_error("type A = number; type A = string;"),
+        "There is already a type with name A"
+    );
+    assert_eq!(
+        get_error("interface A { a: number } type A = string;"),
+        "There is already a type with name A"
+    );
+    assert_eq!(
+        get_error("interface A { a: number } interface A { a: string }"),
+        "Property a of interface A is declared as number and again as string"
+    );
+    assert_eq!(
+        get_error("type A = number; interface B extends A { b: number }"),
+        "Interface B can only extend an object type but got number"
+    );
+    assert_eq!(
+        get_error("interface A { a: number, a: string }"),
+        "Duplicate property a in object type"
+    );
+}
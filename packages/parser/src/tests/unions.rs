@@ -0,0 +1,204 @@
+use ast::{
+    data_type::{create_union, DataType},
+    declaration::{BlockWithCondition, Declaration},
+    Ast,
+};
+use lexer::convert_to_token;
+
+use super::{get_declared_type, get_error, parse_type};
+use crate::{consume_token, traits::DummyImportResolver};
+
+#[test]
+fn test_union_type_declaration() {
+    let number_or_string = DataType::UnionType {
+        types: vec![DataType::Float, DataType::String],
+    };
+
+    // Members are flattened, deduplicated and sorted
+    assert_eq!(parse_type("number | string"), Ok(number_or_string.clone()));
+    assert_eq!(
+        parse_type("string | (number | string)"),
+        Ok(number_or_string.clone())
+    );
+    assert_eq!(parse_type("number | number"), Ok(DataType::Float));
+
+    assert_eq!(
+        parse_type("(number | string)[]"),
+        Ok(DataType::ArrayType {
+            base_type: Box::new(number_or_string)
+        })
+    );
+    assert_eq!(
+        parse_type("number[] | string"),
+        Ok(DataType::UnionType {
+            types: vec![
+                DataType::ArrayType {
+                    base_type: Box::new(DataType::Float)
+                },
+                DataType::String
+            ]
+        })
+    );
+
+    // Members are told apart by their index in the union, so they can share a typeof
+    assert_eq!(
+        parse_type("{ a: number } | number[]"),
+        Ok(DataType::UnionType {
+            types: vec![
+                DataType::ArrayType {
+                    base_type: Box::new(DataType::Float)
+                },
+                DataType::ObjectType {
+                    entries: vec![(String::from("a"), DataType::Float)]
+                        .into_iter()
+                        .collect()
+                }
+            ]
+        })
+    );
+    assert_eq!(
+        parse_type("number | void"),
+        Err("Data type void cannot be a member of a union type".to_string())
+    );
+}
+
+#[test]
+fn test_typeof_narrowing() {
+    let input = "
+    const x: number | string | boolean = 1;
+    if (typeof x === \"number\") {
+        const a = x;
+    } else if (typeof x !== \"string\") {
+        const b = x;
+    } else {
+        const c = x;
+    }
+    const d = x;
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+
+    if let Ast::Declaration(Declaration::NewIfBlockDeclaration {
+        if_block,
+        else_if_block,
+        else_block: Some(else_block),
+        ..
+    }) = &*actual_output[1]
+    {
+        let BlockWithCondition { block, .. } = if_block;
+        assert_eq!(get_declared_type(block, 0), DataType::Float);
+        assert_eq!(
+            get_declared_type(&else_if_block[0].block, 0),
+            DataType::Boolean
+        );
+        assert_eq!(get_declared_type(else_block, 0), DataType::String);
+    } else {
+        panic!("Expected if block with else if and else");
+    }
+
+    assert_eq!(
+        get_declared_type(&actual_output, 2),
+        DataType::UnionType {
+            types: vec![DataType::Boolean, DataType::Float, DataType::String]
+        }
+    );
+}
+
+#[test]
+fn test_instanceof_narrowing() {
+    let input = "
+    class Animal {}
+    class Cat extends Animal {}
+    class Dog {}
+
+    const animal = new Animal();
+    const cat = new Cat();
+    const dog = new Dog();
+
+    const x: Animal | Dog | null = null;
+    if (x instanceof Cat) {
+        const a = x;
+    } else if (x instanceof Dog) {
+        const b = x;
+    } else {
+        const c = x;
+    }
+    ";
+
+    let actual_output = crate::convert_to_ast(convert_to_token(input));
+    let position = actual_output
+        .iter()
+        .position(|ast| {
+            matches!(
+                &**ast,
+                Ast::Declaration(Declaration::VariableDeclaration { .. })
+            )
+        })
+        .unwrap();
+    let animal = get_declared_type(&actual_output, position);
+    let cat = get_declared_type(&actual_output, position + 1);
+    let dog = get_declared_type(&actual_output, position + 2);
+
+    // Instance of Animal may or may not be an instance of Cat
+    if let Ast::Declaration(Declaration::NewIfBlockDeclaration {
+        if_block,
+        else_if_block,
+        else_block: Some(else_block),
+        ..
+    }) = &*actual_output[position + 4]
+    {
+        assert_eq!(get_declared_type(&if_block.block, 0), cat);
+        assert_eq!(get_declared_type(&else_if_block[0].block, 0), dog);
+        assert_eq!(
+            get_declared_type(else_block, 0),
+            create_union(vec![animal, DataType::Null])
+        );
+    } else {
+        panic!("Expected if block with else if and else");
+    }
+
+    assert_eq!(
+        get_error("const x = 1; const y = x instanceof Array;"),
+        "Cannot use instanceof on Array as it is not a class"
+    );
+    assert_eq!(
+        get_error("class A {} const x: number | string = 1; const y = x instanceof A;"),
+        "Left side of instanceof has to be an instance of a class but got number | string"
+    );
+}
+
+#[test]
+fn test_union_operand_without_narrowing() {
+    let resolver = &mut DummyImportResolver::new();
+    let result = consume_token(
+        convert_to_token("const x: number | string = 1; const y = x * 2;"),
+        resolver,
+        None,
+    );
+
+    let diagnostics = result.expect_err("Expected union to be rejected by *");
+    assert_eq!(
+        diagnostics[0].message,
+        "Operator Star cannot be used on number | string, narrow it with typeof first"
+    );
+
+    // Variable assigned in the block is not narrowed there
+    let input = "
+    let x: number | string = 1;
+    if (typeof x === \"number\") {
+        x = \"a\";
+        const y = x * 2;
+    }
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_err());
+
+    // Comparing and concatenating do not need narrowing
+    let input = "
+    const x: number | string = 1;
+    const y = x === 1 || x == \"1\";
+    const z = \"x is \" + x;
+    ";
+    let resolver = &mut DummyImportResolver::new();
+    assert!(consume_token(convert_to_token(input), resolver, None).is_ok());
+}
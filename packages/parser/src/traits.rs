@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::symbol_table::SymbolMetaInsert;
+use lexer::diagnostic::Diagnostic;
+
+use crate::symbol_table::{SymbolMetaInsert, TypeSymbol};
 
 pub trait ImportResolver {
     fn get(
@@ -8,18 +10,49 @@ pub trait ImportResolver {
         relative_file_name: &str,
         cur_file_name: &str,
     ) -> Option<&HashMap<String, SymbolMetaInsert>>;
+    // Type aliases, interfaces and classes declared at the top of the file
+    fn get_types(
+        &self,
+        relative_file_name: &str,
+        cur_file_name: &str,
+    ) -> Option<&HashMap<String, TypeSymbol>>;
     fn contains(&self, relative_file_name: &str, cur_file_name: &str) -> bool;
     fn get_id(&self, relative_file_name: &str, cur_file_name: &str) -> usize;
     fn get_id_for_file_name(&self, absolute_file_name: &str) -> usize;
 
     fn resolve(&mut self, relative_file_name: &str, cur_file_name: &str) -> Result<(), String>;
+
+    /*
+     * In strict mode the parser also lints code which is valid but error prone,
+     * e.g. use of `==`, the lints are reported as warnings
+     *
+     * */
+    fn is_strict_mode(&self) -> bool {
+        return false;
+    }
+
+    // Called with the warnings of a file which was parsed without any error
+    fn report_warnings(&mut self, _cur_file_name: Option<&str>, _warnings: Vec<Diagnostic>) {}
 }
 
-pub struct DummyImportResolver(());
+pub struct DummyImportResolver {
+    strict_mode: bool,
+    pub warnings: Vec<Diagnostic>,
+}
 
 impl DummyImportResolver {
     pub fn new() -> Self {
-        return DummyImportResolver(());
+        return DummyImportResolver {
+            strict_mode: false,
+            warnings: vec![],
+        };
+    }
+
+    pub fn new_strict() -> Self {
+        return DummyImportResolver {
+            strict_mode: true,
+            warnings: vec![],
+        };
     }
 }
 
@@ -31,6 +64,13 @@ impl ImportResolver for DummyImportResolver {
     ) -> Option<&HashMap<String, SymbolMetaInsert>> {
         unreachable!();
     }
+    fn get_types(
+        &self,
+        _relative_file_name: &str,
+        _cur_file_name: &str,
+    ) -> Option<&HashMap<String, TypeSymbol>> {
+        unreachable!();
+    }
     fn contains(&self, _relative_file_name: &str, _cur_file_name: &str) -> bool {
         unreachable!();
     }
@@ -44,4 +84,12 @@ impl ImportResolver for DummyImportResolver {
     fn resolve(&mut self, _relative_file_name: &str, _cur_file_name: &str) -> Result<(), String> {
         unreachable!();
     }
+
+    fn is_strict_mode(&self) -> bool {
+        return self.strict_mode;
+    }
+
+    fn report_warnings(&mut self, _cur_file_name: Option<&str>, warnings: Vec<Diagnostic>) {
+        self.warnings.extend(warnings);
+    }
 }
@@ -1,17 +1,24 @@
 use core::panic;
+use std::collections::HashMap;
 
 use ast::{
-    data_type::DataType,
-    expression::{BinaryOperator, UnaryOperator},
+    class::AccessModifier,
+    data_type::{create_union, is_assignable_with_classes, DataType},
+    declaration::VariableAssignmentOperator,
+    expression::{BinaryOperator, Expression, LogicalOperator, UnaryOperator, UpdateOperator},
 };
 use indexmap::IndexMap;
-use lexer::token::Token;
+use lexer::token::{KeywordKind, Span, Token};
+
+use crate::symbol_table::SymbolContext;
 
 pub(crate) fn convert_token_to_unary_operator(token: &Token) -> UnaryOperator {
     let operator = match token {
         Token::Plus => UnaryOperator::Plus,
         Token::Minus => UnaryOperator::Minus,
         Token::Bang => UnaryOperator::Bang,
+        Token::Tilde => UnaryOperator::Tilde,
+        Token::Keyword(KeywordKind::Typeof) => UnaryOperator::Typeof,
 
         tok => panic!("Cannot convert token {:?} to unaryOperator", tok),
     };
@@ -25,13 +32,21 @@ pub(crate) fn convert_token_to_binary_operator(token: &Token) -> BinaryOperator
         Token::Minus => BinaryOperator::Minus,
         Token::Slash => BinaryOperator::Slash,
         Token::Star => BinaryOperator::Star,
+        Token::Percent => BinaryOperator::Percent,
+        Token::StarStar => BinaryOperator::StarStar,
 
         Token::VerticalBar => BinaryOperator::VerticalBar,
         Token::Ampersand => BinaryOperator::Ampersand,
         Token::Caret => BinaryOperator::Caret,
 
+        Token::LeftShift => BinaryOperator::LeftShift,
+        Token::RightShift => BinaryOperator::RightShift,
+        Token::UnsignedRightShift => BinaryOperator::UnsignedRightShift,
+
         Token::StrictEquality => BinaryOperator::StrictEquality,
         Token::StrictNotEqual => BinaryOperator::StrictNotEqual,
+        Token::Equality => BinaryOperator::Equality,
+        Token::NotEqual => BinaryOperator::NotEqual,
 
         Token::LessThan => BinaryOperator::LessThan,
         Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
@@ -45,6 +60,54 @@ pub(crate) fn convert_token_to_binary_operator(token: &Token) -> BinaryOperator
     return operator;
 }
 
+pub(crate) fn convert_token_to_update_operator(token: &Token) -> UpdateOperator {
+    let operator = match token {
+        Token::Increment => UpdateOperator::Increment,
+        Token::Decrement => UpdateOperator::Decrement,
+
+        tok => panic!("Cannot covert token {:?} to update operator", tok),
+    };
+
+    return operator;
+}
+
+// Returns None if the token is not an assignment operator
+pub(crate) fn convert_token_to_assignment_operator(
+    token: &Token,
+) -> Option<VariableAssignmentOperator> {
+    let operator = match token {
+        Token::Assign => VariableAssignmentOperator::Assign,
+        Token::PlusAssign => VariableAssignmentOperator::PlusAssign,
+        Token::MinusAssign => VariableAssignmentOperator::MinusAssign,
+        Token::StarAssign => VariableAssignmentOperator::StarAssign,
+        Token::SlashAssign => VariableAssignmentOperator::SlashAssign,
+        Token::PercentAssign => VariableAssignmentOperator::PercentAssign,
+        Token::StarStarAssign => VariableAssignmentOperator::StarStarAssign,
+        Token::AmpersandAssign => VariableAssignmentOperator::AmpersandAssign,
+        Token::VerticalBarAssign => VariableAssignmentOperator::VerticalBarAssign,
+        Token::CaretAssign => VariableAssignmentOperator::CaretAssign,
+        Token::LeftShiftAssign => VariableAssignmentOperator::LeftShiftAssign,
+        Token::RightShiftAssign => VariableAssignmentOperator::RightShiftAssign,
+        Token::UnsignedRightShiftAssign => VariableAssignmentOperator::UnsignedRightShiftAssign,
+
+        _ => return None,
+    };
+
+    return Some(operator);
+}
+
+pub(crate) fn convert_token_to_logical_operator(token: &Token) -> LogicalOperator {
+    let operator = match token {
+        Token::LogicalAnd => LogicalOperator::And,
+        Token::LogicalOr => LogicalOperator::Or,
+        Token::NullishCoalescing => LogicalOperator::NullishCoalescing,
+
+        tok => panic!("Cannot covert token {:?} to logical operator", tok),
+    };
+
+    return operator;
+}
+
 pub fn convert_index_map_to_vec(index_map: &IndexMap<String, DataType>) -> Vec<DataType> {
     let mut vec_str: Vec<DataType> = Vec::new();
 
@@ -54,3 +117,569 @@ pub fn convert_index_map_to_vec(index_map: &IndexMap<String, DataType>) -> Vec<D
 
     return vec_str;
 }
+
+/*
+ * `[]` has no element to infer its type from, so it takes the type of whatever it
+ * is assigned or passed to
+ *
+ *      const x: number[] = [];
+ *
+ * Any other expression is returned as is
+ *
+ * */
+pub(crate) fn infer_empty_array(
+    expression: Expression,
+    expected_data_type: &DataType,
+) -> Expression {
+    match (expression, expected_data_type) {
+        (
+            Expression::ArrayLiteral {
+                expression,
+                expression_data_type: DataType::Unknown,
+                span,
+            },
+            DataType::ArrayType { base_type },
+        ) if expression.is_empty() => {
+            return Expression::ArrayLiteral {
+                expression,
+                expression_data_type: base_type.as_ref().clone(),
+                span,
+            };
+        }
+
+        (expression, _) => return expression,
+    }
+}
+
+/*
+ * Converts the expression assigned or passed to a place of the expected data type,
+ * a member of a union is wrapped in Expression::UnionConversion and the elements
+ * of array literals and the fields of object literals are converted one by one,
+ * optional properties left out of an object literal are set to undefined
+ *
+ *      const x: number | string = 1;
+ *      const y: (number | string)[] = [1, "a"];
+ *
+ * Anything else assignable to the expected data type, see data_type::is_assignable,
+ * is wrapped in Expression::StructuralConversion when it can be converted without
+ * a copy, see can_convert_at_runtime
+ *
+ *      const f: (x: number) => void = (x: number | string) => {};
+ *
+ * Any other expression is returned as is, so the caller still has to check that
+ * the result has the same layout as the expected data type
+ *
+ * */
+pub(crate) fn convert_to_data_type(
+    expression: Expression,
+    expected_data_type: &DataType,
+    context: &SymbolContext,
+) -> Expression {
+    let expression = infer_empty_array(expression, expected_data_type);
+    let data_type = expression.get_data_type();
+
+    if data_type.has_same_layout(expected_data_type) {
+        return expression;
+    }
+
+    let expression = match (expression, expected_data_type) {
+        (
+            Expression::ArrayLiteral {
+                expression,
+                expression_data_type,
+                span,
+            },
+            DataType::ArrayType { base_type },
+        ) => {
+            let expression: Vec<Expression> = (*expression)
+                .into_iter()
+                .map(|exp| convert_to_data_type(exp, base_type, context))
+                .collect();
+
+            let is_converted = expression
+                .iter()
+                .all(|exp| exp.get_data_type().has_same_layout(base_type));
+
+            Expression::ArrayLiteral {
+                expression: Box::new(expression),
+                expression_data_type: if is_converted {
+                    base_type.as_ref().clone()
+                } else {
+                    expression_data_type
+                },
+                span,
+            }
+        }
+
+        (
+            Expression::ObjectLiteral {
+                expression,
+                data_type,
+                span,
+            },
+            DataType::ObjectType { entries },
+        ) => {
+            // Optional properties can be left out of the literal
+            let has_same_keys = match &data_type {
+                DataType::ObjectType {
+                    entries: literal_entries,
+                } => {
+                    literal_entries.keys().all(|key| entries.contains_key(key))
+                        && entries.iter().all(|(key, entry_data_type)| {
+                            literal_entries.contains_key(key)
+                                || entry_data_type.accepts_nullish(&DataType::Undefined)
+                        })
+                }
+                _ => false,
+            };
+
+            if has_same_keys {
+                let mut expression = expression;
+                for key in entries.keys() {
+                    if !expression.contains_key(key) {
+                        expression.insert(key.clone(), Expression::UndefinedLiteralExp { span });
+                    }
+                }
+
+                let expression: HashMap<String, Expression> = expression
+                    .into_iter()
+                    .map(|(key, exp)| {
+                        let exp = convert_to_data_type(exp, &entries[&key], context);
+                        return (key, exp);
+                    })
+                    .collect();
+
+                let is_converted = expression
+                    .iter()
+                    .all(|(key, exp)| exp.get_data_type().has_same_layout(&entries[key]));
+
+                Expression::ObjectLiteral {
+                    expression,
+                    data_type: if is_converted {
+                        expected_data_type.clone()
+                    } else {
+                        data_type
+                    },
+                    span,
+                }
+            } else {
+                return convert_object_literal_with_more_keys(
+                    expression,
+                    data_type,
+                    span,
+                    expected_data_type,
+                    context,
+                );
+            }
+        }
+
+        (expression, DataType::UnionType { types }) => {
+            let is_member = match &data_type {
+                DataType::UnionType { types: members } => members
+                    .iter()
+                    .all(|member| types.iter().any(|other| member.has_same_layout(other))),
+                data_type => types.iter().any(|member| data_type.has_same_layout(member)),
+            };
+
+            /*
+             * Value can become a member by its own conversion, e.g. `{ a: 1 }` to
+             * `{ a?: number }` or `{ a: 1, b: 2 }` to `{ a: number }`
+             *
+             * */
+            let converted_member = types.iter().find_map(|member| {
+                if is_member {
+                    return None;
+                }
+
+                let converted = convert_to_data_type(expression.clone(), member, context);
+                if !converted.get_data_type().has_same_layout(member) {
+                    return None;
+                }
+
+                return Some(converted);
+            });
+            let (expression, is_member) = match converted_member {
+                Some(converted) => (converted, true),
+                None => (expression, is_member),
+            };
+
+            if is_member {
+                let span = expression.get_span();
+                return Expression::UnionConversion {
+                    exp: Box::new(expression),
+                    data_type: expected_data_type.clone(),
+                    span,
+                };
+            }
+
+            expression
+        }
+
+        (expression, _) => expression,
+    };
+
+    let data_type = expression.get_data_type();
+    if !data_type.has_same_layout(expected_data_type)
+        && is_assignable_with_classes(&data_type, expected_data_type, &|llvm_name| {
+            return context
+                .get_class(llvm_name)
+                .map(|class| class.get_instance_type());
+        })
+        && can_convert_at_runtime(&data_type, expected_data_type, context)
+    {
+        let span = expression.get_span();
+        return Expression::StructuralConversion {
+            exp: Box::new(expression),
+            data_type: expected_data_type.clone(),
+            span,
+        };
+    }
+
+    return expression;
+}
+
+/*
+ * Object literal having properties which the expected data type does not have is
+ * wrapped in Expression::StructuralConversion, the properties are still evaluated
+ * but the object is copied without them. No other reference to the new object
+ * exists, so nobody can tell it was copied
+ *
+ *      const z: { a: number } = { a: 1, b: 2 };
+ *
+ * */
+fn convert_object_literal_with_more_keys(
+    expression: HashMap<String, Expression>,
+    data_type: DataType,
+    span: Span,
+    expected_data_type: &DataType,
+    context: &SymbolContext,
+) -> Expression {
+    let (literal_entries, entries) = match (&data_type, expected_data_type) {
+        (
+            DataType::ObjectType {
+                entries: literal_entries,
+            },
+            DataType::ObjectType { entries },
+        ) => (literal_entries, entries),
+        _ => {
+            return Expression::ObjectLiteral {
+                expression,
+                data_type,
+                span,
+            }
+        }
+    };
+
+    let expression: HashMap<String, Expression> = expression
+        .into_iter()
+        .map(|(key, exp)| match entries.get(&key) {
+            Some(entry_data_type) => (key, convert_to_data_type(exp, entry_data_type, context)),
+            None => (key, exp),
+        })
+        .collect();
+
+    let literal_entries: IndexMap<String, DataType> = literal_entries
+        .keys()
+        .map(|key| (key.clone(), expression[key].get_data_type()))
+        .collect();
+
+    let is_convertible =
+        entries
+            .iter()
+            .all(|(key, entry_data_type)| match literal_entries.get(key) {
+                Some(literal_entry) => literal_entry.has_same_layout(entry_data_type),
+                None => entry_data_type.accepts_nullish(&DataType::Undefined),
+            });
+
+    let literal = Expression::ObjectLiteral {
+        expression,
+        data_type: DataType::ObjectType {
+            entries: literal_entries,
+        },
+        span,
+    };
+
+    if !is_convertible {
+        return literal;
+    }
+
+    return Expression::StructuralConversion {
+        exp: Box::new(literal),
+        data_type: expected_data_type.clone(),
+        span,
+    };
+}
+
+/*
+ * Whether build_structural_conversion of llvm can convert the value without copying
+ * it. Arrays and objects are shared by reference, so a copy in another layout would
+ * not see the changes made through the original and the other way around, they
+ * have to have the same layout
+ *
+ * Function is wrapped in a thunk which converts the arguments to the parameters of
+ * the function and its return value back, so they have to be convertible as well
+ *
+ * Instance of a class becomes a new object of its methods bound to the instance,
+ * fields cannot be copied into it for the same reason as arrays and objects
+ *
+ *      interface Shape { area(): number }
+ *      const shape: Shape = new Square(3);
+ *
+ * */
+fn can_convert_at_runtime(
+    from_data_type: &DataType,
+    to_data_type: &DataType,
+    context: &SymbolContext,
+) -> bool {
+    if from_data_type.has_same_layout(to_data_type) {
+        return true;
+    }
+
+    match (from_data_type, to_data_type) {
+        (DataType::UnionType { types }, to_data_type) => {
+            return types
+                .iter()
+                .all(|member| can_convert_at_runtime(member, to_data_type, context));
+        }
+
+        (from_data_type, DataType::UnionType { types }) => {
+            return match from_data_type.find_assignable_member(types) {
+                Some(member) => can_convert_at_runtime(from_data_type, member, context),
+                None => false,
+            };
+        }
+
+        (
+            DataType::FunctionType {
+                return_type,
+                arguments,
+            },
+            DataType::FunctionType {
+                return_type: to_return_type,
+                arguments: to_arguments,
+            },
+        ) => {
+            return arguments.len() <= to_arguments.len()
+                && arguments
+                    .iter()
+                    .zip(to_arguments.iter())
+                    .all(|(argument, to_argument)| {
+                        can_convert_at_runtime(to_argument, argument, context)
+                    })
+                && (to_return_type.as_ref() == &DataType::Void
+                    || can_convert_at_runtime(return_type, to_return_type, context));
+        }
+
+        // Instance of a subclass starts with the fields of its parents
+        (DataType::ClassType { parents, .. }, DataType::ClassType { name, .. }) => {
+            return parents.contains(name);
+        }
+
+        (DataType::ClassType { name, .. }, DataType::ObjectType { entries }) => {
+            let class = match context.get_class(name) {
+                Some(class) => class,
+                None => return false,
+            };
+
+            return entries.iter().all(|(key, entry_data_type)| {
+                return match class.methods.get(key) {
+                    Some(method) => {
+                        method.access == AccessModifier::Public
+                            && can_convert_at_runtime(&method.data_type, entry_data_type, context)
+                    }
+                    None => {
+                        !class.fields.contains_key(key)
+                            && entry_data_type.accepts_nullish(&DataType::Undefined)
+                    }
+                };
+            });
+        }
+
+        // Reference is kept along with how to print it, see build_any_object of llvm
+        (
+            DataType::ArrayType { .. } | DataType::ObjectType { .. } | DataType::ClassType { .. },
+            DataType::Object,
+        ) => return true,
+
+        // Member of an enum is its value at runtime, see erase_enums
+        (DataType::EnumType { .. }, to_data_type) => {
+            return from_data_type.erase_enums().has_same_layout(to_data_type);
+        }
+
+        _ => return false,
+    }
+}
+
+/*
+ * Members of a union, any other data type is the only member of itself
+ *
+ * */
+pub(crate) fn get_members(data_type: DataType) -> Vec<DataType> {
+    match data_type {
+        DataType::UnionType { types } => return types,
+        data_type => return vec![data_type],
+    }
+}
+
+/*
+ * Union of the data types, see data_type::create_union. Every member has to be a
+ * value, a union of void makes no sense
+ *
+ * A type parameter is checked once its type argument is known, see
+ * check_union_members
+ *
+ * */
+pub(crate) fn create_union_type(types: Vec<DataType>) -> Result<DataType, String> {
+    let data_type = create_union(types);
+
+    for member in get_members(data_type.clone()) {
+        check_union_member(&member)?;
+    }
+
+    return Ok(data_type);
+}
+
+fn check_union_member(member: &DataType) -> Result<(), String> {
+    if let DataType::TypeParameter { .. } = member {
+        return Ok(());
+    }
+
+    if member == &DataType::Void || member.get_typeof_name().is_none() {
+        return Err(format!(
+            "Data type {} cannot be a member of a union type",
+            member
+        ));
+    }
+
+    return Ok(());
+}
+
+/*
+ * Checks the members of the unions the data type is made of, used once the type
+ * arguments of a generic function or type alias are substituted, e.g. `T | null`
+ * cannot be used with T = void
+ *
+ * */
+pub(crate) fn check_union_members(data_type: &DataType) -> Result<(), String> {
+    match data_type {
+        DataType::UnionType { types } => {
+            for member in types {
+                check_union_member(member)?;
+                check_union_members(member)?;
+            }
+        }
+
+        DataType::ArrayType { base_type } => check_union_members(base_type)?,
+
+        DataType::ObjectType { entries } => {
+            for entry in entries.values() {
+                check_union_members(entry)?;
+            }
+        }
+
+        DataType::FunctionType {
+            return_type,
+            arguments,
+        } => {
+            for argument in arguments {
+                check_union_members(argument)?;
+            }
+            check_union_members(return_type)?;
+        }
+
+        _ => {}
+    }
+
+    return Ok(());
+}
+
+/*
+ * Only unions of numbers, strings, booleans, null and undefined can be converted
+ * to a string
+ *
+ * */
+pub(crate) fn is_primitive_union(data_type: &DataType) -> bool {
+    if let DataType::UnionType { types } = data_type {
+        return types.iter().all(|member| {
+            matches!(
+                member,
+                DataType::Float
+                    | DataType::String
+                    | DataType::Boolean
+                    | DataType::Null
+                    | DataType::Undefined
+            )
+        });
+    }
+
+    return false;
+}
+
+/*
+ * Data types which are converted to a string when concatenated to one, converting
+ * arrays, objects and functions is not supported
+ *
+ * */
+pub(crate) fn can_convert_to_string(data_type: &DataType) -> bool {
+    return matches!(
+        data_type,
+        DataType::Float
+            | DataType::String
+            | DataType::Boolean
+            | DataType::Null
+            | DataType::Undefined
+            | DataType::EnumType { .. }
+    ) || is_primitive_union(data_type);
+}
+
+/*
+ * Data type without null and undefined, None when nothing else is left
+ *
+ *      number | null           number
+ *      number | string | null  number | string
+ *
+ * */
+pub(crate) fn remove_nullish(data_type: &DataType) -> Option<DataType> {
+    match data_type {
+        DataType::UnionType { types } => {
+            let types: Vec<DataType> = types
+                .iter()
+                .filter(|member| !member.is_nullish())
+                .cloned()
+                .collect();
+
+            if types.is_empty() {
+                return None;
+            }
+
+            return create_union_type(types).ok();
+        }
+
+        data_type if data_type.is_nullish() => return None,
+
+        data_type => return Some(data_type.clone()),
+    }
+}
+
+/*
+ * Like `strictNullChecks` of TypeScript, a value which may be null or undefined
+ * has to be checked before it is used
+ *
+ * */
+pub(crate) fn check_not_nullish(data_type: &DataType) -> Result<(), String> {
+    let may_be_null = data_type.accepts_nullish(&DataType::Null);
+    let may_be_undefined = data_type.accepts_nullish(&DataType::Undefined);
+
+    let description = match (may_be_null, may_be_undefined) {
+        (false, false) => return Ok(()),
+        (true, false) => "'null'",
+        (false, true) => "'undefined'",
+        (true, true) => "'null' or 'undefined'",
+    };
+
+    if data_type.is_nullish() {
+        return Err(format!("The value {} cannot be used here", description));
+    }
+
+    return Err(format!("Object is possibly {}", description));
+}
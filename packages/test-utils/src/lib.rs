@@ -9,6 +9,7 @@ use ast::{
     Ast, AstPtr,
 };
 use indexmap::indexmap;
+use lexer::token::Span;
 
 use self::clock::Clock;
 
@@ -150,6 +151,7 @@ fn generate_boolean_true_literal() -> TExp {
     let exp = Expression::BooleanLiteralExp {
         name: "true".to_string(),
         value: true,
+        span: Span::default(),
     };
 
     return TExp {
@@ -166,6 +168,7 @@ fn generate_boolean_false_literal() -> TExp {
     let exp = Expression::BooleanLiteralExp {
         name: "false".to_string(),
         value: false,
+        span: Span::default(),
     };
 
     return TExp {
@@ -182,6 +185,7 @@ fn generate_boolean_ident(var_name: &str) -> TExp {
     let exp = Expression::IdentExp {
         name: var_name.to_string(),
         data_type: DataType::Boolean,
+        span: Span::default(),
     };
 
     let ast_str = format!("const {} = true\n", var_name);
@@ -191,8 +195,10 @@ fn generate_boolean_ident(var_name: &str) -> TExp {
         Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         },
         VariableDeclarationKind::Const,
+        Span::default(),
     )];
 
     return TExp {
@@ -211,7 +217,9 @@ fn generate_unary_bang() -> TExp {
         argument: Box::new(Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -230,11 +238,14 @@ fn generate_binary_strict_equality_bool() -> TExp {
         left: Box::new(Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         }),
         right: Box::new(Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -253,11 +264,14 @@ fn generate_binary_strict_equality_float() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -276,11 +290,14 @@ fn generate_binary_strict_not_equal_bool() -> TExp {
         left: Box::new(Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         }),
         right: Box::new(Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -299,11 +316,14 @@ fn generate_binary_strict_not_equal_float() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -322,11 +342,14 @@ fn generate_binary_less_than() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -345,11 +368,14 @@ fn generate_binary_less_than_or_equal() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -368,11 +394,14 @@ fn generate_binary_greater_than() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -391,11 +420,14 @@ fn generate_binary_greater_than_or_equal() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "2".to_string(),
             value: 2.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -412,6 +444,7 @@ fn generate_float_literal() -> TExp {
     let exp = Expression::FloatLiteralExp {
         name: "1".to_string(),
         value: 1.0,
+        span: Span::default(),
     };
 
     return TExp {
@@ -428,6 +461,7 @@ fn generate_float_ident(var_name: &str) -> TExp {
     let exp = Expression::IdentExp {
         name: var_name.to_string(),
         data_type: DataType::Float,
+        span: Span::default(),
     };
 
     let ast_str = format!("const {} = 1\n", var_name);
@@ -437,8 +471,10 @@ fn generate_float_ident(var_name: &str) -> TExp {
         Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         },
         VariableDeclarationKind::Const,
+        Span::default(),
     )];
 
     return TExp {
@@ -457,7 +493,9 @@ fn generate_unary_plus() -> TExp {
         argument: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -476,7 +514,9 @@ fn generate_unary_minus() -> TExp {
         argument: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -495,11 +535,14 @@ fn generate_binary_float_plus() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -518,11 +561,14 @@ fn generate_binary_float_minus() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -541,11 +587,14 @@ fn generate_binary_float_star() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -564,11 +613,14 @@ fn generate_binary_float_slash() -> TExp {
         left: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
         right: Box::new(Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         }),
+        span: Span::default(),
     };
 
     return TExp {
@@ -584,6 +636,7 @@ fn generate_string_literal() -> TExp {
 
     let exp = Expression::StringLiteralExp {
         value: "Hello World".to_string(),
+        span: Span::default(),
     };
 
     return TExp {
@@ -600,6 +653,7 @@ fn generate_string_ident(var_name: &str) -> TExp {
     let exp = Expression::IdentExp {
         name: var_name.to_string(),
         data_type: DataType::String,
+        span: Span::default(),
     };
 
     let ast_str = format!("const {} = \"12\"\n", var_name);
@@ -608,8 +662,10 @@ fn generate_string_ident(var_name: &str) -> TExp {
         var_name,
         Expression::StringLiteralExp {
             value: "12".to_string(),
+            span: Span::default(),
         },
         VariableDeclarationKind::Const,
+        Span::default(),
     )];
 
     return TExp {
@@ -628,13 +684,16 @@ fn generate_array_float_literal() -> TExp {
             Expression::FloatLiteralExp {
                 name: "1".to_string(),
                 value: 1.0,
+                span: Span::default(),
             },
             Expression::FloatLiteralExp {
                 name: "1".to_string(),
                 value: 1.0,
+                span: Span::default(),
             },
         ]),
         expression_data_type: DataType::Float,
+        span: Span::default(),
     };
 
     return TExp {
@@ -653,6 +712,7 @@ fn generate_array_float_ident(var_name: &str) -> TExp {
         data_type: DataType::ArrayType {
             base_type: Box::new(DataType::Float),
         },
+        span: Span::default(),
     };
 
     let ast_str = format!("const {} = [1,1]\n", var_name);
@@ -664,15 +724,19 @@ fn generate_array_float_ident(var_name: &str) -> TExp {
                 Expression::FloatLiteralExp {
                     name: "1".to_string(),
                     value: 1.0,
+                    span: Span::default(),
                 },
                 Expression::FloatLiteralExp {
                     name: "1".to_string(),
                     value: 1.0,
+                    span: Span::default(),
                 },
             ]),
             expression_data_type: DataType::Float,
+            span: Span::default(),
         },
         VariableDeclarationKind::Const,
+        Span::default(),
     )];
 
     return TExp {
@@ -693,6 +757,7 @@ fn generate_object_literal() -> TExp {
         Expression::FloatLiteralExp {
             name: "1".to_string(),
             value: 1.0,
+            span: Span::default(),
         },
     );
     exp_hash_map.insert(
@@ -700,6 +765,7 @@ fn generate_object_literal() -> TExp {
         Expression::BooleanLiteralExp {
             name: "true".to_string(),
             value: true,
+            span: Span::default(),
         },
     );
 
@@ -708,6 +774,7 @@ fn generate_object_literal() -> TExp {
             entries: indexmap! {"a".to_string() => DataType::Float, "b".to_string() => DataType::Boolean},
         },
         expression: exp_hash_map,
+        span: Span::default(),
     };
 
     return TExp {